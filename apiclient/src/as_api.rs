@@ -26,6 +26,7 @@ use aircommon::{
         connection_package::ConnectionPackage,
         connection_package::VersionedConnectionPackageIn,
     },
+    time::TimeStamp,
 };
 use airprotos::{
     auth_service::v1::{
@@ -36,11 +37,12 @@ use airprotos::{
         GetUserProfileRequest, InitListenUsernamePayload, InvitationCode, IssueTokensPayload,
         ListenUsernameRequest, MergeUserProfilePayload, OperationType,
         PublishConnectionPackagesPayload, RefreshUsernamePayload, RegisterUserRequest,
-        ReportSpamPayload, StageUserProfilePayload, UsernameQueueMessage, connect_username_request,
-        connect_username_response, listen_username_request,
+        ReportSpamPayload, ServerTimeRequest, StageUserProfilePayload, UsernameQueueMessage,
+        connect_username_request, connect_username_response, listen_username_request,
     },
     common::v1::{StatusDetails, StatusDetailsCode, TokenQuotaExceededDetail, status_details},
 };
+use chrono::{DateTime, Duration, Utc};
 use futures_util::{FutureExt, future::BoxFuture};
 use thiserror::Error;
 use tokio::sync::{mpsc, oneshot};
@@ -49,7 +51,7 @@ use tonic::{Code, Request};
 use tracing::error;
 use uuid::Uuid;
 
-use crate::ApiClient;
+use crate::{ApiClient, without_request_timeout};
 
 /// Errors that can occur when sending requests to the AS.
 #[derive(Error, Debug)]
@@ -71,6 +73,16 @@ impl AsRequestError {
         }
     }
 
+    /// Returns whether the error is a gRPC failed precondition error.
+    ///
+    /// The request will fail again when it is repeated.
+    pub fn is_failed_precondition(&self) -> bool {
+        match self {
+            AsRequestError::Tonic(status) => status.code() == Code::FailedPrecondition,
+            _ => false,
+        }
+    }
+
     /// Returns whether the error is a gRPC failed precondition error with a version unsupported
     /// code.
     pub fn is_unsupported_version(&self) -> bool {
@@ -135,6 +147,22 @@ impl AsRequestError {
     }
 }
 
+/// Computes the offset of the local clock from the server's clock.
+///
+/// `sent_at` and `received_at` are the local times when the server time request was sent and
+/// its response was received. The server time is assumed to be taken halfway in between.
+///
+/// The offset is positive if the local clock is behind the server's clock, i.e. adding it to a
+/// local time yields the corresponding server time.
+pub fn clock_offset(
+    sent_at: DateTime<Utc>,
+    server_time: DateTime<Utc>,
+    received_at: DateTime<Utc>,
+) -> Duration {
+    let round_trip = received_at - sent_at;
+    server_time - (sent_at + round_trip / 2)
+}
+
 impl From<LibraryError> for AsRequestError {
     fn from(_: LibraryError) -> Self {
         AsRequestError::LibraryError
@@ -155,6 +183,33 @@ impl ApiClient {
         Ok(response.is_valid)
     }
 
+    /// Returns the current time of the server.
+    pub async fn server_time(&self) -> Result<DateTime<Utc>, AsRequestError> {
+        let response = self
+            .idempotent(async || {
+                self.as_grpc_client()
+                    .server_time(ServerTimeRequest {})
+                    .await
+            })
+            .await?
+            .into_inner();
+        let server_time: TimeStamp = response
+            .server_time
+            .ok_or(AsRequestError::UnexpectedResponse)?
+            .into();
+        Ok(server_time.into())
+    }
+
+    /// Estimates the offset of the local clock from the server's clock.
+    ///
+    /// See [`clock_offset`].
+    pub async fn server_clock_offset(&self) -> Result<Duration, AsRequestError> {
+        let sent_at = Utc::now();
+        let server_time = self.server_time().await?;
+        let received_at = Utc::now();
+        Ok(clock_offset(sent_at, server_time, received_at))
+    }
+
     pub async fn as_get_invitation_codes(
         &self,
         tokens: impl IntoIterator<Item = SerializedToken>,
@@ -217,8 +272,11 @@ impl ApiClient {
             key_index: key_index.into_bytes().to_vec(),
         };
         let response = self
-            .as_grpc_client()
-            .get_user_profile(request)
+            .idempotent(async || {
+                self.as_grpc_client()
+                    .get_user_profile(request.clone())
+                    .await
+            })
             .await?
             .into_inner();
         Ok(GetUserProfileResponse {
@@ -302,12 +360,17 @@ impl ApiClient {
         &self,
         reporter_id: UserId,
         spammer_id: UserId,
+        spammer_username_hashes: Vec<UsernameHash>,
         signing_key: &ClientSigningKey,
     ) -> Result<(), AsRequestError> {
         let payload = ReportSpamPayload {
             client_metadata: Some(self.metadata().clone()),
             reporter_id: Some(reporter_id.into()),
             spammer_id: Some(spammer_id.into()),
+            spammer_username_hashes: spammer_username_hashes
+                .into_iter()
+                .map(From::from)
+                .collect(),
         };
         let request = payload.sign(signing_key)?;
         self.as_grpc_client().report_spam(request).await?;
@@ -432,7 +495,7 @@ impl ApiClient {
 
         let responses = self
             .as_grpc_client()
-            .listen_username(requests)
+            .listen_username(without_request_timeout(requests))
             .await?
             .into_inner();
 
@@ -455,8 +518,7 @@ impl ApiClient {
             client_metadata: Some(self.metadata().clone()),
         };
         let response = self
-            .as_grpc_client()
-            .as_credentials(request)
+            .idempotent(async || self.as_grpc_client().as_credentials(request.clone()).await)
             .await?
             .into_inner();
         Ok(AsCredentialsResponseIn {
@@ -517,8 +579,11 @@ impl ApiClient {
             hash: Some(username_hash.into()),
         };
         let response = self
-            .as_grpc_client()
-            .check_username_exists(request)
+            .idempotent(async || {
+                self.as_grpc_client()
+                    .check_username_exists(request.clone())
+                    .await
+            })
             .await?
             .into_inner();
         Ok(response.exists)
@@ -25,7 +25,9 @@ use aircommon::{
     },
     time::TimeStamp,
 };
-pub use airprotos::delivery_service::v1::ProvisionAttachmentResponse;
+pub use airprotos::delivery_service::v1::{
+    GetAttachmentPartUploadUrlResponse, ProvisionAttachmentResponse, UploadedPart,
+};
 use airprotos::{
     common::v1::{
         AttachmentTooLargeDetail, StatusDetails, StatusDetailsCode,
@@ -35,8 +37,9 @@ use airprotos::{
     delivery_service::v1::{
         AddUsersInfo, ApqAddUsersInfo, ApqAssistedMlsMessage, ApqDeleteGroupPayload,
         ApqGroupOperationPayload, ApqResyncPayload, ApqSelfRemovePayload,
-        ConnectionGroupInfoRequest, CreateApqGroupPayload, CreateGroupPayload, DeleteGroupPayload,
-        ExternalCommitInfoRequest, GetAttachmentUrlPayload, GroupOperationPayload,
+        CompleteAttachmentUploadPayload, ConnectionGroupInfoRequest, CreateApqGroupPayload,
+        CreateGroupPayload, DeleteGroupPayload, ExternalCommitInfoRequest,
+        GetAttachmentPartUploadUrlPayload, GetAttachmentUrlPayload, GroupOperationPayload,
         GroupSessionData, IndexedEncryptedUserProfileKey, JoinConnectionGroupRequest,
         ProvisionAttachmentPayload, RequestGroupIdRequest, ResyncPayload, SelfRemovePayload,
         SendMessageCollisionTags, SendMessagePayload, StorageObjectType, TargetedMessagePayload,
@@ -155,6 +158,14 @@ pub enum DsAttachmentTarget<'a> {
     },
 }
 
+/// A multipart upload of an attachment to a group
+pub struct DsMultipartUpload<'a> {
+    pub group_state_ear_key: &'a GroupStateEarKey,
+    pub group_id: &'a GroupId,
+    pub sender_index: LeafNodeIndex,
+    pub remote_attachment_id: RemoteAttachmentId,
+}
+
 impl ApiClient {
     /// Creates a new group on the DS.
     pub async fn ds_create_group(
@@ -440,8 +451,11 @@ impl ApiClient {
             group_state_ear_key: Some(group_state_ear_key.ref_into()),
         };
         let response = self
-            .ds_grpc_client()
-            .connection_group_info(request)
+            .idempotent(async || {
+                self.ds_grpc_client()
+                    .connection_group_info(request.clone())
+                    .await
+            })
             .await?
             .into_inner();
         let (encrypted_user_profile_keys, indexed_encrypted_user_profile_keys) =
@@ -807,6 +821,51 @@ impl ApiClient {
             Option<ProvisionAttachmentResponse>,
         ),
         DsRequestError,
+    > {
+        self.request_group_id_inner(provision_group_profile_size, request_pq_group_id, None)
+            .await
+    }
+
+    /// Request the given group ID
+    ///
+    /// Same as [`Self::ds_request_group_id`], but reserves `group_id` instead of a random group
+    /// ID. The group ID must be owned by the server's domain. Fails if the group ID is already
+    /// reserved. The PQ group ID (if requested) is still random.
+    ///
+    /// Only intended for reproducible tests and interop.
+    pub async fn ds_request_specific_group_id(
+        &self,
+        group_id: &QualifiedGroupId,
+        provision_group_profile_size: Option<usize>,
+        request_pq_group_id: bool,
+    ) -> Result<
+        (
+            GroupId,
+            Option<GroupId>,
+            Option<ProvisionAttachmentResponse>,
+        ),
+        DsRequestError,
+    > {
+        self.request_group_id_inner(
+            provision_group_profile_size,
+            request_pq_group_id,
+            Some(group_id),
+        )
+        .await
+    }
+
+    async fn request_group_id_inner(
+        &self,
+        provision_group_profile_size: Option<usize>,
+        request_pq_group_id: bool,
+        requested_group_id: Option<&QualifiedGroupId>,
+    ) -> Result<
+        (
+            GroupId,
+            Option<GroupId>,
+            Option<ProvisionAttachmentResponse>,
+        ),
+        DsRequestError,
     > {
         let group_profile_size: Option<i64> = provision_group_profile_size
             .map(|size| size.try_into())
@@ -821,6 +880,7 @@ impl ApiClient {
                 client_metadata: Some(self.metadata().clone()),
                 group_profile_size,
                 request_pq_group_id,
+                requested_group_uuid: requested_group_id.map(|qgid| qgid.group_uuid().into()),
             })
             .await?
             .into_inner();
@@ -836,6 +896,12 @@ impl ApiClient {
                 error!(%error, "unexpected response");
                 DsRequestError::UnexpectedResponse
             })?;
+        if let Some(requested_group_id) = requested_group_id
+            && requested_group_id != &qgid
+        {
+            error!(?qgid, "received group id differs from the requested one");
+            return Err(DsRequestError::UnexpectedResponse);
+        }
         let pq_qgid = if request_pq_group_id {
             let qgid: QualifiedGroupId = response
                 .pq_group_id
@@ -869,6 +935,7 @@ impl ApiClient {
         target: DsAttachmentTarget<'_>,
         content_length: i64,
         object_type: StorageObjectType,
+        use_multipart_upload: bool,
     ) -> Result<ProvisionAttachmentResponse, DsRequestError> {
         let payload = match target {
             DsAttachmentTarget::Group {
@@ -886,6 +953,7 @@ impl ApiClient {
                     use_post_policy: true,
                     content_length,
                     object_type: object_type.into(),
+                    use_multipart_upload,
                 }
             }
             DsAttachmentTarget::User { user_id } => ProvisionAttachmentPayload {
@@ -897,6 +965,8 @@ impl ApiClient {
                 use_post_policy: true,
                 content_length,
                 object_type: object_type.into(),
+                // multipart uploads are only supported for attachments of a group
+                use_multipart_upload: false,
             },
         };
 
@@ -946,12 +1016,75 @@ impl ApiClient {
         };
         let request = payload.sign(signing_key)?;
         let response = self
-            .ds_grpc_client()
-            .get_attachment_url(request)
+            .idempotent(async || {
+                self.ds_grpc_client()
+                    .get_attachment_url(request.clone())
+                    .await
+            })
             .await?
             .into_inner();
         Ok(response.download_url)
     }
+
+    /// Get the upload URL for a part of a multipart attachment upload.
+    ///
+    /// Part numbers start at 1.
+    pub async fn ds_get_attachment_part_upload_url(
+        &self,
+        signing_key: &ClientSigningKey,
+        upload: DsMultipartUpload<'_>,
+        part_number: u32,
+        content_length: u64,
+    ) -> Result<GetAttachmentPartUploadUrlResponse, DsRequestError> {
+        let qgid: QualifiedGroupId = upload.group_id.try_into()?;
+        let payload = GetAttachmentPartUploadUrlPayload {
+            client_metadata: Some(self.metadata().clone()),
+            group_state_ear_key: Some(upload.group_state_ear_key.ref_into()),
+            group_id: Some(qgid.ref_into()),
+            sender: Some(upload.sender_index.into()),
+            object_id: Some(upload.remote_attachment_id.uuid().into()),
+            part_number,
+            content_length,
+        };
+        let request = payload.sign(signing_key)?;
+        let response = self
+            .idempotent(async || {
+                self.ds_grpc_client()
+                    .get_attachment_part_upload_url(request.clone())
+                    .await
+            })
+            .await?
+            .into_inner();
+        Ok(response)
+    }
+
+    /// Complete a multipart attachment upload.
+    ///
+    /// The parts must be ordered by their number.
+    pub async fn ds_complete_attachment_upload(
+        &self,
+        signing_key: &ClientSigningKey,
+        upload: DsMultipartUpload<'_>,
+        parts: Vec<UploadedPart>,
+    ) -> Result<(), DsRequestError> {
+        let qgid: QualifiedGroupId = upload.group_id.try_into()?;
+        let payload = CompleteAttachmentUploadPayload {
+            client_metadata: Some(self.metadata().clone()),
+            group_state_ear_key: Some(upload.group_state_ear_key.ref_into()),
+            group_id: Some(qgid.ref_into()),
+            sender: Some(upload.sender_index.into()),
+            object_id: Some(upload.remote_attachment_id.uuid().into()),
+            parts,
+        };
+        let request = payload.sign(signing_key)?;
+        self.idempotent(async || {
+            self.ds_grpc_client()
+                .complete_attachment_upload(request.clone())
+                .await
+        })
+        .await?;
+        Ok(())
+    }
 }
 
 fn extract_encrypted_user_profile_keys(
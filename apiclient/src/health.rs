@@ -0,0 +1,139 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Health checks of the server services
+
+use std::time::Duration;
+
+use airprotos::{
+    auth_service::v1::auth_service_server, delivery_service::v1::delivery_service_server,
+    queue_service::v1::queue_service_server,
+};
+use thiserror::Error;
+use tonic::{Code, Status};
+pub use tonic_health::ServingStatus;
+use tonic_health::pb::HealthCheckRequest;
+use tracing::warn;
+
+use crate::ApiClient;
+
+/// Timeout of a whole health check.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serving status of the server services.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthReport {
+    pub auth_service: ServingStatus,
+    pub delivery_service: ServingStatus,
+    pub queue_service: ServingStatus,
+}
+
+impl HealthReport {
+    /// Returns whether all services are serving.
+    pub fn is_serving(&self) -> bool {
+        [self.auth_service, self.delivery_service, self.queue_service]
+            .iter()
+            .all(|status| *status == ServingStatus::Serving)
+    }
+}
+
+/// Errors that can occur when checking the health of the server.
+#[derive(Debug, Error)]
+pub enum HealthCheckError {
+    #[error("Server is unreachable: {0}")]
+    Unreachable(Status),
+    #[error("Health check timed out")]
+    Timeout,
+}
+
+impl ApiClient {
+    /// Checks the health of the auth, delivery and queue services of the server.
+    ///
+    /// Returns an error if the server can't be reached within a short timeout. A reachable server
+    /// reports the serving status of each service.
+    pub async fn health_check(&self) -> Result<HealthReport, HealthCheckError> {
+        tokio::time::timeout(HEALTH_CHECK_TIMEOUT, async {
+            Ok(HealthReport {
+                auth_service: self
+                    .service_health(auth_service_server::SERVICE_NAME)
+                    .await?,
+                delivery_service: self
+                    .service_health(delivery_service_server::SERVICE_NAME)
+                    .await?,
+                queue_service: self
+                    .service_health(queue_service_server::SERVICE_NAME)
+                    .await?,
+            })
+        })
+        .await
+        .map_err(|_| HealthCheckError::Timeout)?
+    }
+
+    async fn service_health(&self, service: &str) -> Result<ServingStatus, HealthCheckError> {
+        let request = HealthCheckRequest {
+            service: service.to_owned(),
+        };
+        match self.health_grpc_client().check(request).await {
+            Ok(response) => Ok(serving_status(response.into_inner().status)),
+            Err(status) => service_health_from_error(service, status),
+        }
+    }
+}
+
+fn serving_status(status: i32) -> ServingStatus {
+    use tonic_health::pb::health_check_response::ServingStatus as PbServingStatus;
+    match PbServingStatus::try_from(status) {
+        Ok(PbServingStatus::Serving) => ServingStatus::Serving,
+        Ok(PbServingStatus::NotServing) => ServingStatus::NotServing,
+        Ok(PbServingStatus::Unknown | PbServingStatus::ServiceUnknown) | Err(_) => {
+            ServingStatus::Unknown
+        }
+    }
+}
+
+fn service_health_from_error(
+    service: &str,
+    status: Status,
+) -> Result<ServingStatus, HealthCheckError> {
+    match status.code() {
+        // The server does not know the service
+        Code::NotFound => Ok(ServingStatus::Unknown),
+        Code::Unavailable | Code::Unknown | Code::DeadlineExceeded => {
+            Err(HealthCheckError::Unreachable(status))
+        }
+        _ => {
+            warn!(service, %status, "Unexpected health check error");
+            Ok(ServingStatus::Unknown)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn not_found_is_unknown_service() {
+        let status = service_health_from_error("service", Status::not_found("unknown service"));
+        assert_eq!(status.unwrap(), ServingStatus::Unknown);
+    }
+
+    #[test]
+    fn unavailable_is_unreachable() {
+        let status = service_health_from_error("service", Status::unavailable("unavailable"));
+        assert!(matches!(status, Err(HealthCheckError::Unreachable(_))));
+    }
+
+    #[test]
+    fn report_is_serving() {
+        let mut report = HealthReport {
+            auth_service: ServingStatus::Serving,
+            delivery_service: ServingStatus::Serving,
+            queue_service: ServingStatus::Serving,
+        };
+        assert!(report.is_serving());
+        report.queue_service = ServingStatus::NotServing;
+        assert!(!report.is_serving());
+    }
+}
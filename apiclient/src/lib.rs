@@ -14,16 +14,28 @@ use airprotos::{
     relay_service::v1::relay_service_client::RelayServiceClient,
 };
 use thiserror::Error;
-use tonic::transport::{Channel, ClientTlsConfig, Endpoint, Uri};
+use tonic::{
+    Request, Status,
+    service::{Interceptor, interceptor::InterceptedService},
+    transport::{Channel, ClientTlsConfig, Endpoint, Uri},
+};
+use tonic_health::pb::health_client::HealthClient;
 use tracing::info;
 use url::{Host, Url};
 
+use crate::proxy::ProxyConnector;
+
 pub mod as_api;
 pub mod ds_api;
+pub mod health;
 mod metadata;
+mod proxy;
 pub mod qs_api;
+mod retry;
 pub mod rs_api;
 
+pub use retry::RetryPolicy;
+
 /// The port used for localhost connections.
 ///
 /// Also see server's listen configuration.
@@ -36,10 +48,110 @@ pub enum ApiClientInitError {
     UrlParsingError(String),
     #[error("Invalid URL {0}")]
     InvalidUrl(String),
+    #[error("Insecure endpoint {0}: TLS is required")]
+    InsecureEndpoint(String),
+    #[error("Invalid proxy URL {0}")]
+    InvalidProxy(String),
     #[error(transparent)]
     TonicTransport(#[from] tonic::transport::Error),
 }
 
+/// Security of the transport used to connect to the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransportSecurity {
+    /// Connections must use TLS. Endpoints with a non-`https` scheme are rejected.
+    #[default]
+    RequireTls,
+    /// Plaintext connections are allowed, e.g. for servers running locally in tests.
+    AllowPlaintext,
+}
+
+impl TransportSecurity {
+    /// Transport security used by default when connecting to the given domain.
+    ///
+    /// Plaintext is only allowed for localhost.
+    fn for_domain(domain: &Fqdn) -> Self {
+        if domain.is_localhost() {
+            Self::AllowPlaintext
+        } else {
+            Self::RequireTls
+        }
+    }
+}
+
+/// Configuration of the connection of an [`ApiClient`].
+#[derive(Debug, Clone)]
+pub struct ApiClientConfig {
+    /// Security of the transport; see [`TransportSecurity`].
+    pub transport_security: TransportSecurity,
+    /// Interval at which HTTP2 keep-alive pings are sent.
+    pub keep_alive_interval: Duration,
+    /// Timeout for establishing the connection. No timeout if `None`.
+    ///
+    /// When connecting through a proxy, the timeout covers establishing the tunnel.
+    pub connect_timeout: Option<Duration>,
+    /// Timeout for unary requests. No timeout if `None`.
+    ///
+    /// Long-lived streaming requests (e.g. listening to the queue) are not subject to the timeout.
+    pub request_timeout: Option<Duration>,
+    /// Whether to set `TCP_NODELAY` on the connection. The transport's default is used if `None`.
+    pub tcp_nodelay: Option<bool>,
+    /// Policy for retrying idempotent requests. Requests are not retried if `None`.
+    pub retry_policy: Option<RetryPolicy>,
+    /// URL of an HTTP proxy through which to connect.
+    ///
+    /// If `None`, the proxy is taken from the `HTTPS_PROXY` or `HTTP_PROXY` environment variable,
+    /// depending on the scheme of the endpoint, unless the endpoint is excluded by `NO_PROXY`.
+    /// Connections to localhost are never proxied from the environment, and a malformed proxy in
+    /// the environment is ignored with a warning.
+    pub proxy: Option<Url>,
+}
+
+impl Default for ApiClientConfig {
+    fn default() -> Self {
+        Self {
+            transport_security: TransportSecurity::default(),
+            keep_alive_interval: Duration::from_secs(30),
+            connect_timeout: None,
+            request_timeout: None,
+            tcp_nodelay: None,
+            retry_policy: None,
+            proxy: None,
+        }
+    }
+}
+
+/// Channel shared by all gRPC clients; applies the request timeout to each request.
+type GrpcChannel = InterceptedService<Channel, RequestTimeout>;
+
+/// Sets the timeout of requests which don't opt out via [`NoRequestTimeout`].
+#[derive(Debug, Clone, Copy)]
+struct RequestTimeout(Option<Duration>);
+
+impl Interceptor for RequestTimeout {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(timeout) = self.0
+            && request.extensions().get::<NoRequestTimeout>().is_none()
+        {
+            request.set_timeout(timeout);
+        }
+        Ok(request)
+    }
+}
+
+/// Request extension which exempts a request from the configured request timeout.
+#[derive(Debug, Clone, Copy)]
+struct NoRequestTimeout;
+
+/// Creates a request which is not subject to the configured request timeout.
+///
+/// Used for long-lived streaming requests.
+pub(crate) fn without_request_timeout<T>(message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    request.extensions_mut().insert(NoRequestTimeout);
+    request
+}
+
 /// ApiClient is a thin wrapper around the gRPC clients.
 ///
 /// It exposes a single function for each API endpoint. Internally, it holds a single TCP
@@ -51,27 +163,78 @@ pub struct ApiClient {
 
 #[derive(Debug)]
 struct ApiClientInner {
-    as_grpc_client: AuthServiceClient<Channel>,
-    qs_grpc_client: QueueServiceClient<Channel>,
-    ds_grpc_client: DeliveryServiceClient<Channel>,
-    rs_grpc_client: RelayServiceClient<Channel>,
+    as_grpc_client: AuthServiceClient<GrpcChannel>,
+    qs_grpc_client: QueueServiceClient<GrpcChannel>,
+    ds_grpc_client: DeliveryServiceClient<GrpcChannel>,
+    rs_grpc_client: RelayServiceClient<GrpcChannel>,
+    health_grpc_client: HealthClient<GrpcChannel>,
+    retry_policy: Option<RetryPolicy>,
 }
 
 impl ApiClient {
+    /// Creates a client for the given endpoint.
+    ///
+    /// The scheme of the URL determines whether TLS is used. Use
+    /// [`Self::with_endpoint_and_security`] to require TLS.
     pub fn with_endpoint(url: &Url) -> Result<Self, ApiClientInitError> {
-        info!(%url, "Connecting lazily to GRPC server");
+        Self::with_endpoint_and_security(url, TransportSecurity::AllowPlaintext)
+    }
+
+    /// Creates a client for the given endpoint with the given transport security.
+    ///
+    /// Returns [`ApiClientInitError::InsecureEndpoint`] if TLS is required, but the URL does not
+    /// use the `https` scheme.
+    pub fn with_endpoint_and_security(
+        url: &Url,
+        security: TransportSecurity,
+    ) -> Result<Self, ApiClientInitError> {
+        let config = ApiClientConfig {
+            transport_security: security,
+            ..Default::default()
+        };
+        Self::with_config(url, config)
+    }
+
+    /// Creates a client for the given endpoint with the given connection configuration.
+    ///
+    /// Returns [`ApiClientInitError::InsecureEndpoint`] if TLS is required, but the URL does not
+    /// use the `https` scheme, and [`ApiClientInitError::InvalidProxy`] if the configured proxy
+    /// URL does not use the `http` scheme.
+    pub fn with_config(url: &Url, config: ApiClientConfig) -> Result<Self, ApiClientInitError> {
+        if config.transport_security == TransportSecurity::RequireTls && url.scheme() != "https" {
+            return Err(ApiClientInitError::InsecureEndpoint(url.to_string()));
+        }
+        info!(%url, ?config, "Connecting lazily to GRPC server");
         let uri: Uri = url
             .as_str()
             .parse()
             .map_err(|_| ApiClientInitError::InvalidUrl(url.to_string()))?;
-        let channel = Endpoint::from(uri)
+        let mut endpoint = Endpoint::from(uri)
             .tls_config(ClientTlsConfig::new().with_webpki_roots())?
-            .http2_keep_alive_interval(Duration::from_secs(30))
-            .connect_lazy();
+            .http2_keep_alive_interval(config.keep_alive_interval);
+        if let Some(connect_timeout) = config.connect_timeout {
+            endpoint = endpoint.connect_timeout(connect_timeout);
+        }
+        if let Some(tcp_nodelay) = config.tcp_nodelay {
+            endpoint = endpoint.tcp_nodelay(tcp_nodelay);
+        }
+        let proxy = match &config.proxy {
+            Some(proxy) => Some(ProxyConnector::new(proxy)?),
+            None => ProxyConnector::from_env(url),
+        }
+        .map(|proxy| proxy.with_config(&config));
+        let channel = match proxy {
+            // TLS is established through the tunnel, so the server certificate is still validated
+            // against the hostname of the endpoint.
+            Some(proxy) => endpoint.connect_with_connector_lazy(proxy),
+            None => endpoint.connect_lazy(),
+        };
+        let channel = InterceptedService::new(channel, RequestTimeout(config.request_timeout));
         let as_grpc_client = AuthServiceClient::new(channel.clone());
         let ds_grpc_client = DeliveryServiceClient::new(channel.clone());
         let qs_grpc_client = QueueServiceClient::new(channel.clone());
-        let rs_grpc_client = RelayServiceClient::new(channel);
+        let rs_grpc_client = RelayServiceClient::new(channel.clone());
+        let health_grpc_client = HealthClient::new(channel);
 
         Ok(Self {
             inner: Arc::new(ApiClientInner {
@@ -79,13 +242,30 @@ impl ApiClient {
                 qs_grpc_client,
                 ds_grpc_client,
                 rs_grpc_client,
+                health_grpc_client,
+                retry_policy: config.retry_policy,
             }),
         })
     }
 
+    /// Creates a client for the server of the given domain.
+    ///
+    /// TLS is required for all domains except localhost.
     pub fn with_domain(domain: &Fqdn) -> Result<Self, ApiClientInitError> {
+        Self::with_domain_and_security(domain, TransportSecurity::for_domain(domain))
+    }
+
+    /// Creates a client for the server of the given domain with the given transport security.
+    pub fn with_domain_and_security(
+        domain: &Fqdn,
+        security: TransportSecurity,
+    ) -> Result<Self, ApiClientInitError> {
         let domain_str = if domain.is_localhost() {
-            format!("http://localhost:{LOCALHOST_PORT}")
+            let scheme = match security {
+                TransportSecurity::RequireTls => "https",
+                TransportSecurity::AllowPlaintext => "http",
+            };
+            format!("{scheme}://localhost:{LOCALHOST_PORT}")
         } else if domain == &Fqdn::from(Host::Domain("air.ms".to_string())) {
             // Rewrite the domain to the production endpoint.
             //
@@ -98,26 +278,79 @@ impl ApiClient {
         let url: Url = domain_str
             .parse()
             .map_err(|_| ApiClientInitError::InvalidUrl(domain_str))?;
-        Self::with_endpoint(&url)
+        Self::with_endpoint_and_security(&url, security)
     }
 
-    pub(crate) fn as_grpc_client(&self) -> AuthServiceClient<Channel> {
+    pub(crate) fn as_grpc_client(&self) -> AuthServiceClient<GrpcChannel> {
         self.inner.as_grpc_client.clone()
     }
 
-    pub(crate) fn qs_grpc_client(&self) -> QueueServiceClient<Channel> {
+    pub(crate) fn qs_grpc_client(&self) -> QueueServiceClient<GrpcChannel> {
         self.inner.qs_grpc_client.clone()
     }
 
-    pub(crate) fn ds_grpc_client(&self) -> DeliveryServiceClient<Channel> {
+    pub(crate) fn ds_grpc_client(&self) -> DeliveryServiceClient<GrpcChannel> {
         self.inner.ds_grpc_client.clone()
     }
 
-    pub(crate) fn rs_grpc_client(&self) -> RelayServiceClient<Channel> {
+    pub(crate) fn rs_grpc_client(&self) -> RelayServiceClient<GrpcChannel> {
         self.inner.rs_grpc_client.clone()
     }
 
+    pub(crate) fn health_grpc_client(&self) -> HealthClient<GrpcChannel> {
+        self.inner.health_grpc_client.clone()
+    }
+
+    pub(crate) fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.inner.retry_policy.as_ref()
+    }
+
     pub(crate) fn metadata(&self) -> &ClientMetadata {
         &metadata::METADATA
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn require_tls_rejects_insecure_endpoint() {
+        let url: Url = "http://example.com".parse().unwrap();
+        let result = ApiClient::with_endpoint_and_security(&url, TransportSecurity::RequireTls);
+        assert!(matches!(
+            result,
+            Err(ApiClientInitError::InsecureEndpoint(endpoint)) if endpoint == url.as_str()
+        ));
+    }
+
+    #[test]
+    fn request_timeout_is_applied() {
+        let timeout = Duration::from_secs(5);
+        let mut interceptor = RequestTimeout(Some(timeout));
+
+        let request = interceptor.call(Request::new(())).unwrap();
+        assert!(request.metadata().contains_key("grpc-timeout"));
+
+        let request = interceptor.call(without_request_timeout(())).unwrap();
+        assert!(!request.metadata().contains_key("grpc-timeout"));
+
+        let mut interceptor = RequestTimeout(None);
+        let request = interceptor.call(Request::new(())).unwrap();
+        assert!(!request.metadata().contains_key("grpc-timeout"));
+    }
+
+    #[test]
+    fn plaintext_only_allowed_for_localhost() {
+        let localhost = Fqdn::from(Host::Domain("localhost".to_owned()));
+        assert_eq!(
+            TransportSecurity::for_domain(&localhost),
+            TransportSecurity::AllowPlaintext
+        );
+        let domain = Fqdn::from(Host::Domain("example.com".to_owned()));
+        assert_eq!(
+            TransportSecurity::for_domain(&domain),
+            TransportSecurity::RequireTls
+        );
+    }
+}
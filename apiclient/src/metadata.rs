@@ -8,6 +8,10 @@ use airprotos::common::{self, v1::ClientMetadata};
 
 shadow_rs::shadow!(build);
 
+/// Metadata sent with each request.
+///
+/// The version is the version of this crate, which the server checks against its supported client
+/// versions.
 pub(super) static METADATA: LazyLock<ClientMetadata> = LazyLock::new(|| {
     new_metadata(
         build::PKG_VERSION,
@@ -54,6 +58,15 @@ fn new_metadata(
 
 #[cfg(test)]
 mod test {
+    #[test]
+    fn metadata_has_crate_version() {
+        let pkg_version = semver::Version::parse(env!("CARGO_PKG_VERSION")).unwrap();
+        let version = super::METADATA.version.as_ref().unwrap();
+        assert_eq!(version.major, pkg_version.major);
+        assert_eq!(version.minor, pkg_version.minor);
+        assert_eq!(version.patch, pkg_version.patch);
+    }
+
     #[test]
     fn metadata() {
         let metadata = super::new_metadata("1.2.3", false, "1234567890abcdef", 10);
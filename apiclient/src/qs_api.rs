@@ -48,7 +48,7 @@ use tokio_stream::{Stream, StreamExt, wrappers::ReceiverStream};
 use tokio_util::sync::CancellationToken;
 use tracing::error;
 
-use crate::ApiClient;
+use crate::{ApiClient, without_request_timeout};
 
 #[derive(Error, Debug)]
 pub enum QsRequestError {
@@ -71,6 +71,16 @@ impl From<LibraryError> for QsRequestError {
 }
 
 impl QsRequestError {
+    /// Returns whether the error is a gRPC failed precondition error.
+    ///
+    /// The request will fail again when it is repeated.
+    pub fn is_failed_precondition(&self) -> bool {
+        match self {
+            Self::Tonic(status) => status.code() == tonic::Code::FailedPrecondition,
+            _ => false,
+        }
+    }
+
     pub fn is_unsupported_version(&self) -> bool {
         match self {
             Self::Tonic(status) => {
@@ -159,22 +169,28 @@ impl ApiClient {
         Ok(())
     }
 
+    /// Creates a client record at the QS.
+    ///
+    /// Notifications for the client are pushed to all of `encrypted_push_tokens`.
     pub async fn qs_create_client(
         &self,
         sender: QsUserId,
         client_record_auth_key: QsClientVerifyingKey,
         queue_encryption_key: RatchetEncryptionKey,
-        encrypted_push_token: Option<EncryptedPushToken>,
+        encrypted_push_tokens: Vec<EncryptedPushToken>,
         initial_ratchet_key: RatchetSecret,
         signing_key: &QsUserSigningKey,
     ) -> Result<CreateClientRecordResponse, QsRequestError> {
+        let (encrypted_push_token, additional_push_tokens) =
+            split_push_tokens(encrypted_push_tokens);
         let payload = CreateClientPayload {
             client_metadata: Some(self.metadata().clone()),
             sender: Some(sender.into()),
             client_record_auth_key: Some(client_record_auth_key.into()),
             queue_encryption_key: Some(queue_encryption_key.into()),
-            encrypted_push_token: encrypted_push_token.map(|token| token.into()),
+            encrypted_push_token,
             initial_ratched_secret: Some(initial_ratchet_key.into()),
+            additional_push_tokens,
         };
         let request = payload.sign(signing_key)?;
         let response = self
@@ -194,19 +210,25 @@ impl ApiClient {
         })
     }
 
+    /// Updates the client record at the QS.
+    ///
+    /// The push tokens of the client are replaced by `encrypted_push_tokens`.
     pub async fn qs_update_client(
         &self,
         sender: QsClientId,
         queue_encryption_key: RatchetEncryptionKey,
-        encrypted_push_token: Option<EncryptedPushToken>,
+        encrypted_push_tokens: Vec<EncryptedPushToken>,
         signing_key: &QsClientSigningKey,
     ) -> Result<(), QsRequestError> {
+        let (encrypted_push_token, additional_push_tokens) =
+            split_push_tokens(encrypted_push_tokens);
         let payload = UpdateClientPayload {
             client_metadata: Some(self.metadata().clone()),
             sender: Some(sender.into()),
             client_record_auth_key: Some(signing_key.verifying_key().clone().into()),
             queue_encryption_key: Some(queue_encryption_key.into()),
-            encrypted_push_token: encrypted_push_token.map(|token| token.into()),
+            encrypted_push_token,
+            additional_push_tokens,
         };
         let request = payload.sign(signing_key)?;
         self.qs_grpc_client().update_client(request).await?;
@@ -320,8 +342,11 @@ impl ApiClient {
             client_metadata: Some(self.metadata().clone()),
         };
         let response = self
-            .qs_grpc_client()
-            .qs_encryption_key(request)
+            .idempotent(async || {
+                self.qs_grpc_client()
+                    .qs_encryption_key(request.clone())
+                    .await
+            })
             .await?
             .into_inner();
         let encryption_key = response
@@ -374,7 +399,10 @@ impl ApiClient {
             cancel.clone(),
         );
 
-        let response = self.qs_grpc_client().listen(requests).await?;
+        let response = self
+            .qs_grpc_client()
+            .listen(without_request_timeout(requests))
+            .await?;
         let responses = response.into_inner().map_while(|response| {
             response
                 .inspect_err(|status| error!(?status, "terminating listen stream due to an error"))
@@ -388,6 +416,17 @@ impl ApiClient {
     }
 }
 
+/// Splits the push tokens of a client into the primary token and the additional tokens.
+fn split_push_tokens(
+    encrypted_push_tokens: Vec<EncryptedPushToken>,
+) -> (
+    Option<airprotos::queue_service::v1::EncryptedPushToken>,
+    Vec<airprotos::queue_service::v1::EncryptedPushToken>,
+) {
+    let mut tokens = encrypted_push_tokens.into_iter().map(From::from);
+    (tokens.next(), tokens.collect())
+}
+
 /// Sends responses to the QS listening stream.
 #[derive(Debug, Clone)]
 pub struct QsListenResponder {
@@ -0,0 +1,181 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Retries of idempotent requests on transient errors
+
+use std::time::Duration;
+
+use tonic::{Code, Status};
+use tracing::debug;
+
+use crate::ApiClient;
+
+/// Policy for retrying idempotent requests which failed with a transient error.
+///
+/// A request is retried when the server is unavailable or the connection failed. Other errors,
+/// e.g. [`Code::AlreadyExists`], [`Code::InvalidArgument`] or [`Code::FailedPrecondition`], are
+/// never retried.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the first attempt
+    pub max_retries: u32,
+    /// Backoff before the first retry; doubled for each following retry
+    pub initial_backoff: Duration,
+    /// Maximum backoff between two retries
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff before the retry with the given index (starting at 0).
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .checked_mul(2u32.saturating_pow(retry))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+
+    /// Performs the request, retrying it according to this policy.
+    async fn perform<T>(
+        &self,
+        mut request: impl AsyncFnMut() -> Result<T, Status> + Send,
+    ) -> Result<T, Status> {
+        let mut retry = 0;
+        loop {
+            match request().await {
+                Err(status) if retry < self.max_retries && is_transient(&status) => {
+                    let backoff = self.backoff(retry);
+                    debug!(%status, retry, ?backoff, "Retrying request after transient error");
+                    tokio::time::sleep(backoff).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Returns whether the status signals a transient error after which a request can be retried.
+fn is_transient(status: &Status) -> bool {
+    match status.code() {
+        Code::Unavailable => true,
+        Code::Unknown => std::error::Error::source(status)
+            .is_some_and(|source| source.is::<tonic::transport::Error>()),
+        _ => false,
+    }
+}
+
+impl ApiClient {
+    /// Performs an idempotent request.
+    ///
+    /// If a [`RetryPolicy`] is configured, the request is retried on transient errors.
+    pub(crate) async fn idempotent<T>(
+        &self,
+        mut request: impl AsyncFnMut() -> Result<T, Status> + Send,
+    ) -> Result<T, Status> {
+        match self.retry_policy() {
+            Some(policy) => policy.perform(request).await,
+            None => request().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    fn test_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+        }
+    }
+
+    /// Transport which fails with the given status for the first `failures` requests.
+    struct MockTransport {
+        failures: u32,
+        status: fn() -> Status,
+        requests: AtomicU32,
+    }
+
+    impl MockTransport {
+        fn new(failures: u32, status: fn() -> Status) -> Self {
+            Self {
+                failures,
+                status,
+                requests: AtomicU32::new(0),
+            }
+        }
+
+        async fn request(&self) -> Result<&'static str, Status> {
+            let n = self.requests.fetch_add(1, Ordering::SeqCst);
+            if n < self.failures {
+                Err((self.status)())
+            } else {
+                Ok("response")
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_unavailable_until_success() {
+        let transport = MockTransport::new(2, || Status::unavailable("unavailable"));
+        let response = test_policy()
+            .perform(async || transport.request().await)
+            .await
+            .unwrap();
+        assert_eq!(response, "response");
+        assert_eq!(transport.requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let transport = MockTransport::new(10, || Status::unavailable("unavailable"));
+        let status = test_policy()
+            .perform(async || transport.request().await)
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Unavailable);
+        assert_eq!(transport.requests.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_non_transient_errors() {
+        let statuses: [fn() -> Status; 3] = [
+            || Status::already_exists("exists"),
+            || Status::invalid_argument("invalid"),
+            || Status::failed_precondition("precondition"),
+        ];
+        for status in statuses {
+            let transport = MockTransport::new(1, status);
+            test_policy()
+                .perform(async || transport.request().await)
+                .await
+                .unwrap_err();
+            assert_eq!(transport.requests.load(Ordering::SeqCst), 1);
+        }
+    }
+
+    #[test]
+    fn backoff_is_exponential_and_capped() {
+        let policy = test_policy();
+        assert_eq!(policy.backoff(0), Duration::from_millis(1));
+        assert_eq!(policy.backoff(1), Duration::from_millis(2));
+        assert_eq!(policy.backoff(2), Duration::from_millis(4));
+        assert_eq!(policy.backoff(10), Duration::from_millis(4));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(4));
+    }
+}
@@ -12,7 +12,7 @@ use airprotos::relay_service::v1::{
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 
-use crate::ApiClient;
+use crate::{ApiClient, without_request_timeout};
 
 #[derive(thiserror::Error, Debug)]
 pub enum RsRequestError {
@@ -43,7 +43,7 @@ impl ApiClient {
     ) -> Result<(mpsc::Sender<RelayFrame>, tonic::Streaming<RelayFrame>), RsRequestError> {
         // don't buffer frames: we expect the peer to consume what we send before we move forward
         let (tx, rx) = mpsc::channel::<RelayFrame>(1);
-        let request = tonic::Request::new(ReceiverStream::new(rx));
+        let request = without_request_timeout(ReceiverStream::new(rx));
 
         let response: tonic::Response<tonic::Streaming<RelayFrame>> = self
             .rs_grpc_client()
@@ -73,7 +73,7 @@ impl ApiClient {
 
         let response = self
             .rs_grpc_client()
-            .multi_device_link_client(tonic::Request::new(ReceiverStream::new(rx)))
+            .multi_device_link_client(without_request_timeout(ReceiverStream::new(rx)))
             .await?;
 
         Ok((tx, response.into_inner()))
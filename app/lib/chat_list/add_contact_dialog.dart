@@ -278,6 +278,8 @@ class _SubmitHandler {
           loc.newConnectionDialog_error_duplicateRequest,
         AddUsernameContactError.ownUsername =>
           loc.newConnectionDialog_error_ownUsername,
+        AddUsernameContactError.tooManyPendingRequests =>
+          loc.newConnectionDialog_error_tooManyPendingRequests,
         null => null,
       };
       if (errorMessage != null) {
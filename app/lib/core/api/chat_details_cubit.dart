@@ -459,4 +459,8 @@ sealed class UploadAttachmentError with _$UploadAttachmentError {
     required BigInt maxSizeBytes,
     required BigInt actualSizeBytes,
   }) = UploadAttachmentError_TooLarge;
+
+  /// The image can't be stripped of its metadata
+  const factory UploadAttachmentError.unsupportedImageFormat() =
+      UploadAttachmentError_UnsupportedImageFormat;
 }
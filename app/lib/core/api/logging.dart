@@ -22,6 +22,32 @@ import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
 LogWriter initRustLogging({required String logFile}) =>
     RustLib.instance.api.crateApiLoggingInitRustLogging(logFile: logFile);
 
+/// Same as [`init_rust_logging`], but keeps up to `rotated_segments` compressed segments of older
+/// logs next to the log file instead of overwriting them.
+///
+/// Meant for desktop platforms. On mobile, use [`init_rust_logging`] to keep the fixed-size log
+/// file.
+LogWriter initRustLoggingWithRotation({
+  required String logFile,
+  required int rotatedSegments,
+}) => RustLib.instance.api.crateApiLoggingInitRustLoggingWithRotation(
+  logFile: logFile,
+  rotatedSegments: rotatedSegments,
+);
+
+/// Changes the level of the Rust logs at runtime, e.g. to enable verbose logging.
+///
+/// Applies to the logs sent to Flutter and to the logs written to the log file.
+void setRustLogLevel({required LogEntryLevel level}) =>
+    RustLib.instance.api.crateApiLoggingSetRustLogLevel(level: level);
+
+/// Changes the filter of the Rust logs at runtime to the given directives in the `RUST_LOG`
+/// format, e.g. `info,aircoreclient=debug`.
+///
+/// Invalid directives are rejected and the current filter is kept.
+void setRustLogDirectives({required String directives}) => RustLib.instance.api
+    .crateApiLoggingSetRustLogDirectives(directives: directives);
+
 /// Reads the application logs from the file currently used for writing logs (if any).
 Future<String> readAppLogs() =>
     RustLib.instance.api.crateApiLoggingReadAppLogs();
@@ -11,8 +11,8 @@ import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
 import 'package:freezed_annotation/freezed_annotation.dart' hide protected;
 part 'markdown.freezed.dart';
 
-// These functions are ignored because they are not marked as `pub`: `collect_links`, `parse_block_element`, `parse_inline_elements`, `parse_list_items`, `parse_table_cells`, `parse_table_content`, `try_parse_markdown`
-// These types are ignored because they are neither used by any `pub` functions nor (for structs and enums) marked `#[frb(unignore)]`: `Error`, `RangedEvent`
+// These functions are ignored because they are not marked as `pub`: `cells_len`, `collect_links`, `expand_shortcodes`, `parse_block_element`, `parse_inline_elements`, `parse_list_items`, `parse_table_cells`, `parse_table_content`, `source_len`, `truncate_blocks`, `truncate_inlines`, `truncate_items`, `try_parse_markdown_with`, `try_parse_markdown`, `unlink`
+// These types are ignored because they are neither used by any `pub` functions nor (for structs and enums) marked `#[frb(unignore)]`: `RangedEvent`
 // These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`
 
 @freezed
@@ -35,6 +35,9 @@ sealed class BlockElement with _$BlockElement {
   const factory BlockElement.table({
     required List<List<RangedBlockElement>> head,
     required List<List<List<RangedBlockElement>>> rows,
+
+    /// One alignment per column
+    required List<ColumnAlignment> alignments,
   }) = BlockElement_Table;
   const factory BlockElement.horizontalRule() = BlockElement_HorizontalRule;
 
@@ -42,6 +45,45 @@ sealed class BlockElement with _$BlockElement {
   const factory BlockElement.codeBlock(List<RangedCodeBlock> field0) =
       BlockElement_CodeBlock;
   const factory BlockElement.error(String field0) = BlockElement_Error;
+
+  /// Marks the end of a truncated message
+  ///
+  /// Rendered as a localized hint by the app.
+  const factory BlockElement.truncated() = BlockElement_Truncated;
+}
+
+/// Alignment of a table column, as specified in the delimiter row of the table
+enum ColumnAlignment {
+  /// No alignment specified (`---`)
+  none,
+
+  /// `:--`
+  left,
+
+  /// `:-:`
+  center,
+
+  /// `--:`
+  right;
+
+  static Future<ColumnAlignment> default_() =>
+      RustLib.instance.api.crateApiMarkdownColumnAlignmentDefault();
+}
+
+/// Reason why a message could not be parsed
+enum Error implements FrbException {
+  expectedMoreEvents,
+  expectedSpecificTag,
+  tableContentNotInTable,
+  listItemNotInList,
+  metadataBlocksNotSupported,
+  footnotesNotSupported,
+  definitionListsNotSupported,
+  blockElementInline,
+  htmlNotInBlock,
+  mathNotSupported,
+  depthLimitReached,
+  invalidUtf8;
 }
 
 @freezed
@@ -67,6 +109,26 @@ sealed class InlineElement with _$InlineElement {
       InlineElement_TaskListMarker;
 }
 
+/// Optional markdown syntax recognized by the parser
+///
+/// Disabled syntax is rendered as literal text.
+@freezed
+sealed class MarkdownFeatures with _$MarkdownFeatures {
+  const MarkdownFeatures._();
+  const factory MarkdownFeatures({
+    /// `~~strikethrough~~`
+    required bool strikethrough,
+
+    /// GitHub flavored tables
+    required bool tables,
+
+    /// `- [ ]` and `- [x]` list items
+    required bool taskLists,
+  }) = _MarkdownFeatures;
+  static Future<MarkdownFeatures> default_() =>
+      RustLib.instance.api.crateApiMarkdownMarkdownFeaturesDefault();
+}
+
 @freezed
 sealed class MessageContent with _$MessageContent {
   const MessageContent._();
@@ -86,6 +148,37 @@ sealed class MessageContent with _$MessageContent {
       .instance
       .api
       .crateApiMarkdownMessageContentParseMarkdownRaw(string: string);
+
+  /// Same as [`Self::parse_markdown`], but returns the reason if the message could not be
+  /// parsed instead of an error block.
+  static MessageContent parseMarkdownResult({required String string}) =>
+      RustLib.instance.api.crateApiMarkdownMessageContentParseMarkdownResult(
+        string: string,
+      );
+
+  /// Same as [`Self::parse_markdown`], but keeps at most `max_chars` characters of the source
+  /// text.
+  ///
+  /// If the message is longer, the elements after the cut are dropped and a trailing
+  /// [`BlockElement::Truncated`] marks the message as truncated. Lists, quotes and tables which
+  /// do not fit are cut between their items, so the result is always well-formed. The ranges of
+  /// cut elements end at the kept content.
+  static Future<MessageContent> parseMarkdownTruncated({
+    required String string,
+    required BigInt maxChars,
+  }) => RustLib.instance.api.crateApiMarkdownMessageContentParseMarkdownTruncated(
+    string: string,
+    maxChars: maxChars,
+  );
+
+  /// Same as [`Self::parse_markdown`], but only recognizes the given optional syntax.
+  static Future<MessageContent> parseMarkdownWith({
+    required String string,
+    required MarkdownFeatures features,
+  }) => RustLib.instance.api.crateApiMarkdownMessageContentParseMarkdownWith(
+    string: string,
+    features: features,
+  );
 }
 
 @freezed
@@ -408,7 +408,7 @@ as List<List<RangedBlockElement>>,
 
 
 class BlockElement_Table extends BlockElement {
-  const BlockElement_Table({required final  List<List<RangedBlockElement>> head, required final  List<List<List<RangedBlockElement>>> rows}): _head = head,_rows = rows,super._();
+  const BlockElement_Table({required final  List<List<RangedBlockElement>> head, required final  List<List<List<RangedBlockElement>>> rows, required final  List<ColumnAlignment> alignments}): _head = head,_rows = rows,_alignments = alignments,super._();
   
 
  final  List<List<RangedBlockElement>> _head;
@@ -425,6 +425,15 @@ class BlockElement_Table extends BlockElement {
   return EqualUnmodifiableListView(_rows);
 }
 
+/// One alignment per column
+ final  List<ColumnAlignment> _alignments;
+/// One alignment per column
+ List<ColumnAlignment> get alignments {
+  if (_alignments is EqualUnmodifiableListView) return _alignments;
+  // ignore: implicit_dynamic_type
+  return EqualUnmodifiableListView(_alignments);
+}
+
 
 /// Create a copy of BlockElement
 /// with the given fields replaced by the non-null parameter values.
@@ -436,16 +445,16 @@ $BlockElement_TableCopyWith<BlockElement_Table> get copyWith => _$BlockElement_T
 
 @override
 bool operator ==(Object other) {
-  return identical(this, other) || (other.runtimeType == runtimeType&&other is BlockElement_Table&&const DeepCollectionEquality().equals(other._head, _head)&&const DeepCollectionEquality().equals(other._rows, _rows));
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is BlockElement_Table&&const DeepCollectionEquality().equals(other._head, _head)&&const DeepCollectionEquality().equals(other._rows, _rows)&&const DeepCollectionEquality().equals(other._alignments, _alignments));
 }
 
 
 @override
-int get hashCode => Object.hash(runtimeType,const DeepCollectionEquality().hash(_head),const DeepCollectionEquality().hash(_rows));
+int get hashCode => Object.hash(runtimeType,const DeepCollectionEquality().hash(_head),const DeepCollectionEquality().hash(_rows),const DeepCollectionEquality().hash(_alignments));
 
 @override
 String toString() {
-  return 'BlockElement.table(head: $head, rows: $rows)';
+  return 'BlockElement.table(head: $head, rows: $rows, alignments: $alignments)';
 }
 
 
@@ -456,7 +465,7 @@ abstract mixin class $BlockElement_TableCopyWith<$Res> implements $BlockElementC
   factory $BlockElement_TableCopyWith(BlockElement_Table value, $Res Function(BlockElement_Table) _then) = _$BlockElement_TableCopyWithImpl;
 @useResult
 $Res call({
- List<List<RangedBlockElement>> head, List<List<List<RangedBlockElement>>> rows
+ List<List<RangedBlockElement>> head, List<List<List<RangedBlockElement>>> rows, List<ColumnAlignment> alignments
 });
 
 
@@ -473,11 +482,12 @@ class _$BlockElement_TableCopyWithImpl<$Res>
 
 /// Create a copy of BlockElement
 /// with the given fields replaced by the non-null parameter values.
-@pragma('vm:prefer-inline') $Res call({Object? head = null,Object? rows = null,}) {
+@pragma('vm:prefer-inline') $Res call({Object? head = null,Object? rows = null,Object? alignments = null,}) {
   return _then(BlockElement_Table(
 head: null == head ? _self._head : head // ignore: cast_nullable_to_non_nullable
 as List<List<RangedBlockElement>>,rows: null == rows ? _self._rows : rows // ignore: cast_nullable_to_non_nullable
-as List<List<List<RangedBlockElement>>>,
+as List<List<List<RangedBlockElement>>>,alignments: null == alignments ? _self._alignments : alignments // ignore: cast_nullable_to_non_nullable
+as List<ColumnAlignment>,
   ));
 }
 
@@ -654,6 +664,38 @@ as String,
 
 }
 
+/// @nodoc
+
+
+class BlockElement_Truncated extends BlockElement {
+  const BlockElement_Truncated(): super._();
+  
+
+
+
+
+
+
+@override
+bool operator ==(Object other) {
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is BlockElement_Truncated);
+}
+
+
+@override
+int get hashCode => runtimeType.hashCode;
+
+@override
+String toString() {
+  return 'BlockElement.truncated()';
+}
+
+
+}
+
+
+
+
 /// @nodoc
 mixin _$InlineElement {
 
@@ -1309,6 +1351,146 @@ as bool,
 }
 
 
+}
+
+/// @nodoc
+mixin _$MarkdownFeatures {
+
+/// `~~strikethrough~~`
+ bool get strikethrough;/// GitHub flavored tables
+ bool get tables;/// `- [ ]` and `- [x]` list items
+ bool get taskLists;
+/// Create a copy of MarkdownFeatures
+/// with the given fields replaced by the non-null parameter values.
+@JsonKey(includeFromJson: false, includeToJson: false)
+@pragma('vm:prefer-inline')
+$MarkdownFeaturesCopyWith<MarkdownFeatures> get copyWith => _$MarkdownFeaturesCopyWithImpl<MarkdownFeatures>(this as MarkdownFeatures, _$identity);
+
+
+
+@override
+bool operator ==(Object other) {
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is MarkdownFeatures&&(identical(other.strikethrough, strikethrough) || other.strikethrough == strikethrough)&&(identical(other.tables, tables) || other.tables == tables)&&(identical(other.taskLists, taskLists) || other.taskLists == taskLists));
+}
+
+
+@override
+int get hashCode => Object.hash(runtimeType,strikethrough,tables,taskLists);
+
+@override
+String toString() {
+  return 'MarkdownFeatures(strikethrough: $strikethrough, tables: $tables, taskLists: $taskLists)';
+}
+
+
+}
+
+/// @nodoc
+abstract mixin class $MarkdownFeaturesCopyWith<$Res>  {
+  factory $MarkdownFeaturesCopyWith(MarkdownFeatures value, $Res Function(MarkdownFeatures) _then) = _$MarkdownFeaturesCopyWithImpl;
+@useResult
+$Res call({
+ bool strikethrough, bool tables, bool taskLists
+});
+
+
+
+
+}
+/// @nodoc
+class _$MarkdownFeaturesCopyWithImpl<$Res>
+    implements $MarkdownFeaturesCopyWith<$Res> {
+  _$MarkdownFeaturesCopyWithImpl(this._self, this._then);
+
+  final MarkdownFeatures _self;
+  final $Res Function(MarkdownFeatures) _then;
+
+/// Create a copy of MarkdownFeatures
+/// with the given fields replaced by the non-null parameter values.
+@pragma('vm:prefer-inline') @override $Res call({Object? strikethrough = null,Object? tables = null,Object? taskLists = null,}) {
+  return _then(_self.copyWith(
+strikethrough: null == strikethrough ? _self.strikethrough : strikethrough // ignore: cast_nullable_to_non_nullable
+as bool,tables: null == tables ? _self.tables : tables // ignore: cast_nullable_to_non_nullable
+as bool,taskLists: null == taskLists ? _self.taskLists : taskLists // ignore: cast_nullable_to_non_nullable
+as bool,
+  ));
+}
+
+}
+
+
+
+/// @nodoc
+
+
+class _MarkdownFeatures extends MarkdownFeatures {
+  const _MarkdownFeatures({required this.strikethrough, required this.tables, required this.taskLists}): super._();
+  
+
+/// `~~strikethrough~~`
+@override final  bool strikethrough;
+/// GitHub flavored tables
+@override final  bool tables;
+/// `- [ ]` and `- [x]` list items
+@override final  bool taskLists;
+
+/// Create a copy of MarkdownFeatures
+/// with the given fields replaced by the non-null parameter values.
+@override @JsonKey(includeFromJson: false, includeToJson: false)
+@pragma('vm:prefer-inline')
+_$MarkdownFeaturesCopyWith<_MarkdownFeatures> get copyWith => __$MarkdownFeaturesCopyWithImpl<_MarkdownFeatures>(this, _$identity);
+
+
+
+@override
+bool operator ==(Object other) {
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is _MarkdownFeatures&&(identical(other.strikethrough, strikethrough) || other.strikethrough == strikethrough)&&(identical(other.tables, tables) || other.tables == tables)&&(identical(other.taskLists, taskLists) || other.taskLists == taskLists));
+}
+
+
+@override
+int get hashCode => Object.hash(runtimeType,strikethrough,tables,taskLists);
+
+@override
+String toString() {
+  return 'MarkdownFeatures(strikethrough: $strikethrough, tables: $tables, taskLists: $taskLists)';
+}
+
+
+}
+
+/// @nodoc
+abstract mixin class _$MarkdownFeaturesCopyWith<$Res> implements $MarkdownFeaturesCopyWith<$Res> {
+  factory _$MarkdownFeaturesCopyWith(_MarkdownFeatures value, $Res Function(_MarkdownFeatures) _then) = __$MarkdownFeaturesCopyWithImpl;
+@override @useResult
+$Res call({
+ bool strikethrough, bool tables, bool taskLists
+});
+
+
+
+
+}
+/// @nodoc
+class __$MarkdownFeaturesCopyWithImpl<$Res>
+    implements _$MarkdownFeaturesCopyWith<$Res> {
+  __$MarkdownFeaturesCopyWithImpl(this._self, this._then);
+
+  final _MarkdownFeatures _self;
+  final $Res Function(_MarkdownFeatures) _then;
+
+/// Create a copy of MarkdownFeatures
+/// with the given fields replaced by the non-null parameter values.
+@override @pragma('vm:prefer-inline') $Res call({Object? strikethrough = null,Object? tables = null,Object? taskLists = null,}) {
+  return _then(_MarkdownFeatures(
+strikethrough: null == strikethrough ? _self.strikethrough : strikethrough // ignore: cast_nullable_to_non_nullable
+as bool,tables: null == tables ? _self.tables : tables // ignore: cast_nullable_to_non_nullable
+as bool,taskLists: null == taskLists ? _self.taskLists : taskLists // ignore: cast_nullable_to_non_nullable
+as bool,
+  ));
+}
+
+
 }
 
 /// @nodoc
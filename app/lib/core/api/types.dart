@@ -19,7 +19,12 @@ part 'types.freezed.dart';
 // These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `assert_fields_are_eq`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `cmp`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `eq`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `hash`, `partial_cmp`
 
 /// Mirror of the [`AddUsernameContactError`] type
-enum AddUsernameContactError { usernameNotFound, duplicateRequest, ownUsername }
+enum AddUsernameContactError {
+  usernameNotFound,
+  duplicateRequest,
+  ownUsername,
+  tooManyPendingRequests,
+}
 
 @freezed
 sealed class AirComponent with _$AirComponent {
@@ -35,6 +40,9 @@ sealed class AirFeatures with _$AirFeatures {
     required bool encryptedGroupProfiles,
     required bool emptyConnectionGroupAttributes,
     required bool pqGroups,
+    required bool typingIndicators,
+    required bool presence,
+    required bool userProfileStatusText,
   }) = _AirFeatures;
 }
 
@@ -393,6 +401,14 @@ class UiInactiveChat {
           pastMembers == other.pastMembers;
 }
 
+/// Role of a member in a group
+enum UiMemberRole {
+  regular,
+
+  /// Any role with more permissions than a regular member
+  privileged,
+}
+
 @freezed
 sealed class UiMessage with _$UiMessage {
   const UiMessage._();
@@ -478,6 +494,11 @@ sealed class UiSystemMessage with _$UiSystemMessage {
       UiSystemMessage_NewDirectConnectionChat;
   const factory UiSystemMessage.createGroup(UiUserId field0) =
       UiSystemMessage_CreateGroup;
+  const factory UiSystemMessage.changeRole({
+    required UiUserId sender,
+    required UiUserId target,
+    required UiMemberRole role,
+  }) = UiSystemMessage_ChangeRole;
 }
 
 /// UI representation of an [`UserId`]
@@ -163,7 +163,7 @@ $AirFeaturesCopyWith<$Res> get features {
 /// @nodoc
 mixin _$AirFeatures {
 
- bool get encryptedGroupProfiles; bool get emptyConnectionGroupAttributes; bool get pqGroups;
+ bool get encryptedGroupProfiles; bool get emptyConnectionGroupAttributes; bool get pqGroups; bool get typingIndicators; bool get presence; bool get userProfileStatusText;
 /// Create a copy of AirFeatures
 /// with the given fields replaced by the non-null parameter values.
 @JsonKey(includeFromJson: false, includeToJson: false)
@@ -174,16 +174,16 @@ $AirFeaturesCopyWith<AirFeatures> get copyWith => _$AirFeaturesCopyWithImpl<AirF
 
 @override
 bool operator ==(Object other) {
-  return identical(this, other) || (other.runtimeType == runtimeType&&other is AirFeatures&&(identical(other.encryptedGroupProfiles, encryptedGroupProfiles) || other.encryptedGroupProfiles == encryptedGroupProfiles)&&(identical(other.emptyConnectionGroupAttributes, emptyConnectionGroupAttributes) || other.emptyConnectionGroupAttributes == emptyConnectionGroupAttributes)&&(identical(other.pqGroups, pqGroups) || other.pqGroups == pqGroups));
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is AirFeatures&&(identical(other.encryptedGroupProfiles, encryptedGroupProfiles) || other.encryptedGroupProfiles == encryptedGroupProfiles)&&(identical(other.emptyConnectionGroupAttributes, emptyConnectionGroupAttributes) || other.emptyConnectionGroupAttributes == emptyConnectionGroupAttributes)&&(identical(other.pqGroups, pqGroups) || other.pqGroups == pqGroups)&&(identical(other.typingIndicators, typingIndicators) || other.typingIndicators == typingIndicators)&&(identical(other.presence, presence) || other.presence == presence)&&(identical(other.userProfileStatusText, userProfileStatusText) || other.userProfileStatusText == userProfileStatusText));
 }
 
 
 @override
-int get hashCode => Object.hash(runtimeType,encryptedGroupProfiles,emptyConnectionGroupAttributes,pqGroups);
+int get hashCode => Object.hash(runtimeType,encryptedGroupProfiles,emptyConnectionGroupAttributes,pqGroups,typingIndicators,presence,userProfileStatusText);
 
 @override
 String toString() {
-  return 'AirFeatures(encryptedGroupProfiles: $encryptedGroupProfiles, emptyConnectionGroupAttributes: $emptyConnectionGroupAttributes, pqGroups: $pqGroups)';
+  return 'AirFeatures(encryptedGroupProfiles: $encryptedGroupProfiles, emptyConnectionGroupAttributes: $emptyConnectionGroupAttributes, pqGroups: $pqGroups, typingIndicators: $typingIndicators, presence: $presence, userProfileStatusText: $userProfileStatusText)';
 }
 
 
@@ -194,7 +194,7 @@ abstract mixin class $AirFeaturesCopyWith<$Res>  {
   factory $AirFeaturesCopyWith(AirFeatures value, $Res Function(AirFeatures) _then) = _$AirFeaturesCopyWithImpl;
 @useResult
 $Res call({
- bool encryptedGroupProfiles, bool emptyConnectionGroupAttributes, bool pqGroups
+ bool encryptedGroupProfiles, bool emptyConnectionGroupAttributes, bool pqGroups, bool typingIndicators, bool presence, bool userProfileStatusText
 });
 
 
@@ -211,11 +211,14 @@ class _$AirFeaturesCopyWithImpl<$Res>
 
 /// Create a copy of AirFeatures
 /// with the given fields replaced by the non-null parameter values.
-@pragma('vm:prefer-inline') @override $Res call({Object? encryptedGroupProfiles = null,Object? emptyConnectionGroupAttributes = null,Object? pqGroups = null,}) {
+@pragma('vm:prefer-inline') @override $Res call({Object? encryptedGroupProfiles = null,Object? emptyConnectionGroupAttributes = null,Object? pqGroups = null,Object? typingIndicators = null,Object? presence = null,Object? userProfileStatusText = null,}) {
   return _then(_self.copyWith(
 encryptedGroupProfiles: null == encryptedGroupProfiles ? _self.encryptedGroupProfiles : encryptedGroupProfiles // ignore: cast_nullable_to_non_nullable
 as bool,emptyConnectionGroupAttributes: null == emptyConnectionGroupAttributes ? _self.emptyConnectionGroupAttributes : emptyConnectionGroupAttributes // ignore: cast_nullable_to_non_nullable
 as bool,pqGroups: null == pqGroups ? _self.pqGroups : pqGroups // ignore: cast_nullable_to_non_nullable
+as bool,typingIndicators: null == typingIndicators ? _self.typingIndicators : typingIndicators // ignore: cast_nullable_to_non_nullable
+as bool,presence: null == presence ? _self.presence : presence // ignore: cast_nullable_to_non_nullable
+as bool,userProfileStatusText: null == userProfileStatusText ? _self.userProfileStatusText : userProfileStatusText // ignore: cast_nullable_to_non_nullable
 as bool,
   ));
 }
@@ -228,12 +231,15 @@ as bool,
 
 
 class _AirFeatures implements AirFeatures {
-  const _AirFeatures({required this.encryptedGroupProfiles, required this.emptyConnectionGroupAttributes, required this.pqGroups});
+  const _AirFeatures({required this.encryptedGroupProfiles, required this.emptyConnectionGroupAttributes, required this.pqGroups, required this.typingIndicators, required this.presence, required this.userProfileStatusText});
   
 
 @override final  bool encryptedGroupProfiles;
 @override final  bool emptyConnectionGroupAttributes;
 @override final  bool pqGroups;
+@override final  bool typingIndicators;
+@override final  bool presence;
+@override final  bool userProfileStatusText;
 
 /// Create a copy of AirFeatures
 /// with the given fields replaced by the non-null parameter values.
@@ -245,16 +251,16 @@ _$AirFeaturesCopyWith<_AirFeatures> get copyWith => __$AirFeaturesCopyWithImpl<_
 
 @override
 bool operator ==(Object other) {
-  return identical(this, other) || (other.runtimeType == runtimeType&&other is _AirFeatures&&(identical(other.encryptedGroupProfiles, encryptedGroupProfiles) || other.encryptedGroupProfiles == encryptedGroupProfiles)&&(identical(other.emptyConnectionGroupAttributes, emptyConnectionGroupAttributes) || other.emptyConnectionGroupAttributes == emptyConnectionGroupAttributes)&&(identical(other.pqGroups, pqGroups) || other.pqGroups == pqGroups));
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is _AirFeatures&&(identical(other.encryptedGroupProfiles, encryptedGroupProfiles) || other.encryptedGroupProfiles == encryptedGroupProfiles)&&(identical(other.emptyConnectionGroupAttributes, emptyConnectionGroupAttributes) || other.emptyConnectionGroupAttributes == emptyConnectionGroupAttributes)&&(identical(other.pqGroups, pqGroups) || other.pqGroups == pqGroups)&&(identical(other.typingIndicators, typingIndicators) || other.typingIndicators == typingIndicators)&&(identical(other.presence, presence) || other.presence == presence)&&(identical(other.userProfileStatusText, userProfileStatusText) || other.userProfileStatusText == userProfileStatusText));
 }
 
 
 @override
-int get hashCode => Object.hash(runtimeType,encryptedGroupProfiles,emptyConnectionGroupAttributes,pqGroups);
+int get hashCode => Object.hash(runtimeType,encryptedGroupProfiles,emptyConnectionGroupAttributes,pqGroups,typingIndicators,presence,userProfileStatusText);
 
 @override
 String toString() {
-  return 'AirFeatures(encryptedGroupProfiles: $encryptedGroupProfiles, emptyConnectionGroupAttributes: $emptyConnectionGroupAttributes, pqGroups: $pqGroups)';
+  return 'AirFeatures(encryptedGroupProfiles: $encryptedGroupProfiles, emptyConnectionGroupAttributes: $emptyConnectionGroupAttributes, pqGroups: $pqGroups, typingIndicators: $typingIndicators, presence: $presence, userProfileStatusText: $userProfileStatusText)';
 }
 
 
@@ -265,7 +271,7 @@ abstract mixin class _$AirFeaturesCopyWith<$Res> implements $AirFeaturesCopyWith
   factory _$AirFeaturesCopyWith(_AirFeatures value, $Res Function(_AirFeatures) _then) = __$AirFeaturesCopyWithImpl;
 @override @useResult
 $Res call({
- bool encryptedGroupProfiles, bool emptyConnectionGroupAttributes, bool pqGroups
+ bool encryptedGroupProfiles, bool emptyConnectionGroupAttributes, bool pqGroups, bool typingIndicators, bool presence, bool userProfileStatusText
 });
 
 
@@ -282,11 +288,14 @@ class __$AirFeaturesCopyWithImpl<$Res>
 
 /// Create a copy of AirFeatures
 /// with the given fields replaced by the non-null parameter values.
-@override @pragma('vm:prefer-inline') $Res call({Object? encryptedGroupProfiles = null,Object? emptyConnectionGroupAttributes = null,Object? pqGroups = null,}) {
+@override @pragma('vm:prefer-inline') $Res call({Object? encryptedGroupProfiles = null,Object? emptyConnectionGroupAttributes = null,Object? pqGroups = null,Object? typingIndicators = null,Object? presence = null,Object? userProfileStatusText = null,}) {
   return _then(_AirFeatures(
 encryptedGroupProfiles: null == encryptedGroupProfiles ? _self.encryptedGroupProfiles : encryptedGroupProfiles // ignore: cast_nullable_to_non_nullable
 as bool,emptyConnectionGroupAttributes: null == emptyConnectionGroupAttributes ? _self.emptyConnectionGroupAttributes : emptyConnectionGroupAttributes // ignore: cast_nullable_to_non_nullable
 as bool,pqGroups: null == pqGroups ? _self.pqGroups : pqGroups // ignore: cast_nullable_to_non_nullable
+as bool,typingIndicators: null == typingIndicators ? _self.typingIndicators : typingIndicators // ignore: cast_nullable_to_non_nullable
+as bool,presence: null == presence ? _self.presence : presence // ignore: cast_nullable_to_non_nullable
+as bool,userProfileStatusText: null == userProfileStatusText ? _self.userProfileStatusText : userProfileStatusText // ignore: cast_nullable_to_non_nullable
 as bool,
   ));
 }
@@ -3028,6 +3037,76 @@ as UiUserId,
 }
 
 
+}
+
+/// @nodoc
+
+
+class UiSystemMessage_ChangeRole extends UiSystemMessage {
+  const UiSystemMessage_ChangeRole({required this.sender, required this.target, required this.role}): super._();
+  
+
+ final  UiUserId sender;
+ final  UiUserId target;
+ final  UiMemberRole role;
+
+/// Create a copy of UiSystemMessage
+/// with the given fields replaced by the non-null parameter values.
+@JsonKey(includeFromJson: false, includeToJson: false)
+@pragma('vm:prefer-inline')
+$UiSystemMessage_ChangeRoleCopyWith<UiSystemMessage_ChangeRole> get copyWith => _$UiSystemMessage_ChangeRoleCopyWithImpl<UiSystemMessage_ChangeRole>(this, _$identity);
+
+
+
+@override
+bool operator ==(Object other) {
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is UiSystemMessage_ChangeRole&&(identical(other.sender, sender) || other.sender == sender)&&(identical(other.target, target) || other.target == target)&&(identical(other.role, role) || other.role == role));
+}
+
+
+@override
+int get hashCode => Object.hash(runtimeType,sender,target,role);
+
+@override
+String toString() {
+  return 'UiSystemMessage.changeRole(sender: $sender, target: $target, role: $role)';
+}
+
+
+}
+
+/// @nodoc
+abstract mixin class $UiSystemMessage_ChangeRoleCopyWith<$Res> implements $UiSystemMessageCopyWith<$Res> {
+  factory $UiSystemMessage_ChangeRoleCopyWith(UiSystemMessage_ChangeRole value, $Res Function(UiSystemMessage_ChangeRole) _then) = _$UiSystemMessage_ChangeRoleCopyWithImpl;
+@useResult
+$Res call({
+ UiUserId sender, UiUserId target, UiMemberRole role
+});
+
+
+
+
+}
+/// @nodoc
+class _$UiSystemMessage_ChangeRoleCopyWithImpl<$Res>
+    implements $UiSystemMessage_ChangeRoleCopyWith<$Res> {
+  _$UiSystemMessage_ChangeRoleCopyWithImpl(this._self, this._then);
+
+  final UiSystemMessage_ChangeRole _self;
+  final $Res Function(UiSystemMessage_ChangeRole) _then;
+
+/// Create a copy of UiSystemMessage
+/// with the given fields replaced by the non-null parameter values.
+@pragma('vm:prefer-inline') $Res call({Object? sender = null,Object? target = null,Object? role = null,}) {
+  return _then(UiSystemMessage_ChangeRole(
+sender: null == sender ? _self.sender : sender // ignore: cast_nullable_to_non_nullable
+as UiUserId,target: null == target ? _self.target : target // ignore: cast_nullable_to_non_nullable
+as UiUserId,role: null == role ? _self.role : role // ignore: cast_nullable_to_non_nullable
+as UiMemberRole,
+  ));
+}
+
+
 }
 
 /// @nodoc
@@ -35,6 +35,9 @@ class intArray12 extends NonGrowableListView<int> {
 
 // Rust type: RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<UiUser>>
 abstract class UiUser implements RustOpaqueInterface {
+  /// Whether the connection to the server was lost and is being re-established
+  bool get isReconnecting;
+
   bool get unsupportedVersion;
 
   UiUserId get userId;
@@ -536,6 +536,8 @@ abstract class RustLibApi extends BaseApi {
     required UiUserId target,
   });
 
+  bool crateApiUserCubitUiUserIsReconnecting({required UiUser that});
+
   bool crateApiUserCubitUiUserUnsupportedVersion({required UiUser that});
 
   UiUserId crateApiUserCubitUiUserUserId({required UiUser that});
@@ -820,6 +822,8 @@ abstract class RustLibApi extends BaseApi {
     required String invitationCode,
   });
 
+  Future<ColumnAlignment> crateApiMarkdownColumnAlignmentDefault();
+
   Future<void> crateApiLoggingClearAppLogs();
 
   Future<void> crateApiLoggingClearBackgroundLogs({required String cacheDir});
@@ -842,11 +846,18 @@ abstract class RustLibApi extends BaseApi {
 
   LogWriter crateApiLoggingInitRustLogging({required String logFile});
 
+  LogWriter crateApiLoggingInitRustLoggingWithRotation({
+    required String logFile,
+    required int rotatedSegments,
+  });
+
   Future<InvitationCodesState>
   crateApiInvitationCodesCubitInvitationCodesStateDefault();
 
   Future<bool> crateApiUtilsIsImageFile({required String path});
 
+  Future<MarkdownFeatures> crateApiMarkdownMarkdownFeaturesDefault();
+
   Future<MemberDetailsState>
   crateApiMemberDetailsCubitMemberDetailsStateDefault();
 
@@ -862,6 +873,20 @@ abstract class RustLibApi extends BaseApi {
     required List<int> string,
   });
 
+  MessageContent crateApiMarkdownMessageContentParseMarkdownResult({
+    required String string,
+  });
+
+  Future<MessageContent> crateApiMarkdownMessageContentParseMarkdownTruncated({
+    required String string,
+    required BigInt maxChars,
+  });
+
+  Future<MessageContent> crateApiMarkdownMessageContentParseMarkdownWith({
+    required String string,
+    required MarkdownFeatures features,
+  });
+
   Future<MessageListState> crateApiMessageListCubitMessageListStateDefault();
 
   Stream<MultiDeviceLinkEvent> crateApiMultiDeviceMultiDeviceLinkClient({
@@ -885,6 +910,14 @@ abstract class RustLibApi extends BaseApi {
 
   Future<Uint8List?> crateApiUtilsReadClipboardImage();
 
+  void crateApiLoggingSetRustLogDirectives({
+    required String directives,
+  });
+
+  void crateApiLoggingSetRustLogLevel({
+    required LogEntryLevel level,
+  });
+
   Future<Uint8List> crateApiLoggingTarLogs({required String cacheDir});
 
   UsernameValidationError? crateApiTypesUiUsernameValidationError({
@@ -4592,6 +4625,35 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         argNames: ["that", "target"],
       );
 
+  @override
+  bool crateApiUserCubitUiUserIsReconnecting({required UiUser that}) {
+    return handler.executeSync(
+      SyncTask(
+        callFfi: () {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerUiUser(
+            that,
+            serializer,
+          );
+          return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 191)!;
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_bool,
+          decodeErrorData: null,
+        ),
+        constMeta: kCrateApiUserCubitUiUserIsReconnectingConstMeta,
+        argValues: [that],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiUserCubitUiUserIsReconnectingConstMeta =>
+      const TaskConstMeta(
+        debugName: "UiUser_is_reconnecting",
+        argNames: ["that"],
+      );
+
   @override
   bool crateApiUserCubitUiUserUnsupportedVersion({required UiUser that}) {
     return handler.executeSync(
@@ -7031,6 +7093,36 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         argNames: ["domain", "invitationCode"],
       );
 
+  @override
+  Future<ColumnAlignment> crateApiMarkdownColumnAlignmentDefault() {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 183,
+            port: port_,
+          );
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_column_alignment,
+          decodeErrorData: null,
+        ),
+        constMeta: kCrateApiMarkdownColumnAlignmentDefaultConstMeta,
+        argValues: [],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiMarkdownColumnAlignmentDefaultConstMeta =>
+      const TaskConstMeta(
+        debugName: "column_alignment_default",
+        argNames: [],
+      );
+
   @override
   Future<void> crateApiLoggingClearAppLogs() {
     return handler.executeNormal(
@@ -7304,6 +7396,40 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         argNames: ["logFile"],
       );
 
+  @override
+  LogWriter crateApiLoggingInitRustLoggingWithRotation({
+    required String logFile,
+    required int rotatedSegments,
+  }) {
+    return handler.executeSync(
+      SyncTask(
+        callFfi: () {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(logFile, serializer);
+          sse_encode_u_32(rotatedSegments, serializer);
+          return pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 184,
+          )!;
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLogWriter,
+          decodeErrorData: null,
+        ),
+        constMeta: kCrateApiLoggingInitRustLoggingWithRotationConstMeta,
+        argValues: [logFile, rotatedSegments],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiLoggingInitRustLoggingWithRotationConstMeta =>
+      const TaskConstMeta(
+        debugName: "init_rust_logging_with_rotation",
+        argNames: ["logFile", "rotatedSegments"],
+      );
+
   @override
   Future<InvitationCodesState>
   crateApiInvitationCodesCubitInvitationCodesStateDefault() {
@@ -7365,6 +7491,36 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   TaskConstMeta get kCrateApiUtilsIsImageFileConstMeta =>
       const TaskConstMeta(debugName: "is_image_file", argNames: ["path"]);
 
+  @override
+  Future<MarkdownFeatures> crateApiMarkdownMarkdownFeaturesDefault() {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 185,
+            port: port_,
+          );
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_markdown_features,
+          decodeErrorData: null,
+        ),
+        constMeta: kCrateApiMarkdownMarkdownFeaturesDefaultConstMeta,
+        argValues: [],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiMarkdownMarkdownFeaturesDefaultConstMeta =>
+      const TaskConstMeta(
+        debugName: "markdown_features_default",
+        argNames: [],
+      );
+
   @override
   Future<MemberDetailsState>
   crateApiMemberDetailsCubitMemberDetailsStateDefault() {
@@ -7496,6 +7652,108 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         argNames: ["string"],
       );
 
+  @override
+  MessageContent crateApiMarkdownMessageContentParseMarkdownResult({
+    required String string,
+  }) {
+    return handler.executeSync(
+      SyncTask(
+        callFfi: () {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(string, serializer);
+          return pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 186,
+          )!;
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_message_content,
+          decodeErrorData: sse_decode_error,
+        ),
+        constMeta: kCrateApiMarkdownMessageContentParseMarkdownResultConstMeta,
+        argValues: [string],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiMarkdownMessageContentParseMarkdownResultConstMeta =>
+      const TaskConstMeta(
+        debugName: "message_content_parse_markdown_result",
+        argNames: ["string"],
+      );
+
+  @override
+  Future<MessageContent> crateApiMarkdownMessageContentParseMarkdownTruncated({
+    required String string,
+    required BigInt maxChars,
+  }) {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(string, serializer);
+          sse_encode_usize(maxChars, serializer);
+          pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 187,
+            port: port_,
+          );
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_message_content,
+          decodeErrorData: null,
+        ),
+        constMeta: kCrateApiMarkdownMessageContentParseMarkdownTruncatedConstMeta,
+        argValues: [string, maxChars],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiMarkdownMessageContentParseMarkdownTruncatedConstMeta =>
+      const TaskConstMeta(
+        debugName: "message_content_parse_markdown_truncated",
+        argNames: ["string", "maxChars"],
+      );
+
+  @override
+  Future<MessageContent> crateApiMarkdownMessageContentParseMarkdownWith({
+    required String string,
+    required MarkdownFeatures features,
+  }) {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(string, serializer);
+          sse_encode_box_autoadd_markdown_features(features, serializer);
+          pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 188,
+            port: port_,
+          );
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_message_content,
+          decodeErrorData: null,
+        ),
+        constMeta: kCrateApiMarkdownMessageContentParseMarkdownWithConstMeta,
+        argValues: [string, features],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiMarkdownMessageContentParseMarkdownWithConstMeta =>
+      const TaskConstMeta(
+        debugName: "message_content_parse_markdown_with",
+        argNames: ["string", "features"],
+      );
+
   @override
   Future<MessageListState> crateApiMessageListCubitMessageListStateDefault() {
     return handler.executeNormal(
@@ -7735,6 +7993,70 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   TaskConstMeta get kCrateApiUtilsReadClipboardImageConstMeta =>
       const TaskConstMeta(debugName: "read_clipboard_image", argNames: []);
 
+  @override
+  void crateApiLoggingSetRustLogDirectives({
+    required String directives,
+  }) {
+    return handler.executeSync(
+      SyncTask(
+        callFfi: () {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_String(directives, serializer);
+          return pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 189,
+          )!;
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        ),
+        constMeta: kCrateApiLoggingSetRustLogDirectivesConstMeta,
+        argValues: [directives],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiLoggingSetRustLogDirectivesConstMeta =>
+      const TaskConstMeta(
+        debugName: "set_rust_log_directives",
+        argNames: ["directives"],
+      );
+
+  @override
+  void crateApiLoggingSetRustLogLevel({
+    required LogEntryLevel level,
+  }) {
+    return handler.executeSync(
+      SyncTask(
+        callFfi: () {
+          final serializer = SseSerializer(generalizedFrbRustBinding);
+          sse_encode_log_entry_level(level, serializer);
+          return pdeCallFfi(
+            generalizedFrbRustBinding,
+            serializer,
+            funcId: 190,
+          )!;
+        },
+        codec: SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        ),
+        constMeta: kCrateApiLoggingSetRustLogLevelConstMeta,
+        argValues: [level],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiLoggingSetRustLogLevelConstMeta =>
+      const TaskConstMeta(
+        debugName: "set_rust_log_level",
+        argNames: ["level"],
+      );
+
   @override
   Future<Uint8List> crateApiLoggingTarLogs({required String cacheDir}) {
     return handler.executeNormal(
@@ -8979,12 +9301,15 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   AirFeatures dco_decode_air_features(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
     final arr = raw as List<dynamic>;
-    if (arr.length != 3)
-      throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+    if (arr.length != 6)
+      throw Exception('unexpected arr length: expect 6 but see ${arr.length}');
     return AirFeatures(
       encryptedGroupProfiles: dco_decode_bool(arr[0]),
       emptyConnectionGroupAttributes: dco_decode_bool(arr[1]),
       pqGroups: dco_decode_bool(arr[2]),
+      typingIndicators: dco_decode_bool(arr[3]),
+      presence: dco_decode_bool(arr[4]),
+      userProfileStatusText: dco_decode_bool(arr[5]),
     );
   }
 
@@ -9042,6 +9367,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         return BlockElement_Table(
           head: dco_decode_list_list_ranged_block_element(raw[1]),
           rows: dco_decode_list_list_list_ranged_block_element(raw[2]),
+          alignments: dco_decode_list_column_alignment(raw[3]),
         );
       case 6:
         return BlockElement_HorizontalRule();
@@ -9051,6 +9377,8 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         );
       case 8:
         return BlockElement_Error(dco_decode_String(raw[1]));
+      case 9:
+        return BlockElement_Truncated();
       default:
         throw Exception("unreachable");
     }
@@ -9223,6 +9551,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return dco_decode_invite_users_error(raw);
   }
 
+  @protected
+  MarkdownFeatures dco_decode_box_autoadd_markdown_features(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return dco_decode_markdown_features(raw);
+  }
+
   @protected
   MessageContent dco_decode_box_autoadd_message_content(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -9437,6 +9771,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return ChatListState(chatIds: dco_decode_list_chat_id(arr[0]));
   }
 
+  @protected
+  ColumnAlignment dco_decode_column_alignment(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return ColumnAlignment.values[raw as int];
+  }
+
   @protected
   DebugCapabilities dco_decode_debug_capabilities(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -9483,6 +9823,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     );
   }
 
+  @protected
+  Error dco_decode_error(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return Error.values[raw as int];
+  }
+
   @protected
   ExternalGroupProfileDebugInfo dco_decode_external_group_profile_debug_info(
     dynamic raw,
@@ -9718,6 +10064,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return (raw as List<dynamic>).map(dco_decode_chat_id).toList();
   }
 
+  @protected
+  List<ColumnAlignment> dco_decode_list_column_alignment(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return (raw as List<dynamic>).map(dco_decode_column_alignment).toList();
+  }
+
   @protected
   List<IntroScreenType> dco_decode_list_intro_screen_type(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -9891,6 +10243,19 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return LogEntryLevel.values[raw as int];
   }
 
+  @protected
+  MarkdownFeatures dco_decode_markdown_features(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 3)
+      throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+    return MarkdownFeatures(
+      strikethrough: dco_decode_bool(arr[0]),
+      tables: dco_decode_bool(arr[1]),
+      taskLists: dco_decode_bool(arr[2]),
+    );
+  }
+
   @protected
   MemberDetailsState dco_decode_member_details_state(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -10072,13 +10437,14 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   NotificationContent dco_decode_notification_content(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
     final arr = raw as List<dynamic>;
-    if (arr.length != 4)
-      throw Exception('unexpected arr length: expect 4 but see ${arr.length}');
+    if (arr.length != 5)
+      throw Exception('unexpected arr length: expect 5 but see ${arr.length}');
     return NotificationContent(
       identifier: dco_decode_notification_id(arr[0]),
       title: dco_decode_String(arr[1]),
       body: dco_decode_String(arr[2]),
       chatId: dco_decode_chat_id(arr[3]),
+      newMessages: dco_decode_u_32(arr[4]),
     );
   }
 
@@ -10870,6 +11236,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     }
   }
 
+  @protected
+  UiMemberRole dco_decode_ui_member_role(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return UiMemberRole.values[raw as int];
+  }
+
   @protected
   UiMessage dco_decode_ui_message(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -11002,6 +11374,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         return UiSystemMessage_CreateGroup(
           dco_decode_box_autoadd_ui_user_id(raw[1]),
         );
+      case 11:
+        return UiSystemMessage_ChangeRole(
+          sender: dco_decode_box_autoadd_ui_user_id(raw[1]),
+          target: dco_decode_box_autoadd_ui_user_id(raw[2]),
+          role: dco_decode_ui_member_role(raw[3]),
+        );
       default:
         throw Exception("unreachable");
     }
@@ -11056,6 +11434,8 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           maxSizeBytes: dco_decode_u_64(raw[1]),
           actualSizeBytes: dco_decode_u_64(raw[2]),
         );
+      case 1:
+        return UploadAttachmentError_UnsupportedImageFormat();
       default:
         throw Exception("unreachable");
     }
@@ -12085,10 +12465,16 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     var var_encryptedGroupProfiles = sse_decode_bool(deserializer);
     var var_emptyConnectionGroupAttributes = sse_decode_bool(deserializer);
     var var_pqGroups = sse_decode_bool(deserializer);
+    var var_typingIndicators = sse_decode_bool(deserializer);
+    var var_presence = sse_decode_bool(deserializer);
+    var var_userProfileStatusText = sse_decode_bool(deserializer);
     return AirFeatures(
       encryptedGroupProfiles: var_encryptedGroupProfiles,
       emptyConnectionGroupAttributes: var_emptyConnectionGroupAttributes,
       pqGroups: var_pqGroups,
+      typingIndicators: var_typingIndicators,
+      presence: var_presence,
+      userProfileStatusText: var_userProfileStatusText,
     );
   }
 
@@ -12152,7 +12538,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         var var_rows = sse_decode_list_list_list_ranged_block_element(
           deserializer,
         );
-        return BlockElement_Table(head: var_head, rows: var_rows);
+        var var_alignments = sse_decode_list_column_alignment(deserializer);
+        return BlockElement_Table(
+          head: var_head,
+          rows: var_rows,
+          alignments: var_alignments,
+        );
       case 6:
         return BlockElement_HorizontalRule();
       case 7:
@@ -12161,6 +12552,8 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       case 8:
         var var_field0 = sse_decode_String(deserializer);
         return BlockElement_Error(var_field0);
+      case 9:
+        return BlockElement_Truncated();
       default:
         throw UnimplementedError('');
     }
@@ -12355,6 +12748,14 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return (sse_decode_invite_users_error(deserializer));
   }
 
+  @protected
+  MarkdownFeatures sse_decode_box_autoadd_markdown_features(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    return (sse_decode_markdown_features(deserializer));
+  }
+
   @protected
   MessageContent sse_decode_box_autoadd_message_content(
     SseDeserializer deserializer,
@@ -12601,6 +13002,13 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return ChatListState(chatIds: var_chatIds);
   }
 
+  @protected
+  ColumnAlignment sse_decode_column_alignment(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var inner = sse_decode_i_32(deserializer);
+    return ColumnAlignment.values[inner];
+  }
+
   @protected
   DebugCapabilities sse_decode_debug_capabilities(
     SseDeserializer deserializer,
@@ -12657,6 +13065,13 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     );
   }
 
+  @protected
+  Error sse_decode_error(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var inner = sse_decode_i_32(deserializer);
+    return Error.values[inner];
+  }
+
   @protected
   ExternalGroupProfileDebugInfo sse_decode_external_group_profile_debug_info(
     SseDeserializer deserializer,
@@ -12941,6 +13356,20 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return ans_;
   }
 
+  @protected
+  List<ColumnAlignment> sse_decode_list_column_alignment(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    var len_ = sse_decode_i_32(deserializer);
+    var ans_ = <ColumnAlignment>[];
+    for (var idx_ = 0; idx_ < len_; ++idx_) {
+      ans_.add(sse_decode_column_alignment(deserializer));
+    }
+    return ans_;
+  }
+
   @protected
   List<IntroScreenType> sse_decode_list_intro_screen_type(
     SseDeserializer deserializer,
@@ -13244,6 +13673,19 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return LogEntryLevel.values[inner];
   }
 
+  @protected
+  MarkdownFeatures sse_decode_markdown_features(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_strikethrough = sse_decode_bool(deserializer);
+    var var_tables = sse_decode_bool(deserializer);
+    var var_taskLists = sse_decode_bool(deserializer);
+    return MarkdownFeatures(
+      strikethrough: var_strikethrough,
+      tables: var_tables,
+      taskLists: var_taskLists,
+    );
+  }
+
   @protected
   MemberDetailsState sse_decode_member_details_state(
     SseDeserializer deserializer,
@@ -13452,11 +13894,13 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     var var_title = sse_decode_String(deserializer);
     var var_body = sse_decode_String(deserializer);
     var var_chatId = sse_decode_chat_id(deserializer);
+    var var_newMessages = sse_decode_u_32(deserializer);
     return NotificationContent(
       identifier: var_identifier,
       title: var_title,
       body: var_body,
       chatId: var_chatId,
+      newMessages: var_newMessages,
     );
   }
 
@@ -14511,6 +14955,13 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     }
   }
 
+  @protected
+  UiMemberRole sse_decode_ui_member_role(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var inner = sse_decode_i_32(deserializer);
+    return UiMemberRole.values[inner];
+  }
+
   @protected
   UiMessage sse_decode_ui_message(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -14648,6 +15099,15 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       case 10:
         var var_field0 = sse_decode_box_autoadd_ui_user_id(deserializer);
         return UiSystemMessage_CreateGroup(var_field0);
+      case 11:
+        var var_sender = sse_decode_box_autoadd_ui_user_id(deserializer);
+        var var_target = sse_decode_box_autoadd_ui_user_id(deserializer);
+        var var_role = sse_decode_ui_member_role(deserializer);
+        return UiSystemMessage_ChangeRole(
+          sender: var_sender,
+          target: var_target,
+          role: var_role,
+        );
       default:
         throw UnimplementedError('');
     }
@@ -14703,6 +15163,8 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
           maxSizeBytes: var_maxSizeBytes,
           actualSizeBytes: var_actualSizeBytes,
         );
+      case 1:
+        return UploadAttachmentError_UnsupportedImageFormat();
       default:
         throw UnimplementedError('');
     }
@@ -16011,6 +16473,9 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_bool(self.encryptedGroupProfiles, serializer);
     sse_encode_bool(self.emptyConnectionGroupAttributes, serializer);
     sse_encode_bool(self.pqGroups, serializer);
+    sse_encode_bool(self.typingIndicators, serializer);
+    sse_encode_bool(self.presence, serializer);
+    sse_encode_bool(self.userProfileStatusText, serializer);
   }
 
   @protected
@@ -16055,10 +16520,15 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         sse_encode_i_32(4, serializer);
         sse_encode_u_64(field0, serializer);
         sse_encode_list_list_ranged_block_element(field1, serializer);
-      case BlockElement_Table(head: final head, rows: final rows):
+      case BlockElement_Table(
+        head: final head,
+        rows: final rows,
+        alignments: final alignments,
+      ):
         sse_encode_i_32(5, serializer);
         sse_encode_list_list_ranged_block_element(head, serializer);
         sse_encode_list_list_list_ranged_block_element(rows, serializer);
+        sse_encode_list_column_alignment(alignments, serializer);
       case BlockElement_HorizontalRule():
         sse_encode_i_32(6, serializer);
       case BlockElement_CodeBlock(field0: final field0):
@@ -16067,6 +16537,8 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       case BlockElement_Error(field0: final field0):
         sse_encode_i_32(8, serializer);
         sse_encode_String(field0, serializer);
+      case BlockElement_Truncated():
+        sse_encode_i_32(9, serializer);
     }
   }
 
@@ -16281,6 +16753,15 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_invite_users_error(self, serializer);
   }
 
+  @protected
+  void sse_encode_box_autoadd_markdown_features(
+    MarkdownFeatures self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_markdown_features(self, serializer);
+  }
+
   @protected
   void sse_encode_box_autoadd_message_content(
     MessageContent self,
@@ -16564,6 +17045,15 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_list_chat_id(self.chatIds, serializer);
   }
 
+  @protected
+  void sse_encode_column_alignment(
+    ColumnAlignment self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.index, serializer);
+  }
+
   @protected
   void sse_encode_debug_capabilities(
     DebugCapabilities self,
@@ -16605,6 +17095,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_String(self.aad, serializer);
   }
 
+  @protected
+  void sse_encode_error(Error self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.index, serializer);
+  }
+
   @protected
   void sse_encode_external_group_profile_debug_info(
     ExternalGroupProfileDebugInfo self,
@@ -16841,6 +17337,18 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     }
   }
 
+  @protected
+  void sse_encode_list_column_alignment(
+    List<ColumnAlignment> self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.length, serializer);
+    for (final item in self) {
+      sse_encode_column_alignment(item, serializer);
+    }
+  }
+
   @protected
   void sse_encode_list_intro_screen_type(
     List<IntroScreenType> self,
@@ -17119,6 +17627,17 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_i_32(self.index, serializer);
   }
 
+  @protected
+  void sse_encode_markdown_features(
+    MarkdownFeatures self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_bool(self.strikethrough, serializer);
+    sse_encode_bool(self.tables, serializer);
+    sse_encode_bool(self.taskLists, serializer);
+  }
+
   @protected
   void sse_encode_member_details_state(
     MemberDetailsState self,
@@ -17298,6 +17817,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_String(self.title, serializer);
     sse_encode_String(self.body, serializer);
     sse_encode_chat_id(self.chatId, serializer);
+    sse_encode_u_32(self.newMessages, serializer);
   }
 
   @protected
@@ -18265,6 +18785,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     }
   }
 
+  @protected
+  void sse_encode_ui_member_role(UiMemberRole self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.index, serializer);
+  }
+
   @protected
   void sse_encode_ui_message(UiMessage self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -18394,6 +18920,15 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       case UiSystemMessage_CreateGroup(field0: final field0):
         sse_encode_i_32(10, serializer);
         sse_encode_box_autoadd_ui_user_id(field0, serializer);
+      case UiSystemMessage_ChangeRole(
+        sender: final sender,
+        target: final target,
+        role: final role,
+      ):
+        sse_encode_i_32(11, serializer);
+        sse_encode_box_autoadd_ui_user_id(sender, serializer);
+        sse_encode_box_autoadd_ui_user_id(target, serializer);
+        sse_encode_ui_member_role(role, serializer);
     }
   }
 
@@ -18440,6 +18975,8 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
         sse_encode_i_32(0, serializer);
         sse_encode_u_64(maxSizeBytes, serializer);
         sse_encode_u_64(actualSizeBytes, serializer);
+      case UploadAttachmentError_UnsupportedImageFormat():
+        sse_encode_i_32(1, serializer);
     }
   }
 
@@ -19294,6 +19831,9 @@ class UiUserImpl extends RustOpaque implements UiUser {
         RustLib.instance.api.rust_arc_decrement_strong_count_UiUserPtr,
   );
 
+  bool get isReconnecting =>
+      RustLib.instance.api.crateApiUserCubitUiUserIsReconnecting(that: this);
+
   bool get unsupportedVersion => RustLib.instance.api
       .crateApiUserCubitUiUserUnsupportedVersion(that: this);
 
@@ -737,6 +737,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   InviteUsersError dco_decode_box_autoadd_invite_users_error(dynamic raw);
 
+  @protected
+  MarkdownFeatures dco_decode_box_autoadd_markdown_features(dynamic raw);
+
   @protected
   MessageContent dco_decode_box_autoadd_message_content(dynamic raw);
 
@@ -841,6 +844,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   ChatListState dco_decode_chat_list_state(dynamic raw);
 
+  @protected
+  ColumnAlignment dco_decode_column_alignment(dynamic raw);
+
   @protected
   DebugCapabilities dco_decode_debug_capabilities(dynamic raw);
 
@@ -857,6 +863,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     dynamic raw,
   );
 
+  @protected
+  Error dco_decode_error(dynamic raw);
+
   @protected
   ExternalGroupProfileDebugInfo dco_decode_external_group_profile_debug_info(
     dynamic raw,
@@ -913,6 +922,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   List<ChatId> dco_decode_list_chat_id(dynamic raw);
 
+  @protected
+  List<ColumnAlignment> dco_decode_list_column_alignment(dynamic raw);
+
   @protected
   List<IntroScreenType> dco_decode_list_intro_screen_type(dynamic raw);
 
@@ -990,6 +1002,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   LogEntryLevel dco_decode_log_entry_level(dynamic raw);
 
+  @protected
+  MarkdownFeatures dco_decode_markdown_features(dynamic raw);
+
   @protected
   MemberDetailsState dco_decode_member_details_state(dynamic raw);
 
@@ -1300,6 +1315,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   UiInvitationCode dco_decode_ui_invitation_code(dynamic raw);
 
+  @protected
+  UiMemberRole dco_decode_ui_member_role(dynamic raw);
+
   @protected
   UiMessage dco_decode_ui_message(dynamic raw);
 
@@ -1971,6 +1989,11 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseDeserializer deserializer,
   );
 
+  @protected
+  MarkdownFeatures sse_decode_box_autoadd_markdown_features(
+    SseDeserializer deserializer,
+  );
+
   @protected
   MessageContent sse_decode_box_autoadd_message_content(
     SseDeserializer deserializer,
@@ -2117,6 +2140,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   ChatListState sse_decode_chat_list_state(SseDeserializer deserializer);
 
+  @protected
+  ColumnAlignment sse_decode_column_alignment(SseDeserializer deserializer);
+
   @protected
   DebugCapabilities sse_decode_debug_capabilities(SseDeserializer deserializer);
 
@@ -2133,6 +2159,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseDeserializer deserializer,
   );
 
+  @protected
+  Error sse_decode_error(SseDeserializer deserializer);
+
   @protected
   ExternalGroupProfileDebugInfo sse_decode_external_group_profile_debug_info(
     SseDeserializer deserializer,
@@ -2195,6 +2224,11 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   List<ChatId> sse_decode_list_chat_id(SseDeserializer deserializer);
 
+  @protected
+  List<ColumnAlignment> sse_decode_list_column_alignment(
+    SseDeserializer deserializer,
+  );
+
   @protected
   List<IntroScreenType> sse_decode_list_intro_screen_type(
     SseDeserializer deserializer,
@@ -2298,6 +2332,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   LogEntryLevel sse_decode_log_entry_level(SseDeserializer deserializer);
 
+  @protected
+  MarkdownFeatures sse_decode_markdown_features(SseDeserializer deserializer);
+
   @protected
   MemberDetailsState sse_decode_member_details_state(
     SseDeserializer deserializer,
@@ -2682,6 +2719,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   UiInvitationCode sse_decode_ui_invitation_code(SseDeserializer deserializer);
 
+  @protected
+  UiMemberRole sse_decode_ui_member_role(SseDeserializer deserializer);
+
   @protected
   UiMessage sse_decode_ui_message(SseDeserializer deserializer);
 
@@ -3489,6 +3529,12 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
+  @protected
+  void sse_encode_box_autoadd_markdown_features(
+    MarkdownFeatures self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_box_autoadd_message_content(
     MessageContent self,
@@ -3672,6 +3718,12 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_chat_list_state(ChatListState self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_column_alignment(
+    ColumnAlignment self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_debug_capabilities(
     DebugCapabilities self,
@@ -3693,6 +3745,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
+  @protected
+  void sse_encode_error(Error self, SseSerializer serializer);
+
   @protected
   void sse_encode_external_group_profile_debug_info(
     ExternalGroupProfileDebugInfo self,
@@ -3774,6 +3829,12 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_list_chat_id(List<ChatId> self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_list_column_alignment(
+    List<ColumnAlignment> self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_list_intro_screen_type(
     List<IntroScreenType> self,
@@ -3909,6 +3970,12 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_log_entry_level(LogEntryLevel self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_markdown_features(
+    MarkdownFeatures self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_member_details_state(
     MemberDetailsState self,
@@ -4393,6 +4460,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
+  @protected
+  void sse_encode_ui_member_role(UiMemberRole self, SseSerializer serializer);
+
   @protected
   void sse_encode_ui_message(UiMessage self, SseSerializer serializer);
 
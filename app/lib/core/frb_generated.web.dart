@@ -739,6 +739,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   InviteUsersError dco_decode_box_autoadd_invite_users_error(dynamic raw);
 
+  @protected
+  MarkdownFeatures dco_decode_box_autoadd_markdown_features(dynamic raw);
+
   @protected
   MessageContent dco_decode_box_autoadd_message_content(dynamic raw);
 
@@ -843,6 +846,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   ChatListState dco_decode_chat_list_state(dynamic raw);
 
+  @protected
+  ColumnAlignment dco_decode_column_alignment(dynamic raw);
+
   @protected
   DebugCapabilities dco_decode_debug_capabilities(dynamic raw);
 
@@ -859,6 +865,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     dynamic raw,
   );
 
+  @protected
+  Error dco_decode_error(dynamic raw);
+
   @protected
   ExternalGroupProfileDebugInfo dco_decode_external_group_profile_debug_info(
     dynamic raw,
@@ -915,6 +924,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   List<ChatId> dco_decode_list_chat_id(dynamic raw);
 
+  @protected
+  List<ColumnAlignment> dco_decode_list_column_alignment(dynamic raw);
+
   @protected
   List<IntroScreenType> dco_decode_list_intro_screen_type(dynamic raw);
 
@@ -992,6 +1004,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   LogEntryLevel dco_decode_log_entry_level(dynamic raw);
 
+  @protected
+  MarkdownFeatures dco_decode_markdown_features(dynamic raw);
+
   @protected
   MemberDetailsState dco_decode_member_details_state(dynamic raw);
 
@@ -1302,6 +1317,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   UiInvitationCode dco_decode_ui_invitation_code(dynamic raw);
 
+  @protected
+  UiMemberRole dco_decode_ui_member_role(dynamic raw);
+
   @protected
   UiMessage dco_decode_ui_message(dynamic raw);
 
@@ -1973,6 +1991,11 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseDeserializer deserializer,
   );
 
+  @protected
+  MarkdownFeatures sse_decode_box_autoadd_markdown_features(
+    SseDeserializer deserializer,
+  );
+
   @protected
   MessageContent sse_decode_box_autoadd_message_content(
     SseDeserializer deserializer,
@@ -2119,6 +2142,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   ChatListState sse_decode_chat_list_state(SseDeserializer deserializer);
 
+  @protected
+  ColumnAlignment sse_decode_column_alignment(SseDeserializer deserializer);
+
   @protected
   DebugCapabilities sse_decode_debug_capabilities(SseDeserializer deserializer);
 
@@ -2135,6 +2161,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseDeserializer deserializer,
   );
 
+  @protected
+  Error sse_decode_error(SseDeserializer deserializer);
+
   @protected
   ExternalGroupProfileDebugInfo sse_decode_external_group_profile_debug_info(
     SseDeserializer deserializer,
@@ -2197,6 +2226,11 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   List<ChatId> sse_decode_list_chat_id(SseDeserializer deserializer);
 
+  @protected
+  List<ColumnAlignment> sse_decode_list_column_alignment(
+    SseDeserializer deserializer,
+  );
+
   @protected
   List<IntroScreenType> sse_decode_list_intro_screen_type(
     SseDeserializer deserializer,
@@ -2300,6 +2334,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   LogEntryLevel sse_decode_log_entry_level(SseDeserializer deserializer);
 
+  @protected
+  MarkdownFeatures sse_decode_markdown_features(SseDeserializer deserializer);
+
   @protected
   MemberDetailsState sse_decode_member_details_state(
     SseDeserializer deserializer,
@@ -2684,6 +2721,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   UiInvitationCode sse_decode_ui_invitation_code(SseDeserializer deserializer);
 
+  @protected
+  UiMemberRole sse_decode_ui_member_role(SseDeserializer deserializer);
+
   @protected
   UiMessage sse_decode_ui_message(SseDeserializer deserializer);
 
@@ -3491,6 +3531,12 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
+  @protected
+  void sse_encode_box_autoadd_markdown_features(
+    MarkdownFeatures self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_box_autoadd_message_content(
     MessageContent self,
@@ -3674,6 +3720,12 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_chat_list_state(ChatListState self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_column_alignment(
+    ColumnAlignment self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_debug_capabilities(
     DebugCapabilities self,
@@ -3695,6 +3747,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
+  @protected
+  void sse_encode_error(Error self, SseSerializer serializer);
+
   @protected
   void sse_encode_external_group_profile_debug_info(
     ExternalGroupProfileDebugInfo self,
@@ -3776,6 +3831,12 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_list_chat_id(List<ChatId> self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_list_column_alignment(
+    List<ColumnAlignment> self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_list_intro_screen_type(
     List<IntroScreenType> self,
@@ -3911,6 +3972,12 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_log_entry_level(LogEntryLevel self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_markdown_features(
+    MarkdownFeatures self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_member_details_state(
     MemberDetailsState self,
@@ -4395,6 +4462,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
+  @protected
+  void sse_encode_ui_member_role(UiMemberRole self, SseSerializer serializer);
+
   @protected
   void sse_encode_ui_message(UiMessage self, SseSerializer serializer);
 
@@ -17,16 +17,27 @@ class NotificationContent {
   final String body;
   final ChatId chatId;
 
+  /// Number of new messages summarized by the notification
+  ///
+  /// The body only contains the preview of the latest message. The number is
+  /// prepended to the body when showing the notification.
+  final int newMessages;
+
   const NotificationContent({
     required this.identifier,
     required this.title,
     required this.body,
     required this.chatId,
+    required this.newMessages,
   });
 
   @override
   int get hashCode =>
-      identifier.hashCode ^ title.hashCode ^ body.hashCode ^ chatId.hashCode;
+      identifier.hashCode ^
+      title.hashCode ^
+      body.hashCode ^
+      chatId.hashCode ^
+      newMessages.hashCode;
 
   @override
   bool operator ==(Object other) =>
@@ -36,7 +47,8 @@ class NotificationContent {
           identifier == other.identifier &&
           title == other.title &&
           body == other.body &&
-          chatId == other.chatId;
+          chatId == other.chatId &&
+          newMessages == other.newMessages;
 }
 
 class NotificationHandle {
@@ -1284,6 +1284,48 @@ abstract class AppLocalizations {
   /// **'{new_name}'**
   String systemMessage_userChangedTitle_suffix(Object new_name);
 
+  /// No description provided for @systemMessage_userChangedRole_prefix.
+  ///
+  /// In en, this message translates to:
+  /// **'{user1}'**
+  String systemMessage_userChangedRole_prefix(Object user1);
+
+  /// No description provided for @systemMessage_userChangedRole_infix_1.
+  ///
+  /// In en, this message translates to:
+  /// **' changed the role of '**
+  String get systemMessage_userChangedRole_infix_1;
+
+  /// No description provided for @systemMessage_userChangedRole_infix_2.
+  ///
+  /// In en, this message translates to:
+  /// **'{user2}'**
+  String systemMessage_userChangedRole_infix_2(Object user2);
+
+  /// No description provided for @systemMessage_userChangedRole_infix_3.
+  ///
+  /// In en, this message translates to:
+  /// **' to '**
+  String get systemMessage_userChangedRole_infix_3;
+
+  /// No description provided for @systemMessage_userChangedRole_suffix.
+  ///
+  /// In en, this message translates to:
+  /// **''**
+  String get systemMessage_userChangedRole_suffix;
+
+  /// No description provided for @systemMessage_memberRole_regular.
+  ///
+  /// In en, this message translates to:
+  /// **'member'**
+  String get systemMessage_memberRole_regular;
+
+  /// No description provided for @systemMessage_memberRole_privileged.
+  ///
+  /// In en, this message translates to:
+  /// **'admin'**
+  String get systemMessage_memberRole_privileged;
+
   /// No description provided for @systemMessage_acceptedHandleConnectionRequest.
   ///
   /// In en, this message translates to:
@@ -1395,6 +1437,12 @@ abstract class AppLocalizations {
   /// **'Failed to send'**
   String get messageBubble_failedToSend;
 
+  /// No description provided for @messageBubble_truncated.
+  ///
+  /// In en, this message translates to:
+  /// **'Message truncated'**
+  String get messageBubble_truncated;
+
   /// No description provided for @chatList_newContact.
   ///
   /// In en, this message translates to:
@@ -1551,6 +1599,12 @@ abstract class AppLocalizations {
   /// **'You can\'t send a contact request to yourself.'**
   String get newConnectionDialog_error_ownUsername;
 
+  /// No description provided for @newConnectionDialog_error_tooManyPendingRequests.
+  ///
+  /// In en, this message translates to:
+  /// **'You have too many pending contact requests. Wait until some of them are answered.'**
+  String get newConnectionDialog_error_tooManyPendingRequests;
+
   /// No description provided for @composer_inputHint.
   ///
   /// In en, this message translates to:
@@ -1575,6 +1629,12 @@ abstract class AppLocalizations {
   /// **'Attachment is too large. The maximum size is {maxSize} and the actual size is {actualSize}.'**
   String composer_error_attachment_too_large(Object actualSize, Object maxSize);
 
+  /// No description provided for @composer_error_attachment_unsupported_image.
+  ///
+  /// In en, this message translates to:
+  /// **'This image format is not supported. Convert the image to JPEG or PNG and try again.'**
+  String get composer_error_attachment_unsupported_image;
+
   /// No description provided for @composer_reply_deleted_message_placeholder.
   ///
   /// In en, this message translates to:
@@ -720,6 +720,31 @@ class AppLocalizationsDe extends AppLocalizations {
     return '$new_name';
   }
 
+  @override
+  String systemMessage_userChangedRole_prefix(Object user1) {
+    return '$user1';
+  }
+
+  @override
+  String get systemMessage_userChangedRole_infix_1 => ' hat die Rolle von ';
+
+  @override
+  String systemMessage_userChangedRole_infix_2(Object user2) {
+    return '$user2';
+  }
+
+  @override
+  String get systemMessage_userChangedRole_infix_3 => ' auf ';
+
+  @override
+  String get systemMessage_userChangedRole_suffix => ' geändert';
+
+  @override
+  String get systemMessage_memberRole_regular => 'Mitglied';
+
+  @override
+  String get systemMessage_memberRole_privileged => 'Admin';
+
   @override
   String systemMessage_acceptedHandleConnectionRequest(
     Object displayName,
@@ -802,6 +827,9 @@ class AppLocalizationsDe extends AppLocalizations {
   @override
   String get messageBubble_failedToSend => 'Senden fehlgeschlagen';
 
+  @override
+  String get messageBubble_truncated => 'Nachricht gekürzt';
+
   @override
   String get chatList_newContact => 'Neuer Kontakt';
 
@@ -896,6 +924,10 @@ class AppLocalizationsDe extends AppLocalizations {
   String get newConnectionDialog_error_ownUsername =>
       'Du kannst dir selbst keine Kontaktanfrage senden.';
 
+  @override
+  String get newConnectionDialog_error_tooManyPendingRequests =>
+      'Du hast zu viele offene Kontaktanfragen. Warte, bis einige davon beantwortet wurden.';
+
   @override
   String composer_inputHint(Object chatTitle) {
     return 'Nachricht an $chatTitle';
@@ -916,6 +948,10 @@ class AppLocalizationsDe extends AppLocalizations {
     return 'Der Anhang ist zu groß. Die maximale Größe beträgt $maxSize, die tatsächliche Größe beträgt $actualSize.';
   }
 
+  @override
+  String get composer_error_attachment_unsupported_image =>
+      'Dieses Bildformat wird nicht unterstützt. Wandle das Bild in JPEG oder PNG um und versuche es erneut.';
+
   @override
   String get composer_reply_deleted_message_placeholder =>
       'Ursprüngliche Nachricht gelöscht';
@@ -705,6 +705,31 @@ class AppLocalizationsEn extends AppLocalizations {
     return '$new_name';
   }
 
+  @override
+  String systemMessage_userChangedRole_prefix(Object user1) {
+    return '$user1';
+  }
+
+  @override
+  String get systemMessage_userChangedRole_infix_1 => ' changed the role of ';
+
+  @override
+  String systemMessage_userChangedRole_infix_2(Object user2) {
+    return '$user2';
+  }
+
+  @override
+  String get systemMessage_userChangedRole_infix_3 => ' to ';
+
+  @override
+  String get systemMessage_userChangedRole_suffix => '';
+
+  @override
+  String get systemMessage_memberRole_regular => 'member';
+
+  @override
+  String get systemMessage_memberRole_privileged => 'admin';
+
   @override
   String systemMessage_acceptedHandleConnectionRequest(
     Object displayName,
@@ -786,6 +811,9 @@ class AppLocalizationsEn extends AppLocalizations {
   @override
   String get messageBubble_failedToSend => 'Failed to send';
 
+  @override
+  String get messageBubble_truncated => 'Message truncated';
+
   @override
   String get chatList_newContact => 'New Air contact';
 
@@ -878,6 +906,10 @@ class AppLocalizationsEn extends AppLocalizations {
   String get newConnectionDialog_error_ownUsername =>
       'You can\'t send a contact request to yourself.';
 
+  @override
+  String get newConnectionDialog_error_tooManyPendingRequests =>
+      'You have too many pending contact requests. Wait until some of them are answered.';
+
   @override
   String composer_inputHint(Object chatTitle) {
     return 'Message $chatTitle';
@@ -898,6 +930,10 @@ class AppLocalizationsEn extends AppLocalizations {
     return 'Attachment is too large. The maximum size is $maxSize and the actual size is $actualSize.';
   }
 
+  @override
+  String get composer_error_attachment_unsupported_image =>
+      'This image format is not supported. Convert the image to JPEG or PNG and try again.';
+
   @override
   String get composer_reply_deleted_message_placeholder =>
       'Original message deleted';
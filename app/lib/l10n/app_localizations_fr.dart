@@ -725,6 +725,31 @@ class AppLocalizationsFr extends AppLocalizations {
     return '$new_name';
   }
 
+  @override
+  String systemMessage_userChangedRole_prefix(Object user1) {
+    return '$user1';
+  }
+
+  @override
+  String get systemMessage_userChangedRole_infix_1 => ' a changé le rôle de ';
+
+  @override
+  String systemMessage_userChangedRole_infix_2(Object user2) {
+    return '$user2';
+  }
+
+  @override
+  String get systemMessage_userChangedRole_infix_3 => ' en ';
+
+  @override
+  String get systemMessage_userChangedRole_suffix => '';
+
+  @override
+  String get systemMessage_memberRole_regular => 'membre';
+
+  @override
+  String get systemMessage_memberRole_privileged => 'administrateur';
+
   @override
   String systemMessage_acceptedHandleConnectionRequest(
     Object displayName,
@@ -806,6 +831,9 @@ class AppLocalizationsFr extends AppLocalizations {
   @override
   String get messageBubble_failedToSend => 'Échec de l\'envoi';
 
+  @override
+  String get messageBubble_truncated => 'Message tronqué';
+
   @override
   String get chatList_newContact => 'Nouveau contact';
 
@@ -901,6 +929,10 @@ class AppLocalizationsFr extends AppLocalizations {
   String get newConnectionDialog_error_ownUsername =>
       'Vous ne pouvez pas vous envoyer une demande de contact.';
 
+  @override
+  String get newConnectionDialog_error_tooManyPendingRequests =>
+      'Vous avez trop de demandes de contact en attente. Attendez que certaines aient reçu une réponse.';
+
   @override
   String composer_inputHint(Object chatTitle) {
     return 'Message à $chatTitle';
@@ -921,6 +953,10 @@ class AppLocalizationsFr extends AppLocalizations {
     return 'La pièce jointe est trop volumineuse. La taille maximale est de $maxSize et la taille réelle est de $actualSize.';
   }
 
+  @override
+  String get composer_error_attachment_unsupported_image =>
+      'Ce format d\'image n\'est pas pris en charge. Convertissez l\'image en JPEG ou PNG et réessayez.';
+
   @override
   String get composer_reply_deleted_message_placeholder => 'Message supprimé';
 
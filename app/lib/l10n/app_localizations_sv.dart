@@ -712,6 +712,31 @@ class AppLocalizationsSv extends AppLocalizations {
     return '$new_name';
   }
 
+  @override
+  String systemMessage_userChangedRole_prefix(Object user1) {
+    return '$user1';
+  }
+
+  @override
+  String get systemMessage_userChangedRole_infix_1 => ' ändrade rollen för ';
+
+  @override
+  String systemMessage_userChangedRole_infix_2(Object user2) {
+    return '$user2';
+  }
+
+  @override
+  String get systemMessage_userChangedRole_infix_3 => ' till ';
+
+  @override
+  String get systemMessage_userChangedRole_suffix => '';
+
+  @override
+  String get systemMessage_memberRole_regular => 'medlem';
+
+  @override
+  String get systemMessage_memberRole_privileged => 'administratör';
+
   @override
   String systemMessage_acceptedHandleConnectionRequest(
     Object displayName,
@@ -792,6 +817,9 @@ class AppLocalizationsSv extends AppLocalizations {
   @override
   String get messageBubble_failedToSend => 'Kunde inte skicka';
 
+  @override
+  String get messageBubble_truncated => 'Meddelandet har kortats';
+
   @override
   String get chatList_newContact => 'Ny kontakt';
 
@@ -886,6 +914,10 @@ class AppLocalizationsSv extends AppLocalizations {
   String get newConnectionDialog_error_ownUsername =>
       'Du kan inte skicka en kontaktförfrågan till dig själv.';
 
+  @override
+  String get newConnectionDialog_error_tooManyPendingRequests =>
+      'Du har för många väntande kontaktförfrågningar. Vänta tills några av dem har besvarats.';
+
   @override
   String composer_inputHint(Object chatTitle) {
     return 'Meddelande till $chatTitle';
@@ -906,6 +938,10 @@ class AppLocalizationsSv extends AppLocalizations {
     return 'Bilagan är för stor. Maxstorleken är $maxSize och den faktiska storleken är $actualSize.';
   }
 
+  @override
+  String get composer_error_attachment_unsupported_image =>
+      'Det här bildformatet stöds inte. Konvertera bilden till JPEG eller PNG och försök igen.';
+
   @override
   String get composer_reply_deleted_message_placeholder =>
       'Ursprungligt meddelande har raderats';
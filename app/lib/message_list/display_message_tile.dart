@@ -224,6 +224,38 @@ class _SystemMessageText extends StatelessWidget {
           ),
         );
       }(),
+      UiSystemMessage_ChangeRole(:final sender, :final target, :final role) =>
+        () {
+          final (senderName, targetName) = context.select(
+            (UsersCubit c) => (
+              c.state.profile(userId: sender).displayName,
+              c.state.profile(userId: target).displayName,
+            ),
+          );
+          final roleName = switch (role) {
+            UiMemberRole.regular => loc.systemMessage_memberRole_regular,
+            UiMemberRole.privileged => loc.systemMessage_memberRole_privileged,
+          };
+          return RichText(
+            text: TextSpan(
+              style: textStyle,
+              children: [
+                TextSpan(
+                  text: loc.systemMessage_userChangedRole_prefix(senderName),
+                  style: profileNameStyle,
+                ),
+                TextSpan(text: loc.systemMessage_userChangedRole_infix_1),
+                TextSpan(
+                  text: loc.systemMessage_userChangedRole_infix_2(targetName),
+                  style: profileNameStyle,
+                ),
+                TextSpan(text: loc.systemMessage_userChangedRole_infix_3),
+                TextSpan(text: roleName, style: profileNameStyle),
+                TextSpan(text: loc.systemMessage_userChangedRole_suffix),
+              ],
+            ),
+          );
+        }(),
       UiSystemMessage_NewHandleConnectionChat(:final field0) => () {
         return RichText(
           text: TextSpan(
@@ -455,6 +487,38 @@ RichText buildSystemMessageText(BuildContext context, UiSystemMessage message) {
         ),
       );
     }(),
+    UiSystemMessage_ChangeRole(:final sender, :final target, :final role) =>
+      () {
+        final (senderName, targetName) = context.select(
+          (UsersCubit c) => (
+            c.state.profile(userId: sender).displayName,
+            c.state.profile(userId: target).displayName,
+          ),
+        );
+        final roleName = switch (role) {
+          UiMemberRole.regular => loc.systemMessage_memberRole_regular,
+          UiMemberRole.privileged => loc.systemMessage_memberRole_privileged,
+        };
+        return RichText(
+          text: TextSpan(
+            style: textStyle,
+            children: [
+              TextSpan(
+                text: loc.systemMessage_userChangedRole_prefix(senderName),
+                style: profileNameStyle,
+              ),
+              TextSpan(text: loc.systemMessage_userChangedRole_infix_1),
+              TextSpan(
+                text: loc.systemMessage_userChangedRole_infix_2(targetName),
+                style: profileNameStyle,
+              ),
+              TextSpan(text: loc.systemMessage_userChangedRole_infix_3),
+              TextSpan(text: roleName, style: profileNameStyle),
+              TextSpan(text: loc.systemMessage_userChangedRole_suffix),
+            ],
+          ),
+        );
+      }(),
     UiSystemMessage_NewHandleConnectionChat(:final field0) => () {
       return RichText(
         text: TextSpan(
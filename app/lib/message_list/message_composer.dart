@@ -603,6 +603,15 @@ class _MessageComposerState extends State<MessageComposer>
                     ),
                   );
                   break;
+                case UploadAttachmentError_UnsupportedImageFormat():
+                  showSnackBarStandalone(
+                    (loc) => SnackBar(
+                      content: Text(
+                        loc.composer_error_attachment_unsupported_image,
+                      ),
+                    ),
+                  );
+                  break;
                 case null:
                   break;
               }
@@ -172,7 +172,7 @@ Widget buildBlockElement(
             )
             .toList(),
       ),
-    BlockElement_Table(:final head, :final rows) => Table(
+    BlockElement_Table(:final head, :final rows, :final alignments) => Table(
       border: TableBorder.all(
         color: isSender
             ? CustomColorScheme.of(context).message.selfTableBorder
@@ -183,9 +183,9 @@ Widget buildBlockElement(
       defaultColumnWidth: const IntrinsicColumnWidth(),
       children: [
         TableRow(
-          children: head
+          children: head.indexed
               .map(
-                (itemBlocks) => Padding(
+                (cell) => Padding(
                   padding: const EdgeInsets.symmetric(
                     horizontal: Spacing.px12,
                     vertical: Spacing.px4,
@@ -195,8 +195,11 @@ Widget buildBlockElement(
                     child: Column(
                       spacing: BodyFontSize.base.size,
                       mainAxisSize: MainAxisSize.min,
-                      crossAxisAlignment: CrossAxisAlignment.start,
-                      children: itemBlocks
+                      crossAxisAlignment: _tableCellAlignment(
+                        alignments,
+                        cell.$1,
+                      ),
+                      children: cell.$2
                           .map(
                             (item) => buildBlockElement(
                               context,
@@ -213,9 +216,9 @@ Widget buildBlockElement(
         ),
         ...rows.map(
           (row) => TableRow(
-            children: row
+            children: row.indexed
                 .map(
-                  (itemBlocks) => Padding(
+                  (cell) => Padding(
                     padding: const EdgeInsets.symmetric(
                       horizontal: Spacing.px12,
                       vertical: Spacing.px4,
@@ -223,8 +226,11 @@ Widget buildBlockElement(
                     child: Column(
                       spacing: BodyFontSize.base.size,
                       mainAxisSize: MainAxisSize.min,
-                      crossAxisAlignment: CrossAxisAlignment.start,
-                      children: itemBlocks
+                      crossAxisAlignment: _tableCellAlignment(
+                        alignments,
+                        cell.$1,
+                      ),
+                      children: cell.$2
                           .map(
                             (item) => buildBlockElement(
                               context,
@@ -274,9 +280,28 @@ Widget buildBlockElement(
       ),
       child: Text.rich(TextSpan(text: field0)),
     ),
+    BlockElement_Truncated() => Text(
+      AppLocalizations.of(context).messageBubble_truncated,
+      style: TextStyle(
+        fontStyle: FontStyle.italic,
+        color: isSender
+            ? CustomColorScheme.of(context).message.selfText
+            : CustomColorScheme.of(context).message.otherText,
+      ),
+    ),
   };
 }
 
+CrossAxisAlignment _tableCellAlignment(
+  List<ColumnAlignment> alignments,
+  int column,
+) => switch (alignments.elementAtOrNull(column)) {
+  ColumnAlignment.center => CrossAxisAlignment.center,
+  ColumnAlignment.right => CrossAxisAlignment.end,
+  ColumnAlignment.none || ColumnAlignment.left || null =>
+    CrossAxisAlignment.start,
+};
+
 InlineSpan buildInlineElement(
   BuildContext context,
   RangedInlineElement inline,
@@ -719,6 +744,8 @@ class CustomTextEditingController extends TextEditingController {
           decorationStyle: TextDecorationStyle.wavy,
         ),
       ),
+      // Only produced when truncating a message for display
+      BlockElement_Truncated() => const TextSpan(),
     };
   }
 
@@ -4,9 +4,13 @@
 
 import 'dart:async';
 import 'dart:io';
+import 'dart:ui';
 import 'package:flutter/services.dart';
 import 'package:logging/logging.dart';
+import 'package:air/app.dart';
 import 'package:air/core/core.dart';
+import 'package:air/l10n/app_localizations.dart';
+import 'package:air/l10n/language_options.dart';
 import 'package:air/core/api/utils.dart' as rust_utils;
 import 'package:air/util/notifications.dart';
 import 'package:path_provider/path_provider.dart';
@@ -224,7 +228,7 @@ FutureOr<void> sendNotification(NotificationContent content) async {
     final arguments = <String, dynamic>{
       'identifier': content.identifier.field0.toString(),
       'title': content.title,
-      'body': content.body,
+      'body': _notificationBody(content),
       'chatId': content.chatId.uuid.toString(),
     };
     await platform.invokeMethod('sendNotification', arguments);
@@ -233,6 +237,19 @@ FutureOr<void> sendNotification(NotificationContent content) async {
   }
 }
 
+/// Prepends the localized number of new messages to the body of a notification
+/// summarizing more than one message.
+String _notificationBody(NotificationContent content) {
+  if (content.newMessages <= 1) return content.body;
+  final context = scaffoldMessengerKey.currentContext;
+  final loc = context != null
+      ? AppLocalizations.of(context)
+      : lookupAppLocalizations(
+          supportedLanguageLocale(PlatformDispatcher.instance.locale),
+        );
+  return '${loc.messageList_newMessages(content.newMessages)}: ${content.body}';
+}
+
 FutureOr<List<NotificationHandle>> getActiveNotifications() async {
   try {
     List<Map<Object?, Object?>> res =
@@ -18,18 +18,27 @@ const _allFeatures = AirFeatures(
   encryptedGroupProfiles: true,
   emptyConnectionGroupAttributes: true,
   pqGroups: true,
+  typingIndicators: true,
+  presence: true,
+  userProfileStatusText: true,
 );
 
 const _noPqFeatures = AirFeatures(
   encryptedGroupProfiles: true,
   emptyConnectionGroupAttributes: true,
   pqGroups: false,
+  typingIndicators: true,
+  presence: true,
+  userProfileStatusText: true,
 );
 
 const _noEgpFeatures = AirFeatures(
   encryptedGroupProfiles: false,
   emptyConnectionGroupAttributes: true,
   pqGroups: true,
+  typingIndicators: true,
+  presence: true,
+  userProfileStatusText: true,
 );
 
 final _profiles = [
@@ -7,7 +7,7 @@ use std::{fs, io::Write, sync::Arc};
 use aircoreclient::{
     AttachmentContent, AttachmentId, AttachmentProgress, AttachmentProgressEvent, AttachmentStatus,
     clients::CoreUser,
-    db::notification::{DbEntityId, DbOperation},
+    db::notification::{DbEntityId, DbEntityKind, DbOperation},
     image_is_animated,
 };
 use anyhow::{Context, bail};
@@ -75,7 +75,10 @@ impl AttachmentsRepository {
                             break; // sink is closed
                         }
                     }
-                    AttachmentProgressEvent::Progress { bytes_loaded } => {
+                    AttachmentProgressEvent::Progress { bytes_loaded }
+                    | AttachmentProgressEvent::Uploading {
+                        sent: bytes_loaded, ..
+                    } => {
                         if sink
                             .add(UiAttachmentStatus::Progress(bytes_loaded))
                             .is_err()
@@ -95,6 +98,10 @@ impl AttachmentsRepository {
                         sink.add(UiAttachmentStatus::NotFound).ok();
                         break;
                     }
+                    AttachmentProgressEvent::Cancelled => {
+                        sink.add(UiAttachmentStatus::Pending).ok();
+                        break;
+                    }
                 }
             }
         } else if let Ok(Some(AttachmentStatus::Ready)) =
@@ -119,6 +126,16 @@ impl AttachmentsRepository {
         }
     }
 
+    /// Load the thumbnail of an image attachment from database
+    ///
+    /// The thumbnail is available before the attachment is downloaded.
+    pub async fn load_attachment_thumbnail(
+        &self,
+        attachment_id: AttachmentId,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        self.store.load_attachment_thumbnail(attachment_id).await
+    }
+
     pub async fn load_image_attachment(
         &self,
         attachment_id: AttachmentId,
@@ -215,7 +232,10 @@ impl AttachmentsRepository {
                 AttachmentProgressEvent::Init => {
                     chunk_event_callback(0).await;
                 }
-                AttachmentProgressEvent::Progress { bytes_loaded } => {
+                AttachmentProgressEvent::Progress { bytes_loaded }
+                | AttachmentProgressEvent::Uploading {
+                    sent: bytes_loaded, ..
+                } => {
                     chunk_event_callback(bytes_loaded.try_into()?).await;
                 }
                 AttachmentProgressEvent::Completed => {
@@ -228,6 +248,7 @@ impl AttachmentsRepository {
                 }
                 AttachmentProgressEvent::Failed => bail!("Attachment download failed"),
                 AttachmentProgressEvent::NotFound => bail!("Attachment not found"),
+                AttachmentProgressEvent::Cancelled => bail!("Attachment download cancelled"),
             }
         }
         bail!("Attachment download aborted")
@@ -259,9 +280,10 @@ async fn attachment_downloads_loop(
     let download_tasks_semaphore = Arc::new(Semaphore::new(NUM_CONCURRENT_DOWNLOADS));
 
     // filter the store notifications stream to only care about attachments
-    let store_notifications = store.db_notifications().flat_map(|notification| {
-        let attachment_ids =
-            notification
+    let store_notifications = store
+        .db_notifications_filtered(DbEntityKind::Attachment.into())
+        .flat_map(|notification| {
+            let attachment_ids = notification
                 .ops
                 .clone()
                 .into_iter()
@@ -273,8 +295,8 @@ async fn attachment_downloads_loop(
                     }
                     _ => None,
                 });
-        futures_util::stream::iter(attachment_ids)
-    });
+            futures_util::stream::iter(attachment_ids)
+        });
 
     // download pending attachments once
     let pending_attachment_ids = store
@@ -302,32 +324,33 @@ fn spawn_download_task(
     in_progress: &InProgressMap,
     attachment_id: AttachmentId,
 ) -> AttachmentTaskHandle {
-    let (task, cancel, handle) = match in_progress.entry(attachment_id) {
+    let (task, handle) = match in_progress.entry(attachment_id) {
         Entry::Occupied(mut entry) if entry.get().is_cancelled() || entry.get().is_failed() => {
-            let (progress, task) = store.download_attachment(attachment_id);
             let cancel = cancel.child_token();
+            let (progress, task) = store.download_attachment(attachment_id, cancel.clone());
             let handle = AttachmentTaskHandle::with_cancellation(progress, cancel.clone());
             entry.insert(handle.clone());
-            (task, cancel, handle)
+            (task, handle)
         }
         Entry::Occupied(entry) => {
             return entry.get().clone();
         }
         Entry::Vacant(entry) => {
-            let (progress, task) = store.download_attachment(attachment_id);
             let cancel = cancel.child_token();
+            let (progress, task) = store.download_attachment(attachment_id, cancel.clone());
             let handle = AttachmentTaskHandle::with_cancellation(progress, cancel.clone());
             entry.insert(handle.clone());
-            (task, cancel, handle)
+            (task, handle)
         }
     };
 
-    tokio::spawn(cancel.run_until_cancelled_owned(async move {
+    // The download task observes the cancellation token itself, so that it can clean up.
+    tokio::spawn(async move {
         if let Err(error) = task.await {
             error!(%error, "Failed to download attachment");
         }
         drop(permit);
-    }));
+    });
 
     handle
 }
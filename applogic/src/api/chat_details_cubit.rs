@@ -13,8 +13,8 @@ pub use aircoreclient::{
     RequiredDebugCapabilities,
 };
 use aircoreclient::{
-    AttachmentId, AttachmentProgress, Chat, ChatId, ChatMessage, MessageId,
-    ProvisionAttachmentError, UploadTaskError, clients::CoreUser,
+    AttachmentId, AttachmentProgress, Chat, ChatId, ChatMessage, ChatPreview, MessageId,
+    ProvisionAttachmentError, UploadTaskError, clients::CoreUser, db::notification::DbEntityKind,
 };
 use airprotos::client::component::AirComponent;
 use anyhow::{Context as _, bail};
@@ -183,7 +183,7 @@ impl ChatDetailsCubitBase {
                 Box::pin(
                     self.context
                         .core_user
-                        .delete_message(self.context.chat_id, message_id),
+                        .delete_message_for_everyone(self.context.chat_id, message_id),
                 )
                 .await
                 .inspect_err(|error| error!(%error, "Failed to send delete message"))?;
@@ -354,7 +354,16 @@ impl ChatDetailsCubitBase {
                     .enqueue_chat_message(message.id())
                     .await?;
             }
-            Some(Err(UploadTaskError { message_id, error })) => {
+            Some(Err(UploadTaskError {
+                error,
+                resumable: true,
+                ..
+            })) => {
+                info!(%error, ?attachment_id, "Attachment upload interrupted, will be resumed");
+            }
+            Some(Err(UploadTaskError {
+                message_id, error, ..
+            })) => {
                 error!(%error, ?attachment_id, "Failed to upload attachment");
                 self.context
                     .core_user
@@ -742,7 +751,9 @@ impl ChatDetailsContext {
 
     /// Returns only when `stop` is cancelled
     async fn update_state_task(self) {
-        let mut notifications = self.core_user.db_notifications();
+        let mut notifications = self.core_user.db_notifications_filtered(
+            DbEntityKind::Chat | DbEntityKind::Message | DbEntityKind::User,
+        );
         while let Some(notification) = notifications.next().await {
             if notification.ops.contains_key(&self.chat_id.into()) {
                 self.load_and_emit_state().await;
@@ -780,12 +791,36 @@ impl ChatDetailsContext {
 
 /// Loads additional details for a chat and converts it into a [`UiChatDetails`]
 pub(super) async fn load_chat_details(core_user: &CoreUser, chat: Chat) -> UiChatDetails {
+    let unread_messages = core_user.unread_messages_count(chat.id()).await;
+    let last_message = core_user.last_message(chat.id()).await.ok().flatten();
+    load_chat_details_with(core_user, chat, unread_messages, last_message).await
+}
+
+/// Same as [`load_chat_details`], but takes the last message and unread count from the already
+/// loaded `preview` of the chat.
+///
+/// The last message of the preview is loaded without the replied-to message.
+pub(super) async fn load_chat_details_with_preview(
+    core_user: &CoreUser,
+    chat: Chat,
+    preview: Option<ChatPreview>,
+) -> UiChatDetails {
+    let (unread_messages, last_message) = preview
+        .map(|preview| (preview.unread_messages_count, preview.last_message))
+        .unwrap_or_default();
+    load_chat_details_with(core_user, chat, unread_messages, last_message).await
+}
+
+async fn load_chat_details_with(
+    core_user: &CoreUser,
+    chat: Chat,
+    unread_messages: usize,
+    last_message: Option<ChatMessage>,
+) -> UiChatDetails {
     let messages_count = core_user
         .messages_count(chat.id())
         .await
         .unwrap_or_default();
-    let unread_messages = core_user.unread_messages_count(chat.id()).await;
-    let last_message = core_user.last_message(chat.id()).await.ok().flatten();
     let last_used = last_message
         .as_ref()
         .map(|m| m.timestamp())
@@ -840,6 +875,19 @@ impl IntoUiResult for ProvisionAttachmentError {
                     actual_size_bytes: detail.actual_size_bytes,
                 }))
             }
+            ProvisionAttachmentError::ExceedsMaxSize(error) => {
+                Ok(Some(UploadAttachmentError::TooLarge {
+                    max_size_bytes: error.max_size_bytes,
+                    actual_size_bytes: error.actual_size_bytes,
+                }))
+            }
+            ProvisionAttachmentError::UnsupportedImageFormat { content_type } => {
+                info!(
+                    content_type,
+                    "Rejected image attachment in unsupported format"
+                );
+                Ok(Some(UploadAttachmentError::UnsupportedImageFormat))
+            }
         }
     }
 }
@@ -850,6 +898,8 @@ pub enum UploadAttachmentError {
         max_size_bytes: u64,
         actual_size_bytes: u64,
     },
+    /// The image can't be stripped of its metadata
+    UnsupportedImageFormat,
 }
 
 #[frb(mirror(AcceptContactRequestError))]
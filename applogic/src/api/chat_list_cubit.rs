@@ -10,7 +10,7 @@ use aircommon::identifiers::{Username, UsernameHash};
 use aircoreclient::{
     AddUsernameContactError, ChatId,
     clients::CoreUser,
-    db::notification::{DbEntityId, DbNotification},
+    db::notification::{DbEntityId, DbEntityKind, DbNotification},
 };
 use flutter_rust_bridge::frb;
 use tokio::sync::watch;
@@ -47,7 +47,8 @@ impl ChatListCubitBase {
     #[frb(sync)]
     pub fn new(user_cubit: &UserCubitBase) -> Self {
         let store = user_cubit.core_user().clone();
-        let store_notifications = store.db_notifications();
+        let store_notifications =
+            store.db_notifications_filtered(DbEntityKind::Chat | DbEntityKind::User);
 
         let core = CubitCore::new();
 
@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::{num::NonZeroUsize, sync::Arc};
+use std::{collections::HashMap, num::NonZeroUsize, sync::Arc};
 
 use aircoreclient::{ChatId, clients::CoreUser};
 use flutter_rust_bridge::frb;
@@ -12,7 +12,11 @@ use tokio_util::sync::{CancellationToken, DropGuard};
 use tracing::{debug, error};
 
 use crate::{
-    api::{chat_details_cubit::load_chat_details, types::UiChatDetails, user_cubit::UserCubitBase},
+    api::{
+        chat_details_cubit::{load_chat_details, load_chat_details_with_preview},
+        types::UiChatDetails,
+        user_cubit::UserCubitBase,
+    },
     util::spawn_from_sync,
 };
 
@@ -78,11 +82,32 @@ impl ChatsRepository {
         }) else {
             return;
         };
+        // last messages and unread counts of all chats are loaded with a single query; if this
+        // fails, e.g. because a last message can't be decoded, each chat is loaded on its own
+        let mut previews: Option<HashMap<ChatId, _>> = store
+            .chat_previews()
+            .await
+            .inspect_err(|error| {
+                error!(%error, "Failed to load chat previews; loading chats one by one");
+            })
+            .ok()
+            .map(|previews| {
+                previews
+                    .into_iter()
+                    .map(|preview| (preview.chat_id, preview))
+                    .collect()
+            });
         let len = chat_ids.len().min(CHAT_REPOSITORY_CACHE_SIZE.get());
         debug!(%len, "load_on_startup_task");
         for chat_id in &chat_ids[..len] {
             if let Some(chat) = store.chat(chat_id).await {
-                let chat_details = load_chat_details(&store, chat).await;
+                let chat_details = match previews.as_mut() {
+                    Some(previews) => {
+                        let preview = previews.remove(chat_id);
+                        load_chat_details_with_preview(&store, chat, preview).await
+                    }
+                    None => load_chat_details(&store, chat).await,
+                };
                 chats.lock().put(chat_details.id, chat_details);
             }
         }
@@ -20,7 +20,10 @@ use regex::Regex;
 
 use crate::{
     StreamSink,
-    logging::{LOG_FILE_RING_BUFFER, LOG_FILE_RING_BUFFER_SIZE, init_logger},
+    logging::{
+        LOG_FILE_RING_BUFFER, LOG_FILE_RING_BUFFER_SIZE, LoggerConfig, init_logger,
+        init_logger_with_config, set_log_directives, set_log_level,
+    },
     util::{FileRingBuffer, FileRingBufferLock},
 };
 
@@ -37,6 +40,38 @@ pub fn init_rust_logging(log_file: String) -> LogWriter {
     LogWriter { buffer }
 }
 
+/// Same as [`init_rust_logging`], but keeps up to `rotated_segments` compressed segments of older
+/// logs next to the log file instead of overwriting them.
+///
+/// Meant for desktop platforms. On mobile, use [`init_rust_logging`] to keep the fixed-size log
+/// file.
+#[frb(sync)]
+pub fn init_rust_logging_with_rotation(log_file: String, rotated_segments: u32) -> LogWriter {
+    let config = LoggerConfig {
+        rotated_segments: rotated_segments.try_into().expect("usize overflow"),
+        ..LoggerConfig::from_env()
+    };
+    let buffer = init_logger_with_config(log_file, config);
+    LogWriter { buffer }
+}
+
+/// Changes the level of the Rust logs at runtime, e.g. to enable verbose logging.
+///
+/// Applies to the logs sent to Flutter and to the logs written to the log file.
+#[frb(sync)]
+pub fn set_rust_log_level(level: LogEntryLevel) -> anyhow::Result<()> {
+    set_log_level(tracing::Level::from(level).into())
+}
+
+/// Changes the filter of the Rust logs at runtime to the given directives in the `RUST_LOG`
+/// format, e.g. `info,aircoreclient=debug`.
+///
+/// Invalid directives are rejected and the current filter is kept.
+#[frb(sync)]
+pub fn set_rust_log_directives(directives: String) -> anyhow::Result<()> {
+    set_log_directives(&directives)
+}
+
 /// Reads the application logs from the file currently used for writing logs (if any).
 pub fn read_app_logs() -> anyhow::Result<String> {
     let buffer = LOG_FILE_RING_BUFFER
@@ -112,6 +147,16 @@ fn tar_logs_impl(
         &mut background_buffer()?.buf().reader(),
     )?;
 
+    if let Some(rotation) = app_buffer.rotation() {
+        // Prevents a rotation while the segments are added
+        let _guard = app_buffer.lock_segments();
+        for segment in rotation.segments() {
+            if let Some(name) = segment.file_name() {
+                tar.append_path_with_name(&segment, Path::new("logs").join(name))?;
+            }
+        }
+    }
+
     tar.finish()?;
     drop(tar);
 
@@ -197,6 +242,18 @@ impl From<tracing::Level> for LogEntryLevel {
     }
 }
 
+impl From<LogEntryLevel> for tracing::Level {
+    fn from(level: LogEntryLevel) -> Self {
+        match level {
+            LogEntryLevel::Trace => tracing::Level::TRACE,
+            LogEntryLevel::Debug => tracing::Level::DEBUG,
+            LogEntryLevel::Info => tracing::Level::INFO,
+            LogEntryLevel::Warn => tracing::Level::WARN,
+            LogEntryLevel::Error => tracing::Level::ERROR,
+        }
+    }
+}
+
 /// Assigns the given sink as the log sink on the Rust side.
 ///
 /// If there was already a different sink assigned, it is replaced.
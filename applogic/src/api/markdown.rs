@@ -5,18 +5,23 @@
 use std::{iter::Peekable, sync::LazyLock};
 
 use flutter_rust_bridge::frb;
-use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use pulldown_cmark::{Alignment, Event, Options, Parser, Tag, TagEnd};
 use regex::Regex;
 
+use crate::emoji_shortcodes;
+
 const MAX_DEPTH: usize = 50;
 
 pub(crate) static URL_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(
-        r#"(?i)(?:mailto:|https?://)[^\p{Cc}\p{Cf}\s<>""{}\^⟨⟩`\\]*[^\p{Cc}\p{Cf}\s<>""{}\^⟨⟩`\\\.,;:!\?\)\]]"#
+        r#"(?i)(?:mailto:|https?://|\bwww\.)[^\p{Cc}\p{Cf}\s<>""{}\^⟨⟩`\\]*[^\p{Cc}\p{Cf}\s<>""{}\^⟨⟩`\\\.,;:!\?\)\]]"#
     ).unwrap()
 });
 
-#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+static SHORTCODE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r":[a-z0-9_+\-]+:").unwrap());
+
+/// Reason why a message could not be parsed
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Error {
     #[error("expected more events")]
     ExpectedMoreEvents,
@@ -46,6 +51,41 @@ pub enum Error {
 
 type Result<T> = std::result::Result<T, Error>;
 
+/// Optional markdown syntax recognized by the parser
+///
+/// Disabled syntax is rendered as literal text.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[frb(dart_metadata = ("freezed"))]
+pub struct MarkdownFeatures {
+    /// `~~strikethrough~~`
+    pub strikethrough: bool,
+    /// GitHub flavored tables
+    pub tables: bool,
+    /// `- [ ]` and `- [x]` list items
+    pub task_lists: bool,
+}
+
+impl Default for MarkdownFeatures {
+    fn default() -> Self {
+        Self {
+            strikethrough: true,
+            tables: true,
+            task_lists: true,
+        }
+    }
+}
+
+impl From<MarkdownFeatures> for Options {
+    fn from(features: MarkdownFeatures) -> Self {
+        // Do not enable Options::ENABLE_GFM, it activates special blockquotes which are not part of the GFM spec https://github.com/orgs/community/discussions/16925
+        let mut options = Options::empty();
+        options.set(Options::ENABLE_STRIKETHROUGH, features.strikethrough);
+        options.set(Options::ENABLE_TABLES, features.tables);
+        options.set(Options::ENABLE_TASKLISTS, features.task_lists);
+        options
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 #[frb(dart_metadata = ("freezed"))]
 pub struct MessageContent {
@@ -95,6 +135,8 @@ pub enum BlockElement {
     Table {
         head: Vec<Vec<RangedBlockElement>>,
         rows: Vec<Vec<Vec<RangedBlockElement>>>,
+        /// One alignment per column
+        alignments: Vec<ColumnAlignment>,
     },
     HorizontalRule,
 
@@ -102,6 +144,36 @@ pub enum BlockElement {
     CodeBlock(Vec<RangedCodeBlock>),
 
     Error(String),
+
+    /// Marks the end of a truncated message
+    ///
+    /// Rendered as a localized hint by the app.
+    Truncated,
+}
+
+/// Alignment of a table column, as specified in the delimiter row of the table
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum ColumnAlignment {
+    /// No alignment specified (`---`)
+    #[default]
+    None,
+    /// `:--`
+    Left,
+    /// `:-:`
+    Center,
+    /// `--:`
+    Right,
+}
+
+impl From<Alignment> for ColumnAlignment {
+    fn from(alignment: Alignment) -> Self {
+        match alignment {
+            Alignment::None => Self::None,
+            Alignment::Left => Self::Left,
+            Alignment::Center => Self::Center,
+            Alignment::Right => Self::Right,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -144,18 +216,53 @@ impl MessageContent {
         Self::try_parse_markdown(&String::from_utf8(string).map_err(|_| Error::InvalidUtf8)?)
     }
 
-    pub fn parse_markdown(string: &str) -> Self {
+    /// Same as [`Self::parse_markdown`], but returns the reason if the message could not be
+    /// parsed instead of an error block.
+    #[frb(sync)]
+    pub fn parse_markdown_result(string: &str) -> std::result::Result<Self, Error> {
         Self::try_parse_markdown(string)
+    }
+
+    pub fn parse_markdown(string: &str) -> Self {
+        Self::parse_markdown_with(string, MarkdownFeatures::default())
+    }
+
+    /// Same as [`Self::parse_markdown`], but only recognizes the given optional syntax.
+    pub fn parse_markdown_with(string: &str, features: MarkdownFeatures) -> Self {
+        Self::try_parse_markdown_with(string, features)
             .unwrap_or_else(|e| Self::error(format!("Invalid message: {e}")))
     }
 
+    /// Same as [`Self::parse_markdown`], but keeps at most `max_chars` characters of the source
+    /// text.
+    ///
+    /// If the message is longer, the elements after the cut are dropped and a trailing
+    /// [`BlockElement::Truncated`] marks the message as truncated. Lists, quotes and tables which
+    /// do not fit are cut between their items, so the result is always well-formed. The ranges of
+    /// cut elements end at the kept content.
+    pub fn parse_markdown_truncated(string: &str, max_chars: usize) -> Self {
+        let mut content = Self::parse_markdown(string);
+        let Some((cut, _)) = string.char_indices().nth(max_chars) else {
+            return content;
+        };
+        let cut = u32::try_from(cut).unwrap_or(u32::MAX);
+        if truncate_blocks(&mut content.elements, string, cut) {
+            let start = content.elements.last().map(|block| block.end).unwrap_or(0);
+            content.elements.push(RangedBlockElement {
+                start,
+                end: u32::try_from(string.len()).unwrap_or(u32::MAX),
+                element: BlockElement::Truncated,
+            });
+        }
+        content
+    }
+
     fn try_parse_markdown(string: &str) -> Result<Self> {
-        let parsed = Parser::new_ext(
-            string,
-            // Do not enable Options::ENABLE_GFM, it activates special blockquotes which are not part of the GFM spec https://github.com/orgs/community/discussions/16925
-            Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS,
-        )
-        .into_offset_iter();
+        Self::try_parse_markdown_with(string, MarkdownFeatures::default())
+    }
+
+    fn try_parse_markdown_with(string: &str, features: MarkdownFeatures) -> Result<Self> {
+        let parsed = Parser::new_ext(string, features.into()).into_offset_iter();
         let mut result = Vec::new();
         let mut iter = parsed
             .map(|(event, range)| RangedEvent {
@@ -250,9 +357,10 @@ where
                 element: value,
             }
         }
-        Event::Start(Tag::Table(_alignments)) => {
+        Event::Start(Tag::Table(alignments)) => {
             let start = iter.next().ok_or(Error::ExpectedMoreEvents)?;
-            let value = parse_table_content(iter, source, depth + 1)?;
+            let alignments = alignments.into_iter().map(ColumnAlignment::from).collect();
+            let value = parse_table_content(iter, source, alignments, depth + 1)?;
             let end = iter.next().ok_or(Error::ExpectedMoreEvents)?;
 
             if end.event != Event::End(TagEnd::Table) {
@@ -389,7 +497,9 @@ where
             return Err(Error::FootnotesNotSupported);
         }
 
-        Event::Start(Tag::Superscript | Tag::Subscript) => return Err(Error::MathNotSupported),
+        Event::Start(Tag::Superscript | Tag::Subscript) => {
+            return Err(Error::MathNotSupported);
+        }
 
         Event::Start(Tag::DefinitionList)
         | Event::Start(Tag::DefinitionListTitle)
@@ -475,9 +585,12 @@ where
 
             Event::Start(Tag::Link { dest_url, .. }) => {
                 let start = iter.next().ok_or(Error::ExpectedMoreEvents)?;
+                let mut children = parse_inline_elements(iter, depth + 1)?;
+                // URLs in the link text are not linked again
+                unlink(&mut children);
                 let value = InlineElement::Link {
                     dest_url: dest_url.to_string(),
-                    children: parse_inline_elements(iter, depth + 1)?,
+                    children,
                 };
                 let end = iter.next().ok_or(Error::ExpectedMoreEvents)?;
 
@@ -527,7 +640,11 @@ where
                     iter.next(); // consume the next event
                 }
 
-                collect_links(start, end, &full_text, &mut result);
+                let mut elements = Vec::new();
+                collect_links(start, end, &full_text, &mut elements);
+                for element in elements {
+                    expand_shortcodes(element, &mut result);
+                }
             }
 
             Event::Code(str) => {
@@ -596,7 +713,9 @@ where
                 return Err(Error::FootnotesNotSupported);
             }
 
-            Event::Start(Tag::Superscript | Tag::Subscript) => return Err(Error::MathNotSupported),
+            Event::Start(Tag::Superscript | Tag::Subscript) => {
+                return Err(Error::MathNotSupported);
+            }
 
             Event::Start(Tag::DefinitionList)
             | Event::Start(Tag::DefinitionListTitle)
@@ -653,6 +772,7 @@ where
 fn parse_table_content<'a, I>(
     iter: &mut Peekable<I>,
     source: &str,
+    alignments: Vec<ColumnAlignment>,
     depth: usize,
 ) -> Result<BlockElement>
 where
@@ -714,6 +834,7 @@ where
     Ok(BlockElement::Table {
         head: table_head,
         rows: table_rows,
+        alignments,
     })
 }
 
@@ -757,6 +878,131 @@ where
     }
 }
 
+/// Number of characters of the source text in the given range
+fn source_len(source: &str, start: u32, end: u32) -> usize {
+    source
+        .get(start as usize..end as usize)
+        .map_or(0, |s| s.chars().count())
+}
+
+/// Drops everything after the source position `cut` from the blocks.
+///
+/// A block which extends beyond `cut` is truncated recursively, and its range is shortened to
+/// the kept content. It is dropped if nothing of it is left. Returns whether anything was
+/// dropped.
+fn truncate_blocks(blocks: &mut Vec<RangedBlockElement>, source: &str, cut: u32) -> bool {
+    let Some(i) = blocks.iter().position(|block| block.end > cut) else {
+        return false;
+    };
+
+    let block = &mut blocks[i];
+    let end = if block.start < cut {
+        match &mut block.element {
+            BlockElement::Paragraph(inlines) | BlockElement::Heading(inlines) => {
+                truncate_inlines(inlines, source, cut);
+                inlines.last().map(|inline| inline.end)
+            }
+            BlockElement::Quote(children) => {
+                truncate_blocks(children, source, cut);
+                children.last().map(|child| child.end)
+            }
+            BlockElement::UnorderedList(items) | BlockElement::OrderedList(_, items) => {
+                truncate_items(items, source, cut);
+                items.iter().flatten().last().map(|child| child.end)
+            }
+            BlockElement::Table { head, rows, .. } => {
+                // Rows are kept or dropped as a whole, so all rows have the same number of cells
+                let kept_rows = rows.iter().take_while(|row| cells_end(row) <= cut).count();
+                rows.truncate(kept_rows);
+                let head_end = cells_end(head);
+                (head_end <= cut).then(|| rows.last().map_or(head_end, |row| cells_end(row)))
+            }
+            BlockElement::CodeBlock(lines) => {
+                let kept_lines = lines.iter().take_while(|line| line.end <= cut).count();
+                lines.truncate(kept_lines);
+                lines.last().map(|line| line.end)
+            }
+            BlockElement::HorizontalRule | BlockElement::Error(_) | BlockElement::Truncated => None,
+        }
+    } else {
+        None
+    };
+
+    match end {
+        Some(end) => {
+            block.end = end;
+            blocks.truncate(i + 1);
+        }
+        None => blocks.truncate(i),
+    }
+    true
+}
+
+/// Same as [`truncate_blocks`], but for list items
+fn truncate_items(items: &mut Vec<Vec<RangedBlockElement>>, source: &str, cut: u32) -> bool {
+    let Some(i) = items
+        .iter()
+        .position(|item| item.last().is_some_and(|block| block.end > cut))
+    else {
+        return false;
+    };
+    truncate_blocks(&mut items[i], source, cut);
+    items.truncate(if items[i].is_empty() { i } else { i + 1 });
+    true
+}
+
+/// End of the source range of the given table cells
+fn cells_end(cells: &[Vec<RangedBlockElement>]) -> u32 {
+    cells
+        .iter()
+        .flatten()
+        .map(|block| block.end)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Same as [`truncate_blocks`], but for inline elements
+///
+/// Text and code keep as many characters as their source has before `cut`.
+fn truncate_inlines(inlines: &mut Vec<RangedInlineElement>, source: &str, cut: u32) -> bool {
+    let Some(i) = inlines.iter().position(|inline| inline.end > cut) else {
+        return false;
+    };
+
+    let inline = &mut inlines[i];
+    let end = if inline.start < cut {
+        match &mut inline.element {
+            InlineElement::Text(text) | InlineElement::Code(text) => {
+                let kept_chars = source_len(source, inline.start, cut);
+                if let Some((index, _)) = text.char_indices().nth(kept_chars) {
+                    text.truncate(index);
+                }
+                (!text.is_empty()).then_some(cut)
+            }
+            InlineElement::Link { children, .. }
+            | InlineElement::Bold(children)
+            | InlineElement::Italic(children)
+            | InlineElement::Strikethrough(children)
+            | InlineElement::Spoiler(children) => {
+                truncate_inlines(children, source, cut);
+                children.last().map(|child| child.end)
+            }
+            InlineElement::Image(_) | InlineElement::TaskListMarker(_) => None,
+        }
+    } else {
+        None
+    };
+
+    match end {
+        Some(end) => {
+            inline.end = end;
+            inlines.truncate(i + 1);
+        }
+        None => inlines.truncate(i),
+    }
+    true
+}
+
 /// Collects links and surrounding text from a string into `elements`.
 ///
 /// If there are no links, a single element with the entire string is added.
@@ -776,11 +1022,19 @@ fn collect_links(start: u32, end: u32, str: &str, elements: &mut Vec<RangedInlin
 
         // Matched link
         let text = mat.as_str().to_string();
+        let dest_url = if text
+            .get(..4)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("www."))
+        {
+            format!("https://{text}")
+        } else {
+            text.clone()
+        };
         elements.push(RangedInlineElement {
             start: start + mat.start() as u32,
             end: start + mat.end() as u32,
             element: InlineElement::Link {
-                dest_url: text.to_string(),
+                dest_url,
                 children: vec![RangedInlineElement {
                     start: start + mat.start() as u32,
                     end: start + mat.end() as u32,
@@ -803,6 +1057,78 @@ fn collect_links(start: u32, end: u32, str: &str, elements: &mut Vec<RangedInlin
     }
 }
 
+/// Replaces emoji shortcodes like `:smile:` in a text element and pushes the result to
+/// `elements`.
+///
+/// Each emoji becomes a separate text element spanning the range of its shortcode. Other elements
+/// and unknown shortcodes are pushed unchanged.
+fn expand_shortcodes(element: RangedInlineElement, elements: &mut Vec<RangedInlineElement>) {
+    let InlineElement::Text(text) = &element.element else {
+        elements.push(element);
+        return;
+    };
+
+    let start = element.start;
+    let mut last_end = 0;
+    let mut pos = 0;
+    while let Some(mat) = SHORTCODE_RE.find_at(text, pos) {
+        let Some(emoji) = emoji_shortcodes::emoji(&text[mat.start() + 1..mat.end() - 1]) else {
+            // The closing colon might open the next shortcode
+            pos = mat.end() - 1;
+            continue;
+        };
+
+        if mat.start() > last_end {
+            elements.push(RangedInlineElement {
+                start: start + last_end as u32,
+                end: start + mat.start() as u32,
+                element: InlineElement::Text(text[last_end..mat.start()].to_owned()),
+            });
+        }
+        elements.push(RangedInlineElement {
+            start: start + mat.start() as u32,
+            end: start + mat.end() as u32,
+            element: InlineElement::Text(emoji.to_owned()),
+        });
+        last_end = mat.end();
+        pos = mat.end();
+    }
+
+    if last_end == 0 {
+        elements.push(element);
+    } else if last_end < text.len() {
+        elements.push(RangedInlineElement {
+            start: start + last_end as u32,
+            end: element.end,
+            element: InlineElement::Text(text[last_end..].to_owned()),
+        });
+    }
+}
+
+/// Replaces links in `elements` by their children.
+fn unlink(elements: &mut Vec<RangedInlineElement>) {
+    let mut i = 0;
+    while i < elements.len() {
+        match &mut elements[i].element {
+            InlineElement::Link { children, .. } => {
+                let children = std::mem::take(children);
+                // The children are visited next, since they might contain links themselves
+                elements.splice(i..=i, children);
+                continue;
+            }
+            InlineElement::Bold(children)
+            | InlineElement::Italic(children)
+            | InlineElement::Strikethrough(children)
+            | InlineElement::Spoiler(children) => unlink(children),
+            InlineElement::Text(_)
+            | InlineElement::Code(_)
+            | InlineElement::Image(_)
+            | InlineElement::TaskListMarker(_) => {}
+        }
+        i += 1;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -892,6 +1218,14 @@ But it ends after the paragraph"#,
             MessageContent::try_parse_markdown(&">".repeat(MAX_DEPTH + 1)),
             Err(Error::DepthLimitReached)
         );
+        assert_eq!(
+            MessageContent::parse_markdown_result(&">".repeat(MAX_DEPTH + 1)),
+            Err(Error::DepthLimitReached)
+        );
+        assert_eq!(
+            MessageContent::parse_markdown_raw(vec![0xff]),
+            Err(Error::InvalidUtf8)
+        );
     }
 
     #[test]
@@ -904,6 +1238,115 @@ But it ends after the paragraph"#,
         MessageContent::try_parse_markdown("|>\n|-\n<Y>").unwrap();
     }
 
+    #[test]
+    fn table_alignments() {
+        let content = MessageContent::try_parse_markdown(
+            "| a | b | c | d |\n|---|:--|:-:|--:|\n| 1 | 2 | 3 | 4 |",
+        )
+        .unwrap();
+        match &content.elements[0].element {
+            BlockElement::Table {
+                head,
+                rows,
+                alignments,
+            } => {
+                assert_eq!(head.len(), 4);
+                assert_eq!(rows.len(), 1);
+                assert_eq!(
+                    alignments,
+                    &[
+                        ColumnAlignment::None,
+                        ColumnAlignment::Left,
+                        ColumnAlignment::Center,
+                        ColumnAlignment::Right,
+                    ]
+                );
+            }
+            other => panic!("Expected Table, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn truncated() {
+        let input = "first\n\nsecond";
+
+        // Everything fits
+        let content = MessageContent::parse_markdown_truncated(input, 100);
+        assert_eq!(content, MessageContent::parse_markdown(input));
+
+        // Only trailing whitespace is cut
+        let content = MessageContent::parse_markdown_truncated("first\n\n", 6);
+        assert_eq!(content, MessageContent::parse_markdown("first\n\n"));
+
+        // Text is cut within the first paragraph, the second one is dropped
+        let content = MessageContent::parse_markdown_truncated(input, 3);
+        assert_eq!(content.elements.len(), 2);
+        assert_eq!((content.elements[0].start, content.elements[0].end), (0, 3));
+        match &content.elements[0].element {
+            BlockElement::Paragraph(inlines) => {
+                assert_eq!(inlines.len(), 1);
+                is_text(&inlines[0], "fir", (0, 3));
+            }
+            other => panic!("Expected Paragraph, got {other:?}"),
+        }
+        assert_eq!(
+            content.elements[1],
+            RangedBlockElement {
+                start: 3,
+                end: 13,
+                element: BlockElement::Truncated,
+            }
+        );
+
+        // Characters are counted in the source, including markup
+        let content = MessageContent::parse_markdown_truncated("**bold** text", 10);
+        match &content.elements[0].element {
+            BlockElement::Paragraph(inlines) => {
+                assert_eq!(inlines.len(), 2);
+                is_text(&inlines[1], " t", (8, 10));
+            }
+            other => panic!("Expected Paragraph, got {other:?}"),
+        }
+        assert_eq!(content.elements[0].end, 10);
+    }
+
+    #[test]
+    fn truncated_nested() {
+        // Lists are cut between items
+        let content = MessageContent::parse_markdown_truncated("- a\n- b\n  - c\n  - d\n- e", 13);
+        assert_eq!(content.elements[0].end, 13);
+        match &content.elements[0].element {
+            BlockElement::UnorderedList(items) => {
+                assert_eq!(items.len(), 2);
+                assert_eq!(items[1][1].end, 13);
+                match &items[1][1].element {
+                    BlockElement::UnorderedList(nested) => assert_eq!(nested.len(), 1),
+                    other => panic!("Expected UnorderedList, got {other:?}"),
+                }
+            }
+            other => panic!("Expected UnorderedList, got {other:?}"),
+        }
+        assert_eq!(
+            content.elements.last().unwrap().element,
+            BlockElement::Truncated
+        );
+
+        // Tables keep the head and whole rows
+        let content = MessageContent::parse_markdown_truncated(
+            "| a | b |\n|---|---|\n| 1 | 2 |\n| 3 | 4 |",
+            30,
+        );
+        match &content.elements[0].element {
+            BlockElement::Table { head, rows, .. } => {
+                assert_eq!(head.len(), 2);
+                assert_eq!(rows.len(), 1);
+                assert_eq!(rows[0].len(), 2);
+                assert_eq!(content.elements[0].end, cells_end(&rows[0]));
+            }
+            other => panic!("Expected Table, got {other:?}"),
+        }
+    }
+
     fn parse_links(str_: &str) -> Vec<RangedInlineElement> {
         let mut elements = Vec::new();
         collect_links(0, str_.len() as u32, str_, &mut elements);
@@ -1028,6 +1471,126 @@ But it ends after the paragraph"#,
         is_text(&elems[4], "!", (70, 71));
     }
 
+    #[test]
+    fn collect_links_www() {
+        let elems = parse_links("See www.example.com/path, or awww.example.com.");
+        assert_eq!(elems.len(), 3);
+        is_text(&elems[0], "See ", (0, 4));
+        match &elems[1].element {
+            InlineElement::Link { dest_url, children } => {
+                assert_eq!(dest_url, "https://www.example.com/path");
+                assert_eq!((elems[1].start, elems[1].end), (4, 24));
+                assert_eq!(
+                    children[0].element,
+                    InlineElement::Text("www.example.com/path".to_owned())
+                );
+            }
+            other => panic!("Expected Link, got {other:?}"),
+        }
+        is_text(&elems[2], ", or awww.example.com.", (24, 46));
+
+        let elems = parse_links("https://www.example.com");
+        assert_eq!(elems.len(), 1);
+        is_link(&elems[0], "https://www.example.com", (0, 23));
+    }
+
+    #[test]
+    fn links_in_markdown_links_are_not_linked() {
+        let content =
+            MessageContent::try_parse_markdown("[www.example.com](https://example.org)").unwrap();
+        match &content.elements[0].element {
+            BlockElement::Paragraph(inlines) => {
+                assert_eq!(inlines.len(), 1);
+                match &inlines[0].element {
+                    InlineElement::Link { dest_url, children } => {
+                        assert_eq!(dest_url, "https://example.org");
+                        assert_eq!(children.len(), 1);
+                        assert_eq!(
+                            children[0].element,
+                            InlineElement::Text("www.example.com".to_owned())
+                        );
+                    }
+                    other => panic!("Expected Link, got {other:?}"),
+                }
+            }
+            other => panic!("Expected Paragraph, got {other:?}"),
+        }
+    }
+
+    fn paragraph(input: &str) -> Vec<RangedInlineElement> {
+        let content = MessageContent::try_parse_markdown(input).unwrap();
+        match content.elements.into_iter().next().unwrap().element {
+            BlockElement::Paragraph(inlines) => inlines,
+            other => panic!("Expected Paragraph, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn disabled_features_are_literal_text() {
+        let features = MarkdownFeatures {
+            strikethrough: false,
+            tables: false,
+            task_lists: false,
+        };
+
+        let content = MessageContent::try_parse_markdown_with("~~a~~", features).unwrap();
+        match &content.elements[0].element {
+            BlockElement::Paragraph(inlines) => {
+                assert_eq!(inlines.len(), 1);
+                assert_eq!(inlines[0].element, InlineElement::Text("~~a~~".to_owned()));
+            }
+            other => panic!("Expected Paragraph, got {other:?}"),
+        }
+
+        let content =
+            MessageContent::try_parse_markdown_with("| a |\n|---|\n| 1 |", features).unwrap();
+        assert!(matches!(
+            content.elements.as_slice(),
+            [RangedBlockElement {
+                element: BlockElement::Paragraph(_),
+                ..
+            }]
+        ));
+
+        let content = MessageContent::try_parse_markdown_with("- [x] done", features).unwrap();
+        match &content.elements[0].element {
+            BlockElement::UnorderedList(items) => assert!(matches!(
+                &items[0][0].element,
+                BlockElement::Paragraph(inlines)
+                    if !inlines.iter().any(|inline| matches!(inline.element, InlineElement::TaskListMarker(_)))
+            )),
+            other => panic!("Expected UnorderedList, got {other:?}"),
+        }
+
+        // The default keeps all features enabled
+        assert_eq!(
+            MessageContent::parse_markdown_with("~~a~~", MarkdownFeatures::default()),
+            MessageContent::parse_markdown("~~a~~")
+        );
+    }
+
+    #[test]
+    fn emoji_shortcodes() {
+        let inlines = paragraph("hi :smile: :unknown:wave: :+1:");
+        assert_eq!(inlines.len(), 6);
+        is_text(&inlines[0], "hi ", (0, 3));
+        is_text(&inlines[1], "😄", (3, 10));
+        is_text(&inlines[2], " :unknown", (10, 19));
+        is_text(&inlines[3], "👋", (19, 25));
+        is_text(&inlines[4], " ", (25, 26));
+        is_text(&inlines[5], "👍", (26, 30));
+    }
+
+    #[test]
+    fn emoji_shortcodes_not_in_code() {
+        let inlines = paragraph("`:smile:`");
+        assert_eq!(inlines.len(), 1);
+        assert_eq!(
+            inlines[0].element,
+            InlineElement::Code(":smile:".to_owned())
+        );
+    }
+
     #[test]
     fn autolink_with_underscores() {
         let text = "https://example.com/path/_suffix";
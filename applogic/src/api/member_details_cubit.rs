@@ -3,7 +3,11 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use aircommon::identifiers::UserId;
-use aircoreclient::{ChatId, clients::CoreUser, db::notification::DbEntityId};
+use aircoreclient::{
+    ChatId,
+    clients::CoreUser,
+    db::notification::{DbEntityId, DbEntityKind},
+};
 use flutter_rust_bridge::frb;
 use mimi_room_policy::{MimiProposal, RoleIndex, VerifiedRoomState};
 use tls_codec::Serialize;
@@ -131,7 +135,9 @@ impl MemberDetailsContext {
     }
 
     async fn update_state_task(self) {
-        let mut notifications = self.store.db_notifications();
+        let mut notifications = self
+            .store
+            .db_notifications_filtered(DbEntityKind::Chat | DbEntityKind::User);
         while let Some(notification) = notifications.next().await {
             // If this chat has changed, or any user changed
             if notification.ops.contains_key(&self.chat_id.into())
@@ -9,7 +9,7 @@ use std::sync::Arc;
 use aircoreclient::{
     MessageId,
     clients::CoreUser,
-    db::notification::{DbNotification, DbOperation},
+    db::notification::{DbEntityKind, DbNotification, DbOperation},
 };
 use flutter_rust_bridge::frb;
 use tokio::sync::watch;
@@ -50,7 +50,7 @@ impl MessageCubitBase {
         let message_id = initial_state.message.id.into();
 
         let store = user_cubit.core_user().clone();
-        let store_notifications = store.db_notifications();
+        let store_notifications = store.db_notifications_filtered(DbEntityKind::Message.into());
 
         let core = CubitCore::with_initial_state(initial_state);
 
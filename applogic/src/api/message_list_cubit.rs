@@ -12,7 +12,7 @@ use std::{
 use aircoreclient::{
     AttachmentId, ChatId, ChatMessage, ChatType, MessageId,
     clients::CoreUser,
-    db::notification::{DbEntityId, DbNotification, DbOperation},
+    db::notification::{DbEntityId, DbEntityKind, DbNotification, DbOperation},
 };
 use flutter_rust_bridge::frb;
 use tokio::sync::{Notify, broadcast, mpsc, watch};
@@ -568,7 +568,7 @@ impl MessageListCubitBase {
     #[frb(sync)]
     pub fn new(user_cubit: &UserCubitBase, chat_id: ChatId) -> Self {
         let store = user_cubit.core_user().clone();
-        let store_notifications = store.db_notifications();
+        let store_notifications = store.db_notifications_filtered(DbEntityKind::Message.into());
 
         let core = CubitCore::new();
         let (commands_tx, commands_rx) = mpsc::channel(4);
@@ -24,6 +24,7 @@ use chrono::{DateTime, Duration, Local, Utc};
 use flutter_rust_bridge::frb;
 use indexmap::IndexMap;
 use mimi_content::MessageStatus;
+use mimi_room_policy::RoleIndex;
 use uuid::Uuid;
 
 use crate::api::message_content::{UiMimiContent, UiMimiId, UnresolvedMimiContent};
@@ -543,6 +544,28 @@ pub enum UiSystemMessage {
     NewHandleConnectionChat(UiUsername),
     NewDirectConnectionChat(UiUserId),
     CreateGroup(UiUserId),
+    ChangeRole {
+        sender: UiUserId,
+        target: UiUserId,
+        role: UiMemberRole,
+    },
+}
+
+/// Role of a member in a group
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum UiMemberRole {
+    Regular,
+    /// Any role with more permissions than a regular member
+    Privileged,
+}
+
+impl From<RoleIndex> for UiMemberRole {
+    fn from(role: RoleIndex) -> Self {
+        match role {
+            RoleIndex::Regular => Self::Regular,
+            _ => Self::Privileged,
+        }
+    }
 }
 
 impl From<SystemMessage> for UiSystemMessage {
@@ -594,6 +617,15 @@ impl From<SystemMessage> for UiSystemMessage {
                 UiSystemMessage::NewDirectConnectionChat(user_id.into())
             }
             SystemMessage::CreateGroup(user_id) => UiSystemMessage::CreateGroup(user_id.into()),
+            SystemMessage::ChangeRole {
+                sender,
+                target,
+                role,
+            } => UiSystemMessage::ChangeRole {
+                sender: sender.into(),
+                target: target.into(),
+                role: role.into(),
+            },
         }
     }
 }
@@ -719,6 +751,7 @@ pub enum _AddUsernameContactError {
     UsernameNotFound,
     DuplicateRequest,
     OwnUsername,
+    TooManyPendingRequests,
 }
 
 /// Profile of a user
@@ -865,4 +898,7 @@ struct _AirFeatures {
     pub encrypted_group_profiles: bool,
     pub empty_connection_group_attributes: bool,
     pub pq_groups: bool,
+    pub typing_indicators: bool,
+    pub presence: bool,
+    pub user_profile_status_text: bool,
 }
@@ -79,11 +79,10 @@ impl User {
         )
         .await?;
 
-        let user_profile = UserProfile {
-            user_id: user.user_id().clone(),
-            display_name: display_name.parse()?,
-            profile_picture: profile_picture.map(Asset::Value),
-        };
+        // Keep the other fields of the stored profile, e.g. the status text
+        let mut user_profile = user.own_user_profile().await?;
+        user_profile.display_name = display_name.parse()?;
+        user_profile.profile_picture = profile_picture.map(Asset::Value);
 
         if let Err(error) = CoreUser::set_own_user_profile(&user, user_profile).await {
             error!(%error, "Could not set own user profile");
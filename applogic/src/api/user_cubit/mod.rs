@@ -9,7 +9,7 @@ use std::sync::Arc;
 pub(crate) use aircommon::identifiers::UsernameHash;
 use aircommon::identifiers::{UserId, Username};
 pub(crate) use aircoreclient::InviteUsersError;
-use aircoreclient::clients::StorageObjectType;
+use aircoreclient::clients::{StorageObjectType, reconnect::ListenStatus};
 use aircoreclient::{Asset, ChatId, ContactType, PartialContact, clients::CoreUser};
 use anyhow::ensure;
 use flutter_rust_bridge::frb;
@@ -18,7 +18,6 @@ use tokio::sync::watch;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error};
 use url::Url;
-use username::{UsernameBackgroundTasks, UsernameContext};
 
 use crate::api::logging::tar_logs;
 use crate::api::types::UiContact;
@@ -65,6 +64,8 @@ struct UiUserInner {
     user_id: UserId,
     usernames: Vec<Username>,
     unsupported_version: bool,
+    /// Connection status of the QS queue; `None` if not listening
+    listen_status: Option<ListenStatus>,
 }
 
 impl UiUser {
@@ -108,6 +109,15 @@ impl UiUser {
     pub fn unsupported_version(&self) -> bool {
         self.inner.unsupported_version
     }
+
+    /// Whether the connection to the server was lost and is being re-established
+    #[frb(getter, sync)]
+    pub fn is_reconnecting(&self) -> bool {
+        matches!(
+            self.inner.listen_status,
+            Some(ListenStatus::Reconnecting { .. })
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -132,7 +142,6 @@ pub struct UserCubitBase {
     core: CubitCore<UiUser>,
     context: CubitContext,
     app_state_tx: watch::Sender<AppState>,
-    background_listen_username_tasks: UsernameBackgroundTasks,
     cancel: CancellationToken,
 }
 
@@ -145,6 +154,7 @@ impl UserCubitBase {
             user_id: user.user.user_id().clone(),
             usernames: Vec::new(),
             unsupported_version: false,
+            listen_status: None,
         })));
 
         UiUser::spawn_load(core.state_tx().clone(), core_user.clone());
@@ -167,20 +177,21 @@ impl UserCubitBase {
         // emit persisted store notifications
         context.spawn_emit_stored_notifications(cancel.clone());
 
-        // start background task listening for incoming messages
+        // keep the focused chat in sync for deferring read receipts
+        context.spawn_track_chat_focus(cancel.clone());
+
+        // measure the clock skew to the server
+        context.spawn_update_server_clock_offset();
+
+        // start background task listening for incoming messages and username messages
         QueueContext::new(context.clone())
             .into_task(cancel.clone())
             .spawn();
 
-        // start background tasks listening for incoming username messages
-        let background_listen_username_tasks =
-            UsernameContext::spawn_loading(context.clone(), cancel.clone());
-
         Self {
             core,
             context,
             app_state_tx,
-            background_listen_username_tasks,
             cancel: cancel.clone(),
         }
     }
@@ -355,14 +366,16 @@ impl UserCubitBase {
 
     pub async fn add_username(&self, username: UiUsername) -> anyhow::Result<bool> {
         let username = Username::new(username.plaintext)?;
-        let Some(record) = self
+        // the username queue is listened to by the QS background task
+        if self
             .context
             .core_user
             .add_username(username.clone())
             .await?
-        else {
+            .is_none()
+        {
             return Ok(false);
-        };
+        }
 
         // add username to UI state
         self.core.state_tx().send_modify(|state| {
@@ -370,14 +383,6 @@ impl UserCubitBase {
             inner.usernames.push(username);
         });
 
-        // start background listen stream for the username
-        UsernameContext::new(self.context.clone(), record)
-            .into_task(
-                self.cancel.child_token(),
-                &self.background_listen_username_tasks,
-            )
-            .spawn();
-
         Ok(true)
     }
 
@@ -396,9 +401,6 @@ impl UserCubitBase {
             true
         });
 
-        // stop background listen stream for the username
-        self.background_listen_username_tasks.remove(username);
-
         Ok(())
     }
 
@@ -554,14 +556,86 @@ impl CubitContext {
                 continue;
             }
             // Finally eat these yummy notifications! Nom nom nom
-            match core_user.dequeue_db_notification().await {
-                Ok(store_notification) if !store_notification.is_empty() => {
+            let result = core_user
+                .dequeue_db_notification_with(async |_txn, store_notification| {
                     core_user.send_db_notification(store_notification);
+                    Ok(())
+                })
+                .await;
+            if let Err(error) = result {
+                error!(%error, "Failed to dequeue stored notifications");
+            }
+        }
+    }
+}
+
+impl CubitContext {
+    fn spawn_update_server_clock_offset(&self) {
+        let core_user = self.core_user.clone();
+        spawn_from_sync(async move {
+            match core_user.update_server_clock_offset().await {
+                Ok(offset) => debug!(%offset, "Updated server clock offset"),
+                Err(error) => error!(%error, "Failed to update server clock offset"),
+            }
+        });
+    }
+
+    fn spawn_track_chat_focus(&self, cancel: CancellationToken) {
+        let core_user = self.core_user.clone();
+        let app_state = self.app_state.clone();
+        let navigation_state = self.navigation_state.clone();
+        spawn_from_sync(async move {
+            Self::track_chat_focus(core_user, app_state, navigation_state, cancel).await
+        });
+    }
+
+    /// Propagates the chat which is focused in the UI to the core user.
+    ///
+    /// A chat is focused when it is open and the app is in the foreground.
+    async fn track_chat_focus(
+        core_user: CoreUser,
+        mut app_state: watch::Receiver<AppState>,
+        mut navigation_state: watch::Receiver<NavigationState>,
+        cancel: CancellationToken,
+    ) {
+        let mut focused: Option<ChatId> = None;
+        loop {
+            let chat_id = match (
+                *app_state.borrow_and_update(),
+                &*navigation_state.borrow_and_update(),
+            ) {
+                (
+                    AppState::Foreground,
+                    NavigationState::Home {
+                        home:
+                            HomeNavigationState {
+                                chat_open: true,
+                                chat_id,
+                                ..
+                            },
+                    },
+                ) => *chat_id,
+                _ => None,
+            };
+
+            if chat_id != focused {
+                if let Some(previous) = focused
+                    && let Err(error) = core_user.set_chat_focused(previous, false).await
+                {
+                    error!(%error, "Failed to unfocus chat");
                 }
-                Ok(_) => {}
-                Err(error) => {
-                    error!(%error, "Failed to dequeue stored notifications");
+                if let Some(chat_id) = chat_id
+                    && let Err(error) = core_user.set_chat_focused(chat_id, true).await
+                {
+                    error!(%error, "Failed to focus chat");
                 }
+                focused = chat_id;
+            }
+
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                changed = app_state.changed() => if changed.is_err() { return },
+                changed = navigation_state.changed() => if changed.is_err() { return },
             }
         }
     }
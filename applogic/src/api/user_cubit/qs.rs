@@ -6,7 +6,9 @@ use std::sync::Arc;
 
 use aircoreclient::clients::{
     ListenResponse,
+    listen_all::InboundEvent,
     process::process_qs::{ProcessedQsMessages, QsProcessEventResult},
+    reconnect::{ListenStatus, ReconnectPolicy},
 };
 use flutter_rust_bridge::frb;
 use tokio_stream::Stream;
@@ -20,6 +22,8 @@ use crate::{
 
 use super::{AppState, CubitContext};
 
+/// The context of the background task that listens to the QS queue and the queues of all
+/// usernames.
 #[derive(Debug)]
 #[frb(ignore)]
 pub(super) struct QueueContext {
@@ -27,18 +31,17 @@ pub(super) struct QueueContext {
 }
 
 impl CubitContext {
-    async fn show_notifications_for_processed_qs_messages(
-        &self,
-        ProcessedQsMessages {
+    async fn show_notifications_for_processed_qs_messages(&self, processed: ProcessedQsMessages) {
+        let new_messages = processed.group_by_chat();
+        let ProcessedQsMessages {
             new_chats,
             changed_chats: _,
-            new_messages,
+            new_messages: _,
             errors: _,
             processed: _,
             new_connections,
             reaction_notifications,
-        }: ProcessedQsMessages,
-    ) {
+        } = processed;
         let mut notifications = Vec::with_capacity(new_chats.len() + new_messages.len());
         let user = User::from_core_user(self.core_user.clone());
         user.new_chat_notifications(&new_chats, &mut notifications)
@@ -53,23 +56,38 @@ impl CubitContext {
     }
 }
 
-impl BackgroundStreamContext<ListenResponse> for QueueContext {
+impl BackgroundStreamContext<InboundEvent> for QueueContext {
     async fn create_stream(
         &mut self,
-    ) -> anyhow::Result<impl Stream<Item = ListenResponse> + 'static> {
-        let (stream, responder) = match self.cubit_context.core_user.listen_queue().await {
-            Ok(stream) => {
-                self.cubit_context.state_tx.send_if_modified(|state| {
-                    if !state.inner.unsupported_version {
-                        return false;
-                    }
-                    let inner = Arc::make_mut(&mut state.inner);
-                    inner.unsupported_version = false;
-                    true
-                });
-                stream
+    ) -> anyhow::Result<impl Stream<Item = InboundEvent> + 'static> {
+        Ok(self
+            .cubit_context
+            .core_user
+            .listen_all(ReconnectPolicy::default()))
+    }
+
+    async fn on_stream_end(&mut self) {
+        self.set_listen_status(None);
+    }
+
+    async fn handle_event(&mut self, event: InboundEvent) -> bool {
+        match event {
+            InboundEvent::Status(status) => {
+                self.set_listen_status(Some(status));
+                true // continue
             }
-            Err(error) if error.is_unsupported_version() => {
+            InboundEvent::QsEvent(event) => self.handle_qs_event(event).await,
+            InboundEvent::HandleMessage {
+                username,
+                message,
+                responder,
+            } => {
+                self.cubit_context
+                    .process_username_queue_message(username, message, &responder)
+                    .await;
+                true // continue
+            }
+            InboundEvent::UnsupportedVersion => {
                 self.cubit_context.state_tx.send_if_modified(|state| {
                     if state.inner.unsupported_version {
                         return false;
@@ -78,41 +96,9 @@ impl BackgroundStreamContext<ListenResponse> for QueueContext {
                     inner.unsupported_version = true;
                     true
                 });
-                return Err(error.into());
-            }
-            Err(error) => return Err(error.into()),
-        };
-        self.cubit_context
-            .core_user
-            .replace_qs_listen_responder(responder)
-            .await;
-        Ok(stream)
-    }
-
-    async fn handle_event(&mut self, event: ListenResponse) -> bool {
-        let result = match self.cubit_context.core_user.process_qs_event(event).await {
-            Ok(result) => result,
-            Err(error) => {
-                error!(%error, "Failed to process QS event");
-                return false;
-            }
-        };
-
-        let is_partially_processed = result.is_partially_processed();
-        match result {
-            QsProcessEventResult::FullyProcessed { processed }
-            | QsProcessEventResult::PartiallyProcessed { processed, .. } => {
-                self.cubit_context
-                    .show_notifications_for_processed_qs_messages(processed)
-                    .await;
+                true // continue
             }
-            QsProcessEventResult::Accumulated | QsProcessEventResult::Ignored => (),
-        };
-
-        // Stop stream if partially processed
-        // => There is a hole in the sequence of the messages, therefore we cannot continue
-        // processing them.
-        !is_partially_processed
+        }
     }
 
     async fn in_foreground(&self) {
@@ -144,10 +130,55 @@ impl QueueContext {
         Self { cubit_context }
     }
 
+    /// Forwards the connection status of the QS queue to the cubit state.
+    ///
+    /// A successful connection also means that the client version is supported.
+    fn set_listen_status(&self, status: Option<ListenStatus>) {
+        self.cubit_context.state_tx.send_if_modified(|state| {
+            let unsupported_version =
+                state.inner.unsupported_version && status != Some(ListenStatus::Connected);
+            if state.inner.listen_status == status
+                && state.inner.unsupported_version == unsupported_version
+            {
+                return false;
+            }
+            let inner = Arc::make_mut(&mut state.inner);
+            inner.listen_status = status;
+            inner.unsupported_version = unsupported_version;
+            true
+        });
+    }
+
+    async fn handle_qs_event(&mut self, event: ListenResponse) -> bool {
+        let result = match self.cubit_context.core_user.process_qs_event(event).await {
+            Ok(result) => result,
+            Err(error) => {
+                error!(%error, "Failed to process QS event");
+                return false;
+            }
+        };
+
+        let is_partially_processed = result.is_partially_processed();
+        match result {
+            QsProcessEventResult::FullyProcessed { processed }
+            | QsProcessEventResult::PartiallyProcessed { processed, .. } => {
+                self.cubit_context
+                    .show_notifications_for_processed_qs_messages(processed)
+                    .await;
+            }
+            QsProcessEventResult::Accumulated | QsProcessEventResult::Ignored => (),
+        };
+
+        // Stop stream if partially processed
+        // => There is a hole in the sequence of the messages, therefore we cannot continue
+        // processing them.
+        !is_partially_processed
+    }
+
     pub(super) fn into_task(
         self,
         cancel: CancellationToken,
-    ) -> BackgroundStreamTask<Self, ListenResponse> {
+    ) -> BackgroundStreamTask<Self, InboundEvent> {
         BackgroundStreamTask::new("qs", self, cancel)
     }
 }
@@ -11,7 +11,7 @@ use aircommon::identifiers::UserId;
 use aircoreclient::{
     DisplayName, UserProfile,
     clients::CoreUser,
-    db::notification::{DbEntityId, DbNotification, DbOperation},
+    db::notification::{DbEntityId, DbEntityKind, DbNotification, DbOperation},
 };
 use flutter_rust_bridge::frb;
 use tokio::sync::{mpsc, watch};
@@ -261,7 +261,9 @@ impl ProfileLoadingTask {
     }
 
     async fn process(mut self) -> Option<()> {
-        let mut store_notifications = self.core_user.db_notifications();
+        let mut store_notifications = self
+            .core_user
+            .db_notifications_filtered(DbEntityKind::User.into());
         loop {
             // wait for the next store notification, explicit load profile request or cancellation
             let changed_profiles = tokio::select! {
@@ -142,6 +142,7 @@ pub(crate) async fn retrieve_messages(path: String) -> anyhow::Result<Notificati
                     title: "Software update required".to_string(),
                     body: "Update to keep using Air".to_string(),
                     chat_id: ChatId::new(Uuid::nil()),
+                    new_messages: 0,
                 }]
             }
             FetchAndProcessAllMessagesError::Fatal(error) => {
@@ -161,6 +162,10 @@ pub(crate) async fn retrieve_messages(path: String) -> anyhow::Result<Notificati
     Ok(NotificationBatch {
         badge_count,
         removals: Vec::new(),
-        additions: notifications,
+        // shown by the native notification extension without the localizations of the app
+        additions: notifications
+            .into_iter()
+            .map(NotificationContent::with_summary_body)
+            .collect(),
     })
 }
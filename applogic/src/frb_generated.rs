@@ -4789,6 +4789,54 @@ fn wire__crate__api__member_details_cubit__UiRoomState_can_kick_impl(
         },
     )
 }
+fn wire__crate__api__user_cubit__UiUser_is_reconnecting_impl(
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) -> flutter_rust_bridge::for_generated::WireSyncRust2DartSse {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_sync::<flutter_rust_bridge::for_generated::SseCodec, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "UiUser_is_reconnecting",
+            port: None,
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Sync,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_that = <RustOpaqueMoi<
+                flutter_rust_bridge::for_generated::RustAutoOpaqueInner<UiUser>,
+            >>::sse_decode(&mut deserializer);
+            deserializer.end();
+            transform_result_sse::<_, ()>((move || {
+                let mut api_that_guard = None;
+                let decode_indices_ =
+                    flutter_rust_bridge::for_generated::lockable_compute_decode_order(vec![
+                        flutter_rust_bridge::for_generated::LockableOrderInfo::new(
+                            &api_that, 0, false,
+                        ),
+                    ]);
+                for i in decode_indices_ {
+                    match i {
+                        0 => api_that_guard = Some(api_that.lockable_decode_sync_ref()),
+                        _ => unreachable!(),
+                    }
+                }
+                let api_that_guard = api_that_guard.unwrap();
+                let output_ok = Result::<_, ()>::Ok(
+                    crate::api::user_cubit::UiUser::is_reconnecting(&*api_that_guard),
+                )?;
+                Ok(output_ok)
+            })())
+        },
+    )
+}
 fn wire__crate__api__user_cubit__UiUser_unsupported_version_impl(
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
     rust_vec_len_: i32,
@@ -8158,6 +8206,39 @@ fn wire__crate__api__invitation_code__check_invitation_code_impl(
         },
     )
 }
+fn wire__crate__api__markdown__column_alignment_default_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "column_alignment_default",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            deserializer.end();
+            move |context| {
+                transform_result_sse::<_, ()>((move || {
+                    let output_ok =
+                        Result::<_, ()>::Ok(crate::api::markdown::ColumnAlignment::default())?;
+                    Ok(output_ok)
+                })())
+            }
+        },
+    )
+}
 fn wire__crate__api__logging__clear_app_logs_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
@@ -8469,6 +8550,41 @@ fn wire__crate__api__logging__init_rust_logging_impl(
         },
     )
 }
+fn wire__crate__api__logging__init_rust_logging_with_rotation_impl(
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) -> flutter_rust_bridge::for_generated::WireSyncRust2DartSse {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_sync::<flutter_rust_bridge::for_generated::SseCodec, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "init_rust_logging_with_rotation",
+            port: None,
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Sync,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_log_file = <String>::sse_decode(&mut deserializer);
+            let api_rotated_segments = <u32>::sse_decode(&mut deserializer);
+            deserializer.end();
+            transform_result_sse::<_, ()>((move || {
+                let output_ok =
+                    Result::<_, ()>::Ok(crate::api::logging::init_rust_logging_with_rotation(
+                        api_log_file,
+                        api_rotated_segments,
+                    ))?;
+                Ok(output_ok)
+            })())
+        },
+    )
+}
 fn wire__crate__api__invitation_codes_cubit__invitation_codes_state_default_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
@@ -8537,6 +8653,39 @@ fn wire__crate__api__utils__is_image_file_impl(
         },
     )
 }
+fn wire__crate__api__markdown__markdown_features_default_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "markdown_features_default",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            deserializer.end();
+            move |context| {
+                transform_result_sse::<_, ()>((move || {
+                    let output_ok =
+                        Result::<_, ()>::Ok(crate::api::markdown::MarkdownFeatures::default())?;
+                    Ok(output_ok)
+                })())
+            }
+        },
+    )
+}
 fn wire__crate__api__member_details_cubit__member_details_state_default_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
@@ -8674,6 +8823,116 @@ fn wire__crate__api__markdown__message_content_parse_markdown_raw_impl(
         },
     )
 }
+fn wire__crate__api__markdown__message_content_parse_markdown_result_impl(
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) -> flutter_rust_bridge::for_generated::WireSyncRust2DartSse {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_sync::<flutter_rust_bridge::for_generated::SseCodec, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "message_content_parse_markdown_result",
+            port: None,
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Sync,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_string = <String>::sse_decode(&mut deserializer);
+            deserializer.end();
+            transform_result_sse::<_, crate::api::markdown::Error>((move || {
+                let output_ok =
+                    crate::api::markdown::MessageContent::parse_markdown_result(&api_string)?;
+                Ok(output_ok)
+            })())
+        },
+    )
+}
+fn wire__crate__api__markdown__message_content_parse_markdown_truncated_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "message_content_parse_markdown_truncated",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_string = <String>::sse_decode(&mut deserializer);
+            let api_max_chars = <usize>::sse_decode(&mut deserializer);
+            deserializer.end();
+            move |context| {
+                transform_result_sse::<_, ()>((move || {
+                    let output_ok = Result::<_, ()>::Ok(
+                        crate::api::markdown::MessageContent::parse_markdown_truncated(
+                            &api_string,
+                            api_max_chars,
+                        ),
+                    )?;
+                    Ok(output_ok)
+                })())
+            }
+        },
+    )
+}
+fn wire__crate__api__markdown__message_content_parse_markdown_with_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::SseCodec, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "message_content_parse_markdown_with",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_string = <String>::sse_decode(&mut deserializer);
+            let api_features =
+                <crate::api::markdown::MarkdownFeatures>::sse_decode(&mut deserializer);
+            deserializer.end();
+            move |context| {
+                transform_result_sse::<_, ()>((move || {
+                    let output_ok = Result::<_, ()>::Ok(
+                        crate::api::markdown::MessageContent::parse_markdown_with(
+                            &api_string,
+                            api_features,
+                        ),
+                    )?;
+                    Ok(output_ok)
+                })())
+            }
+        },
+    )
+}
 fn wire__crate__api__message_list_cubit__message_list_state_default_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
@@ -8997,6 +9256,70 @@ fn wire__crate__api__utils__read_clipboard_image_impl(
         },
     )
 }
+fn wire__crate__api__logging__set_rust_log_directives_impl(
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) -> flutter_rust_bridge::for_generated::WireSyncRust2DartSse {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_sync::<flutter_rust_bridge::for_generated::SseCodec, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "set_rust_log_directives",
+            port: None,
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Sync,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_directives = <String>::sse_decode(&mut deserializer);
+            deserializer.end();
+            transform_result_sse::<_, flutter_rust_bridge::for_generated::anyhow::Error>(
+                (move || {
+                    let output_ok = crate::api::logging::set_rust_log_directives(api_directives)?;
+                    Ok(output_ok)
+                })(),
+            )
+        },
+    )
+}
+fn wire__crate__api__logging__set_rust_log_level_impl(
+    ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
+    rust_vec_len_: i32,
+    data_len_: i32,
+) -> flutter_rust_bridge::for_generated::WireSyncRust2DartSse {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_sync::<flutter_rust_bridge::for_generated::SseCodec, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "set_rust_log_level",
+            port: None,
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Sync,
+        },
+        move || {
+            let message = unsafe {
+                flutter_rust_bridge::for_generated::Dart2RustMessageSse::from_wire(
+                    ptr_,
+                    rust_vec_len_,
+                    data_len_,
+                )
+            };
+            let mut deserializer =
+                flutter_rust_bridge::for_generated::SseDeserializer::new(message);
+            let api_level = <crate::api::logging::LogEntryLevel>::sse_decode(&mut deserializer);
+            deserializer.end();
+            transform_result_sse::<_, flutter_rust_bridge::for_generated::anyhow::Error>(
+                (move || {
+                    let output_ok = crate::api::logging::set_rust_log_level(api_level)?;
+                    Ok(output_ok)
+                })(),
+            )
+        },
+    )
+}
 fn wire__crate__api__logging__tar_logs_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     ptr_: flutter_rust_bridge::for_generated::PlatformGeneralizedUint8ListPtr,
@@ -10161,6 +10484,7 @@ impl SseDecode for crate::api::types::AddUsernameContactError {
             0 => crate::api::types::AddUsernameContactError::UsernameNotFound,
             1 => crate::api::types::AddUsernameContactError::DuplicateRequest,
             2 => crate::api::types::AddUsernameContactError::OwnUsername,
+            3 => crate::api::types::AddUsernameContactError::TooManyPendingRequests,
             _ => unreachable!("Invalid variant for AddUsernameContactError: {}", inner),
         };
     }
@@ -10184,10 +10508,16 @@ impl SseDecode for crate::api::types::AirFeatures {
         let mut var_encryptedGroupProfiles = <bool>::sse_decode(deserializer);
         let mut var_emptyConnectionGroupAttributes = <bool>::sse_decode(deserializer);
         let mut var_pqGroups = <bool>::sse_decode(deserializer);
+        let mut var_typingIndicators = <bool>::sse_decode(deserializer);
+        let mut var_presence = <bool>::sse_decode(deserializer);
+        let mut var_userProfileStatusText = <bool>::sse_decode(deserializer);
         return crate::api::types::AirFeatures {
             encrypted_group_profiles: var_encryptedGroupProfiles,
             empty_connection_group_attributes: var_emptyConnectionGroupAttributes,
             pq_groups: var_pqGroups,
+            typing_indicators: var_typingIndicators,
+            presence: var_presence,
+            user_profile_status_text: var_userProfileStatusText,
         };
     }
 }
@@ -10264,9 +10594,12 @@ impl SseDecode for crate::api::markdown::BlockElement {
                     <Vec<Vec<Vec<crate::api::markdown::RangedBlockElement>>>>::sse_decode(
                         deserializer,
                     );
+                let mut var_alignments =
+                    <Vec<crate::api::markdown::ColumnAlignment>>::sse_decode(deserializer);
                 return crate::api::markdown::BlockElement::Table {
                     head: var_head,
                     rows: var_rows,
+                    alignments: var_alignments,
                 };
             }
             6 => {
@@ -10281,6 +10614,9 @@ impl SseDecode for crate::api::markdown::BlockElement {
                 let mut var_field0 = <String>::sse_decode(deserializer);
                 return crate::api::markdown::BlockElement::Error(var_field0);
             }
+            9 => {
+                return crate::api::markdown::BlockElement::Truncated;
+            }
             _ => {
                 unimplemented!("");
             }
@@ -10334,6 +10670,20 @@ impl SseDecode for crate::api::chat_list_cubit::ChatListState {
     }
 }
 
+impl SseDecode for crate::api::markdown::ColumnAlignment {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut inner = <i32>::sse_decode(deserializer);
+        return match inner {
+            0 => crate::api::markdown::ColumnAlignment::None,
+            1 => crate::api::markdown::ColumnAlignment::Left,
+            2 => crate::api::markdown::ColumnAlignment::Center,
+            3 => crate::api::markdown::ColumnAlignment::Right,
+            _ => unreachable!("Invalid variant for ColumnAlignment: {}", inner),
+        };
+    }
+}
+
 impl SseDecode for crate::api::chat_details_cubit::DebugCapabilities {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -10396,6 +10746,28 @@ impl SseDecode for crate::api::chat_details_cubit::EncryptedGroupTitleDebugInfo
     }
 }
 
+impl SseDecode for crate::api::markdown::Error {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut inner = <i32>::sse_decode(deserializer);
+        return match inner {
+            0 => crate::api::markdown::Error::ExpectedMoreEvents,
+            1 => crate::api::markdown::Error::ExpectedSpecificTag,
+            2 => crate::api::markdown::Error::TableContentNotInTable,
+            3 => crate::api::markdown::Error::ListItemNotInList,
+            4 => crate::api::markdown::Error::MetadataBlocksNotSupported,
+            5 => crate::api::markdown::Error::FootnotesNotSupported,
+            6 => crate::api::markdown::Error::DefinitionListsNotSupported,
+            7 => crate::api::markdown::Error::BlockElementInline,
+            8 => crate::api::markdown::Error::HtmlNotInBlock,
+            9 => crate::api::markdown::Error::MathNotSupported,
+            10 => crate::api::markdown::Error::DepthLimitReached,
+            11 => crate::api::markdown::Error::InvalidUtf8,
+            _ => unreachable!("Invalid variant for Error: {}", inner),
+        };
+    }
+}
+
 impl SseDecode for crate::api::chat_details_cubit::ExternalGroupProfileDebugInfo {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -10726,6 +11098,20 @@ impl SseDecode for Vec<crate::api::types::ChatId> {
     }
 }
 
+impl SseDecode for Vec<crate::api::markdown::ColumnAlignment> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut len_ = <i32>::sse_decode(deserializer);
+        let mut ans_ = Vec::with_capacity(len_ as usize);
+        for idx_ in 0..len_ {
+            ans_.push(<crate::api::markdown::ColumnAlignment>::sse_decode(
+                deserializer,
+            ));
+        }
+        return ans_;
+    }
+}
+
 impl SseDecode for Vec<crate::api::navigation_cubit::IntroScreenType> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -11039,6 +11425,20 @@ impl SseDecode for crate::api::logging::LogEntryLevel {
     }
 }
 
+impl SseDecode for crate::api::markdown::MarkdownFeatures {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_strikethrough = <bool>::sse_decode(deserializer);
+        let mut var_tables = <bool>::sse_decode(deserializer);
+        let mut var_taskLists = <bool>::sse_decode(deserializer);
+        return crate::api::markdown::MarkdownFeatures {
+            strikethrough: var_strikethrough,
+            tables: var_tables,
+            task_lists: var_taskLists,
+        };
+    }
+}
+
 impl SseDecode for crate::api::member_details_cubit::MemberDetailsState {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -11282,11 +11682,13 @@ impl SseDecode for crate::notifications::NotificationContent {
         let mut var_title = <String>::sse_decode(deserializer);
         let mut var_body = <String>::sse_decode(deserializer);
         let mut var_chatId = <crate::api::types::ChatId>::sse_decode(deserializer);
+        let mut var_newMessages = <u32>::sse_decode(deserializer);
         return crate::notifications::NotificationContent {
             identifier: var_identifier,
             title: var_title,
             body: var_body,
             chat_id: var_chatId,
+            new_messages: var_newMessages,
         };
     }
 }
@@ -12368,6 +12770,18 @@ impl SseDecode for crate::api::invitation_codes_cubit::UiInvitationCode {
     }
 }
 
+impl SseDecode for crate::api::types::UiMemberRole {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut inner = <i32>::sse_decode(deserializer);
+        return match inner {
+            0 => crate::api::types::UiMemberRole::Regular,
+            1 => crate::api::types::UiMemberRole::Privileged,
+            _ => unreachable!("Invalid variant for UiMemberRole: {}", inner),
+        };
+    }
+}
+
 impl SseDecode for crate::api::types::UiMessage {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -12541,6 +12955,16 @@ impl SseDecode for crate::api::types::UiSystemMessage {
                 let mut var_field0 = <crate::api::types::UiUserId>::sse_decode(deserializer);
                 return crate::api::types::UiSystemMessage::CreateGroup(var_field0);
             }
+            11 => {
+                let mut var_sender = <crate::api::types::UiUserId>::sse_decode(deserializer);
+                let mut var_target = <crate::api::types::UiUserId>::sse_decode(deserializer);
+                let mut var_role = <crate::api::types::UiMemberRole>::sse_decode(deserializer);
+                return crate::api::types::UiSystemMessage::ChangeRole {
+                    sender: var_sender,
+                    target: var_target,
+                    role: var_role,
+                };
+            }
             _ => {
                 unimplemented!("");
             }
@@ -12603,6 +13027,9 @@ impl SseDecode for crate::api::chat_details_cubit::UploadAttachmentError {
                     actual_size_bytes: var_actualSizeBytes,
                 };
             }
+            1 => {
+                return crate::api::chat_details_cubit::UploadAttachmentError::UnsupportedImageFormat;
+            }
             _ => {
                 unimplemented!("");
             }
@@ -12809,6 +13236,10 @@ fn pde_ffi_dispatcher_primary_impl(
 178 => wire__crate__api__utils__read_clipboard_file_paths_impl(port, ptr, rust_vec_len, data_len),
 179 => wire__crate__api__utils__read_clipboard_image_impl(port, ptr, rust_vec_len, data_len),
 180 => wire__crate__api__logging__tar_logs_impl(port, ptr, rust_vec_len, data_len),
+183 => wire__crate__api__markdown__column_alignment_default_impl(port, ptr, rust_vec_len, data_len),
+185 => wire__crate__api__markdown__markdown_features_default_impl(port, ptr, rust_vec_len, data_len),
+187 => wire__crate__api__markdown__message_content_parse_markdown_truncated_impl(port, ptr, rust_vec_len, data_len),
+188 => wire__crate__api__markdown__message_content_parse_markdown_with_impl(port, ptr, rust_vec_len, data_len),
                         _ => unreachable!(),
                     }
 }
@@ -13040,6 +13471,21 @@ fn pde_ffi_dispatcher_sync_impl(
             rust_vec_len,
             data_len,
         ),
+        184 => wire__crate__api__logging__init_rust_logging_with_rotation_impl(
+            ptr,
+            rust_vec_len,
+            data_len,
+        ),
+        186 => wire__crate__api__markdown__message_content_parse_markdown_result_impl(
+            ptr,
+            rust_vec_len,
+            data_len,
+        ),
+        189 => wire__crate__api__logging__set_rust_log_directives_impl(ptr, rust_vec_len, data_len),
+        190 => wire__crate__api__logging__set_rust_log_level_impl(ptr, rust_vec_len, data_len),
+        191 => {
+            wire__crate__api__user_cubit__UiUser_is_reconnecting_impl(ptr, rust_vec_len, data_len)
+        }
         _ => unreachable!(),
     }
 }
@@ -13442,6 +13888,7 @@ impl flutter_rust_bridge::IntoDart for FrbWrapper<crate::api::types::AddUsername
             crate::api::types::AddUsernameContactError::UsernameNotFound => 0.into_dart(),
             crate::api::types::AddUsernameContactError::DuplicateRequest => 1.into_dart(),
             crate::api::types::AddUsernameContactError::OwnUsername => 2.into_dart(),
+            crate::api::types::AddUsernameContactError::TooManyPendingRequests => 3.into_dart(),
             _ => unreachable!(),
         }
     }
@@ -13587,10 +14034,15 @@ impl flutter_rust_bridge::IntoDart for crate::api::markdown::BlockElement {
                 field1.into_into_dart().into_dart(),
             ]
             .into_dart(),
-            crate::api::markdown::BlockElement::Table { head, rows } => [
+            crate::api::markdown::BlockElement::Table {
+                head,
+                rows,
+                alignments,
+            } => [
                 5.into_dart(),
                 head.into_into_dart().into_dart(),
                 rows.into_into_dart().into_dart(),
+                alignments.into_into_dart().into_dart(),
             ]
             .into_dart(),
             crate::api::markdown::BlockElement::HorizontalRule => [6.into_dart()].into_dart(),
@@ -13600,6 +14052,7 @@ impl flutter_rust_bridge::IntoDart for crate::api::markdown::BlockElement {
             crate::api::markdown::BlockElement::Error(field0) => {
                 [8.into_dart(), field0.into_into_dart().into_dart()].into_dart()
             }
+            crate::api::markdown::BlockElement::Truncated => [9.into_dart()].into_dart(),
             _ => {
                 unimplemented!("");
             }
@@ -13673,6 +14126,29 @@ impl flutter_rust_bridge::IntoIntoDart<crate::api::chat_list_cubit::ChatListStat
     }
 }
 // Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::api::markdown::ColumnAlignment {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        match self {
+            Self::None => 0.into_dart(),
+            Self::Left => 1.into_dart(),
+            Self::Center => 2.into_dart(),
+            Self::Right => 3.into_dart(),
+            _ => unreachable!(),
+        }
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::markdown::ColumnAlignment
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::markdown::ColumnAlignment>
+    for crate::api::markdown::ColumnAlignment
+{
+    fn into_into_dart(self) -> crate::api::markdown::ColumnAlignment {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
 impl flutter_rust_bridge::IntoDart
     for FrbWrapper<crate::api::chat_details_cubit::DebugCapabilities>
 {
@@ -13770,6 +14246,34 @@ impl
     }
 }
 // Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::api::markdown::Error {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        match self {
+            Self::ExpectedMoreEvents => 0.into_dart(),
+            Self::ExpectedSpecificTag => 1.into_dart(),
+            Self::TableContentNotInTable => 2.into_dart(),
+            Self::ListItemNotInList => 3.into_dart(),
+            Self::MetadataBlocksNotSupported => 4.into_dart(),
+            Self::FootnotesNotSupported => 5.into_dart(),
+            Self::DefinitionListsNotSupported => 6.into_dart(),
+            Self::BlockElementInline => 7.into_dart(),
+            Self::HtmlNotInBlock => 8.into_dart(),
+            Self::MathNotSupported => 9.into_dart(),
+            Self::DepthLimitReached => 10.into_dart(),
+            Self::InvalidUtf8 => 11.into_dart(),
+            _ => unreachable!(),
+        }
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive for crate::api::markdown::Error {}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::markdown::Error>
+    for crate::api::markdown::Error
+{
+    fn into_into_dart(self) -> crate::api::markdown::Error {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
 impl flutter_rust_bridge::IntoDart
     for FrbWrapper<crate::api::chat_details_cubit::ExternalGroupProfileDebugInfo>
 {
@@ -14143,6 +14647,28 @@ impl flutter_rust_bridge::IntoIntoDart<crate::api::logging::LogEntryLevel>
     }
 }
 // Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::api::markdown::MarkdownFeatures {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        [
+            self.strikethrough.into_into_dart().into_dart(),
+            self.tables.into_into_dart().into_dart(),
+            self.task_lists.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::markdown::MarkdownFeatures
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::markdown::MarkdownFeatures>
+    for crate::api::markdown::MarkdownFeatures
+{
+    fn into_into_dart(self) -> crate::api::markdown::MarkdownFeatures {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
 impl flutter_rust_bridge::IntoDart for crate::api::member_details_cubit::MemberDetailsState {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
         [self.room_state.into_into_dart().into_dart()].into_dart()
@@ -14456,6 +14982,7 @@ impl flutter_rust_bridge::IntoDart for crate::notifications::NotificationContent
             self.title.into_into_dart().into_dart(),
             self.body.into_into_dart().into_dart(),
             self.chat_id.into_into_dart().into_dart(),
+            self.new_messages.into_into_dart().into_dart(),
         ]
         .into_dart()
     }
@@ -15184,6 +15711,27 @@ impl flutter_rust_bridge::IntoIntoDart<crate::api::invitation_codes_cubit::UiInv
     }
 }
 // Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::api::types::UiMemberRole {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        match self {
+            Self::Regular => 0.into_dart(),
+            Self::Privileged => 1.into_dart(),
+            _ => unreachable!(),
+        }
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::types::UiMemberRole
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::types::UiMemberRole>
+    for crate::api::types::UiMemberRole
+{
+    fn into_into_dart(self) -> crate::api::types::UiMemberRole {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
 impl flutter_rust_bridge::IntoDart for crate::api::types::UiMessage {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
         match self {
@@ -15384,6 +15932,17 @@ impl flutter_rust_bridge::IntoDart for crate::api::types::UiSystemMessage {
             crate::api::types::UiSystemMessage::CreateGroup(field0) => {
                 [10.into_dart(), field0.into_into_dart().into_dart()].into_dart()
             }
+            crate::api::types::UiSystemMessage::ChangeRole {
+                sender,
+                target,
+                role,
+            } => [
+                11.into_dart(),
+                sender.into_into_dart().into_dart(),
+                target.into_into_dart().into_dart(),
+                role.into_into_dart().into_dart(),
+            ]
+            .into_dart(),
             _ => {
                 unimplemented!("");
             }
@@ -15468,6 +16027,9 @@ impl flutter_rust_bridge::IntoDart for crate::api::chat_details_cubit::UploadAtt
                 actual_size_bytes.into_into_dart().into_dart(),
             ]
             .into_dart(),
+            crate::api::chat_details_cubit::UploadAttachmentError::UnsupportedImageFormat => {
+                [1.into_dart()].into_dart()
+            }
             _ => {
                 unimplemented!("");
             }
@@ -16257,6 +16819,7 @@ impl SseEncode for crate::api::types::AddUsernameContactError {
                 crate::api::types::AddUsernameContactError::UsernameNotFound => 0,
                 crate::api::types::AddUsernameContactError::DuplicateRequest => 1,
                 crate::api::types::AddUsernameContactError::OwnUsername => 2,
+                crate::api::types::AddUsernameContactError::TooManyPendingRequests => 3,
                 _ => {
                     unimplemented!("");
                 }
@@ -16280,6 +16843,9 @@ impl SseEncode for crate::api::types::AirFeatures {
         <bool>::sse_encode(self.encrypted_group_profiles, serializer);
         <bool>::sse_encode(self.empty_connection_group_attributes, serializer);
         <bool>::sse_encode(self.pq_groups, serializer);
+        <bool>::sse_encode(self.typing_indicators, serializer);
+        <bool>::sse_encode(self.presence, serializer);
+        <bool>::sse_encode(self.user_profile_status_text, serializer);
     }
 }
 
@@ -16344,12 +16910,17 @@ impl SseEncode for crate::api::markdown::BlockElement {
                     field1, serializer,
                 );
             }
-            crate::api::markdown::BlockElement::Table { head, rows } => {
+            crate::api::markdown::BlockElement::Table {
+                head,
+                rows,
+                alignments,
+            } => {
                 <i32>::sse_encode(5, serializer);
                 <Vec<Vec<crate::api::markdown::RangedBlockElement>>>::sse_encode(head, serializer);
                 <Vec<Vec<Vec<crate::api::markdown::RangedBlockElement>>>>::sse_encode(
                     rows, serializer,
                 );
+                <Vec<crate::api::markdown::ColumnAlignment>>::sse_encode(alignments, serializer);
             }
             crate::api::markdown::BlockElement::HorizontalRule => {
                 <i32>::sse_encode(6, serializer);
@@ -16362,6 +16933,9 @@ impl SseEncode for crate::api::markdown::BlockElement {
                 <i32>::sse_encode(8, serializer);
                 <String>::sse_encode(field0, serializer);
             }
+            crate::api::markdown::BlockElement::Truncated => {
+                <i32>::sse_encode(9, serializer);
+            }
             _ => {
                 unimplemented!("");
             }
@@ -16405,6 +16979,24 @@ impl SseEncode for crate::api::chat_list_cubit::ChatListState {
     }
 }
 
+impl SseEncode for crate::api::markdown::ColumnAlignment {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(
+            match self {
+                crate::api::markdown::ColumnAlignment::None => 0,
+                crate::api::markdown::ColumnAlignment::Left => 1,
+                crate::api::markdown::ColumnAlignment::Center => 2,
+                crate::api::markdown::ColumnAlignment::Right => 3,
+                _ => {
+                    unimplemented!("");
+                }
+            },
+            serializer,
+        );
+    }
+}
+
 impl SseEncode for crate::api::chat_details_cubit::DebugCapabilities {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
@@ -16463,6 +17055,32 @@ impl SseEncode for crate::api::chat_details_cubit::EncryptedGroupTitleDebugInfo
     }
 }
 
+impl SseEncode for crate::api::markdown::Error {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(
+            match self {
+                crate::api::markdown::Error::ExpectedMoreEvents => 0,
+                crate::api::markdown::Error::ExpectedSpecificTag => 1,
+                crate::api::markdown::Error::TableContentNotInTable => 2,
+                crate::api::markdown::Error::ListItemNotInList => 3,
+                crate::api::markdown::Error::MetadataBlocksNotSupported => 4,
+                crate::api::markdown::Error::FootnotesNotSupported => 5,
+                crate::api::markdown::Error::DefinitionListsNotSupported => 6,
+                crate::api::markdown::Error::BlockElementInline => 7,
+                crate::api::markdown::Error::HtmlNotInBlock => 8,
+                crate::api::markdown::Error::MathNotSupported => 9,
+                crate::api::markdown::Error::DepthLimitReached => 10,
+                crate::api::markdown::Error::InvalidUtf8 => 11,
+                _ => {
+                    unimplemented!("");
+                }
+            },
+            serializer,
+        );
+    }
+}
+
 impl SseEncode for crate::api::chat_details_cubit::ExternalGroupProfileDebugInfo {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
@@ -16730,6 +17348,16 @@ impl SseEncode for Vec<crate::api::types::ChatId> {
     }
 }
 
+impl SseEncode for Vec<crate::api::markdown::ColumnAlignment> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(self.len() as _, serializer);
+        for item in self {
+            <crate::api::markdown::ColumnAlignment>::sse_encode(item, serializer);
+        }
+    }
+}
+
 impl SseEncode for Vec<crate::api::navigation_cubit::IntroScreenType> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
@@ -16969,6 +17597,15 @@ impl SseEncode for crate::api::logging::LogEntryLevel {
     }
 }
 
+impl SseEncode for crate::api::markdown::MarkdownFeatures {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <bool>::sse_encode(self.strikethrough, serializer);
+        <bool>::sse_encode(self.tables, serializer);
+        <bool>::sse_encode(self.task_lists, serializer);
+    }
+}
+
 impl SseEncode for crate::api::member_details_cubit::MemberDetailsState {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
@@ -17169,6 +17806,7 @@ impl SseEncode for crate::notifications::NotificationContent {
         <String>::sse_encode(self.title, serializer);
         <String>::sse_encode(self.body, serializer);
         <crate::api::types::ChatId>::sse_encode(self.chat_id, serializer);
+        <u32>::sse_encode(self.new_messages, serializer);
     }
 }
 
@@ -18053,6 +18691,22 @@ impl SseEncode for crate::api::invitation_codes_cubit::UiInvitationCode {
     }
 }
 
+impl SseEncode for crate::api::types::UiMemberRole {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(
+            match self {
+                crate::api::types::UiMemberRole::Regular => 0,
+                crate::api::types::UiMemberRole::Privileged => 1,
+                _ => {
+                    unimplemented!("");
+                }
+            },
+            serializer,
+        );
+    }
+}
+
 impl SseEncode for crate::api::types::UiMessage {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
@@ -18198,6 +18852,16 @@ impl SseEncode for crate::api::types::UiSystemMessage {
                 <i32>::sse_encode(10, serializer);
                 <crate::api::types::UiUserId>::sse_encode(field0, serializer);
             }
+            crate::api::types::UiSystemMessage::ChangeRole {
+                sender,
+                target,
+                role,
+            } => {
+                <i32>::sse_encode(11, serializer);
+                <crate::api::types::UiUserId>::sse_encode(sender, serializer);
+                <crate::api::types::UiUserId>::sse_encode(target, serializer);
+                <crate::api::types::UiMemberRole>::sse_encode(role, serializer);
+            }
             _ => {
                 unimplemented!("");
             }
@@ -18246,6 +18910,9 @@ impl SseEncode for crate::api::chat_details_cubit::UploadAttachmentError {
                 <u64>::sse_encode(max_size_bytes, serializer);
                 <u64>::sse_encode(actual_size_bytes, serializer);
             }
+            crate::api::chat_details_cubit::UploadAttachmentError::UnsupportedImageFormat => {
+                <i32>::sse_encode(1, serializer);
+            }
             _ => {
                 unimplemented!("");
             }
@@ -11,43 +11,170 @@ use std::{
 };
 
 use anyhow::Context;
-use tracing::{info, level_filters::LevelFilter};
+use tracing::{Subscriber, info, level_filters::LevelFilter};
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{EnvFilter, registry};
-use tracing_subscriber::{fmt, layer::SubscriberExt};
+use tracing_subscriber::{EnvFilter, Layer, Registry, registry};
+use tracing_subscriber::{
+    fmt::{self, MakeWriter},
+    layer::SubscriberExt,
+    registry::LookupSpan,
+    reload,
+};
 
-use crate::util::{FileRingBuffer, FileRingBufferLock};
+use crate::util::{FileRingBuffer, FileRingBufferLock, LogRotation};
 
 pub(crate) const LOG_FILE_RING_BUFFER_SIZE: usize = 4 * 1024 * 1024; // 4 MiB
 
 pub(crate) static LOG_FILE_RING_BUFFER: OnceLock<Arc<FileRingBufferLock>> = OnceLock::new();
 
+type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Handle for changing the filter of the global logger at runtime
+static LOG_FILTER: OnceLock<LogFilterHandle> = OnceLock::new();
+
+/// Environment variable selecting the [`LogFormat`]; set it to `json` for JSON output.
+pub const LOG_FORMAT_ENV: &str = "AIR_LOG_FORMAT";
+
+/// Output format of log lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line including the fields of the current span and its parents, e.g.
+    /// for shipping logs to an aggregator
+    Json,
+}
+
+impl LogFormat {
+    /// Reads the format from the [`LOG_FORMAT_ENV`] environment variable.
+    ///
+    /// Falls back to [`LogFormat::Text`] if the variable is not set or has an unknown value.
+    pub fn from_env() -> Self {
+        Self::parse(std::env::var(LOG_FORMAT_ENV).ok().as_deref())
+    }
+
+    fn parse(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            Some(value) if value.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// Configuration of the logger
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoggerConfig {
+    pub format: LogFormat,
+    /// Number of compressed log segments kept next to the log file
+    ///
+    /// If `0`, the oldest logs in the log file are overwritten when it is full. Otherwise, the full
+    /// log file is compressed into a new segment. This is meant for desktop platforms; mobile
+    /// platforms should keep the fixed-size log file.
+    pub rotated_segments: usize,
+}
+
+impl LoggerConfig {
+    /// Default configuration with the log format read from the environment
+    pub fn from_env() -> Self {
+        Self {
+            format: LogFormat::from_env(),
+            ..Default::default()
+        }
+    }
+}
+
 pub fn init_logger(log_file: impl AsRef<Path>) -> Arc<FileRingBufferLock> {
+    init_logger_with_config(log_file, LoggerConfig::from_env())
+}
+
+pub fn init_logger_with_config(
+    log_file: impl AsRef<Path>,
+    config: LoggerConfig,
+) -> Arc<FileRingBufferLock> {
     let buffer_path = log_file.as_ref();
-    let buffer = LOG_FILE_RING_BUFFER
-        .get_or_init(|| init_app_log(buffer_path).expect("failed to init log file"));
+    let buffer = LOG_FILE_RING_BUFFER.get_or_init(|| {
+        init_app_log(buffer_path, config.rotated_segments).expect("failed to init log file")
+    });
 
-    do_init_logger(buffer.clone());
-    info!(log_file =% buffer_path.display(), "Rust logging initialized");
+    do_init_logger(buffer.clone(), config.format);
+    info!(log_file =% buffer_path.display(), ?config, "Rust logging initialized");
 
     buffer.clone()
 }
 
-fn do_init_logger(log_file: Arc<FileRingBufferLock>) {
+/// Changes the log level of all log outputs at runtime.
+///
+/// Replaces the filter directives set at startup (e.g. via `RUST_LOG`).
+pub fn set_log_level(level: LevelFilter) -> anyhow::Result<()> {
+    let filter = EnvFilter::builder()
+        .with_default_directive(level.into())
+        .parse("")?;
+    reload_log_filter(log_filter_handle()?, filter)?;
+    info!(%level, "Log level changed");
+    Ok(())
+}
+
+/// Changes the log filter of all log outputs at runtime to the given directives, e.g.
+/// `info,aircoreclient=debug`.
+///
+/// Invalid directives are rejected, and the current filter is kept.
+pub fn set_log_directives(directives: &str) -> anyhow::Result<()> {
+    let filter = parse_log_directives(directives)?;
+    reload_log_filter(log_filter_handle()?, filter)?;
+    info!(directives, "Log filter changed");
+    Ok(())
+}
+
+fn log_filter_handle() -> anyhow::Result<&'static LogFilterHandle> {
+    LOG_FILTER.get().context("logging is not initialized")
+}
+
+fn parse_log_directives(directives: &str) -> anyhow::Result<EnvFilter> {
+    EnvFilter::builder()
+        .parse(directives)
+        .with_context(|| format!("invalid log directives: {directives}"))
+}
+
+fn reload_log_filter(handle: &LogFilterHandle, filter: EnvFilter) -> anyhow::Result<()> {
+    handle.reload(filter).context("failed to reload log filter")
+}
+
+/// Formatting layer writing log lines in the given format
+fn fmt_layer<S, W>(format: LogFormat, writer: W) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Text => fmt::Layer::new().with_writer(writer).boxed(),
+        LogFormat::Json => fmt::Layer::new()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_writer(writer)
+            .boxed(),
+    }
+}
+
+fn do_init_logger(log_file: Arc<FileRingBufferLock>, format: LogFormat) {
     let env_filter = EnvFilter::builder()
         .with_default_directive(LevelFilter::INFO.into())
         .from_env_lossy();
 
+    let (env_filter, filter_handle) = reload::Layer::new(env_filter);
     let registry = registry().with(env_filter);
 
     #[cfg(any(target_os = "android", target_os = "ios"))]
     {
         if let Err(error) = registry
             .with(dart::layer())
-            .with(fmt::Layer::new().with_writer(log_file))
+            .with(fmt_layer(format, log_file))
             .try_init()
         {
             tracing::warn!(%error, "skip logger init; already initialized");
+        } else {
+            let _ = LOG_FILTER.set(filter_handle);
         }
     }
 
@@ -55,10 +182,12 @@ fn do_init_logger(log_file: Arc<FileRingBufferLock>) {
     {
         use fmt::writer::MakeWriterExt;
         if let Err(error) = registry
-            .with(fmt::Layer::new().map_writer(|w| w.and(log_file)))
+            .with(fmt_layer(format, std::io::stdout.and(log_file)))
             .try_init()
         {
             tracing::warn!(%error, "skip logger init; already initialized");
+        } else {
+            let _ = LOG_FILTER.set(filter_handle);
         }
     }
 
@@ -74,7 +203,10 @@ fn do_init_logger(log_file: Arc<FileRingBufferLock>) {
     }
 }
 
-fn init_app_log(file_path: impl AsRef<Path>) -> anyhow::Result<Arc<FileRingBufferLock>> {
+fn init_app_log(
+    file_path: impl AsRef<Path>,
+    rotated_segments: usize,
+) -> anyhow::Result<Arc<FileRingBufferLock>> {
     let file_path = file_path.as_ref();
     if let Some(parent) = file_path.parent() {
         std::fs::create_dir_all(parent)?;
@@ -83,5 +215,106 @@ fn init_app_log(file_path: impl AsRef<Path>) -> anyhow::Result<Arc<FileRingBuffe
     let buffer = FileRingBuffer::open(file_path, LOG_FILE_RING_BUFFER_SIZE)
         .with_context(|| format!("failed to open log file at {}", file_path.display()))?;
 
-    Ok(Arc::new(FileRingBufferLock::new(buffer)))
+    if rotated_segments > 0 {
+        let rotation = LogRotation::new(file_path, rotated_segments);
+        let buffer = FileRingBufferLock::with_rotation(buffer, rotation)
+            .context("failed to spawn log rotation thread")?;
+        Ok(Arc::new(buffer))
+    } else {
+        Ok(Arc::new(FileRingBufferLock::new(buffer)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use bytes::Buf;
+    use tracing::{debug, info_span};
+
+    use super::*;
+
+    fn test_buffer() -> Arc<FileRingBufferLock> {
+        Arc::new(FileRingBufferLock::new(
+            FileRingBuffer::anon(64 * 1024).unwrap(),
+        ))
+    }
+
+    fn read_buffer(buffer: &FileRingBufferLock) -> String {
+        let mut out = Vec::new();
+        buffer.lock().buf().reader().read_to_end(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn log_with_format(format: LogFormat) -> String {
+        let buffer = test_buffer();
+        let subscriber = registry().with(fmt_layer(format, buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = info_span!("sync", chat_id = 42).entered();
+            info!(count = 3, "Processed messages");
+        });
+        read_buffer(&buffer)
+    }
+
+    #[test]
+    fn parse_log_format() {
+        assert_eq!(LogFormat::parse(None), LogFormat::Text);
+        assert_eq!(LogFormat::parse(Some("json")), LogFormat::Json);
+        assert_eq!(LogFormat::parse(Some(" JSON ")), LogFormat::Json);
+        assert_eq!(LogFormat::parse(Some("text")), LogFormat::Text);
+        assert_eq!(LogFormat::parse(Some("yaml")), LogFormat::Text);
+    }
+
+    #[test]
+    fn text_log_format() {
+        let log = log_with_format(LogFormat::Text);
+        assert!(log.contains("Processed messages"), "{log}");
+        // Span names and fields might be styled with ANSI escape codes
+        assert!(log.contains("sync"), "{log}");
+        assert!(log.contains("chat_id"), "{log}");
+        assert!(serde_json::from_str::<serde_json::Value>(log.trim()).is_err());
+    }
+
+    #[test]
+    fn json_log_format() {
+        let log = log_with_format(LogFormat::Json);
+        let mut lines = log.lines();
+        let line: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert_eq!(lines.next(), None);
+
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["fields"]["message"], "Processed messages");
+        assert_eq!(line["fields"]["count"], 3);
+        assert_eq!(line["span"]["name"], "sync");
+        assert_eq!(line["span"]["chat_id"], 42);
+        assert_eq!(line["spans"][0]["chat_id"], 42);
+    }
+
+    #[test]
+    fn reload_log_level() {
+        let buffer = test_buffer();
+        let (filter, handle) = reload::Layer::new(parse_log_directives("info").unwrap());
+        let subscriber = registry()
+            .with(filter)
+            .with(fmt_layer(LogFormat::Json, buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            debug!("hidden");
+            reload_log_filter(&handle, parse_log_directives("debug").unwrap()).unwrap();
+            debug!("verbose");
+
+            // An invalid directive keeps the current filter
+            assert!(parse_log_directives("debug,aircoreclient=loud").is_err());
+            debug!("still verbose");
+        });
+
+        let log = read_buffer(&buffer);
+        let messages: Vec<String> = log
+            .lines()
+            .map(|line| {
+                let line: serde_json::Value = serde_json::from_str(line).unwrap();
+                line["fields"]["message"].as_str().unwrap().to_owned()
+            })
+            .collect();
+        assert_eq!(messages, ["verbose", "still verbose"]);
+    }
 }
@@ -23,10 +23,17 @@ pub(crate) trait MarkAsReadService {
         until: MessageId,
     ) -> anyhow::Result<(bool, Vec<(MessageId, MimiId)>)>;
 
+    fn is_chat_focused(&self, chat_id: ChatId) -> bool;
+
+    /// Enqueues read receipts for the given messages.
+    ///
+    /// If the chat was not `focused` when the messages were read, the receipts are deferred until
+    /// the chat is focused.
     async fn enqueue_read_receipts(
         &self,
         chat_id: ChatId,
         statuses: Vec<(MessageId, MimiId)>,
+        focused: bool,
     ) -> anyhow::Result<()>;
 
     async fn message_ordering(&self, a: MessageId, b: MessageId) -> anyhow::Result<Ordering>;
@@ -78,18 +85,27 @@ impl MarkAsReadService for MarkAsRead<'_> {
         Ok((marked, ids))
     }
 
+    fn is_chat_focused(&self, chat_id: ChatId) -> bool {
+        self.core_user.outbound_service().is_chat_focused(chat_id)
+    }
+
     async fn enqueue_read_receipts(
         &self,
         chat_id: ChatId,
         statuses: Vec<(MessageId, MimiId)>,
+        focused: bool,
     ) -> anyhow::Result<()> {
-        let statuses = statuses
-            .iter()
-            .map(|(id, mimi_id)| (*id, mimi_id, MessageStatus::Read));
-        self.core_user
-            .outbound_service()
-            .enqueue_receipts(chat_id, statuses)
-            .await
+        let outbound_service = self.core_user.outbound_service();
+        if focused {
+            let statuses = statuses
+                .iter()
+                .map(|(id, mimi_id)| (*id, mimi_id, MessageStatus::Read));
+            outbound_service.enqueue_receipts(chat_id, statuses).await
+        } else {
+            outbound_service
+                .enqueue_read_receipts(chat_id, statuses)
+                .await
+        }
     }
 
     async fn message_ordering(&self, a: MessageId, b: MessageId) -> anyhow::Result<Ordering> {
@@ -175,6 +191,9 @@ pub(crate) async fn mark_as_read(
         return Ok(());
     }
 
+    // The messages are read now; the chat might be left before the debounce expires.
+    let focused = service.is_chat_focused(chat_id);
+
     // debounce
     let mut rx = mark_as_read_tx.subscribe();
     tokio::select! {
@@ -204,7 +223,7 @@ pub(crate) async fn mark_as_read(
     let read_receipts_enabled = user_settings_rx.borrow().read_receipts;
     if read_receipts_enabled
         && let Err(error) = service
-            .enqueue_read_receipts(chat_id, read_message_ids)
+            .enqueue_read_receipts(chat_id, read_message_ids, focused)
             .await
     {
         error!(%error, "Failed to enqueue read receipt");
@@ -252,6 +271,12 @@ mod test {
         let mimi_id = MimiId::from_slice(&[0; 32]).unwrap();
 
         // Mark as read and enqueue receipts
+        service
+            .expect_is_chat_focused()
+            .withf(move |cid| *cid == chat_id)
+            .returning(|_| true)
+            .times(1);
+
         service
             .expect_mark_chat_as_read()
             .withf(move |cid, mid| *cid == chat_id && *mid == until_message_id)
@@ -260,8 +285,10 @@ mod test {
 
         service
             .expect_enqueue_read_receipts()
-            .withf(move |cid, mids| *cid == chat_id && mids == &[(until_message_id, mimi_id)])
-            .returning(|_, _| Ok(()))
+            .withf(move |cid, mids, focused| {
+                *cid == chat_id && mids == &[(until_message_id, mimi_id)] && *focused
+            })
+            .returning(|_, _, _| Ok(()))
             .times(1);
 
         mark_as_read(
@@ -286,6 +313,12 @@ mod test {
         });
         user_settings_tx.send_modify(|settings| settings.read_receipts = false);
 
+        service
+            .expect_is_chat_focused()
+            .withf(move |cid| *cid == chat_id)
+            .returning(|_| true)
+            .times(1);
+
         service
             .expect_mark_chat_as_read()
             .withf(move |cid, mid| *cid == chat_id && *mid == until_message_id)
@@ -356,6 +389,12 @@ mod test {
             .times(1);
 
         // Mark as read called with until_message_id
+        service
+            .expect_is_chat_focused()
+            .withf(move |cid| *cid == chat_id)
+            .returning(|_| true)
+            .times(1);
+
         service
             .expect_mark_chat_as_read()
             .withf(move |cid, mid| *cid == chat_id && *mid == until_message_id)
@@ -451,6 +490,12 @@ mod test {
         let mimi_id = MimiId::from_slice(&[0; 32]).unwrap();
 
         // Should schedule and complete even though state was NotLoaded
+        service
+            .expect_is_chat_focused()
+            .withf(move |cid| *cid == chat_id)
+            .returning(|_| true)
+            .times(1);
+
         service
             .expect_mark_chat_as_read()
             .withf(move |cid, mid| *cid == chat_id && *mid == until_message_id)
@@ -61,10 +61,11 @@ impl From<MimiContent> for UnresolvedMimiContent {
                 parts,
                 ..
             } => {
-                let Some(attachment) = convert_attachment(parts) else {
+                let attachments = convert_attachments(parts);
+                if attachments.is_empty() {
                     return res.error_message("Unsupported attachment message");
-                };
-                res.attachments = vec![attachment];
+                }
+                res.attachments = attachments;
             }
 
             // single part message
@@ -92,6 +93,32 @@ impl From<MimiContent> for UnresolvedMimiContent {
     }
 }
 
+/// Converts the parts of an attachment message.
+///
+/// A message with multiple attachments contains one attachment multipart per attachment.
+fn convert_attachments(parts: Vec<NestedPart>) -> Vec<UnresolvedAttachment> {
+    let is_multi_attachment = parts.iter().all(|part| {
+        matches!(
+            part,
+            NestedPart::MultiPart {
+                disposition: Disposition::Attachment,
+                part_semantics: PartSemantics::ProcessAll,
+                ..
+            }
+        )
+    });
+    if !is_multi_attachment {
+        return convert_attachment(parts).into_iter().collect();
+    }
+    parts
+        .into_iter()
+        .filter_map(|part| match part {
+            NestedPart::MultiPart { parts, .. } => convert_attachment(parts),
+            _ => None,
+        })
+        .collect()
+}
+
 fn convert_attachment(parts: Vec<NestedPart>) -> Option<UnresolvedAttachment> {
     let mut attachment: Option<UnresolvedAttachment> = None;
     let mut blurhash: Option<String> = None;
@@ -151,6 +178,13 @@ fn convert_attachment(parts: Vec<NestedPart>) -> Option<UnresolvedAttachment> {
                 blurhash = Some(content);
             }
 
+            // thumbnail preview is loaded from the attachments repository
+            NestedPart::SinglePart {
+                disposition: Disposition::Preview,
+                content_type,
+                ..
+            } if content_type == "image/webp" => (),
+
             // other parts
             part => {
                 warn!(
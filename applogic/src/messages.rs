@@ -2,13 +2,11 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use aircommon::messages::QueueMessage;
 use aircoreclient::{
     ChatId,
-    clients::{ListenQueueError, listen_response, process::process_qs::ProcessedQsMessages},
+    clients::{FetchedQsMessages, ListenQueueError, process::process_qs::ProcessedQsMessages},
 };
 use anyhow::Result;
-use tokio_stream::StreamExt;
 use tracing::{debug, error};
 
 use crate::{api::user::User, notifications::NotificationContent};
@@ -20,24 +18,20 @@ pub(crate) struct ProcessedMessages {
 
 impl User {
     /// Fetch and process AS messages
+    ///
+    /// Username queues which could not be listened to are skipped; they are logged by the core
+    /// client.
     async fn fetch_and_process_as_messages(&self) -> Result<Vec<ChatId>> {
-        self.user.fetch_and_process_username_messages().await
+        let processed = self.user.fetch_and_process_username_messages().await?;
+        Ok(processed.chat_ids)
     }
 
     /// Fetch and process QS messages
     async fn fetch_and_process_qs_messages(&self) -> Result<ProcessedQsMessages, ListenQueueError> {
-        let (stream, responder) = self.user.listen_queue().await?;
-        let mut stream = stream
-            .take_while(|message| !matches!(message.event, Some(listen_response::Event::Empty(_))))
-            .filter_map(|message| match message.event? {
-                listen_response::Event::Empty(_) => unreachable!(),
-                listen_response::Event::Message(queue_message) => queue_message.try_into().ok(),
-                listen_response::Event::Payload(_) => None,
-            });
-
-        let mut messages: Vec<QueueMessage> = Vec::new();
-        while let Some(message) = stream.next().await {
-            messages.push(message);
+        let (mut stream, responder) = self.user.listen_queue().await?;
+        let FetchedQsMessages { messages, invalid } = FetchedQsMessages::collect(&mut stream).await;
+        for error in invalid {
+            error!(%error, "dropping invalid QS message");
         }
 
         // Invariant: messages are sorted by sequence number
@@ -73,15 +67,7 @@ impl User {
 
         // Fetch QS messages
         debug!("fetch QS messages");
-        let ProcessedQsMessages {
-            new_chats,
-            changed_chats: _,
-            new_messages,
-            errors: _,
-            processed: _,
-            mut new_connections,
-            reaction_notifications,
-        } = Box::pin(self.fetch_and_process_qs_messages())
+        let processed = Box::pin(self.fetch_and_process_qs_messages())
             .await
             .map_err(|error| {
                 if error.is_unsupported_version() {
@@ -90,6 +76,16 @@ impl User {
                     FetchAndProcessAllMessagesError::Fatal(error.into())
                 }
             })?;
+        let new_messages = processed.group_by_chat();
+        let ProcessedQsMessages {
+            new_chats,
+            changed_chats: _,
+            new_messages: _,
+            errors: _,
+            processed: _,
+            mut new_connections,
+            reaction_notifications,
+        } = processed;
         self.new_chat_notifications(&new_chats, &mut notifications)
             .await;
         self.new_message_notifications(&new_messages, &mut notifications)
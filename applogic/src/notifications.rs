@@ -3,7 +3,8 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use aircoreclient::{
-    ChatId, ChatMessage, ChatType, clients::process::process_qs::ReactionNotification,
+    ChatId, ChatType,
+    clients::process::process_qs::{ChatNotificationSummary, ReactionNotification},
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -11,14 +12,14 @@ use uuid::Uuid;
 use crate::api::{notifications::DartNotificationService, user::User};
 
 impl User {
-    /// Send notifications for new messages.
+    /// Send notifications for new messages, one per chat.
     pub(crate) async fn new_message_notifications(
         &self,
-        messages: &[ChatMessage],
+        summaries: &[ChatNotificationSummary],
         notifications: &mut Vec<NotificationContent>,
     ) {
-        for message in messages {
-            if let Some(chat) = self.user.chat(&message.chat_id()).await {
+        for summary in summaries {
+            if let Some(chat) = self.user.chat(&summary.chat_id).await {
                 if chat.is_muted() {
                     continue;
                 }
@@ -34,7 +35,8 @@ impl User {
                     ChatType::HandleConnection(handle) => handle.plaintext().to_owned(),
                     ChatType::Group(attrs) => attrs.title().to_owned(),
                 };
-                let Some(body) = message
+                let Some(preview) = summary
+                    .latest_message
                     .message()
                     .string_representation(&self.user, chat.chat_type(), true)
                     .await
@@ -44,8 +46,9 @@ impl User {
                 notifications.push(NotificationContent {
                     identifier: NotificationId::random(),
                     title,
-                    body,
+                    body: preview,
                     chat_id: chat.id(),
+                    new_messages: summary.new_messages.try_into().unwrap_or(u32::MAX),
                 });
             }
         }
@@ -92,6 +95,7 @@ impl User {
                     title,
                     body,
                     chat_id: chat.id(),
+                    new_messages: 0,
                 });
             }
         }
@@ -118,6 +122,7 @@ impl User {
                     title: title.to_owned(),
                     body: body.to_owned(),
                     chat_id: *chat_id,
+                    new_messages: 0,
                 });
             }
         }
@@ -153,6 +158,7 @@ impl User {
                     title,
                     body,
                     chat_id: *chat_id,
+                    new_messages: 0,
                 });
             }
         }
@@ -179,6 +185,25 @@ pub struct NotificationContent {
     pub title: String,
     pub body: String,
     pub chat_id: ChatId,
+    /// Number of new messages summarized by the notification
+    ///
+    /// The body only contains the preview of the latest message. The number is
+    /// prepended to the body when showing the notification.
+    #[serde(skip)]
+    pub new_messages: u32,
+}
+
+impl NotificationContent {
+    /// Prepends the number of new messages to the body, if it summarizes more than one message.
+    ///
+    /// The app prepends a localized text instead, so this is only used where the notification
+    /// is shown without the app.
+    pub(crate) fn with_summary_body(mut self) -> Self {
+        if self.new_messages > 1 {
+            self.body = format!("{} new messages: {}", self.new_messages, self.body);
+        }
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -211,6 +236,8 @@ impl NotificationService {
     pub(crate) async fn show_notification(&self, notification: NotificationContent) {
         #[cfg(any(target_os = "ios", target_os = "android", target_os = "macos"))]
         self.dart_service.send_notification(notification).await;
+        #[cfg(any(target_os = "windows", target_os = "linux"))]
+        let notification = notification.with_summary_body();
         #[cfg(target_os = "windows")]
         {
             if let Err(error) = notify_rust::Notification::new()
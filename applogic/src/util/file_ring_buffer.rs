@@ -6,7 +6,16 @@ use bytes::{Buf, BufMut};
 use memmap2::{MmapMut, MmapOptions};
 use parking_lot::Mutex;
 
-use std::{fs::OpenOptions, io, ops::DerefMut, path::Path};
+use std::{
+    fs::OpenOptions,
+    io,
+    ops::DerefMut,
+    path::Path,
+    sync::{Arc, mpsc},
+    thread::{self, JoinHandle},
+};
+
+use super::LogRotation;
 
 /// Append-only fixed-length ring buffer backed by a memory-mapped file.
 ///
@@ -110,6 +119,33 @@ impl FileRingBuffer {
         }
     }
 
+    /// Returns a copy of the valid data, oldest byte first.
+    ///
+    /// Same data as returned by [`Self::buf()`], e.g. for attaching the logs
+    /// to a bug report.
+    pub fn export_logs(&self) -> Vec<u8> {
+        let buf = self.buf();
+        let mut logs = Vec::with_capacity(buf.remaining());
+        logs.put(buf);
+        logs
+    }
+
+    /// Same as [`Self::export_logs()`], but as a string.
+    ///
+    /// Invalid UTF-8 sequences are replaced with the replacement character.
+    /// The oldest character is usually partially overwritten once the buffer
+    /// has wrapped.
+    pub fn export_logs_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.export_logs()).into_owned()
+    }
+
+    /// Returns `true` if writing `len` more bytes would overwrite previously
+    /// written data.
+    fn would_overwrite(&self, len: usize) -> bool {
+        let written = self.read_written();
+        written > 0 && written + len > self.len()
+    }
+
     /// Returns the current write position (`written % len`), where the next
     /// byte will be written.
     fn read_tail(&self) -> usize {
@@ -218,30 +254,126 @@ impl Buf for RingBufferReader<'_> {
 ///
 /// `Arc<FileRingBufferLock>` can be used as a writer for
 /// [`tracing_subscriber::fmt::Subscriber`].
+///
+/// With a [`LogRotation`], the buffer is rotated into a compressed segment
+/// instead of overwriting the oldest data. The full buffer is taken out while
+/// holding the lock and passed to a dedicated rotation thread, which
+/// compresses and writes the segments in order. So log calls are never
+/// blocked by compressing or syncing a segment.
 #[derive(Debug)]
 pub struct FileRingBufferLock {
     inner: Mutex<FileRingBuffer>,
+    rotator: Option<Rotator>,
+    /// Held by the rotation thread while writing a segment
+    rotating: Arc<Mutex<()>>,
+}
+
+/// Handle to the thread rotating the logs
+#[derive(Debug)]
+struct Rotator {
+    rotation: LogRotation,
+    tx: mpsc::Sender<Vec<u8>>,
+    thread: JoinHandle<()>,
 }
 
 impl FileRingBufferLock {
     pub fn new(buffer: FileRingBuffer) -> Self {
         Self {
             inner: Mutex::new(buffer),
+            rotator: None,
+            rotating: Default::default(),
         }
     }
 
+    /// Creates a buffer which is rotated with the given rotation.
+    ///
+    /// Spawns the thread which writes the rotated segments.
+    pub fn with_rotation(buffer: FileRingBuffer, rotation: LogRotation) -> io::Result<Self> {
+        let rotating: Arc<Mutex<()>> = Default::default();
+        let (tx, rx) = mpsc::channel::<Vec<u8>>();
+        let thread = thread::Builder::new()
+            .name("log-rotation".to_owned())
+            .spawn({
+                let rotation = rotation.clone();
+                let rotating = rotating.clone();
+                move || {
+                    for logs in rx {
+                        let _rotating = rotating.lock();
+                        // Note: If the rotation fails, the rotated logs are lost. We
+                        // cannot log the error here, because this would log into the
+                        // buffer which is being rotated.
+                        let _ = rotation.rotate(&logs);
+                    }
+                }
+            })?;
+        Ok(Self {
+            inner: Mutex::new(buffer),
+            rotator: Some(Rotator {
+                rotation,
+                tx,
+                thread,
+            }),
+            rotating,
+        })
+    }
+
+    pub fn rotation(&self) -> Option<&LogRotation> {
+        self.rotator.as_ref().map(|rotator| &rotator.rotation)
+    }
+
     pub fn lock(&self) -> impl DerefMut<Target = FileRingBuffer> + '_ {
         self.inner.lock()
     }
 
+    /// Prevents the rotation thread from changing the segments until the
+    /// returned guard is dropped.
+    ///
+    /// Writing to the buffer is not blocked: a buffer which becomes full in
+    /// the meantime is cleared, and its logs are written to a segment after
+    /// the guard is dropped.
+    pub fn lock_segments(&self) -> impl Sized + '_ {
+        self.rotating.lock()
+    }
+
+    /// Returns the buffer after all pending rotations are written.
     pub fn into_inner(self) -> FileRingBuffer {
+        if let Some(Rotator { tx, thread, .. }) = self.rotator {
+            // Ends the rotation thread after it has processed the pending logs
+            drop(tx);
+            let _ = thread.join();
+        }
         self.inner.into_inner()
     }
+
+    /// See [`FileRingBuffer::export_logs()`].
+    pub fn export_logs(&self) -> Vec<u8> {
+        self.lock().export_logs()
+    }
+
+    /// See [`FileRingBuffer::export_logs_lossy()`].
+    pub fn export_logs_lossy(&self) -> String {
+        self.lock().export_logs_lossy()
+    }
 }
 
 impl io::Write for &FileRingBufferLock {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.lock().write(buf)
+        let mut buffer = self.inner.lock();
+        let Some(rotator) = &self.rotator else {
+            return buffer.write(buf);
+        };
+        if !buffer.would_overwrite(buf.len()) {
+            return buffer.write(buf);
+        }
+
+        let logs = buffer.export_logs();
+        buffer.clear();
+        // Sent while holding the buffer, so the segments are written in the
+        // order the logs were written. Sending does not block.
+        //
+        // Note: If the rotation thread is gone, the rotated logs are lost.
+        let _ = rotator.tx.send(logs);
+        buffer.write(buf)
     }
 
     fn flush(&mut self) -> io::Result<()> {
@@ -461,4 +593,52 @@ mod tests {
         let _ = std::fs::remove_file(&path);
         Ok(())
     }
+
+    #[test]
+    fn export_logs_in_chronological_order() -> io::Result<()> {
+        let ring_buffer = FileRingBufferLock::new(FileRingBuffer::anon(8)?);
+        assert!(ring_buffer.export_logs().is_empty());
+
+        write!(&ring_buffer, "01234")?;
+        assert_eq!(ring_buffer.export_logs(), b"01234");
+
+        write!(&ring_buffer, "56789")?;
+        assert_eq!(ring_buffer.export_logs(), b"23456789");
+
+        Ok(())
+    }
+
+    #[test]
+    fn export_logs_lossy_replaces_overwritten_char() -> io::Result<()> {
+        let mut ring_buffer = FileRingBuffer::anon(6)?;
+        // The first byte of the 2 bytes long "ä" is overwritten
+        write!(ring_buffer, "äbc")?;
+        write!(ring_buffer, "def")?;
+        assert_eq!(ring_buffer.export_logs_lossy(), "\u{FFFD}bcdef");
+
+        Ok(())
+    }
+
+    #[test]
+    fn rotate_instead_of_overwrite() -> io::Result<()> {
+        let dir = temp_path("rotation");
+        std::fs::create_dir_all(&dir)?;
+        let rotation = LogRotation::new(dir.join("app.log"), 3);
+        let ring_buffer =
+            FileRingBufferLock::with_rotation(FileRingBuffer::anon(8)?, rotation.clone())?;
+
+        write!(&ring_buffer, "0123")?;
+        write!(&ring_buffer, "4567")?;
+        assert_eq!(ring_buffer.export_logs(), b"01234567");
+        assert!(rotation.segments().is_empty());
+
+        write!(&ring_buffer, "89")?;
+        assert_eq!(ring_buffer.export_logs(), b"89");
+
+        // Waits for the rotation thread
+        ring_buffer.into_inner();
+        assert_eq!(rotation.segments().len(), 1);
+
+        std::fs::remove_dir_all(dir)
+    }
 }
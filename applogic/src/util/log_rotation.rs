@@ -0,0 +1,120 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use flate2::{Compression, write::GzEncoder};
+
+/// Rotation of a full log ring buffer into compressed segments.
+///
+/// Instead of overwriting the oldest logs, the contents of the full buffer are
+/// compressed into a segment next to the live log file, and the buffer is
+/// cleared. The segments are named `<log_file>.<n>.gz` where `1` is the newest
+/// segment. At most `max_segments` segments are kept; older ones are deleted.
+///
+/// A segment is first written to a temporary file and then renamed, so a
+/// segment is either complete or does not exist.
+#[derive(Debug, Clone)]
+pub struct LogRotation {
+    log_file: PathBuf,
+    max_segments: usize,
+}
+
+impl LogRotation {
+    pub fn new(log_file: impl Into<PathBuf>, max_segments: usize) -> Self {
+        Self {
+            log_file: log_file.into(),
+            max_segments,
+        }
+    }
+
+    /// Returns the paths of the existing segments, newest first.
+    pub fn segments(&self) -> Vec<PathBuf> {
+        (1..=self.max_segments)
+            .map(|n| self.segment_path(n))
+            .filter(|path| path.exists())
+            .collect()
+    }
+
+    /// Compresses the given logs into the newest segment, and shifts the
+    /// existing segments.
+    pub(super) fn rotate(&self, logs: &[u8]) -> io::Result<()> {
+        if self.max_segments == 0 {
+            return Ok(());
+        }
+
+        let tmp_path = self.path_with_suffix(".tmp.gz");
+        write_compressed(&tmp_path, logs)?;
+
+        // Shifting renames onto the oldest segment, which replaces it
+        for n in (1..self.max_segments).rev() {
+            let path = self.segment_path(n);
+            if path.exists() {
+                fs::rename(path, self.segment_path(n + 1))?;
+            }
+        }
+        fs::rename(tmp_path, self.segment_path(1))
+    }
+
+    fn segment_path(&self, n: usize) -> PathBuf {
+        self.path_with_suffix(&format!(".{n}.gz"))
+    }
+
+    fn path_with_suffix(&self, suffix: &str) -> PathBuf {
+        let mut path = OsString::from(self.log_file.as_os_str());
+        path.push(suffix);
+        path.into()
+    }
+}
+
+fn write_compressed(path: &Path, data: &[u8]) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()?.into_inner()?.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+
+    use super::*;
+
+    fn read_compressed(path: &Path) -> io::Result<String> {
+        let mut content = String::new();
+        GzDecoder::new(File::open(path)?).read_to_string(&mut content)?;
+        Ok(content)
+    }
+
+    #[test]
+    fn rotate_keeps_newest_segments() -> io::Result<()> {
+        let dir =
+            std::env::temp_dir().join(format!("air_log_rotation_test_{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let rotation = LogRotation::new(dir.join("app.log"), 2);
+
+        rotation.rotate(b"first")?;
+        rotation.rotate(b"second")?;
+        rotation.rotate(b"third")?;
+
+        let segments = rotation.segments();
+        assert_eq!(
+            segments,
+            [dir.join("app.log.1.gz"), dir.join("app.log.2.gz")]
+        );
+        assert_eq!(read_compressed(&segments[0])?, "third");
+        assert_eq!(read_compressed(&segments[1])?, "second");
+        assert!(!dir.join("app.log.3.gz").exists());
+        assert!(!dir.join("app.log.tmp.gz").exists());
+
+        fs::remove_dir_all(dir)
+    }
+}
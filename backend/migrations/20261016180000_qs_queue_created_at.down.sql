@@ -0,0 +1,8 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+DROP INDEX qs_queues_created_at_idx;
+
+ALTER TABLE qs_queues
+DROP COLUMN created_at;
@@ -0,0 +1,12 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+ALTER TABLE qs_client_record
+ADD COLUMN encrypted_push_token aead_ciphertext;
+
+UPDATE qs_client_record
+SET encrypted_push_token = encrypted_push_tokens[1];
+
+ALTER TABLE qs_client_record
+DROP COLUMN encrypted_push_tokens;
@@ -0,0 +1,13 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+ALTER TABLE qs_client_record
+ADD COLUMN encrypted_push_tokens aead_ciphertext[] NOT NULL DEFAULT '{}';
+
+UPDATE qs_client_record
+SET encrypted_push_tokens = ARRAY[encrypted_push_token]
+WHERE encrypted_push_token IS NOT NULL;
+
+ALTER TABLE qs_client_record
+DROP COLUMN encrypted_push_token;
@@ -0,0 +1,8 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+DROP INDEX IF EXISTS idx_invitation_code_expires_at;
+
+ALTER TABLE invitation_code
+DROP COLUMN expires_at;
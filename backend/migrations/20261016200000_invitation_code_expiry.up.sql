@@ -0,0 +1,9 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+ALTER TABLE invitation_code
+ADD COLUMN expires_at timestamptz;
+
+CREATE INDEX idx_invitation_code_expires_at ON invitation_code (expires_at)
+WHERE redeemed = FALSE;
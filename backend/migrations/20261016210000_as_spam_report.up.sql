@@ -0,0 +1,23 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+-- Each reporter counts at most once per reported user; reporting again only refreshes the time.
+CREATE TABLE as_spam_report (
+    spammer_uuid uuid NOT NULL,
+    spammer_domain TEXT NOT NULL,
+    reporter_uuid uuid NOT NULL,
+    reporter_domain TEXT NOT NULL,
+    reported_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (spammer_uuid, spammer_domain, reporter_uuid, reporter_domain),
+    FOREIGN KEY (reporter_uuid, reporter_domain) REFERENCES as_user_record (user_uuid, user_domain) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_as_spam_report_reported_at ON as_spam_report (reported_at);
+
+CREATE TABLE as_flagged_user (
+    user_uuid uuid NOT NULL,
+    user_domain TEXT NOT NULL,
+    flagged_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_uuid, user_domain)
+);
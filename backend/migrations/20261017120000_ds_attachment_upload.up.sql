@@ -0,0 +1,15 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+-- Multipart attachment uploads together with the group member who provisioned
+-- them. Completed uploads are kept, such that completing an upload is
+-- idempotent.
+CREATE TABLE ds_attachment_upload (
+    object_id      UUID    PRIMARY KEY,
+    group_id       UUID    NOT NULL REFERENCES encrypted_group (group_id) ON DELETE CASCADE,
+    sender_index   BIGINT  NOT NULL,
+    upload_id      TEXT    NOT NULL,
+    content_length BIGINT  NOT NULL,
+    completed      BOOLEAN NOT NULL DEFAULT FALSE
+);
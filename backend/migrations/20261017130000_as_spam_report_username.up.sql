@@ -0,0 +1,17 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+
+-- Usernames of a reported user, as known to the reporter. The AS can't link usernames to users, so
+-- they are only known from reports.
+CREATE TABLE as_spam_report_username (
+    hash BYTEA NOT NULL,
+    spammer_uuid uuid NOT NULL,
+    spammer_domain TEXT NOT NULL,
+    reporter_uuid uuid NOT NULL,
+    reporter_domain TEXT NOT NULL,
+    PRIMARY KEY (hash, spammer_uuid, spammer_domain, reporter_uuid, reporter_domain),
+    FOREIGN KEY (spammer_uuid, spammer_domain, reporter_uuid, reporter_domain)
+        REFERENCES as_spam_report (spammer_uuid, spammer_domain, reporter_uuid, reporter_domain)
+        ON DELETE CASCADE
+);
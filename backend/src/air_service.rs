@@ -31,6 +31,11 @@ impl<T: Into<sqlx::Error>> From<T> for ServiceCreationError {
     }
 }
 
+/// Checks that the database of a service is reachable.
+pub async fn ping_database(db_pool: &PgPool) -> sqlx::Result<()> {
+    db_pool.acquire().await?.ping().await
+}
+
 #[expect(async_fn_in_trait)]
 pub trait BackendService: Sized {
     async fn new(
@@ -33,6 +33,40 @@ impl AuthService {
         Ok(())
     }
 
+    /// Generates `n` invitation codes which can't be redeemed after `expires_at`.
+    pub async fn invitation_codes_generate_with_expiry(
+        &self,
+        n: usize,
+        expires_at: DateTime<Utc>,
+    ) -> sqlx::Result<()> {
+        let mut connection = self.db_pool().acquire().await?;
+        for _ in 0..n {
+            let code =
+                InvitationCodeRecord::generate_with_expiry(&mut connection, Some(expires_at))
+                    .await?;
+            println!("{code}");
+        }
+        Ok(())
+    }
+
+    /// Lists expired codes which were never redeemed together with their expiry.
+    pub async fn invitation_codes_list_expired(
+        &self,
+        limit: usize,
+    ) -> sqlx::Result<impl Iterator<Item = (String, DateTime<Utc>)>> {
+        let codes = InvitationCodeRecord::load_expired(&self.db_pool, Utc::now(), limit).await?;
+        Ok(codes
+            .into_iter()
+            .filter_map(|code| Some((code.code, code.expires_at?))))
+    }
+
+    /// Deletes expired codes which were never redeemed.
+    ///
+    /// Returns the number of deleted codes.
+    pub async fn invitation_codes_delete_expired(&self) -> sqlx::Result<u64> {
+        InvitationCodeRecord::delete_expired(&self.db_pool, Utc::now()).await
+    }
+
     pub async fn usernames_list(
         &self,
     ) -> sqlx::Result<impl Iterator<Item = ([u8; 32], ExpirationData)>> {
@@ -5,5 +5,6 @@
 pub mod anonymous;
 pub mod key_packages;
 pub mod privacy_pass;
+pub mod spam_report;
 pub mod user;
 pub mod user_profile;
@@ -0,0 +1,249 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use aircommon::identifiers::{UserId, UsernameHash};
+use chrono::{DateTime, Utc};
+use metrics::counter;
+use sqlx::PgExecutor;
+use tracing::info;
+
+use crate::{
+    auth_service::{
+        AuthService,
+        spam_report::{FlaggedUser, SpamReport},
+    },
+    errors::auth_service::ReportSpamError,
+};
+
+impl AuthService {
+    /// Records a spam report and flags the reported user if the report threshold is reached.
+    ///
+    /// `spammer_username_hashes` are the hashes of the usernames of the spammer known to the
+    /// reporter.
+    pub(crate) async fn as_report_spam(
+        &self,
+        reporter: &UserId,
+        spammer: &UserId,
+        spammer_username_hashes: &[UsernameHash],
+    ) -> Result<(), ReportSpamError> {
+        if reporter == spammer {
+            return Err(ReportSpamError::SelfReport);
+        }
+
+        let now = Utc::now();
+        let mut txn = self.db_pool.begin().await?;
+
+        SpamReport::store(txn.as_mut(), reporter, spammer, now).await?;
+        SpamReport::store_usernames(txn.as_mut(), reporter, spammer, spammer_username_hashes)
+            .await?;
+        counter!("air_spam_reports_total").increment(1);
+
+        let since = now - self.spam_report_policy.window;
+        let reports = SpamReport::count_since(txn.as_mut(), spammer, since).await?;
+        if reports >= self.spam_report_policy.threshold.into() {
+            let was_flagged = self.is_flagged_at(txn.as_mut(), spammer, now).await?;
+            FlaggedUser::flag(txn.as_mut(), spammer, now).await?;
+            if !was_flagged {
+                info!(reports, "Flagged user because of spam reports");
+                counter!("air_flagged_users_total").increment(1);
+            }
+        }
+
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns whether the user is flagged because of spam reports and the flag has not expired.
+    pub(crate) async fn as_is_flagged(&self, user_id: &UserId) -> sqlx::Result<bool> {
+        self.is_flagged_at(&self.db_pool, user_id, Utc::now()).await
+    }
+
+    async fn is_flagged_at(
+        &self,
+        executor: impl PgExecutor<'_>,
+        user_id: &UserId,
+        now: DateTime<Utc>,
+    ) -> sqlx::Result<bool> {
+        let since = now - self.spam_report_policy.flag_duration;
+        FlaggedUser::is_flagged(executor, user_id, since).await
+    }
+
+    /// Returns whether the username belongs to a flagged user, see [`FlaggedUser`].
+    pub(crate) async fn as_is_username_flagged(&self, hash: &UsernameHash) -> sqlx::Result<bool> {
+        let since = Utc::now() - self.spam_report_policy.flag_duration;
+        FlaggedUser::is_username_flagged(
+            &self.db_pool,
+            hash,
+            since,
+            self.spam_report_policy.threshold,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use sqlx::PgPool;
+    use tokio_util::sync::CancellationToken;
+
+    use crate::{
+        air_service::BackendService,
+        auth_service::{
+            spam_report::SpamReportPolicy,
+            user_record::persistence::tests::store_random_user_record,
+        },
+    };
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn flag_after_reaching_threshold(pool: PgPool) -> anyhow::Result<()> {
+        let mut service = AuthService::initialize(
+            pool.clone(),
+            "example.com".parse()?,
+            None,
+            CancellationToken::new(),
+        )
+        .await?;
+        service.spam_report_policy = SpamReportPolicy {
+            threshold: 3,
+            window: Duration::hours(1),
+            flag_duration: Duration::days(1),
+        };
+
+        let spammer = UserId::random("example.com".parse()?);
+        let mut reporters = Vec::new();
+        for _ in 0..3 {
+            reporters.push(store_random_user_record(&pool).await?.user_id().clone());
+        }
+
+        // Self reports are rejected
+        assert!(matches!(
+            service.as_report_spam(&spammer, &spammer, &[]).await,
+            Err(ReportSpamError::SelfReport)
+        ));
+
+        // Repeated reports by the same reporter count once
+        for _ in 0..3 {
+            service.as_report_spam(&reporters[0], &spammer, &[]).await?;
+        }
+        service.as_report_spam(&reporters[1], &spammer, &[]).await?;
+        assert!(!service.as_is_flagged(&spammer).await?);
+
+        // Crossing the threshold flags the user
+        service.as_report_spam(&reporters[2], &spammer, &[]).await?;
+        assert!(service.as_is_flagged(&spammer).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn flag_expires(pool: PgPool) -> anyhow::Result<()> {
+        let mut service = AuthService::initialize(
+            pool.clone(),
+            "example.com".parse()?,
+            None,
+            CancellationToken::new(),
+        )
+        .await?;
+        service.spam_report_policy = SpamReportPolicy {
+            threshold: 1,
+            window: Duration::hours(1),
+            flag_duration: Duration::days(1),
+        };
+
+        let spammer = UserId::random("example.com".parse()?);
+        let reporter = store_random_user_record(&pool).await?;
+
+        FlaggedUser::flag(&pool, &spammer, Utc::now() - Duration::days(2)).await?;
+        assert!(!service.as_is_flagged(&spammer).await?);
+
+        // Reporting the user again renews the flag
+        service
+            .as_report_spam(reporter.user_id(), &spammer, &[])
+            .await?;
+        assert!(service.as_is_flagged(&spammer).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn reports_outside_window_are_not_counted(pool: PgPool) -> anyhow::Result<()> {
+        let mut service = AuthService::initialize(
+            pool.clone(),
+            "example.com".parse()?,
+            None,
+            CancellationToken::new(),
+        )
+        .await?;
+        service.spam_report_policy = SpamReportPolicy {
+            threshold: 2,
+            window: Duration::hours(1),
+            flag_duration: Duration::days(1),
+        };
+
+        let spammer = UserId::random("example.com".parse()?);
+        let old_reporter = store_random_user_record(&pool).await?;
+        let reporter = store_random_user_record(&pool).await?;
+
+        SpamReport::store(
+            &pool,
+            old_reporter.user_id(),
+            &spammer,
+            Utc::now() - Duration::hours(2),
+        )
+        .await?;
+        service
+            .as_report_spam(reporter.user_id(), &spammer, &[])
+            .await?;
+        assert!(!service.as_is_flagged(&spammer).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn flag_reported_usernames(pool: PgPool) -> anyhow::Result<()> {
+        let mut service = AuthService::initialize(
+            pool.clone(),
+            "example.com".parse()?,
+            None,
+            CancellationToken::new(),
+        )
+        .await?;
+        service.spam_report_policy = SpamReportPolicy {
+            threshold: 2,
+            window: Duration::hours(1),
+            flag_duration: Duration::days(1),
+        };
+
+        let spammer = UserId::random("example.com".parse()?);
+        let reporters = [
+            store_random_user_record(&pool).await?,
+            store_random_user_record(&pool).await?,
+        ];
+        let username = UsernameHash::new([1; 32]);
+        let other_username = UsernameHash::new([2; 32]);
+
+        service
+            .as_report_spam(
+                reporters[0].user_id(),
+                &spammer,
+                &[username, other_username],
+            )
+            .await?;
+        assert!(!service.as_is_username_flagged(&username).await?);
+
+        // The username reported by all reporters of the flagged user is flagged
+        service
+            .as_report_spam(reporters[1].user_id(), &spammer, &[username])
+            .await?;
+        assert!(service.as_is_flagged(&spammer).await?);
+        assert!(service.as_is_username_flagged(&username).await?);
+        assert!(!service.as_is_username_flagged(&other_username).await?);
+
+        Ok(())
+    }
+}
@@ -10,6 +10,7 @@ use airprotos::{
     signed::{SignedRequest, VerifiableRequest},
     validation::MissingFieldExt,
 };
+use chrono::Utc;
 use displaydoc::Display;
 use futures_util::stream::BoxStream;
 use metrics::counter;
@@ -31,6 +32,7 @@ use aircommon::{
             StageUserProfileParamsTbs,
         },
     },
+    time::TimeStamp,
     utils::CancellableStream,
 };
 use privacypass::{
@@ -47,7 +49,7 @@ use tracing::{error, warn};
 
 use crate::{
     auth_service::{
-        invitation_code_record::{CODES_PER_DAY, InvitationCodeRecord},
+        invitation_code_record::{CODES_PER_DAY, InvitationCodeRecord, NotRedeemableReason},
         usernames::ConnectUsernameProtocol,
     },
     util::{find_cause, select_until_first_ends},
@@ -81,6 +83,29 @@ impl GrpcAs {
         Ok((user_id, payload))
     }
 
+    /// Like [`Self::verify_user_auth`], but rejects users which are flagged because of spam
+    /// reports.
+    ///
+    /// Used by all user-authenticated requests which reach other users.
+    async fn verify_unflagged_user_auth<R, P, const TAG: u32>(
+        &self,
+        request: SignedRequest<R, TAG>,
+    ) -> Result<(identifiers::UserId, P), Status>
+    where
+        R: WithUserId + VerifiableRequest,
+        P: VerifiedStruct<SignedRequest<R, TAG>>,
+    {
+        let (user_id, payload) = self.verify_user_auth(request).await?;
+        let flagged = self.inner.as_is_flagged(&user_id).await.map_err(|error| {
+            error!(%error, "failed to check whether user is flagged");
+            Status::internal("database error")
+        })?;
+        if flagged {
+            return Err(Status::permission_denied("user is flagged as spammer"));
+        }
+        Ok((user_id, payload))
+    }
+
     async fn load_client_verifying_key(
         &self,
         user_id: &identifiers::UserId,
@@ -217,7 +242,8 @@ impl auth_service_server::AuthService for GrpcAs {
                 Status::internal("database error")
             })?;
 
-        let is_valid = record.filter(|r| !r.redeemed).is_some();
+        let now = Utc::now();
+        let is_valid = record.is_some_and(|r| r.check_redeemable(now).is_ok());
 
         counter!(
             "air_invitation_codes_checked_total",
@@ -228,6 +254,15 @@ impl auth_service_server::AuthService for GrpcAs {
         Ok(Response::new(CheckInvitationCodeResponse { is_valid }))
     }
 
+    async fn server_time(
+        &self,
+        _request: Request<ServerTimeRequest>,
+    ) -> Result<Response<ServerTimeResponse>, Status> {
+        Ok(Response::new(ServerTimeResponse {
+            server_time: Some(TimeStamp::now().into()),
+        }))
+    }
+
     async fn get_invitation_codes(
         &self,
         request: Request<GetInvitationCodesRequest>,
@@ -321,6 +356,7 @@ impl auth_service_server::AuthService for GrpcAs {
                 Some(InvitationCodeRecord {
                     code: code.code,
                     redeemed: false,
+                    expires_at: None,
                 })
             } else {
                 InvitationCodeRecord::load(&self.inner.db_pool, &code.code)
@@ -329,11 +365,19 @@ impl auth_service_server::AuthService for GrpcAs {
                         error!(%error, "failed to load invitation code");
                         Status::internal("database error")
                     })?
-                    .filter(|r| !r.redeemed)
             };
             let Some(code_record) = code_record else {
                 return Err(Status::invalid_argument("invalid invitation code"));
             };
+            match code_record.check_redeemable(Utc::now()) {
+                Ok(()) => {}
+                Err(NotRedeemableReason::Redeemed) => {
+                    return Err(Status::invalid_argument("invalid invitation code"));
+                }
+                Err(NotRedeemableReason::Expired) => {
+                    return Err(Status::failed_precondition("invitation code expired"));
+                }
+            }
             Some(code_record)
         } else {
             None
@@ -450,7 +494,7 @@ impl auth_service_server::AuthService for GrpcAs {
     ) -> Result<Response<StageUserProfileResponse>, Status> {
         let request = request.into_inner();
         let (user_id, payload) = self
-            .verify_user_auth::<_, StageUserProfilePayload, _>(request)
+            .verify_unflagged_user_auth::<_, StageUserProfilePayload, _>(request)
             .await?;
         self.verify_client_version(payload.client_metadata.as_ref())?;
         let params = StageUserProfileParamsTbs {
@@ -470,7 +514,7 @@ impl auth_service_server::AuthService for GrpcAs {
     ) -> Result<Response<MergeUserProfileResponse>, Status> {
         let request = request.into_inner();
         let (user_id, payload) = self
-            .verify_user_auth::<_, MergeUserProfilePayload, _>(request)
+            .verify_unflagged_user_auth::<_, MergeUserProfilePayload, _>(request)
             .await?;
         self.verify_client_version(payload.client_metadata.as_ref())?;
         let params = MergeUserProfileParamsTbs { user_id };
@@ -503,7 +547,7 @@ impl auth_service_server::AuthService for GrpcAs {
     ) -> Result<Response<IssueTokensResponse>, Status> {
         let request = request.into_inner();
         let (user_id, payload) = self
-            .verify_user_auth::<_, IssueTokensPayload, _>(request)
+            .verify_unflagged_user_auth::<_, IssueTokensPayload, _>(request)
             .await?;
         self.verify_client_version(payload.client_metadata.as_ref())?;
 
@@ -531,12 +575,23 @@ impl auth_service_server::AuthService for GrpcAs {
         request: Request<SignedRequest<ReportSpamRequest>>,
     ) -> Result<Response<ReportSpamResponse>, Status> {
         let request = request.into_inner();
-        let (_user_id, payload) = self
-            .verify_user_auth::<_, ReportSpamPayload, _>(request)
+        let (user_id, payload) = self
+            .verify_unflagged_user_auth::<_, ReportSpamPayload, _>(request)
             .await?;
         self.verify_client_version(payload.client_metadata.as_ref())?;
 
-        // TODO: forward to the spam reporting service
+        let spammer_id: identifiers::UserId = payload
+            .spammer_id
+            .ok_or_missing_field("spammer_id")?
+            .try_into()?;
+        let spammer_username_hashes: Vec<identifiers::UsernameHash> = payload
+            .spammer_username_hashes
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?;
+        self.inner
+            .as_report_spam(&user_id, &spammer_id, &spammer_username_hashes)
+            .await?;
 
         Ok(Response::new(ReportSpamResponse {}))
     }
@@ -687,6 +742,17 @@ impl auth_service_server::AuthService for GrpcAs {
         let (hash, _payload) = self
             .verify_username_auth::<_, InitListenUsernamePayload, _>(signed_request)
             .await?;
+        let flagged = self
+            .inner
+            .as_is_username_flagged(&hash)
+            .await
+            .map_err(|error| {
+                error!(%error, "failed to check whether username is flagged");
+                Status::internal("database error")
+            })?;
+        if flagged {
+            return Err(Status::permission_denied("username is flagged as spammer"));
+        }
 
         let messages = self.inner.username_queues.listen(hash).await?;
 
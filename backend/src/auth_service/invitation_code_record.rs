@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use chrono::{DateTime, Utc};
 use rand::RngExt;
 use sqlx::PgTransaction;
 
@@ -10,6 +11,17 @@ use crate::auth_service::cli::InvitationCodeStats;
 pub struct InvitationCodeRecord {
     pub(crate) code: String,
     pub(crate) redeemed: bool,
+    /// Time after which the code can no longer be redeemed; codes without expiry never expire
+    pub(crate) expires_at: Option<DateTime<Utc>>,
+}
+
+/// Reason why an invitation code can't be redeemed
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum NotRedeemableReason {
+    /// The code has already been redeemed
+    Redeemed,
+    /// The code has expired
+    Expired,
 }
 
 const ALPHABET: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTUVWXYZ";
@@ -27,6 +39,21 @@ impl InvitationCodeRecord {
     pub(crate) fn validate_code(code: &str) -> bool {
         code.len() == CODE_LEN && code.bytes().all(|c| ALPHABET.contains(&c))
     }
+
+    pub(crate) fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Checks whether the code can be redeemed at the given time.
+    pub(crate) fn check_redeemable(&self, now: DateTime<Utc>) -> Result<(), NotRedeemableReason> {
+        if self.redeemed {
+            Err(NotRedeemableReason::Redeemed)
+        } else if self.is_expired(now) {
+            Err(NotRedeemableReason::Expired)
+        } else {
+            Ok(())
+        }
+    }
 }
 
 mod persistence {
@@ -58,7 +85,7 @@ mod persistence {
                 query_as!(
                     InvitationCodeRecord,
                     "
-                        SELECT code, redeemed
+                        SELECT code, redeemed, expires_at
                         FROM invitation_code
                         ORDER BY code
                         LIMIT $1
@@ -71,7 +98,7 @@ mod persistence {
                 query_as!(
                     InvitationCodeRecord,
                     "
-                        SELECT code, redeemed
+                        SELECT code, redeemed, expires_at
                         FROM invitation_code
                         WHERE redeemed = FALSE
                         ORDER BY code
@@ -91,7 +118,7 @@ mod persistence {
             query_as!(
                 InvitationCodeRecord,
                 "
-                    SELECT code, redeemed
+                    SELECT code, redeemed, expires_at
                     FROM invitation_code
                     WHERE code = $1
                 ",
@@ -101,19 +128,59 @@ mod persistence {
             .await
         }
 
+        /// Loads expired codes which were never redeemed.
+        pub(crate) async fn load_expired(
+            pool: &PgPool,
+            now: DateTime<Utc>,
+            limit: usize,
+        ) -> sqlx::Result<Vec<InvitationCodeRecord>> {
+            query_as!(
+                InvitationCodeRecord,
+                "
+                    SELECT code, redeemed, expires_at
+                    FROM invitation_code
+                    WHERE redeemed = FALSE AND expires_at <= $1
+                    ORDER BY expires_at, code
+                    LIMIT $2
+                ",
+                now,
+                limit as i64,
+            )
+            .fetch_all(pool)
+            .await
+        }
+
+        /// Deletes expired codes which were never redeemed.
+        ///
+        /// Returns the number of deleted codes.
+        pub(crate) async fn delete_expired(
+            executor: impl PgExecutor<'_>,
+            now: DateTime<Utc>,
+        ) -> sqlx::Result<u64> {
+            let result = query!(
+                "DELETE FROM invitation_code WHERE redeemed = FALSE AND expires_at <= $1",
+                now,
+            )
+            .execute(executor)
+            .await?;
+            Ok(result.rows_affected())
+        }
+
         async fn insert(
             executor: impl PgExecutor<'_>,
             code: &str,
             redeemed: bool,
+            expires_at: Option<DateTime<Utc>>,
         ) -> sqlx::Result<Option<String>> {
             query_scalar!(
                 "
-                    INSERT INTO invitation_code (code, redeemed)
-                    VALUES ($1, $2)
+                    INSERT INTO invitation_code (code, redeemed, expires_at)
+                    VALUES ($1, $2, $3)
                     RETURNING code
                 ",
                 code,
-                redeemed
+                redeemed,
+                expires_at,
             )
             .fetch_optional(executor)
             .await
@@ -122,12 +189,13 @@ mod persistence {
         pub(crate) async fn save(&self, executor: impl PgExecutor<'_>) -> sqlx::Result<()> {
             query!(
                 "
-                    INSERT INTO invitation_code (code, redeemed)
-                    VALUES ($1, $2)
+                    INSERT INTO invitation_code (code, redeemed, expires_at)
+                    VALUES ($1, $2, $3)
                     ON CONFLICT (code) DO UPDATE SET redeemed = $2
                 ",
                 self.code,
-                self.redeemed
+                self.redeemed,
+                self.expires_at,
             )
             .execute(executor)
             .await?;
@@ -135,11 +203,20 @@ mod persistence {
         }
 
         pub(crate) async fn generate(connection: &mut PgConnection) -> sqlx::Result<String> {
+            Self::generate_with_expiry(connection, None).await
+        }
+
+        pub(crate) async fn generate_with_expiry(
+            connection: &mut PgConnection,
+            expires_at: Option<DateTime<Utc>>,
+        ) -> sqlx::Result<String> {
             let mut code = String::with_capacity(CODE_LEN);
             loop {
                 code.clear();
                 Self::generate_code(&mut code);
-                if let Some(invitation_code) = Self::insert(&mut *connection, &code, false).await? {
+                if let Some(invitation_code) =
+                    Self::insert(&mut *connection, &code, false, expires_at).await?
+                {
                     return Ok(invitation_code);
                 }
             }
@@ -175,8 +252,8 @@ mod persistence {
 
         #[sqlx::test]
         async fn load_all_includes_redeemed(pool: PgPool) -> anyhow::Result<()> {
-            InvitationCodeRecord::insert(&pool, "CODE_A", true).await?;
-            InvitationCodeRecord::insert(&pool, "CODE_B", false).await?;
+            InvitationCodeRecord::insert(&pool, "CODE_A", true, None).await?;
+            InvitationCodeRecord::insert(&pool, "CODE_B", false, None).await?;
 
             let records = InvitationCodeRecord::load_all(&pool, true, 10).await?;
 
@@ -195,8 +272,8 @@ mod persistence {
 
         #[sqlx::test]
         async fn load_all_excludes_redeemed(pool: PgPool) -> anyhow::Result<()> {
-            InvitationCodeRecord::insert(&pool, "CODE_C", true).await?;
-            InvitationCodeRecord::insert(&pool, "CODE_D", false).await?;
+            InvitationCodeRecord::insert(&pool, "CODE_C", true, None).await?;
+            InvitationCodeRecord::insert(&pool, "CODE_D", false, None).await?;
 
             let records = InvitationCodeRecord::load_all(&pool, false, 10).await?;
 
@@ -209,7 +286,7 @@ mod persistence {
 
         #[sqlx::test]
         async fn load_existing_code(pool: PgPool) -> anyhow::Result<()> {
-            InvitationCodeRecord::insert(&pool, "LOAD_ME", true).await?;
+            InvitationCodeRecord::insert(&pool, "LOAD_ME", true, None).await?;
 
             let result = InvitationCodeRecord::load(&pool, "LOAD_ME").await?;
 
@@ -230,11 +307,12 @@ mod persistence {
 
         #[sqlx::test]
         async fn save_updates_existing_record(pool: PgPool) -> anyhow::Result<()> {
-            InvitationCodeRecord::insert(&pool, "UPDATE_ME", false).await?;
+            InvitationCodeRecord::insert(&pool, "UPDATE_ME", false, None).await?;
 
             let updated_record = InvitationCodeRecord {
                 code: "UPDATE_ME".to_string(),
                 redeemed: true, // Changing the state,
+                expires_at: None,
             };
 
             updated_record.save(&pool).await?;
@@ -268,5 +346,58 @@ mod persistence {
 
             Ok(())
         }
+
+        #[sqlx::test]
+        async fn redeem_before_and_after_expiry(pool: PgPool) -> anyhow::Result<()> {
+            let expires_at = Utc::now() + chrono::Duration::hours(1);
+            let mut connection = pool.acquire().await?;
+            let code =
+                InvitationCodeRecord::generate_with_expiry(&mut connection, Some(expires_at))
+                    .await?;
+
+            let record = InvitationCodeRecord::load(&pool, &code)
+                .await?
+                .expect("missing code");
+            // Postgres stores timestamps with microsecond precision
+            let expires_at = record.expires_at.expect("missing expiry");
+
+            let just_before = expires_at - chrono::Duration::milliseconds(1);
+            assert_eq!(record.check_redeemable(just_before), Ok(()));
+
+            let just_after = expires_at + chrono::Duration::milliseconds(1);
+            assert_eq!(
+                record.check_redeemable(just_after),
+                Err(NotRedeemableReason::Expired)
+            );
+
+            Ok(())
+        }
+
+        #[sqlx::test]
+        async fn sweep_expired_codes(pool: PgPool) -> anyhow::Result<()> {
+            let now = Utc::now();
+            let past = Some(now - chrono::Duration::days(1));
+            let future = Some(now + chrono::Duration::days(1));
+            InvitationCodeRecord::insert(&pool, "EXPIRED", false, past).await?;
+            InvitationCodeRecord::insert(&pool, "EXPIRED_REDEEMED", true, past).await?;
+            InvitationCodeRecord::insert(&pool, "VALID", false, future).await?;
+            InvitationCodeRecord::insert(&pool, "NO_EXPIRY", false, None).await?;
+
+            let expired = InvitationCodeRecord::load_expired(&pool, now, 10).await?;
+            assert_eq!(expired.len(), 1);
+            assert_eq!(expired[0].code, "EXPIRED");
+
+            let deleted = InvitationCodeRecord::delete_expired(&pool, now).await?;
+            assert_eq!(deleted, 1);
+
+            let remaining: Vec<_> = InvitationCodeRecord::load_all(&pool, true, 10)
+                .await?
+                .into_iter()
+                .map(|record| record.code)
+                .collect();
+            assert_eq!(remaining, ["EXPIRED_REDEEMED", "NO_EXPIRY", "VALID"]);
+
+            Ok(())
+        }
     }
 }
@@ -21,8 +21,9 @@ use usernames::UsernameQueues;
 
 use crate::{
     air_service::{BackendService, ServiceCreationError},
-    auth_service::client_record::ClientRecord,
+    auth_service::{client_record::ClientRecord, spam_report::SpamReportPolicy},
     errors::StorageError,
+    settings::SpamSettings,
 };
 
 pub mod cli;
@@ -33,6 +34,7 @@ mod credentials;
 pub mod grpc;
 mod invitation_code_record;
 pub mod privacy_pass;
+mod spam_report;
 pub mod user_record;
 mod usernames;
 
@@ -43,6 +45,7 @@ pub struct AuthService {
     client_version_req: Option<VersionReq>,
     invitation_only: bool,
     unredeemable_code: Option<Arc<str>>,
+    spam_report_policy: SpamReportPolicy,
     stop: CancellationToken,
 }
 
@@ -63,6 +66,11 @@ impl AuthService {
         self.unredeemable_code.as_deref() == Some(code)
     }
 
+    /// Sets when users reported as spammers are flagged.
+    pub fn set_spam_settings(&mut self, settings: &SpamSettings) {
+        self.spam_report_policy = settings.into();
+    }
+
     pub async fn load_client_verifying_key(
         &self,
         user_id: &UserId,
@@ -102,6 +110,7 @@ impl BackendService for AuthService {
             client_version_req,
             invitation_only: true,
             unredeemable_code: None,
+            spam_report_policy: SpamReportPolicy::default(),
             stop,
         };
 
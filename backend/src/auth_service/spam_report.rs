@@ -0,0 +1,207 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Aggregation of spam reports
+//!
+//! Users which are reported by too many distinct users within a time window are flagged. Flagged
+//! users are rejected by all user-authenticated requests which reach other users: they are no
+//! longer issued privacy pass tokens, so they can neither claim new usernames nor request
+//! invitation codes, and they can neither update their profile nor report other users.
+//!
+//! Usernames are unlinkable to users by design, so the AS only knows the usernames of a user from
+//! the reports: a reporter includes the hashes of the usernames of the reported user it knows. A
+//! username of a flagged user which was reported by as many distinct reporters as needed to flag a
+//! user is flagged as well. Flagged usernames can neither be listened on nor connected to.
+//!
+//! A flag expires after a configurable duration, unless the user is reported again.
+
+use chrono::Duration;
+
+use crate::settings::SpamSettings;
+
+/// Policy when to flag a reported user
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SpamReportPolicy {
+    /// Number of distinct reporters after which a user is flagged
+    pub(crate) threshold: u32,
+    /// Time window in which reports are counted
+    pub(crate) window: Duration,
+    /// Time after which a flag expires
+    pub(crate) flag_duration: Duration,
+}
+
+impl Default for SpamReportPolicy {
+    fn default() -> Self {
+        (&SpamSettings::default()).into()
+    }
+}
+
+impl From<&SpamSettings> for SpamReportPolicy {
+    fn from(settings: &SpamSettings) -> Self {
+        Self {
+            threshold: settings.reportthreshold.get(),
+            window: Duration::from_std(settings.reportwindow).unwrap_or(Duration::MAX),
+            flag_duration: Duration::from_std(settings.flagduration).unwrap_or(Duration::MAX),
+        }
+    }
+}
+
+/// A report of a user as spammer
+pub(crate) struct SpamReport;
+
+/// A user which was flagged because of spam reports
+pub(crate) struct FlaggedUser;
+
+mod persistence {
+    use aircommon::identifiers::{UserId, UsernameHash};
+    use chrono::{DateTime, Utc};
+    use sqlx::{PgExecutor, query, query_scalar};
+
+    use super::{FlaggedUser, SpamReport};
+
+    impl SpamReport {
+        /// Stores the report or refreshes the time of an existing report by the same reporter.
+        pub(in crate::auth_service) async fn store(
+            executor: impl PgExecutor<'_>,
+            reporter: &UserId,
+            spammer: &UserId,
+            reported_at: DateTime<Utc>,
+        ) -> sqlx::Result<()> {
+            query!(
+                "INSERT INTO as_spam_report (
+                    spammer_uuid,
+                    spammer_domain,
+                    reporter_uuid,
+                    reporter_domain,
+                    reported_at
+                ) VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (spammer_uuid, spammer_domain, reporter_uuid, reporter_domain)
+                DO UPDATE SET reported_at = EXCLUDED.reported_at",
+                spammer.uuid(),
+                spammer.domain() as _,
+                reporter.uuid(),
+                reporter.domain() as _,
+                reported_at,
+            )
+            .execute(executor)
+            .await?;
+            Ok(())
+        }
+
+        /// Stores the hashes of the usernames of the spammer known to the reporter.
+        ///
+        /// The report of the spammer by the reporter must already be stored.
+        pub(in crate::auth_service) async fn store_usernames(
+            executor: impl PgExecutor<'_>,
+            reporter: &UserId,
+            spammer: &UserId,
+            hashes: &[UsernameHash],
+        ) -> sqlx::Result<()> {
+            let hashes: Vec<&[u8]> = hashes.iter().map(UsernameHash::as_bytes).collect();
+            query!(
+                "INSERT INTO as_spam_report_username (
+                    hash,
+                    spammer_uuid,
+                    spammer_domain,
+                    reporter_uuid,
+                    reporter_domain
+                ) SELECT hash, $2, $3, $4, $5 FROM UNNEST($1::bytea[]) AS hash
+                ON CONFLICT DO NOTHING",
+                &hashes as _,
+                spammer.uuid(),
+                spammer.domain() as _,
+                reporter.uuid(),
+                reporter.domain() as _,
+            )
+            .execute(executor)
+            .await?;
+            Ok(())
+        }
+
+        /// Counts the distinct reporters of the given user since the given time.
+        pub(in crate::auth_service) async fn count_since(
+            executor: impl PgExecutor<'_>,
+            spammer: &UserId,
+            since: DateTime<Utc>,
+        ) -> sqlx::Result<u64> {
+            let count = query_scalar!(
+                "SELECT COUNT(*) FROM as_spam_report
+                WHERE spammer_uuid = $1 AND spammer_domain = $2 AND reported_at >= $3",
+                spammer.uuid(),
+                spammer.domain() as _,
+                since,
+            )
+            .fetch_one(executor)
+            .await?
+            .unwrap_or_default();
+            Ok(count as u64)
+        }
+    }
+
+    impl FlaggedUser {
+        /// Flags the user at the given time or renews an existing flag.
+        pub(in crate::auth_service) async fn flag(
+            executor: impl PgExecutor<'_>,
+            user_id: &UserId,
+            flagged_at: DateTime<Utc>,
+        ) -> sqlx::Result<()> {
+            query!(
+                "INSERT INTO as_flagged_user (user_uuid, user_domain, flagged_at)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (user_uuid, user_domain) DO UPDATE
+                SET flagged_at = EXCLUDED.flagged_at",
+                user_id.uuid(),
+                user_id.domain() as _,
+                flagged_at,
+            )
+            .execute(executor)
+            .await?;
+            Ok(())
+        }
+
+        /// Returns whether the user was flagged since the given time.
+        pub(in crate::auth_service) async fn is_flagged(
+            executor: impl PgExecutor<'_>,
+            user_id: &UserId,
+            since: DateTime<Utc>,
+        ) -> sqlx::Result<bool> {
+            query_scalar!(
+                r#"SELECT EXISTS (
+                    SELECT 1 FROM as_flagged_user
+                    WHERE user_uuid = $1 AND user_domain = $2 AND flagged_at >= $3
+                ) AS "exists!""#,
+                user_id.uuid(),
+                user_id.domain() as _,
+                since,
+            )
+            .fetch_one(executor)
+            .await
+        }
+
+        /// Returns whether the username belongs to a user flagged since the given time and was
+        /// reported by at least `threshold` distinct reporters of this user.
+        pub(in crate::auth_service) async fn is_username_flagged(
+            executor: impl PgExecutor<'_>,
+            hash: &UsernameHash,
+            since: DateTime<Utc>,
+            threshold: u32,
+        ) -> sqlx::Result<bool> {
+            query_scalar!(
+                r#"SELECT EXISTS (
+                    SELECT 1 FROM as_spam_report_username r
+                    INNER JOIN as_flagged_user f ON f.user_uuid = r.spammer_uuid
+                        AND f.user_domain = r.spammer_domain
+                    WHERE r.hash = $1 AND f.flagged_at >= $2
+                    GROUP BY r.spammer_uuid, r.spammer_domain
+                    HAVING COUNT(*) >= $3
+                ) AS "exists!""#,
+                hash.as_bytes(),
+                since,
+                i64::from(threshold),
+            )
+            .fetch_one(executor)
+            .await
+        }
+    }
+}
@@ -50,6 +50,8 @@ pub(crate) trait ConnectUsernameProtocol {
         hash: &UsernameHash,
     ) -> sqlx::Result<Option<ExpirationData>>;
 
+    async fn is_username_flagged(&self, hash: &UsernameHash) -> sqlx::Result<bool>;
+
     async fn get_connection_package_for_username(
         &self,
         hash: &UsernameHash,
@@ -109,6 +111,10 @@ async fn run_protocol_impl(
         .ok_or_missing_field("hash")?
         .try_into()?;
 
+    if protocol.is_username_flagged(&hash).await? {
+        return Err(ConnectProtocolError::UsernameFlagged);
+    }
+
     debug!("load username expiration data");
     let Some(expiration_data) = protocol.load_username_expiration_data(&hash).await? else {
         return Err(ConnectProtocolError::UsernameNotFound);
@@ -185,6 +191,8 @@ pub(crate) enum ConnectProtocolError {
     Database(#[from] sqlx::Error),
     /// Username not found
     UsernameNotFound,
+    /// Username is flagged as spammer
+    UsernameFlagged,
     /// Invalid hash: $0
     InvalidHash(#[from] UsernameHashError),
     /// Missing required field in request
@@ -205,6 +213,7 @@ impl From<ConnectProtocolError> for Status {
                 Status::internal(msg)
             }
             ConnectProtocolError::UsernameNotFound => Status::not_found(msg),
+            ConnectProtocolError::UsernameFlagged => Status::permission_denied(msg),
             ConnectProtocolError::MissingField(_) | ConnectProtocolError::InvalidHash(_) => {
                 Status::invalid_argument(msg)
             }
@@ -225,6 +234,10 @@ impl ConnectUsernameProtocol for AuthService {
         Self::load_username_expiration_data_impl(&self.db_pool, hash).await
     }
 
+    async fn is_username_flagged(&self, hash: &UsernameHash) -> sqlx::Result<bool> {
+        self.as_is_username_flagged(hash).await
+    }
+
     async fn get_connection_package_for_username(
         &self,
         hash: &UsernameHash,
@@ -358,6 +371,11 @@ mod tests {
 
         let mut mock_protocol = MockConnectUsernameProtocol::new();
 
+        mock_protocol
+            .expect_is_username_flagged()
+            .with(eq(hash))
+            .returning(|_| Ok(false));
+
         mock_protocol
             .expect_load_username_expiration_data()
             .with(eq(hash))
@@ -436,6 +454,11 @@ mod tests {
 
         let mut mock_protocol = MockConnectUsernameProtocol::new();
 
+        mock_protocol
+            .expect_is_username_flagged()
+            .with(eq(hash))
+            .returning(|_| Ok(false));
+
         mock_protocol
             .expect_load_username_expiration_data()
             .with(eq(hash))
@@ -472,6 +495,11 @@ mod tests {
 
         let mut mock_protocol = MockConnectUsernameProtocol::new();
 
+        mock_protocol
+            .expect_is_username_flagged()
+            .with(eq(hash))
+            .returning(|_| Ok(false));
+
         mock_protocol
             .expect_load_username_expiration_data()
             .with(eq(hash))
@@ -500,6 +528,42 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn connect_username_protocol_username_flagged() -> anyhow::Result<()> {
+        init_test_tracing();
+
+        let hash = UsernameHash::new([1; 32]);
+
+        let mut mock_protocol = MockConnectUsernameProtocol::new();
+
+        mock_protocol
+            .expect_is_username_flagged()
+            .with(eq(hash))
+            .returning(|_| Ok(true));
+
+        mock_protocol.expect_client_version_req().returning(|| None);
+
+        let (requests, mut responses, run_handle) = run_test_protocol(mock_protocol);
+
+        let request_fetch = ConnectUsernameRequest {
+            step: Some(connect_username_request::Step::Fetch(
+                FetchConnectionPackageStep {
+                    client_metadata: Some(CLIENT_METADATA.clone()),
+                    hash: Some(hash.into()),
+                },
+            )),
+        };
+
+        requests.send(Ok(request_fetch)).await.unwrap();
+
+        let response = responses.recv().await.unwrap();
+        assert_eq!(response.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+        run_handle.await.expect("protocol panicked");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn connect_username_protocol_protocol_violation() -> anyhow::Result<()> {
         init_test_tracing();
@@ -559,6 +623,11 @@ mod tests {
 
         let mut mock_protocol = MockConnectUsernameProtocol::new();
 
+        mock_protocol
+            .expect_is_username_flagged()
+            .with(eq(hash))
+            .returning(|_| Ok(false));
+
         mock_protocol
             .expect_load_username_expiration_data()
             .with(eq(hash))
@@ -10,22 +10,27 @@ use airprotos::{
         AttachmentTooLargeDetail, StatusDetails, StatusDetailsCode, status_details::Detail,
     },
     delivery_service::v1::{
-        GetAttachmentUrlResponse, HeaderEntry, ProvisionAttachmentResponse, SignedPostPolicy,
-        StorageObjectType,
+        GetAttachmentPartUploadUrlResponse, GetAttachmentUrlResponse, HeaderEntry, MultipartUpload,
+        ProvisionAttachmentResponse, SignedPostPolicy, StorageObjectType, UploadedPart,
     },
 };
 use aws_sdk_s3::{
     config::http,
     error::{BuildError, SdkError},
-    operation::{get_object, put_object},
+    operation::{
+        complete_multipart_upload, create_multipart_upload, get_object, put_object, upload_part,
+    },
     presigning::{PresigningConfig, PresigningConfigError},
+    types::{CompletedMultipartUpload, CompletedPart},
 };
 use base64::{Engine, prelude::BASE64_STANDARD};
 use chrono::{DateTime, Utc};
 use displaydoc::Display;
+use mls_assist::openmls::prelude::LeafNodeIndex;
 use prost::Message;
 use serde::Serialize;
 use serde_json::json;
+use sqlx::PgExecutor;
 use tonic::{Code, Response, Status};
 use tracing::error;
 use uuid::Uuid;
@@ -34,12 +39,30 @@ use crate::settings::{StoragePaths, StorageSettings};
 
 use super::{Ds, storage::Storage};
 
+/// Size of the parts of a multipart upload
+///
+/// S3 requires all parts except the last one to be at least 5 MiB large.
+const MULTIPART_UPLOAD_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Maximum number of parts of a multipart upload supported by S3
+const MULTIPART_UPLOAD_MAX_PARTS: u64 = 10_000;
+
+/// The group member who provisioned a multipart upload
+///
+/// Only this member is allowed to upload the parts and to complete the upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct AttachmentUploadOwner {
+    pub(super) group_id: Uuid,
+    pub(super) sender_index: LeafNodeIndex,
+}
+
 impl Ds {
     pub(super) async fn provision_object(
         &self,
         object_type: StorageObjectType,
         content_length: Option<u64>,
         use_post_policy: bool,
+        multipart_upload_owner: Option<AttachmentUploadOwner>,
     ) -> Result<ProvisionAttachmentResponse, ProvisionObjectError> {
         let Some(storage) = self.storage.as_ref() else {
             return Err(ProvisionObjectError::NoStorageConfigured);
@@ -48,7 +71,28 @@ impl Ds {
         let object_id = Uuid::new_v4();
         let expiration = ExpirationData::now(storage.settings().upload_expiration);
 
-        let response = if storage.settings().use_post_policy && use_post_policy {
+        // Multipart uploads are only supported for attachments with a known size.
+        let multipart_upload = content_length
+            .filter(|content_length| *content_length > 0)
+            .zip(multipart_upload_owner)
+            .filter(|_| {
+                matches!(
+                    object_type,
+                    StorageObjectType::Unspecified | StorageObjectType::Attachment
+                )
+            });
+
+        let response = if let Some((content_length, owner)) = multipart_upload {
+            create_multipart_upload(
+                storage,
+                &self.db_pool,
+                object_id,
+                expiration,
+                content_length,
+                owner,
+            )
+            .await?
+        } else if storage.settings().use_post_policy && use_post_policy {
             create_signed_post(storage, object_id, expiration, object_type)
         } else {
             // We still allow content length 0 for legacy clients.
@@ -108,6 +152,122 @@ impl Ds {
             download_headers: headers,
         }))
     }
+
+    pub(super) async fn part_upload_url(
+        &self,
+        object_id: Uuid,
+        owner: AttachmentUploadOwner,
+        part_number: u32,
+        content_length: u64,
+    ) -> Result<GetAttachmentPartUploadUrlResponse, MultipartUploadError> {
+        let Some(storage) = self.storage.as_ref() else {
+            return Err(MultipartUploadError::NoStorageConfigured);
+        };
+        let settings = storage.settings();
+
+        let upload = AttachmentUpload::load(&self.db_pool, object_id, owner)
+            .await?
+            .ok_or(MultipartUploadError::UploadNotFound)?;
+        if upload.completed {
+            return Err(MultipartUploadError::UploadCompleted);
+        }
+        if upload.part_length(part_number) != Some(content_length) {
+            return Err(MultipartUploadError::InvalidPart);
+        }
+
+        let expiration = ExpirationData::now(settings.upload_expiration);
+        let not_before: DateTime<Utc> = expiration.not_before().into();
+        let not_after: DateTime<Utc> = expiration.not_after().into();
+        let duration = not_after - not_before;
+
+        let mut presigning_config = PresigningConfig::builder();
+        presigning_config.set_start_time(Some(not_before.into()));
+        presigning_config.set_expires_in(Some(duration.to_std()?));
+        let presigning_config = presigning_config.build()?;
+
+        let object_type = StorageObjectType::Attachment;
+        let bucket = select_bucket(settings, object_type);
+        let key = storage_key(&settings.storage_paths, object_id, object_type);
+        let request = storage
+            .client()
+            .upload_part()
+            .bucket(bucket)
+            .key(key)
+            .upload_id(upload.upload_id)
+            .part_number(part_number.try_into()?)
+            .content_length(content_length.try_into()?)
+            .presigned(presigning_config)
+            .await
+            .map_err(Box::new)?;
+
+        let url = request.uri().to_owned();
+        let headers: Vec<HeaderEntry> = request
+            .headers()
+            .map(|(k, v)| HeaderEntry {
+                key: k.to_owned(),
+                value: v.to_owned(),
+            })
+            .collect();
+
+        Ok(GetAttachmentPartUploadUrlResponse {
+            upload_url_expiration: Some(expiration.into()),
+            upload_url: url,
+            upload_headers: headers,
+        })
+    }
+
+    pub(super) async fn complete_upload(
+        &self,
+        object_id: Uuid,
+        owner: AttachmentUploadOwner,
+        parts: Vec<UploadedPart>,
+    ) -> Result<(), MultipartUploadError> {
+        let Some(storage) = self.storage.as_ref() else {
+            return Err(MultipartUploadError::NoStorageConfigured);
+        };
+        let settings = storage.settings();
+
+        let upload = AttachmentUpload::load(&self.db_pool, object_id, owner)
+            .await?
+            .ok_or(MultipartUploadError::UploadNotFound)?;
+        if upload.completed {
+            // completed by a previous request whose response was lost
+            return Ok(());
+        }
+        let all_parts_numbered = (1..).zip(&parts).all(|(n, part)| part.part_number == n);
+        if !all_parts_numbered || parts.len() as u64 != upload.num_parts() {
+            return Err(MultipartUploadError::InvalidPart);
+        }
+
+        let parts = parts
+            .into_iter()
+            .map(|part| {
+                Ok(CompletedPart::builder()
+                    .part_number(part.part_number.try_into()?)
+                    .e_tag(part.etag)
+                    .build())
+            })
+            .collect::<Result<Vec<_>, MultipartUploadError>>()?;
+
+        let object_type = StorageObjectType::Attachment;
+        storage
+            .client()
+            .complete_multipart_upload()
+            .bucket(select_bucket(settings, object_type))
+            .key(storage_key(&settings.storage_paths, object_id, object_type))
+            .upload_id(&upload.upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(Box::new)?;
+
+        AttachmentUpload::mark_completed(&self.db_pool, object_id).await?;
+        Ok(())
+    }
 }
 
 async fn create_signed_put(
@@ -166,9 +326,145 @@ async fn create_signed_put(
         upload_url: url,
         upload_headers: header,
         post_policy: None,
+        multipart_upload: None,
+    })
+}
+
+/// Creates a multipart upload at the storage provider and records its owner
+async fn create_multipart_upload(
+    storage: &Storage,
+    executor: impl PgExecutor<'_>,
+    object_id: Uuid,
+    expiration: ExpirationData,
+    content_length: u64,
+    owner: AttachmentUploadOwner,
+) -> Result<ProvisionAttachmentResponse, ProvisionObjectError> {
+    let settings = storage.settings();
+    if settings.max_attachment_size < content_length
+        || MULTIPART_UPLOAD_MAX_PARTS < content_length.div_ceil(MULTIPART_UPLOAD_PART_SIZE)
+    {
+        return Err(ProvisionObjectError::DataTooLarge {
+            max_size: settings.max_attachment_size,
+            actual_size: content_length,
+        });
+    }
+
+    let object_type = StorageObjectType::Attachment;
+    let output = storage
+        .client()
+        .create_multipart_upload()
+        .bucket(select_bucket(settings, object_type))
+        .key(storage_key(&settings.storage_paths, object_id, object_type))
+        .send()
+        .await
+        .map_err(Box::new)?;
+    let upload_id = output
+        .upload_id
+        .ok_or(ProvisionObjectError::MissingUploadId)?;
+
+    AttachmentUpload {
+        object_id,
+        owner,
+        upload_id,
+        content_length,
+        completed: false,
+    }
+    .store(executor)
+    .await?;
+
+    Ok(ProvisionAttachmentResponse {
+        object_id: Some(object_id.into()),
+        upload_url_expiration: Some(expiration.into()),
+        multipart_upload: Some(MultipartUpload {
+            part_size: MULTIPART_UPLOAD_PART_SIZE,
+        }),
+        ..Default::default()
     })
 }
 
+/// A multipart upload of an attachment
+struct AttachmentUpload {
+    object_id: Uuid,
+    owner: AttachmentUploadOwner,
+    /// Id of the upload at the storage provider
+    upload_id: String,
+    content_length: u64,
+    completed: bool,
+}
+
+impl AttachmentUpload {
+    fn num_parts(&self) -> u64 {
+        self.content_length.div_ceil(MULTIPART_UPLOAD_PART_SIZE)
+    }
+
+    /// Returns the length of the part with the given number, or `None` if there is no such part.
+    fn part_length(&self, part_number: u32) -> Option<u64> {
+        let offset = u64::from(part_number.checked_sub(1)?) * MULTIPART_UPLOAD_PART_SIZE;
+        let length = self
+            .content_length
+            .checked_sub(offset)?
+            .min(MULTIPART_UPLOAD_PART_SIZE);
+        (length > 0).then_some(length)
+    }
+
+    async fn store(&self, executor: impl PgExecutor<'_>) -> Result<(), ProvisionObjectError> {
+        let sender_index = i64::from(self.owner.sender_index.u32());
+        let content_length: i64 = self.content_length.try_into()?;
+        sqlx::query!(
+            "INSERT INTO ds_attachment_upload (
+                object_id, group_id, sender_index, upload_id, content_length
+            ) VALUES ($1, $2, $3, $4, $5)",
+            self.object_id,
+            self.owner.group_id,
+            sender_index,
+            self.upload_id,
+            content_length,
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Loads the upload of the object, if it was provisioned by the given owner.
+    async fn load(
+        executor: impl PgExecutor<'_>,
+        object_id: Uuid,
+        owner: AttachmentUploadOwner,
+    ) -> Result<Option<Self>, MultipartUploadError> {
+        let sender_index = i64::from(owner.sender_index.u32());
+        let Some(record) = sqlx::query!(
+            "SELECT upload_id, content_length, completed
+            FROM ds_attachment_upload
+            WHERE object_id = $1 AND group_id = $2 AND sender_index = $3",
+            object_id,
+            owner.group_id,
+            sender_index,
+        )
+        .fetch_optional(executor)
+        .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            object_id,
+            owner,
+            upload_id: record.upload_id,
+            content_length: record.content_length.try_into()?,
+            completed: record.completed,
+        }))
+    }
+
+    async fn mark_completed(executor: impl PgExecutor<'_>, object_id: Uuid) -> sqlx::Result<()> {
+        sqlx::query!(
+            "UPDATE ds_attachment_upload SET completed = TRUE WHERE object_id = $1",
+            object_id
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+}
+
 #[derive(Serialize)]
 struct Policy {
     expiration: DateTime<Utc>,
@@ -280,6 +576,17 @@ pub(super) enum ProvisionObjectError {
     Presigning(#[from] PresigningConfigError),
     /// Internal error
     Sdk(#[from] Box<SdkError<put_object::PutObjectError, http::HttpResponse>>),
+    /// Internal error
+    CreateMultipartUpload(
+        #[from]
+        Box<SdkError<create_multipart_upload::CreateMultipartUploadError, http::HttpResponse>>,
+    ),
+    /// Internal error
+    MissingUploadId,
+    /// Internal error
+    Database(#[from] sqlx::Error),
+    /// Internal error
+    Conversion(#[from] std::num::TryFromIntError),
     /// Content length is required
     ContentLengthRequired,
     /// Attachment is too large: {actual_size} bytes > {max_size} bytes
@@ -310,6 +617,22 @@ impl From<ProvisionObjectError> for Status {
                 error!(%error, "Failed to build S3 request");
                 Status::internal(msg)
             }
+            ProvisionObjectError::CreateMultipartUpload(error) => {
+                error!(%error, "Failed to create multipart upload");
+                Status::internal(msg)
+            }
+            ProvisionObjectError::MissingUploadId => {
+                error!("Created multipart upload has no upload id");
+                Status::internal(msg)
+            }
+            ProvisionObjectError::Database(error) => {
+                error!(%error, "Failed to store multipart upload");
+                Status::internal(msg)
+            }
+            ProvisionObjectError::Conversion(error) => {
+                error!(%error, "Failed to convert content length");
+                Status::internal(msg)
+            }
             ProvisionObjectError::ContentLengthRequired => {
                 Status::invalid_argument("content length is required")
             }
@@ -381,6 +704,79 @@ impl From<GetAttachmentUrlError> for Status {
     }
 }
 
+#[derive(Debug, thiserror::Error, Display)]
+pub(super) enum MultipartUploadError {
+    /// Attachments are not supported
+    NoStorageConfigured,
+    /// Multipart upload not found
+    UploadNotFound,
+    /// Multipart upload is already completed
+    UploadCompleted,
+    /// Invalid part number or part size
+    InvalidPart,
+    /// Internal error
+    Build(#[from] BuildError),
+    /// Internal error
+    Duration(#[from] chrono::OutOfRangeError),
+    /// Internal error
+    Presigning(#[from] PresigningConfigError),
+    /// Internal error
+    UploadPart(#[from] Box<SdkError<upload_part::UploadPartError, http::HttpResponse>>),
+    /// Failed to complete the upload
+    CompleteMultipartUpload(
+        #[from]
+        Box<SdkError<complete_multipart_upload::CompleteMultipartUploadError, http::HttpResponse>>,
+    ),
+    /// Internal error
+    Database(#[from] sqlx::Error),
+    /// Internal error
+    Conversion(#[from] std::num::TryFromIntError),
+}
+
+impl From<MultipartUploadError> for Status {
+    fn from(error: MultipartUploadError) -> Self {
+        let msg = error.to_string();
+        match error {
+            MultipartUploadError::NoStorageConfigured => {
+                error!("Storage is not configured");
+                Status::internal(msg)
+            }
+            MultipartUploadError::UploadNotFound => Status::not_found(msg),
+            MultipartUploadError::UploadCompleted => Status::failed_precondition(msg),
+            MultipartUploadError::InvalidPart => Status::invalid_argument(msg),
+            MultipartUploadError::Build(error) => {
+                error!(%error, "Failed to build S3 config");
+                Status::internal(msg)
+            }
+            MultipartUploadError::Duration(error) => {
+                error!(%error, "Failed to convert chrono to std duration");
+                Status::internal(msg)
+            }
+            MultipartUploadError::Presigning(error) => {
+                error!(%error, "Failed to create presigning config");
+                Status::internal(msg)
+            }
+            MultipartUploadError::UploadPart(error) => {
+                error!(%error, "Failed to build S3 request");
+                Status::internal(msg)
+            }
+            MultipartUploadError::CompleteMultipartUpload(error) => {
+                // e.g. a part was not uploaded or its ETag does not match
+                error!(%error, "Failed to complete multipart upload");
+                Status::failed_precondition(msg)
+            }
+            MultipartUploadError::Database(error) => {
+                error!(%error, "Failed to load multipart upload");
+                Status::internal(msg)
+            }
+            MultipartUploadError::Conversion(error) => {
+                error!(%error, "Failed to convert part number or size");
+                Status::internal(msg)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use chrono::Duration;
@@ -464,6 +860,26 @@ mod test {
         insta::assert_debug_snapshot!(policy);
     }
 
+    #[test]
+    fn test_attachment_upload_parts() {
+        let upload = AttachmentUpload {
+            object_id: uuid!("ba521fc6-1ec2-4f8e-a85e-3dacc1e96989"),
+            owner: AttachmentUploadOwner {
+                group_id: uuid!("f1c5f4e2-6b1d-4b6a-9f6e-2a4c3b1d0e9f"),
+                sender_index: LeafNodeIndex::new(1),
+            },
+            upload_id: "upload-id".to_owned(),
+            content_length: 2 * MULTIPART_UPLOAD_PART_SIZE + 42,
+            completed: false,
+        };
+        assert_eq!(upload.num_parts(), 3);
+        assert_eq!(upload.part_length(0), None);
+        assert_eq!(upload.part_length(1), Some(MULTIPART_UPLOAD_PART_SIZE));
+        assert_eq!(upload.part_length(2), Some(MULTIPART_UPLOAD_PART_SIZE));
+        assert_eq!(upload.part_length(3), Some(42));
+        assert_eq!(upload.part_length(4), None);
+    }
+
     #[test]
     fn test_storage_key_with_default_paths() {
         let paths = StoragePaths::default();
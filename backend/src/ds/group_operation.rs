@@ -29,9 +29,11 @@ use aircommon::{
     },
     identifiers::QsReference,
     messages::{
+        AirProtocolVersion,
         client_ds::{
-            AadMessage, AadPayload, AddUsersInfo, ApqWelcomeBundle, DsJoinerInformation,
-            GroupOperationParams, GroupOperationParamsAad, QsQueueMessagePayload, WelcomeBundle,
+            AadMessage, AadPayload, AddUsersInfo, ApqWelcomeBundle, ChangeRoleParamsAad,
+            DsJoinerInformation, GroupOperationParams, GroupOperationParamsAad,
+            QsQueueMessagePayload, WelcomeBundle,
         },
         welcome_attribution_info::EncryptedWelcomeAttributionInfo,
     },
@@ -40,7 +42,7 @@ use aircommon::{
     utils::removed_clients,
 };
 use tls_codec::DeserializeBytes;
-use tracing::{error, warn};
+use tracing::{debug, error, warn};
 
 use crate::{
     errors::GroupOperationError,
@@ -88,10 +90,33 @@ impl DsGroupState {
                 warn!(%e, "Error deserializing AAD message");
                 GroupOperationError::InvalidMessage
             })?;
-        // TODO: Check version of Aad Message
-        let AadPayload::GroupOperation(aad_payload) = aad_message.into_payload() else {
-            warn!("AAD payload is not a group operation");
-            return Err(GroupOperationError::InvalidMessage);
+        // The payload is interpreted according to the version of the AAD message
+        match aad_message.version() {
+            AirProtocolVersion::Alpha => {}
+        }
+        let mut skipped_payload = false;
+        let (aad_payload, role_change) = match aad_message.into_payload() {
+            AadPayload::GroupOperation(aad_payload) => (aad_payload, None),
+            AadPayload::ChangeRole(role_change) => {
+                let aad_payload = GroupOperationParamsAad {
+                    new_encrypted_user_profile_keys: Vec::new(),
+                };
+                (aad_payload, Some(role_change))
+            }
+            // Optional information of a newer client, which is forwarded to the members as part
+            // of the commit
+            AadPayload::Unknown(payload) if payload.is_ignorable() => {
+                debug!(tag = payload.tag(), "Skipping unknown AAD payload");
+                skipped_payload = true;
+                let aad_payload = GroupOperationParamsAad {
+                    new_encrypted_user_profile_keys: Vec::new(),
+                };
+                (aad_payload, None)
+            }
+            _ => {
+                warn!("AAD payload is not a group operation");
+                return Err(GroupOperationError::InvalidMessage);
+            }
         };
 
         // Extract the message's content
@@ -130,6 +155,27 @@ impl DsGroupState {
         // Check if the operation adds a user.
         let adds_users = staged_commit.add_proposals().count() != 0;
 
+        // Without understanding the payload, the added users can't be validated, and clients
+        // skipping the payload must not miss membership changes.
+        if skipped_payload && (adds_users || staged_commit.remove_proposals().count() != 0) {
+            warn!("Commit with an unknown AAD payload must not add or remove users");
+            return Err(GroupOperationError::InvalidMessage);
+        }
+
+        // Validation related to role changes. Adding and removing users has to go through the
+        // respective proposals.
+        if let Some(ChangeRoleParamsAad { target, role }) = role_change {
+            if adds_users
+                || staged_commit.remove_proposals().count() != 0
+                || matches!(role, RoleIndex::Outsider)
+            {
+                warn!("Role change must not add or remove users");
+                return Err(GroupOperationError::InvalidMessage);
+            }
+            self.room_state_change_role(sender.user_id(), &target, role)
+                .ok_or(GroupOperationError::InvalidMessage)?;
+        }
+
         // TODO: Validate that the senders of the proposals have sufficient
         //       privileges (if this isn't done by an MLS extension). Note that
         //       we have to check the sender of the proposals not those of the
@@ -46,7 +46,11 @@ use tracing::{error, warn};
 
 use crate::{
     auth_service::AsConnector,
-    ds::{attachments::ProvisionObjectError, group_state::MemberProfile, process::Provider},
+    ds::{
+        attachments::{AttachmentUploadOwner, ProvisionObjectError},
+        group_state::MemberProfile,
+        process::Provider,
+    },
     messages::intra_backend::{DsFanOutMessage, DsFanOutPayload},
     qs::QsConnector,
     rate_limiter::{RateLimiter, RlConfig, RlKey, provider::RlPostgresStorage},
@@ -626,7 +630,22 @@ impl<Qep: QsConnector, As: AsConnector> DeliveryService for GrpcDs<Qep, As> {
     ) -> Result<Response<RequestGroupIdResponse>, Status> {
         let request = request.into_inner();
         self.verify_client_version(request.client_metadata.as_ref())?;
-        let qgid = self.ds.request_group_id().await;
+        let qgid = match request.requested_group_uuid {
+            // Clients must not pick group ids outside of tests
+            #[cfg(feature = "test_utils")]
+            Some(group_uuid) => self
+                .ds
+                .request_specific_group_id(group_uuid.into())
+                .await
+                .ok_or_else(|| Status::already_exists("group id already reserved"))?,
+            #[cfg(not(feature = "test_utils"))]
+            Some(_) => {
+                return Err(Status::invalid_argument(
+                    "requesting a specific group id is not supported",
+                ));
+            }
+            None => self.ds.request_group_id().await,
+        };
 
         let pq_qgid = if request.request_pq_group_id {
             Some(self.ds.request_group_id().await)
@@ -1903,7 +1922,7 @@ impl<Qep: QsConnector, As: AsConnector> DeliveryService for GrpcDs<Qep, As> {
         self.verify_client_version(payload.client_metadata.as_ref())?;
 
         // the payload can be signed in different ways depending of the object type
-        let payload: ProvisionAttachmentPayload = match payload.object_type() {
+        let (payload, owner): (ProvisionAttachmentPayload, _) = match payload.object_type() {
             StorageObjectType::Unspecified
             | StorageObjectType::Attachment
             | StorageObjectType::GroupProfile
@@ -1919,9 +1938,14 @@ impl<Qep: QsConnector, As: AsConnector> DeliveryService for GrpcDs<Qep, As> {
 
                 let sender_credential = sender_client_credential(&group_state, sender_index)?;
 
-                request
+                let payload = request
                     .verify(sender_credential.verifying_key())
-                    .map_err(InvalidSignature)?
+                    .map_err(InvalidSignature)?;
+                let owner = AttachmentUploadOwner {
+                    group_id: qgid.group_uuid(),
+                    sender_index,
+                };
+                (payload, Some(owner))
             }
             StorageObjectType::DebugLogs => {
                 let user_id = payload
@@ -1939,9 +1963,10 @@ impl<Qep: QsConnector, As: AsConnector> DeliveryService for GrpcDs<Qep, As> {
                     })?
                     .ok_or_else(|| Status::not_found("user not found"))?;
 
-                request
+                let payload = request
                     .verify(&client_verifying_key)
-                    .map_err(InvalidSignature)?
+                    .map_err(InvalidSignature)?;
+                (payload, None)
             }
         };
 
@@ -1956,6 +1981,8 @@ impl<Qep: QsConnector, As: AsConnector> DeliveryService for GrpcDs<Qep, As> {
                 payload.object_type.try_into().unwrap_or_default(),
                 Some(content_length),
                 payload.use_post_policy,
+                // multipart uploads are only supported for attachments of a group
+                owner.filter(|_| payload.use_multipart_upload),
             )
             .await?;
 
@@ -2030,6 +2057,110 @@ impl<Qep: QsConnector, As: AsConnector> DeliveryService for GrpcDs<Qep, As> {
         Ok(self.ds.get_object_url(object_id, object_type).await?)
     }
 
+    async fn get_attachment_part_upload_url(
+        &self,
+        request: Request<SignedRequest<GetAttachmentPartUploadUrlRequest>>,
+    ) -> Result<Response<GetAttachmentPartUploadUrlResponse>, Status> {
+        let request = request.into_inner();
+
+        request
+            .inner()
+            .signature
+            .as_ref()
+            .ok_or_missing_field("signature")?;
+
+        let payload = request
+            .inner()
+            .payload
+            .as_ref()
+            .ok_or_missing_field("payload")?;
+        self.verify_client_version(payload.client_metadata.as_ref())?;
+
+        // multipart uploads are only provisioned for attachments of a group
+        let ear_key = payload.ear_key()?;
+        let qgid = payload.validated_qgid(self.ds.own_domain())?;
+        let sender_index = payload.sender.ok_or_missing_field("sender")?.into();
+
+        let (_group_data, group_state) = self
+            .load_group_state_immutable(&qgid, &ear_key)
+            .await
+            .map_err(to_status)?;
+
+        let sender_credential = sender_client_credential(&group_state, sender_index)?;
+
+        let payload: GetAttachmentPartUploadUrlPayload = request
+            .verify(sender_credential.verifying_key())
+            .map_err(InvalidSignature)?;
+
+        // the upload must have been provisioned by the sender in this group
+        let object_id = payload.object_id.ok_or_missing_field("object_id")?.into();
+        let owner = AttachmentUploadOwner {
+            group_id: qgid.group_uuid(),
+            sender_index,
+        };
+
+        let response = self
+            .ds
+            .part_upload_url(
+                object_id,
+                owner,
+                payload.part_number,
+                payload.content_length,
+            )
+            .await?;
+
+        Ok(Response::new(response))
+    }
+
+    async fn complete_attachment_upload(
+        &self,
+        request: Request<SignedRequest<CompleteAttachmentUploadRequest>>,
+    ) -> Result<Response<CompleteAttachmentUploadResponse>, Status> {
+        let request = request.into_inner();
+
+        request
+            .inner()
+            .signature
+            .as_ref()
+            .ok_or_missing_field("signature")?;
+
+        let payload = request
+            .inner()
+            .payload
+            .as_ref()
+            .ok_or_missing_field("payload")?;
+        self.verify_client_version(payload.client_metadata.as_ref())?;
+
+        // multipart uploads are only provisioned for attachments of a group
+        let ear_key = payload.ear_key()?;
+        let qgid = payload.validated_qgid(self.ds.own_domain())?;
+        let sender_index = payload.sender.ok_or_missing_field("sender")?.into();
+
+        let (_group_data, group_state) = self
+            .load_group_state_immutable(&qgid, &ear_key)
+            .await
+            .map_err(to_status)?;
+
+        let sender_credential = sender_client_credential(&group_state, sender_index)?;
+
+        let payload: CompleteAttachmentUploadPayload = request
+            .verify(sender_credential.verifying_key())
+            .map_err(InvalidSignature)?;
+
+        // the upload must have been provisioned by the sender in this group
+        let object_id = payload.object_id.ok_or_missing_field("object_id")?.into();
+        let owner = AttachmentUploadOwner {
+            group_id: qgid.group_uuid(),
+            sender_index,
+        };
+
+        self.ds
+            .complete_upload(object_id, owner, payload.parts)
+            .await?;
+
+        Ok(Response::new(CompleteAttachmentUploadResponse {}))
+    }
+
     async fn targeted_message(
         &self,
         request: Request<SignedRequest<TargetedMessageRequest>>,
@@ -2229,6 +2360,26 @@ impl WithQualifiedGroupId for GetAttachmentUrlPayload {
     }
 }
 
+impl WithQualifiedGroupId for GetAttachmentPartUploadUrlPayload {
+    fn qgid(&self) -> Result<QualifiedGroupId, Status> {
+        self.group_id
+            .as_ref()
+            .ok_or_missing_field("group_id")?
+            .try_ref_into()
+            .map_err(From::from)
+    }
+}
+
+impl WithQualifiedGroupId for CompleteAttachmentUploadPayload {
+    fn qgid(&self) -> Result<QualifiedGroupId, Status> {
+        self.group_id
+            .as_ref()
+            .ok_or_missing_field("group_id")?
+            .try_ref_into()
+            .map_err(From::from)
+    }
+}
+
 impl WithQualifiedGroupId for GroupSessionData {
     fn qgid(&self) -> Result<QualifiedGroupId, Status> {
         self.qgid
@@ -2335,6 +2486,18 @@ impl WithGroupStateEarKey for GetAttachmentUrlPayload {
     }
 }
 
+impl WithGroupStateEarKey for GetAttachmentPartUploadUrlPayload {
+    fn ear_key_proto(&self) -> Option<&v1::GroupStateEarKey> {
+        self.group_state_ear_key.as_ref()
+    }
+}
+
+impl WithGroupStateEarKey for CompleteAttachmentUploadPayload {
+    fn ear_key_proto(&self) -> Option<&v1::GroupStateEarKey> {
+        self.group_state_ear_key.as_ref()
+    }
+}
+
 impl WithGroupStateEarKey for GroupSessionData {
     fn ear_key_proto(&self) -> Option<&v1::GroupStateEarKey> {
         self.group_state_ear_key.as_ref()
@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use aircommon::{
-    messages::client_ds::{AadMessage, AadPayload, JoinConnectionGroupParams},
+    messages::{
+        AirProtocolVersion,
+        client_ds::{AadMessage, AadPayload, JoinConnectionGroupParams},
+    },
     time::{Duration, TimeStamp},
 };
 use mls_assist::{
@@ -69,7 +72,10 @@ impl DsGroupState {
                 tracing::warn!("Invalid message: Failed to deserialize AAD.");
                 JoinConnectionGroupError::InvalidMessage
             })?;
-        // TODO: Check version of Aad Message
+        // The payload is interpreted according to the version of the AAD message
+        match aad_message.version() {
+            AirProtocolVersion::Alpha => {}
+        }
         let aad_payload = if let AadPayload::JoinConnectionGroup(aad) = aad_message.into_payload() {
             aad
         } else {
@@ -66,6 +66,11 @@ impl BackendService for Ds {
 }
 
 impl Ds {
+    /// Returns a reference to the database pool for spawning background tasks.
+    pub fn db_pool(&self) -> &PgPool {
+        &self.db_pool
+    }
+
     pub fn set_storage(&mut self, storage: Storage) {
         self.storage = Some(storage);
     }
@@ -173,6 +173,20 @@ impl Ds {
         }
         QualifiedGroupId::new(group_uuid, self.own_domain.clone())
     }
+
+    /// Reserves the group id with the given UUID.
+    ///
+    /// Returns `None` if the UUID is already reserved. Creating the group
+    /// still fails later if a group with the same id already exists.
+    #[cfg(feature = "test_utils")]
+    pub(crate) async fn request_specific_group_id(
+        &self,
+        group_uuid: Uuid,
+    ) -> Option<QualifiedGroupId> {
+        self.reserve_group_id(group_uuid)
+            .await
+            .then(|| QualifiedGroupId::new(group_uuid, self.own_domain.clone()))
+    }
 }
 
 #[derive(Debug)]
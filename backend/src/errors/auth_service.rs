@@ -66,6 +66,33 @@ impl From<DeleteUserError> for Status {
     }
 }
 
+#[derive(Error, Debug)]
+pub(crate) enum ReportSpamError {
+    /// Storage provider error
+    #[error("Storage provider error")]
+    StorageError,
+    /// Users can't report themselves
+    #[error("Users can't report themselves")]
+    SelfReport,
+}
+
+impl From<sqlx::Error> for ReportSpamError {
+    fn from(e: sqlx::Error) -> Self {
+        error!(%e, "Error storing spam report");
+        ReportSpamError::StorageError
+    }
+}
+
+impl From<ReportSpamError> for Status {
+    fn from(e: ReportSpamError) -> Self {
+        let msg = e.to_string();
+        match e {
+            ReportSpamError::StorageError => Status::internal(msg),
+            ReportSpamError::SelfReport => Status::invalid_argument(msg),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub(crate) enum PublishConnectionPackageError {
     /// Storage provider error
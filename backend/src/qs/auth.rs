@@ -19,12 +19,29 @@ use airprotos::{
     },
     signed::{SignedRequest, VerifiableRequest},
 };
-use tonic::Status;
+use tonic::{Request, Status};
 use tracing::error;
 
 use crate::qs::{client_record::QsClientRecord, grpc::GrpcQs, user_record::UserRecord};
 
 impl GrpcQs {
+    /// Verifies that the request carries the admin token as bearer token.
+    pub(super) fn verify_admin_auth<R>(&self, request: &Request<R>) -> Result<(), Status> {
+        let Some(admin_token) = self.qs.admin_token.as_deref() else {
+            return Err(Status::permission_denied("admin operations are disabled"));
+        };
+        let token = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("missing admin token"))?;
+        if !constant_time_eq(token.as_bytes(), admin_token.as_bytes()) {
+            return Err(Status::unauthenticated("invalid admin token"));
+        }
+        Ok(())
+    }
+
     /// Verifies request with QS user authentication.
     pub(super) async fn verify_user_auth<R, P, const TAG: u32>(
         &self,
@@ -182,6 +199,7 @@ impl WithQsUserId for CreateClientRequest {
             queue_encryption_key,
             encrypted_push_token,
             initial_ratched_secret,
+            additional_push_tokens: Vec::new(),
         }
     }
 }
@@ -227,6 +245,7 @@ impl WithQsClientId for UpdateClientRequest {
             client_record_auth_key,
             queue_encryption_key,
             encrypted_push_token,
+            additional_push_tokens: Vec::new(),
         }
     }
 }
@@ -306,3 +325,8 @@ impl WithQsClientId for InitListenRequest {
         }
     }
 }
+
+/// Compares the two byte strings in time independent of their content.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
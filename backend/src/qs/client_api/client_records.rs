@@ -27,7 +27,7 @@ impl Qs {
             sender,
             client_record_auth_key,
             queue_encryption_key,
-            encrypted_push_token,
+            encrypted_push_tokens,
             initial_ratchet_secret,
         } = params;
 
@@ -42,7 +42,7 @@ impl Qs {
             &mut connection,
             TimeStamp::now(),
             sender,
-            encrypted_push_token,
+            encrypted_push_tokens,
             queue_encryption_key,
             client_record_auth_key,
             ratchet_key,
@@ -70,7 +70,7 @@ impl Qs {
             sender,
             client_record_auth_key,
             queue_encryption_key,
-            encrypted_push_token,
+            encrypted_push_tokens,
         } = params;
 
         let mut transaction = self.db_pool.begin().await.map_err(|error| {
@@ -87,7 +87,7 @@ impl Qs {
 
         client_record.auth_key = client_record_auth_key;
         client_record.queue_encryption_key = queue_encryption_key;
-        client_record.encrypted_push_token = encrypted_push_token;
+        client_record.encrypted_push_tokens = encrypted_push_tokens;
 
         client_record
             .update(&mut *transaction)
@@ -41,7 +41,7 @@ impl Qs {
             sender: user_record.user_id,
             client_record_auth_key,
             queue_encryption_key,
-            encrypted_push_token,
+            encrypted_push_tokens: encrypted_push_token.into_iter().collect(),
             initial_ratchet_secret,
         };
 
@@ -48,7 +48,8 @@ pub(super) enum QueueMessageType {
 pub(super) struct QsClientRecord<const UPDATABLE: bool = true> {
     pub(super) user_id: QsUserId,
     pub(super) client_id: QsClientId,
-    pub(super) encrypted_push_token: Option<EncryptedPushToken>,
+    /// Push tokens of all devices notified about messages in the queue
+    pub(super) encrypted_push_tokens: Vec<EncryptedPushToken>,
     pub(super) queue_encryption_key: RatchetEncryptionKey,
     pub(super) auth_key: QsClientVerifyingKey,
     pub(super) ratchet_key: QsQueueRatchet,
@@ -60,7 +61,7 @@ impl QsClientRecord {
         connection: &mut PgConnection,
         now: TimeStamp,
         user_id: QsUserId,
-        encrypted_push_token: Option<EncryptedPushToken>,
+        encrypted_push_tokens: Vec<EncryptedPushToken>,
         queue_encryption_key: RatchetEncryptionKey,
         auth_key: QsClientVerifyingKey,
         ratchet_key: QsQueueRatchet,
@@ -69,7 +70,7 @@ impl QsClientRecord {
         let record = Self {
             user_id,
             client_id,
-            encrypted_push_token,
+            encrypted_push_tokens,
             queue_encryption_key,
             auth_key,
             ratchet_key,
@@ -101,13 +102,13 @@ pub(crate) mod persistence {
             query!(
                 "INSERT INTO
                     qs_client_record
-                    (client_id, user_id, encrypted_push_token, owner_public_key,
+                    (client_id, user_id, encrypted_push_tokens, owner_public_key,
                     owner_signature_key, ratchet, activity_time)
                 VALUES
                     ($1, $2, $3, $4, $5, $6, $7)",
                 &self.client_id as &QsClientId,
                 &self.user_id as &QsUserId,
-                self.encrypted_push_token.as_ref() as Option<&EncryptedPushToken>,
+                &self.encrypted_push_tokens as &[EncryptedPushToken],
                 owner_public_key as _,
                 owner_signature_key as _,
                 ratchet as _,
@@ -128,7 +129,7 @@ pub(crate) mod persistence {
             let record = sqlx::query!(
                 r#"SELECT
                     user_id as "user_id: QsUserId",
-                    encrypted_push_token as "encrypted_push_token: EncryptedPushToken",
+                    encrypted_push_tokens as "encrypted_push_tokens: Vec<EncryptedPushToken>",
                     owner_public_key AS "owner_public_key: BlobDecoded<RatchetEncryptionKey>",
                     owner_signature_key AS "owner_signature_key: BlobDecoded<QsClientVerifyingKey>",
                     ratchet AS "ratchet: BlobDecoded<QsQueueRatchet>",
@@ -144,7 +145,7 @@ pub(crate) mod persistence {
             Ok(record.map(|record| QsClientRecord {
                 user_id: record.user_id,
                 client_id: (*client_id).into(),
-                encrypted_push_token: record.encrypted_push_token,
+                encrypted_push_tokens: record.encrypted_push_tokens,
                 queue_encryption_key: record.owner_public_key.into_inner(),
                 auth_key: record.owner_signature_key.into_inner(),
                 ratchet_key: record.ratchet.into_inner(),
@@ -161,7 +162,7 @@ pub(crate) mod persistence {
             let record = sqlx::query!(
                 r#"SELECT
                     user_id as "user_id: QsUserId",
-                    encrypted_push_token as "encrypted_push_token: EncryptedPushToken",
+                    encrypted_push_tokens as "encrypted_push_tokens: Vec<EncryptedPushToken>",
                     owner_public_key AS "owner_public_key: BlobDecoded<RatchetEncryptionKey>",
                     owner_signature_key AS "owner_signature_key: BlobDecoded<QsClientVerifyingKey>",
                     ratchet AS "ratchet: BlobDecoded<QsQueueRatchet>",
@@ -178,7 +179,7 @@ pub(crate) mod persistence {
             Ok(record.map(|record| QsClientRecord {
                 user_id: record.user_id,
                 client_id: (*client_id).into(),
-                encrypted_push_token: record.encrypted_push_token,
+                encrypted_push_tokens: record.encrypted_push_tokens,
                 queue_encryption_key: record.owner_public_key.into_inner(),
                 auth_key: record.owner_signature_key.into_inner(),
                 ratchet_key: record.ratchet.into_inner(),
@@ -239,7 +240,7 @@ pub(crate) mod persistence {
                     user_id,
                     deleted_at,
                     activity_time,
-                    encrypted_push_token,
+                    encrypted_push_tokens,
                     owner_public_key,
                     owner_signature_key,
                     ratchet
@@ -249,7 +250,7 @@ pub(crate) mod persistence {
                     user_id,
                     NOW(),
                     NOW(),
-                    NULL,
+                    '{}',
                     '\x',
                     '\x',
                     '\x'
@@ -293,24 +294,34 @@ pub(crate) mod persistence {
             }
         }
 
-        /// Deletes token from client's database record if it still set.
-        pub(in crate::qs) async fn delete_push_token(
+        /// Deletes the given tokens from the client's database record if its tokens were not
+        /// changed in the meantime.
+        pub(in crate::qs) async fn delete_push_tokens(
             &self,
             executor: impl PgExecutor<'_>,
+            invalid_push_tokens: &[EncryptedPushToken],
         ) -> sqlx::Result<()> {
-            if let Some(encrypted_push_token) = self.encrypted_push_token.as_ref() {
-                query!(
-                    "UPDATE qs_client_record
-                    SET encrypted_push_token = NULL
-                    WHERE client_id = $1
-                        AND encrypted_push_token = $2
-                        AND deleted_at IS NULL",
-                    self.client_id as _,
-                    encrypted_push_token as _,
-                )
-                .execute(executor)
-                .await?;
+            let remaining_push_tokens: Vec<EncryptedPushToken> = self
+                .encrypted_push_tokens
+                .iter()
+                .filter(|token| !invalid_push_tokens.contains(token))
+                .cloned()
+                .collect();
+            if remaining_push_tokens.len() == self.encrypted_push_tokens.len() {
+                return Ok(());
             }
+            query!(
+                "UPDATE qs_client_record
+                SET encrypted_push_tokens = $2
+                WHERE client_id = $1
+                    AND encrypted_push_tokens = $3
+                    AND deleted_at IS NULL",
+                self.client_id as _,
+                &remaining_push_tokens as &[EncryptedPushToken],
+                &self.encrypted_push_tokens as &[EncryptedPushToken],
+            )
+            .execute(executor)
+            .await?;
             Ok(())
         }
     }
@@ -327,7 +338,7 @@ pub(crate) mod persistence {
             query!(
                 "UPDATE qs_client_record
                 SET
-                    encrypted_push_token = $1,
+                    encrypted_push_tokens = $1,
                     owner_public_key = $2,
                     owner_signature_key = $3,
                     ratchet = $4,
@@ -335,7 +346,7 @@ pub(crate) mod persistence {
                 WHERE
                     client_id = $6
                     AND deleted_at IS NULL",
-                self.encrypted_push_token.as_ref() as Option<&EncryptedPushToken>,
+                &self.encrypted_push_tokens as &[EncryptedPushToken],
                 owner_public_key as _,
                 owner_signature_key as _,
                 ratchet as _,
@@ -380,7 +391,7 @@ pub(crate) mod persistence {
             QsClientRecord {
                 user_id,
                 client_id: QsClientId::random(&mut rand::rng()),
-                encrypted_push_token: Some(EncryptedPushToken::dummy()),
+                encrypted_push_tokens: vec![EncryptedPushToken::dummy()],
                 queue_encryption_key: RatchetEncryptionKey::new_for_test(
                     b"encryption_key_32_bytes".to_vec(),
                 ),
@@ -480,7 +491,7 @@ pub(crate) mod persistence {
         }
 
         #[sqlx::test]
-        async fn delete_push_token(pool: PgPool) -> anyhow::Result<()> {
+        async fn delete_push_tokens(pool: PgPool) -> anyhow::Result<()> {
             let user_record = store_random_user_record(&pool).await?;
             let mut client_record = store_random_client_record(&pool, user_record.user_id).await?;
 
@@ -490,15 +501,18 @@ pub(crate) mod persistence {
             assert_eq!(loaded, client_record);
 
             // push token is deleted
-            client_record.delete_push_token(&pool).await?;
+            client_record
+                .delete_push_tokens(&pool, &[EncryptedPushToken::dummy()])
+                .await?;
             let loaded = QsClientRecord::load_for_update(&pool, &client_record.client_id)
                 .await?
                 .expect("missing client record");
-            assert_eq!(loaded.encrypted_push_token, None);
+            assert_eq!(loaded.encrypted_push_tokens, []);
 
-            // push token is not deleted because it is a different one
-            let push_token = EncryptedPushToken::random();
-            client_record.encrypted_push_token = Some(push_token.clone());
+            // only the invalid push token is deleted
+            let push_token_a = EncryptedPushToken::random();
+            let push_token_b = EncryptedPushToken::random();
+            client_record.encrypted_push_tokens = vec![push_token_a.clone(), push_token_b.clone()];
             client_record.update(&pool).await?;
 
             let loaded = QsClientRecord::load(&pool, &client_record.client_id)
@@ -506,20 +520,22 @@ pub(crate) mod persistence {
                 .expect("missing client record");
             assert_eq!(loaded, client_record);
 
-            client_record.encrypted_push_token = Some(EncryptedPushToken::random());
-            client_record.delete_push_token(&pool).await?;
+            client_record
+                .delete_push_tokens(&pool, std::slice::from_ref(&push_token_a))
+                .await?;
             let loaded = QsClientRecord::load(&pool, &client_record.client_id)
                 .await?
                 .expect("missing client record");
-            assert_eq!(loaded.encrypted_push_token, Some(push_token.clone()));
+            assert_eq!(loaded.encrypted_push_tokens, [push_token_b.clone()]);
 
-            // push token is not deleted because it is not set
-            client_record.encrypted_push_token = None;
-            client_record.delete_push_token(&pool).await?;
+            // push tokens are not deleted because they were changed in the meantime
+            client_record
+                .delete_push_tokens(&pool, std::slice::from_ref(&push_token_b))
+                .await?;
             let loaded = QsClientRecord::load(&pool, &client_record.client_id)
                 .await?
                 .expect("missing client record");
-            assert_eq!(loaded.encrypted_push_token, Some(push_token));
+            assert_eq!(loaded.encrypted_push_tokens, [push_token_b]);
 
             Ok(())
         }
@@ -544,81 +560,21 @@ impl QsClientRecord {
                     Self::do_enqueue(pool, client_id, queues, queue_message).await?;
 
                 // Try to send a notification over the websocket, otherwise use push tokens if available
-                if !has_listener {
-                    trace!("Trying to send push notification");
-
-                    // Send a push notification under the following conditions:
-                    // - there is a push token associated with the queue
-                    // - there is a push token decryption key
-                    // - the decryption is successful
-                    if let Some(ref encrypted_push_token) = client_record.encrypted_push_token
-                        && let Some(ear_key) = push_token_key_option
-                    {
-                        // Attempt to decrypt the push token.
-                        match PushToken::decrypt(ear_key, encrypted_push_token) {
-                            Err(error) => {
-                                error!(%error, "Push token decryption failed");
-                            }
-                            Ok(push_token) => {
-                                trace!("Send push notification");
-
-                                // Send the push notification.
-                                if let Err(e) = push_notification_provider.push(push_token).await {
-                                    match e {
-                                        // The push notification failed for some other reason.
-                                        PushNotificationError::Other(error_description) => {
-                                            error!(
-                                                %error_description,
-                                                "Push notification failed unexpectedly",
-                                            )
-                                        }
-                                        // The token is no longer valid and should be deleted.
-                                        PushNotificationError::InvalidToken(error_description) => {
-                                            info!(
-                                                %error_description,
-                                                "Push notification failed because the token is invalid",
-                                            );
-                                            client_record.delete_push_token(pool).await?;
-                                        }
-                                        // There was a network error when trying to send the push notification.
-                                        PushNotificationError::NetworkError(error) => {
-                                            info!(
-                                                %error,
-                                                "Push notification failed because of a network error",
-                                            )
-                                        }
-                                        PushNotificationError::UnsupportedType => {
-                                            warn!(
-                                                "Push notification failed because the push token type is unsupported",
-                                            )
-                                        }
-                                        PushNotificationError::JwtCreationError(error) => {
-                                            error!(
-                                                error,
-                                                "Push notification failed because the JWT token could not be created",
-                                            )
-                                        }
-                                        PushNotificationError::OAuthError(error) => {
-                                            error!(
-                                                %error,
-                                                "Push notification failed because of an OAuth error",
-                                            )
-                                        }
-                                        PushNotificationError::InvalidConfiguration(error) => {
-                                            error!(
-                                                error,
-                                                "Push notification failed because of an invalid configuration",
-                                            )
-                                        }
-                                        PushNotificationError::InvalidBearer => {
-                                            error!(
-                                                "Push notification failed because of an invalid bearer"
-                                            )
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                // Send push notifications under the following conditions:
+                // - there are push tokens associated with the queue
+                // - there is a push token decryption key
+                if !has_listener
+                    && !client_record.encrypted_push_tokens.is_empty()
+                    && let Some(ear_key) = push_token_key_option
+                {
+                    trace!("Trying to send push notifications");
+                    let invalid_push_tokens = client_record
+                        .push_notifications(ear_key, push_notification_provider)
+                        .await;
+                    if !invalid_push_tokens.is_empty() {
+                        client_record
+                            .delete_push_tokens(pool, &invalid_push_tokens)
+                            .await?;
                     }
                 }
             }
@@ -671,6 +627,89 @@ impl QsClientRecord {
 
         Ok((client_record, has_listener))
     }
+
+    /// Sends a push notification to each device of the client.
+    ///
+    /// Tokens of the same device are only notified once. Returns the tokens which the provider
+    /// reported as invalid.
+    async fn push_notifications<P: PushNotificationProvider>(
+        &self,
+        ear_key: &PushTokenEarKey,
+        push_notification_provider: &P,
+    ) -> Vec<EncryptedPushToken> {
+        let mut push_tokens: Vec<(PushToken, Vec<&EncryptedPushToken>)> = Vec::new();
+        for encrypted_push_token in &self.encrypted_push_tokens {
+            match PushToken::decrypt(ear_key, encrypted_push_token) {
+                Ok(push_token) => {
+                    match push_tokens
+                        .iter_mut()
+                        .find(|(token, _)| *token == push_token)
+                    {
+                        Some((_, encrypted)) => encrypted.push(encrypted_push_token),
+                        None => push_tokens.push((push_token, vec![encrypted_push_token])),
+                    }
+                }
+                Err(error) => {
+                    error!(%error, "Push token decryption failed");
+                }
+            }
+        }
+
+        trace!(num_devices = push_tokens.len(), "Send push notifications");
+        let (push_tokens, encrypted_push_tokens): (Vec<_>, Vec<_>) =
+            push_tokens.into_iter().unzip();
+        let results = push_notification_provider.push_all(push_tokens).await;
+
+        let mut invalid_push_tokens = Vec::new();
+        for (result, encrypted_push_tokens) in results.into_iter().zip(encrypted_push_tokens) {
+            let Err(error) = result else {
+                continue;
+            };
+            match error {
+                // The push notification failed for some other reason.
+                PushNotificationError::Other(error_description) => {
+                    error!(%error_description, "Push notification failed unexpectedly")
+                }
+                // The token is no longer valid and should be deleted.
+                PushNotificationError::InvalidToken(error_description) => {
+                    info!(
+                        %error_description,
+                        "Push notification failed because the token is invalid",
+                    );
+                    invalid_push_tokens.extend(encrypted_push_tokens.into_iter().cloned());
+                }
+                // There was a network error when trying to send the push notification.
+                PushNotificationError::NetworkError(error) => {
+                    info!(
+                        %error,
+                        "Push notification failed because of a network error",
+                    )
+                }
+                PushNotificationError::UnsupportedType => {
+                    warn!("Push notification failed because the push token type is unsupported")
+                }
+                PushNotificationError::JwtCreationError(error) => {
+                    error!(
+                        error,
+                        "Push notification failed because the JWT token could not be created",
+                    )
+                }
+                PushNotificationError::OAuthError(error) => {
+                    error!(%error, "Push notification failed because of an OAuth error")
+                }
+                PushNotificationError::InvalidConfiguration(error) => {
+                    error!(
+                        error,
+                        "Push notification failed because of an invalid configuration",
+                    )
+                }
+                PushNotificationError::InvalidBearer => {
+                    error!("Push notification failed because of an invalid bearer")
+                }
+            }
+        }
+        invalid_push_tokens
+    }
 }
 
 #[cfg(test)]
@@ -684,7 +723,7 @@ mod tests {
 
     use crate::qs::{
         client_record::persistence::tests::store_random_client_record,
-        queue::{Queue, Queues},
+        queue::{PurgedQueue, Queue, Queues},
         user_record::persistence::tests::store_random_user_record,
     };
 
@@ -754,6 +793,110 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn purge_queue(pool: PgPool) -> anyhow::Result<()> {
+        let user_record = store_random_user_record(&pool).await?;
+        let client_record = store_random_client_record(&pool, user_record.user_id).await?;
+        let other_client_record = store_random_client_record(&pool, user_record.user_id).await?;
+        let queues = Queues::new(pool.clone(), CancellationToken::new()).await?;
+
+        let payload = QsQueueMessagePayload {
+            timestamp: TimeStamp::now(),
+            message_type: QsQueueMessageType::WelcomeBundle,
+            payload: vec![0, 1, 2, 3],
+        };
+        const N: u64 = 3;
+        for client_id in [client_record.client_id, other_client_record.client_id] {
+            for _ in 0..N {
+                QsClientRecord::do_enqueue(&pool, client_id, &queues, &payload).await?;
+            }
+        }
+
+        let purged = queues.purge(client_record.client_id).await?.unwrap();
+        assert_eq!(
+            purged,
+            PurgedQueue {
+                purged_messages: N,
+                next_sequence_number: N,
+            }
+        );
+
+        let mut queue_messages = VecDeque::new();
+        Queue::fetch_into(&pool, &client_record.client_id, 0, 10, &mut queue_messages).await?;
+        assert!(queue_messages.is_empty());
+
+        // Other queues are not affected
+        Queue::fetch_into(
+            &pool,
+            &other_client_record.client_id,
+            0,
+            10,
+            &mut queue_messages,
+        )
+        .await?;
+        assert_eq!(queue_messages.len(), N as usize);
+
+        // Purging is idempotent
+        let purged = queues.purge(client_record.client_id).await?.unwrap();
+        assert_eq!(
+            purged,
+            PurgedQueue {
+                purged_messages: 0,
+                next_sequence_number: N,
+            }
+        );
+
+        // The sequence number continues after the purged messages
+        QsClientRecord::do_enqueue(&pool, client_record.client_id, &queues, &payload).await?;
+        let mut queue_messages = VecDeque::new();
+        Queue::fetch_into(&pool, &client_record.client_id, 0, 10, &mut queue_messages).await?;
+        let sequence_numbers: Vec<_> = queue_messages.iter().map(|m| m.sequence_number).collect();
+        assert_eq!(sequence_numbers, [N]);
+
+        assert!(
+            queues
+                .purge(QsClientId::random(&mut rand::rng()))
+                .await?
+                .is_none()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn delete_expired_queue_messages(pool: PgPool) -> anyhow::Result<()> {
+        let user_record = store_random_user_record(&pool).await?;
+        let client_record = store_random_client_record(&pool, user_record.user_id).await?;
+        let queues = Queues::new(pool.clone(), CancellationToken::new()).await?;
+
+        let payload = QsQueueMessagePayload {
+            timestamp: TimeStamp::now(),
+            message_type: QsQueueMessageType::WelcomeBundle,
+            payload: vec![0, 1, 2, 3],
+        };
+        for _ in 0..3 {
+            QsClientRecord::do_enqueue(&pool, client_record.client_id, &queues, &payload).await?;
+        }
+        // Age the first two messages
+        sqlx::query(
+            "UPDATE qs_queues SET created_at = now() - INTERVAL '2 days'
+            WHERE sequence_number < 2",
+        )
+        .execute(&pool)
+        .await?;
+
+        let expired =
+            Queue::delete_expired(&pool, chrono::Utc::now() - chrono::Duration::days(1)).await?;
+        assert_eq!(expired, 2);
+
+        let mut queue_messages = VecDeque::new();
+        Queue::fetch_into(&pool, &client_record.client_id, 0, 10, &mut queue_messages).await?;
+        let sequence_numbers: Vec<_> = queue_messages.iter().map(|m| m.sequence_number).collect();
+        assert_eq!(sequence_numbers, [2]);
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn load_client_ids(pool: PgPool) -> anyhow::Result<()> {
         use std::collections::HashSet;
@@ -795,4 +938,67 @@ mod tests {
 
         Ok(())
     }
+
+    /// Push notification provider which records the pushed tokens.
+    #[derive(Debug, Default)]
+    struct RecordingPushNotificationProvider {
+        pushed: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl PushNotificationProvider for RecordingPushNotificationProvider {
+        async fn push(&self, push_token: PushToken) -> Result<(), PushNotificationError> {
+            let token = push_token.token().to_owned();
+            self.pushed.lock().unwrap().push(token.clone());
+            if token == "unregistered" {
+                Err(PushNotificationError::InvalidToken(token))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[sqlx::test]
+    async fn push_notifications_to_all_devices(pool: PgPool) -> anyhow::Result<()> {
+        use aircommon::{crypto::aead::AeadEncryptable, messages::push_token::PushTokenOperator};
+
+        let user_record = store_random_user_record(&pool).await?;
+        let mut client_record = store_random_client_record(&pool, user_record.user_id).await?;
+
+        let ear_key = PushTokenEarKey::random()?;
+        let encrypt = |token: &str| {
+            PushToken::new(PushTokenOperator::Google, token.to_owned()).encrypt(&ear_key)
+        };
+        let phone = encrypt("phone")?;
+        let phone_duplicate = encrypt("phone")?;
+        let unregistered = encrypt("unregistered")?;
+        let tablet = encrypt("tablet")?;
+        client_record.encrypted_push_tokens = vec![
+            phone.clone(),
+            unregistered.clone(),
+            phone_duplicate,
+            tablet.clone(),
+        ];
+        client_record.update(&pool).await?;
+
+        let provider = RecordingPushNotificationProvider::default();
+        let invalid_push_tokens = client_record.push_notifications(&ear_key, &provider).await;
+
+        // Each device is notified once
+        assert_eq!(
+            *provider.pushed.lock().unwrap(),
+            ["phone", "unregistered", "tablet"]
+        );
+        assert_eq!(invalid_push_tokens, [unregistered.clone()]);
+
+        client_record
+            .delete_push_tokens(&pool, &invalid_push_tokens)
+            .await?;
+        let loaded = QsClientRecord::load(&pool, &client_record.client_id)
+            .await?
+            .context("no client record")?;
+        assert_eq!(loaded.encrypted_push_tokens.len(), 3);
+        assert!(!loaded.encrypted_push_tokens.contains(&unregistered));
+
+        Ok(())
+    }
 }
@@ -5,6 +5,7 @@
 use aircommon::{
     crypto::hpke::HpkeDecryptable, identifiers::ClientConfig, messages::AirProtocolVersion,
 };
+use metrics::counter;
 use tls_codec::Serialize;
 use tracing::error;
 
@@ -13,7 +14,7 @@ use crate::{
         intra_backend::DsFanOutMessage,
         qs_qs::{QsToQsMessage, QsToQsPayload},
     },
-    qs::errors::EnqueueError,
+    qs::{METRIC_AIR_QS_ENQUEUED_MESSAGES, errors::EnqueueError},
 };
 
 use super::{
@@ -38,21 +39,19 @@ impl Qs {
         message: DsFanOutMessage,
     ) -> Result<(), QsEnqueueError<N>> {
         let own_domain = self.domain.clone();
-        if message.client_reference.client_homeserver_domain != own_domain {
+        let recipient_domain = message.client_reference.client_homeserver_domain.clone();
+        if recipient_domain != own_domain {
             let qs_to_qs_message = QsToQsMessage {
                 protocol_version: AirProtocolVersion::Alpha,
                 sender: own_domain.clone(),
-                recipient: message.client_reference.client_homeserver_domain.clone(),
+                recipient: recipient_domain.clone(),
                 payload: QsToQsPayload::FanOutMessageRequest(message.clone()),
             };
             let serialized_message = qs_to_qs_message
                 .tls_serialize_detached()
                 .map_err(|_| QsEnqueueError::LibraryError)?;
             network_provider
-                .deliver(
-                    serialized_message,
-                    message.client_reference.client_homeserver_domain,
-                )
+                .deliver(serialized_message, recipient_domain.clone())
                 .await
                 .map_err(QsEnqueueError::NetworkError)
                 .and_then(|result| {
@@ -61,7 +60,9 @@ impl Qs {
                     } else {
                         Err(QsEnqueueError::InvalidResponse)
                     }
-                })?
+                })?;
+            counter!(METRIC_AIR_QS_ENQUEUED_MESSAGES, "domain" => recipient_domain.to_string())
+                .increment(1);
         } else {
             let decryption_key = StorableClientIdDecryptionKey::load(&self.db_pool)
                 .await
@@ -105,7 +106,13 @@ impl Qs {
                 )
                 .await
                 {
-                    Ok(()) => (),
+                    Ok(()) => {
+                        counter!(
+                            METRIC_AIR_QS_ENQUEUED_MESSAGES,
+                            "domain" => own_domain.to_string(),
+                        )
+                        .increment(1);
+                    }
                     Err(EnqueueError::ClientNotFound) => {
                         // Sibling was soft-deleted mid fan-out => drop silently
                     }
@@ -217,6 +224,8 @@ mod tests {
         )
         .await?;
 
+        assert_eq!(Queue::count(&pool).await?, 2);
+
         for client in [client_a, client_b] {
             let mut buf = VecDeque::new();
             let client_id = client.client_id;
@@ -18,6 +18,7 @@ use aircommon::{
         DeleteUserRecordParams, KeyPackageParams, PublishKeyPackagesParams,
         UpdateClientRecordParams, UpdateUserRecordParams,
     },
+    messages::push_token,
     time::TimeStamp,
     utils::CancellableStream,
 };
@@ -37,6 +38,9 @@ use crate::{
 
 use super::Qs;
 
+/// Maximum number of push tokens of a single client
+const MAX_PUSH_TOKENS: usize = 16;
+
 pub struct GrpcQs {
     pub(super) qs: Qs,
 }
@@ -279,6 +283,7 @@ impl QueueService for GrpcQs {
             queue_encryption_key,
             encrypted_push_token,
             initial_ratched_secret,
+            additional_push_tokens,
         } = self.verify_user_auth(request).await?;
         self.verify_client_version(client_metadata.as_ref())?;
         let params = CreateClientRecordParams {
@@ -289,9 +294,10 @@ impl QueueService for GrpcQs {
             queue_encryption_key: queue_encryption_key
                 .ok_or_missing_field("queue_encryption_key")?
                 .into(),
-            encrypted_push_token: encrypted_push_token
-                .map(|token| token.try_into())
-                .transpose()?,
+            encrypted_push_tokens: encrypted_push_tokens(
+                encrypted_push_token,
+                additional_push_tokens,
+            )?,
             initial_ratchet_secret: initial_ratched_secret
                 .ok_or_missing_field("initial_ratched_secret")?
                 .try_into()?,
@@ -321,6 +327,7 @@ impl QueueService for GrpcQs {
             client_record_auth_key,
             queue_encryption_key,
             encrypted_push_token,
+            additional_push_tokens,
         } = self.verify_client_auth(request).await?;
         let params = UpdateClientRecordParams {
             sender: sender.ok_or_missing_field("sender")?.try_into()?,
@@ -330,9 +337,10 @@ impl QueueService for GrpcQs {
             queue_encryption_key: queue_encryption_key
                 .ok_or_missing_field("queue_encryption_key")?
                 .into(),
-            encrypted_push_token: encrypted_push_token
-                .map(|token| token.try_into())
-                .transpose()?,
+            encrypted_push_tokens: encrypted_push_tokens(
+                encrypted_push_token,
+                additional_push_tokens,
+            )?,
         };
         self.qs.qs_update_client_record(params).await?;
         Ok(Response::new(UpdateClientResponse {}))
@@ -461,6 +469,28 @@ impl QueueService for GrpcQs {
         }))
     }
 
+    async fn purge_queue(
+        &self,
+        request: Request<PurgeQueueRequest>,
+    ) -> Result<Response<PurgeQueueResponse>, Status> {
+        self.verify_admin_auth(&request)?;
+        let request = request.into_inner();
+        let client_id = request
+            .client_id
+            .ok_or_missing_field("client_id")?
+            .try_into()?;
+        let purged = self
+            .qs
+            .queues
+            .purge(client_id)
+            .await?
+            .ok_or_else(|| Status::not_found("unknown QS client"))?;
+        Ok(Response::new(PurgeQueueResponse {
+            purged_messages: purged.purged_messages,
+            next_sequence_number: purged.next_sequence_number,
+        }))
+    }
+
     type ListenStream =
         Pin<Box<dyn Stream<Item = Result<ListenResponse, Status>> + Send + 'static>>;
 
@@ -545,6 +575,29 @@ impl QueueService for GrpcQs {
     }
 }
 
+/// Collects the push tokens of a client from a create or update request.
+///
+/// Duplicate tokens are dropped.
+fn encrypted_push_tokens(
+    encrypted_push_token: Option<EncryptedPushToken>,
+    additional_push_tokens: Vec<EncryptedPushToken>,
+) -> Result<Vec<push_token::EncryptedPushToken>, Status> {
+    let mut tokens = Vec::with_capacity(additional_push_tokens.len() + 1);
+    for token in encrypted_push_token
+        .into_iter()
+        .chain(additional_push_tokens)
+    {
+        let token = token.try_into()?;
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    if tokens.len() > MAX_PUSH_TOKENS {
+        return Err(Status::invalid_argument("too many push tokens"));
+    }
+    Ok(tokens)
+}
+
 #[derive(Debug, thiserror::Error, Display)]
 enum ListenQueueProtocolViolation {
     /// Missing initial request
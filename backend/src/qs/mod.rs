@@ -61,6 +61,8 @@
 //! smaller than the smallest requested one and responds with the requested
 //! messages.
 
+use std::sync::Arc;
+
 use aircommon::{
     crypto::signatures::keys::QsUserVerifyingKey,
     identifiers::{Fqdn, QsClientId, QsUserId},
@@ -68,16 +70,21 @@ use aircommon::{
 };
 use client_id_decryption_key::StorableClientIdDecryptionKey;
 
-use metrics::describe_gauge;
+use metrics::{describe_counter, describe_gauge};
 use semver::VersionReq;
 use sqlx::PgPool;
 use tokio_util::sync::CancellationToken;
+use tracing::info;
 
 use crate::{
     air_service::{BackendService, ServiceCreationError},
     errors::StorageError,
     messages::intra_backend::DsFanOutMessage,
-    qs::{queue::Queues, user_record::UserRecord},
+    qs::{
+        queue::{QUEUED_MESSAGES_METRICS_INTERVAL, Queues},
+        user_record::UserRecord,
+    },
+    settings::QueueSettings,
 };
 
 mod auth;
@@ -99,6 +106,8 @@ pub struct Qs {
     db_pool: PgPool,
     queues: Queues,
     client_version_req: Option<VersionReq>,
+    /// Token authenticating admin operations; admin operations are disabled if `None`
+    admin_token: Option<Arc<str>>,
     stop: CancellationToken,
 }
 
@@ -107,6 +116,10 @@ pub(crate) const METRIC_AIR_QS_MAU_USERS: &str = "air_qs_mau_users";
 pub(crate) const METRIC_AIR_QS_WAU_USERS: &str = "air_qs_wau_users";
 pub(crate) const METRIC_AIR_QS_DAU_USERS: &str = "air_qs_dau_users";
 pub(crate) const METRIC_AIR_ACTIVE_USERS: &str = "air_qs_active_users";
+pub(crate) const METRIC_AIR_QS_ENQUEUED_MESSAGES: &str = "air_qs_enqueued_messages_total";
+pub(crate) const METRIC_AIR_QS_DELIVERED_MESSAGES: &str = "air_qs_delivered_messages_total";
+pub(crate) const METRIC_AIR_QS_QUEUED_MESSAGES: &str = "air_qs_queued_messages";
+pub(crate) const METRIC_AIR_QS_EXPIRED_MESSAGES: &str = "air_qs_expired_messages_total";
 
 impl BackendService for Qs {
     async fn initialize(
@@ -127,12 +140,18 @@ impl BackendService for Qs {
         }
 
         let queues = Queues::new(db_pool.clone(), stop.clone()).await?;
+        queues.spawn_metrics_task(
+            domain.clone(),
+            QUEUED_MESSAGES_METRICS_INTERVAL,
+            stop.clone(),
+        );
 
         Ok(Self {
             domain,
             db_pool,
             queues,
             client_version_req,
+            admin_token: None,
             stop,
         })
     }
@@ -155,10 +174,47 @@ impl BackendService for Qs {
             METRIC_AIR_ACTIVE_USERS,
             "Number of currently connetected users"
         );
+        describe_counter!(
+            METRIC_AIR_QS_ENQUEUED_MESSAGES,
+            "Number of enqueued messages by domain of the recipient"
+        );
+        describe_counter!(
+            METRIC_AIR_QS_DELIVERED_MESSAGES,
+            "Number of messages delivered to listening clients"
+        );
+        describe_gauge!(
+            METRIC_AIR_QS_QUEUED_MESSAGES,
+            "Number of messages waiting in the queues of this domain"
+        );
+        describe_counter!(
+            METRIC_AIR_QS_EXPIRED_MESSAGES,
+            "Number of queued messages deleted because they expired"
+        );
     }
 }
 
 impl Qs {
+    /// Returns a reference to the database pool for spawning background tasks.
+    pub fn db_pool(&self) -> &PgPool {
+        &self.db_pool
+    }
+
+    /// Enables admin operations authenticated with the given token.
+    pub fn set_admin_token(&mut self, token: String) {
+        self.admin_token = Some(token.into());
+    }
+
+    /// Starts deleting expired queue messages according to the settings.
+    ///
+    /// Does nothing if no message TTL is configured.
+    pub fn expire_queue_messages(&self, settings: &QueueSettings) {
+        if let Some(ttl) = settings.messagettl {
+            info!(?ttl, sweep_interval = ?settings.sweepinterval, "Expiring queue messages");
+            self.queues
+                .spawn_expiry_task(ttl, settings.sweepinterval, self.stop.clone());
+        }
+    }
+
     pub(crate) fn queues(&self) -> &Queues {
         &self.queues
     }
@@ -218,6 +274,20 @@ pub trait PushNotificationProvider: std::fmt::Debug + Send + Sync + 'static {
         &self,
         push_token: PushToken,
     ) -> impl Future<Output = Result<(), PushNotificationError>> + Send;
+
+    /// Sends a push notification to each of the given tokens.
+    ///
+    /// The results are returned in the order of the tokens.
+    fn push_all(
+        &self,
+        push_tokens: Vec<PushToken>,
+    ) -> impl Future<Output = Vec<Result<(), PushNotificationError>>> + Send {
+        futures_util::future::join_all(
+            push_tokens
+                .into_iter()
+                .map(|push_token| self.push(push_token)),
+        )
+    }
 }
 
 pub trait QsConnector: Sync + Send + std::fmt::Debug + 'static {
@@ -2,32 +2,40 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::{borrow::Cow, collections::VecDeque, sync::Arc};
+use std::{borrow::Cow, collections::VecDeque, sync::Arc, time::Duration};
 
-use aircommon::identifiers::QsClientId;
+use aircommon::identifiers::{Fqdn, QsClientId};
 use airprotos::queue_service::v1::{
     ListenResponse, QueueEmpty, QueueEventPayload, QueueMessage, listen_response,
 };
+use chrono::Utc;
 use dashmap::DashMap;
 use futures_util::{Stream, stream};
-use metrics::gauge;
+use metrics::{counter, gauge};
 use semver::Version;
 use sqlx::{PgExecutor, PgPool, PgTransaction};
 use tokio::sync::mpsc;
 use tokio_stream::StreamExt;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error};
+use tracing::{debug, error, info};
 use uuid::Uuid;
 
 use crate::{
     errors::QueueError,
     pg_listen::{PgChannelName, PgListenerTaskHandle, spawn_pg_listener_task},
-    qs::METRIC_AIR_ACTIVE_USERS,
+    qs::client_record::QsClientRecord,
+    qs::{
+        METRIC_AIR_ACTIVE_USERS, METRIC_AIR_QS_DELIVERED_MESSAGES, METRIC_AIR_QS_EXPIRED_MESSAGES,
+        METRIC_AIR_QS_QUEUED_MESSAGES,
+    },
 };
 
 /// Maximum number of messages to fetch at once.
 const MAX_BUFFER_SIZE: usize = 32;
 
+/// Interval at which the number of queued messages is reported.
+pub(crate) const QUEUED_MESSAGES_METRICS_INTERVAL: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Clone)]
 pub(crate) struct Queues {
     pool: PgPool,
@@ -147,6 +155,86 @@ impl Queues {
         Ok(())
     }
 
+    /// Drops all messages in the queue.
+    ///
+    /// The sequence number of the queue is not reset: the next enqueued message continues with
+    /// the sequence number following the dropped messages, such that the client can ratchet
+    /// forward over the gap. Purging an empty queue is a no-op.
+    ///
+    /// Returns `None` if the client of the queue does not exist.
+    pub(crate) async fn purge(
+        &self,
+        queue_id: QsClientId,
+    ) -> Result<Option<PurgedQueue>, QueueError> {
+        let mut txn = self.pool.begin().await?;
+        // Lock the client record to not race with concurrent enqueues
+        let Some(client_record) = QsClientRecord::load_for_update(txn.as_mut(), &queue_id).await?
+        else {
+            return Ok(None);
+        };
+        let next_sequence_number = client_record.ratchet_key.sequence_number();
+        let purged_messages = Queue::delete(txn.as_mut(), queue_id, next_sequence_number).await?;
+        txn.commit().await?;
+        info!(%queue_id, purged_messages, next_sequence_number, "Purged queue");
+        Ok(Some(PurgedQueue {
+            purged_messages,
+            next_sequence_number,
+        }))
+    }
+
+    /// Spawns a task deleting messages older than `ttl` every `sweep_interval` until `stop` is
+    /// cancelled.
+    ///
+    /// Like purged messages, expired messages leave a gap in the sequence numbers of the queue,
+    /// over which the client ratchets forward.
+    pub(crate) fn spawn_expiry_task(
+        &self,
+        ttl: Duration,
+        sweep_interval: Duration,
+        stop: CancellationToken,
+    ) {
+        let Ok(ttl) = chrono::Duration::from_std(ttl) else {
+            error!(?ttl, "Invalid queue message TTL");
+            return;
+        };
+        let pool = self.pool.clone();
+        tokio::spawn(stop.run_until_cancelled_owned(async move {
+            loop {
+                tokio::time::sleep(sweep_interval).await;
+                match Queue::delete_expired(&pool, Utc::now() - ttl).await {
+                    Ok(expired) => {
+                        if expired > 0 {
+                            info!(expired, "Deleted expired queue messages");
+                        }
+                        counter!(METRIC_AIR_QS_EXPIRED_MESSAGES).increment(expired);
+                    }
+                    Err(error) => error!(%error, "Failed to delete expired queue messages"),
+                }
+            }
+        }));
+    }
+
+    /// Spawns a task reporting the number of messages in all queues of the domain every
+    /// `interval` until `stop` is cancelled.
+    pub(crate) fn spawn_metrics_task(
+        &self,
+        domain: Fqdn,
+        interval: Duration,
+        stop: CancellationToken,
+    ) {
+        let pool = self.pool.clone();
+        tokio::spawn(stop.run_until_cancelled_owned(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(error) = Queue::report_metrics(&pool, &domain).await {
+                    error!(%error, "Failed to report queued messages");
+                }
+            }
+        }));
+    }
+
     pub(crate) async fn trigger_fetch(&self, queue_id: QsClientId) -> Result<(), QueueError> {
         sqlx::query("SELECT pg_notify($1, '')")
             .bind(queue_id.pg_channel())
@@ -199,6 +287,15 @@ impl Queues {
     }
 }
 
+/// Result of purging a queue.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct PurgedQueue {
+    /// Number of dropped messages
+    pub(crate) purged_messages: u64,
+    /// Sequence number of the next message enqueued in the queue
+    pub(crate) next_sequence_number: u64,
+}
+
 impl PgChannelName for QsClientId {
     fn pg_channel(&self) -> String {
         format!("qs_{}", self.as_uuid())
@@ -258,6 +355,7 @@ impl<S: Stream<Item = ()> + Send + Unpin> QueueStreamContext<S> {
             async |mut context| -> Option<(Option<QueueMessage>, Self)> {
                 loop {
                     if let Some(message) = context.buffer.pop_front() {
+                        counter!(METRIC_AIR_QS_DELIVERED_MESSAGES).increment(1);
                         return Some((Some(message), context));
                     }
 
@@ -332,10 +430,24 @@ fn client_version_label(client_version: Option<&Version>) -> Cow<'static, str> {
 
 pub(super) struct Queue {}
 
+impl Queue {
+    /// Reports the number of messages in all queues of the domain as gauge.
+    pub(super) async fn report_metrics(
+        executor: impl PgExecutor<'_>,
+        domain: &Fqdn,
+    ) -> sqlx::Result<()> {
+        let queued_messages = Self::count(executor).await?;
+        gauge!(METRIC_AIR_QS_QUEUED_MESSAGES, "domain" => domain.to_string())
+            .set(queued_messages as f64);
+        Ok(())
+    }
+}
+
 pub(crate) mod persistence {
     use super::*;
 
     use airprotos::queue_service::v1::QueueMessage;
+    use chrono::{DateTime, Utc};
     use prost::Message;
     use sqlx::{
         Database, Decode, Encode, Postgres, Type, encode::IsNull, error::BoxDynError, query,
@@ -429,19 +541,39 @@ pub(crate) mod persistence {
             Ok(())
         }
 
+        pub(crate) async fn count(executor: impl PgExecutor<'_>) -> sqlx::Result<i64> {
+            query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM qs_queues"#)
+                .fetch_one(executor)
+                .await
+        }
+
+        /// Deletes all messages which were enqueued before the given time.
+        pub(crate) async fn delete_expired(
+            executor: impl PgExecutor<'_>,
+            expired_before: DateTime<Utc>,
+        ) -> sqlx::Result<u64> {
+            let result = query!(
+                "DELETE FROM qs_queues WHERE created_at < $1",
+                expired_before
+            )
+            .execute(executor)
+            .await?;
+            Ok(result.rows_affected())
+        }
+
         pub(super) async fn delete(
             executor: impl PgExecutor<'_>,
             queue_id: QsClientId,
             up_to_sequence_number: u64,
-        ) -> sqlx::Result<()> {
-            query!(
+        ) -> sqlx::Result<u64> {
+            let result = query!(
                 "DELETE FROM qs_queues WHERE queue_id = $1 AND sequence_number < $2",
                 queue_id as QsClientId,
                 up_to_sequence_number as i64,
             )
             .execute(executor)
             .await?;
-            Ok(())
+            Ok(result.rows_affected())
         }
     }
 }
@@ -4,6 +4,7 @@
 
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    num::NonZeroU32,
     path::PathBuf,
 };
 
@@ -26,6 +27,10 @@ pub struct Settings {
     pub storage: Option<StorageSettings>,
     #[serde(default)]
     pub ratelimits: RateLimitsSettings,
+    #[serde(default)]
+    pub queues: QueueSettings,
+    #[serde(default)]
+    pub spam: SpamSettings,
 }
 
 /// Configuration for the application.
@@ -61,6 +66,15 @@ pub struct ApplicationSettings {
     /// and allow open registration.
     #[serde(default = "default_true")]
     pub invitationonly: bool,
+    /// Interval in milliseconds at which the connectivity to the databases is checked
+    ///
+    /// The health service reports a service as not serving while its database is unreachable.
+    #[serde(with = "duration_millis", default = "default_10s")]
+    pub healthcheckinterval: std::time::Duration,
+    /// Token authenticating admin operations
+    ///
+    /// Admin operations are disabled if not set.
+    pub admintoken: Option<String>,
 }
 
 fn default_listen() -> SocketAddr {
@@ -206,12 +220,17 @@ impl DatabaseSettings {
 
 /// Every `period`, allow bursts of up to `burst`-many requests, and replenish one element after
 /// the `period`.
+///
+/// The limits apply to all gRPC methods which are not matched by any of the `methods` limits.
 #[derive(Debug, Deserialize, Clone)]
 pub struct RateLimitsSettings {
     #[serde(with = "duration_millis", default = "default_500ms")]
     pub period: std::time::Duration,
     #[serde(default = "default_burst")]
     pub burst: u32,
+    /// Limits of specific gRPC services or methods
+    #[serde(default)]
+    pub methods: Vec<MethodRateLimitSettings>,
 }
 
 impl Default for RateLimitsSettings {
@@ -219,6 +238,84 @@ impl Default for RateLimitsSettings {
         Self {
             period: std::time::Duration::from_millis(500),
             burst: 100,
+            methods: Vec::new(),
+        }
+    }
+}
+
+/// Rate limit of a gRPC service or method
+///
+/// Every `period`, allow bursts of up to `burst`-many requests, and replenish one element after
+/// the `period`. Requests to the matched methods are limited independently of other requests.
+#[derive(Debug, Deserialize, Clone)]
+pub struct MethodRateLimitSettings {
+    /// Either a fully qualified service, e.g. `queue_service.v1.QueueService`, or a method of a
+    /// service, e.g. `queue_service.v1.QueueService/Listen`
+    ///
+    /// If several entries match a request, the first one is applied.
+    pub method: String,
+    #[serde(with = "duration_millis")]
+    pub period: std::time::Duration,
+    pub burst: u32,
+}
+
+impl MethodRateLimitSettings {
+    /// Returns whether this limit applies to the gRPC request with the given path.
+    ///
+    /// The path has the form `/<service>/<method>`.
+    pub fn matches(&self, path: &str) -> bool {
+        let path = path.trim_start_matches('/');
+        let method = self.method.trim_matches('/');
+        match path.strip_prefix(method) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/') && !method.contains('/'),
+            None => false,
+        }
+    }
+}
+
+/// Configuration of the QS queues
+#[derive(Debug, Deserialize, Clone)]
+pub struct QueueSettings {
+    /// Time to live of queued messages in seconds
+    ///
+    /// Messages older than this are deleted, even if they were not acknowledged by the client.
+    /// Messages never expire if not set.
+    #[serde(default, with = "optional_duration_seconds")]
+    pub messagettl: Option<std::time::Duration>,
+    /// Interval in seconds at which expired messages are deleted; must be positive
+    #[serde(with = "positive_duration_seconds", default = "default_1h")]
+    pub sweepinterval: std::time::Duration,
+}
+
+impl Default for QueueSettings {
+    fn default() -> Self {
+        Self {
+            messagettl: None,
+            sweepinterval: default_1h(),
+        }
+    }
+}
+
+/// Configuration of the spam report aggregation
+#[derive(Debug, Deserialize, Clone)]
+pub struct SpamSettings {
+    /// Number of distinct reporters after which a user is flagged as spammer; must be positive
+    #[serde(default = "default_spam_report_threshold")]
+    pub reportthreshold: NonZeroU32,
+    /// Time window in seconds in which reports are counted
+    #[serde(with = "duration_seconds_std", default = "default_1day")]
+    pub reportwindow: std::time::Duration,
+    /// Time in seconds after which a flag expires, unless the user is reported again
+    #[serde(with = "positive_duration_seconds", default = "default_30days")]
+    pub flagduration: std::time::Duration,
+}
+
+impl Default for SpamSettings {
+    fn default() -> Self {
+        Self {
+            reportthreshold: default_spam_report_threshold(),
+            reportwindow: default_1day(),
+            flagduration: default_30days(),
         }
     }
 }
@@ -251,6 +348,26 @@ fn default_1week() -> Duration {
     Duration::days(7)
 }
 
+fn default_1h() -> std::time::Duration {
+    std::time::Duration::from_secs(60 * 60)
+}
+
+fn default_1day() -> std::time::Duration {
+    std::time::Duration::from_secs(24 * 60 * 60)
+}
+
+fn default_30days() -> std::time::Duration {
+    std::time::Duration::from_secs(30 * 24 * 60 * 60)
+}
+
+fn default_spam_report_threshold() -> NonZeroU32 {
+    NonZeroU32::new(5).unwrap()
+}
+
+fn default_10s() -> std::time::Duration {
+    std::time::Duration::from_secs(10)
+}
+
 fn default_500ms() -> std::time::Duration {
     std::time::Duration::from_millis(500)
 }
@@ -288,6 +405,55 @@ mod duration_seconds {
     }
 }
 
+mod duration_seconds_std {
+    use serde::de;
+
+    use std::time::Duration;
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let seconds: u64 = serde::Deserialize::deserialize(d)?;
+        Ok(Duration::from_secs(seconds))
+    }
+}
+
+/// Like `duration_seconds_std`, but rejects a zero duration.
+mod positive_duration_seconds {
+    use serde::de;
+
+    use std::time::Duration;
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let seconds: u64 = serde::Deserialize::deserialize(d)?;
+        if seconds == 0 {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(0),
+                &"a positive number of seconds",
+            ));
+        }
+        Ok(Duration::from_secs(seconds))
+    }
+}
+
+mod optional_duration_seconds {
+    use serde::de;
+
+    use std::time::Duration;
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let seconds: Option<u64> = serde::Deserialize::deserialize(d)?;
+        Ok(seconds.map(Duration::from_secs))
+    }
+}
+
 mod duration_millis {
     use serde::de;
 
@@ -147,6 +147,15 @@ impl QualifiedGroupId {
         }
     }
 
+    /// Creates a group id with a fixed UUID supplied by the caller.
+    ///
+    /// Makes group creation reproducible in tests. Outside of tests, group ids are reserved via
+    /// the DS which picks a random UUID.
+    #[cfg(any(test, feature = "test_utils"))]
+    pub fn with_fixed_uuid(uuid: Uuid, owning_domain: Fqdn) -> Self {
+        Self::new(uuid, owning_domain)
+    }
+
     pub fn group_uuid(&self) -> Uuid {
         Uuid::from_bytes(self.group_uuid)
     }
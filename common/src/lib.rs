@@ -8,6 +8,7 @@
 
 use std::fmt::Display;
 
+pub use mls_assist::openmls::prelude::Ciphersuite;
 pub use mls_assist::openmls_rust_crypto::RustCrypto;
 pub use mls_assist::openmls_traits::random::OpenMlsRand;
 use serde::{Deserialize, Serialize};
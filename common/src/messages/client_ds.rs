@@ -8,6 +8,7 @@
 //! module, to allow re-use by the client implementation.
 
 use apqmls::messages::{ApqMlsMessageIn, ApqWelcome};
+use mimi_room_policy::RoleIndex;
 use mls_assist::{
     messages::{AssistedMessageIn, AssistedWelcome, SerializedMlsMessage},
     openmls::prelude::{GroupEpoch, GroupId, LeafNodeIndex, MlsMessageIn, RatchetTreeIn},
@@ -15,7 +16,8 @@ use mls_assist::{
 };
 use serde::{Deserialize, Serialize};
 use tls_codec::{
-    DeserializeBytes, Serialize as TlsSerializeTrait, TlsDeserializeBytes, TlsSerialize, TlsSize,
+    DeserializeBytes, Serialize as TlsSerializeTrait, Size, TlsDeserializeBytes, TlsSerialize,
+    TlsSize, VLBytes,
 };
 
 use crate::{
@@ -28,7 +30,7 @@ use crate::{
         hpke::{HpkeDecryptable, HpkeEncryptable, JoinerInfoKeyType},
         ratchet::QueueRatchet,
     },
-    identifiers::QsReference,
+    identifiers::{QsReference, UserId},
     time::TimeStamp,
 };
 
@@ -245,6 +247,10 @@ impl From<SerializedMlsMessage> for QsQueueMessagePayload {
 impl AeadEncryptable<RatchetKey, EncryptedQsQueueMessageCtype> for QsQueueMessagePayload {}
 impl AeadDecryptable<RatchetKey, EncryptedQsQueueMessageCtype> for QsQueueMessagePayload {}
 
+/// AAD of a commit
+///
+/// The payload is interpreted according to the version of the message. Changing the encoding of
+/// a payload requires a new version.
 #[derive(TlsSerialize, TlsDeserializeBytes, TlsSize)]
 pub struct AadMessage {
     version: AirProtocolVersion,
@@ -270,16 +276,175 @@ impl AadMessage {
     }
 }
 
-#[derive(TlsSerialize, TlsDeserializeBytes, TlsSize)]
-#[repr(u8)]
+/// Payload of the AAD of a commit, signaling the operation of the commit.
+///
+/// None of the known payloads can be skipped: each carries information which is required to
+/// process the commit, e.g. the encrypted user profile keys of added users or the role change to
+/// apply.
+///
+/// New payloads are introduced as extension payloads, which older clients can parse without
+/// knowing them, see [`UnknownAadPayload`]. The content of all payloads is interpreted according
+/// to the version of the [`AadMessage`]. Non-extension payloads with an unknown tag fail to
+/// deserialize.
 pub enum AadPayload {
     GroupOperation(GroupOperationParamsAad),
     JoinConnectionGroup(JoinConnectionGroupParamsAad),
     Resync,
     DeleteGroup,
+    ChangeRole(ChangeRoleParamsAad),
     // There is no SelfRemoveClient entry, since that message consists of a
     // single proposal and since we don't otherwise support individual
     // proposals, there is not need to signal it explicitly.
+    /// Extension payload which is not known to this client
+    Unknown(UnknownAadPayload),
+}
+
+impl AadPayload {
+    /// Whether the commit can be processed without understanding this payload
+    pub fn is_ignorable(&self) -> bool {
+        match self {
+            Self::Unknown(payload) => payload.is_ignorable(),
+            _ => false,
+        }
+    }
+
+    fn tag(&self) -> u8 {
+        match self {
+            Self::GroupOperation(_) => 0,
+            Self::JoinConnectionGroup(_) => 1,
+            Self::Resync => 2,
+            Self::DeleteGroup => 3,
+            Self::ChangeRole(_) => CHANGE_ROLE_AAD_PAYLOAD_TAG,
+            Self::Unknown(payload) => payload.tag,
+        }
+    }
+}
+
+/// Tag of the first extension AAD payload
+///
+/// An extension payload is encoded as its tag and its length-prefixed content. Tags below this
+/// value belong to the payloads which are encoded without a length prefix.
+const FIRST_EXTENSION_AAD_PAYLOAD_TAG: u8 = 0x80;
+
+/// Tag of the first extension AAD payload which must not be ignored
+///
+/// Extension payloads with tags from [`FIRST_EXTENSION_AAD_PAYLOAD_TAG`] up to this value only
+/// carry optional information: a client which does not know them can still process the commit.
+/// Payloads from this tag on are critical and a client which does not know them rejects the
+/// commit.
+const FIRST_CRITICAL_AAD_PAYLOAD_TAG: u8 = 0xc0;
+
+/// Tag of the [`AadPayload::ChangeRole`] extension payload
+///
+/// Older clients can't apply the role change, so the payload is critical.
+const CHANGE_ROLE_AAD_PAYLOAD_TAG: u8 = FIRST_CRITICAL_AAD_PAYLOAD_TAG;
+
+/// Extension AAD payload which is not known to this client
+///
+/// Introduced by a newer client.
+#[derive(Debug, Clone, PartialEq, Eq, TlsSerialize, TlsSize)]
+pub struct UnknownAadPayload {
+    tag: u8,
+    content: VLBytes,
+}
+
+impl UnknownAadPayload {
+    /// Creates an extension payload with the given tag.
+    ///
+    /// Fails if the tag is not in the range of extension payloads, since such a payload would be
+    /// deserialized as a different payload.
+    pub fn new(tag: u8, content: VLBytes) -> Result<Self, tls_codec::Error> {
+        if tag < FIRST_EXTENSION_AAD_PAYLOAD_TAG {
+            return Err(tls_codec::Error::UnknownValue(tag.into()));
+        }
+        Ok(Self { tag, content })
+    }
+
+    pub fn tag(&self) -> u8 {
+        self.tag
+    }
+
+    pub fn content(&self) -> &[u8] {
+        self.content.as_slice()
+    }
+
+    /// Whether the payload only carries optional information and can be ignored
+    pub fn is_ignorable(&self) -> bool {
+        self.tag < FIRST_CRITICAL_AAD_PAYLOAD_TAG
+    }
+}
+
+impl DeserializeBytes for UnknownAadPayload {
+    fn tls_deserialize_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), tls_codec::Error> {
+        let (tag, rest) = u8::tls_deserialize_bytes(bytes)?;
+        let (content, rest) = VLBytes::tls_deserialize_bytes(rest)?;
+        Ok((Self::new(tag, content)?, rest))
+    }
+}
+
+impl Size for AadPayload {
+    fn tls_serialized_len(&self) -> usize {
+        match self {
+            // The tag is part of the extension payload
+            Self::Unknown(payload) => payload.tls_serialized_len(),
+            Self::GroupOperation(payload) => 1 + payload.tls_serialized_len(),
+            Self::JoinConnectionGroup(payload) => 1 + payload.tls_serialized_len(),
+            Self::Resync | Self::DeleteGroup => 1,
+            // The payload is an extension payload
+            Self::ChangeRole(payload) => payload
+                .to_extension()
+                .map(|payload| payload.tls_serialized_len())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl TlsSerializeTrait for AadPayload {
+    fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<usize, tls_codec::Error> {
+        let written = match self {
+            // The tag is part of the extension payload
+            Self::Unknown(payload) => return payload.tls_serialize(writer),
+            Self::GroupOperation(payload) => {
+                self.tag().tls_serialize(writer)? + payload.tls_serialize(writer)?
+            }
+            Self::JoinConnectionGroup(payload) => {
+                self.tag().tls_serialize(writer)? + payload.tls_serialize(writer)?
+            }
+            Self::Resync | Self::DeleteGroup => self.tag().tls_serialize(writer)?,
+            Self::ChangeRole(payload) => payload.to_extension()?.tls_serialize(writer)?,
+        };
+        Ok(written)
+    }
+}
+
+impl DeserializeBytes for AadPayload {
+    fn tls_deserialize_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), tls_codec::Error> {
+        let (tag, rest) = u8::tls_deserialize_bytes(bytes)?;
+        let (payload, rest) = match tag {
+            0 => {
+                let (payload, rest) = GroupOperationParamsAad::tls_deserialize_bytes(rest)?;
+                (Self::GroupOperation(payload), rest)
+            }
+            1 => {
+                let (payload, rest) = JoinConnectionGroupParamsAad::tls_deserialize_bytes(rest)?;
+                (Self::JoinConnectionGroup(payload), rest)
+            }
+            2 => (Self::Resync, rest),
+            3 => (Self::DeleteGroup, rest),
+            FIRST_EXTENSION_AAD_PAYLOAD_TAG.. => {
+                let (payload, rest) = UnknownAadPayload::tls_deserialize_bytes(bytes)?;
+                let payload = match payload.tag {
+                    CHANGE_ROLE_AAD_PAYLOAD_TAG => Self::ChangeRole(
+                        ChangeRoleParamsAad::tls_deserialize_exact_bytes(payload.content())?,
+                    ),
+                    _ => Self::Unknown(payload),
+                };
+                (payload, rest)
+            }
+            _ => return Err(tls_codec::Error::UnknownValue(tag.into())),
+        };
+        Ok((payload, rest))
+    }
 }
 
 #[derive(
@@ -387,6 +552,27 @@ pub struct GroupOperationParamsAad {
     pub new_encrypted_user_profile_keys: Vec<EncryptedUserProfileKey>,
 }
 
+/// Role change of a group member, carried by a commit without adds or removes.
+///
+/// The role change is also carried in the group data of the group context, such that all members
+/// agree on it.
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserializeBytes, TlsSize,
+)]
+pub struct ChangeRoleParamsAad {
+    pub target: UserId,
+    pub role: RoleIndex,
+}
+
+impl ChangeRoleParamsAad {
+    fn to_extension(&self) -> Result<UnknownAadPayload, tls_codec::Error> {
+        UnknownAadPayload::new(
+            CHANGE_ROLE_AAD_PAYLOAD_TAG,
+            self.tls_serialize_detached()?.into(),
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct JoinConnectionGroupParams {
     pub external_commit: AssistedMessageIn,
@@ -464,3 +650,76 @@ pub struct ApqWelcomeBundle {
     // This part is added by the DS later.
     pub encrypted_joiner_info: EncryptedDsJoinerInformation,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aad_bytes(payload: AadPayload) -> Vec<u8> {
+        AadMessage::from(payload).tls_serialize_detached().unwrap()
+    }
+
+    fn deserialize_aad_payload(bytes: &[u8]) -> Result<AadPayload, tls_codec::Error> {
+        Ok(AadMessage::tls_deserialize_exact_bytes(bytes)?.into_payload())
+    }
+
+    #[test]
+    fn aad_payload_encoding() {
+        assert_eq!(aad_bytes(AadPayload::Resync), [0, 2]);
+        assert_eq!(aad_bytes(AadPayload::DeleteGroup), [0, 3]);
+
+        let bytes = aad_bytes(AadPayload::GroupOperation(GroupOperationParamsAad {
+            new_encrypted_user_profile_keys: Vec::new(),
+        }));
+        assert_eq!(bytes, [0, 0, 0]);
+        assert!(matches!(
+            deserialize_aad_payload(&bytes),
+            Ok(AadPayload::GroupOperation(_))
+        ));
+
+        // Unknown non-extension payloads are rejected
+        assert!(deserialize_aad_payload(&[0, 5]).is_err());
+    }
+
+    #[test]
+    fn unknown_extension_aad_payload() {
+        // Optional payload of a newer client with content [1, 2, 3]
+        let bytes = [0, 0x80, 3, 1, 2, 3];
+        let payload = deserialize_aad_payload(&bytes).unwrap();
+        assert!(payload.is_ignorable());
+        let AadPayload::Unknown(unknown) = &payload else {
+            panic!("expected an unknown payload");
+        };
+        assert_eq!(unknown.tag(), 0x80);
+        assert_eq!(unknown.content(), [1, 2, 3]);
+        assert_eq!(aad_bytes(payload), bytes);
+
+        // Critical payload of a newer client
+        let payload = deserialize_aad_payload(&[0, 0xc1, 0]).unwrap();
+        assert!(matches!(payload, AadPayload::Unknown(_)));
+        assert!(!payload.is_ignorable());
+
+        // Extension payloads are length-prefixed
+        assert!(deserialize_aad_payload(&[0, 0x80, 3, 1, 2]).is_err());
+
+        // Tags of non-extension payloads can't be used for extension payloads
+        assert!(UnknownAadPayload::new(0x7f, vec![1].into()).is_err());
+        assert!(UnknownAadPayload::new(0x80, vec![1].into()).is_ok());
+    }
+
+    #[test]
+    fn change_role_aad_payload_is_critical() {
+        let role_change = ChangeRoleParamsAad {
+            target: UserId::random("localhost".parse().unwrap()),
+            role: RoleIndex::Regular,
+        };
+        let bytes = aad_bytes(AadPayload::ChangeRole(role_change.clone()));
+        assert_eq!(bytes[1], 0xc0);
+
+        let AadPayload::ChangeRole(payload) = deserialize_aad_payload(&bytes).unwrap() else {
+            panic!("expected a role change");
+        };
+        assert_eq!(payload, role_change);
+        assert!(!AadPayload::ChangeRole(payload).is_ignorable());
+    }
+}
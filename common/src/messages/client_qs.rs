@@ -59,7 +59,8 @@ pub struct CreateClientRecordParams {
     pub sender: QsUserId,
     pub client_record_auth_key: QsClientVerifyingKey,
     pub queue_encryption_key: RatchetEncryptionKey,
-    pub encrypted_push_token: Option<EncryptedPushToken>,
+    /// Push tokens of all devices notified about new messages in the queue
+    pub encrypted_push_tokens: Vec<EncryptedPushToken>,
     pub initial_ratchet_secret: RatchetSecret, // TODO: This can be dropped once we support PCS
 }
 
@@ -73,7 +74,8 @@ pub struct UpdateClientRecordParams {
     pub sender: QsClientId,
     pub client_record_auth_key: QsClientVerifyingKey,
     pub queue_encryption_key: RatchetEncryptionKey,
-    pub encrypted_push_token: Option<EncryptedPushToken>,
+    /// Push tokens of all devices notified about new messages in the queue
+    pub encrypted_push_tokens: Vec<EncryptedPushToken>,
 }
 
 #[derive(Debug)]
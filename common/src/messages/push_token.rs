@@ -9,14 +9,14 @@ use crate::{
 
 use super::*;
 
-#[derive(Serialize, Deserialize, TlsSize, TlsSerialize, TlsDeserializeBytes)]
+#[derive(PartialEq, Eq, Serialize, Deserialize, TlsSize, TlsSerialize, TlsDeserializeBytes)]
 #[repr(u8)]
 pub enum PushTokenOperator {
     Apple,
     Google,
 }
 
-#[derive(Serialize, Deserialize, TlsSize, TlsSerialize, TlsDeserializeBytes)]
+#[derive(PartialEq, Eq, Serialize, Deserialize, TlsSize, TlsSerialize, TlsDeserializeBytes)]
 pub struct PushToken {
     operator: PushTokenOperator,
     token: TlsString,
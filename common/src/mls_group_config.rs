@@ -68,7 +68,9 @@ pub const GROUP_DATA_EXTENSION_TYPE: u16 = 0xff01;
 pub const QS_CLIENT_REFERENCE_EXTENSION_TYPE: u16 = 0xff00;
 
 const DEFAULT_MLS_VERSION: ProtocolVersion = ProtocolVersion::Mls10;
-const DEFAULT_CIPHERSUITE: Ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+/// Ciphersuite of new groups and key packages, unless a user selects another supported one.
+pub const DEFAULT_CIPHERSUITE: Ciphersuite =
+    Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
 
 const PQ_CIPHERSUITE: Ciphersuite = Ciphersuite::MLS_128_MLKEM768_AES256GCM_SHA384_Ed25519;
 
@@ -97,7 +99,11 @@ pub fn default_group_required_extensions() -> RequiredCapabilitiesExtension {
 
 // Supported capabilities (subset of required capabilities)
 pub const SUPPORTED_PROTOCOL_VERSIONS: &[ProtocolVersion] = &[DEFAULT_MLS_VERSION];
-pub const SUPPORTED_CIPHERSUITES: &[Ciphersuite] = &[DEFAULT_CIPHERSUITE];
+/// All supported ciphersuites use Ed25519 signatures, because client credentials are Ed25519 keys.
+pub const SUPPORTED_CIPHERSUITES: &[Ciphersuite] = &[
+    DEFAULT_CIPHERSUITE,
+    Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
+];
 pub const SUPPORTED_EXTENSIONS: &[ExtensionType] = &[
     ExtensionType::Unknown(QS_CLIENT_REFERENCE_EXTENSION_TYPE), // Also in REQUIRED_EXTENSIONS
     ExtensionType::Unknown(GROUP_DATA_EXTENSION_TYPE),          // Also in REQUIRED_EXTENSIONS
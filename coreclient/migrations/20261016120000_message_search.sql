@@ -0,0 +1,25 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+--
+-- Full-text search index over the rendered text of content messages.
+--
+-- The index is maintained when messages are stored and updated. Messages
+-- stored before this migration are not indexed.
+CREATE VIRTUAL TABLE message_search USING fts5 (
+    message_id UNINDEXED,
+    body,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- Remove the terms of deleted and edited messages from the index right away,
+-- instead of only marking them as deleted.
+INSERT INTO message_search (message_search, rank) VALUES ('secure-delete', 1);
+
+-- Virtual tables don't support foreign keys, so deletions (including cascades
+-- from deleted chats) are propagated by a trigger.
+CREATE TRIGGER message_search_delete AFTER DELETE ON message
+BEGIN
+    DELETE FROM message_search WHERE message_id = OLD.message_id;
+END;
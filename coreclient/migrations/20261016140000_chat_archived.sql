@@ -0,0 +1,8 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+--
+-- Archived chats are hidden from the active chat list until a new message
+-- arrives.
+ALTER TABLE chat ADD COLUMN archived BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,16 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+--
+-- Chat messages scheduled for being sent at a later time.
+--
+-- When `send_at` is due, the message is moved to the `chat_message_queue`.
+CREATE TABLE scheduled_message (
+    message_id BLOB PRIMARY KEY NOT NULL,
+    chat_id BLOB NOT NULL,
+    send_at TEXT NOT NULL,
+    FOREIGN KEY (message_id) REFERENCES message (message_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_scheduled_message_send_at ON scheduled_message (send_at);
@@ -0,0 +1,8 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+--
+-- Small preview of image attachments, available before the attachment is
+-- downloaded.
+ALTER TABLE attachment ADD COLUMN thumbnail BLOB;
@@ -0,0 +1,27 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+--
+-- Encrypted attachments which are uploaded in parts but not yet completely
+-- uploaded.
+--
+-- The ciphertext is kept until the upload succeeds, such that an interrupted
+-- upload can be resumed after the last uploaded part without encrypting and
+-- provisioning the attachment again.
+CREATE TABLE attachment_upload_queue (
+    attachment_id BLOB PRIMARY KEY NOT NULL,
+    ciphertext BLOB NOT NULL,
+    part_size INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (attachment_id) REFERENCES attachment (attachment_id) ON DELETE CASCADE
+);
+
+-- Parts of queued attachment uploads which are already uploaded.
+CREATE TABLE attachment_upload_part (
+    attachment_id BLOB NOT NULL,
+    part_number INTEGER NOT NULL,
+    etag TEXT NOT NULL,
+    PRIMARY KEY (attachment_id, part_number),
+    FOREIGN KEY (attachment_id) REFERENCES attachment_upload_queue (attachment_id) ON DELETE CASCADE
+);
@@ -0,0 +1,24 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+--
+-- User-defined labels of contacts (e.g. "Family", "Work").
+--
+-- Note: There is no foreign key constraint on the contact table, because
+-- contacts are upserted with `INSERT OR REPLACE`, which would drop the labels.
+-- Instead, labels are removed by a trigger when the contact is deleted.
+CREATE TABLE contact_label (
+    user_uuid BLOB NOT NULL,
+    user_domain TEXT NOT NULL,
+    label TEXT NOT NULL,
+    PRIMARY KEY (user_uuid, user_domain, label)
+);
+
+CREATE INDEX idx_contact_label_label ON contact_label (label);
+
+CREATE TRIGGER contact_label_delete AFTER DELETE ON contact
+BEGIN
+    DELETE FROM contact_label
+    WHERE user_uuid = OLD.user_uuid AND user_domain = OLD.user_domain;
+END;
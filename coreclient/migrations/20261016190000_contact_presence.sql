@@ -0,0 +1,13 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+--
+-- Last known presence of users, updated from ephemeral presence messages.
+CREATE TABLE contact_presence (
+    user_uuid BLOB NOT NULL,
+    user_domain TEXT NOT NULL,
+    state INTEGER NOT NULL,
+    last_seen TEXT NOT NULL,
+    PRIMARY KEY (user_uuid, user_domain)
+);
@@ -0,0 +1,7 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+--
+-- Optional status text (a short bio or availability note) of a user profile.
+ALTER TABLE user ADD COLUMN status_text TEXT;
@@ -0,0 +1,17 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+--
+-- Messages pinned in a chat.
+--
+-- Pins are local state and are not synced with other members or devices.
+CREATE TABLE pinned_message (
+    message_id BLOB PRIMARY KEY NOT NULL,
+    chat_id BLOB NOT NULL,
+    pinned_at TEXT NOT NULL,
+    FOREIGN KEY (message_id) REFERENCES message (message_id) ON DELETE CASCADE,
+    FOREIGN KEY (chat_id) REFERENCES chat (chat_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_pinned_message_chat_id ON pinned_message (chat_id, pinned_at);
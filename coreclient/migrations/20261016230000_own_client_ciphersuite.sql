@@ -0,0 +1,9 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+--
+-- MLS ciphersuite selected when the user was created; used for new groups and
+-- key packages. Existing clients use MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519.
+ALTER TABLE own_client_info
+ADD COLUMN ciphersuite INTEGER NOT NULL DEFAULT 1;
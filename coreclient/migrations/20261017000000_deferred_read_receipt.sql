@@ -0,0 +1,15 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+--
+-- Read receipts which are held back until their chat is focused in the UI.
+CREATE TABLE deferred_read_receipt (
+    message_id BLOB PRIMARY KEY NOT NULL,
+    chat_id BLOB NOT NULL,
+    mimi_id BLOB NOT NULL,
+    FOREIGN KEY (message_id) REFERENCES message (message_id) ON DELETE CASCADE,
+    FOREIGN KEY (chat_id) REFERENCES chat (chat_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_deferred_read_receipt_chat_id ON deferred_read_receipt (chat_id);
@@ -0,0 +1,17 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+--
+-- Messages stored before the full-text search index existed are not indexed.
+-- Their text can only be rendered by the client from the encoded content, so
+-- they are queued here and indexed when the database is opened.
+CREATE TABLE message_search_backfill (
+    message_id BLOB NOT NULL PRIMARY KEY,
+    FOREIGN KEY (message_id) REFERENCES message (message_id) ON DELETE CASCADE
+);
+
+INSERT INTO message_search_backfill (message_id)
+SELECT message_id
+FROM message
+WHERE message_id NOT IN (SELECT message_id FROM message_search);
@@ -0,0 +1,47 @@
+-- SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+--
+-- SPDX-License-Identifier: AGPL-3.0-or-later
+--
+-- Allow marking pending chat operations whose request is in flight, and store
+-- the room state from before a leave was applied locally, so that a cancelled
+-- leave can be reverted.
+ALTER TABLE pending_chat_operation RENAME TO pending_chat_operation_old;
+
+CREATE TABLE pending_chat_operation (
+    group_id BLOB NOT NULL PRIMARY KEY,
+    operation_type TEXT NOT NULL,
+    operation_data BLOB NOT NULL,
+    retry_due_at TEXT,
+    number_of_attempts INTEGER NOT NULL DEFAULT 0,
+    locked_by BLOB,
+    request_status TEXT NOT NULL CHECK (
+        request_status IN (
+            'waiting_for_queue_response',
+            'ready_to_retry',
+            'in_flight'
+        )
+    ),
+    previous_room_state BLOB,
+    FOREIGN KEY (group_id) REFERENCES "group" (group_id) ON DELETE CASCADE
+);
+
+INSERT INTO pending_chat_operation (
+    group_id,
+    operation_type,
+    operation_data,
+    retry_due_at,
+    number_of_attempts,
+    locked_by,
+    request_status
+)
+SELECT
+    group_id,
+    operation_type,
+    operation_data,
+    retry_due_at,
+    number_of_attempts,
+    locked_by,
+    request_status
+FROM pending_chat_operation_old;
+
+DROP TABLE pending_chat_operation_old;
@@ -8,6 +8,7 @@ use mimi_content::{
     MessageStatus, MimiContent,
     content_container::{Disposition, NestedPart, PartSemantics},
 };
+use mimi_room_policy::RoleIndex;
 use tracing::{error, warn};
 
 use crate::{
@@ -286,6 +287,14 @@ impl ChatMessage {
         self.in_reply_to.as_ref()
     }
 
+    /// Local id of the message this message replies to, if it is known locally.
+    pub fn in_reply_to_message_id(&self) -> Option<MessageId> {
+        self.in_reply_to
+            .as_ref()
+            .and_then(|(_, message)| message.as_ref())
+            .map(|message| message.message_id)
+    }
+
     pub fn take_in_reply_to(&mut self) -> Option<(MimiId, Option<InReplyToMessage>)> {
         self.in_reply_to.take()
     }
@@ -575,6 +584,12 @@ pub enum SystemMessage {
     /// We requested a connection with another user through a group.
     NewDirectConnectionChat(UserId),
     CreateGroup(UserId),
+    /// The sender changed the role of the target in the group.
+    ChangeRole {
+        sender: UserId,
+        target: UserId,
+        role: RoleIndex,
+    },
 }
 
 impl SystemMessage {
@@ -652,6 +667,19 @@ impl SystemMessage {
                 let user_display_name = core_user.user_profile(user_id).await.display_name;
                 format!("{user_display_name} created the group")
             }
+            SystemMessage::ChangeRole {
+                sender,
+                target,
+                role,
+            } => {
+                let sender_display_name = core_user.user_profile(sender).await.display_name;
+                let target_display_name = core_user.user_profile(target).await.display_name;
+                let role = match role {
+                    RoleIndex::Regular => "member",
+                    _ => "admin",
+                };
+                format!("{sender_display_name} changed the role of {target_display_name} to {role}")
+            }
         }
     }
 }
@@ -96,19 +96,19 @@ impl VersionedMessage {
 
 use super::{MessageId, TimestampedMessage};
 
-struct SqlChatMessage {
-    message_id: MessageId,
-    mimi_id: Option<MimiId>,
-    chat_id: ChatId,
-    timestamp: TimeStamp,
-    sender_user_uuid: Option<Uuid>,
-    sender_user_domain: Option<Fqdn>,
-    content: BlobDecoded<VersionedMessage>,
-    sent: bool,
-    status: i64,
-    edited_at: Option<TimeStamp>,
-    is_blocked: bool,
-    in_reply_to_mimi_id: Option<MimiId>,
+pub(in crate::chats) struct SqlChatMessage {
+    pub(in crate::chats) message_id: MessageId,
+    pub(in crate::chats) mimi_id: Option<MimiId>,
+    pub(in crate::chats) chat_id: ChatId,
+    pub(in crate::chats) timestamp: TimeStamp,
+    pub(in crate::chats) sender_user_uuid: Option<Uuid>,
+    pub(in crate::chats) sender_user_domain: Option<Fqdn>,
+    pub(in crate::chats) content: BlobDecoded<VersionedMessage>,
+    pub(in crate::chats) sent: bool,
+    pub(in crate::chats) status: i64,
+    pub(in crate::chats) edited_at: Option<TimeStamp>,
+    pub(in crate::chats) is_blocked: bool,
+    pub(in crate::chats) in_reply_to_mimi_id: Option<MimiId>,
 }
 
 impl From<SqlChatMessage> for ChatMessage {
@@ -673,6 +673,16 @@ impl ChatMessage {
         .execute(connection.as_mut())
         .await?;
 
+        if let Some(body) = self.search_body() {
+            query!(
+                "INSERT INTO message_search (message_id, body) VALUES (?, ?)",
+                self.message_id,
+                body,
+            )
+            .execute(connection.as_mut())
+            .await?;
+        }
+
         connection
             .notifier()
             .add(self.message_id)
@@ -680,6 +690,20 @@ impl ChatMessage {
         Ok(())
     }
 
+    /// Text of this message in the full-text search index
+    ///
+    /// Only content messages which are not deleted are indexed.
+    fn search_body(&self) -> Option<String> {
+        if self.message().is_deleted() {
+            return None;
+        }
+        self.message()
+            .mimi_content()?
+            .string_rendering()
+            .ok()
+            .filter(|body| !body.trim().is_empty())
+    }
+
     pub(crate) async fn update(&self, mut connection: impl WriteConnection) -> anyhow::Result<()> {
         let mimi_id = self.message().mimi_id();
         let content = match &self.timestamped_message.message {
@@ -718,11 +742,131 @@ impl ChatMessage {
         .execute(connection.as_mut())
         .await?;
 
+        query!(
+            "DELETE FROM message_search WHERE message_id = ?",
+            message_id
+        )
+        .execute(connection.as_mut())
+        .await?;
+        if let Some(body) = self.search_body() {
+            query!(
+                "INSERT INTO message_search (message_id, body) VALUES (?, ?)",
+                message_id,
+                body,
+            )
+            .execute(connection.as_mut())
+            .await?;
+        }
+
         connection.notifier().update(self.id());
         connection.notifier().update(self.chat_id);
         Ok(())
     }
 
+    /// Loads the messages whose text matches all words of `query`, newest first.
+    ///
+    /// Words are matched as prefixes. Messages in chats with blocked contacts and messages from
+    /// blocked users are skipped. If `chat_id` is given, only messages of this chat are searched.
+    pub(crate) async fn search(
+        mut connection: impl ReadConnection,
+        chat_id: Option<ChatId>,
+        query: &str,
+        limit: u32,
+    ) -> sqlx::Result<Vec<ChatMessage>> {
+        let Some(query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let messages: Vec<ChatMessage> = query_as!(
+            SqlChatMessage,
+            r#"
+            SELECT
+                m.message_id AS "message_id: _",
+                m.mimi_id AS "mimi_id: _",
+                m.chat_id AS "chat_id: _",
+                m.timestamp AS "timestamp: _",
+                m.sender_user_uuid AS "sender_user_uuid: _",
+                m.sender_user_domain AS "sender_user_domain: _",
+                m.content AS "content: _",
+                m.sent,
+                m.status,
+                m.edited_at AS "edited_at: _",
+                b.user_uuid IS NOT NULL AS "is_blocked!: _",
+                m.in_reply_to_mimi_id AS "in_reply_to_mimi_id: _"
+            FROM message_search s
+            INNER JOIN message m ON m.message_id = s.message_id
+            INNER JOIN chat c ON c.chat_id = m.chat_id
+            LEFT JOIN blocked_contact b ON b.user_uuid = m.sender_user_uuid
+                AND b.user_domain = m.sender_user_domain
+            LEFT JOIN blocked_contact cb ON cb.user_uuid = c.connection_user_uuid
+                AND cb.user_domain = c.connection_user_domain
+            WHERE message_search MATCH ?1
+                AND (?2 IS NULL OR m.chat_id = ?2)
+                AND b.user_uuid IS NULL
+                AND cb.user_uuid IS NULL
+            ORDER BY m.timestamp DESC, m.message_id DESC
+            LIMIT ?3"#,
+            query,
+            chat_id,
+            limit,
+        )
+        .fetch(connection.as_mut())
+        .filter_map(Self::decode_row)
+        .collect::<sqlx::Result<Vec<_>>>()
+        .await?;
+
+        messages.with_loaded_in_reply_to(&mut connection).await
+    }
+
+    /// Indexes the messages which were stored before the full-text search index existed.
+    ///
+    /// The messages are queued in `message_search_backfill` by a migration and are indexed in
+    /// batches, each in its own transaction, so other writers are not blocked for the whole
+    /// backfill. Messages which fail to decode are skipped.
+    pub(crate) async fn backfill_search_index(
+        mut connection: impl WriteConnection,
+    ) -> sqlx::Result<()> {
+        const BATCH_SIZE: i64 = 500;
+        loop {
+            let mut txn = connection.begin().await?;
+            let message_ids: Vec<MessageId> = query_scalar!(
+                r#"SELECT message_id AS "message_id: _" FROM message_search_backfill LIMIT ?"#,
+                BATCH_SIZE,
+            )
+            .fetch_all(txn.as_mut())
+            .await?;
+            if message_ids.is_empty() {
+                return txn.commit().await;
+            }
+            for message_id in message_ids {
+                // Messages which fail to decode are not indexed, but still dequeued, so they
+                // don't block the backfill.
+                let message = Self::load(&mut txn, message_id)
+                    .await
+                    .inspect_err(|e| warn!(?message_id, "Error loading message: {e}"))
+                    .ok()
+                    .flatten();
+                if let Some(message) = message
+                    && let Some(body) = message.search_body()
+                {
+                    query!(
+                        "INSERT INTO message_search (message_id, body) VALUES (?, ?)",
+                        message_id,
+                        body,
+                    )
+                    .execute(txn.as_mut())
+                    .await?;
+                }
+                query!(
+                    "DELETE FROM message_search_backfill WHERE message_id = ?",
+                    message_id
+                )
+                .execute(txn.as_mut())
+                .await?;
+            }
+            txn.commit().await?;
+        }
+    }
+
     /// Delete a message from the database.
     ///
     /// This removes the message row entirely. This will also remove associated
@@ -768,6 +912,47 @@ impl ChatMessage {
         Ok(())
     }
 
+    /// Loads the unsent messages which failed to be sent, oldest first.
+    ///
+    /// If `chat_id` is given, only messages of this chat are loaded.
+    pub(crate) async fn load_failed(
+        mut connection: impl ReadConnection,
+        chat_id: Option<ChatId>,
+    ) -> sqlx::Result<Vec<Self>> {
+        let failed_status: u8 = MessageStatus::Error.into();
+        let messages: Vec<ChatMessage> = query_as!(
+            SqlChatMessage,
+            r#"SELECT
+                message_id AS "message_id: _",
+                mimi_id AS "mimi_id: _",
+                chat_id AS "chat_id: _",
+                timestamp AS "timestamp: _",
+                sender_user_uuid AS "sender_user_uuid: _",
+                sender_user_domain AS "sender_user_domain: _",
+                content AS "content: _",
+                sent,
+                status,
+                edited_at AS "edited_at: _",
+                b.user_uuid IS NOT NULL AS "is_blocked!: _",
+                in_reply_to_mimi_id AS "in_reply_to_mimi_id: _"
+            FROM message
+            LEFT JOIN blocked_contact b ON b.user_uuid = sender_user_uuid
+                AND b.user_domain = sender_user_domain
+            WHERE status = ?1
+                AND sent = FALSE
+                AND (?2 IS NULL OR chat_id = ?2)
+            ORDER BY timestamp ASC, message_id ASC"#,
+            failed_status,
+            chat_id,
+        )
+        .fetch(connection.as_mut())
+        .filter_map(Self::decode_row)
+        .collect::<sqlx::Result<Vec<_>>>()
+        .await?;
+
+        messages.with_loaded_in_reply_to(&mut connection).await
+    }
+
     /// Get the last message in the chat.
     pub(crate) async fn last_message(
         mut connection: impl ReadConnection,
@@ -967,6 +1152,18 @@ struct SqlInReplyToMessage {
     content: Option<BlobDecoded<VersionedMessage>>,
 }
 
+/// Turns a user-provided search query into an FTS5 query.
+///
+/// Each word is quoted, so that FTS5 operators are matched literally, and matched as a prefix.
+/// Returns `None` if the query contains no words.
+fn fts_query(query: &str) -> Option<String> {
+    let words: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
 impl From<SqlInReplyToMessage> for Option<InReplyToMessage> {
     fn from(
         SqlInReplyToMessage {
@@ -1100,8 +1297,12 @@ pub(crate) mod tests {
     use sqlx::SqlitePool;
 
     use crate::{
-        ContentMessage, Message, MessageId, chats::persistence::tests::test_chat,
-        clients::attachment::persistence::test::test_attachment_record, db::access::DbAccess,
+        ContentMessage, Message, MessageId,
+        chats::persistence::tests::test_chat,
+        clients::{
+            attachment::persistence::test::test_attachment_record, block_contact::BlockedContact,
+        },
+        db::access::DbAccess,
     };
 
     use super::*;
@@ -1264,6 +1465,37 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn load_failed(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+        let mut txn = connection.begin().await?;
+
+        let chat_a = test_chat();
+        chat_a.store(&mut txn).await?;
+        let chat_b = test_chat();
+        chat_b.store(&mut txn).await?;
+
+        let mut failed_a = test_chat_message(chat_a.id());
+        failed_a.store(&mut txn).await?;
+        failed_a.set_status(MessageStatus::Error);
+        failed_a.update(&mut txn).await?;
+        let pending_a = test_chat_message(chat_a.id());
+        pending_a.store(&mut txn).await?;
+        let mut failed_b = test_chat_message(chat_b.id());
+        failed_b.store(&mut txn).await?;
+        failed_b.set_status(MessageStatus::Error);
+        failed_b.update(&mut txn).await?;
+
+        let failed = ChatMessage::load_failed(&mut txn, None).await?;
+        assert_eq!(failed, [failed_a.clone(), failed_b]);
+
+        let failed = ChatMessage::load_failed(&mut txn, Some(chat_a.id())).await?;
+        assert_eq!(failed, [failed_a]);
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn update_sent_status(pool: SqlitePool) -> anyhow::Result<()> {
         let pool = DbAccess::for_tests(pool);
@@ -1399,6 +1631,41 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn delete_message_updates_unread_count_and_preview(
+        pool: SqlitePool,
+    ) -> anyhow::Result<()> {
+        use crate::{Chat, ChatPreview};
+
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+
+        let chat = test_chat();
+        chat.store(&mut connection).await?;
+        let first = test_chat_message_with_salt(chat.id(), [0; 16]);
+        first.store(&mut connection).await?;
+        let second = test_chat_message_with_salt(chat.id(), [1; 16]);
+        second.store(&mut connection).await?;
+        assert_eq!(
+            Chat::unread_messages_count(&mut connection, chat.id()).await?,
+            2
+        );
+
+        ChatMessage::delete(&mut connection, second.id()).await?;
+
+        assert_eq!(
+            Chat::unread_messages_count(&mut connection, chat.id()).await?,
+            1
+        );
+        let previews = ChatPreview::load(&mut connection, Some(chat.id())).await?;
+        assert_eq!(
+            previews[0].last_message.as_ref().map(ChatMessage::id),
+            Some(first.id())
+        );
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn delete_message_cascade_edit_history(pool: SqlitePool) -> anyhow::Result<()> {
         use crate::chats::messages::edit::MessageEdit;
@@ -1754,4 +2021,142 @@ pub(crate) mod tests {
 
         Ok(())
     }
+
+    fn text_message_at(chat_id: ChatId, sender: &UserId, secs: i64, text: &str) -> ChatMessage {
+        ChatMessage::new_for_test(
+            chat_id,
+            MessageId::random(),
+            TimeStamp::from(secs * 1_000_000_000),
+            ContentMessage::new(
+                sender.clone(),
+                true,
+                MimiContent::simple_markdown_message(text.to_owned(), [secs as u8; 16]),
+                &GroupId::from_slice(&[0]),
+            ),
+        )
+    }
+
+    #[test]
+    fn fts_query_quotes_words() {
+        assert_eq!(fts_query("  "), None);
+        assert_eq!(
+            fts_query(r#"hello "world OR"#).as_deref(),
+            Some(r#""hello"* """world"* "OR"*"#)
+        );
+    }
+
+    #[sqlx::test]
+    async fn search(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+        let mut txn = connection.begin().await?;
+
+        let chat_a = test_chat();
+        chat_a.store(&mut txn).await?;
+        let chat_b = test_chat();
+        chat_b.store(&mut txn).await?;
+
+        let sender = &*TEST_SENDER;
+        let blocked = UserId::random("localhost".parse().unwrap());
+        let apples = text_message_at(chat_a.id(), sender, 10, "I like apples");
+        let pears = text_message_at(chat_a.id(), sender, 20, "Pears and apples");
+        let other_chat = text_message_at(chat_b.id(), sender, 30, "Apple pie");
+        let from_blocked = text_message_at(chat_b.id(), &blocked, 40, "apples");
+        for message in [&apples, &pears, &other_chat, &from_blocked] {
+            message.store(&mut txn).await?;
+        }
+        BlockedContact::new(blocked).store(&mut txn).await?;
+
+        let ids = |messages: Vec<ChatMessage>| -> Vec<MessageId> {
+            messages.iter().map(|message| message.id()).collect()
+        };
+
+        // Newest first, words are matched as prefixes and case-insensitively
+        assert_eq!(
+            ids(ChatMessage::search(&mut txn, None, "APPLE", 10).await?),
+            [other_chat.id(), pears.id(), apples.id()]
+        );
+        assert_eq!(
+            ids(ChatMessage::search(&mut txn, None, "apples pear", 10).await?),
+            [pears.id()]
+        );
+        assert_eq!(
+            ids(ChatMessage::search(&mut txn, Some(chat_a.id()), "apple", 10).await?),
+            [pears.id(), apples.id()]
+        );
+        assert!(ids(ChatMessage::search(&mut txn, None, "banana", 10).await?).is_empty());
+        assert!(ids(ChatMessage::search(&mut txn, None, "", 10).await?).is_empty());
+
+        // Edits and deletions update the index
+        let mut edited = pears.clone();
+        edited.set_content_message(ContentMessage::new(
+            sender.clone(),
+            true,
+            MimiContent::simple_markdown_message("Bananas".to_owned(), [0; 16]),
+            &GroupId::from_slice(&[0]),
+        ));
+        edited.update(&mut txn).await?;
+        ChatMessage::delete(&mut txn, apples.id()).await?;
+        assert_eq!(
+            ids(ChatMessage::search(&mut txn, None, "apple", 10).await?),
+            [other_chat.id()]
+        );
+        assert_eq!(
+            ids(ChatMessage::search(&mut txn, None, "banana", 10).await?),
+            [pears.id()]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn backfill_search_index(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+
+        let chat = test_chat();
+        chat.store(&mut connection).await?;
+        let sender = &*TEST_SENDER;
+        let apples = text_message_at(chat.id(), sender, 10, "I like apples");
+        let pears = text_message_at(chat.id(), sender, 20, "Pears and apples");
+        let broken = text_message_at(chat.id(), sender, 30, "Broken apples");
+        for message in [&apples, &pears, &broken] {
+            message.store(&mut connection).await?;
+        }
+        let broken_id = broken.id();
+        query!(
+            "UPDATE message SET content = X'FF' WHERE message_id = ?",
+            broken_id
+        )
+        .execute(connection.as_mut())
+        .await?;
+
+        // Simulate messages stored before the index existed
+        query!("DELETE FROM message_search")
+            .execute(connection.as_mut())
+            .await?;
+        query!("INSERT INTO message_search_backfill (message_id) SELECT message_id FROM message")
+            .execute(connection.as_mut())
+            .await?;
+        assert!(
+            ChatMessage::search(&mut connection, None, "apples", 10)
+                .await?
+                .is_empty()
+        );
+
+        ChatMessage::backfill_search_index(&mut connection).await?;
+
+        let found: Vec<MessageId> = ChatMessage::search(&mut connection, None, "apples", 10)
+            .await?
+            .iter()
+            .map(|message| message.id())
+            .collect();
+        assert_eq!(found, [pears.id(), apples.id()]);
+        let queued = query_scalar!("SELECT COUNT(*) FROM message_search_backfill")
+            .fetch_one(connection.as_mut())
+            .await?;
+        assert_eq!(queued, 0);
+
+        Ok(())
+    }
 }
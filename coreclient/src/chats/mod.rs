@@ -24,12 +24,15 @@ use crate::{
 };
 
 pub use draft::MessageDraft;
+pub use preview::ChatPreview;
 pub(crate) use {pending::PendingConnectionInfo, status::StatusRecord};
 
 mod draft;
 pub(crate) mod messages;
 pub(crate) mod pending;
 pub(crate) mod persistence;
+pub(crate) mod pins;
+pub(crate) mod preview;
 pub(crate) mod reactions;
 mod sqlx_support;
 pub(crate) mod status;
@@ -94,6 +97,8 @@ pub struct Chat {
     pub status: ChatStatus,
     pub chat_type: ChatType,
     pub muted_until: Option<ChatMuted>,
+    // Archived chats are hidden from the active chat list.
+    pub archived: bool,
 }
 
 impl Chat {
@@ -107,6 +112,7 @@ impl Chat {
             status: ChatStatus::Active,
             chat_type: ChatType::HandleConnection(username),
             muted_until: None,
+            archived: false,
         }
     }
 
@@ -120,6 +126,7 @@ impl Chat {
             status: ChatStatus::Active,
             chat_type: ChatType::TargetedMessageConnection(user_id),
             muted_until: None,
+            archived: false,
         }
     }
 
@@ -133,6 +140,7 @@ impl Chat {
             status: ChatStatus::Active,
             chat_type: ChatType::Group(attributes),
             muted_until: None,
+            archived: false,
         }
     }
 
@@ -145,6 +153,7 @@ impl Chat {
             status: ChatStatus::Active,
             chat_type: ChatType::PendingConnection(user_id),
             muted_until: None,
+            archived: false,
         }
     }
 
@@ -200,6 +209,10 @@ impl Chat {
         self.muted_until.as_ref().is_some_and(|cm| cm.is_muted(now))
     }
 
+    pub fn is_archived(&self) -> bool {
+        self.archived
+    }
+
     pub(crate) async fn set_picture(
         &mut self,
         connection: impl WriteConnection,
@@ -237,6 +250,16 @@ impl Chat {
         Ok(())
     }
 
+    pub(crate) async fn set_active(
+        &mut self,
+        connection: impl WriteTransaction,
+    ) -> sqlx::Result<()> {
+        let new_status = ChatStatus::Active;
+        Self::update_status(connection, self.id, &new_status).await?;
+        self.status = new_status;
+        Ok(())
+    }
+
     /// Confirm a connection chat by setting the chat type to `Connection`.
     pub(crate) async fn confirm(
         &mut self,
@@ -415,6 +438,7 @@ impl GroupDataExt for GroupData {
             legacy_picture,
             encrypted_title,
             external_group_profile,
+            role_change: _,
         } = self;
 
         let title = if let Some(encrypted_title) = encrypted_title
@@ -471,3 +495,72 @@ impl From<DateTime<Utc>> for ChatMuted {
         }
     }
 }
+
+/// Selects which chats are returned when listing chats.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum ChatListFilter {
+    /// All chats
+    #[default]
+    All,
+    /// Only chats that are not archived
+    Active,
+    /// Only archived chats
+    Archived,
+}
+
+impl ChatListFilter {
+    pub fn matches(&self, chat: &Chat) -> bool {
+        match self {
+            Self::All => true,
+            Self::Active => !chat.is_archived(),
+            Self::Archived => chat.is_archived(),
+        }
+    }
+}
+
+/// Selects which chats are skipped when marking all chats as read.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct MarkAllAsReadOptions {
+    /// Skip archived chats
+    pub skip_archived: bool,
+    /// Skip chats with blocked contacts
+    pub skip_blocked: bool,
+}
+
+/// Maximum number of member names included in a derived chat title.
+const DERIVED_TITLE_MAX_NAMES: usize = 2;
+
+/// Derives a chat title from the display names of the other chat members.
+///
+/// The names are sorted and at most [`DERIVED_TITLE_MAX_NAMES`] are included, e.g. "Alice, Bob,
+/// +3". Returns an empty string if there are no other members.
+pub(crate) fn derive_chat_title(mut names: Vec<String>) -> String {
+    names.sort_unstable();
+    let remaining = names.len().saturating_sub(DERIVED_TITLE_MAX_NAMES);
+    names.truncate(DERIVED_TITLE_MAX_NAMES);
+    let mut title = names.join(", ");
+    if remaining > 0 {
+        title.push_str(&format!(", +{remaining}"));
+    }
+    title
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn derive_chat_title_from_names() {
+        assert_eq!(derive_chat_title(Vec::new()), "");
+        assert_eq!(derive_chat_title(names(&["Alice"])), "Alice");
+        assert_eq!(derive_chat_title(names(&["Bob", "Alice"])), "Alice, Bob");
+        assert_eq!(
+            derive_chat_title(names(&["Eve", "Bob", "Dave", "Alice", "Carol"])),
+            "Alice, Bob, +3"
+        );
+    }
+}
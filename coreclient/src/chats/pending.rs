@@ -4,7 +4,7 @@
 
 use aircommon::{
     crypto::{aead::AeadEncryptable, indexed_aead::keys::UserProfileKey},
-    identifiers::{QualifiedGroupId, Username},
+    identifiers::{QualifiedGroupId, UserId, Username},
     messages::{
         client_as::ConnectionOfferHash,
         client_ds::{AadMessage, AadPayload, JoinConnectionGroupParamsAad},
@@ -41,7 +41,65 @@ pub(crate) struct PendingConnectionInfo {
     pub(crate) connection_package_hash: Option<ConnectionPackageHash>,
 }
 
+/// An incoming connection request which was neither accepted nor declined yet
+#[derive(Debug, Clone)]
+pub struct ConnectionRequest {
+    /// The unconfirmed chat created for the request
+    pub chat_id: ChatId,
+    pub sender: UserId,
+    /// Own username the request was sent to; `None` if it was sent via a targeted message
+    pub username: Option<Username>,
+    pub created_at: TimeStamp,
+}
+
 impl CoreUser {
+    /// Returns the pending incoming connection requests, most recent first.
+    pub async fn pending_connection_requests(&self) -> anyhow::Result<Vec<ConnectionRequest>> {
+        Ok(ConnectionRequest::load_all(self.db().read().await?).await?)
+    }
+
+    /// Declines the incoming connection request of the pending connection chat with the given id.
+    ///
+    /// Removes the chat together with its pending connection info and the partial contact. The
+    /// sender is not notified: the connection group is never joined, so the sender's chat stays
+    /// unconfirmed, same as if the request was not yet handled.
+    #[instrument(skip(self), err)]
+    pub async fn decline_connection_request(&self, chat_id: ChatId) -> anyhow::Result<()> {
+        self.db()
+            .with_write_transaction(async |txn| {
+                // Outgoing requests are cancelled by deleting the chat instead
+                ConnectionRequest::load(&mut *txn, chat_id)
+                    .await?
+                    .with_context(|| {
+                        format!("No incoming connection request for chat: {chat_id}")
+                    })?;
+                let chat = Chat::load(&mut *txn, &chat_id)
+                    .await?
+                    .with_context(|| format!("Can't find chat with id {chat_id}"))?;
+                let pending_connection_info = PendingConnectionInfo::load(&mut *txn, chat_id)
+                    .await?
+                    .with_context(|| {
+                        format!("No pending connection info found for chat: {chat_id}")
+                    })?;
+                pending_connection_info
+                    .delete_key_material(&mut *txn)
+                    .await?;
+
+                // A group only exists if accepting the request failed half-way
+                if Group::load_with_chat_id(&mut *txn, chat_id)
+                    .await?
+                    .is_some()
+                {
+                    Group::delete_from_db(&mut *txn, chat.group_id()).await?;
+                }
+
+                // Cascades to the pending connection info and the partial contact
+                Chat::delete(&mut *txn, chat_id).await?;
+                Ok(())
+            })
+            .await
+    }
+
     #[instrument(skip(self), err)]
     pub async fn accept_contact_request(
         &self,
@@ -185,19 +243,8 @@ impl CoreUser {
                 let now = TimeStamp::now();
                 group.store_update(&mut *txn, Some(now), Some(now)).await?;
 
-                if let Some(hash) = connection_package_hash {
-                    // Delete the connection package if it's not last resort
-                    let is_last_resort =
-                        <ConnectionPackage as StorableConnectionPackage>::is_last_resort(
-                            &mut *txn, &hash,
-                        )
-                        .await?
-                        .unwrap_or(false);
-                    if !is_last_resort {
-                        ConnectionPackage::delete(&mut *txn, &hash)
-                            .await
-                            .context("Failed to delete connection package")?;
-                    }
+                if let Some(hash) = &connection_package_hash {
+                    delete_connection_package(&mut *txn, hash).await?;
                 }
 
                 Ok(Ok((commit, group_info)))
@@ -291,13 +338,128 @@ impl CoreUser {
     }
 }
 
+impl PendingConnectionInfo {
+    /// Deletes the key material of the request: the connection offer PSK and the connection
+    /// package (unless it is a last resort package).
+    pub(crate) async fn delete_key_material(
+        &self,
+        mut connection: impl WriteConnection,
+    ) -> anyhow::Result<()> {
+        if let Some(hash) = self.connection_offer_hash {
+            Group::delete_connection_offer_psk(&mut connection, hash)?;
+        }
+        if let Some(hash) = &self.connection_package_hash {
+            delete_connection_package(&mut connection, hash).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Deletes the connection package if it's not last resort.
+async fn delete_connection_package(
+    mut connection: impl WriteConnection,
+    hash: &ConnectionPackageHash,
+) -> anyhow::Result<()> {
+    let is_last_resort =
+        <ConnectionPackage as StorableConnectionPackage>::is_last_resort(&mut connection, hash)
+            .await?
+            .unwrap_or(false);
+    if !is_last_resort {
+        ConnectionPackage::delete(&mut connection, hash)
+            .await
+            .context("Failed to delete connection package")?;
+    }
+    Ok(())
+}
+
 mod persistence {
+    use aircommon::identifiers::Fqdn;
     use sqlx::{query, query_as};
+    use uuid::Uuid;
 
     use crate::db::access::ReadConnection;
 
     use super::*;
 
+    struct SqlConnectionRequest {
+        chat_id: ChatId,
+        created_at: TimeStamp,
+        handle: Option<Username>,
+        sender_user_uuid: Uuid,
+        sender_user_domain: Fqdn,
+    }
+
+    impl From<SqlConnectionRequest> for ConnectionRequest {
+        fn from(
+            SqlConnectionRequest {
+                chat_id,
+                created_at,
+                handle,
+                sender_user_uuid,
+                sender_user_domain,
+            }: SqlConnectionRequest,
+        ) -> Self {
+            Self {
+                chat_id,
+                sender: UserId::new(sender_user_uuid, sender_user_domain),
+                username: handle,
+                created_at,
+            }
+        }
+    }
+
+    impl ConnectionRequest {
+        pub(super) async fn load(
+            mut connection: impl ReadConnection,
+            chat_id: ChatId,
+        ) -> sqlx::Result<Option<ConnectionRequest>> {
+            let request = query_as!(
+                SqlConnectionRequest,
+                r#"SELECT
+                    p.chat_id AS "chat_id: ChatId",
+                    p.created_at AS "created_at: TimeStamp",
+                    p.handle AS "handle: _",
+                    c.connection_user_uuid AS "sender_user_uuid!: _",
+                    c.connection_user_domain AS "sender_user_domain!: _"
+                FROM pending_connection_info p
+                INNER JOIN chat c ON c.chat_id = p.chat_id
+                WHERE p.chat_id = ?
+                    AND c.is_incoming
+                    AND NOT c.is_confirmed_connection
+                    AND c.connection_user_uuid IS NOT NULL
+                    AND c.connection_user_domain IS NOT NULL"#,
+                chat_id,
+            )
+            .fetch_optional(connection.as_mut())
+            .await?;
+            Ok(request.map(From::from))
+        }
+
+        pub(super) async fn load_all(
+            mut connection: impl ReadConnection,
+        ) -> sqlx::Result<Vec<ConnectionRequest>> {
+            let requests = query_as!(
+                SqlConnectionRequest,
+                r#"SELECT
+                    p.chat_id AS "chat_id: ChatId",
+                    p.created_at AS "created_at: TimeStamp",
+                    p.handle AS "handle: _",
+                    c.connection_user_uuid AS "sender_user_uuid!: _",
+                    c.connection_user_domain AS "sender_user_domain!: _"
+                FROM pending_connection_info p
+                INNER JOIN chat c ON c.chat_id = p.chat_id
+                WHERE c.is_incoming
+                    AND NOT c.is_confirmed_connection
+                    AND c.connection_user_uuid IS NOT NULL
+                    AND c.connection_user_domain IS NOT NULL
+                ORDER BY p.created_at DESC"#,
+            )
+            .fetch_all(connection.as_mut())
+            .await?;
+            Ok(requests.into_iter().map(From::from).collect())
+        }
+    }
+
     impl PendingConnectionInfo {
         pub(crate) async fn load(
             mut connection: impl ReadConnection,
@@ -2,26 +2,38 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::collections::{HashMap, HashSet};
+
 use aircommon::identifiers::{Fqdn, MimiId, UserId, Username};
 use chrono::{DateTime, Utc};
 use mimi_content::MessageStatus;
 use openmls::group::GroupId;
 use sqlx::{query, query_as, query_scalar};
 use tokio_stream::StreamExt;
-use tracing::info;
+use tracing::{error, info};
 use uuid::Uuid;
 
 use crate::{
     Chat, ChatAttributes, ChatId, ChatStatus, ChatType, MessageId,
-    chats::ChatMuted,
+    chats::{ChatListFilter, ChatMuted, MarkAllAsReadOptions},
     db::access::{
         ReadConnection, ReadTransaction, WriteConnection, WriteDbTransaction, WriteTransaction,
     },
+    groups::Group,
     utils::persistence::GroupIdWrapper,
 };
 
 use super::InactiveChat;
 
+/// A chat with the data it can be found by in the chat list.
+pub(crate) struct ChatSearchCandidate {
+    pub(crate) chat_id: ChatId,
+    /// Title of a group chat or handle of a handle connection chat
+    pub(crate) name: Option<String>,
+    /// Connection user or participants of the group
+    pub(crate) user_ids: HashSet<UserId>,
+}
+
 struct SqlChat {
     chat_id: ChatId,
     chat_title: String,
@@ -37,6 +49,7 @@ struct SqlChat {
     is_blocked: bool,
     is_incoming: bool,
     muted_until: Option<DateTime<Utc>>,
+    archived: bool,
 }
 
 impl SqlChat {
@@ -56,6 +69,7 @@ impl SqlChat {
             is_blocked,
             is_incoming,
             muted_until,
+            archived,
         } = self;
 
         let chat_type = match (
@@ -95,6 +109,7 @@ impl SqlChat {
             status,
             chat_type,
             muted_until,
+            archived,
         })
     }
 
@@ -275,7 +290,8 @@ impl Chat {
                 is_active,
                 is_incoming,
                 blocked_contact.user_uuid IS NOT NULL AS "is_blocked!: _",
-                muted_until AS "muted_until: _"
+                muted_until AS "muted_until: _",
+                archived
             FROM chat
             LEFT JOIN blocked_contact ON blocked_contact.user_uuid = chat.connection_user_uuid
                 AND blocked_contact.user_domain = chat.connection_user_domain
@@ -319,6 +335,184 @@ impl Chat {
         .await
     }
 
+    /// Loads the chats matching `filter` in the same order as [`Chat::load_ordered_ids`].
+    ///
+    /// Chats and past members are loaded with one query each.
+    pub(crate) async fn load_ordered(
+        mut connection: impl ReadConnection,
+        filter: ChatListFilter,
+    ) -> sqlx::Result<Vec<Chat>> {
+        let archived = match filter {
+            ChatListFilter::All => None,
+            ChatListFilter::Active => Some(false),
+            ChatListFilter::Archived => Some(true),
+        };
+        let chats = query_as!(
+            SqlChat,
+            r#"SELECT
+                c.chat_id AS "chat_id: _",
+                c.chat_title,
+                c.chat_picture,
+                c.group_id AS "group_id: _",
+                c.last_read AS "last_read: _",
+                (SELECT timestamp FROM message
+                    WHERE chat_id = c.chat_id
+                    ORDER BY timestamp DESC
+                    LIMIT 1
+                ) AS "last_message_at: _",
+                c.connection_user_uuid AS "connection_user_uuid: _",
+                c.connection_user_domain AS "connection_user_domain: _",
+                c.connection_user_handle AS "connection_user_handle: _",
+                c.is_confirmed_connection,
+                c.is_active,
+                c.is_incoming,
+                b.user_uuid IS NOT NULL AS "is_blocked!: _",
+                c.muted_until AS "muted_until: _",
+                c.archived
+            FROM chat c
+            LEFT JOIN blocked_contact b ON b.user_uuid = c.connection_user_uuid
+                AND b.user_domain = c.connection_user_domain
+            LEFT OUTER JOIN message_draft d ON
+                d.chat_id = c.chat_id AND
+                d.is_committed = TRUE AND
+                NOT (TRIM(d.message) = '' AND d.editing_id IS NULL)
+            WHERE ?1 IS NULL OR c.archived = ?1
+            ORDER BY
+                d.updated_at DESC,
+                (SELECT timestamp
+                    FROM message
+                    WHERE chat_id = c.chat_id
+                    ORDER BY timestamp DESC
+                    LIMIT 1
+                ) DESC,
+                c.chat_id"#,
+            archived,
+        )
+        .fetch_all(connection.as_mut())
+        .await?;
+
+        struct SqlChatPastMember {
+            chat_id: ChatId,
+            member_user_uuid: Uuid,
+            member_user_domain: Fqdn,
+        }
+        let past_members = query_as!(
+            SqlChatPastMember,
+            r#"SELECT
+                m.chat_id AS "chat_id: _",
+                m.member_user_uuid AS "member_user_uuid: _",
+                m.member_user_domain AS "member_user_domain: _"
+            FROM chat_past_member m
+            INNER JOIN chat c ON c.chat_id = m.chat_id
+            WHERE NOT c.is_active
+            ORDER BY m.chat_id, m.member_user_uuid, m.member_user_domain"#,
+        )
+        .fetch_all(connection.as_mut())
+        .await?;
+        let mut past_members_by_chat: HashMap<ChatId, Vec<SqlPastMember>> = HashMap::new();
+        for member in past_members {
+            past_members_by_chat
+                .entry(member.chat_id)
+                .or_default()
+                .push(SqlPastMember {
+                    member_user_uuid: member.member_user_uuid,
+                    member_user_domain: member.member_user_domain,
+                });
+        }
+
+        Ok(chats
+            .into_iter()
+            .filter_map(|chat| {
+                let members = if chat.is_active {
+                    Vec::new()
+                } else {
+                    past_members_by_chat
+                        .remove(&chat.chat_id)
+                        .unwrap_or_default()
+                };
+                chat.convert(members)
+            })
+            .collect())
+    }
+
+    /// Loads the data of all chats needed to search the chat list, in the same order as
+    /// [`Chat::load_ordered_ids`].
+    ///
+    /// The participants of group chats are decoded from the room state of the group, such that
+    /// all chats are loaded with a single query and without loading their MLS groups.
+    pub(crate) async fn load_search_candidates(
+        mut connection: impl ReadConnection,
+    ) -> sqlx::Result<Vec<ChatSearchCandidate>> {
+        struct SqlChatSearchCandidate {
+            chat_id: ChatId,
+            chat_title: String,
+            connection_user_uuid: Option<Uuid>,
+            connection_user_domain: Option<Fqdn>,
+            connection_user_handle: Option<Username>,
+            room_state: Option<Vec<u8>>,
+        }
+
+        let chats = query_as!(
+            SqlChatSearchCandidate,
+            r#"SELECT
+                c.chat_id AS "chat_id: _",
+                c.chat_title,
+                c.connection_user_uuid AS "connection_user_uuid: _",
+                c.connection_user_domain AS "connection_user_domain: _",
+                c.connection_user_handle AS "connection_user_handle: _",
+                g.room_state AS "room_state?"
+            FROM chat c
+            LEFT JOIN "group" g ON g.group_id = c.group_id
+            LEFT OUTER JOIN message_draft d ON
+                d.chat_id = c.chat_id AND
+                d.is_committed = TRUE AND
+                NOT (TRIM(d.message) = '' AND d.editing_id IS NULL)
+            ORDER BY
+                d.updated_at DESC,
+                (SELECT timestamp
+                    FROM message
+                    WHERE chat_id = c.chat_id
+                    ORDER BY timestamp DESC
+                    LIMIT 1
+                ) DESC,
+                c.chat_id"#,
+        )
+        .fetch_all(connection.as_mut())
+        .await?;
+
+        Ok(chats
+            .into_iter()
+            .map(|chat| {
+                let mut user_ids = HashSet::new();
+                let name = match (
+                    chat.connection_user_uuid,
+                    chat.connection_user_domain,
+                    chat.connection_user_handle,
+                ) {
+                    (Some(user_uuid), Some(domain), _) => {
+                        user_ids.insert(UserId::new(user_uuid, domain));
+                        None
+                    }
+                    (None, None, Some(username)) => Some(username.plaintext().to_owned()),
+                    _ => Some(chat.chat_title),
+                };
+                if let Some(room_state) = chat.room_state {
+                    match Group::participants_from_room_state(&room_state) {
+                        Ok(participants) => user_ids.extend(participants),
+                        Err(error) => {
+                            error!(%error, chat_id = %chat.chat_id, "Failed to load participants")
+                        }
+                    }
+                }
+                ChatSearchCandidate {
+                    chat_id: chat.chat_id,
+                    name,
+                    user_ids,
+                }
+            })
+            .collect())
+    }
+
     /// Load chat ids for self-update
     ///
     /// Returns all chat ids that have a group attached with `self_updated_at` < `until_due_at`
@@ -366,7 +560,8 @@ impl Chat {
                 is_active,
                 is_incoming,
                 blocked_contact.user_uuid IS NOT NULL AS "is_blocked!: _",
-                muted_until AS "muted_until: _"
+                muted_until AS "muted_until: _",
+                archived
             FROM chat
                 LEFT JOIN blocked_contact
                 ON blocked_contact.user_uuid = chat.connection_user_uuid
@@ -516,6 +711,70 @@ impl Chat {
         Ok(())
     }
 
+    /// Set the `last_read` marker of all chats to the given timestamp, except for the chats
+    /// skipped by the given options.
+    ///
+    /// Unlike [`Self::mark_as_read`], all chats are updated with a single statement. Only chats
+    /// with unread messages are updated and notified.
+    pub(crate) async fn mark_all_as_read(
+        txn: &mut WriteDbTransaction<'_>,
+        timestamp: DateTime<Utc>,
+        options: MarkAllAsReadOptions,
+    ) -> sqlx::Result<()> {
+        let MarkAllAsReadOptions {
+            skip_archived,
+            skip_blocked,
+        } = options;
+
+        let unread_messages: Vec<MessageId> = query_scalar!(
+            r#"SELECT
+                m.message_id AS "message_id: _"
+            FROM message m
+            INNER JOIN chat c ON c.chat_id = m.chat_id
+            LEFT JOIN blocked_contact b
+                ON b.user_uuid = c.connection_user_uuid
+                AND b.user_domain = c.connection_user_domain
+            WHERE m.timestamp > c.last_read AND m.timestamp <= ?1
+                AND (NOT ?2 OR NOT c.archived)
+                AND (NOT ?3 OR b.user_uuid IS NULL)"#,
+            timestamp,
+            skip_archived,
+            skip_blocked,
+        )
+        .fetch_all(txn.as_mut())
+        .await?;
+        for message_id in unread_messages {
+            txn.notifier().update(message_id);
+        }
+
+        let updated_chats: Vec<ChatId> = query_scalar!(
+            r#"UPDATE chat
+            SET last_read = ?1
+            WHERE last_read < ?1
+                AND EXISTS (
+                    SELECT 1 FROM message m
+                    WHERE m.chat_id = chat.chat_id
+                        AND m.timestamp > chat.last_read AND m.timestamp <= ?1
+                )
+                AND (NOT ?2 OR NOT archived)
+                AND (NOT ?3 OR NOT EXISTS (
+                    SELECT 1 FROM blocked_contact b
+                    WHERE b.user_uuid = chat.connection_user_uuid
+                        AND b.user_domain = chat.connection_user_domain
+                ))
+            RETURNING chat_id AS "chat_id: _""#,
+            timestamp,
+            skip_archived,
+            skip_blocked,
+        )
+        .fetch_all(txn.as_mut())
+        .await?;
+        for chat_id in updated_chats {
+            txn.notifier().update(chat_id);
+        }
+        Ok(())
+    }
+
     /// Mark all messages in the chat as read until including the given message id.
     ///
     /// Returns whether the chat was marked as read and the mimi ids of the messages that
@@ -647,6 +906,28 @@ impl Chat {
         Ok(())
     }
 
+    /// Sets the archive flag of the chat.
+    ///
+    /// Returns `false` if the chat did not change.
+    pub(crate) async fn set_archived(
+        mut connection: impl WriteConnection,
+        chat_id: ChatId,
+        archived: bool,
+    ) -> sqlx::Result<bool> {
+        let res = query!(
+            "UPDATE chat SET archived = ?1 WHERE chat_id = ?2 AND archived != ?1",
+            archived,
+            chat_id,
+        )
+        .execute(connection.as_mut())
+        .await?;
+        let changed = res.rows_affected() > 0;
+        if changed {
+            connection.notifier().update(chat_id);
+        }
+        Ok(changed)
+    }
+
     pub(crate) async fn messages_count(
         mut connection: impl ReadConnection,
         chat_id: ChatId,
@@ -913,6 +1194,29 @@ impl Chat {
         Ok(is_blocked.unwrap_or(false))
     }
 
+    /// Returns the usernames via which connections to the given user were established.
+    ///
+    /// The username of a handle connection chat is kept when the connection is confirmed.
+    pub(crate) async fn load_connection_usernames(
+        mut connection: impl ReadConnection,
+        user_id: &UserId,
+    ) -> sqlx::Result<Vec<Username>> {
+        let uuid = user_id.uuid();
+        let domain = user_id.domain();
+        query_scalar!(
+            r#"SELECT DISTINCT
+                connection_user_handle AS "connection_user_handle!: Username"
+            FROM chat
+            WHERE connection_user_uuid = ?
+                AND connection_user_domain = ?
+                AND connection_user_handle IS NOT NULL"#,
+            uuid,
+            domain,
+        )
+        .fetch_all(connection.as_mut())
+        .await
+    }
+
     pub(crate) async fn load_is_apq(
         mut connection: impl ReadConnection,
         chat_id: ChatId,
@@ -964,6 +1268,7 @@ pub mod tests {
                 picture: None,
             }),
             muted_until: None,
+            archived: false,
         }
     }
 
@@ -997,6 +1302,54 @@ pub mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn set_archived(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+        let mut txn = connection.begin().await?;
+
+        let chat = test_chat();
+        chat.store(&mut txn).await?;
+
+        assert!(Chat::set_archived(&mut txn, chat.id(), true).await?);
+        // Archiving again is a no-op
+        assert!(!Chat::set_archived(&mut txn, chat.id(), true).await?);
+        let loaded = Chat::load(&mut txn, &chat.id).await?.unwrap();
+        assert!(loaded.is_archived());
+
+        assert!(Chat::set_archived(&mut txn, chat.id(), false).await?);
+        let loaded = Chat::load(&mut txn, &chat.id).await?.unwrap();
+        assert!(!loaded.is_archived());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn load_ordered_with_filter(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+
+        let active = test_chat();
+        active.store(&mut connection).await?;
+        let archived = test_chat();
+        archived.store(&mut connection).await?;
+        Chat::set_archived(&mut connection, archived.id(), true).await?;
+        let mut message = test_chat_message(archived.id());
+        message.set_timestamp(Utc::now().checked_add_days(Days::new(1)).unwrap().into());
+        message.store(&mut connection).await?;
+
+        let ids = |chats: Vec<Chat>| chats.into_iter().map(|chat| chat.id()).collect::<Vec<_>>();
+
+        let all = Chat::load_ordered(&mut connection, ChatListFilter::All).await?;
+        assert_eq!(ids(all), Chat::load_ordered_ids(&mut connection).await?);
+        let loaded = Chat::load_ordered(&mut connection, ChatListFilter::Active).await?;
+        assert_eq!(ids(loaded), [active.id()]);
+        let loaded = Chat::load_ordered(&mut connection, ChatListFilter::Archived).await?;
+        assert_eq!(ids(loaded), [archived.id()]);
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn store_load_all(pool: SqlitePool) -> anyhow::Result<()> {
         let pool = DbAccess::for_tests(pool);
@@ -1306,6 +1659,55 @@ pub mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn mark_all_as_read(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+
+        let chat = test_chat();
+        chat.store(&mut connection).await?;
+        let mut archived_chat = test_chat();
+        archived_chat.archived = true;
+        archived_chat.store(&mut connection).await?;
+        let mut read_chat = test_chat();
+        read_chat.last_read = Utc::now() - Duration::hours(1);
+        read_chat.store(&mut connection).await?;
+
+        test_chat_message(chat.id()).store(&mut connection).await?;
+        test_chat_message(archived_chat.id())
+            .store(&mut connection)
+            .await?;
+        let n = Chat::global_unread_message_count(&mut connection).await?;
+        assert_eq!(n, 2);
+
+        let options = MarkAllAsReadOptions {
+            skip_archived: true,
+            skip_blocked: true,
+        };
+        let mut txn = connection.begin().await?;
+        Chat::mark_all_as_read(&mut txn, Utc::now(), options).await?;
+        txn.commit().await?;
+        let n = Chat::unread_messages_count(&mut connection, chat.id()).await?;
+        assert_eq!(n, 0);
+        let n = Chat::unread_messages_count(&mut connection, archived_chat.id()).await?;
+        assert_eq!(n, 1, "archived chat is skipped");
+        let loaded = Chat::load(&mut connection, &read_chat.id)
+            .await?
+            .expect("missing chat");
+        assert_eq!(
+            loaded.last_read, read_chat.last_read,
+            "chat without unread messages is not updated"
+        );
+
+        let mut txn = connection.begin().await?;
+        Chat::mark_all_as_read(&mut txn, Utc::now(), MarkAllAsReadOptions::default()).await?;
+        txn.commit().await?;
+        let n = Chat::global_unread_message_count(&mut connection).await?;
+        assert_eq!(n, 0);
+
+        Ok(())
+    }
+
     /// Regression test: `mark_as_read_until_message_id` must never move
     /// `last_read` backwards.
     #[sqlx::test]
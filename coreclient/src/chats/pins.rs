@@ -0,0 +1,178 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Messages pinned in a chat.
+//!
+//! Pins are local state and are not synced. A pin is removed when its message is deleted, locally
+//! or for everyone.
+
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_scalar};
+
+use crate::{
+    ChatId, MessageId,
+    db::access::{ReadConnection, WriteConnection},
+};
+
+pub(crate) struct PinnedMessage;
+
+impl PinnedMessage {
+    /// Pins the message in the chat.
+    ///
+    /// Returns `false` if the message does not exist in the chat. Pinning a message again is a
+    /// no-op.
+    pub(crate) async fn pin(
+        mut connection: impl WriteConnection,
+        chat_id: ChatId,
+        message_id: MessageId,
+        pinned_at: DateTime<Utc>,
+    ) -> sqlx::Result<bool> {
+        let message_exists = query_scalar!(
+            r#"SELECT EXISTS (
+                SELECT 1 FROM message WHERE message_id = ?1 AND chat_id = ?2
+            ) AS "exists!: bool""#,
+            message_id,
+            chat_id,
+        )
+        .fetch_one(connection.as_mut())
+        .await?;
+        if !message_exists {
+            return Ok(false);
+        }
+
+        let res = query!(
+            "INSERT INTO pinned_message (message_id, chat_id, pinned_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT (message_id) DO NOTHING",
+            message_id,
+            chat_id,
+            pinned_at,
+        )
+        .execute(connection.as_mut())
+        .await?;
+        if res.rows_affected() == 1 {
+            connection.notifier().update(message_id).update(chat_id);
+        }
+        Ok(true)
+    }
+
+    /// Unpins the message.
+    pub(crate) async fn unpin(
+        mut connection: impl WriteConnection,
+        message_id: MessageId,
+    ) -> sqlx::Result<()> {
+        let chat_id = query_scalar!(
+            r#"DELETE FROM pinned_message WHERE message_id = ?
+            RETURNING chat_id AS "chat_id: ChatId""#,
+            message_id,
+        )
+        .fetch_optional(connection.as_mut())
+        .await?;
+        if let Some(chat_id) = chat_id {
+            connection.notifier().update(message_id).update(chat_id);
+        }
+        Ok(())
+    }
+
+    /// Loads the ids of the pinned messages in the chat, most recently pinned first.
+    pub(crate) async fn load_message_ids(
+        mut connection: impl ReadConnection,
+        chat_id: ChatId,
+    ) -> sqlx::Result<Vec<MessageId>> {
+        query_scalar!(
+            r#"SELECT message_id AS "message_id: _"
+            FROM pinned_message
+            WHERE chat_id = ?
+            ORDER BY pinned_at DESC"#,
+            chat_id,
+        )
+        .fetch_all(connection.as_mut())
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::SqlitePool;
+
+    use crate::{
+        ChatMessage,
+        chats::{
+            messages::persistence::tests::test_chat_message_with_salt,
+            persistence::tests::test_chat,
+        },
+        db::access::DbAccess,
+    };
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn pin_and_unpin(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+
+        let chat = test_chat();
+        chat.store(&mut connection).await?;
+        let first = test_chat_message_with_salt(chat.id(), [0; 16]);
+        first.store(&mut connection).await?;
+        let second = test_chat_message_with_salt(chat.id(), [1; 16]);
+        second.store(&mut connection).await?;
+
+        let now = Utc::now();
+        assert!(PinnedMessage::pin(&mut connection, chat.id(), first.id(), now).await?);
+        let later = now + chrono::Duration::seconds(1);
+        assert!(PinnedMessage::pin(&mut connection, chat.id(), second.id(), later).await?);
+        // Pinning again is a no-op
+        assert!(PinnedMessage::pin(&mut connection, chat.id(), first.id(), later).await?);
+
+        let pinned = PinnedMessage::load_message_ids(&mut connection, chat.id()).await?;
+        assert_eq!(pinned, [second.id(), first.id()]);
+
+        PinnedMessage::unpin(&mut connection, second.id()).await?;
+        let pinned = PinnedMessage::load_message_ids(&mut connection, chat.id()).await?;
+        assert_eq!(pinned, [first.id()]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn pin_message_of_other_chat(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+
+        let chat = test_chat();
+        chat.store(&mut connection).await?;
+        let other_chat = test_chat();
+        other_chat.store(&mut connection).await?;
+        let message = test_chat_message_with_salt(other_chat.id(), [0; 16]);
+        message.store(&mut connection).await?;
+
+        let pinned =
+            PinnedMessage::pin(&mut connection, chat.id(), message.id(), Utc::now()).await?;
+        assert!(!pinned);
+        let pinned = PinnedMessage::load_message_ids(&mut connection, chat.id()).await?;
+        assert!(pinned.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn pin_removed_on_message_delete(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+
+        let chat = test_chat();
+        chat.store(&mut connection).await?;
+        let message = test_chat_message_with_salt(chat.id(), [0; 16]);
+        message.store(&mut connection).await?;
+        PinnedMessage::pin(&mut connection, chat.id(), message.id(), Utc::now()).await?;
+
+        ChatMessage::delete(&mut connection, message.id()).await?;
+
+        let pinned = PinnedMessage::load_message_ids(&mut connection, chat.id()).await?;
+        assert!(pinned.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use aircommon::{
+    codec::BlobDecoded,
+    identifiers::{Fqdn, MimiId},
+    time::TimeStamp,
+};
+use chrono::{DateTime, Utc};
+use mimi_content::MessageStatus;
+use sqlx::query_as;
+use uuid::Uuid;
+
+use crate::{
+    ChatId, ChatMessage, ChatMuted, MessageId,
+    chats::messages::persistence::{SqlChatMessage, VersionedMessage},
+    db::access::ReadConnection,
+};
+
+/// Preview of a chat in the chat list
+///
+/// The last message is loaded without the replied-to message and reactions.
+#[derive(Debug, Clone)]
+pub struct ChatPreview {
+    pub chat_id: ChatId,
+    /// The latest message (content or system) of the chat
+    pub last_message: Option<ChatMessage>,
+    pub unread_messages_count: usize,
+    pub muted_until: Option<ChatMuted>,
+    pub archived: bool,
+}
+
+impl ChatPreview {
+    /// Timestamp of the last message, if any
+    pub fn last_message_at(&self) -> Option<DateTime<Utc>> {
+        self.last_message.as_ref().map(ChatMessage::timestamp)
+    }
+
+    /// Sorts the previews by the timestamp of the last message, descending.
+    ///
+    /// Chats without messages come last.
+    pub(crate) fn sort(previews: &mut [ChatPreview]) {
+        previews.sort_by(|a, b| {
+            b.last_message_at()
+                .cmp(&a.last_message_at())
+                .then_with(|| a.chat_id.cmp(&b.chat_id))
+        });
+    }
+}
+
+struct SqlChatPreview {
+    chat_id: ChatId,
+    muted_until: Option<DateTime<Utc>>,
+    archived: bool,
+    unread_messages_count: i64,
+    message_id: Option<MessageId>,
+    mimi_id: Option<MimiId>,
+    timestamp: Option<TimeStamp>,
+    sender_user_uuid: Option<Uuid>,
+    sender_user_domain: Option<Fqdn>,
+    content: Option<BlobDecoded<VersionedMessage>>,
+    sent: Option<bool>,
+    status: Option<i64>,
+    edited_at: Option<TimeStamp>,
+    is_blocked: bool,
+    in_reply_to_mimi_id: Option<MimiId>,
+}
+
+impl From<SqlChatPreview> for ChatPreview {
+    fn from(
+        SqlChatPreview {
+            chat_id,
+            muted_until,
+            archived,
+            unread_messages_count,
+            message_id,
+            mimi_id,
+            timestamp,
+            sender_user_uuid,
+            sender_user_domain,
+            content,
+            sent,
+            status,
+            edited_at,
+            is_blocked,
+            in_reply_to_mimi_id,
+        }: SqlChatPreview,
+    ) -> Self {
+        let last_message = match (message_id, timestamp, content) {
+            (Some(message_id), Some(timestamp), Some(content)) => Some(
+                SqlChatMessage {
+                    message_id,
+                    mimi_id,
+                    chat_id,
+                    timestamp,
+                    sender_user_uuid,
+                    sender_user_domain,
+                    content,
+                    sent: sent.unwrap_or_default(),
+                    status: status.unwrap_or_default(),
+                    edited_at,
+                    is_blocked,
+                    in_reply_to_mimi_id,
+                }
+                .into(),
+            ),
+            _ => None,
+        };
+        Self {
+            chat_id,
+            last_message,
+            unread_messages_count: unread_messages_count.try_into().unwrap_or_default(),
+            muted_until: muted_until.map(ChatMuted::from),
+            archived,
+        }
+    }
+}
+
+impl ChatPreview {
+    /// Loads the previews of all chats, or only of the chat with the given id.
+    ///
+    /// Ordered as in [`Self::sort`].
+    pub(crate) async fn load(
+        mut connection: impl ReadConnection,
+        chat_id: Option<ChatId>,
+    ) -> sqlx::Result<Vec<Self>> {
+        // Deleted messages are excluded from the unread count, see
+        // `Chat::unread_messages_count`.
+        let excluded_status: u8 = MessageStatus::Deleted.into();
+        let previews = query_as!(
+            SqlChatPreview,
+            r#"SELECT
+                c.chat_id AS "chat_id: _",
+                c.muted_until AS "muted_until: _",
+                c.archived,
+                (SELECT COUNT(*) FROM message
+                    WHERE chat_id = c.chat_id
+                        AND sender_user_uuid IS NOT NULL
+                        AND sender_user_domain IS NOT NULL
+                        AND status != ?2
+                        AND timestamp > c.last_read
+                ) AS "unread_messages_count!: _",
+                m.message_id AS "message_id?: _",
+                m.mimi_id AS "mimi_id?: _",
+                m.timestamp AS "timestamp?: _",
+                m.sender_user_uuid AS "sender_user_uuid?: _",
+                m.sender_user_domain AS "sender_user_domain?: _",
+                m.content AS "content?: _",
+                m.sent AS "sent?",
+                m.status AS "status?",
+                m.edited_at AS "edited_at?: _",
+                b.user_uuid IS NOT NULL AS "is_blocked!: _",
+                m.in_reply_to_mimi_id AS "in_reply_to_mimi_id?: _"
+            FROM chat c
+            LEFT JOIN message m ON m.message_id = (
+                SELECT message_id FROM message
+                WHERE chat_id = c.chat_id
+                ORDER BY timestamp DESC
+                LIMIT 1
+            )
+            LEFT JOIN blocked_contact b ON b.user_uuid = m.sender_user_uuid
+                AND b.user_domain = m.sender_user_domain
+            WHERE ?1 IS NULL OR c.chat_id = ?1
+            ORDER BY m.timestamp DESC, c.chat_id"#,
+            chat_id,
+            excluded_status,
+        )
+        .fetch_all(connection.as_mut())
+        .await?;
+        Ok(previews.into_iter().map(From::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::SqlitePool;
+
+    use crate::{
+        chats::{
+            messages::persistence::tests::test_chat_message_at, persistence::tests::test_chat,
+        },
+        db::access::DbAccess,
+    };
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn load_previews(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+
+        let empty_chat = test_chat();
+        empty_chat.store(&mut connection).await?;
+
+        let chat = test_chat();
+        chat.store(&mut connection).await?;
+        // All messages are newer than the last read timestamp of the chat
+        let now = Utc::now();
+        let mut messages = Vec::new();
+        for seconds in 1..=4 {
+            let timestamp = (now + chrono::Duration::seconds(seconds)).into();
+            let message = test_chat_message_at(chat.id(), [seconds as u8; 16], timestamp);
+            message.store(&mut connection).await?;
+            messages.push(message);
+        }
+        let last = messages.last().unwrap();
+
+        let previews = ChatPreview::load(&mut connection, None).await?;
+        assert_eq!(previews.len(), 2);
+        assert_eq!(previews[0].chat_id, chat.id());
+        assert_eq!(
+            previews[0].last_message.as_ref().map(ChatMessage::id),
+            Some(last.id())
+        );
+        assert_eq!(previews[0].unread_messages_count, 4);
+        assert_eq!(previews[1].chat_id, empty_chat.id());
+        assert!(previews[1].last_message.is_none());
+        assert_eq!(previews[1].unread_messages_count, 0);
+
+        let previews = ChatPreview::load(&mut connection, Some(empty_chat.id())).await?;
+        assert_eq!(previews.len(), 1);
+        assert_eq!(previews[0].chat_id, empty_chat.id());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Read-only export of the account data.
+//!
+//! The archive is a [JSON Lines](https://jsonlines.org) document: every line is a JSON object
+//! with a `type` field, which is one of:
+//!
+//! * `header`: the first line; contains the format `version`, the `user_id` and the time of the
+//!   export (`exported_at`)
+//! * `profile`: the own user profile (`user_id`, `display_name`)
+//! * `contact`: a contact (`user_id`, `display_name`)
+//! * `chat`: a chat (`chat_id`, `title`); followed by the chat's messages
+//! * `message`: a message of the last chat (`chat_id`, `message_id`, `timestamp`, `sender`,
+//!   `text`); `sender` is missing for system messages
+//! * `attachment`: the manifest of an attachment of the last message (`chat_id`, `message_id`,
+//!   `content_type`, `size`, `filename`); the attachment content is not included
+//!
+//! Messages are ordered by time. User ids are formatted as `uuid@domain`. The chats are written
+//! like a chat export in [`ExportFormat::JsonLines`], so messages of blocked contacts are not
+//! included.
+//!
+//! Unlike the identity bundle used for multi-device, the archive contains no key material and
+//! cannot be used to restore the account.
+
+use aircommon::identifiers::UserId;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use uuid::Uuid;
+
+use crate::{
+    chats::Chat,
+    clients::{
+        CoreUser,
+        chat_export::{ChatExportWriter, ExportFormat},
+    },
+    contacts::Contact,
+};
+
+/// Version of the account archive format
+const ACCOUNT_ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum ArchiveRecord<'a> {
+    Header {
+        version: u32,
+        user_id: String,
+        exported_at: DateTime<Utc>,
+    },
+    Profile {
+        user_id: String,
+        display_name: &'a str,
+    },
+    Contact {
+        user_id: String,
+        display_name: &'a str,
+    },
+    Chat {
+        chat_id: Uuid,
+        title: &'a str,
+    },
+    Message {
+        chat_id: Uuid,
+        message_id: Uuid,
+        timestamp: DateTime<Utc>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sender: Option<String>,
+        text: Option<&'a str>,
+    },
+    Attachment {
+        chat_id: Uuid,
+        message_id: Uuid,
+        content_type: &'a str,
+        size: u64,
+        filename: &'a str,
+    },
+}
+
+impl ArchiveRecord<'_> {
+    /// Serializes the record as a single line.
+    fn to_line(&self) -> anyhow::Result<Vec<u8>> {
+        let mut line = serde_json::to_vec(self)?;
+        line.push(b'\n');
+        Ok(line)
+    }
+
+    pub(super) async fn write(&self, writer: &mut (impl AsyncWrite + Unpin)) -> anyhow::Result<()> {
+        writer.write_all(&self.to_line()?).await?;
+        Ok(())
+    }
+}
+
+pub(super) fn format_user_id(user_id: &UserId) -> String {
+    format!("{}@{}", user_id.uuid(), user_id.domain())
+}
+
+impl CoreUser {
+    /// Exports the profile, contacts and chat transcripts of this user.
+    ///
+    /// See the [module documentation](self) for the format.
+    pub async fn export_account_archive(&self) -> anyhow::Result<Vec<u8>> {
+        let mut archive = Vec::new();
+        self.write_account_archive(&mut archive).await?;
+        Ok(archive)
+    }
+
+    /// Same as [`Self::export_account_archive`], but writes the archive to `writer`.
+    ///
+    /// Messages are loaded page by page, so memory usage does not grow with the size of the
+    /// account.
+    pub async fn write_account_archive(
+        &self,
+        mut writer: impl AsyncWrite + Unpin,
+    ) -> anyhow::Result<()> {
+        ArchiveRecord::Header {
+            version: ACCOUNT_ARCHIVE_VERSION,
+            user_id: format_user_id(self.user_id()),
+            exported_at: Utc::now(),
+        }
+        .write(&mut writer)
+        .await?;
+
+        let profile = self.own_user_profile().await?;
+        ArchiveRecord::Profile {
+            user_id: format_user_id(&profile.user_id),
+            display_name: profile.display_name.as_ref(),
+        }
+        .write(&mut writer)
+        .await?;
+
+        let contacts = Contact::load_all(self.db().read().await?).await?;
+        for contact in contacts {
+            let profile = self.user_profile(&contact.user_id).await;
+            ArchiveRecord::Contact {
+                user_id: format_user_id(&contact.user_id),
+                display_name: profile.display_name.as_ref(),
+            }
+            .write(&mut writer)
+            .await?;
+        }
+
+        for chat_id in Chat::load_ordered_ids(self.db().read().await?).await? {
+            let chat_writer = ChatExportWriter::new(&mut writer, ExportFormat::JsonLines);
+            self.write_chat_export(chat_id, chat_writer).await?;
+        }
+
+        writer.flush().await?;
+        Ok(())
+    }
+}
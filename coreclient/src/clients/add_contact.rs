@@ -21,6 +21,7 @@ use aircommon::{
 };
 use airprotos::client::group::GroupData;
 use anyhow::{Context, bail};
+use chrono::Utc;
 use openmls::group::GroupId;
 use tracing::info;
 
@@ -29,9 +30,11 @@ use crate::{
     chats::GroupDataExt,
     clients::{
         connection_offer::{FriendshipPackage, payload::ConnectionInfo},
+        own_client_info::OwnClientInfo,
         targeted_message::TargetedMessageContent,
+        user_settings::ConnectionRequestLimitSetting,
     },
-    contacts::{TargetedMessageContact, UsernameContact},
+    contacts::{PartialContact, TargetedMessageContact, UsernameContact},
     db::access::WriteDbTransaction,
     groups::{Group, PartialCreateGroupParams, openmls_provider::AirOpenMlsProvider},
     key_stores::{MemoryUserKeyStore, indexed_keys::StorableIndexedKey},
@@ -47,8 +50,15 @@ pub enum AddUsernameContactError {
     DuplicateRequest,
     /// The given username is our own
     OwnUsername,
+    /// Too many connection requests are pending, see [`ConnectionRequestLimitSetting`]
+    TooManyPendingRequests,
 }
 
+/// Too many connection requests are pending, see [`ConnectionRequestLimitSetting`]
+#[derive(Debug, thiserror::Error)]
+#[error("too many pending connection requests")]
+pub struct TooManyPendingRequestsError;
+
 impl CoreUser {
     /// Create a connection with a new user via their username.
     ///
@@ -75,6 +85,9 @@ impl CoreUser {
         if self.usernames().await?.contains(&username) {
             return Ok(Err(AddUsernameContactError::OwnUsername));
         }
+        if self.connection_request_limit_exceeded().await? {
+            return Ok(Err(AddUsernameContactError::TooManyPendingRequests));
+        }
 
         // Phase 1: Fetch a connection package from the AS
         let (connection_package, connection_offer_responder) =
@@ -167,6 +180,10 @@ impl CoreUser {
             bail!("Connection request is already pending");
         }
 
+        if self.connection_request_limit_exceeded().await? {
+            return Err(TooManyPendingRequestsError.into());
+        }
+
         // Phase 1: Prepare the connection locally
         // No need to provision a group profile here, because we only have the group title and no
         // any additional data to upload.
@@ -207,6 +224,30 @@ impl CoreUser {
         }))
         .await
     }
+
+    /// Returns the limits on outgoing connection requests.
+    pub async fn connection_request_limit(&self) -> ConnectionRequestLimitSetting {
+        self.user_setting::<ConnectionRequestLimitSetting>()
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Sets the limits on outgoing connection requests.
+    ///
+    /// Requests exceeding the limits are rejected before anything is sent.
+    pub async fn set_connection_request_limit(
+        &self,
+        limit: ConnectionRequestLimitSetting,
+    ) -> anyhow::Result<()> {
+        self.set_user_setting(&limit).await
+    }
+
+    /// Returns whether sending another connection request exceeds the connection request limit.
+    async fn connection_request_limit_exceeded(&self) -> anyhow::Result<bool> {
+        let limit = self.connection_request_limit().await;
+        let sent_at = PartialContact::load_outgoing_created_at(self.db().read().await?).await?;
+        Ok(limit.is_exceeded_by_next(&sent_at, Utc::now()))
+    }
 }
 
 struct VerifiedConnectionPackagesWithGroupId<Payload = ConnectionPackage> {
@@ -226,15 +267,18 @@ impl<Payload> VerifiedConnectionPackagesWithGroupId<Payload> {
             external_group_profile: None,
             legacy_title: Some(String::new()), // Old clients still expect a title
             legacy_picture: None,
+            role_change: None,
         }
         .encode()?;
 
+        let ciphersuite = OwnClientInfo::load_ciphersuite(&mut *txn).await?;
         let (group, partial_params) = Group::create_group(
             &mut *txn,
             signing_key,
             identity_link_wrapper_key,
             self.group_id.clone(),
             group_data_bytes,
+            ciphersuite,
         )?;
 
         group.store(txn).await?;
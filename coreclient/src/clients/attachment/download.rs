@@ -21,6 +21,7 @@ use mimi_content::content_container::{EncryptionAlgorithm, HashAlgorithm};
 use reqwest::StatusCode;
 use sha2::{Digest, Sha256};
 use tokio_stream::StreamExt;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info};
 use url::Url;
 
@@ -58,16 +59,32 @@ enum AttachmentDownloadError {
     UnsupportedHashAlgorithm(HashAlgorithm),
     #[error("failed to decrypt attachment: {0}")]
     Decryption(#[from] DecryptionError),
-    #[error("hash mismatch")]
-    HashMismatch,
+    #[error(transparent)]
+    HashMismatch(#[from] AttachmentHashMismatchError),
     #[error("attachment not found")]
     NotFound,
+    #[error("attachment download cancelled")]
+    Cancelled,
+}
+
+/// The content hash of a downloaded attachment does not match the hash announced by the sender
+///
+/// The downloaded content is discarded.
+#[derive(Debug, thiserror::Error)]
+#[error("attachment content hash mismatch")]
+pub struct AttachmentHashMismatchError {
+    pub attachment_id: AttachmentId,
 }
 
 impl CoreUser {
+    /// Downloads the attachment with the given id.
+    ///
+    /// Returns a progress tracker and the download future. When `cancel` is cancelled, the
+    /// download stops and the attachment is reset to pending, so it can be downloaded again later.
     pub fn download_attachment(
         &self,
         attachment_id: AttachmentId,
+        cancel: CancellationToken,
     ) -> (
         AttachmentProgress,
         impl Future<Output = anyhow::Result<()>> + use<>,
@@ -75,7 +92,7 @@ impl CoreUser {
         let (progress_tx, progress) = AttachmentProgress::new();
         let fut = self
             .clone()
-            .download_attachment_impl(attachment_id, progress_tx);
+            .download_attachment_impl(attachment_id, progress_tx, cancel);
         (progress, fut)
     }
 
@@ -83,6 +100,7 @@ impl CoreUser {
         self,
         attachment_id: AttachmentId,
         mut progress_tx: AttachmentProgressSender,
+        cancel: CancellationToken,
     ) -> anyhow::Result<()> {
         info!(?attachment_id, "downloading attachment");
         progress_tx.report(0);
@@ -123,10 +141,18 @@ impl CoreUser {
             return Ok(());
         };
 
-        match self
-            .download_and_decrypt_attachment(pending_record, &group, &progress_tx)
+        // Cancellation aborts the download at any point, including the request of the download
+        // URL and the streaming of the content.
+        let res = cancel
+            .run_until_cancelled(self.download_and_decrypt_attachment(
+                attachment_id,
+                pending_record,
+                &group,
+                &progress_tx,
+            ))
             .await
-        {
+            .unwrap_or(Err(AttachmentDownloadError::Cancelled));
+        match res {
             Ok(content) => {
                 // Store the attachment and mark it as downloaded
                 let bytes = content.bytes.as_slice();
@@ -168,6 +194,45 @@ impl CoreUser {
 
                 Err(error.into())
             }
+            Err(AttachmentDownloadError::HashMismatch(error)) => {
+                error!(
+                    ?attachment_id,
+                    "attachment content hash mismatch, discarding content"
+                );
+
+                // The content is not stored; the pending attachment record is kept, so the
+                // download can be retried.
+                AttachmentRecord::update_status(
+                    self.db().write().await?,
+                    attachment_id,
+                    AttachmentStatus::DownloadFailed,
+                )
+                .await
+                .inspect_err(|e| error!(?attachment_id, %e, "failed to mark download as failed"))
+                .ok();
+
+                progress_tx.failed();
+
+                Err(error.into())
+            }
+            Err(AttachmentDownloadError::Cancelled) => {
+                info!(?attachment_id, "attachment download cancelled");
+
+                // The downloaded bytes are discarded and the pending attachment record is kept,
+                // so the download can be started again.
+                AttachmentRecord::update_status(
+                    self.db().write().await?,
+                    attachment_id,
+                    AttachmentStatus::Pending,
+                )
+                .await
+                .inspect_err(|e| error!(?attachment_id, %e, "failed to reset cancelled download"))
+                .ok();
+
+                progress_tx.cancelled();
+
+                Ok(())
+            }
             Err(error) => {
                 error!(
                     ?attachment_id,
@@ -212,6 +277,7 @@ impl CoreUser {
 
     async fn download_and_decrypt_attachment(
         &self,
+        attachment_id: AttachmentId,
         PendingAttachmentRecord {
             aad: _,
             remote_attachment_id,
@@ -301,11 +367,38 @@ impl CoreUser {
 
         // Verify hash
         debug!(?remote_attachment_id, "Verifying hash");
-        let recalculated_content_hash = Sha256::digest(&content.bytes);
-        if recalculated_content_hash.as_slice() != hash {
-            return Err(AttachmentDownloadError::HashMismatch);
-        }
+        verify_content_hash(attachment_id, &content.bytes, &hash)?;
 
         Ok(content)
     }
 }
+
+/// Verifies that the SHA-256 hash of the content matches the expected hash.
+fn verify_content_hash(
+    attachment_id: AttachmentId,
+    content: &[u8],
+    hash: &[u8],
+) -> Result<(), AttachmentHashMismatchError> {
+    if Sha256::digest(content).as_slice() != hash {
+        return Err(AttachmentHashMismatchError { attachment_id });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_content_hash_detects_mismatch() {
+        let attachment_id = AttachmentId::random();
+        let content = b"attachment content";
+        let hash = Sha256::digest(content);
+
+        verify_content_hash(attachment_id, content, &hash).unwrap();
+
+        let error = verify_content_hash(attachment_id, b"tampered content", &hash).unwrap_err();
+        assert_eq!(error.attachment_id, attachment_id);
+        verify_content_hash(attachment_id, content, &[]).unwrap_err();
+    }
+}
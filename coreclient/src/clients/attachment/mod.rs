@@ -7,15 +7,19 @@ use std::{collections::HashMap, fmt, str::FromStr};
 use aircommon::identifiers::{RemoteAttachmentId, RemoteAttachmentIdParseError};
 use chrono::{DateTime, Utc};
 pub use content::MimiContentExt;
+pub use download::AttachmentHashMismatchError;
 pub(crate) use persistence::AttachmentRecord;
 pub use persistence::{AttachmentContent, AttachmentStatus};
 use thiserror::Error;
 use tls_codec::{TlsDeserializeBytes, TlsSerialize, TlsSize, VLBytes};
-pub use upload::{ProvisionAttachmentError, UploadTaskError};
+pub use upload::{AttachmentTooLargeError, ProvisionAttachmentError, UploadTaskError};
 use url::Url;
 use uuid::Uuid;
 
-use crate::{ChatId, MessageId, clients::CoreUser};
+use crate::{
+    ChatId, MessageId,
+    clients::{CoreUser, user_settings::MaxAttachmentSizeSetting},
+};
 
 mod aead;
 mod content;
@@ -25,7 +29,29 @@ mod process;
 pub(crate) mod progress;
 pub(crate) mod upload;
 
+/// Content type of the inline thumbnail preview of an image attachment
+const THUMBNAIL_CONTENT_TYPE: &str = "image/webp";
+
+/// Maximum size of an attachment, unless configured otherwise
+const DEFAULT_MAX_ATTACHMENT_BYTES: u64 = 20 * 1024 * 1024;
+
 impl CoreUser {
+    /// Returns the maximum size in bytes of an attachment that can be sent.
+    pub async fn max_attachment_bytes(&self) -> u64 {
+        self.user_setting::<MaxAttachmentSizeSetting>()
+            .await
+            .map(|setting| setting.0)
+            .unwrap_or(DEFAULT_MAX_ATTACHMENT_BYTES)
+    }
+
+    /// Sets the maximum size in bytes of an attachment that can be sent.
+    ///
+    /// Larger attachments are rejected before they are encrypted and uploaded.
+    pub async fn set_max_attachment_bytes(&self, max_attachment_bytes: u64) -> anyhow::Result<()> {
+        self.set_user_setting(&MaxAttachmentSizeSetting(max_attachment_bytes))
+            .await
+    }
+
     pub async fn pending_attachments(&self) -> anyhow::Result<Vec<AttachmentId>> {
         Ok(AttachmentRecord::load_all_pending(self.db().read().await?).await?)
     }
@@ -37,6 +63,17 @@ impl CoreUser {
         Ok(AttachmentRecord::load_content(self.db().read().await?, attachment_id).await?)
     }
 
+    /// Loads the thumbnail of an image attachment.
+    ///
+    /// The thumbnail is sent inline with the message, so it is available before the attachment
+    /// is downloaded. Returns `None` for non-image attachments.
+    pub async fn load_attachment_thumbnail(
+        &self,
+        attachment_id: AttachmentId,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(AttachmentRecord::load_thumbnail(self.db().read().await?, attachment_id).await?)
+    }
+
     pub async fn attachment_status(
         &self,
         attachment_id: AttachmentId,
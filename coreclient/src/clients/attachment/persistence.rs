@@ -287,6 +287,35 @@ impl AttachmentRecord {
         Ok(())
     }
 
+    pub(crate) async fn set_thumbnail(
+        mut connection: impl WriteConnection,
+        attachment_id: AttachmentId,
+        thumbnail: &[u8],
+    ) -> sqlx::Result<()> {
+        query!(
+            "UPDATE attachment SET thumbnail = ? WHERE attachment_id = ?",
+            thumbnail,
+            attachment_id,
+        )
+        .execute(connection.as_mut())
+        .await?;
+        connection.notifier().update(attachment_id);
+        Ok(())
+    }
+
+    pub(crate) async fn load_thumbnail(
+        mut connection: impl ReadConnection,
+        attachment_id: AttachmentId,
+    ) -> sqlx::Result<Option<Vec<u8>>> {
+        let thumbnail = query_scalar!(
+            "SELECT thumbnail FROM attachment WHERE attachment_id = ?",
+            attachment_id
+        )
+        .fetch_optional(connection.as_mut())
+        .await?;
+        Ok(thumbnail.flatten())
+    }
+
     pub(crate) async fn load_content(
         mut connection: impl ReadConnection,
         attachment_id: AttachmentId,
@@ -596,6 +625,34 @@ pub(crate) mod test {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn attachment_thumbnail(pool: Pool<Sqlite>) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let chat = test_chat();
+        chat.store(pool.write().await?).await?;
+        let message = test_chat_message(chat.id());
+        message.store(pool.write().await?).await?;
+        let record = test_attachment_record(chat.id(), message.id());
+        record.store(pool.write().await?, None).await?;
+
+        let thumbnail =
+            AttachmentRecord::load_thumbnail(pool.read().await?, record.attachment_id).await?;
+        assert_eq!(thumbnail, None);
+
+        AttachmentRecord::set_thumbnail(pool.write().await?, record.attachment_id, b"thumbnail")
+            .await?;
+        let thumbnail =
+            AttachmentRecord::load_thumbnail(pool.read().await?, record.attachment_id).await?;
+        assert_eq!(thumbnail.as_deref(), Some(b"thumbnail".as_slice()));
+
+        // The thumbnail is available while the attachment is still pending
+        let content =
+            AttachmentRecord::load_content(pool.read().await?, record.attachment_id).await?;
+        assert_eq!(content, AttachmentContent::Pending);
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn attachment_content_lifecycle(pool: Pool<Sqlite>) -> anyhow::Result<()> {
         let pool = DbAccess::for_tests(pool);
@@ -4,13 +4,18 @@
 
 //! Process incoming attachments.
 
-use std::mem;
+use std::{collections::HashMap, mem};
 
 use aircommon::identifiers::RemoteAttachmentId;
-use mimi_content::{Disposition, content_container::NestedPart};
+use mimi_content::{
+    Disposition,
+    content_container::{NestedPart, PartSemantics},
+};
 use tracing::error;
 
-use super::{content::MimiContentExt, persistence::PendingAttachmentRecord};
+use super::{
+    THUMBNAIL_CONTENT_TYPE, content::MimiContentExt, persistence::PendingAttachmentRecord,
+};
 
 use crate::{
     AttachmentId, ChatMessage,
@@ -23,12 +28,15 @@ use crate::{
 impl CoreUser {
     /// Extract attachments from message's mimi content and store them as pending.
     ///
+    /// Inline thumbnails are returned together with the records of the attachments they belong
+    /// to.
+    ///
     /// Note: This function cannot store the attachment records and pending attachment records
     /// directly, because first the message needs to be stored due to foreign key constraints.
     /// But this function also modifies the message's mimi content.
     pub(crate) fn extract_attachments(
         message: &mut ChatMessage,
-    ) -> Vec<(AttachmentRecord, PendingAttachmentRecord)> {
+    ) -> Vec<(AttachmentRecord, PendingAttachmentRecord, Option<Vec<u8>>)> {
         let mut records = Vec::new();
 
         let chat_id = message.chat_id();
@@ -39,6 +47,9 @@ impl CoreUser {
             return Vec::new();
         };
 
+        let mut thumbnails = HashMap::new();
+        collect_thumbnails(&mimi_content.nested_part, &mut thumbnails, 0);
+
         let visit_res = mimi_content.visit_attachments_mut(|part| {
             let NestedPart::ExternalPart {
                 url,
@@ -93,13 +104,60 @@ impl CoreUser {
                 hash_alg: *hash_alg,
                 hash: mem::take(content_hash),
             };
-            records.push((record, pending_record));
+            records.push((record, pending_record, thumbnails.remove(url.as_str())));
 
             Ok(())
         });
         if let Err(error) = visit_res {
             error!(%error, "Failed to visit attachment; continue");
         }
+
         records
     }
 }
+
+/// Collects the inline thumbnail previews of attachments keyed by the attachment url.
+///
+/// A thumbnail belongs to the attachment in the same multipart, if it is the only attachment
+/// there.
+fn collect_thumbnails(
+    part: &NestedPart,
+    thumbnails: &mut HashMap<String, Vec<u8>>,
+    recursion_depth: usize,
+) {
+    const MAX_RECURSION_DEPTH: usize = 3;
+    if recursion_depth >= MAX_RECURSION_DEPTH {
+        return;
+    }
+
+    let NestedPart::MultiPart {
+        part_semantics: PartSemantics::ProcessAll,
+        parts,
+        ..
+    } = part
+    else {
+        return;
+    };
+
+    let mut urls = Vec::new();
+    let mut thumbnail = None;
+    for part in parts {
+        match part {
+            NestedPart::ExternalPart { url, .. } => urls.push(url),
+            NestedPart::SinglePart {
+                disposition: Disposition::Preview,
+                content_type,
+                content,
+                ..
+            } if content_type == THUMBNAIL_CONTENT_TYPE => thumbnail = Some(content),
+            NestedPart::MultiPart { .. } => {
+                collect_thumbnails(part, thumbnails, recursion_depth + 1)
+            }
+            _ => (),
+        }
+    }
+
+    if let ([url], Some(thumbnail)) = (urls.as_slice(), thumbnail) {
+        thumbnails.insert((*url).clone(), thumbnail.clone());
+    }
+}
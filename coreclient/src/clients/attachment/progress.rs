@@ -15,10 +15,18 @@ pub struct AttachmentProgress {
 #[derive(Debug, Clone, Copy)]
 pub enum AttachmentProgressEvent {
     Init,
-    Progress { bytes_loaded: usize },
+    Progress {
+        bytes_loaded: usize,
+    },
+    /// Upload progress: `sent` out of `total` bytes of the encrypted attachment are uploaded
+    Uploading {
+        sent: usize,
+        total: usize,
+    },
     Completed,
     Failed,
     NotFound,
+    Cancelled,
 }
 
 impl AttachmentProgress {
@@ -47,12 +55,30 @@ impl AttachmentProgressSender {
         }
     }
 
+    pub(super) fn uploading(&self, sent: usize, total: usize) {
+        if let Some(tx) = &self.tx {
+            let _ignore_closed = tx.send(AttachmentProgressEvent::Uploading { sent, total });
+        }
+    }
+
     pub(super) fn not_found(&mut self) {
         if let Some(tx) = self.tx.take() {
             let _ignore_closed = tx.send(AttachmentProgressEvent::NotFound);
         }
     }
 
+    pub(super) fn cancelled(&mut self) {
+        if let Some(tx) = self.tx.take() {
+            let _ignore_closed = tx.send(AttachmentProgressEvent::Cancelled);
+        }
+    }
+
+    pub(super) fn failed(&mut self) {
+        if let Some(tx) = self.tx.take() {
+            let _ignore_closed = tx.send(AttachmentProgressEvent::Failed);
+        }
+    }
+
     pub(super) fn completed(&mut self) {
         if let Some(tx) = self.tx.take() {
             let _ignore_closed = tx.send(AttachmentProgressEvent::Completed);
@@ -3,19 +3,25 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::{
+    error::Error,
     ffi::OsStr,
-    io::Cursor,
-    mem,
+    io::{self, Cursor},
+    iter, mem,
     path::{Path, PathBuf},
 };
 
 use airapiclient::{
     ApiClient,
-    ds_api::{DsAttachmentTarget, ProvisionAttachmentResponse},
+    ds_api::{
+        self, DsAttachmentTarget, DsMultipartUpload, DsRequestError, ProvisionAttachmentResponse,
+    },
 };
 use aircommon::{
     credentials::keys::ClientSigningKey,
-    crypto::aead::{AeadCiphertext, AeadEncryptable, keys::AttachmentEarKey},
+    crypto::aead::{
+        AeadCiphertext, AeadEncryptable,
+        keys::{AttachmentEarKey, GroupStateEarKey},
+    },
     identifiers::{RemoteAttachmentId, UserId},
 };
 use airprotos::{
@@ -30,12 +36,15 @@ use mimi_content::{
     MimiContent,
     content_container::{Disposition, NestedPart, PartSemantics},
 };
-use reqwest::{Body, multipart};
+use openmls::prelude::{GroupId, LeafNodeIndex};
+use reqwest::{Body, StatusCode, header::ETAG, multipart};
 use serde::Deserialize;
 use serde_json::Value;
 use sha2::{Digest, Sha256};
+use tls_codec::VLBytes;
 use tokio_stream::StreamExt;
 use tokio_util::io::ReaderStream;
+use tracing::info;
 use url::Url;
 
 use crate::{
@@ -44,15 +53,25 @@ use crate::{
     clients::{
         CoreUser,
         attachment::{
-            AttachmentBytes, AttachmentRecord,
+            AttachmentBytes, AttachmentRecord, MimiContentExt, THUMBNAIL_CONTENT_TYPE,
             aead::{AIR_ATTACHMENT_ENCRYPTION_ALG, AIR_ATTACHMENT_HASH_ALG},
             progress::{AttachmentProgress, AttachmentProgressSender},
         },
     },
+    db::access::{DbAccess, ReadConnection, WriteDbTransaction},
     groups::Group,
-    utils::image::{ReencodedAttachmentImage, load_attachment_image},
+    outbound_service::{
+        attachment_upload_queue::{AttachmentUploadQueue, UploadedPart},
+        attachment_uploads::UploadInFlightGuard,
+    },
+    utils::image::{
+        ReencodedAttachmentImage, load_attachment_image, load_attachment_image_from_bytes,
+    },
 };
 
+/// Size of the chunks in which an encrypted attachment is uploaded
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
 impl CoreUser {
     /// Uploads an attachment tied to the user (signed with their signing key)
     pub async fn upload_user_attachment(
@@ -119,16 +138,30 @@ impl CoreUser {
             .await?
             .with_context(|| format!("Can't find group with id {chat_id:?}"))?;
 
+        // check the size of the file before loading and re-encoding it
+        let max_size_bytes = self.max_attachment_bytes().await;
+        let file_size = std::fs::metadata(path)
+            .with_context(|| format!("Failed to read metadata of file at {}", path.display()))?
+            .len();
+        if let Err(error) = check_attachment_size(file_size, max_size_bytes) {
+            return Ok(Err(error));
+        }
+
         // load the attachment data
-        let mut attachment = ProcessedAttachment::from_file(path)?;
+        let attachment = ProcessedAttachment::from_file(path)?;
+
+        // check the format and size before doing any encryption or upload work
+        if let Err(error) = attachment.check_image_format() {
+            return Ok(Err(error));
+        }
+        if let Err(error) = attachment.check_size(max_size_bytes) {
+            return Ok(Err(error));
+        }
 
         // encrypt the content and provision the attachment, but don't upload it yet
-        let ProvisionedAttachment {
-            metadata,
-            ciphertext,
-            response,
-        } = match encrypt_and_provision(
-            &self.api_client()?,
+        let api_client = self.api_client()?;
+        let provisioned = match encrypt_and_provision(
+            &api_client,
             self.signing_key(),
             AttachmentTarget::Group(&group),
             StorageObjectType::Attachment,
@@ -139,18 +172,21 @@ impl CoreUser {
             Ok(result) => result,
             Err(error) => return Ok(Err(error)),
         };
+        let (metadata, upload) = start_chat_upload(&group, provisioned);
 
         // store local attachment message
         let attachment_id = metadata.attachment_id;
-        let remote_attachment_id = metadata.remote_attachment_id;
-        let content_bytes = mem::replace(&mut attachment.content.bytes, Vec::new().into());
-        let content_type = attachment.content_type;
+        let upload_guard = self
+            .outbound_service()
+            .acquire_upload(attachment_id)
+            .context("Attachment upload already in flight")?;
+        let (local_attachment, parts) = attachment.into_local_and_nested_parts(metadata)?;
 
         let content = MimiContent {
             nested_part: NestedPart::MultiPart {
                 disposition: Disposition::Attachment,
                 part_semantics: PartSemantics::ProcessAll,
-                parts: attachment.into_nested_parts(metadata)?,
+                parts,
                 language: Default::default(),
             },
             ..Default::default()
@@ -167,16 +203,10 @@ impl CoreUser {
 
                 // store attachment locally
                 // (must be done after the message is stored locally due to foreign key constraints)
-                let record = AttachmentRecord {
-                    attachment_id,
-                    remote_attachment_id: Some(remote_attachment_id),
-                    chat_id,
-                    message_id,
-                    content_type: content_type.to_owned(),
-                    status: AttachmentStatus::Uploading,
-                    created_at: Utc::now(),
-                };
-                record.store(txn, Some(content_bytes.as_slice())).await?;
+                local_attachment
+                    .store(&mut *txn, chat_id, message_id)
+                    .await?;
+                upload.enqueue(txn).await?;
 
                 Ok(message)
             },
@@ -185,10 +215,126 @@ impl CoreUser {
 
         // upload the encrypted attachment
         let (progress, task) =
-            self.upload_attachment_task(attachment_id, message, ciphertext, response);
+            self.upload_attachment_task(upload_guard, message, api_client, upload);
         Ok(Ok((attachment_id, progress, task)))
     }
 
+    /// Sends multiple attachments in a single message
+    ///
+    /// Each attachment is given as its content and filename. All attachments are uploaded before
+    /// the message is queued for sending. If any upload fails, the message is not sent, and the
+    /// failed uploads can be retried with [`CoreUser::retry_upload_chat_attachment`]. Interrupted
+    /// uploads are resumed by the outbound service, which then sends the message.
+    pub async fn send_attachments(
+        &self,
+        chat_id: ChatId,
+        contents: Vec<(Vec<u8>, String)>,
+    ) -> anyhow::Result<Result<ChatMessage, ProvisionAttachmentError>> {
+        ensure!(!contents.is_empty(), "No attachments to send");
+
+        let group = Group::load_with_chat_id_clean(self.db().read().await?, chat_id)
+            .await?
+            .with_context(|| format!("Can't find group with id {chat_id:?}"))?;
+
+        // load the attachments data and check their formats and sizes before doing any encryption
+        // or upload work
+        let max_size_bytes = self.max_attachment_bytes().await;
+        let mut attachments = Vec::with_capacity(contents.len());
+        for (content, filename) in contents {
+            let attachment = ProcessedAttachment::from_bytes(content, &filename)?;
+            if let Err(error) = attachment.check_image_format() {
+                return Ok(Err(error));
+            }
+            if let Err(error) = attachment.check_size(max_size_bytes) {
+                return Ok(Err(error));
+            }
+            attachments.push(attachment);
+        }
+
+        // encrypt the contents and provision the attachments, but don't upload them yet
+        let api_client = self.api_client()?;
+        let mut local_attachments = Vec::with_capacity(attachments.len());
+        let mut parts = Vec::with_capacity(attachments.len());
+        let mut uploads = Vec::with_capacity(attachments.len());
+        for attachment in attachments {
+            let provisioned = match encrypt_and_provision(
+                &api_client,
+                self.signing_key(),
+                AttachmentTarget::Group(&group),
+                StorageObjectType::Attachment,
+                &attachment.content,
+            )
+            .await?
+            {
+                Ok(result) => result,
+                Err(error) => return Ok(Err(error)),
+            };
+            let (metadata, upload) = start_chat_upload(&group, provisioned);
+
+            let upload_guard = self
+                .outbound_service()
+                .acquire_upload(metadata.attachment_id)
+                .context("Attachment upload already in flight")?;
+            uploads.push((upload_guard, upload));
+            let (local_attachment, attachment_parts) =
+                attachment.into_local_and_nested_parts(metadata)?;
+            local_attachments.push(local_attachment);
+            parts.push(NestedPart::MultiPart {
+                disposition: Disposition::Attachment,
+                part_semantics: PartSemantics::ProcessAll,
+                parts: attachment_parts,
+                language: Default::default(),
+            });
+        }
+
+        let content = MimiContent {
+            nested_part: NestedPart::MultiPart {
+                disposition: Disposition::Attachment,
+                part_semantics: PartSemantics::ProcessAll,
+                parts,
+                language: Default::default(),
+            },
+            ..Default::default()
+        };
+
+        // store local attachment message
+        let message = Box::pin(self.db().with_write_transaction(
+            async |txn| -> anyhow::Result<ChatMessage> {
+                let message_id = MessageId::random();
+                let message = self
+                    .send_message_transactional(&mut *txn, chat_id, message_id, content)
+                    .await?;
+                for (local_attachment, (_upload_guard, upload)) in
+                    local_attachments.into_iter().zip(&uploads)
+                {
+                    local_attachment
+                        .store(&mut *txn, chat_id, message_id)
+                        .await?;
+                    upload.enqueue(&mut *txn).await?;
+                }
+                Ok(message)
+            },
+        ))
+        .await?;
+
+        // upload the encrypted attachments
+        for (upload_guard, upload) in uploads {
+            let (_progress, task) = self.upload_attachment_task(
+                upload_guard,
+                message.clone(),
+                api_client.clone(),
+                upload,
+            );
+            task.await.map_err(|error| error.error)?;
+        }
+
+        self.outbound_service()
+            .enqueue_chat_message(message.id())
+            .await?;
+
+        Ok(Ok(message))
+    }
+
     pub async fn retry_upload_chat_attachment(
         &self,
         attachment_id: AttachmentId,
@@ -202,7 +348,7 @@ impl CoreUser {
         >,
     > {
         // load locally stored data
-        let (group, mut message, content) = self
+        let (group, mut message, content, remote_attachment_id) = self
             .db()
             .with_read_transaction(async |txn| {
                 let content = match self.load_attachment(attachment_id).await? {
@@ -235,17 +381,19 @@ impl CoreUser {
                 let group = Group::load_clean(txn, group_id)
                     .await?
                     .with_context(|| format!("Can't find group with id {group_id:?}"))?;
-                Ok((group, message, content))
+                Ok((
+                    group,
+                    message,
+                    content,
+                    attachment_record.remote_attachment_id,
+                ))
             })
             .await?;
 
         // encrypt the content and provision the attachment, but don't upload it yet
-        let ProvisionedAttachment {
-            metadata,
-            ciphertext,
-            response,
-        } = match encrypt_and_provision(
-            &self.api_client()?,
+        let api_client = self.api_client()?;
+        let provisioned = match encrypt_and_provision(
+            &api_client,
             self.signing_key(),
             AttachmentTarget::Group(&group),
             StorageObjectType::Attachment,
@@ -256,99 +404,133 @@ impl CoreUser {
             Ok(result) => result,
             Err(error) => return Ok(Err(error)),
         };
+        let (metadata, mut upload) = start_chat_upload(&group, provisioned);
+        // the content is stored under the original attachment id
+        upload.set_attachment_id(attachment_id);
 
         // update local attachment message
 
         // Note: The url of the attachment also changes here, so the relationship between the old
         // attachment record and this message is broken. We must copy the attachment record with
         // the new attachment id.
-        if let Some(mimi_content) = message.message_mut().mimi_content_mut()
-            && let NestedPart::MultiPart { parts, .. } = &mut mimi_content.nested_part
-            && let Some(attachment_part) = parts
-                .iter_mut()
-                .find(|part| part.disposition() == Disposition::Attachment)
-            && let NestedPart::ExternalPart {
-                url, key, nonce, ..
-            } = attachment_part
-            && let Ok(attachment_url) = AttachmentUrl::from_url(&url.parse()?)
-        {
-            *url = AttachmentUrl::new(metadata.remote_attachment_id, attachment_url.dimensions)
-                .to_string();
-            *key = metadata.key.into_bytes().to_vec();
-            *nonce = metadata.nonce.to_vec();
-
-            self.db()
-                .with_write_transaction(async |txn| -> anyhow::Result<()> {
-                    message.update(&mut *txn).await?;
-                    AttachmentRecord::update_remote_attachment_id(
-                        &mut *txn,
-                        metadata.attachment_id,
+        let new_key = metadata.key.into_bytes().to_vec();
+        let mut updated = false;
+        if let Some(mimi_content) = message.message_mut().mimi_content_mut() {
+            mimi_content.visit_attachments_mut(|part| {
+                if let NestedPart::ExternalPart {
+                    url, key, nonce, ..
+                } = part
+                    && let Ok(attachment_url) = url.parse::<AttachmentUrl>()
+                    && Some(attachment_url.remote_attachment_id()) == remote_attachment_id
+                {
+                    *url = AttachmentUrl::new(
                         metadata.remote_attachment_id,
+                        attachment_url.dimensions(),
                     )
-                    .await?;
-                    Ok(())
-                })
-                .await?;
-        } else {
-            bail!("Invalid attachment mimi content")
+                    .to_string();
+                    *key = new_key.clone();
+                    *nonce = metadata.nonce.to_vec();
+                    updated = true;
+                }
+                Ok(())
+            })?;
         }
+        ensure!(updated, "Invalid attachment mimi content");
+
+        let upload_guard = self
+            .outbound_service()
+            .acquire_upload(attachment_id)
+            .context("Attachment upload already in flight")?;
+        self.db()
+            .with_write_transaction(async |txn| -> anyhow::Result<()> {
+                message.update(&mut *txn).await?;
+                AttachmentRecord::update_remote_attachment_id(
+                    &mut *txn,
+                    attachment_id,
+                    metadata.remote_attachment_id,
+                )
+                .await?;
+                upload.enqueue(txn).await?;
+                Ok(())
+            })
+            .await?;
 
         // upload task
         let (progress, upload_task) =
-            self.upload_attachment_task(metadata.attachment_id, message, ciphertext, response);
+            self.upload_attachment_task(upload_guard, message, api_client, upload);
         Ok(Ok((progress, upload_task)))
     }
 
+    /// Uploads the encrypted attachment, which must already be enqueued; see
+    /// [`ChatAttachmentUpload::enqueue`].
+    ///
+    /// If a multipart upload is interrupted, the attachment stays in the queue and the upload is
+    /// resumed by the outbound service.
     fn upload_attachment_task(
         &self,
-        attachment_id: AttachmentId,
+        upload_guard: UploadInFlightGuard,
         message: ChatMessage,
-        ciphertext: Vec<u8>,
-        provision_response: ProvisionAttachmentResponse,
+        api_client: ApiClient,
+        upload: ChatAttachmentUpload,
     ) -> (
         AttachmentProgress,
         impl Future<Output = Result<ChatMessage, UploadTaskError>> + use<>,
     ) {
-        let (progress_tx, progress) = AttachmentProgress::new();
+        let (mut progress_tx, progress) = AttachmentProgress::new();
         let http_client = self.http_client();
+        let signing_key = self.signing_key().clone();
         let db = self.db().clone();
         let task = async move {
-            let res = upload_encrypted_attachment(
-                &http_client,
-                provision_response,
-                progress_tx,
-                ciphertext,
-            )
-            .await;
-            let connection = db
-                .write()
-                .await
-                .map_err(|error| UploadTaskError::new(message.id(), error.into()))?;
-            match res {
-                Ok(()) => {
-                    AttachmentRecord::update_status(
-                        connection,
-                        attachment_id,
-                        AttachmentStatus::Ready,
-                    )
-                    .await
-                    .map_err(|error| UploadTaskError::new(message.id(), error.into()))?;
+            let attachment_id = upload_guard.attachment_id();
+            let (res, resumable) = match upload {
+                ChatAttachmentUpload::Multipart(upload) => {
+                    let res = upload
+                        .upload(
+                            &db,
+                            &http_client,
+                            &api_client,
+                            &signing_key,
+                            &mut progress_tx,
+                        )
+                        .await;
+                    (res, true)
                 }
-                Err(error) => {
-                    AttachmentRecord::update_status(
-                        connection,
-                        attachment_id,
-                        AttachmentStatus::UploadFailed,
+                ChatAttachmentUpload::Single {
+                    ciphertext,
+                    response,
+                } => {
+                    let res = upload_encrypted_attachment(
+                        &http_client,
+                        response,
+                        progress_tx,
+                        ciphertext,
                     )
-                    .await
-                    .map_err(|error| UploadTaskError::new(message.id(), error.into()))?;
+                    .await;
+                    (res, false)
+                }
+            };
+            let (status, error) = match res {
+                Ok(()) => (AttachmentStatus::Ready, None),
+                Err(error) if resumable && is_upload_interrupted(&error) => {
+                    info!(?attachment_id, %error, "Attachment upload interrupted");
                     return Err(UploadTaskError {
                         message_id: message.id(),
                         error,
+                        resumable: true,
                     });
                 }
+                Err(error) => (AttachmentStatus::UploadFailed, Some(error)),
+            };
+            db.with_write_transaction(async |txn| {
+                finish_queued_upload(txn, attachment_id, status).await
+            })
+            .await
+            .map_err(|error| UploadTaskError::new(message.id(), error))?;
+            drop(upload_guard);
+            match error {
+                None => Ok(message),
+                Some(error) => Err(UploadTaskError::new(message.id(), error)),
             }
-            Ok(message)
         };
         (progress, task)
     }
@@ -358,11 +540,17 @@ impl CoreUser {
 pub struct UploadTaskError {
     pub message_id: MessageId,
     pub error: anyhow::Error,
+    /// The upload was interrupted and is resumed by the outbound service
+    pub resumable: bool,
 }
 
 impl UploadTaskError {
     fn new(message_id: MessageId, error: anyhow::Error) -> Self {
-        Self { message_id, error }
+        Self {
+            message_id,
+            error,
+            resumable: false,
+        }
     }
 }
 
@@ -378,48 +566,130 @@ struct ProcessedAttachment {
     size: u64,
 }
 
+/// Outgoing attachment which is stored locally while it is uploaded
+struct LocalAttachment {
+    attachment_id: AttachmentId,
+    remote_attachment_id: RemoteAttachmentId,
+    content_type: &'static str,
+    content: VLBytes,
+    thumbnail: Option<Vec<u8>>,
+}
+
+impl LocalAttachment {
+    /// Stores the attachment with `Uploading` status.
+    ///
+    /// Must be called after the message is stored due to foreign key constraints.
+    async fn store(
+        self,
+        txn: &mut WriteDbTransaction<'_>,
+        chat_id: ChatId,
+        message_id: MessageId,
+    ) -> anyhow::Result<()> {
+        let record = AttachmentRecord {
+            attachment_id: self.attachment_id,
+            remote_attachment_id: Some(self.remote_attachment_id),
+            chat_id,
+            message_id,
+            content_type: self.content_type.to_owned(),
+            status: AttachmentStatus::Uploading,
+            created_at: Utc::now(),
+        };
+        record
+            .store(&mut *txn, Some(self.content.as_slice()))
+            .await?;
+        if let Some(thumbnail) = self.thumbnail {
+            AttachmentRecord::set_thumbnail(txn, self.attachment_id, &thumbnail).await?;
+        }
+        Ok(())
+    }
+}
+
 struct ProcessedAttachmentImageData {
     blurhash: String,
+    thumbnail: Vec<u8>,
     width: u32,
     height: u32,
 }
 
+/// Checks that an attachment of the given size does not exceed the maximum attachment size.
+fn check_attachment_size(
+    size_bytes: u64,
+    max_size_bytes: u64,
+) -> Result<(), ProvisionAttachmentError> {
+    if size_bytes > max_size_bytes {
+        return Err(ProvisionAttachmentError::ExceedsMaxSize(
+            AttachmentTooLargeError {
+                max_size_bytes,
+                actual_size_bytes: size_bytes,
+            },
+        ));
+    }
+    Ok(())
+}
+
 impl ProcessedAttachment {
     fn from_file(path: &Path) -> anyhow::Result<Self> {
-        let (content, content_type, image_data): (AttachmentBytes, _, _) =
-            if let Some(ReencodedAttachmentImage {
-                webp_image,
-                image_dimensions: (width, height),
-                blurhash,
-            }) = load_attachment_image(path)?
-            {
-                let image_data = ProcessedAttachmentImageData {
-                    blurhash,
-                    width,
-                    height,
-                };
-                (webp_image.into(), "image/webp", Some(image_data))
-            } else {
-                let content = std::fs::read(path)
-                    .with_context(|| format!("Failed to read file at {}", path.display()))?;
-                let mime = infer::get(&content);
-                let content_type = mime
-                    .as_ref()
-                    .map(|mime| mime.mime_type())
-                    .unwrap_or("application/octet-stream");
-                (content.into(), content_type, None)
-            };
+        if let Some(image) = load_attachment_image(path)? {
+            return Self::from_image(image);
+        }
+        let content = std::fs::read(path)
+            .with_context(|| format!("Failed to read file at {}", path.display()))?;
+        let filename = path
+            .file_name()
+            .unwrap_or_else(|| OsStr::new("attachment.bin"));
+        Self::from_non_image(content, filename)
+    }
 
-        let content_hash = Sha256::digest(&content).to_vec();
+    fn from_bytes(content: Vec<u8>, filename: &str) -> anyhow::Result<Self> {
+        if let Some(image) = load_attachment_image_from_bytes(&content)? {
+            return Self::from_image(image);
+        }
+        // Only keep the file name, in case the given name contains a path
+        let filename = Path::new(filename)
+            .file_name()
+            .unwrap_or_else(|| OsStr::new("attachment.bin"));
+        Self::from_non_image(content, filename)
+    }
 
-        let filename = if image_data.is_some() {
-            PathBuf::from(Self::image_filename()).with_extension("webp")
-        } else {
-            PathBuf::from(
-                path.file_name()
-                    .unwrap_or_else(|| OsStr::new("attachment.bin")),
-            )
+    fn from_image(
+        ReencodedAttachmentImage {
+            webp_image,
+            image_dimensions: (width, height),
+            blurhash,
+            thumbnail,
+        }: ReencodedAttachmentImage,
+    ) -> anyhow::Result<Self> {
+        let image_data = ProcessedAttachmentImageData {
+            blurhash,
+            thumbnail,
+            width,
+            height,
         };
+        let filename = PathBuf::from(Self::image_filename()).with_extension("webp");
+        Self::new(
+            webp_image.into(),
+            "image/webp",
+            Some(image_data),
+            filename.as_os_str(),
+        )
+    }
+
+    fn from_non_image(content: Vec<u8>, filename: &OsStr) -> anyhow::Result<Self> {
+        let mime = infer::get(&content);
+        let content_type = mime
+            .as_ref()
+            .map(|mime| mime.mime_type())
+            .unwrap_or("application/octet-stream");
+        Self::new(content.into(), content_type, None, filename)
+    }
+
+    fn new(
+        content: AttachmentBytes,
+        content_type: &'static str,
+        image_data: Option<ProcessedAttachmentImageData>,
+        filename: &OsStr,
+    ) -> anyhow::Result<Self> {
+        let content_hash = Sha256::digest(&content).to_vec();
 
         let size = content
             .as_ref()
@@ -437,6 +707,40 @@ impl ProcessedAttachment {
         })
     }
 
+    /// Checks that the attachment is not an image which would be sent as is.
+    ///
+    /// Only re-encoded images are stripped of their metadata (e.g. EXIF with the GPS location).
+    /// Images in other formats, like HEIC, are rejected.
+    fn check_image_format(&self) -> Result<(), ProvisionAttachmentError> {
+        if self.image_data.is_none() && self.content_type.starts_with("image/") {
+            return Err(ProvisionAttachmentError::UnsupportedImageFormat {
+                content_type: self.content_type,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that the attachment does not exceed the maximum attachment size.
+    fn check_size(&self, max_size_bytes: u64) -> Result<(), ProvisionAttachmentError> {
+        check_attachment_size(self.size, max_size_bytes)
+    }
+
+    /// Splits the attachment into the data stored locally and the nested parts sent in the
+    /// message.
+    fn into_local_and_nested_parts(
+        mut self,
+        metadata: AttachmentMetadata,
+    ) -> anyhow::Result<(LocalAttachment, Vec<NestedPart>)> {
+        let local_attachment = LocalAttachment {
+            attachment_id: metadata.attachment_id,
+            remote_attachment_id: metadata.remote_attachment_id,
+            content_type: self.content_type,
+            content: mem::replace(&mut self.content.bytes, Vec::new().into()),
+            thumbnail: self.image_data.as_ref().map(|data| data.thumbnail.clone()),
+        };
+        Ok((local_attachment, self.into_nested_parts(metadata)?))
+    }
+
     fn image_filename() -> String {
         let timestamp = Local::now().format("%Y-%m-%d--%H-%M-%S");
         format!("Air--{timestamp}")
@@ -467,14 +771,29 @@ impl ProcessedAttachment {
             filename: self.filename,
         };
 
-        let blurhash = self.image_data.map(|data| NestedPart::SinglePart {
-            disposition: Disposition::Preview,
-            language: String::new(),
-            content_type: "text/blurhash".to_owned(),
-            content: data.blurhash.into_bytes(),
-        });
+        let (blurhash, thumbnail) = self
+            .image_data
+            .map(|data| {
+                let blurhash = NestedPart::SinglePart {
+                    disposition: Disposition::Preview,
+                    language: String::new(),
+                    content_type: "text/blurhash".to_owned(),
+                    content: data.blurhash.into_bytes(),
+                };
+                let thumbnail = NestedPart::SinglePart {
+                    disposition: Disposition::Preview,
+                    language: String::new(),
+                    content_type: THUMBNAIL_CONTENT_TYPE.to_owned(),
+                    content: data.thumbnail,
+                };
+                (blurhash, thumbnail)
+            })
+            .unzip();
 
-        Ok([Some(attachment), blurhash].into_iter().flatten().collect())
+        Ok([Some(attachment), blurhash, thumbnail]
+            .into_iter()
+            .flatten()
+            .collect())
     }
 }
 
@@ -498,7 +817,22 @@ impl AttachmentMetadata {
 
 #[derive(Debug)]
 pub enum ProvisionAttachmentError {
+    /// The attachment was rejected by the server
     TooLarge(AttachmentTooLargeDetail),
+    /// The attachment exceeds the locally configured maximum size
+    ExceedsMaxSize(AttachmentTooLargeError),
+    /// The attachment is an image which can't be re-encoded, so its metadata can't be stripped
+    UnsupportedImageFormat { content_type: &'static str },
+}
+
+/// The attachment exceeds the maximum attachment size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "attachment too large: {actual_size_bytes} bytes exceeds maximum of {max_size_bytes} bytes"
+)]
+pub struct AttachmentTooLargeError {
+    pub max_size_bytes: u64,
+    pub actual_size_bytes: u64,
 }
 
 enum AttachmentTarget<'a> {
@@ -538,9 +872,19 @@ async fn encrypt_and_provision(
     let (ciphertext, nonce) = ciphertext.into_parts();
 
     // provision attachment
+    //
+    // Chat attachments are uploaded in parts, such that an interrupted upload can be resumed.
+    let use_multipart_upload = matches!(target, AttachmentTarget::Group(_))
+        && matches!(object_type, StorageObjectType::Attachment);
     let content_length = ciphertext.len().try_into().context("usize overflow")?;
     let response = match api_client
-        .ds_provision_attachment(signing_key, target.into(), content_length, object_type)
+        .ds_provision_attachment(
+            signing_key,
+            target.into(),
+            content_length,
+            object_type,
+            use_multipart_upload,
+        )
         .await
     {
         Ok(response) => response,
@@ -570,16 +914,237 @@ async fn encrypt_and_provision(
     Ok(Ok(attachment))
 }
 
-async fn upload_encrypted_attachment(
+/// Starts the upload of a provisioned chat attachment.
+///
+/// If the server provisioned a multipart upload, the attachment is uploaded in parts.
+fn start_chat_upload(
+    group: &Group,
+    provisioned: ProvisionedAttachment,
+) -> (AttachmentMetadata, ChatAttachmentUpload) {
+    let ProvisionedAttachment {
+        metadata,
+        ciphertext,
+        response,
+    } = provisioned;
+    let upload = match &response.multipart_upload {
+        Some(multipart_upload) => ChatAttachmentUpload::Multipart(QueuedAttachmentUpload {
+            upload: AttachmentUploadQueue {
+                attachment_id: metadata.attachment_id,
+                ciphertext,
+                part_size: multipart_upload.part_size,
+            },
+            target: MultipartUploadTarget::new(group, metadata.remote_attachment_id),
+        }),
+        None => ChatAttachmentUpload::Single {
+            ciphertext,
+            response,
+        },
+    };
+    (metadata, upload)
+}
+
+/// Upload of an encrypted chat attachment
+enum ChatAttachmentUpload {
+    /// Uploaded in parts; an interrupted upload is resumed after the last uploaded part
+    Multipart(QueuedAttachmentUpload),
+    /// Uploaded in a single request
+    ///
+    /// Used if the server does not support multipart uploads. An interrupted upload fails.
+    Single {
+        ciphertext: Vec<u8>,
+        response: ProvisionAttachmentResponse,
+    },
+}
+
+impl ChatAttachmentUpload {
+    fn set_attachment_id(&mut self, attachment_id: AttachmentId) {
+        if let Self::Multipart(upload) = self {
+            upload.upload.attachment_id = attachment_id;
+        }
+    }
+
+    /// Stores a multipart upload in the attachment upload queue.
+    ///
+    /// Must be called after the attachment is stored due to foreign key constraints.
+    async fn enqueue(&self, txn: &mut WriteDbTransaction<'_>) -> anyhow::Result<()> {
+        if let Self::Multipart(upload) = self {
+            upload.upload.enqueue(txn).await?;
+        }
+        Ok(())
+    }
+}
+
+/// The group and the remote id of an attachment, which authorize the requests of a multipart upload
+/// at the DS
+struct MultipartUploadTarget {
+    group_state_ear_key: GroupStateEarKey,
+    group_id: GroupId,
+    sender_index: LeafNodeIndex,
+    remote_attachment_id: RemoteAttachmentId,
+}
+
+impl MultipartUploadTarget {
+    fn new(group: &Group, remote_attachment_id: RemoteAttachmentId) -> Self {
+        Self {
+            group_state_ear_key: group.group_state_ear_key().clone(),
+            group_id: group.group_id().clone(),
+            sender_index: group.own_index(),
+            remote_attachment_id,
+        }
+    }
+
+    fn ds_upload(&self) -> DsMultipartUpload<'_> {
+        DsMultipartUpload {
+            group_state_ear_key: &self.group_state_ear_key,
+            group_id: &self.group_id,
+            sender_index: self.sender_index,
+            remote_attachment_id: self.remote_attachment_id,
+        }
+    }
+}
+
+/// An attachment in the attachment upload queue together with its upload target
+pub(crate) struct QueuedAttachmentUpload {
+    upload: AttachmentUploadQueue,
+    target: MultipartUploadTarget,
+}
+
+impl QueuedAttachmentUpload {
+    /// Loads a queued upload for resuming it.
+    pub(crate) async fn load(
+        mut connection: impl ReadConnection,
+        attachment_id: AttachmentId,
+    ) -> anyhow::Result<Option<Self>> {
+        let Some(upload) = AttachmentUploadQueue::load(&mut connection, attachment_id).await?
+        else {
+            return Ok(None);
+        };
+        let record = AttachmentRecord::load(&mut connection, attachment_id)
+            .await?
+            .context("Attachment not found")?;
+        let remote_attachment_id = record
+            .remote_attachment_id
+            .context("Attachment has no remote id")?;
+        let group = Group::load_with_chat_id_clean(&mut connection, record.chat_id)
+            .await?
+            .with_context(|| format!("Can't find group with chat id {}", record.chat_id))?;
+
+        Ok(Some(Self {
+            upload,
+            target: MultipartUploadTarget::new(&group, remote_attachment_id),
+        }))
+    }
+
+    pub(crate) fn attachment_id(&self) -> AttachmentId {
+        self.upload.attachment_id
+    }
+
+    /// Uploads the parts which are not uploaded yet and completes the upload.
+    ///
+    /// Each uploaded part is recorded, such that an interrupted upload continues after the last
+    /// uploaded part. The progress is reported per part.
+    pub(crate) async fn upload(
+        &self,
+        db: &DbAccess,
+        http_client: &reqwest::Client,
+        api_client: &ApiClient,
+        signing_key: &ClientSigningKey,
+        progress_tx: &mut AttachmentProgressSender,
+    ) -> anyhow::Result<()> {
+        let attachment_id = self.attachment_id();
+        let part_size: usize = self.upload.part_size.try_into().context("usize overflow")?;
+        ensure!(part_size > 0, "Invalid part size");
+
+        let uploaded_parts =
+            AttachmentUploadQueue::load_parts(db.read().await?, attachment_id).await?;
+        let ciphertext = &self.upload.ciphertext;
+        let total = ciphertext.len();
+        progress_tx.uploading(0, total);
+
+        let mut sent = 0;
+        for (index, content) in ciphertext.chunks(part_size).enumerate() {
+            let part_number: u32 = (index + 1).try_into().context("too many parts")?;
+            if !uploaded_parts
+                .iter()
+                .any(|part| part.part_number == part_number)
+            {
+                let content_length = content.len().try_into().context("usize overflow")?;
+                let response = send_presigned(async || -> anyhow::Result<_> {
+                    let response = api_client
+                        .ds_get_attachment_part_upload_url(
+                            signing_key,
+                            self.target.ds_upload(),
+                            part_number,
+                            content_length,
+                        )
+                        .await?;
+                    let mut request = http_client.put(response.upload_url);
+                    for header in response.upload_headers {
+                        request = request.header(header.key, header.value);
+                    }
+                    Ok(request.body(content.to_vec()))
+                })
+                .await?;
+                let etag = response
+                    .headers()
+                    .get(ETAG)
+                    .context("Missing ETag of uploaded part")?
+                    .to_str()?
+                    .to_owned();
+                let part = UploadedPart { part_number, etag };
+                AttachmentUploadQueue::add_part(db.write().await?, attachment_id, &part).await?;
+            }
+            sent += content.len();
+            progress_tx.uploading(sent, total);
+        }
+
+        // complete the upload
+        let parts = AttachmentUploadQueue::load_parts(db.read().await?, attachment_id).await?;
+        ensure!(
+            parts.len() == total.div_ceil(part_size),
+            "Missing uploaded parts"
+        );
+        let parts = parts
+            .into_iter()
+            .map(|part| ds_api::UploadedPart {
+                part_number: part.part_number,
+                etag: part.etag,
+            })
+            .collect();
+        api_client
+            .ds_complete_attachment_upload(signing_key, self.target.ds_upload(), parts)
+            .await?;
+
+        progress_tx.completed();
+        Ok(())
+    }
+}
+
+/// Sends a request to a presigned URL, which is requested by `build_request`.
+///
+/// If the storage provider rejects the request, e.g. because the URL expired in the meantime, a new
+/// URL is requested and the request is sent once more.
+async fn send_presigned(
+    build_request: impl AsyncFn() -> anyhow::Result<reqwest::RequestBuilder>,
+) -> anyhow::Result<reqwest::Response> {
+    let response = build_request().await?.send().await?;
+    if response.status() != StatusCode::FORBIDDEN {
+        return Ok(response.error_for_status()?);
+    }
+    info!("Presigned request was rejected; retrying with a new URL");
+    Ok(build_request().await?.send().await?.error_for_status()?)
+}
+
+pub(crate) async fn upload_encrypted_attachment(
     http_client: &reqwest::Client,
     provision_response: ProvisionAttachmentResponse,
     mut progress_tx: AttachmentProgressSender,
     ciphertext: Vec<u8>,
 ) -> anyhow::Result<()> {
+    let total = ciphertext.len();
     if let Some(signed_post_policy) = provision_response.post_policy {
         // upload encrypted content via multipart upload
-        progress_tx.report(0);
-        let total_len = ciphertext.len();
+        progress_tx.uploading(0, total);
         multipart_upload(
             http_client,
             &provision_response.upload_url,
@@ -588,28 +1153,29 @@ async fn upload_encrypted_attachment(
         )
         .await?;
         // Note: multipart does not support reporting progress for now
-        progress_tx.report(total_len);
+        progress_tx.uploading(total, total);
         progress_tx.completed();
     } else {
-        // upload encrypted content via signed PUT url
+        // upload encrypted content in chunks via signed PUT url
         let mut request = http_client.put(provision_response.upload_url);
         for header in provision_response.upload_headers {
             request = request.header(header.key, header.value);
         }
 
-        let mut uploaded = 0;
+        let mut sent = 0;
         let tx = progress_tx.tx();
-        let stream = ReaderStream::new(Cursor::new(ciphertext)).map(move |chunk| {
-            if let Ok(chunk) = &chunk {
-                uploaded += chunk.len();
-                if let Some(tx) = tx.as_ref() {
-                    let _ignore_closed = tx.send(AttachmentProgressEvent::Progress {
-                        bytes_loaded: uploaded,
-                    });
+        let stream = ReaderStream::with_capacity(Cursor::new(ciphertext), UPLOAD_CHUNK_SIZE).map(
+            move |chunk| {
+                if let Ok(chunk) = &chunk {
+                    sent += chunk.len();
+                    if let Some(tx) = tx.as_ref() {
+                        let _ignore_closed =
+                            tx.send(AttachmentProgressEvent::Uploading { sent, total });
+                    }
                 }
-            }
-            chunk
-        });
+                chunk
+            },
+        );
 
         request
             .body(Body::wrap_stream(stream))
@@ -622,6 +1188,66 @@ async fn upload_encrypted_attachment(
     Ok(())
 }
 
+/// Returns whether the upload failed because the connection was interrupted or the server was
+/// temporarily unavailable.
+///
+/// An interrupted multipart upload stays in the attachment upload queue and is resumed by the
+/// outbound service. All other errors, e.g. a rejected request, are permanent.
+pub(crate) fn is_upload_interrupted(error: &anyhow::Error) -> bool {
+    error.chain().any(|error| {
+        if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+            match error.status() {
+                Some(status) => {
+                    status.is_server_error()
+                        || status == StatusCode::REQUEST_TIMEOUT
+                        || status == StatusCode::TOO_MANY_REQUESTS
+                }
+                None => error.is_connect() || error.is_timeout() || has_io_source(error),
+            }
+        } else if let Some(error) = error.downcast_ref::<DsRequestError>() {
+            error.is_network_error()
+        } else {
+            false
+        }
+    })
+}
+
+/// Returns whether the error was caused by an I/O error, e.g. a reset connection.
+fn has_io_source(error: &dyn Error) -> bool {
+    iter::successors(error.source(), |error| error.source()).any(|error| error.is::<io::Error>())
+}
+
+/// Sets the status of a queued attachment after its upload finished and removes it from the
+/// upload queue.
+///
+/// Returns the message the attachment belongs to.
+pub(crate) async fn finish_queued_upload(
+    txn: &mut WriteDbTransaction<'_>,
+    attachment_id: AttachmentId,
+    status: AttachmentStatus,
+) -> anyhow::Result<Option<ChatMessage>> {
+    AttachmentRecord::update_status(&mut *txn, attachment_id, status).await?;
+    AttachmentUploadQueue::remove(&mut *txn, attachment_id).await?;
+    let Some(record) = AttachmentRecord::load(&mut *txn, attachment_id).await? else {
+        return Ok(None);
+    };
+    Ok(ChatMessage::load(txn, record.message_id).await?)
+}
+
+/// Returns whether all attachments of the message are uploaded.
+pub(crate) async fn all_attachments_uploaded(
+    txn: &mut WriteDbTransaction<'_>,
+    message_id: MessageId,
+) -> sqlx::Result<bool> {
+    for attachment_id in AttachmentRecord::load_ids_by_message_id(&mut *txn, message_id).await? {
+        let status = AttachmentRecord::status(&mut *txn, attachment_id).await?;
+        if !matches!(status, Some(AttachmentStatus::Ready)) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
 #[derive(Debug, Deserialize)]
 struct PostPolicy {
     expiration: DateTime<Utc>,
@@ -665,3 +1291,41 @@ async fn multipart_upload(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// HEIF container with the HEIC brand and an EXIF payload
+    fn heic_with_exif() -> Vec<u8> {
+        let mut heic = Vec::new();
+        // ftyp box
+        heic.extend_from_slice(&24u32.to_be_bytes());
+        heic.extend_from_slice(b"ftypheic");
+        heic.extend_from_slice(&0u32.to_be_bytes());
+        heic.extend_from_slice(b"mif1heic");
+        // mdat box with the EXIF payload
+        let exif = b"\0\0\0\x06Exif\0\0MM\0*\0\0\0\x08";
+        heic.extend_from_slice(&u32::try_from(exif.len() + 8).unwrap().to_be_bytes());
+        heic.extend_from_slice(b"mdat");
+        heic.extend_from_slice(exif);
+        heic
+    }
+
+    #[test]
+    fn reject_images_which_are_not_reencoded() {
+        let attachment = ProcessedAttachment::from_bytes(heic_with_exif(), "photo.heic").unwrap();
+        assert!(attachment.image_data.is_none());
+        assert!(matches!(
+            attachment.check_image_format(),
+            Err(ProvisionAttachmentError::UnsupportedImageFormat { content_type })
+                if content_type.starts_with("image/hei")
+        ));
+
+        // Other files are sent as is
+        let attachment =
+            ProcessedAttachment::from_bytes(b"%PDF-1.7\n".to_vec(), "document.pdf").unwrap();
+        assert_eq!(attachment.content_type, "application/pdf");
+        attachment.check_image_format().unwrap();
+    }
+}
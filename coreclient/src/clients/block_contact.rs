@@ -5,7 +5,9 @@
 use aircommon::identifiers::UserId;
 use chrono::{DateTime, Utc};
 
-use crate::{clients::CoreUser, user_profiles::display_name::BaseDisplayName};
+use crate::{
+    clients::CoreUser, contacts::label::ContactLabels, user_profiles::display_name::BaseDisplayName,
+};
 
 impl CoreUser {
     pub async fn block_contact(&self, user_id: UserId) -> anyhow::Result<()> {
@@ -15,7 +17,13 @@ impl CoreUser {
             last_display_name: profile.display_name.clone(),
             blocked_at: Utc::now(),
         };
-        Ok(blocked_contact.store(self.db().write().await?).await?)
+        self.db()
+            .with_write_transaction(async |txn| {
+                blocked_contact.store(&mut *txn).await?;
+                ContactLabels::delete(txn, &blocked_contact.user_id).await?;
+                Ok(())
+            })
+            .await
     }
 
     pub async fn unblock_contact(&self, user_id: UserId) -> anyhow::Result<()> {
@@ -0,0 +1,402 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Export of a single chat.
+//!
+//! A chat can be exported as a JSON array of messages, as [JSON Lines](https://jsonlines.org) in
+//! the format of the account archive, or as a plain text transcript. Every message contains the
+//! time it was sent, the sender, the rendered text and references to its attachments. The content
+//! of the attachments is not included.
+//!
+//! Messages of blocked contacts are not exported. A 1:1 chat with a blocked contact is exported
+//! without any messages.
+
+use std::collections::HashMap;
+
+use aircommon::{identifiers::UserId, time::TimeStamp};
+use chrono::{DateTime, Utc};
+use mimi_content::{MessageStatus, content_container::NestedPart};
+use serde::Serialize;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use uuid::Uuid;
+
+use crate::{
+    ChatId, ChatMessage, ChatStatus, MessageId, MimiContentExt,
+    clients::{
+        CoreUser,
+        account_archive::{ArchiveRecord, format_user_id},
+        block_contact::BlockedContact,
+    },
+};
+
+/// Number of messages loaded from the database at once
+const MESSAGES_PAGE_SIZE: u32 = 100;
+
+/// Format of an exported chat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// JSON array with one object per message
+    Json,
+    /// JSON Lines with a `chat` record followed by `message` and `attachment` records, like the
+    /// chats in the account archive
+    JsonLines,
+    /// Plain text transcript with one line per message
+    Text,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportedMessage<'a> {
+    #[serde(skip)]
+    chat_id: ChatId,
+    #[serde(skip)]
+    message_id: MessageId,
+    timestamp: DateTime<Utc>,
+    /// Display name of the sender; missing for system messages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sender: Option<&'a str>,
+    #[serde(skip)]
+    sender_id: Option<&'a UserId>,
+    text: Option<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    attachments: Vec<ExportedAttachment<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportedAttachment<'a> {
+    url: &'a str,
+    content_type: &'a str,
+    filename: &'a str,
+    size: u64,
+}
+
+/// Writes exported messages in the given [`ExportFormat`] to the underlying writer.
+pub(super) struct ChatExportWriter<W> {
+    writer: W,
+    format: ExportFormat,
+    is_empty: bool,
+}
+
+impl<W: AsyncWrite + Unpin> ChatExportWriter<W> {
+    pub(super) fn new(writer: W, format: ExportFormat) -> Self {
+        Self {
+            writer,
+            format,
+            is_empty: true,
+        }
+    }
+
+    /// Writes the header of the chat; only JSON Lines have one.
+    async fn write_chat(&mut self, chat_id: ChatId, title: &str) -> anyhow::Result<()> {
+        if self.format == ExportFormat::JsonLines {
+            ArchiveRecord::Chat {
+                chat_id: chat_id.uuid(),
+                title,
+            }
+            .write(&mut self.writer)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn write_message(&mut self, message: &ExportedMessage<'_>) -> anyhow::Result<()> {
+        match self.format {
+            ExportFormat::Json => {
+                let mut json = vec![if self.is_empty { b'[' } else { b',' }];
+                serde_json::to_writer(&mut json, message)?;
+                self.writer.write_all(&json).await?;
+            }
+            ExportFormat::JsonLines => {
+                let chat_id = message.chat_id.uuid();
+                let message_id = message.message_id.uuid();
+                ArchiveRecord::Message {
+                    chat_id,
+                    message_id,
+                    timestamp: message.timestamp,
+                    sender: message.sender_id.map(format_user_id),
+                    text: message.text,
+                }
+                .write(&mut self.writer)
+                .await?;
+                for attachment in &message.attachments {
+                    ArchiveRecord::Attachment {
+                        chat_id,
+                        message_id,
+                        content_type: attachment.content_type,
+                        size: attachment.size,
+                        filename: attachment.filename,
+                    }
+                    .write(&mut self.writer)
+                    .await?;
+                }
+            }
+            ExportFormat::Text => {
+                let timestamp = message.timestamp.format("%Y-%m-%d %H:%M:%S");
+                let text = message.text.unwrap_or_default();
+                let mut lines = match message.sender {
+                    Some(sender) => format!("[{timestamp}] {sender}: {text}\n"),
+                    None => format!("[{timestamp}] {text}\n"),
+                };
+                for attachment in &message.attachments {
+                    lines.push_str(&format!(
+                        "    [attachment: {} ({})]\n",
+                        attachment.filename, attachment.url
+                    ));
+                }
+                self.writer.write_all(lines.as_bytes()).await?;
+            }
+        }
+        self.is_empty = false;
+        Ok(())
+    }
+
+    pub(super) async fn finish(mut self) -> anyhow::Result<W> {
+        if self.format == ExportFormat::Json {
+            if self.is_empty {
+                self.writer.write_all(b"[").await?;
+            }
+            self.writer.write_all(b"]").await?;
+        }
+        self.writer.flush().await?;
+        Ok(self.writer)
+    }
+}
+
+impl CoreUser {
+    /// Exports the messages of the chat with the given [`ChatId`] in the given [`ExportFormat`].
+    ///
+    /// See the [module documentation](self) for details.
+    pub async fn export_chat(
+        &self,
+        chat_id: ChatId,
+        format: ExportFormat,
+    ) -> anyhow::Result<Vec<u8>> {
+        let writer = ChatExportWriter::new(Vec::new(), format);
+        self.write_chat_export(chat_id, writer).await
+    }
+
+    /// Writes the export of the chat with the given [`ChatId`] to `writer` and finishes it.
+    pub(super) async fn write_chat_export<W: AsyncWrite + Unpin>(
+        &self,
+        chat_id: ChatId,
+        mut writer: ChatExportWriter<W>,
+    ) -> anyhow::Result<W> {
+        let Some(chat) = self.chat(&chat_id).await else {
+            return writer.finish().await;
+        };
+        let title = self.chat_display_title(chat_id).await;
+        writer.write_chat(chat_id, &title).await?;
+        if matches!(chat.status(), ChatStatus::Blocked)
+            || BlockedContact::check_blocked_chat(self.db().read().await?, chat_id).await?
+        {
+            return writer.finish().await;
+        }
+
+        let mut display_names: HashMap<UserId, String> = HashMap::new();
+        let mut after: TimeStamp = DateTime::<Utc>::UNIX_EPOCH.into();
+        let mut after_id = MessageId::new(Uuid::nil());
+        loop {
+            let (messages, has_newer) = ChatMessage::load_after(
+                self.db().read().await?,
+                chat_id,
+                after,
+                after_id,
+                MESSAGES_PAGE_SIZE,
+            )
+            .await?;
+            for message in &messages {
+                // Messages of blocked contacts are hidden
+                if message.status() == MessageStatus::Hidden {
+                    continue;
+                }
+                let sender = match message.message().sender() {
+                    Some(sender) => {
+                        if !display_names.contains_key(sender) {
+                            let profile = self.user_profile(sender).await;
+                            display_names.insert(sender.clone(), profile.display_name.to_string());
+                        }
+                        display_names.get(sender).map(String::as_str)
+                    }
+                    None => None,
+                };
+                let text = message
+                    .message()
+                    .string_representation(self, chat.chat_type(), false)
+                    .await;
+                let mut attachments = Vec::new();
+                if let Some(content) = message.message().mimi_content() {
+                    content.visit_attachments(|part| {
+                        if let NestedPart::ExternalPart {
+                            url,
+                            content_type,
+                            filename,
+                            size,
+                            ..
+                        } = part
+                        {
+                            attachments.push(ExportedAttachment {
+                                url,
+                                content_type,
+                                filename,
+                                size: *size,
+                            });
+                        }
+                        Ok(())
+                    })?;
+                }
+                writer
+                    .write_message(&ExportedMessage {
+                        chat_id,
+                        message_id: message.id(),
+                        timestamp: message.timestamp(),
+                        sender,
+                        sender_id: message.message().sender(),
+                        text: text.as_deref(),
+                        attachments,
+                    })
+                    .await?;
+            }
+            match messages.last() {
+                Some(last) if has_newer => {
+                    after = last.timestamp().into();
+                    after_id = last.id();
+                }
+                _ => break,
+            }
+        }
+
+        writer.finish().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exported_message(
+        sender: Option<&'static str>,
+        text: &'static str,
+    ) -> ExportedMessage<'static> {
+        ExportedMessage {
+            chat_id: ChatId::new(Uuid::from_u128(1)),
+            message_id: MessageId::new(Uuid::from_u128(2)),
+            timestamp: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            sender,
+            sender_id: None,
+            text: Some(text),
+            attachments: Vec::new(),
+        }
+    }
+
+    fn image_attachment() -> ExportedAttachment<'static> {
+        ExportedAttachment {
+            url: "air:attachment/1",
+            content_type: "image/png",
+            filename: "image.png",
+            size: 42,
+        }
+    }
+
+    #[tokio::test]
+    async fn write_json_array() -> anyhow::Result<()> {
+        let empty = ChatExportWriter::new(Vec::new(), ExportFormat::Json)
+            .finish()
+            .await?;
+        assert_eq!(empty, b"[]");
+
+        let mut writer = ChatExportWriter::new(Vec::new(), ExportFormat::Json);
+        writer
+            .write_message(&exported_message(Some("Alice"), "Hello"))
+            .await?;
+        let mut message = exported_message(None, "Alice added Bob to the chat");
+        message.attachments.push(image_attachment());
+        writer.write_message(&message).await?;
+        let exported: serde_json::Value = serde_json::from_slice(&writer.finish().await?)?;
+
+        assert_eq!(
+            exported,
+            serde_json::json!([
+                {
+                    "timestamp": "2023-11-14T22:13:20Z",
+                    "sender": "Alice",
+                    "text": "Hello",
+                },
+                {
+                    "timestamp": "2023-11-14T22:13:20Z",
+                    "text": "Alice added Bob to the chat",
+                    "attachments": [{
+                        "url": "air:attachment/1",
+                        "content_type": "image/png",
+                        "filename": "image.png",
+                        "size": 42,
+                    }],
+                },
+            ])
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_json_lines() -> anyhow::Result<()> {
+        let chat_id = ChatId::new(Uuid::from_u128(1));
+        let mut writer = ChatExportWriter::new(Vec::new(), ExportFormat::JsonLines);
+        writer.write_chat(chat_id, "Bob").await?;
+        let mut message = exported_message(Some("Alice"), "Hello");
+        message.attachments.push(image_attachment());
+        writer.write_message(&message).await?;
+        let exported = writer.finish().await?;
+        let records: Vec<serde_json::Value> = exported
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(serde_json::from_slice)
+            .collect::<Result<_, _>>()?;
+
+        let chat_id = "00000000-0000-0000-0000-000000000001";
+        let message_id = "00000000-0000-0000-0000-000000000002";
+        assert_eq!(
+            records,
+            [
+                serde_json::json!({
+                    "type": "chat",
+                    "chat_id": chat_id,
+                    "title": "Bob",
+                }),
+                serde_json::json!({
+                    "type": "message",
+                    "chat_id": chat_id,
+                    "message_id": message_id,
+                    "timestamp": "2023-11-14T22:13:20Z",
+                    "text": "Hello",
+                }),
+                serde_json::json!({
+                    "type": "attachment",
+                    "chat_id": chat_id,
+                    "message_id": message_id,
+                    "content_type": "image/png",
+                    "size": 42,
+                    "filename": "image.png",
+                }),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_text_transcript() -> anyhow::Result<()> {
+        let mut writer = ChatExportWriter::new(Vec::new(), ExportFormat::Text);
+        writer
+            .write_message(&exported_message(Some("Alice"), "Hello"))
+            .await?;
+        writer
+            .write_message(&exported_message(None, "Alice added Bob to the chat"))
+            .await?;
+        let exported = String::from_utf8(writer.finish().await?)?;
+
+        assert_eq!(
+            exported,
+            "[2023-11-14 22:13:20] Alice: Hello\n\
+             [2023-11-14 22:13:20] Alice added Bob to the chat\n"
+        );
+        Ok(())
+    }
+}
@@ -2,17 +2,25 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::collections::HashSet;
+
+#[cfg(feature = "test_utils")]
+use aircommon::identifiers::QualifiedGroupId;
 use aircommon::{
     identifiers::{MimiId, UserId},
     time::TimeStamp,
 };
-use anyhow::{Context, Result, anyhow, bail};
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use chrono::Utc;
 use mimi_room_policy::VerifiedRoomState;
 use tracing::error;
 
 use crate::{
-    ChatAttributes, ChatType, MessageDraft, MessageId,
-    chats::{Chat, PendingConnectionInfo, messages::ChatMessage},
+    ChatAttributes, ChatListFilter, ChatPreview, ChatType, MessageDraft, MessageId, UserProfile,
+    chats::{
+        Chat, PendingConnectionInfo, derive_chat_title, messages::ChatMessage, pins::PinnedMessage,
+    },
+    db::notification::{DbEntityId, DbNotification, DbOperation},
     groups::Group,
     job::{chat_operation::ChatOperation, create_chat::CreateChat},
     utils::image::resize_profile_image,
@@ -46,6 +54,25 @@ impl CoreUser {
         Ok(chat_id)
     }
 
+    /// Create a new chat with a fixed group id.
+    ///
+    /// Same as [`Self::create_chat`], but the DS reserves `group_id` instead of a random id. This
+    /// makes group creation reproducible in tests. The group id must be owned by the user's
+    /// domain and must not be reserved or used by another group.
+    #[cfg(feature = "test_utils")]
+    pub async fn create_group_with_id(
+        &self,
+        group_id: QualifiedGroupId,
+        chat_attributes: ChatAttributes,
+    ) -> Result<ChatId> {
+        let client_reference = self.create_own_client_reference();
+        let is_apq = false;
+        let job =
+            CreateChat::new(chat_attributes, client_reference, is_apq).with_group_id(group_id);
+        let chat_id = self.execute_job(job).await?;
+        Ok(chat_id)
+    }
+
     /// Delete the chat with the given [`ChatId`].
     ///
     /// Since this function causes the creation of an MLS commit, it can cause
@@ -65,6 +92,61 @@ impl CoreUser {
         Ok(Chat::load_ordered_ids(self.db().read().await?).await?)
     }
 
+    /// Returns the chats matching `filter` in the same order as [`Self::ordered_chat_ids`].
+    pub async fn chats(&self, filter: ChatListFilter) -> Result<Vec<Chat>> {
+        self.db()
+            .with_read_transaction(async |txn| Ok(Chat::load_ordered(txn, filter).await?))
+            .await
+    }
+
+    /// Returns the previews of all chats, ordered by the timestamp of the last message,
+    /// descending.
+    ///
+    /// Loads all previews with a single query. Use [`Self::update_chat_previews`] to keep the
+    /// previews up to date.
+    pub async fn chat_previews(&self) -> Result<Vec<ChatPreview>> {
+        Ok(ChatPreview::load(self.db().read().await?, None).await?)
+    }
+
+    /// Applies the chat changes in the database `notification` to `previews`.
+    ///
+    /// Only the previews of added or updated chats are reloaded; previews of removed chats are
+    /// dropped. Storing or updating a message also notifies its chat as updated.
+    pub async fn update_chat_previews(
+        &self,
+        previews: &mut Vec<ChatPreview>,
+        notification: &DbNotification,
+    ) -> Result<()> {
+        let mut connection = self.db().read().await?;
+        let mut changed = false;
+        for (id, ops) in &notification.ops {
+            let DbEntityId::Chat(chat_id) = id else {
+                continue;
+            };
+            previews.retain(|preview| preview.chat_id != *chat_id);
+            changed = true;
+            if !ops.contains(DbOperation::Remove) {
+                previews.extend(ChatPreview::load(&mut connection, Some(*chat_id)).await?);
+            }
+        }
+        if changed {
+            ChatPreview::sort(previews);
+        }
+        Ok(())
+    }
+
+    /// Archives or unarchives the chat with the given [`ChatId`].
+    ///
+    /// An archived chat is unarchived again when a new message is received in it.
+    pub async fn set_chat_archived(&self, chat_id: ChatId, archived: bool) -> Result<()> {
+        self.db()
+            .with_write_transaction(async |txn| {
+                Chat::set_archived(txn, chat_id, archived).await?;
+                Ok(())
+            })
+            .await
+    }
+
     /// Erases the chat data with the given [`ChatId`].
     ///
     /// Must not be called before the chat is deleted.
@@ -76,9 +158,8 @@ impl CoreUser {
                     .context("missing chat for deletion")?;
                 if let ChatType::PendingConnection(_) = chat.chat_type()
                     && let Some(info) = PendingConnectionInfo::load(&mut *txn, chat_id).await?
-                    && let Some(hash) = info.connection_offer_hash
                 {
-                    Group::delete_connection_offer_psk(&mut *txn, hash)?;
+                    info.delete_key_material(&mut *txn).await?;
                 }
                 Group::delete_from_db(txn, chat.group_id())
                     .await
@@ -177,6 +258,51 @@ impl CoreUser {
             .map_err(Into::into)
     }
 
+    /// Pins the message in the chat with the given [`ChatId`].
+    ///
+    /// Pins are local and are not sent to other members or devices. Pinning a message again is a
+    /// no-op. A pin is removed when its message is deleted.
+    pub async fn pin_message(&self, chat_id: ChatId, message_id: MessageId) -> Result<()> {
+        self.db()
+            .with_write_transaction(async |txn| {
+                let pinned = PinnedMessage::pin(txn, chat_id, message_id, Utc::now()).await?;
+                ensure!(
+                    pinned,
+                    "Can't find message {message_id:?} in chat {chat_id}"
+                );
+                Ok(())
+            })
+            .await
+    }
+
+    /// Unpins the message with the given [`MessageId`].
+    pub async fn unpin_message(&self, message_id: MessageId) -> Result<()> {
+        self.db()
+            .with_write_transaction(async |txn| {
+                PinnedMessage::unpin(txn, message_id).await?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Returns the pinned messages of the chat with the given [`ChatId`], most recently pinned
+    /// first.
+    ///
+    /// Messages are unpinned when they are deleted for everyone.
+    pub async fn pinned_messages(&self, chat_id: ChatId) -> Result<Vec<ChatMessage>> {
+        self.db()
+            .with_read_transaction(async |txn| {
+                let mut messages = Vec::new();
+                for message_id in PinnedMessage::load_message_ids(&mut *txn, chat_id).await? {
+                    if let Some(message) = ChatMessage::load(&mut *txn, message_id).await? {
+                        messages.push(message);
+                    }
+                }
+                Ok(messages)
+            })
+            .await
+    }
+
     pub async fn prev_message(
         &self,
         chat_id: ChatId,
@@ -247,6 +373,38 @@ impl CoreUser {
             .await
     }
 
+    /// Returns the text of the draft in the chat with the given [`ChatId`].
+    pub async fn draft(&self, chat_id: ChatId) -> anyhow::Result<Option<String>> {
+        Ok(self
+            .message_draft(chat_id)
+            .await?
+            .map(|message_draft| message_draft.message))
+    }
+
+    /// Sets the text of the draft in the chat with the given [`ChatId`], or removes the draft if
+    /// `draft` is `None`.
+    ///
+    /// A reply or edit in progress is kept. Unlike [`Self::store_message_draft`], the draft is
+    /// committed right away, so it is immediately visible in the chat list.
+    pub async fn set_draft(&self, chat_id: ChatId, draft: Option<String>) -> anyhow::Result<()> {
+        self.db()
+            .with_write_transaction(async |txn| {
+                let Some(message) = draft else {
+                    MessageDraft::delete(txn, chat_id).await?;
+                    return Ok(());
+                };
+                let mut message_draft = MessageDraft::load(&mut *txn, chat_id)
+                    .await?
+                    .unwrap_or_else(MessageDraft::empty);
+                message_draft.message = message;
+                message_draft.updated_at = Utc::now();
+                message_draft.is_committed = true;
+                message_draft.store(txn, chat_id).await?;
+                Ok(())
+            })
+            .await
+    }
+
     pub async fn commit_all_message_drafts(&self) -> anyhow::Result<()> {
         self.db()
             .with_write_transaction(async |txn| Ok(MessageDraft::commit_all(txn).await?))
@@ -268,6 +426,103 @@ impl CoreUser {
             .flatten()
     }
 
+    /// Returns the title to display for the chat with the given [`ChatId`].
+    ///
+    /// This is the explicit title of a group chat if set. Otherwise, the title is derived from
+    /// the display names of the other participants, e.g. "Alice, Bob, +3". Since it is computed
+    /// from the current state, the result reflects title and membership changes.
+    ///
+    /// In case of an error, or if the chat is not found, an empty string is returned.
+    pub async fn chat_display_title(&self, chat_id: ChatId) -> String {
+        self.try_chat_display_title(chat_id)
+            .await
+            .inspect_err(|error| {
+                error!(%chat_id, %error, "Failed to load chat display title");
+            })
+            .unwrap_or_default()
+    }
+
+    async fn try_chat_display_title(&self, chat_id: ChatId) -> Result<String> {
+        self.db()
+            .with_read_transaction(async |txn| {
+                let chat = Chat::load(&mut *txn, &chat_id)
+                    .await?
+                    .with_context(|| format!("Can't find chat with id {chat_id}"))?;
+                if let Some(attributes) = chat.attributes()
+                    && !attributes.title().is_empty()
+                {
+                    return Ok(attributes.title().to_owned());
+                }
+
+                let Some(group) = Group::load_with_chat_id(&mut *txn, chat_id).await? else {
+                    return Ok(String::new());
+                };
+                let user_ids: Vec<UserId> = group
+                    .participants()?
+                    .into_iter()
+                    .filter(|user_id| user_id != self.user_id())
+                    .collect();
+                let names = UserProfile::load_display_names(&mut *txn, &user_ids)
+                    .await?
+                    .into_iter()
+                    .map(|display_name| display_name.into_string())
+                    .collect();
+                Ok(derive_chat_title(names))
+            })
+            .await
+    }
+
+    /// Searches the chat list by chat title and by the display names of the chat participants.
+    ///
+    /// The query is split into words, and a chat matches if each word is contained in its title
+    /// or in the display name of one of the participants, ignoring case. The own display name is
+    /// not matched. For blocked contacts, the display name at the time of blocking is matched
+    /// instead of the current one.
+    ///
+    /// The chats and the display names of all participants are loaded with one query each.
+    ///
+    /// Returns group and connection chats in the same order as [`Self::ordered_chat_ids`].
+    pub async fn find_chats(&self, query: &str) -> Result<Vec<ChatId>> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.db()
+            .with_read_transaction(async |txn| {
+                let mut chats = Chat::load_search_candidates(&mut *txn).await?;
+                for chat in &mut chats {
+                    chat.user_ids.remove(self.user_id());
+                }
+                let user_ids: Vec<UserId> = chats
+                    .iter()
+                    .flat_map(|chat| chat.user_ids.iter().cloned())
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .collect();
+                let display_names =
+                    UserProfile::load_visible_display_names(&mut *txn, &user_ids).await?;
+
+                let chat_ids = chats
+                    .into_iter()
+                    .filter(|chat| {
+                        let names: Vec<String> = chat
+                            .name
+                            .iter()
+                            .map(String::as_str)
+                            .chain(chat.user_ids.iter().filter_map(|user_id| {
+                                display_names.get(user_id).map(|name| name.as_ref())
+                            }))
+                            .map(str::to_lowercase)
+                            .collect();
+                        matches_all_words(&names, &words)
+                    })
+                    .map(|chat| chat.chat_id)
+                    .collect();
+                Ok(chat_ids)
+            })
+            .await
+    }
+
     /// Get the most recent `number_of_messages` messages from the chat with the given [`ChatId`].
     pub async fn messages(
         &self,
@@ -279,6 +534,22 @@ impl CoreUser {
             .map_err(Into::into)
     }
 
+    /// Searches the text of messages, optionally only in the chat with the given [`ChatId`].
+    ///
+    /// Returns at most `limit` messages containing all words of `query`, newest first. Messages
+    /// in blocked chats and from blocked users are skipped.
+    pub async fn search_messages(
+        &self,
+        chat_id: Option<ChatId>,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<ChatMessage>> {
+        let limit = u32::try_from(limit).unwrap_or(u32::MAX);
+        ChatMessage::search(self.db().read().await?, chat_id, query, limit)
+            .await
+            .map_err(Into::into)
+    }
+
     pub async fn messages_before(
         &self,
         chat_id: ChatId,
@@ -296,6 +567,26 @@ impl CoreUser {
         .await?)
     }
 
+    /// Returns up to `count` messages of the chat with the given [`ChatId`] which are older than
+    /// the message with the given [`MessageId`], oldest first.
+    ///
+    /// Returns an empty list if `before` is the first message of the chat.
+    pub async fn messages_before_message(
+        &self,
+        chat_id: ChatId,
+        before: MessageId,
+        count: usize,
+    ) -> Result<Vec<ChatMessage>> {
+        let message = ChatMessage::load(self.db().read().await?, before)
+            .await?
+            .filter(|message| message.chat_id() == chat_id)
+            .with_context(|| format!("message {before:?} not found in chat {chat_id}"))?;
+        let (messages, _has_older) = self
+            .messages_before(chat_id, message.timestamp().into(), before, count)
+            .await?;
+        Ok(messages)
+    }
+
     pub async fn messages_after(
         &self,
         chat_id: ChatId,
@@ -356,3 +647,28 @@ impl CoreUser {
         bail!("Room does not exist")
     }
 }
+
+/// Returns whether each of the lowercase `words` is contained in one of the lowercase `names`.
+fn matches_all_words(names: &[String], words: &[String]) -> bool {
+    words
+        .iter()
+        .all(|word| names.iter().any(|name| name.contains(word.as_str())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(strings: &[&str]) -> Vec<String> {
+        strings.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn match_words_in_names() {
+        let names = strings(&["book club", "alice", "bob"]);
+        assert!(matches_all_words(&names, &strings(&["ali"])));
+        assert!(matches_all_words(&names, &strings(&["club", "bo"])));
+        assert!(!matches_all_words(&names, &strings(&["alice", "carol"])));
+        assert!(matches_all_words(&names, &[]));
+    }
+}
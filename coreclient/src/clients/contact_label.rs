@@ -0,0 +1,50 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use aircommon::identifiers::UserId;
+use anyhow::Context;
+
+use crate::{
+    Contact,
+    clients::CoreUser,
+    contacts::label::{ContactLabels, normalize_labels},
+};
+
+impl CoreUser {
+    /// Replaces the labels of the contact with the given [`UserId`].
+    ///
+    /// Labels are trimmed, and empty and duplicate labels are ignored. Passing no labels removes
+    /// all labels of the contact.
+    pub async fn set_contact_labels(
+        &self,
+        user_id: UserId,
+        labels: Vec<String>,
+    ) -> anyhow::Result<()> {
+        let labels = normalize_labels(labels)?;
+        self.db()
+            .with_write_transaction(async |txn| {
+                Contact::load(&mut *txn, &user_id)
+                    .await?
+                    .context("contact not found")?;
+                ContactLabels::replace(txn, &user_id, &labels).await?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Returns the labels of the contact with the given [`UserId`] in alphabetical order.
+    pub async fn contact_labels(&self, user_id: &UserId) -> sqlx::Result<Vec<String>> {
+        ContactLabels::load(self.db().read().await?, user_id).await
+    }
+
+    /// Returns all contacts with the given label.
+    pub async fn contacts_by_label(&self, label: &str) -> sqlx::Result<Vec<Contact>> {
+        ContactLabels::load_contacts(self.db().read().await?, label).await
+    }
+
+    /// Returns all distinct labels of contacts in alphabetical order.
+    pub async fn all_contact_labels(&self) -> sqlx::Result<Vec<String>> {
+        ContactLabels::load_all_distinct(self.db().read().await?).await
+    }
+}
@@ -386,6 +386,7 @@ impl PersistedUserState {
         db: DbAccess,
         api_clients: ApiClients,
         global_lock: GlobalLock,
+        event_loop_config: EventLoopConfig,
     ) -> CoreUser {
         let QsRegisteredUserState {
             key_store,
@@ -404,7 +405,7 @@ impl PersistedUserState {
         );
 
         // listen to handles and queue messages
-        let (event_loop, event_loop_sender, event_loop_cancel) = EventLoop::new();
+        let (event_loop, event_loop_sender, event_loop_cancel) = EventLoop::new(event_loop_config);
 
         let inner = Arc::new(CoreUserInner {
             db,
@@ -414,6 +415,7 @@ impl PersistedUserState {
             api_clients,
             http_client,
             db_notifications_pending: Arc::new(Notify::new()),
+            username_changes: watch::Sender::new(()),
             outbound_service,
             event_loop_sender,
             _event_loop_cancel: event_loop_cancel.drop_guard(),
@@ -9,12 +9,15 @@
 use airapiclient::qs_api::QsListenResponder;
 use aircommon::identifiers::Username;
 use airprotos::{auth_service::v1::UsernameQueueMessage, queue_service::v1::ListenResponse};
+use tracing::debug;
 
 use crate::{
     ChatId,
     clients::{
         CoreUser,
-        event_loop::{ClientOperation, RemoteQueueEvent},
+        event_loop::{
+            ClientOperation, EventLoopOccupancy, EventLoopStoppedError, RemoteQueueEvent,
+        },
         process::process_qs::QsProcessEventResult,
     },
 };
@@ -32,7 +35,7 @@ impl CoreUser {
         self.inner
             .event_loop_sender
             .send_remote_queue_event(message)
-            .await;
+            .await?;
         response.await.map_err(Into::into)
     }
 
@@ -45,11 +48,10 @@ impl CoreUser {
         event: ListenResponse,
     ) -> anyhow::Result<QsProcessEventResult> {
         let (event, response) = RemoteQueueEvent::qs_event(event);
-        let _ = self
-            .inner
+        self.inner
             .event_loop_sender
             .send_remote_queue_event(event)
-            .await;
+            .await?;
         response.await.map_err(Into::into)
     }
 
@@ -57,11 +59,77 @@ impl CoreUser {
     ///
     /// This is used to replace the QS listen responder after a new QS listen connection was
     /// established.
-    pub async fn replace_qs_listen_responder(&self, responder: QsListenResponder) {
-        let _ = self
-            .inner
+    pub async fn replace_qs_listen_responder(
+        &self,
+        responder: QsListenResponder,
+    ) -> Result<(), EventLoopStoppedError> {
+        self.inner
             .event_loop_sender
             .send_client_operation(ClientOperation::ReplaceQsListenResponder(responder))
+            .await
+    }
+
+    /// Lets the event loop send the pending messages of the chat after the user sent a message,
+    /// even while incoming messages are processed.
+    pub(crate) async fn request_send_messages(&self, chat_id: ChatId) {
+        self.request_flush(ClientOperation::SendMessages(chat_id))
+            .await;
+    }
+
+    /// Lets the event loop send the pending messages of the chat after the user retried a failed
+    /// message, once the queue events received before are processed.
+    pub(crate) async fn request_retry_messages(&self, chat_id: ChatId) {
+        self.request_flush(ClientOperation::RetryMessages(chat_id))
             .await;
     }
+
+    async fn request_flush(&self, operation: ClientOperation) {
+        // If the event loop is shut down, the messages are sent when the outbound service is
+        // started again.
+        if let Err(error) = self
+            .inner
+            .event_loop_sender
+            .send_client_operation(operation)
+            .await
+        {
+            debug!(%error, "Not flushing chat");
+        }
+    }
+
+    /// Gracefully stops the event loop and the outbound service, e.g. when the app is moved to the
+    /// background.
+    ///
+    /// New queue events and client operations are not accepted anymore from the moment this
+    /// function is called. The returned future resolves when the events and operations received
+    /// before are processed and the outbound service is stopped. The shutdown is undone by
+    /// [`Self::resume`].
+    pub fn shutdown(&self) -> impl Future<Output = ()> + Send + use<> {
+        let event_loop_stopped = self.inner.event_loop_sender.shutdown();
+        let core_user = self.clone();
+        async move {
+            event_loop_stopped.await;
+            // Keep the outbound service running if the event loop was resumed in the meantime
+            if !core_user.inner.event_loop_sender.is_running() {
+                core_user.stop_outbound_service().await;
+            }
+        }
+    }
+
+    /// Resumes the event loop and starts the outbound service after a [`Self::shutdown`], e.g.
+    /// when the app is moved to the foreground again.
+    ///
+    /// New queue events and client operations are accepted again immediately. If the shutdown is
+    /// still in progress, they are processed after the events and operations received before the
+    /// shutdown.
+    pub fn resume(&self) {
+        self.inner.event_loop_sender.resume();
+        drop(self.outbound_service().start());
+    }
+
+    /// Returns the number of events waiting to be processed by the event loop.
+    ///
+    /// Useful as a diagnostic for the memory pressure caused by incoming events.
+    pub fn event_loop_occupancy(&self) -> EventLoopOccupancy {
+        self.inner.event_loop_sender.occupancy()
+    }
 }
@@ -67,4 +67,30 @@ impl RemoteQueueEvent {
 /// Incoming event from the client.
 pub enum ClientOperation {
     ReplaceQsListenResponder(QsListenResponder),
+    /// Sends the pending messages of the chat after the user sent a message
+    ///
+    /// While remote queue events are processed, the outbound service is stopped, so without this
+    /// operation, the message would only be sent after all incoming messages are processed.
+    SendMessages(ChatId),
+    /// Sends the pending messages of the chat after the user retried a failed message
+    RetryMessages(ChatId),
+}
+
+impl ClientOperation {
+    /// Whether the operation is latency-sensitive and is sent on the priority lane, which the
+    /// event loop polls before remote queue events.
+    ///
+    /// Other operations are sent on the normal lane, which the event loop polls after remote
+    /// queue events.
+    pub(super) fn is_priority(&self) -> bool {
+        match self {
+            // Acks of the processed events must go to the new connection as soon as possible
+            Self::ReplaceQsListenResponder(_) => true,
+            // Sending must not be starved by a flood of incoming messages
+            Self::SendMessages(_) => true,
+            // The queued events might update the group which the message failed to be sent to,
+            // so they are processed first
+            Self::RetryMessages(_) => false,
+        }
+    }
 }
@@ -8,11 +8,11 @@
 //! via message passing. In particular, the execution of operations and processing of events is
 //! linearized.
 
-use std::sync::Weak;
+use std::sync::{Arc, Weak};
 
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio_util::sync::CancellationToken;
-use tracing::info;
+use tracing::{debug, info, warn};
 
 use crate::clients::{
     CoreUserInner,
@@ -27,6 +27,90 @@ mod api;
 mod event;
 mod response;
 
+/// Capacities of the channels passing messages to the [`EventLoop`].
+///
+/// Messages are never dropped: when a channel is full, the sender waits until the event loop has
+/// consumed a message. This way, a flood of incoming events slows down the producer (e.g. the QS
+/// listen stream) instead of growing the memory usage unboundedly.
+///
+/// Larger capacities allow to buffer bursts without slowing down the producer, at the cost of
+/// memory, since each buffered remote queue event holds a complete queue message. Smaller
+/// capacities bound the memory usage on constrained devices, at the cost of throughput.
+///
+/// Capacities are clamped to at least 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventLoopConfig {
+    /// Capacity of the channel for events from the QS and AS username queues
+    pub remote_queue_event_capacity: usize,
+    /// Capacity of the channel for client operations
+    pub client_operation_capacity: usize,
+    /// Capacity of the channel for latency-sensitive client operations, which are processed
+    /// before any remote queue events
+    pub priority_client_operation_capacity: usize,
+}
+
+impl EventLoopConfig {
+    /// Returns the config with all capacities clamped to at least 1.
+    fn clamped(self) -> Self {
+        fn clamp(name: &str, capacity: usize) -> usize {
+            if capacity == 0 {
+                warn!(
+                    name,
+                    "Event loop channel capacity must be at least 1; using 1"
+                );
+            }
+            capacity.max(1)
+        }
+        Self {
+            remote_queue_event_capacity: clamp(
+                "remote_queue_event_capacity",
+                self.remote_queue_event_capacity,
+            ),
+            client_operation_capacity: clamp(
+                "client_operation_capacity",
+                self.client_operation_capacity,
+            ),
+            priority_client_operation_capacity: clamp(
+                "priority_client_operation_capacity",
+                self.priority_client_operation_capacity,
+            ),
+        }
+    }
+}
+
+impl Default for EventLoopConfig {
+    fn default() -> Self {
+        Self {
+            remote_queue_event_capacity: 1024,
+            client_operation_capacity: 1024,
+            priority_client_operation_capacity: 64,
+        }
+    }
+}
+
+/// State of the [`EventLoop`], see [`EventLoopSender::shutdown`] and [`EventLoopSender::resume`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventLoopState {
+    Running,
+    /// Messages are not accepted anymore; the messages sent before are processed
+    ShuttingDown,
+    /// All messages sent before the shutdown are processed; waiting to be resumed
+    Paused,
+}
+
+/// The event loop does not accept messages anymore, because it was shut down or stopped.
+#[derive(Debug, thiserror::Error)]
+#[error("event loop stopped")]
+pub struct EventLoopStoppedError;
+
+/// Number of messages waiting in the channels of the [`EventLoop`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventLoopOccupancy {
+    pub remote_queue_events: usize,
+    pub client_operations: usize,
+    pub priority_client_operations: usize,
+}
+
 /// The main event loop of the [`CoreUser`].
 ///
 /// Only a single instance of this struct exists per `CoreUser`. The `CoreUser` creates the event
@@ -42,7 +126,16 @@ mod response;
 pub(crate) struct EventLoop {
     remote_queue_event_rx: mpsc::Receiver<RemoteQueueEvent>,
     client_operation_rx: mpsc::Receiver<ClientOperation>,
+    priority_client_operation_rx: mpsc::Receiver<ClientOperation>,
     cancel: CancellationToken,
+    state: Arc<watch::Sender<EventLoopState>>,
+    /// Cancelled when the event loop stopped
+    stopped: CancellationToken,
+}
+
+enum Incoming {
+    Remote(RemoteQueueEvent),
+    Client(ClientOperation),
 }
 
 impl EventLoop {
@@ -50,19 +143,32 @@ impl EventLoop {
     ///
     /// Returns the event loop, event loop sender for passing messages to the event loop and a
     /// cancellation token for stopping the event loop.
-    pub(crate) fn new() -> (Self, EventLoopSender, CancellationToken) {
-        let (remote_queue_event_tx, remote_queue_event_rx) = mpsc::channel(1024);
-        let (client_operation_tx, client_operation_rx) = mpsc::channel(1024);
+    pub(crate) fn new(config: EventLoopConfig) -> (Self, EventLoopSender, CancellationToken) {
+        let config = config.clamped();
+        let (remote_queue_event_tx, remote_queue_event_rx) =
+            mpsc::channel(config.remote_queue_event_capacity);
+        let (client_operation_tx, client_operation_rx) =
+            mpsc::channel(config.client_operation_capacity);
+        let (priority_client_operation_tx, priority_client_operation_rx) =
+            mpsc::channel(config.priority_client_operation_capacity);
 
         let cancel = CancellationToken::new();
+        let state = Arc::new(watch::Sender::new(EventLoopState::Running));
+        let stopped = CancellationToken::new();
         let event_loop_sender = EventLoopSender {
             remote_queue_event_tx,
             client_operation_tx,
+            priority_client_operation_tx,
+            state: state.clone(),
+            stopped: stopped.clone(),
         };
         let event_loop = Self {
             remote_queue_event_rx,
             client_operation_rx,
+            priority_client_operation_rx,
             cancel: cancel.clone(),
+            state,
+            stopped,
         };
         (event_loop, event_loop_sender, cancel)
     }
@@ -73,6 +179,9 @@ impl EventLoop {
     /// * the cancellation token from the creation of the event loop is cancelled
     /// * the last instance of the `CoreUser` is dropped
     /// * the event loop sender channels are closed
+    ///
+    /// When the event loop is shut down via [`EventLoopSender::shutdown`], the task processes the
+    /// messages sent before and then waits until it is resumed via [`EventLoopSender::resume`].
     pub(crate) fn spawn(self, core_user: Weak<CoreUserInner>) {
         let task = self
             .cancel
@@ -82,16 +191,21 @@ impl EventLoop {
     }
 
     async fn run(mut self, core_user: Weak<CoreUserInner>) {
-        enum Incoming {
-            Remote(RemoteQueueEvent),
-            Client(ClientOperation),
-        }
+        let _stopped = self.stopped.clone().drop_guard();
 
         let mut qs_stream_processor = QsStreamProcessor::new(None);
+        let mut state_rx = self.state.subscribe();
 
         loop {
             let incoming = tokio::select! {
-                biased; // prefer remote queue polling first
+                // prefer latency-sensitive client operations, then remote queue polling
+                biased;
+                message = self.priority_client_operation_rx.recv() => {
+                    match message {
+                        Some(message) => Incoming::Client(message),
+                        None => return, // channel closed
+                    }
+                }
                 message = self.remote_queue_event_rx.recv() => {
                     match message {
                         Some(message) => Incoming::Remote(message),
@@ -104,57 +218,357 @@ impl EventLoop {
                         None => return, // channel closed
                     }
                 }
-            };
-
-            match incoming {
-                Incoming::Remote(RemoteQueueEvent::Qs {
-                    response: event,
-                    responder,
-                }) => {
-                    let Some(core_user) = CoreUserInner::upgrade(&core_user) else {
-                        info!("Core user dropped; exit event loop");
+                _ = wait_for_state(&mut state_rx, EventLoopState::ShuttingDown) => {
+                    if !self.drain(&mut qs_stream_processor, &core_user).await {
                         return;
-                    };
-                    let result = qs_stream_processor.process_event(&core_user, event).await;
-                    responder.send(Ok(result));
+                    }
+                    self.state.send_if_modified(|state| {
+                        let shutting_down = *state == EventLoopState::ShuttingDown;
+                        if shutting_down {
+                            *state = EventLoopState::Paused;
+                        }
+                        shutting_down
+                    });
+                    info!("Event loop shut down");
+                    wait_for_state(&mut state_rx, EventLoopState::Running).await;
+                    info!("Event loop resumed");
+                    continue;
                 }
+            };
+            if !Self::handle(&mut qs_stream_processor, &core_user, incoming).await {
+                return;
+            }
+        }
+    }
 
-                Incoming::Remote(RemoteQueueEvent::Username {
-                    username,
-                    message,
-                    responder,
-                }) => {
-                    let Some(core_user) = CoreUserInner::upgrade(&core_user) else {
-                        info!("Core user dropped; exit event loop");
-                        return;
-                    };
-                    let chat_id = core_user
-                        .process_username_queue_message_event_loop(username, message)
-                        .await;
-                    responder.send(chat_id.map_err(ResponderError::Fatal));
-                }
+    /// Processes the messages which were sent before the shutdown.
+    ///
+    /// The messages are taken from the channels in the same order as by the running event loop.
+    ///
+    /// Returns `false` if the core user was dropped and the event loop must exit.
+    async fn drain(
+        &mut self,
+        qs_stream_processor: &mut QsStreamProcessor,
+        core_user: &Weak<CoreUserInner>,
+    ) -> bool {
+        info!("Shutting down event loop");
+        while let Some(incoming) = self.try_next() {
+            if !Self::handle(qs_stream_processor, core_user, incoming).await {
+                return false;
+            }
+        }
+        true
+    }
 
-                Incoming::Client(ClientOperation::ReplaceQsListenResponder(responder)) => {
-                    qs_stream_processor.replace_responder(responder);
-                }
+    /// Takes the next message from the channels without waiting, in the same order as the
+    /// running event loop.
+    fn try_next(&mut self) -> Option<Incoming> {
+        if let Ok(message) = self.priority_client_operation_rx.try_recv() {
+            Some(Incoming::Client(message))
+        } else if let Ok(message) = self.remote_queue_event_rx.try_recv() {
+            Some(Incoming::Remote(message))
+        } else if let Ok(message) = self.client_operation_rx.try_recv() {
+            Some(Incoming::Client(message))
+        } else {
+            None
+        }
+    }
+
+    /// Handles a single message.
+    ///
+    /// Returns `false` if the core user was dropped and the event loop must exit.
+    async fn handle(
+        qs_stream_processor: &mut QsStreamProcessor,
+        core_user: &Weak<CoreUserInner>,
+        incoming: Incoming,
+    ) -> bool {
+        match incoming {
+            Incoming::Remote(RemoteQueueEvent::Qs {
+                response: event,
+                responder,
+            }) => {
+                let Some(core_user) = CoreUserInner::upgrade(core_user) else {
+                    info!("Core user dropped; exit event loop");
+                    return false;
+                };
+                let result = qs_stream_processor.process_event(&core_user, event).await;
+                responder.send(Ok(result));
+            }
+
+            Incoming::Remote(RemoteQueueEvent::Username {
+                username,
+                message,
+                responder,
+            }) => {
+                let Some(core_user) = CoreUserInner::upgrade(core_user) else {
+                    info!("Core user dropped; exit event loop");
+                    return false;
+                };
+                let chat_id = core_user
+                    .process_username_queue_message_event_loop(username, message)
+                    .await;
+                responder.send(chat_id.map_err(ResponderError::Fatal));
+            }
+
+            Incoming::Client(ClientOperation::ReplaceQsListenResponder(responder)) => {
+                qs_stream_processor.replace_responder(responder);
+            }
+
+            Incoming::Client(
+                ClientOperation::SendMessages(chat_id) | ClientOperation::RetryMessages(chat_id),
+            ) => {
+                let Some(core_user) = CoreUserInner::upgrade(core_user) else {
+                    info!("Core user dropped; exit event loop");
+                    return false;
+                };
+                qs_stream_processor.flush_chat(&core_user, chat_id).await;
             }
         }
+        true
     }
 }
 
+/// Waits until the event loop is in the given state.
+async fn wait_for_state(state_rx: &mut watch::Receiver<EventLoopState>, expected: EventLoopState) {
+    // Never fails, since the event loop holds the state sender
+    let _ = state_rx.wait_for(|state| *state == expected).await;
+}
+
 /// Passes messages to the event loop.
 #[derive(Debug)]
 pub(crate) struct EventLoopSender {
     remote_queue_event_tx: mpsc::Sender<RemoteQueueEvent>,
     client_operation_tx: mpsc::Sender<ClientOperation>,
+    priority_client_operation_tx: mpsc::Sender<ClientOperation>,
+    state: Arc<watch::Sender<EventLoopState>>,
+    stopped: CancellationToken,
 }
 
 impl EventLoopSender {
-    async fn send_remote_queue_event(&self, message: RemoteQueueEvent) {
-        let _ = self.remote_queue_event_tx.send(message).await;
+    fn is_running(&self) -> bool {
+        *self.state.borrow() == EventLoopState::Running
+    }
+
+    async fn send_remote_queue_event(
+        &self,
+        message: RemoteQueueEvent,
+    ) -> Result<(), EventLoopStoppedError> {
+        if !self.is_running() {
+            debug!("Event loop is shut down; rejecting remote queue event");
+            return Err(EventLoopStoppedError);
+        }
+        if self.remote_queue_event_tx.capacity() == 0 {
+            debug!("Remote queue event channel is full; waiting for the event loop");
+        }
+        self.remote_queue_event_tx
+            .send(message)
+            .await
+            .map_err(|_| EventLoopStoppedError)
     }
 
-    async fn send_client_operation(&self, message: ClientOperation) {
-        let _ = self.client_operation_tx.send(message).await;
+    /// Sends the operation on the priority lane if it is latency-sensitive (see
+    /// [`ClientOperation::is_priority`]), otherwise on the normal lane.
+    async fn send_client_operation(
+        &self,
+        message: ClientOperation,
+    ) -> Result<(), EventLoopStoppedError> {
+        if !self.is_running() {
+            debug!("Event loop is shut down; rejecting client operation");
+            return Err(EventLoopStoppedError);
+        }
+        let tx = if message.is_priority() {
+            &self.priority_client_operation_tx
+        } else {
+            &self.client_operation_tx
+        };
+        if tx.capacity() == 0 {
+            debug!("Client operation channel is full; waiting for the event loop");
+        }
+        tx.send(message).await.map_err(|_| EventLoopStoppedError)
+    }
+
+    /// Stops accepting new messages and lets the event loop process the messages sent so far.
+    ///
+    /// Returns a future which resolves when the event loop processed these messages or stopped.
+    fn shutdown(&self) -> impl Future<Output = ()> + Send + use<> {
+        self.state.send_if_modified(|state| {
+            let running = *state == EventLoopState::Running;
+            if running {
+                *state = EventLoopState::ShuttingDown;
+            }
+            running
+        });
+        let mut state_rx = self.state.subscribe();
+        let stopped = self.stopped.clone();
+        async move {
+            tokio::select! {
+                _ = state_rx.wait_for(|state| *state != EventLoopState::ShuttingDown) => {}
+                _ = stopped.cancelled() => {}
+            }
+        }
+    }
+
+    /// Accepts new messages again after a [`Self::shutdown`].
+    ///
+    /// If the event loop is still processing the messages sent before the shutdown, it continues
+    /// with the new messages afterwards.
+    fn resume(&self) {
+        self.state.send_if_modified(|state| {
+            let shut_down = *state != EventLoopState::Running;
+            *state = EventLoopState::Running;
+            shut_down
+        });
+    }
+
+    fn occupancy(&self) -> EventLoopOccupancy {
+        fn occupancy<T>(tx: &mpsc::Sender<T>) -> usize {
+            tx.max_capacity() - tx.capacity()
+        }
+        EventLoopOccupancy {
+            remote_queue_events: occupancy(&self.remote_queue_event_tx),
+            client_operations: occupancy(&self.client_operation_tx),
+            priority_client_operations: occupancy(&self.priority_client_operation_tx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use airprotos::queue_service::v1::ListenResponse;
+    use tokio::time::timeout;
+
+    use crate::ChatId;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn full_channel_applies_backpressure() {
+        let config = EventLoopConfig {
+            remote_queue_event_capacity: 1,
+            client_operation_capacity: 1,
+            priority_client_operation_capacity: 1,
+        };
+        let (mut event_loop, sender, _cancel) = EventLoop::new(config);
+
+        let (event, _response_a) = RemoteQueueEvent::qs_event(ListenResponse::default());
+        sender.send_remote_queue_event(event).await.unwrap();
+        assert_eq!(sender.occupancy().remote_queue_events, 1);
+
+        // The channel is full: the sender waits instead of dropping the event
+        let (event, _response_b) = RemoteQueueEvent::qs_event(ListenResponse::default());
+        let send = sender.send_remote_queue_event(event);
+        tokio::pin!(send);
+        assert!(timeout(Duration::from_millis(50), &mut send).await.is_err());
+
+        // Consuming an event unblocks the sender
+        assert!(event_loop.remote_queue_event_rx.recv().await.is_some());
+        send.await.unwrap();
+        assert_eq!(sender.occupancy().remote_queue_events, 1);
+        assert!(event_loop.remote_queue_event_rx.recv().await.is_some());
+        assert_eq!(sender.occupancy().remote_queue_events, 0);
+    }
+
+    #[test]
+    fn zero_capacities_are_clamped() {
+        let config = EventLoopConfig {
+            remote_queue_event_capacity: 0,
+            client_operation_capacity: 0,
+            priority_client_operation_capacity: 0,
+        };
+        let (_event_loop, sender, _cancel) = EventLoop::new(config);
+        assert_eq!(sender.remote_queue_event_tx.max_capacity(), 1);
+        assert_eq!(sender.client_operation_tx.max_capacity(), 1);
+        assert_eq!(sender.priority_client_operation_tx.max_capacity(), 1);
+    }
+
+    #[tokio::test]
+    async fn shutdown_drains_channels() {
+        let (event_loop, sender, _cancel) = EventLoop::new(EventLoopConfig::default());
+
+        // Sent before the event loop runs, so it is queued on shutdown
+        let (event, response) = RemoteQueueEvent::qs_event(ListenResponse::default());
+        sender.send_remote_queue_event(event).await.unwrap();
+
+        let stopped = sender.shutdown();
+        // Not accepted anymore
+        let (event, _rejected) = RemoteQueueEvent::qs_event(ListenResponse::default());
+        assert!(sender.send_remote_queue_event(event).await.is_err());
+        assert_eq!(sender.occupancy().remote_queue_events, 1);
+
+        // Without a core user, the queued event is taken from the channel, but not processed
+        event_loop.spawn(Weak::new());
+        timeout(Duration::from_secs(1), stopped).await.unwrap();
+        assert!(response.await.is_err());
+        assert_eq!(sender.occupancy().remote_queue_events, 0);
+    }
+
+    #[tokio::test]
+    async fn shutdown_and_resume() {
+        let (event_loop, sender, _cancel) = EventLoop::new(EventLoopConfig::default());
+        event_loop.spawn(Weak::new());
+
+        timeout(Duration::from_secs(1), sender.shutdown())
+            .await
+            .unwrap();
+        let (event, _rejected) = RemoteQueueEvent::qs_event(ListenResponse::default());
+        assert!(sender.send_remote_queue_event(event).await.is_err());
+
+        // After resuming, events are accepted and taken from the channel again
+        sender.resume();
+        let (event, response) = RemoteQueueEvent::qs_event(ListenResponse::default());
+        sender.send_remote_queue_event(event).await.unwrap();
+        // Without a core user, the event is not processed and the event loop stops
+        assert!(
+            timeout(Duration::from_secs(1), response)
+                .await
+                .unwrap()
+                .is_err()
+        );
+        timeout(Duration::from_secs(1), sender.stopped.cancelled())
+            .await
+            .unwrap();
+        assert_eq!(sender.occupancy().remote_queue_events, 0);
+    }
+
+    #[tokio::test]
+    async fn priority_operations_are_processed_first() {
+        let (mut event_loop, sender, _cancel) = EventLoop::new(EventLoopConfig::default());
+        let chat_id = ChatId::new(uuid::Uuid::new_v4());
+
+        sender
+            .send_client_operation(ClientOperation::RetryMessages(chat_id))
+            .await
+            .unwrap();
+        let (event, _response) = RemoteQueueEvent::qs_event(ListenResponse::default());
+        sender.send_remote_queue_event(event).await.unwrap();
+        sender
+            .send_client_operation(ClientOperation::SendMessages(chat_id))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            event_loop.try_next(),
+            Some(Incoming::Client(ClientOperation::SendMessages(_)))
+        ));
+        assert!(matches!(
+            event_loop.try_next(),
+            Some(Incoming::Remote(RemoteQueueEvent::Qs { .. }))
+        ));
+        assert!(matches!(
+            event_loop.try_next(),
+            Some(Incoming::Client(ClientOperation::RetryMessages(_)))
+        ));
+        assert!(event_loop.try_next().is_none());
+    }
+
+    #[tokio::test]
+    async fn send_to_stopped_event_loop_fails() {
+        let (event_loop, sender, _cancel) = EventLoop::new(EventLoopConfig::default());
+        drop(event_loop);
+
+        let (event, _response) = RemoteQueueEvent::qs_event(ListenResponse::default());
+        assert!(sender.send_remote_queue_event(event).await.is_err());
     }
 }
@@ -0,0 +1,241 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Integrity check and repair of the client database.
+//!
+//! Besides SQLite's own integrity check, the invariants the client relies on are validated. The
+//! repair only deletes rows which are clearly orphaned; messages and their contents are never
+//! touched.
+
+use anyhow::Context;
+use sqlx::{query, query_scalar};
+
+use crate::{
+    ChatId,
+    clients::{CoreUser, attachment::AttachmentId},
+    db::access::{ReadConnection, WriteDbTransaction},
+};
+
+/// Result of an integrity check of the client database; see [`CoreUser::check_integrity`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Problems reported by SQLite's integrity check
+    pub sqlite_errors: Vec<String>,
+    /// Chats whose group is missing
+    ///
+    /// Incoming connection requests don't have a group before they are accepted and are not
+    /// included.
+    pub chats_without_group: Vec<ChatId>,
+    /// Attachments whose message or chat does not exist
+    pub orphaned_attachments: Vec<AttachmentId>,
+    /// Number of pending attachments and queued uploads whose attachment does not exist
+    pub orphaned_attachment_records: usize,
+    /// Whether the QS queue ratchet is missing
+    pub queue_ratchet_missing: bool,
+}
+
+impl IntegrityReport {
+    /// Returns whether no problems were found.
+    pub fn is_ok(&self) -> bool {
+        *self == Self::default()
+    }
+
+    async fn check(mut connection: impl ReadConnection) -> sqlx::Result<Self> {
+        let sqlite_errors = query_scalar!("PRAGMA integrity_check")
+            .fetch_all(connection.as_mut())
+            .await?
+            .into_iter()
+            .flatten()
+            .filter(|row| row != "ok")
+            .collect();
+
+        let chats_without_group = query_scalar!(
+            r#"SELECT chat_id AS "chat_id: ChatId" FROM chat c
+            WHERE NOT EXISTS (SELECT 1 FROM "group" g WHERE g.group_id = c.group_id)
+                AND NOT (c.is_incoming AND NOT c.is_confirmed_connection)"#
+        )
+        .fetch_all(connection.as_mut())
+        .await?;
+
+        let orphaned_attachments = query_scalar!(
+            r#"SELECT attachment_id AS "attachment_id: AttachmentId" FROM attachment a
+            WHERE NOT EXISTS (SELECT 1 FROM message m WHERE m.message_id = a.message_id)
+                OR NOT EXISTS (SELECT 1 FROM chat c WHERE c.chat_id = a.chat_id)"#
+        )
+        .fetch_all(connection.as_mut())
+        .await?;
+
+        let orphaned_attachment_records = query_scalar!(
+            r#"SELECT
+                (SELECT COUNT(*) FROM pending_attachment p
+                    WHERE NOT EXISTS (
+                        SELECT 1 FROM attachment a
+                        WHERE a.remote_attachment_id = p.remote_attachment_id
+                    ))
+                + (SELECT COUNT(*) FROM attachment_upload_queue q
+                    WHERE NOT EXISTS (
+                        SELECT 1 FROM attachment a WHERE a.attachment_id = q.attachment_id
+                    )) AS "count!: i64""#
+        )
+        .fetch_one(connection.as_mut())
+        .await?;
+
+        let queue_ratchet_missing = query_scalar!(
+            r#"SELECT NOT EXISTS (
+                SELECT 1 FROM queue_ratchet WHERE queue_type = 'qs'
+            ) AS "missing!: bool""#
+        )
+        .fetch_one(connection.as_mut())
+        .await?;
+
+        Ok(Self {
+            sqlite_errors,
+            chats_without_group,
+            orphaned_attachments,
+            orphaned_attachment_records: orphaned_attachment_records.try_into().unwrap_or_default(),
+            queue_ratchet_missing,
+        })
+    }
+
+    /// Deletes the orphaned attachment records.
+    ///
+    /// Returns the number of deleted rows.
+    async fn repair(txn: &mut WriteDbTransaction<'_>) -> sqlx::Result<u64> {
+        let mut deleted = 0;
+        // Attachments cascade to their pending attachments and queued uploads.
+        deleted += query!(
+            "DELETE FROM attachment
+            WHERE NOT EXISTS (SELECT 1 FROM message m WHERE m.message_id = attachment.message_id)
+                OR NOT EXISTS (SELECT 1 FROM chat c WHERE c.chat_id = attachment.chat_id)"
+        )
+        .execute(txn.as_mut())
+        .await?
+        .rows_affected();
+        deleted += query!(
+            "DELETE FROM pending_attachment
+            WHERE NOT EXISTS (
+                SELECT 1 FROM attachment a
+                WHERE a.remote_attachment_id = pending_attachment.remote_attachment_id
+            )"
+        )
+        .execute(txn.as_mut())
+        .await?
+        .rows_affected();
+        deleted += query!(
+            "DELETE FROM attachment_upload_queue
+            WHERE NOT EXISTS (
+                SELECT 1 FROM attachment a
+                WHERE a.attachment_id = attachment_upload_queue.attachment_id
+            )"
+        )
+        .execute(txn.as_mut())
+        .await?
+        .rows_affected();
+        Ok(deleted)
+    }
+}
+
+impl CoreUser {
+    /// Checks the integrity of the client database.
+    ///
+    /// Runs under the global lock, such that no other work modifies the database during the check.
+    pub async fn check_integrity(&self) -> anyhow::Result<IntegrityReport> {
+        let db = self.db().clone();
+        let report = self
+            .outbound_service()
+            .run_exclusive(async move { IntegrityReport::check(db.read().await?).await })
+            .await
+            .context("outbound service is gone")??;
+        Ok(report)
+    }
+
+    /// Deletes clearly orphaned rows from the client database, i.e. attachment records without
+    /// their message or chat.
+    ///
+    /// Other problems found by [`Self::check_integrity`] are not repaired. Runs under the global
+    /// lock and returns the number of deleted rows.
+    pub async fn repair_database(&self) -> anyhow::Result<u64> {
+        let db = self.db().clone();
+        let deleted = self
+            .outbound_service()
+            .run_exclusive(async move {
+                db.with_write_transaction(async |txn| IntegrityReport::repair(txn).await)
+                    .await
+            })
+            .await
+            .context("outbound service is gone")??;
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::SqlitePool;
+
+    use crate::{
+        chats::{
+            messages::persistence::tests::test_chat_message_with_salt,
+            persistence::tests::test_chat,
+        },
+        db::access::{DbAccess, WriteConnection},
+    };
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn check_and_repair(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+
+        let chat = test_chat();
+        chat.store(&mut connection).await?;
+        let message = test_chat_message_with_salt(chat.id(), [0; 16]);
+        message.store(&mut connection).await?;
+
+        let report = IntegrityReport::check(&mut connection).await?;
+        assert!(report.sqlite_errors.is_empty());
+        assert_eq!(report.chats_without_group, [chat.id()]);
+        assert!(report.orphaned_attachments.is_empty());
+        assert_eq!(report.orphaned_attachment_records, 0);
+        assert!(report.queue_ratchet_missing);
+
+        // Orphan an attachment by removing its message without cascading
+        let attachment_id = AttachmentId::random();
+        query("PRAGMA foreign_keys = OFF")
+            .execute(connection.as_mut())
+            .await?;
+        query(
+            "INSERT INTO attachment
+                (attachment_id, chat_id, message_id, content_type, status, created_at)
+            VALUES (?, ?, ?, 'image/png', 0, '2026-01-01T00:00:00Z')",
+        )
+        .bind(attachment_id)
+        .bind(chat.id())
+        .bind(message.id())
+        .execute(connection.as_mut())
+        .await?;
+        query("DELETE FROM message WHERE message_id = ?")
+            .bind(message.id())
+            .execute(connection.as_mut())
+            .await?;
+        query("PRAGMA foreign_keys = ON")
+            .execute(connection.as_mut())
+            .await?;
+
+        let report = IntegrityReport::check(&mut connection).await?;
+        assert_eq!(report.orphaned_attachments, [attachment_id]);
+
+        let deleted = connection
+            .with_transaction(async |txn| IntegrityReport::repair(txn).await)
+            .await?;
+        assert_eq!(deleted, 1);
+
+        let report = IntegrityReport::check(&mut connection).await?;
+        assert!(report.orphaned_attachments.is_empty());
+        // The chat is not repaired
+        assert_eq!(report.chats_without_group, [chat.id()]);
+
+        Ok(())
+    }
+}
@@ -3,13 +3,14 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use aircommon::identifiers::UserId;
+use anyhow::bail;
 use displaydoc::Display;
 
 use crate::{
     ChatId, ChatMessage,
     job::{
         JobError,
-        chat_operation::{ChatOperation, ChatOperationError},
+        chat_operation::{ChatOperation, ChatOperationError, PartialAddMembers},
     },
 };
 
@@ -28,16 +29,39 @@ impl CoreUser {
         invited_users: &[UserId],
     ) -> anyhow::Result<Result<Vec<ChatMessage>, InviteUsersError>> {
         let job = ChatOperation::add_members(chat_id, invited_users.to_vec());
-        match self.execute_job(job).await {
-            Ok(messages) => Ok(Ok(messages)),
-            Err(JobError::Domain(ChatOperationError::LeafNodeValidation(leaf_node_validation))) => {
-                Ok(Err(InviteUsersError::IncompatibleClient {
-                    reason: leaf_node_validation.to_string(),
-                }))
-            }
-            Err(JobError::Fatal(error)) => Err(error),
-            Err(other) => Err(other.into()),
+        into_invite_result(self.execute_job(job).await)
+    }
+
+    /// Invite users to an existing chat, skipping the users which can't be invited.
+    ///
+    /// Same as [`Self::invite_users`], but a user which can't be invited, e.g. because no key
+    /// package could be fetched for them, does not fail the whole operation. Only the remaining
+    /// users are added in a single commit. Users which are already members are ignored.
+    ///
+    /// An incompatible client still fails the whole operation, because the capabilities of all
+    /// added clients are checked together when the commit is created.
+    pub async fn invite_users_partial(
+        &self,
+        chat_id: ChatId,
+        invited_users: &[UserId],
+    ) -> anyhow::Result<Result<InviteOutcome, InviteUsersError>> {
+        let job = PartialAddMembers::new(chat_id, invited_users.to_vec());
+        into_invite_result(self.execute_job(job).await)
+    }
+}
+
+fn into_invite_result<T>(
+    result: Result<T, JobError<ChatOperationError>>,
+) -> anyhow::Result<Result<T, InviteUsersError>> {
+    match result {
+        Ok(value) => Ok(Ok(value)),
+        Err(JobError::Domain(ChatOperationError::LeafNodeValidation(leaf_node_validation))) => {
+            Ok(Err(InviteUsersError::IncompatibleClient {
+                reason: leaf_node_validation.to_string(),
+            }))
         }
+        Err(JobError::Fatal(error)) => Err(error),
+        Err(other) => Err(other.into()),
     }
 }
 
@@ -46,3 +70,41 @@ pub enum InviteUsersError {
     /// The client is not compatible with the group
     IncompatibleClient { reason: String },
 }
+
+/// Outcome of [`CoreUser::invite_users_partial`]
+#[derive(Debug, Default)]
+pub struct InviteOutcome {
+    /// Messages representing the changes to the group; already persisted
+    pub messages: Vec<ChatMessage>,
+    /// Users which were added to the chat
+    pub invited: Vec<UserId>,
+    /// Users which were skipped, together with the reason
+    pub failed: Vec<(UserId, InviteFailure)>,
+}
+
+impl InviteOutcome {
+    pub(crate) fn skip(&mut self, user_id: UserId, reason: InviteFailure) {
+        self.failed.push((user_id, reason));
+    }
+
+    /// Fails if any user was skipped.
+    pub(crate) fn ensure_none_failed(&self) -> anyhow::Result<()> {
+        if let Some((user_id, reason)) = self.failed.first() {
+            bail!("Can't add {user_id:?}: {reason}");
+        }
+        Ok(())
+    }
+}
+
+/// Reason why a user could not be invited to a chat
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum InviteFailure {
+    /// The user is not a contact
+    NotAContact,
+    /// The client credential of the user is unknown
+    MissingClientCredential,
+    /// The room policy does not allow inviting the user: {reason}
+    NotAllowed { reason: String },
+    /// Failed to fetch or verify the key package of the user: {reason}
+    KeyPackageUnavailable { reason: String },
+}
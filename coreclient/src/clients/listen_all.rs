@@ -0,0 +1,188 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! A single stream of inbound events from the QS queue and all username queues.
+
+use std::{
+    collections::HashSet,
+    pin::Pin,
+    sync::{Arc, Weak},
+};
+
+use airapiclient::as_api::AsListenUsernameResponder;
+use aircommon::identifiers::Username;
+use airprotos::{auth_service::v1::UsernameQueueMessage, queue_service::v1::ListenResponse};
+use tokio::sync::{mpsc, watch};
+use tokio_stream::{Stream, StreamExt, StreamMap, wrappers::ReceiverStream};
+use tracing::{debug, error, info};
+
+use crate::clients::{
+    CoreUser, CoreUserInner, ListenQueueError, ListenUsernameError,
+    reconnect::{ListenItem, ListenStatus, ReconnectPolicy, UsernameQueueEvent},
+};
+
+/// Capacity of the channel between the listen task and the returned stream
+const INBOUND_EVENT_CHANNEL_CAPACITY: usize = 16; // not too big for applying backpressure
+
+/// An event received from one of the remote queues of the user.
+#[derive(Debug)]
+pub enum InboundEvent {
+    /// Connection status of the QS queue changed
+    Status(ListenStatus),
+    /// Event from the QS queue
+    ///
+    /// To be processed with [`CoreUser::process_qs_event`]. The QS listen responder is already
+    /// passed to the event loop.
+    QsEvent(ListenResponse),
+    /// Message from the AS queue of a username
+    ///
+    /// To be processed with [`CoreUser::process_username_queue_message`] and acked with the
+    /// `responder` afterwards.
+    HandleMessage {
+        username: Username,
+        message: UsernameQueueMessage,
+        responder: Arc<AsListenUsernameResponder>,
+    },
+    /// Listening to a queue failed, because the client version is not supported anymore
+    UnsupportedVersion,
+}
+
+type QsStream =
+    Pin<Box<dyn Stream<Item = Result<ListenItem<ListenResponse>, ListenQueueError>> + Send>>;
+
+type UsernameStream =
+    Pin<Box<dyn Stream<Item = Result<ListenItem<UsernameQueueEvent>, ListenUsernameError>> + Send>>;
+
+impl CoreUser {
+    /// Listens to the QS queue and the queues of all usernames of the user.
+    ///
+    /// The returned stream multiplexes all queues. Each queue is reconnected with the given
+    /// `policy` when its connection fails or drops; the connection status of the QS queue is
+    /// yielded as [`InboundEvent::Status`]. When a username is added or removed, the username
+    /// queues are re-subscribed automatically.
+    ///
+    /// A username queue which failed with a terminal error is subscribed to again only after the
+    /// usernames changed. The stream ends when the QS queue failed with a terminal error.
+    pub fn listen_all(
+        &self,
+        policy: ReconnectPolicy,
+    ) -> impl Stream<Item = InboundEvent> + Send + use<> {
+        let qs_stream = Box::pin(self.listen_queue_reconnecting(policy.clone()));
+        let username_changes = self.inner.username_changes.subscribe();
+        let (tx, rx) = mpsc::channel(INBOUND_EVENT_CHANNEL_CAPACITY);
+        tokio::spawn(listen_all_task(
+            Arc::downgrade(&self.inner),
+            qs_stream,
+            username_changes,
+            policy,
+            tx,
+        ));
+        ReceiverStream::new(rx)
+    }
+}
+
+async fn listen_all_task(
+    core_user: Weak<CoreUserInner>,
+    mut qs_stream: QsStream,
+    mut username_changes: watch::Receiver<()>,
+    policy: ReconnectPolicy,
+    tx: mpsc::Sender<InboundEvent>,
+) {
+    let mut usernames = StreamMap::<Username, UsernameStream>::new();
+    let mut sync_usernames = true;
+
+    loop {
+        if std::mem::take(&mut sync_usernames) {
+            let Some(core_user) = CoreUserInner::upgrade(&core_user) else {
+                return;
+            };
+            sync_username_streams(&core_user, &mut usernames, &policy).await;
+        }
+
+        let event = tokio::select! {
+            _ = tx.closed() => return,
+            changed = username_changes.changed() => {
+                if changed.is_err() {
+                    return; // core user dropped
+                }
+                sync_usernames = true;
+                continue;
+            }
+            item = qs_stream.next() => match item {
+                Some(Ok(ListenItem::Status(status))) => InboundEvent::Status(status),
+                Some(Ok(ListenItem::Event(event))) => InboundEvent::QsEvent(event),
+                Some(Err(error)) => {
+                    error!(%error, "failed to listen to QS queue");
+                    if error.is_unsupported_version() {
+                        let _ = tx.send(InboundEvent::UnsupportedVersion).await;
+                    }
+                    return;
+                }
+                None => {
+                    info!("QS queue stream ended");
+                    return;
+                }
+            },
+            Some((username, item)) = usernames.next() => match item {
+                Ok(ListenItem::Status(status)) => {
+                    debug!(?status, "username queue status changed");
+                    continue;
+                }
+                Ok(ListenItem::Event(UsernameQueueEvent { message, responder })) => {
+                    InboundEvent::HandleMessage {
+                        username,
+                        message,
+                        responder,
+                    }
+                }
+                // The stream ends after a terminal error and is removed from the map
+                Err(error) if error.is_unsupported_version() => {
+                    error!(%error, "failed to listen to username queue");
+                    InboundEvent::UnsupportedVersion
+                }
+                Err(error) => {
+                    error!(%error, "failed to listen to username queue");
+                    continue;
+                }
+            },
+        };
+        if tx.send(event).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Subscribes to the queues of added usernames and unsubscribes from removed usernames.
+async fn sync_username_streams(
+    core_user: &CoreUser,
+    streams: &mut StreamMap<Username, UsernameStream>,
+    policy: &ReconnectPolicy,
+) {
+    let records = match core_user.username_records().await {
+        Ok(records) => records,
+        Err(error) => {
+            error!(%error, "failed to load username records");
+            return;
+        }
+    };
+
+    let current: HashSet<&Username> = records.iter().map(|record| &record.username).collect();
+    let removed: Vec<Username> = streams
+        .keys()
+        .filter(|username| !current.contains(username))
+        .cloned()
+        .collect();
+    for username in removed {
+        streams.remove(&username);
+    }
+
+    for record in records {
+        if streams.contains_key(&record.username) {
+            continue;
+        }
+        let username = record.username.clone();
+        let stream = core_user.listen_username_reconnecting(record, policy.clone());
+        streams.insert(username, Box::pin(stream));
+    }
+}
@@ -0,0 +1,29 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use aircommon::identifiers::UserId;
+use mimi_room_policy::RoleIndex;
+
+use crate::{ChatId, ChatMessage, job::chat_operation::ChatOperation};
+
+use super::CoreUser;
+
+impl CoreUser {
+    /// Change the role of a member of the chat with the given [`ChatId`].
+    ///
+    /// Fails with the room policy error if we are not allowed to change the role. Members are
+    /// added and removed with [`Self::invite_users`] and [`Self::remove_users`] instead.
+    ///
+    /// Returns the persisted [`ChatMessage`]s resulting from the commit, including the system
+    /// message describing the role change.
+    pub async fn set_member_role(
+        &self,
+        chat_id: ChatId,
+        target: UserId,
+        role: RoleIndex,
+    ) -> anyhow::Result<Vec<ChatMessage>> {
+        let job = ChatOperation::change_role(chat_id, target, role);
+        Ok(self.execute_job(job).await?)
+    }
+}
@@ -2,26 +2,39 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use aircommon::{identifiers::UserId, time::TimeStamp};
+use aircommon::{
+    OpenMlsRand, RustCrypto,
+    identifiers::{MimiId, UserId},
+    time::TimeStamp,
+};
 use anyhow::{Context, bail};
+use chrono::{DateTime, Utc};
 use mimi_content::{MessageStatus, MimiContent};
 
 use crate::{
     Chat, ChatId, ChatMessage, ContentMessage, MessageId,
-    chats::{StatusRecord, messages::edit::MessageEdit},
-    clients::{attachment::AttachmentRecord, block_contact::BlockedContactError},
+    chats::{StatusRecord, messages::edit::MessageEdit, pins::PinnedMessage},
+    clients::{
+        attachment::{AttachmentRecord, upload::all_attachments_uploaded},
+        block_contact::BlockedContactError,
+        user_settings::Feature,
+    },
+    contacts::presence::PresenceState,
     db::access::{WriteConnection, WriteDbTransaction},
+    outbound_service::scheduled_message_queue::ScheduledMessageQueue,
 };
 
 use super::{CoreUser, Group};
 
 impl CoreUser {
-    /// Delete a message and send the deletion to other group members.
+    /// Delete a message for everyone in the chat.
     ///
     /// This sends a NullPart message that replaces the original message,
     /// notifying all group members that the message has been deleted.
     /// The message remains visible as a "deleted" placeholder.
-    pub async fn delete_message(
+    ///
+    /// See [`Self::delete_message_locally`] for deleting a message only on this device.
+    pub async fn delete_message_for_everyone(
         &self,
         chat_id: ChatId,
         message_id: MessageId,
@@ -70,7 +83,10 @@ impl CoreUser {
     /// Delete a message locally without sending a network message.
     ///
     /// This completely removes the message from the database, including edit history
-    /// and status records. The message will no longer appear in the chat.
+    /// and status records. The message will no longer appear in the chat, and it is no longer
+    /// counted as unread. No edit record is created; other members still see the message.
+    ///
+    /// The chat is notified as updated, so that its unread count and preview are reloaded.
     pub async fn delete_message_locally(&self, message_id: MessageId) -> anyhow::Result<()> {
         self.db()
             .with_write_transaction(async |txn| {
@@ -146,10 +162,194 @@ impl CoreUser {
             },
         ))
         .await?;
+        self.request_send_messages(chat_id).await;
 
         Ok(unsent_group_message.message)
     }
 
+    /// Send a message replying to the message with the given MIMI ID and return it.
+    ///
+    /// Same as [`Self::send_message`], but sets the reply reference of the content. Recipients
+    /// resolve it to their local copy of the replied-to message, see
+    /// [`ChatMessage::in_reply_to`].
+    pub async fn send_reply(
+        &self,
+        chat_id: ChatId,
+        mut content: MimiContent,
+        reply_to: Option<MimiId>,
+    ) -> anyhow::Result<ChatMessage> {
+        content.in_reply_to = reply_to.map(|mimi_id| mimi_id.as_slice().to_vec());
+        self.send_message(chat_id, content, None).await
+    }
+
+    /// Forward the message with the given [`MessageId`] to the chat `target_chat`.
+    ///
+    /// The content of the original message is sent as a new message with a fresh MIMI ID.
+    /// Attachments are forwarded by reference, i.e. the recipients download the same blob as the
+    /// recipients of the original message. Reply and edit references are dropped, since they
+    /// point into the source chat.
+    pub async fn forward_message(
+        &self,
+        source: MessageId,
+        target_chat: ChatId,
+    ) -> anyhow::Result<ChatMessage> {
+        let message = {
+            let mut connection = self.db().read().await?;
+            let message = ChatMessage::load(&mut connection, source)
+                .await?
+                .with_context(|| format!("Can't find message with id {source:?}"))?;
+            if Chat::is_blocked(&mut connection, message.chat_id()).await? {
+                bail!(BlockedContactError);
+            }
+            message
+        };
+
+        let mut content = message
+            .message()
+            .mimi_content()
+            .context("Forwarded message does not have mimi content")?
+            .clone();
+        if content.nested_part.is_null_part() {
+            bail!("Cannot forward a deleted message");
+        }
+        let salt: [u8; 16] = RustCrypto::default().random_array()?;
+        content.salt = salt.to_vec();
+        content.replaces = None;
+        content.in_reply_to = None;
+
+        self.send_message(target_chat, content, None).await
+    }
+
+    /// Schedule a message to be sent at `send_at` and return its id.
+    ///
+    /// The message is stored right away and shown as not yet sent until it is due. It is sent by
+    /// the outbound service on its first run after `send_at`, also after a restart. Until then,
+    /// it can be canceled with [`Self::cancel_scheduled_message`].
+    pub async fn schedule_message(
+        &self,
+        chat_id: ChatId,
+        content: MimiContent,
+        send_at: DateTime<Utc>,
+    ) -> anyhow::Result<MessageId> {
+        if Chat::is_blocked(self.db().read().await?, chat_id).await? {
+            bail!(BlockedContactError);
+        }
+
+        let message_id = self
+            .db()
+            .with_write_transaction(async |txn| -> anyhow::Result<_> {
+                let message_id = MessageId::random();
+                UnsentContent {
+                    chat_id,
+                    message_id,
+                    content,
+                }
+                .store_unsent_message(&mut *txn, self.user_id(), None)
+                .await?
+                .store_group_update(&mut *txn, self.user_id())
+                .await?;
+                ScheduledMessageQueue::new(chat_id, message_id, send_at)
+                    .enqueue(&mut *txn)
+                    .await?;
+                Ok(message_id)
+            })
+            .await?;
+        // Let the service pick up the new due time
+        self.outbound_service().notify_scheduled_message();
+        Ok(message_id)
+    }
+
+    /// Cancel a message scheduled with [`Self::schedule_message`] and delete it.
+    ///
+    /// Returns `false` if the message is not scheduled anymore, e.g. because it is already due.
+    pub async fn cancel_scheduled_message(&self, message_id: MessageId) -> anyhow::Result<bool> {
+        self.db()
+            .with_write_transaction(async |txn| {
+                if !ScheduledMessageQueue::remove(&mut *txn, message_id).await? {
+                    return Ok(false);
+                }
+                ChatMessage::delete(txn, message_id).await?;
+                Ok(true)
+            })
+            .await
+    }
+
+    /// Returns the messages which failed to be sent, oldest first.
+    ///
+    /// If `chat_id` is given, only the failed messages of this chat are returned.
+    pub async fn failed_messages(
+        &self,
+        chat_id: Option<ChatId>,
+    ) -> anyhow::Result<Vec<ChatMessage>> {
+        Ok(ChatMessage::load_failed(self.db().read().await?, chat_id).await?)
+    }
+
+    /// Retries sending the message with the given id which failed to be sent.
+    ///
+    /// The message is marked as pending again and enqueued for sending. A message with attachments
+    /// which are not uploaded can't be retried; its failed uploads must be retried instead with
+    /// [`CoreUser::retry_upload_chat_attachment`], which sends the message once they succeed.
+    pub async fn retry_message(&self, message_id: MessageId) -> anyhow::Result<()> {
+        let chat_id = self
+            .db()
+            .with_write_transaction(async |txn| -> anyhow::Result<ChatId> {
+                let mut message = ChatMessage::load(&mut *txn, message_id)
+                    .await?
+                    .with_context(|| format!("Can't find message with id {message_id:?}"))?;
+                if message.status() != MessageStatus::Error || message.is_sent() {
+                    bail!("Message with id {message_id:?} did not fail to be sent");
+                }
+                if Chat::is_blocked(&mut *txn, message.chat_id()).await? {
+                    bail!(BlockedContactError);
+                }
+                if !all_attachments_uploaded(&mut *txn, message_id).await? {
+                    bail!(
+                        "Message with id {message_id:?} has attachments which are not uploaded; \
+                        retry their upload instead"
+                    );
+                }
+
+                message.set_status(MessageStatus::Unread);
+                message.update(&mut *txn).await?;
+
+                self.outbound_service()
+                    .enqueue_chat_message_in_transaction(txn, message_id)
+                    .await?;
+                Ok(message.chat_id())
+            })
+            .await?;
+        self.request_retry_messages(chat_id).await;
+        Ok(())
+    }
+
+    /// Signal the other members of the chat that the user is typing.
+    ///
+    /// The signal is neither stored nor retried, and is rate limited per chat, so this can be
+    /// called on every keystroke. It is sent by the outbound service, and only to chats in which
+    /// all members support typing indicators. Nothing is sent unless typing indicators are active.
+    pub async fn send_typing(&self, chat_id: ChatId) -> anyhow::Result<()> {
+        if self
+            .feature_flags()
+            .await
+            .is_active(Feature::TypingIndicators)
+        {
+            self.outbound_service().send_typing(chat_id);
+        }
+        Ok(())
+    }
+
+    /// Publishes the presence state of the user to all contacts.
+    ///
+    /// Presence updates are neither stored as messages nor retried. They are sent by the outbound
+    /// service, and only to connection chats in which the contact supports presence. Blocked
+    /// contacts don't receive them. Nothing is sent unless presence is active.
+    pub async fn publish_presence(&self, state: PresenceState) -> anyhow::Result<()> {
+        if self.feature_flags().await.is_active(Feature::Presence) {
+            self.outbound_service().publish_presence(state);
+        }
+        Ok(())
+    }
+
     // TODO: This should be merged with send_message as soon as we don't
     // automatically send updates before attempting to enqueue a message.
     pub(crate) async fn send_message_transactional(
@@ -243,6 +443,7 @@ impl UnsentContent {
             // (FK cascade handles local deletion where the message row is deleted)
             if is_deletion {
                 AttachmentRecord::delete_by_message_id(&mut *txn, updated.id()).await?;
+                PinnedMessage::unpin(&mut *txn, updated.id()).await?;
             }
 
             updated
@@ -25,21 +25,24 @@ use aircommon::{
         kdf::keys::RatchetSecret,
         signatures::keys::{QsClientSigningKey, QsUserSigningKey},
     },
-    identifiers::{ClientConfig, QsClientId, QsReference, QsUserId, UserId},
+    identifiers::{ClientConfig, QsClientId, QsReference, QsUserId, UserId, Username},
     messages::{FriendshipToken, QueueMessage, push_token::PushToken},
+    mls_group_config::{DEFAULT_CIPHERSUITE, SUPPORTED_CIPHERSUITES},
 };
 pub use airprotos::auth_service::v1::{UsernameQueueMessage, username_queue_message};
 pub use airprotos::delivery_service::v1::StorageObjectType;
+pub use airprotos::queue_service::convert::{InvalidQueueMessage, InvalidQueueMessageReason};
 pub use airprotos::queue_service::v1::{ListenResponse, QueueEventPayload, listen_response};
 use anyhow::{Context, Result, anyhow, ensure};
 use chrono::{DateTime, Utc};
+use mimi_room_policy::RoleIndex;
 use openmls::prelude::Ciphersuite;
 use own_client_info::OwnClientInfo;
 
 use serde::{Deserialize, Serialize};
-use sqlx::{Row, query};
+use sqlx::{Column, Row, query};
 use store::ClientRecord;
-use tokio::sync::Notify;
+use tokio::sync::{Notify, watch};
 use tokio::task::spawn_blocking;
 use tokio_stream::{Stream, StreamExt};
 use tokio_util::sync::DropGuard;
@@ -48,8 +51,12 @@ use url::Url;
 
 use crate::{
     Asset, ChatMuted, PartialContact, UsernameRecord,
-    clients::event_loop::{EventLoop, EventLoopSender},
-    contacts::{TargetedMessageContact, UsernameContact},
+    clients::{
+        attachment::AttachmentRecord,
+        block_contact::BlockedContact,
+        event_loop::{EventLoop, EventLoopConfig, EventLoopSender, EventLoopStoppedError},
+    },
+    contacts::{TargetedMessageContact, UsernameContact, presence::ContactPresence},
     db::access::{DbAccess, WriteDbTransaction},
     groups::Group,
     job::{Job, JobContext, JobContextDb, JobError},
@@ -57,21 +64,24 @@ use crate::{
     outbound_service::OutboundService,
     utils::{
         global_lock::GlobalLock,
-        image::resize_profile_image,
+        image::{ProfileImageOptions, resize_profile_image_with_options},
         persistence::{delete_client_database, open_lock_file},
     },
 };
 use crate::{ChatId, key_stores::as_credentials::AsCredentials};
-use crate::{ContactType, user_profiles::UserProfile};
+use crate::{
+    ContactType,
+    user_profiles::{UserProfile, status_text::normalize_status_text},
+};
 use crate::{
     MessageId,
     chats::{
-        Chat,
+        Chat, MarkAllAsReadOptions,
         messages::{ChatMessage, TimestampedMessage},
     },
     clients::connection_offer::FriendshipPackage,
     contacts::Contact,
-    db::notification::DbNotification,
+    db::notification::{DbEntityKinds, DbNotification},
     key_stores::MemoryUserKeyStore,
     user_profiles::IndexedUserProfile,
     utils::persistence::{open_air_db, open_client_db},
@@ -79,25 +89,33 @@ use crate::{
 
 use self::{api_clients::ApiClients, create_user::InitialUserState, store::UserCreationState};
 
+mod account_archive;
 pub(crate) mod add_contact;
 pub(crate) mod api_clients;
 pub(crate) mod attachment;
 pub(crate) mod block_contact;
+pub mod chat_export;
 pub mod chats;
 pub(crate) mod connection_offer;
+mod contact_label;
 mod create_user;
 pub mod debug_info;
 mod delete_account;
-mod event_loop;
+pub(crate) mod event_loop;
+pub(crate) mod integrity;
 pub(crate) mod invitation_code;
 pub(crate) mod invite_users;
+pub mod listen_all;
+mod member_role;
 mod message;
 pub mod multi_device;
 pub(crate) mod own_client_info;
+pub(crate) mod pending_operations;
 mod persistence;
 pub mod process;
 pub(crate) mod push_token_state;
 mod reactions;
+pub mod reconnect;
 mod remove_users;
 pub(crate) mod safety_code;
 pub mod store;
@@ -110,9 +128,6 @@ pub(crate) mod update_key;
 mod user_profile;
 pub(crate) mod user_settings;
 
-pub(crate) const CIPHERSUITE: Ciphersuite =
-    Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
-
 #[cfg(not(feature = "test_utils"))]
 pub(crate) const CONNECTION_PACKAGES: usize = 50;
 
@@ -133,6 +148,8 @@ pub(crate) struct CoreUserInner {
     qs_client_id: QsClientId,
     key_store: MemoryUserKeyStore,
     db_notifications_pending: Arc<Notify>,
+    /// Notified when a username is added or removed
+    username_changes: watch::Sender<()>,
     outbound_service: OutboundService,
     event_loop_sender: EventLoopSender,
     _event_loop_cancel: DropGuard,
@@ -148,13 +165,50 @@ impl CoreUser {
     /// Create a new user with the given `user_id`.
     ///
     /// If a user with this name already exists, this will overwrite that user.
+    ///
+    /// New groups and key packages use the default ciphersuite.
     pub async fn new(
         user_id: UserId,
         db_path: &str,
         push_token: Option<PushToken>,
         invitation_code: String,
     ) -> Result<Self> {
-        Self::new_impl(user_id, None, db_path, push_token, invitation_code).await
+        Self::new_impl(
+            user_id,
+            None,
+            db_path,
+            push_token,
+            invitation_code,
+            DEFAULT_CIPHERSUITE,
+        )
+        .await
+    }
+
+    /// Same as [`Self::new`], but new groups and key packages of the user use the given
+    /// ciphersuite.
+    ///
+    /// The ciphersuite is persisted and can't be changed afterwards. Fails with
+    /// [`UnsupportedCiphersuiteError`] if the ciphersuite is not supported.
+    ///
+    /// Only available for tests: key packages are published for the ciphersuite of the user only,
+    /// so users with different ciphersuites can't add each other to groups.
+    #[cfg(any(test, feature = "test_utils"))]
+    pub async fn new_with_ciphersuite(
+        user_id: UserId,
+        db_path: &str,
+        push_token: Option<PushToken>,
+        invitation_code: String,
+        ciphersuite: Ciphersuite,
+    ) -> Result<Self> {
+        Self::new_impl(
+            user_id,
+            None,
+            db_path,
+            push_token,
+            invitation_code,
+            ciphersuite,
+        )
+        .await
     }
 
     /// Same as [`new`], but allows to override the server URL.
@@ -166,7 +220,15 @@ impl CoreUser {
         push_token: Option<PushToken>,
         invitation_code: String,
     ) -> Result<Self> {
-        Self::new_impl(user_id, server_url, db_path, push_token, invitation_code).await
+        Self::new_impl(
+            user_id,
+            server_url,
+            db_path,
+            push_token,
+            invitation_code,
+            DEFAULT_CIPHERSUITE,
+        )
+        .await
     }
 
     async fn new_impl(
@@ -175,8 +237,12 @@ impl CoreUser {
         db_path: &str,
         push_token: Option<PushToken>,
         invitation_code: String,
+        ciphersuite: Ciphersuite,
     ) -> Result<Self> {
-        info!(?user_id, "creating new user");
+        // Checked before anything is created or stored
+        check_ciphersuite(ciphersuite)?;
+
+        info!(?user_id, ?ciphersuite, "creating new user");
 
         // Open the air db to store the client record
         let air_db = open_air_db(db_path).await?;
@@ -194,10 +260,12 @@ impl CoreUser {
             client_db,
             global_lock,
             invitation_code,
+            ciphersuite,
         )
         .await
     }
 
+    #[expect(clippy::too_many_arguments)]
     async fn new_with_connections(
         user_id: UserId,
         server_url: Option<Url>,
@@ -206,6 +274,7 @@ impl CoreUser {
         client_db: DbAccess,
         global_lock: GlobalLock,
         invitation_code: String,
+        ciphersuite: Ciphersuite,
     ) -> Result<Self> {
         let api_clients = ApiClients::new(user_id.domain().clone(), server_url.clone());
 
@@ -223,11 +292,17 @@ impl CoreUser {
             user_id: final_state.user_id().clone(),
             self_group_id: None,          // Created lazily on first use
             self_group_signing_key: None, // Same as above
+            ciphersuite,
         }
         .store(client_db.write().await?)
         .await?;
 
-        let self_user = final_state.into_self_user(client_db, api_clients, global_lock);
+        let self_user = final_state.into_self_user(
+            client_db,
+            api_clients,
+            global_lock,
+            EventLoopConfig::default(),
+        );
 
         Ok(self_user)
     }
@@ -237,7 +312,16 @@ impl CoreUser {
     /// If a user creation process with a matching `UserId` was interrupted before, this will
     /// resume that process.
     pub async fn load(user_id: &UserId, db_path: &str) -> Result<CoreUser> {
-        Self::load_impl(user_id, db_path, None).await
+        Self::load_impl(user_id, db_path, None, EventLoopConfig::default()).await
+    }
+
+    /// Same as [`load`], but allows to configure the event loop.
+    pub async fn load_with_event_loop_config(
+        user_id: &UserId,
+        db_path: &str,
+        event_loop_config: EventLoopConfig,
+    ) -> Result<CoreUser> {
+        Self::load_impl(user_id, db_path, None, event_loop_config).await
     }
 
     /// Same as [`load`], but allows to override the server URL.
@@ -247,13 +331,14 @@ impl CoreUser {
         db_path: &str,
         server_url: Option<Url>,
     ) -> Result<CoreUser> {
-        Self::load_impl(user_id, db_path, server_url).await
+        Self::load_impl(user_id, db_path, server_url, EventLoopConfig::default()).await
     }
 
     async fn load_impl(
         user_id: &UserId,
         db_path: &str,
         server_url: Option<Url>,
+        event_loop_config: EventLoopConfig,
     ) -> Result<CoreUser> {
         let client_db = open_client_db(user_id, db_path).await?;
 
@@ -270,7 +355,7 @@ impl CoreUser {
 
         let global_lock = open_lock_file(db_path)?;
 
-        Ok(final_state.into_self_user(client_db, api_clients, global_lock))
+        Ok(final_state.into_self_user(client_db, api_clients, global_lock, event_loop_config))
     }
 
     /// Delete this user on the server and locally.
@@ -320,8 +405,11 @@ impl CoreUser {
     }
 
     /// Stop the outbound service and wait until it is fully stopped.
+    ///
+    /// Also cancels the attachment uploads resumed by the service.
     pub async fn stop_outbound_service(&self) {
         self.inner.outbound_service.stop().await;
+        self.inner.outbound_service.cancel_uploads();
     }
 
     pub(crate) fn key_store(&self) -> &MemoryUserKeyStore {
@@ -342,6 +430,18 @@ impl CoreUser {
         self.inner.db.notifier_tx.subscribe()
     }
 
+    /// Same as [`Self::db_notifications`], but only observes notifications which contain an entity
+    /// of any of the given kinds.
+    ///
+    /// Useful for subscribers which are not interested in most changes, such that they are not
+    /// woken up by unrelated notifications.
+    pub fn db_notifications_filtered(
+        &self,
+        kinds: DbEntityKinds,
+    ) -> impl Stream<Item = Arc<DbNotification>> + Send + 'static {
+        self.inner.db.notifier_tx.subscribe_filtered(kinds)
+    }
+
     /// Subscribes to pending db notifications.
     ///
     /// Unlike [`Self::db_notifications`], this function does not remove stored notifications from
@@ -361,6 +461,21 @@ impl CoreUser {
         Ok(DbNotification::dequeue(self.db().write().await?).await?)
     }
 
+    /// Dequeues the persisted db notifications and handles them before they are removed from the
+    /// queue.
+    ///
+    /// `handle` gets the transaction in which the notifications are dequeued. The notifications
+    /// are removed only if `handle` succeeds, together with the writes `handle` makes in this
+    /// transaction. If it fails or the app is killed before, they are dequeued again the next
+    /// time. Since the database is locked for writing, `handle` must not acquire another write
+    /// connection.
+    pub async fn dequeue_db_notification_with<T: Send>(
+        &self,
+        handle: impl AsyncFnOnce(&mut WriteDbTransaction<'_>, DbNotification) -> Result<T>,
+    ) -> Result<T> {
+        DbNotification::dequeue_with(self.db().write().await?, handle).await
+    }
+
     /// Signals that new db notifications were persisted and should be drained.
     pub fn signal_pending_db_notifications(&self) {
         self.inner.db_notifications_pending.notify_one();
@@ -371,11 +486,23 @@ impl CoreUser {
         self.inner.db_notifications_pending.clone()
     }
 
-    pub async fn set_own_user_profile(&self, mut user_profile: UserProfile) -> Result<UserProfile> {
+    pub async fn set_own_user_profile(&self, user_profile: UserProfile) -> Result<UserProfile> {
+        self.set_own_user_profile_with_image_options(user_profile, ProfileImageOptions::default())
+            .await
+    }
+
+    /// Same as [`Self::set_own_user_profile`], but re-encodes a changed profile picture with the
+    /// given options, e.g. as smaller image for bandwidth-constrained users.
+    pub async fn set_own_user_profile_with_image_options(
+        &self,
+        mut user_profile: UserProfile,
+        image_options: ProfileImageOptions,
+    ) -> Result<UserProfile> {
         ensure!(
             &user_profile.user_id == self.user_id(),
             "Can't set user profile for users other than the current user"
         );
+        user_profile.status_text = normalize_status_text(user_profile.status_text.take())?;
         if let Some(profile_picture) = &mut user_profile.profile_picture {
             // Only resize the profile picture if it has changed, to avoid
             // needless re-encoding.
@@ -384,8 +511,10 @@ impl CoreUser {
                 match profile_picture {
                     Asset::Value(image_bytes) => {
                         let bytes = mem::take(image_bytes);
-                        *image_bytes =
-                            spawn_blocking(move || resize_profile_image(&bytes)).await??;
+                        *image_bytes = spawn_blocking(move || {
+                            resize_profile_image_with_options(&bytes, &image_options)
+                        })
+                        .await??;
                     }
                 }
             }
@@ -410,15 +539,24 @@ impl CoreUser {
 
     /// Fetch and process messages from all username queues.
     ///
-    /// Returns the list of [`ChatId`]s of any newly created chats.
-    pub async fn fetch_and_process_username_messages(&self) -> Result<Vec<ChatId>> {
+    /// A username queue which cannot be listened to does not prevent processing the remaining
+    /// queues; the error is collected in the returned [`ProcessedUsernameMessages`] instead.
+    pub async fn fetch_and_process_username_messages(&self) -> Result<ProcessedUsernameMessages> {
         let records = self.username_records().await?;
         let api_client = self.api_client()?;
-        let mut chat_ids = Vec::new();
+        let mut processed = ProcessedUsernameMessages::default();
         for record in records {
-            let (mut stream, responder) = api_client
+            let (mut stream, responder) = match api_client
                 .as_listen_username(record.hash, &record.signing_key)
-                .await?;
+                .await
+            {
+                Ok(listen) => listen,
+                Err(error) => {
+                    error!(%error, "failed to listen to username queue");
+                    processed.errors.push((record.username, error));
+                    continue;
+                }
+            };
             while let Some(Some(message)) = stream.next().await {
                 let Some(message_id) = message.message_id else {
                     error!("no message id in username queue message");
@@ -429,7 +567,7 @@ impl CoreUser {
                     .await
                 {
                     Ok(chat_id) => {
-                        chat_ids.push(chat_id);
+                        processed.chat_ids.push(chat_id);
                     }
                     Err(error) => {
                         error!(%error, "failed to process username queue message");
@@ -439,7 +577,7 @@ impl CoreUser {
                 responder.ack(message_id.into()).await;
             }
         }
-        Ok(chat_ids)
+        Ok(processed)
     }
 
     /// Fetches all messages from all username queues and returns them.
@@ -468,25 +606,40 @@ impl CoreUser {
 
     /// Fetches all messages from the QS queue.
     ///
+    /// Messages which could not be converted are dropped, see [`Self::qs_fetch_messages_checked`].
+    ///
     /// Must *not* be used outside of integration tests, because the messages are not acked.
     pub async fn qs_fetch_messages(&self) -> Result<Vec<QueueMessage>> {
-        let (mut stream, _responder) = self.listen_queue().await?;
-        let mut messages: Vec<QueueMessage> = Vec::new();
+        Ok(self.qs_fetch_messages_checked().await?.messages)
+    }
 
-        while let Some(message) = stream.next().await {
-            match message.event {
-                Some(listen_response::Event::Empty(_)) => break,
-                Some(listen_response::Event::Message(queue_message)) => {
-                    if let Ok(queue_message) = queue_message.try_into() {
-                        messages.push(queue_message);
-                    }
-                }
-                Some(listen_response::Event::Payload(_)) => {}
-                None => {}
-            }
-        }
+    /// Fetches all messages from the QS queue together with the messages which could not be
+    /// converted.
+    ///
+    /// Must *not* be used outside of integration tests, because the messages are not acked.
+    pub async fn qs_fetch_messages_checked(&self) -> Result<FetchedQsMessages> {
+        let queue_ratchet = StorableQsQueueRatchet::load(self.db().read().await?).await?;
+        self.qs_fetch_messages_from(queue_ratchet.sequence_number())
+            .await
+    }
 
-        Ok(messages)
+    /// Fetches all messages from the QS queue starting at the given sequence number, independent
+    /// of the local queue ratchet.
+    ///
+    /// Must *not* be used outside of integration tests, because the messages are not acked.
+    pub async fn qs_fetch_messages_from(
+        &self,
+        sequence_number_start: u64,
+    ) -> Result<FetchedQsMessages> {
+        let api_client = self.inner.api_clients.default_client()?;
+        let (stream, _responder) = api_client
+            .qs_listen_queue(
+                self.inner.qs_client_id,
+                sequence_number_start,
+                &self.inner.key_store.qs_client_signing_key,
+            )
+            .await?;
+        Ok(FetchedQsMessages::collect(stream).await)
     }
 
     pub async fn contacts(&self) -> sqlx::Result<Vec<Contact>> {
@@ -525,6 +678,20 @@ impl CoreUser {
         Contact::load(self.db().read().await?, user_id).await
     }
 
+    /// Returns the last known presence of the user with the given [`UserId`].
+    ///
+    /// Returns `None` if the user never published their presence or is blocked.
+    pub async fn contact_presence(
+        &self,
+        user_id: &UserId,
+    ) -> sqlx::Result<Option<ContactPresence>> {
+        let mut connection = self.db().read().await?;
+        if BlockedContact::check_blocked(&mut connection, user_id).await? {
+            return Ok(None);
+        }
+        ContactPresence::load(&mut connection, user_id).await
+    }
+
     pub async fn try_targeted_message_contact(
         &self,
         user_id: &UserId,
@@ -595,6 +762,30 @@ impl CoreUser {
         Ok(Some(group.participants()?))
     }
 
+    /// Returns the members of the chat together with their role in the room, including the
+    /// user themself.
+    ///
+    /// Returns None if there is no chat with the given id.
+    pub async fn chat_members_with_roles(
+        &self,
+        chat_id: ChatId,
+    ) -> Option<Vec<(UserId, RoleIndex)>> {
+        self.try_chat_members_with_roles(chat_id)
+            .await
+            .inspect_err(|e| error!(?e, "Error loading chat members with roles"))
+            .ok()?
+    }
+
+    async fn try_chat_members_with_roles(
+        &self,
+        chat_id: ChatId,
+    ) -> Result<Option<Vec<(UserId, RoleIndex)>>> {
+        let Some(group) = Group::load_with_chat_id(self.db().read().await?, chat_id).await? else {
+            return Ok(None);
+        };
+        Ok(Some(group.participants_with_roles()?))
+    }
+
     pub async fn pending_removes(&self, chat_id: ChatId) -> Option<Vec<UserId>> {
         Group::load_with_chat_id(self.db().read().await.ok()?, chat_id)
             .await
@@ -679,6 +870,28 @@ impl CoreUser {
         Ok(())
     }
 
+    /// Mark all messages in all chats as read.
+    ///
+    /// Archived chats and chats with blocked contacts are skipped if requested in the `options`.
+    pub async fn mark_all_as_read(&self, options: MarkAllAsReadOptions) -> anyhow::Result<()> {
+        let now = Utc::now();
+        self.db()
+            .with_write_transaction(async |txn| Chat::mark_all_as_read(txn, now, options).await)
+            .await?;
+        Ok(())
+    }
+
+    /// Sets whether the chat with the given id is currently focused in the UI.
+    ///
+    /// Automatically scheduled read receipts are only sent for the focused chat. Read receipts
+    /// for other chats are deferred until the chat is focused, or the messages are explicitly
+    /// marked as read. Delivery receipts are not affected.
+    pub async fn set_chat_focused(&self, chat_id: ChatId, focused: bool) -> anyhow::Result<()> {
+        self.outbound_service()
+            .set_chat_focused(chat_id, focused)
+            .await
+    }
+
     /// Returns how many messages are marked as unread across all chats.
     pub async fn global_unread_messages_count(&self) -> sqlx::Result<usize> {
         Chat::global_unread_message_count(self.db().read().await?).await
@@ -718,6 +931,20 @@ impl CoreUser {
             .await
     }
 
+    /// Mutes notifications of the chat with the given [`ChatId`] until the given time, or unmutes
+    /// it if `muted_until` is `None`.
+    ///
+    /// Messages of a muted chat are still received and counted as unread. Once `muted_until` has
+    /// passed, the chat is unmuted.
+    pub async fn set_chat_muted(
+        &self,
+        chat_id: ChatId,
+        muted_until: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()> {
+        self.set_chat_muted_until(chat_id, muted_until.map(ChatMuted::from))
+            .await
+    }
+
     /// Schedules the client's push token update on the QS.
     pub async fn update_push_token(&self, push_token: Option<PushToken>) -> Result<()> {
         let should_notify =
@@ -745,11 +972,18 @@ impl CoreUser {
             let mut message = ChatMessage::new(chat_id, message_id, timestamped_message);
             let attachment_records = Self::extract_attachments(&mut message);
             message.store(&mut *txn).await?;
-            for (record, pending_record) in attachment_records {
+            for (record, pending_record, thumbnail) in attachment_records {
                 if let Err(error) = record.store(&mut *txn, None).await {
                     error!(%error, "Failed to store attachment");
                     continue;
                 }
+                if let Some(thumbnail) = thumbnail
+                    && let Err(error) =
+                        AttachmentRecord::set_thumbnail(&mut *txn, record.attachment_id, &thumbnail)
+                            .await
+                {
+                    error!(%error, "Failed to store attachment thumbnail");
+                }
                 if let Err(error) = pending_record.store(&mut *txn, record.attachment_id).await {
                     error!(%error, "Failed to store pending attachment");
                 }
@@ -767,13 +1001,27 @@ impl CoreUser {
             .map(|user_option| user_option.unwrap().into())
     }
 
+    /// Reports the user as spammer.
+    ///
+    /// The hashes of the usernames via which this user connected to the spammer are included in
+    /// the report, so the AS can flag them as well.
     pub async fn report_spam(&self, spammer_id: UserId) -> anyhow::Result<()> {
+        let usernames =
+            Chat::load_connection_usernames(self.db().read().await?, &spammer_id).await?;
+        let spammer_username_hashes = spawn_blocking(move || {
+            usernames
+                .iter()
+                .map(Username::calculate_hash)
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .await??;
         self.inner
             .api_clients
             .default_client()?
             .as_report_spam(
                 self.user_id().clone(),
                 spammer_id,
+                spammer_username_hashes,
                 &self.inner.key_store.signing_key,
             )
             .await?;
@@ -781,7 +1029,43 @@ impl CoreUser {
     }
 
     /// This function goes through all tables of the database and returns all columns that contain the query.
+    ///
+    /// Convenience wrapper of [`Self::scan_database_bytes`]; BLOB values which are not valid UTF-8
+    /// are returned lossily converted.
     pub async fn scan_database(&self, query: &str, strict: bool) -> anyhow::Result<Vec<String>> {
+        let values = self.scan_database_bytes(query.as_bytes(), strict).await?;
+        Ok(values
+            .into_iter()
+            .map(|value| String::from_utf8_lossy(&value).into_owned())
+            .collect())
+    }
+
+    /// Goes through all tables of the database and returns the raw values of all TEXT and BLOB
+    /// columns that contain the query as a byte substring.
+    pub async fn scan_database_bytes(
+        &self,
+        query: &[u8],
+        strict: bool,
+    ) -> anyhow::Result<Vec<Vec<u8>>> {
+        let matches = self.scan_database_matches(query, strict).await?;
+        Ok(matches.into_iter().map(|m| m.value).collect())
+    }
+
+    /// Same as [`Self::scan_database_bytes`], but also returns where each match was found.
+    #[cfg(any(test, feature = "test_utils"))]
+    pub async fn scan_database_located(
+        &self,
+        query: &[u8],
+        strict: bool,
+    ) -> anyhow::Result<Vec<ScanMatch>> {
+        self.scan_database_matches(query, strict).await
+    }
+
+    async fn scan_database_matches(
+        &self,
+        query: &[u8],
+        strict: bool,
+    ) -> anyhow::Result<Vec<ScanMatch>> {
         self.db()
             .with_read_transaction(async |txn| {
                 let tables = query!("SELECT name FROM sqlite_schema WHERE type='table'")
@@ -791,35 +1075,48 @@ impl CoreUser {
                 let mut result = Vec::new();
 
                 for table in tables {
-                    for row in sqlx::query(sqlx::AssertSqlSafe(format!(
-                        "SELECT * FROM '{}'",
-                        table.name.unwrap()
+                    let table = table.name.unwrap();
+                    // Tables created `WITHOUT ROWID` fail when selecting the rowid
+                    let (rows, first_column) = match sqlx::query(sqlx::AssertSqlSafe(format!(
+                        "SELECT rowid, * FROM '{table}'"
                     )))
                     .fetch_all(txn.as_mut())
-                    .await?
+                    .await
                     {
-                        for i in 0..row.len() {
-                            let string = if let Ok(column) = row.try_get::<String, _>(i) {
-                                column
+                        Ok(rows) => (rows, 1),
+                        Err(_) => {
+                            let rows = sqlx::query(sqlx::AssertSqlSafe(format!(
+                                "SELECT * FROM '{table}'"
+                            )))
+                            .fetch_all(txn.as_mut())
+                            .await?;
+                            (rows, 0)
+                        }
+                    };
+
+                    for row in rows {
+                        let rowid = if first_column == 1 {
+                            row.try_get::<i64, _>(0).ok()
+                        } else {
+                            None
+                        };
+                        for i in first_column..row.len() {
+                            let value = if let Ok(column) = row.try_get::<String, _>(i) {
+                                column.into_bytes()
                             } else if let Ok(column) = row.try_get::<Vec<u8>, _>(i) {
-                                String::from_utf8_lossy(&column).to_string()
+                                column
                             } else {
                                 // Unable to decode this type
                                 continue;
                             };
 
-                            if string.contains(query) {
-                                result.push(string.to_string());
-                                continue;
-                            }
-
-                            if !strict {
-                                // Try again without 0x18, because that's the CBOR unsigned byte indicator for Vec<u8>
-                                let string2 = string.replace('\x18', "");
-                                if string2.contains(query) {
-                                    result.push(string.to_string());
-                                    continue;
-                                }
+                            if scanned_value_matches(&value, query, strict) {
+                                result.push(ScanMatch {
+                                    table: table.clone(),
+                                    column: row.column(i).name().to_owned(),
+                                    rowid,
+                                    value,
+                                });
                             }
                         }
                     }
@@ -855,6 +1152,86 @@ impl CoreUser {
     }
 }
 
+/// Value found by [`CoreUser::scan_database`] and where it is stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanMatch {
+    pub table: String,
+    pub column: String,
+    /// `None` for tables without rowid
+    pub rowid: Option<i64>,
+    /// Raw value of the column
+    pub value: Vec<u8>,
+}
+
+/// Returns whether the value contains the query as a byte substring.
+///
+/// Unless `strict`, the value is also matched with all 0x18 bytes removed, because that's the CBOR
+/// unsigned byte indicator for `Vec<u8>`.
+fn scanned_value_matches(value: &[u8], query: &[u8], strict: bool) -> bool {
+    fn contains(value: &[u8], query: &[u8]) -> bool {
+        query.is_empty() || value.windows(query.len()).any(|window| window == query)
+    }
+
+    if contains(value, query) {
+        return true;
+    }
+    if strict {
+        return false;
+    }
+    let stripped: Vec<u8> = value.iter().copied().filter(|&byte| byte != 0x18).collect();
+    contains(&stripped, query)
+}
+
+/// Result of fetching and processing the messages from all username queues.
+#[derive(Debug, Default)]
+pub struct ProcessedUsernameMessages {
+    /// [`ChatId`]s of any newly created chats
+    pub chat_ids: Vec<ChatId>,
+    /// Usernames whose queue could not be listened to
+    pub errors: Vec<(Username, AsRequestError)>,
+}
+
+/// Messages fetched from the QS queue until it was empty
+#[derive(Debug, Default)]
+pub struct FetchedQsMessages {
+    pub messages: Vec<QueueMessage>,
+    /// Messages which could not be converted and were skipped
+    pub invalid: Vec<InvalidQueueMessage>,
+}
+
+impl FetchedQsMessages {
+    /// Collects the messages of a QS listen stream until the queue is empty or the stream ends.
+    pub async fn collect(mut stream: impl Stream<Item = ListenResponse> + Unpin) -> Self {
+        let mut fetched = Self::default();
+        while let Some(message) = stream.next().await {
+            match message.event {
+                Some(listen_response::Event::Empty(_)) => break,
+                Some(listen_response::Event::Message(queue_message)) => {
+                    match queue_message.try_into() {
+                        Ok(queue_message) => fetched.messages.push(queue_message),
+                        Err(error) => fetched.invalid.push(error),
+                    }
+                }
+                Some(listen_response::Event::Payload(_)) | None => {}
+            }
+        }
+        fetched
+    }
+}
+
+/// The ciphersuite is not in [`SUPPORTED_CIPHERSUITES`].
+#[derive(Debug, thiserror::Error)]
+#[error("unsupported ciphersuite: {0:?}")]
+pub struct UnsupportedCiphersuiteError(pub Ciphersuite);
+
+fn check_ciphersuite(ciphersuite: Ciphersuite) -> Result<(), UnsupportedCiphersuiteError> {
+    if SUPPORTED_CIPHERSUITES.contains(&ciphersuite) {
+        Ok(())
+    } else {
+        Err(UnsupportedCiphersuiteError(ciphersuite))
+    }
+}
+
 /// Error which can occur when listening to the queue.
 #[derive(Debug, thiserror::Error)]
 pub enum ListenQueueError {
@@ -864,6 +1241,8 @@ pub enum ListenQueueError {
     ApiClient(#[from] ApiClientInitError),
     #[error(transparent)]
     Qs(#[from] QsRequestError),
+    #[error(transparent)]
+    EventLoopStopped(#[from] EventLoopStoppedError),
 }
 
 impl ListenQueueError {
@@ -873,6 +1252,16 @@ impl ListenQueueError {
             _ => false,
         }
     }
+
+    /// Returns whether listening to the queue will fail again when retried.
+    pub fn is_terminal(&self) -> bool {
+        match self {
+            Self::Sqlx(_) => false,
+            Self::ApiClient(_) => true,
+            Self::Qs(error) => error.is_failed_precondition(),
+            Self::EventLoopStopped(_) => true,
+        }
+    }
 }
 
 /// Error which can occur when listening to a username.
@@ -891,4 +1280,12 @@ impl ListenUsernameError {
             _ => false,
         }
     }
+
+    /// Returns whether listening to the username will fail again when retried.
+    pub fn is_terminal(&self) -> bool {
+        match self {
+            Self::ApiClient(_) => true,
+            Self::As(error) => error.is_failed_precondition() || error.is_not_found(),
+        }
+    }
 }
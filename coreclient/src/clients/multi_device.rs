@@ -17,10 +17,10 @@ use aircommon::crypto::indexed_aead::keys::UserProfileKey;
 use aircommon::crypto::kdf::keys::RatchetSecret;
 use aircommon::crypto::signatures::keys::{QsClientSigningKey, QsUserSigningKey};
 use aircommon::identifiers::{Fqdn, QsClientId, QsUserId, UserId};
-use aircommon::messages::{FriendshipToken, QueueMessage};
+use aircommon::messages::FriendshipToken;
 use aircommon::mls_group_config::{
-    APQ_CIPHERSUITE, QS_CLIENT_REFERENCE_EXTENSION_TYPE, default_key_package_extensions,
-    default_leaf_node_capabilities, default_leaf_node_extensions,
+    APQ_CIPHERSUITE, DEFAULT_CIPHERSUITE, QS_CLIENT_REFERENCE_EXTENSION_TYPE,
+    default_key_package_extensions, default_leaf_node_capabilities, default_leaf_node_extensions,
 };
 use airprotos::client::component::AirComponent;
 use airprotos::relay_service::v1::{LinkingSessionId, RelayFrame};
@@ -29,7 +29,7 @@ use apqmls::authentication::ApqCredentialWithKey;
 use apqmls::messages::ApqKeyPackage;
 use openmls::components::vc_derivation_info::EpochId;
 use openmls::group::GroupId;
-use openmls::prelude::{Credential, CredentialType, SignaturePublicKey};
+use openmls::prelude::{Ciphersuite, Credential, CredentialType, SignaturePublicKey};
 use openmls::{
     group::{MlsGroup, MlsGroupCreateConfig, MlsGroupJoinConfig, StagedWelcome},
     prelude::{
@@ -52,10 +52,10 @@ use url::Url;
 use crate::groups::self_group::SelfGroup;
 use crate::{
     clients::{
-        CIPHERSUITE, CoreUser,
+        CoreUser, FetchedQsMessages,
         api_clients::ApiClients,
         create_user::QsRegisteredUserState,
-        listen_response,
+        event_loop::EventLoopConfig,
         own_client_info::OwnClientInfo,
         process::process_qs::ProcessedQsMessages,
         store::{ClientRecord, UserCreationState},
@@ -74,6 +74,13 @@ use crate::{
 
 const EXPORTER_LABEL: &str = "multi-device-linking";
 
+/// Ciphersuite of the ephemeral group which establishes the linking channel.
+///
+/// The new device doesn't know the ciphersuite of the user before it is linked, so the linking
+/// channel doesn't use it. The ciphersuite of the user is transferred in the
+/// [`ProvisioningPackage`].
+const LINKING_CIPHERSUITE: Ciphersuite = DEFAULT_CIPHERSUITE;
+
 /// Everything the old (existing) device hands to the new device over the
 /// secure linking channel so the new device can bootstrap a working
 /// [`CoreUser`] and join the user's self group.
@@ -99,6 +106,14 @@ pub(crate) struct ProvisioningPackage {
     // Self-group metadata not carried by the Welcome.
     pub(crate) self_group_id: GroupId,
     pub(crate) identity_link_wrapper_key: IdentityLinkWrapperKey,
+    // Ciphersuite of new groups and key packages. Missing in packages of older devices, which
+    // only supported the default ciphersuite.
+    #[serde(default = "default_ciphersuite")]
+    pub(crate) ciphersuite: Ciphersuite,
+}
+
+fn default_ciphersuite() -> Ciphersuite {
+    DEFAULT_CIPHERSUITE
 }
 
 #[derive(Debug)]
@@ -147,7 +162,7 @@ fn make_provider_and_credential(
     let provider = OpenMlsRustCrypto::default();
     let credential = BasicCredential::new(identity.to_vec());
     let signature_keys =
-        SignatureKeyPair::new(CIPHERSUITE.signature_algorithm()).context("keygen")?;
+        SignatureKeyPair::new(LINKING_CIPHERSUITE.signature_algorithm()).context("keygen")?;
     signature_keys
         .store(provider.storage())
         .map_err(|e| anyhow!("store keys: {e}"))?;
@@ -200,7 +215,12 @@ impl CoreUser {
             make_provider_and_credential(b"initiator")?;
 
         let key_package_bundle = KeyPackage::builder()
-            .build(CIPHERSUITE, &provider, &signature_keys, credential_with_key)
+            .build(
+                LINKING_CIPHERSUITE,
+                &provider,
+                &signature_keys,
+                credential_with_key,
+            )
             .context("build key package")?;
         let key_package_bytes = MlsMessageOut::from(key_package_bundle)
             .to_bytes()
@@ -336,7 +356,7 @@ impl CoreUser {
 
         let group_config = MlsGroupCreateConfig::builder()
             .use_ratchet_tree_extension(true)
-            .ciphersuite(CIPHERSUITE)
+            .ciphersuite(LINKING_CIPHERSUITE)
             .build();
 
         let mut group = MlsGroup::new(
@@ -420,7 +440,7 @@ impl CoreUser {
                 qs_client_signing_key.verifying_key().clone(),
                 qs_queue_decryption_key.encryption_key().clone(),
                 // MVP: no push token for the new device yet.
-                None,
+                Vec::new(),
                 qs_initial_ratchet_secret.clone(),
                 &key_store.qs_user_signing_key,
             )
@@ -428,6 +448,7 @@ impl CoreUser {
         let qs_client_id = response.qs_client_id;
 
         let user_profile_key = UserProfileKey::load_own(self.db().read().await?).await?;
+        let ciphersuite = OwnClientInfo::load_ciphersuite(self.db().read().await?).await?;
 
         Ok(ProvisioningPackage {
             user_id: self.user_id().clone(),
@@ -445,6 +466,7 @@ impl CoreUser {
             user_profile_key,
             self_group_id,
             identity_link_wrapper_key,
+            ciphersuite,
         })
     }
 
@@ -649,19 +671,9 @@ impl CoreUser {
     /// via the responder, so it is safe to use outside of integration tests.
     async fn drain_and_process_qs_queue(&self) -> anyhow::Result<ProcessedQsMessages> {
         let (mut stream, responder) = self.listen_queue().await?;
-        let mut messages: Vec<QueueMessage> = Vec::new();
-
-        while let Some(message) = stream.next().await {
-            match message.event {
-                // Empty event is the sentinel: the queue is drained.
-                Some(listen_response::Event::Empty(_)) => break,
-                Some(listen_response::Event::Message(queue_message)) => {
-                    if let Ok(queue_message) = queue_message.try_into() {
-                        messages.push(queue_message);
-                    }
-                }
-                Some(listen_response::Event::Payload(_)) | None => {}
-            }
+        let FetchedQsMessages { messages, invalid } = FetchedQsMessages::collect(&mut stream).await;
+        for error in invalid {
+            error!(%error, "dropping invalid self-group queue message");
         }
 
         let num_messages = messages.len();
@@ -710,6 +722,7 @@ impl CoreUser {
             user_profile_key,
             self_group_id,
             identity_link_wrapper_key: _,
+            ciphersuite,
         } = package;
 
         let shared_client_credential = client_signing_key.credential().clone();
@@ -744,6 +757,7 @@ impl CoreUser {
                     user_id: user_id.clone(),
                     self_group_id: Some(self_group_id),
                     self_group_signing_key: Some(self_group_signing_key),
+                    ciphersuite,
                 }
                 .store(&mut *txn)
                 .await?;
@@ -771,8 +785,11 @@ impl CoreUser {
         client_record.finish();
         client_record.store(air_db.write().await?).await?;
 
-        Ok(final_state
-            .final_state()?
-            .into_self_user(client_db, api_clients, global_lock))
+        Ok(final_state.final_state()?.into_self_user(
+            client_db,
+            api_clients,
+            global_lock,
+            EventLoopConfig::default(),
+        ))
     }
 }
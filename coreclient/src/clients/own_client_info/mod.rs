@@ -6,7 +6,7 @@ use aircommon::{
     credentials::keys::ClientSigningKey,
     identifiers::{QsClientId, QsUserId, UserId},
 };
-use openmls::group::GroupId;
+use openmls::{group::GroupId, prelude::Ciphersuite};
 
 mod persistence;
 
@@ -19,4 +19,6 @@ pub(crate) struct OwnClientInfo {
     pub(crate) user_id: UserId,
     pub(crate) self_group_id: Option<GroupId>,
     pub(crate) self_group_signing_key: Option<ClientSigningKey>,
+    /// Ciphersuite of new groups and key packages
+    pub(crate) ciphersuite: Ciphersuite,
 }
@@ -6,7 +6,7 @@ use aircommon::{
     credentials::keys::ClientSigningKey,
     identifiers::{Fqdn, QsClientId, QsUserId, UserId},
 };
-use openmls::group::GroupId;
+use openmls::{group::GroupId, prelude::Ciphersuite};
 use sqlx::{query, query_scalar};
 use uuid::Uuid;
 
@@ -23,6 +23,7 @@ impl OwnClientInfo {
         let domain = self.user_id.domain();
         let self_group_id = self.self_group_id.as_ref().map(GroupIdRefWrapper::from);
         let self_group_signing_key = self.self_group_signing_key.as_ref();
+        let ciphersuite = self.ciphersuite as u16;
         query!(
             "INSERT INTO own_client_info (
                 qs_user_id,
@@ -30,14 +31,16 @@ impl OwnClientInfo {
                 user_uuid,
                 user_domain,
                 self_group_id,
-                self_group_signing_key
-            ) VALUES (?,  ?, ?, ?, ?, ?)",
+                self_group_signing_key,
+                ciphersuite
+            ) VALUES (?,  ?, ?, ?, ?, ?, ?)",
             self.qs_user_id,
             self.qs_client_id,
             uuid,
             domain,
             self_group_id,
-            self_group_signing_key
+            self_group_signing_key,
+            ciphersuite,
         )
         .execute(connection.as_mut())
         .await?;
@@ -52,6 +55,7 @@ impl OwnClientInfo {
             user_domain: Fqdn,
             self_group_id: Option<GroupIdWrapper>,
             self_group_signing_key: Option<ClientSigningKey>,
+            ciphersuite: u16,
         }
         let sql = sqlx::query_as!(
             SqlOwnClientInfo,
@@ -61,7 +65,8 @@ impl OwnClientInfo {
                 user_uuid AS "user_uuid: _",
                 user_domain AS "user_domain: _",
                 self_group_id AS "self_group_id: _",
-                self_group_signing_key AS "self_group_signing_key: _"
+                self_group_signing_key AS "self_group_signing_key: _",
+                ciphersuite AS "ciphersuite: _"
             FROM own_client_info"#,
         )
         .fetch_one(connection.as_mut())
@@ -72,9 +77,21 @@ impl OwnClientInfo {
             user_id: UserId::new(sql.user_uuid, sql.user_domain),
             self_group_id: sql.self_group_id.map(From::from),
             self_group_signing_key: sql.self_group_signing_key,
+            ciphersuite: decode_ciphersuite(sql.ciphersuite)?,
         })
     }
 
+    /// Returns the ciphersuite of new groups and key packages.
+    pub(crate) async fn load_ciphersuite(
+        mut connection: impl ReadConnection,
+    ) -> sqlx::Result<Ciphersuite> {
+        let ciphersuite: u16 =
+            query_scalar!(r#"SELECT ciphersuite AS "ciphersuite: _" FROM own_client_info"#)
+                .fetch_one(connection.as_mut())
+                .await?;
+        decode_ciphersuite(ciphersuite)
+    }
+
     /// Returns the `self_group_id`.
     pub(crate) async fn load_self_group_id(
         mut connection: impl ReadConnection,
@@ -119,6 +136,11 @@ impl OwnClientInfo {
     }
 }
 
+fn decode_ciphersuite(value: u16) -> sqlx::Result<Ciphersuite> {
+    Ciphersuite::try_from(value)
+        .map_err(|_| sqlx::Error::Decode(format!("unknown ciphersuite: {value:#06x}").into()))
+}
+
 #[cfg(test)]
 mod tests {
     use aircommon::{
@@ -143,6 +165,7 @@ mod tests {
             user_id: UserId::new(Uuid::new_v4(), "localhost".parse().unwrap()),
             self_group_id: Some(GroupId::random(&RustCrypto::default())),
             self_group_signing_key: None,
+            ciphersuite: Ciphersuite::MLS_128_MLKEM768_AES256GCM_SHA384_Ed25519,
         };
 
         own_client_info.store(pool.write().await?).await?;
@@ -153,6 +176,11 @@ mod tests {
         assert_eq!(loaded.user_id, own_client_info.user_id);
         assert_eq!(loaded.self_group_id, own_client_info.self_group_id);
         assert!(loaded.self_group_signing_key.is_none());
+        assert_eq!(loaded.ciphersuite, own_client_info.ciphersuite);
+        assert_eq!(
+            OwnClientInfo::load_ciphersuite(pool.read().await?).await?,
+            own_client_info.ciphersuite
+        );
 
         Ok(())
     }
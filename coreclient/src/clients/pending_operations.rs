@@ -0,0 +1,61 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use chrono::{DateTime, Utc};
+
+use crate::{ChatId, job::pending_chat_operation::PendingChatOperation};
+
+use super::CoreUser;
+
+/// Kind of a pending chat operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingOperationKind {
+    /// Leaving the chat
+    Leave,
+    /// Deleting the chat
+    Delete,
+    /// Any other group operation, e.g. adding or removing members or updating the chat
+    Other,
+}
+
+/// Summary of a chat operation which was not yet confirmed by the server
+#[derive(Debug, Clone)]
+pub struct PendingOperationSummary {
+    pub chat_id: ChatId,
+    pub kind: PendingOperationKind,
+    /// Number of failed attempts to send the operation to the server
+    pub number_of_attempts: u32,
+    /// When the operation is retried next; `None` if it can be retried immediately
+    pub retry_due_at: Option<DateTime<Utc>>,
+    /// Whether the outcome is unknown and the operation waits for the server's queue to resolve it
+    ///
+    /// Such an operation can't be cancelled.
+    pub waiting_for_queue_response: bool,
+    /// Whether the operation is currently being sent to the server
+    ///
+    /// Such an operation can't be cancelled.
+    pub in_flight: bool,
+}
+
+impl CoreUser {
+    /// Returns the chat operations which were not yet confirmed by the server, e.g. leaving a
+    /// chat while offline.
+    pub async fn pending_operations(&self) -> anyhow::Result<Vec<PendingOperationSummary>> {
+        Ok(PendingChatOperation::load_summaries(self.db().read().await?).await?)
+    }
+
+    /// Cancels the pending operation of the chat with the given id.
+    ///
+    /// The staged commit is discarded and the operation is not retried anymore. If a leave was
+    /// already applied locally, the chat is made active again and the previous roles are restored.
+    ///
+    /// Returns `false` if there is no pending operation for the chat. Fails if the operation is
+    /// being sent to the server, or if it waits for the server's queue to resolve whether it was
+    /// accepted.
+    pub async fn cancel_pending_operation(&self, chat_id: ChatId) -> anyhow::Result<bool> {
+        self.db()
+            .with_write_transaction(async |txn| PendingChatOperation::cancel(txn, chat_id).await)
+            .await
+    }
+}
@@ -31,7 +31,7 @@ use mimi_room_policy::RoleIndex;
 use openmls::{
     group::{GroupId, QueuedProposal},
     prelude::{
-        ApplicationMessage, MlsMessageBodyIn, MlsMessageIn, ProcessedMessageContent,
+        ApplicationMessage, MlsMessageBodyIn, MlsMessageIn, ProcessedMessageContent, Proposal,
         ProtocolMessage, Sender, StagedCommit,
     },
 };
@@ -43,7 +43,7 @@ use crate::{
     SystemMessage,
     chats::{
         GroupDataExt, GroupDataProfilePart, StatusRecord, messages::edit::MessageEdit,
-        reactions::Reaction,
+        pins::PinnedMessage, reactions::Reaction,
     },
     clients::{
         QsListenResponder,
@@ -55,7 +55,10 @@ use crate::{
         update_key::{update_chat_attributes, update_chat_title},
         user_settings::ReadReceiptsSetting,
     },
-    contacts::{PartialContact, PartialContactType},
+    contacts::{
+        PartialContact, PartialContactType,
+        presence::{ContactPresence, PRESENCE_CONTENT_TYPE, PresenceState},
+    },
     db::access::{WriteConnection, WriteDbTransaction},
     groups::{
         DecryptedProfileInfos, Group, GroupDataBytes, VerifiedGroup,
@@ -64,7 +67,7 @@ use crate::{
     },
     job::{JobContext, JobContextDb, pending_chat_operation::PendingChatOperation},
     key_stores::{indexed_keys::StorableIndexedKey, queue_ratchets::StorableQsQueueRatchet},
-    outbound_service::resync::Resync,
+    outbound_service::{resync::Resync, typing::TYPING_CONTENT_TYPE},
 };
 
 use super::{Chat, ChatId, CoreUser, FriendshipPackage, TimestampedMessage, anyhow};
@@ -101,6 +104,16 @@ pub struct ReactionNotification {
     pub original_chat_message: ChatMessage,
 }
 
+/// New messages of a single chat, collapsed into one notification.
+#[derive(Debug, Clone)]
+pub struct ChatNotificationSummary {
+    pub chat_id: ChatId,
+    /// The most recent of the new messages
+    pub latest_message: ChatMessage,
+    /// Number of new messages in the chat
+    pub new_messages: usize,
+}
+
 impl ProcessedQsMessages {
     pub fn is_empty(&self) -> bool {
         self.new_chats.is_empty()
@@ -108,6 +121,33 @@ impl ProcessedQsMessages {
             && self.new_messages.is_empty()
             && self.errors.is_empty()
     }
+
+    /// Groups the new messages by chat, so that a burst of messages results in a single
+    /// notification per chat.
+    ///
+    /// The summaries are in the order of the first new message of each chat.
+    pub fn group_by_chat(&self) -> Vec<ChatNotificationSummary> {
+        let mut summaries: Vec<ChatNotificationSummary> = Vec::new();
+        for message in &self.new_messages {
+            match summaries
+                .iter_mut()
+                .find(|summary| summary.chat_id == message.chat_id())
+            {
+                Some(summary) => {
+                    summary.new_messages += 1;
+                    if message.timestamp() >= summary.latest_message.timestamp() {
+                        summary.latest_message = message.clone();
+                    }
+                }
+                None => summaries.push(ChatNotificationSummary {
+                    chat_id: message.chat_id(),
+                    latest_message: message.clone(),
+                    new_messages: 1,
+                }),
+            }
+        }
+        summaries
+    }
 }
 
 #[derive(Default)]
@@ -231,10 +271,6 @@ impl CoreUser {
         // If yes, merge the commit and store the updated group
         let (mut group_messages, group_data_bytes) =
             group.merge_pending_commit(txn, None, timestamp).await?;
-        group
-            .group_mut()
-            .store_update(&mut *txn, Some(timestamp), None)
-            .await?;
 
         let mut chat = Chat::load_by_group_id(&mut *txn, &group_id)
             .await?
@@ -242,13 +278,17 @@ impl CoreUser {
 
         self.finalize_own_commit(
             txn,
-            &group,
+            group.group_mut(),
             &mut chat,
             group_data_bytes,
             &mut group_messages,
             timestamp,
         )
         .await?;
+        group
+            .group_mut()
+            .store_update(&mut *txn, Some(timestamp), None)
+            .await?;
 
         CoreUser::store_new_messages(&mut *txn, chat.id(), group_messages).await?;
 
@@ -257,8 +297,9 @@ impl CoreUser {
 
     /// Applies the side effects of merging one of our own commits: any group
     /// data change carried by the commit (currently the chat title) is applied
-    /// to `chat`, appending the corresponding system messages to
-    /// `group_messages`, and the pending chat operation is deleted.
+    /// to `chat`, any role change to the room state of `group`, appending the
+    /// corresponding system messages to `group_messages`, and the pending chat
+    /// operation is deleted.
     ///
     /// Shared by the `DsCommitResponse` and `OwnPendingCommit` paths, which
     /// race to merge our own commit: the DS both responds directly and echoes
@@ -268,14 +309,24 @@ impl CoreUser {
     async fn finalize_own_commit(
         &self,
         txn: &mut WriteDbTransaction<'_>,
-        group: &Group,
+        group: &mut Group,
         chat: &mut Chat,
         group_data_bytes: Option<GroupDataBytes>,
         group_messages: &mut Vec<TimestampedMessage>,
         ds_timestamp: TimeStamp,
     ) -> anyhow::Result<()> {
-        // Update group data in chat attributes if present
-        if let Some(group_data_bytes) = group_data_bytes {
+        let pending_operation =
+            PendingChatOperation::load_by_group_id(&mut *txn, group.group_id()).await?;
+        if let Some(role_change) = pending_operation
+            .as_ref()
+            .and_then(|operation| operation.role_change())
+        {
+            // The group data only carries the role change
+            let message =
+                group.apply_role_change(self.user_id(), role_change.clone(), ds_timestamp)?;
+            group_messages.push(message);
+        } else if let Some(group_data_bytes) = group_data_bytes {
+            // Update group data in chat attributes if present
             let group_data = GroupData::decode(&group_data_bytes)?;
             let (chat_title, _external_group_profile) =
                 group_data.into_parts(group.identity_link_wrapper_key());
@@ -784,8 +835,9 @@ impl CoreUser {
                         .await?;
                     (new_messages, Vec::new(), updated, Vec::new())
                 }
-                ProcessedMessageContent::ExternalJoinProposalMessage(_) => {
-                    let (new_messages, updated) = self.handle_external_join_proposal_message()?;
+                ProcessedMessageContent::ExternalJoinProposalMessage(proposal) => {
+                    let (new_messages, updated) =
+                        self.handle_external_join_proposal_message(&group, *proposal)?;
                     (new_messages, Vec::new(), updated, Vec::new())
                 }
                 ProcessedMessageContent::OwnPendingCommit => {
@@ -795,19 +847,19 @@ impl CoreUser {
                     let (mut group_messages, group_data_bytes) = group
                         .merge_pending_commit(&mut *txn, None, ds_timestamp)
                         .await?;
-                    group
-                        .group_mut()
-                        .store_update(&mut *txn, Some(ds_timestamp), None)
-                        .await?;
                     self.finalize_own_commit(
                         &mut *txn,
-                        &group,
+                        group.group_mut(),
                         &mut chat,
                         group_data_bytes,
                         &mut group_messages,
                         ds_timestamp,
                     )
                     .await?;
+                    group
+                        .group_mut()
+                        .store_update(&mut *txn, Some(ds_timestamp), None)
+                        .await?;
                     (group_messages, Vec::new(), true, Vec::new())
                 }
                 ProcessedMessageContent::OwnPrivateMessage => {
@@ -819,6 +871,15 @@ impl CoreUser {
             };
 
         let mut messages = Self::store_new_messages(&mut *txn, chat_id, new_messages).await?;
+
+        // A new message brings an archived chat back to the active chat list
+        if messages
+            .iter()
+            .any(|message| matches!(message.message(), Message::Content(_)))
+        {
+            Chat::set_archived(&mut *txn, chat_id, false).await?;
+        }
+
         for updated_message in updated_messages {
             updated_message.update(&mut *txn).await?;
             messages.push(updated_message);
@@ -896,6 +957,43 @@ impl CoreUser {
             return Ok(Default::default());
         }
 
+        // Typing signal
+        if let Ok(content) = &content
+            && let NestedPart::SinglePart { content_type, .. } = &content.nested_part
+            && content_type == TYPING_CONTENT_TYPE
+        {
+            if sender != self.user_id() && !BlockedContact::check_blocked(&mut *txn, sender).await?
+            {
+                let chat_id = ChatId::try_from(group.group_id())?;
+                txn.notifier().typing(chat_id, sender.clone());
+            }
+            // Typing signals are not stored
+            return Ok(Default::default());
+        }
+
+        // Presence update
+        if let Ok(content) = &content
+            && let NestedPart::SinglePart {
+                content_type,
+                content: presence_content,
+                ..
+            } = &content.nested_part
+            && content_type == PRESENCE_CONTENT_TYPE
+        {
+            if sender != self.user_id()
+                && !BlockedContact::check_blocked(&mut *txn, sender).await?
+                && let Some(state) = PresenceState::from_content(presence_content)
+            {
+                let presence = ContactPresence {
+                    state,
+                    last_seen: ds_timestamp.into(),
+                };
+                presence.store_if_newer(&mut *txn, sender).await?;
+            }
+            // Presence updates are not stored as messages
+            return Ok(Default::default());
+        }
+
         // Reaction (add or retraction).
         //
         // Must come before the message-edit branch: a retraction carries
@@ -1103,6 +1201,12 @@ impl CoreUser {
         // If a client joined externally, we check if the
         // group belongs to an unconfirmed chat.
 
+        // The role change carried by the commit (if any) is applied after merging.
+        let role_change = match AadMessage::tls_deserialize_exact_bytes(&aad)?.into_payload() {
+            AadPayload::ChangeRole(role_change) => Some(role_change),
+            _ => None,
+        };
+
         // StagedCommitMessage Phase 1: Confirm the chat if unconfirmed
 
         let (chat_changed, mut group_messages) = if chat.is_unconfirmed() {
@@ -1135,7 +1239,16 @@ impl CoreUser {
 
         group_messages.extend(messages_from_commit);
 
-        if let Some(group_data_bytes) = group_data_bytes {
+        if let Some(role_change) = role_change {
+            // The role change was checked to be part of the group context, which is otherwise
+            // unchanged by the commit.
+            let message = group.group_mut().apply_role_change(
+                sender_client_credential.user_id(),
+                role_change,
+                ds_timestamp,
+            )?;
+            group_messages.push(message);
+        } else if let Some(group_data_bytes) = group_data_bytes {
             let group_data = GroupData::decode(&group_data_bytes)?;
             let (chat_title, group_profile_part) =
                 group_data.into_parts(group.identity_link_wrapper_key());
@@ -1230,13 +1343,18 @@ impl CoreUser {
         // de-serialized this in the group processing
         // function, but we need the encrypted
         // friendship package here.
-        let encrypted_friendship_package = if let AadPayload::JoinConnectionGroup(payload) =
-            AadMessage::tls_deserialize_exact_bytes(&aad)?.into_payload()
-        {
-            payload.encrypted_friendship_package
-        } else {
-            bail!("Unexpected AAD payload")
-        };
+        //
+        // Unknown payloads are not skipped, even if ignorable, since the chat can't be confirmed
+        // without the friendship package.
+        let encrypted_friendship_package =
+            match AadMessage::tls_deserialize_exact_bytes(&aad)?.into_payload() {
+                AadPayload::JoinConnectionGroup(payload) => payload.encrypted_friendship_package,
+                AadPayload::Unknown(payload) => bail!(
+                    "Unknown AAD payload with tag {} in unconfirmed chat",
+                    payload.tag()
+                ),
+                _ => bail!("Unexpected AAD payload"),
+            };
 
         let friendship_package = FriendshipPackage::decrypt(
             contact.friendship_package_ear_key(),
@@ -1314,10 +1432,28 @@ impl CoreUser {
         Ok(ProcessQsMessageResult::None)
     }
 
+    /// Handles a proposal of a non-member to join the group.
+    ///
+    /// Members are only added by commits of existing members, which also provide the add infos
+    /// required by the DS. A join proposal can therefore not be committed by us and is dropped
+    /// without being stored, such that it does not end up in our next commit.
     fn handle_external_join_proposal_message(
         &self,
+        group: &VerifiedGroup,
+        proposal: QueuedProposal,
     ) -> anyhow::Result<(Vec<TimestampedMessage>, bool)> {
-        unimplemented!()
+        let Proposal::Add(add_proposal) = proposal.proposal() else {
+            bail!("External join proposal is not an add proposal");
+        };
+        let joiner = VerifiableClientCredential::from_basic_credential(
+            add_proposal.key_package().leaf_node().credential(),
+        )?;
+        info!(
+            group_id = ?group.group_id(),
+            joiner = ?joiner.user_id(),
+            "Dropping external join proposal"
+        );
+        Ok((Vec::new(), false))
     }
 
     /// Convenience function that takes a list of `QueueMessage`s retrieved from
@@ -1337,6 +1473,12 @@ impl CoreUser {
         // Each loop iteration MUST be a cancel-safe and process-safe future. The former is
         // important because the app can be shut down any time. The latter is important because the
         // QS messages are processed in the foreground and background handlers.
+        //
+        // Note: Messages of different groups are intentionally not processed concurrently. The
+        // group of a message is only known after it was decrypted with the queue ratchet, which
+        // must happen in queue order and in the same transaction as the processing (see above).
+        // Moreover, all processing happens in write transactions, which are serialized by the
+        // database anyway.
         for (idx, qs_message) in qs_messages.into_iter().enumerate() {
             // Start an outer transaction where the ratchet is loaded and updated. A savepoint after
             // the ratchet is loaded is passed to the processing of the QS message. This savepoint
@@ -1559,6 +1701,8 @@ async fn handle_message_edit(
         MessageEdit::delete_by_message_id(&mut *txn, message.id()).await?;
         // Delete attachments for this message
         AttachmentRecord::delete_by_message_id(&mut *txn, message.id()).await?;
+        // Unpin the message
+        PinnedMessage::unpin(&mut *txn, message.id()).await?;
     } else {
         // Store message edit
         MessageEdit::new(
@@ -1592,6 +1736,9 @@ async fn handle_message_edit(
     Ok(message)
 }
 
+/// Default maximum number of accumulated messages which are processed at once
+pub const DEFAULT_MAX_QS_BATCH_SIZE: usize = 100;
+
 /// A processor for the streamed QS events.
 ///
 /// This processor is meant to be used in the streaming context where the events are streamed one
@@ -1600,6 +1747,8 @@ async fn handle_message_edit(
 #[derive(Debug)]
 pub struct QsStreamProcessor {
     responder: Option<QsListenResponder>,
+    /// Whether processed messages are not acked, see [`Self::new_read_only`]
+    read_only: bool,
     /// Accumulated but not yet processed messages
     ///
     /// Note: It is safe to keep messages in memory here, because they are not yet decrypted.
@@ -1607,20 +1756,61 @@ pub struct QsStreamProcessor {
     /// which messages should be fetched from the server. In case, the app is shut down, the
     /// messages will be received again.
     messages: Vec<QueueMessage>,
+    /// When this number of messages is accumulated, they are processed without waiting for the
+    /// queue to be empty.
+    max_batch_size: usize,
+    /// Whether the background task is stopped until the queue is empty
+    outbound_stopped: bool,
 }
 
 impl QsStreamProcessor {
     pub fn new(responder: Option<QsListenResponder>) -> Self {
         Self {
             responder,
+            read_only: false,
             messages: Vec::new(),
+            max_batch_size: DEFAULT_MAX_QS_BATCH_SIZE,
+            outbound_stopped: false,
+        }
+    }
+
+    /// Creates a processor for a read-only observer, which never acks processed messages.
+    ///
+    /// Messages are processed as usual, in particular the local queue ratchet is advanced and
+    /// written back into the database. However, the messages stay in the server queue until they
+    /// are acked by a regular processor or expire. Since the next listen request starts at the
+    /// local ratchet sequence number, the messages are not received again by this client, but
+    /// they still count towards the queue size on the server.
+    pub fn new_read_only() -> Self {
+        Self {
+            read_only: true,
+            ..Self::new(None)
         }
     }
 
+    /// Sets the maximum number of accumulated messages which are processed at once (at least 1).
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size.max(1);
+        self
+    }
+
     pub fn replace_responder(&mut self, responder: QsListenResponder) {
         self.responder.replace(responder);
     }
 
+    /// Sends the pending messages of the chat if the background task is stopped while messages
+    /// are received. Otherwise, the background task sends them.
+    ///
+    /// If messages are accumulated, they are not processed here, because the result of the
+    /// processing must reach the consumer of the stream. The pending messages are then sent by
+    /// the background task, which is started again when the queue is empty.
+    pub async fn flush_chat(&mut self, core_user: &CoreUser, chat_id: ChatId) {
+        if !self.outbound_stopped || !self.messages.is_empty() {
+            return;
+        }
+        core_user.outbound_service().flush_chat(chat_id).await;
+    }
+
     pub async fn process_event(
         &mut self,
         core_user: &CoreUser,
@@ -1641,13 +1831,20 @@ impl QsStreamProcessor {
             Some(listen_response::Event::Message(message)) => match message.try_into() {
                 Ok(message) => {
                     // Invariant: after a message there is always an Empty event as sentinel
-                    // => accumulated messages will be processed there
+                    // => accumulated messages will be processed there at the latest
                     self.messages.push(message);
 
                     // Stop the background task and wait until it is fully stopped
                     core_user.outbound_service().stop().await;
-
-                    QsProcessEventResult::Accumulated
+                    self.outbound_stopped = true;
+
+                    if self.messages.len() >= self.max_batch_size {
+                        // Don't let the accumulated messages grow unbounded. The background task
+                        // stays stopped until the queue is empty.
+                        self.process_messages(core_user).await
+                    } else {
+                        QsProcessEventResult::Accumulated
+                    }
                 }
                 Err(error) => {
                     error!(%error, "failed to convert QS message; dropping");
@@ -1656,44 +1853,59 @@ impl QsStreamProcessor {
             },
             // Empty event indicates that the queue is empty
             Some(listen_response::Event::Empty(_)) => {
-                let max_sequence_number = self.messages.last().map(|m| m.sequence_number);
+                let result = self.process_messages(core_user).await;
 
-                let messages = std::mem::take(&mut self.messages);
-                let num_messages = messages.len();
+                // Start the background task, but don't wait for it to start
+                drop(core_user.outbound_service().start());
+                self.outbound_stopped = false;
 
-                let processed_messages = core_user.fully_process_qs_messages(messages).await;
+                result
+            }
+        }
+    }
 
-                let result = if processed_messages.processed < num_messages {
-                    error!(
-                        processed_messages.processed,
-                        num_messages, "failed to fully process messages"
-                    );
-                    QsProcessEventResult::PartiallyProcessed {
-                        dropped: num_messages - processed_messages.processed,
-                        processed: processed_messages,
-                    }
-                } else {
-                    if let Some(max_sequence_number) = max_sequence_number {
-                        // We received some messages, so we can ack them *after* they were fully
-                        // processed. In particular, the queue ratchet sequence number has been already
-                        // written back into the database.
-                        if let Some(responder) = self.responder.as_ref() {
-                            // Acks all messages before max_sequence_number + 1 (exclusive)
-                            responder.ack(max_sequence_number + 1).await;
-                        } else {
-                            error!("logic error: no responder to ack QS messages");
-                        }
-                    }
+    /// Processes all accumulated messages and acks them if they were fully processed, unless the
+    /// processor is read-only.
+    ///
+    /// Acking is cumulative: the ack of a batch also acks the messages of previous batches which
+    /// were only partially processed.
+    async fn process_messages(&mut self, core_user: &CoreUser) -> QsProcessEventResult {
+        let max_sequence_number = self.messages.last().map(|m| m.sequence_number);
 
-                    QsProcessEventResult::FullyProcessed {
-                        processed: processed_messages,
-                    }
-                };
+        let messages = std::mem::take(&mut self.messages);
+        let num_messages = messages.len();
 
-                // Start the background task, but don't wait for it to start
-                drop(core_user.outbound_service().start());
+        let processed_messages = core_user.fully_process_qs_messages(messages).await;
 
-                result
+        if processed_messages.processed < num_messages {
+            error!(
+                processed_messages.processed,
+                num_messages, "failed to fully process messages"
+            );
+            QsProcessEventResult::PartiallyProcessed {
+                dropped: num_messages - processed_messages.processed,
+                processed: processed_messages,
+            }
+        } else {
+            if let Some(max_sequence_number) = max_sequence_number {
+                // We received some messages, so we can ack them *after* they were fully
+                // processed. In particular, the queue ratchet sequence number has been already
+                // written back into the database.
+                if self.read_only {
+                    debug!(
+                        max_sequence_number,
+                        "read-only processor; not acking QS messages"
+                    );
+                } else if let Some(responder) = self.responder.as_ref() {
+                    // Acks all messages before max_sequence_number + 1 (exclusive)
+                    responder.ack(max_sequence_number + 1).await;
+                } else {
+                    error!("logic error: no responder to ack QS messages");
+                }
+            }
+
+            QsProcessEventResult::FullyProcessed {
+                processed: processed_messages,
             }
         }
     }
@@ -1732,16 +1944,71 @@ impl QsProcessEventResult {
 #[cfg(test)]
 mod tests {
     use aircommon::{identifiers::UserId, time::TimeStamp};
+    use chrono::Utc;
     use mimi_content::MimiContent;
     use sqlx::SqlitePool;
+    use tokio_stream::StreamExt;
 
     use crate::{
-        ChatMessage, ContentMessage, MessageId,
-        chats::persistence::tests::test_chat,
-        clients::process::process_qs::handle_message_edit,
-        db::access::{DbAccess, WriteConnection},
+        ChatId, ChatMessage, ContentMessage, MessageId,
+        chats::{persistence::tests::test_chat, pins::PinnedMessage},
+        clients::process::process_qs::{ProcessedQsMessages, handle_message_edit},
+        db::{
+            access::{DbAccess, WriteConnection},
+            notification::{DbEntityId, DbNotificationsSender, DbOperation},
+        },
     };
 
+    #[test]
+    fn group_new_messages_by_chat() {
+        let alice = UserId::random("localhost".parse().unwrap());
+        let chat_a = test_chat();
+        let chat_b = test_chat();
+        let message = |chat_id: ChatId, text: &str, seconds: i64| {
+            ChatMessage::new_for_test(
+                chat_id,
+                MessageId::random(),
+                chrono::DateTime::from_timestamp(seconds, 0).unwrap().into(),
+                ContentMessage::new(
+                    alice.clone(),
+                    false,
+                    MimiContent::simple_markdown_message(text.to_owned(), [0; 16]),
+                    chat_a.group_id(),
+                ),
+            )
+        };
+
+        let processed = ProcessedQsMessages {
+            new_messages: vec![
+                message(chat_a.id(), "a1", 1),
+                message(chat_b.id(), "b1", 2),
+                message(chat_a.id(), "a2", 3),
+                message(chat_a.id(), "a3", 4),
+            ],
+            ..Default::default()
+        };
+
+        let summaries = processed.group_by_chat();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].chat_id, chat_a.id());
+        assert_eq!(summaries[0].new_messages, 3);
+        let latest_text = |index: usize| {
+            summaries[index]
+                .latest_message
+                .message()
+                .mimi_content()
+                .unwrap()
+                .string_rendering()
+                .unwrap()
+        };
+        assert_eq!(latest_text(0), "a3");
+        assert_eq!(summaries[1].chat_id, chat_b.id());
+        assert_eq!(summaries[1].new_messages, 1);
+        assert_eq!(latest_text(1), "b1");
+
+        assert!(ProcessedQsMessages::default().group_by_chat().is_empty());
+    }
+
     /// Editing a message (without deleting) should not update any `in_reply_to` references.
     #[sqlx::test]
     async fn test_handle_message_edit_does_not_update_reply_references(
@@ -1862,6 +2129,71 @@ mod tests {
         Ok(())
     }
 
+    /// Deleting a pinned message should unpin it.
+    #[sqlx::test]
+    async fn test_handle_message_delete_unpins_message(pool: SqlitePool) -> anyhow::Result<()> {
+        let notifications = DbNotificationsSender::new();
+        let pool = DbAccess::with_single_pool(pool, notifications.clone());
+
+        let chat = test_chat();
+        chat.store(pool.write().await?).await?;
+
+        let group_id = chat.group_id();
+        let alice = UserId::random("localhost".parse().unwrap());
+
+        // Alice sends a message which is pinned
+        let alice_message = ChatMessage::new_for_test(
+            chat.id(),
+            MessageId::random(),
+            TimeStamp::now(),
+            ContentMessage::new(
+                alice.clone(),
+                false,
+                MimiContent::simple_markdown_message("Hello from Alice!".to_string(), [0; 16]),
+                group_id,
+            ),
+        );
+        alice_message.store(pool.write().await?).await?;
+        PinnedMessage::pin(
+            pool.write().await?,
+            chat.id(),
+            alice_message.id(),
+            Utc::now(),
+        )
+        .await?;
+
+        let mut notifications = std::pin::pin!(notifications.subscribe());
+
+        // Alice deletes her message
+        let mut connection = pool.write().await?;
+        let mut txn = connection.begin().await?;
+        let alice_message = handle_message_edit(
+            &mut txn,
+            group_id,
+            TimeStamp::now(),
+            &alice,
+            *alice_message.message().mimi_id().unwrap(),
+            alice_message.null_part_content()?,
+        )
+        .await?;
+        alice_message.update(&mut txn).await?;
+        txn.commit().await?;
+        connection.notify();
+
+        let pinned = PinnedMessage::load_message_ids(&mut connection, chat.id()).await?;
+        assert!(pinned.is_empty());
+
+        let notification = notifications.next().await.unwrap();
+        assert!(
+            notification
+                .ops
+                .get(&DbEntityId::Chat(chat.id()))
+                .is_some_and(|ops| ops.contains(DbOperation::Update))
+        );
+
+        Ok(())
+    }
+
     /// When multiple messages reply to the same message, deleting it should update all of their
     /// `in_reply_to` references.
     #[sqlx::test]
@@ -13,7 +13,7 @@ use crate::{
     Chat, ChatId, ChatMessage, MessageId,
     chats::reactions::{Reaction, reaction_content, reaction_tombstone_content},
     clients::block_contact::BlockedContactError,
-    db::access::WriteConnection,
+    db::access::{WriteConnection, WriteDbTransaction},
 };
 
 use super::CoreUser;
@@ -29,61 +29,100 @@ impl CoreUser {
         target: MessageId,
         emoji: String,
     ) -> anyhow::Result<()> {
+        Box::pin(self.db().with_write_transaction(async |txn| {
+            self.add_reaction(txn, chat_id, target, emoji).await
+        }))
+        .await
+    }
+
+    /// Adds our reaction with `emoji` to a message if there is none, otherwise removes it.
+    ///
+    /// Returns whether the message has our reaction afterwards.
+    pub async fn toggle_reaction(
+        &self,
+        chat_id: ChatId,
+        target: MessageId,
+        emoji: String,
+    ) -> anyhow::Result<bool> {
         Box::pin(
             self.db()
-                .with_write_transaction(async |txn| -> anyhow::Result<()> {
-                    if Chat::is_blocked(&mut *txn, chat_id).await? {
-                        bail!(BlockedContactError);
-                    }
-
+                .with_write_transaction(async |txn| -> anyhow::Result<bool> {
                     let target_message = ChatMessage::load(&mut *txn, target)
                         .await?
                         .with_context(|| format!("Can't find message with id {target:?}"))?;
-                    let target_mimi_id = target_message
-                        .message()
-                        .mimi_id()
-                        .copied()
-                        .context("Can't react to a message without a MimiId")?;
-
-                    let chat = Chat::load(&mut *txn, &chat_id)
+                    let has_reaction = match target_message.message().mimi_id() {
+                        Some(target_mimi_id) => Reaction::load_mimi_id(
+                            &mut *txn,
+                            target_mimi_id,
+                            self.user_id(),
+                            &emoji,
+                        )
                         .await?
-                        .with_context(|| format!("Can't find chat with id {chat_id}"))?;
-
-                    let sender = self.user_id().clone();
-                    let content = reaction_content(&target_mimi_id, &emoji)?;
-                    let reaction_mimi_id = MimiId::calculate(chat.group_id(), &sender, &content)?;
-
-                    let reaction = Reaction::new(
-                        reaction_mimi_id,
-                        target_mimi_id,
-                        chat_id,
-                        sender,
-                        emoji,
-                        TimeStamp::now(),
-                    );
-
-                    // Idempotent: if we already reacted with this emoji, do nothing.
-                    if !reaction.store(&mut *txn).await? {
-                        return Ok(());
+                        .is_some(),
+                        None => false,
+                    };
+                    if has_reaction {
+                        self.remove_reaction(txn, chat_id, target, emoji).await?;
+                    } else {
+                        self.add_reaction(txn, chat_id, target, emoji).await?;
                     }
-
-                    let bytes = content.serialize()?;
-                    self.outbound_service()
-                        .enqueue_reaction_in_transaction(
-                            txn,
-                            chat_id,
-                            Some(&reaction_mimi_id),
-                            &bytes,
-                        )
-                        .await?;
-
-                    txn.notifier().update(target);
-                    Ok(())
+                    Ok(!has_reaction)
                 }),
         )
         .await
     }
 
+    async fn add_reaction(
+        &self,
+        txn: &mut WriteDbTransaction<'_>,
+        chat_id: ChatId,
+        target: MessageId,
+        emoji: String,
+    ) -> anyhow::Result<()> {
+        if Chat::is_blocked(&mut *txn, chat_id).await? {
+            bail!(BlockedContactError);
+        }
+
+        let target_message = ChatMessage::load(&mut *txn, target)
+            .await?
+            .with_context(|| format!("Can't find message with id {target:?}"))?;
+        let target_mimi_id = target_message
+            .message()
+            .mimi_id()
+            .copied()
+            .context("Can't react to a message without a MimiId")?;
+
+        let chat = Chat::load(&mut *txn, &chat_id)
+            .await?
+            .with_context(|| format!("Can't find chat with id {chat_id}"))?;
+
+        let sender = self.user_id().clone();
+        let content = reaction_content(&target_mimi_id, &emoji)?;
+        let reaction_mimi_id = MimiId::calculate(chat.group_id(), &sender, &content)?;
+
+        let reaction = Reaction::new(
+            reaction_mimi_id,
+            target_mimi_id,
+            chat_id,
+            sender,
+            emoji,
+            TimeStamp::now(),
+        );
+
+        // Idempotent: if we already reacted with this emoji, do nothing.
+        if !reaction.store(&mut *txn).await? {
+            return Ok(());
+        }
+
+        let bytes = content.serialize()?;
+        self.outbound_service()
+            .enqueue_reaction_in_transaction(txn, chat_id, Some(&reaction_mimi_id), &bytes)
+            .await?;
+
+        txn.notifier().update(target);
+        Ok(())
+    }
+
     /// Load all reactions on a message, ordered oldest first.
     ///
     /// In group chats this is also how the UI shows who reacted with what.
@@ -120,35 +159,42 @@ impl CoreUser {
         target: MessageId,
         emoji: String,
     ) -> anyhow::Result<()> {
-        Box::pin(
-            self.db()
-                .with_write_transaction(async |txn| -> anyhow::Result<()> {
-                    let target_message = ChatMessage::load(&mut *txn, target)
-                        .await?
-                        .with_context(|| format!("Can't find message with id {target:?}"))?;
-                    let Some(target_mimi_id) = target_message.message().mimi_id().copied() else {
-                        return Ok(());
-                    };
+        Box::pin(self.db().with_write_transaction(async |txn| {
+            self.remove_reaction(txn, chat_id, target, emoji).await
+        }))
+        .await
+    }
 
-                    let sender = self.user_id().clone();
-                    let Some(reaction_mimi_id) =
-                        Reaction::load_mimi_id(&mut *txn, &target_mimi_id, &sender, &emoji).await?
-                    else {
-                        return Ok(()); // we have no such reaction
-                    };
+    async fn remove_reaction(
+        &self,
+        txn: &mut WriteDbTransaction<'_>,
+        chat_id: ChatId,
+        target: MessageId,
+        emoji: String,
+    ) -> anyhow::Result<()> {
+        let target_message = ChatMessage::load(&mut *txn, target)
+            .await?
+            .with_context(|| format!("Can't find message with id {target:?}"))?;
+        let Some(target_mimi_id) = target_message.message().mimi_id().copied() else {
+            return Ok(());
+        };
 
-                    Reaction::delete_by_mimi_id(&mut *txn, &reaction_mimi_id).await?;
+        let sender = self.user_id().clone();
+        let Some(reaction_mimi_id) =
+            Reaction::load_mimi_id(&mut *txn, &target_mimi_id, &sender, &emoji).await?
+        else {
+            return Ok(()); // we have no such reaction
+        };
 
-                    let content = reaction_tombstone_content(&target_mimi_id, &reaction_mimi_id)?;
-                    let bytes = content.serialize()?;
-                    self.outbound_service()
-                        .enqueue_reaction_in_transaction(txn, chat_id, None, &bytes)
-                        .await?;
+        Reaction::delete_by_mimi_id(&mut *txn, &reaction_mimi_id).await?;
 
-                    txn.notifier().update(target);
-                    Ok(())
-                }),
-        )
-        .await
+        let content = reaction_tombstone_content(&target_mimi_id, &reaction_mimi_id)?;
+        let bytes = content.serialize()?;
+        self.outbound_service()
+            .enqueue_reaction_in_transaction(txn, chat_id, None, &bytes)
+            .await?;
+
+        txn.notifier().update(target);
+        Ok(())
     }
 }
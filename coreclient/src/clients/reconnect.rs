@@ -0,0 +1,288 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Listen streams which reconnect after the connection dropped.
+
+use std::{sync::Arc, time::Duration};
+
+use airapiclient::as_api::AsListenUsernameResponder;
+use airprotos::{auth_service::v1::UsernameQueueMessage, queue_service::v1::ListenResponse};
+use tokio::sync::mpsc;
+use tokio_stream::{Stream, StreamExt, wrappers::ReceiverStream};
+use tracing::{info, warn};
+
+use crate::{
+    UsernameRecord,
+    clients::{CoreUser, ListenQueueError, ListenUsernameError},
+};
+
+/// Capacity of the channel between the reconnect task and the returned stream
+const RECONNECT_CHANNEL_CAPACITY: usize = 16; // not too big for applying backpressure
+
+/// Exponential backoff between reconnection attempts.
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    /// Backoff before the first reconnection attempt; doubled for each following attempt
+    pub initial_backoff: Duration,
+    /// Maximum backoff between two reconnection attempts
+    pub max_backoff: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Backoff before the reconnection attempt with the given index (starting at 0).
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .checked_mul(2u32.saturating_pow(attempt))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+/// Connection status of a reconnecting listen stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListenStatus {
+    /// The stream is connected and receives events
+    Connected,
+    /// The connection failed or dropped; the next attempt is made after `retry_in`
+    Reconnecting { attempt: u32, retry_in: Duration },
+}
+
+/// Item of a reconnecting listen stream
+#[derive(Debug)]
+pub enum ListenItem<T> {
+    Status(ListenStatus),
+    Event(T),
+}
+
+/// Message from a username queue together with the responder of the connection it was received
+/// on.
+#[derive(Debug)]
+pub struct UsernameQueueEvent {
+    pub message: UsernameQueueMessage,
+    pub responder: Arc<AsListenUsernameResponder>,
+}
+
+/// Error of a listen request which is either transient or terminal.
+trait ListenError: std::error::Error + Send + 'static {
+    fn is_terminal(&self) -> bool;
+}
+
+impl ListenError for ListenQueueError {
+    fn is_terminal(&self) -> bool {
+        ListenQueueError::is_terminal(self)
+    }
+}
+
+impl ListenError for ListenUsernameError {
+    fn is_terminal(&self) -> bool {
+        ListenUsernameError::is_terminal(self)
+    }
+}
+
+impl CoreUser {
+    /// Same as [`Self::listen_queue`], but reconnects when the connection fails or drops.
+    ///
+    /// On each connection, the new QS listen responder is passed to the event loop, and the queue
+    /// is listened to from the sequence number after the last processed message.
+    ///
+    /// The stream yields a terminal error, e.g. an unsupported client version, and ends
+    /// afterwards.
+    pub fn listen_queue_reconnecting(
+        &self,
+        policy: ReconnectPolicy,
+    ) -> impl Stream<Item = Result<ListenItem<ListenResponse>, ListenQueueError>> + Send + use<>
+    {
+        let core_user = self.clone();
+        reconnecting(policy, move || {
+            let core_user = core_user.clone();
+            async move {
+                let (stream, responder) = core_user.listen_queue().await?;
+                core_user.replace_qs_listen_responder(responder).await?;
+                Ok(stream)
+            }
+        })
+    }
+
+    /// Same as [`Self::listen_username`], but reconnects when the connection fails or drops.
+    ///
+    /// Messages must be acked with the responder they are yielded with.
+    ///
+    /// The stream yields a terminal error, e.g. an unsupported client version or a username which
+    /// does not exist anymore, and ends afterwards.
+    pub fn listen_username_reconnecting(
+        &self,
+        username_record: UsernameRecord,
+        policy: ReconnectPolicy,
+    ) -> impl Stream<Item = Result<ListenItem<UsernameQueueEvent>, ListenUsernameError>> + Send + use<>
+    {
+        let core_user = self.clone();
+        reconnecting(policy, move || {
+            let core_user = core_user.clone();
+            let username_record = username_record.clone();
+            async move {
+                let (stream, responder) = core_user.listen_username(&username_record).await?;
+                let responder = Arc::new(responder);
+                Ok(stream.filter_map(move |message| {
+                    Some(UsernameQueueEvent {
+                        message: message?,
+                        responder: responder.clone(),
+                    })
+                }))
+            }
+        })
+    }
+}
+
+/// Connects with `connect` and reconnects with a backoff after a connection failed or the stream
+/// ended, until a terminal error occurs or the returned stream is dropped.
+fn reconnecting<T, E, S, F>(
+    policy: ReconnectPolicy,
+    mut connect: impl FnMut() -> F + Send + 'static,
+) -> impl Stream<Item = Result<ListenItem<T>, E>> + Send + use<T, E, S, F>
+where
+    T: Send + 'static,
+    E: ListenError,
+    S: Stream<Item = T> + Send + 'static,
+    F: Future<Output = Result<S, E>> + Send,
+{
+    let (tx, rx) = mpsc::channel(RECONNECT_CHANNEL_CAPACITY);
+    tokio::spawn(async move {
+        let mut attempt = 0;
+        loop {
+            match connect().await {
+                Ok(stream) => {
+                    attempt = 0;
+                    if tx
+                        .send(Ok(ListenItem::Status(ListenStatus::Connected)))
+                        .await
+                        .is_err()
+                    {
+                        return;
+                    }
+                    let mut stream = std::pin::pin!(stream);
+                    loop {
+                        let event = tokio::select! {
+                            _ = tx.closed() => return,
+                            event = stream.next() => event,
+                        };
+                        let Some(event) = event else {
+                            info!("listen stream ended");
+                            break;
+                        };
+                        if tx.send(Ok(ListenItem::Event(event))).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(error) if error.is_terminal() => {
+                    warn!(%error, "listen failed with terminal error");
+                    let _ = tx.send(Err(error)).await;
+                    return;
+                }
+                Err(error) => {
+                    warn!(%error, "listen failed");
+                }
+            }
+
+            let retry_in = policy.backoff(attempt);
+            attempt += 1;
+            let status = ListenStatus::Reconnecting { attempt, retry_in };
+            if tx.send(Ok(ListenItem::Status(status))).await.is_err() {
+                return;
+            }
+            tokio::select! {
+                _ = tx.closed() => return,
+                _ = tokio::time::sleep(retry_in) => {}
+            }
+        }
+    });
+    ReceiverStream::new(rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use airapiclient::qs_api::QsRequestError;
+    use tonic::Status;
+
+    use super::*;
+
+    fn test_policy() -> ReconnectPolicy {
+        ReconnectPolicy {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+        }
+    }
+
+    fn qs_error(status: Status) -> ListenQueueError {
+        ListenQueueError::Qs(QsRequestError::Tonic(status))
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let policy = test_policy();
+        let backoffs: Vec<_> = (0..4).map(|attempt| policy.backoff(attempt)).collect();
+        assert_eq!(
+            backoffs,
+            [1, 2, 4, 4].map(Duration::from_millis),
+            "backoff doubles up to the maximum"
+        );
+        assert_eq!(policy.backoff(u32::MAX), policy.max_backoff);
+    }
+
+    #[tokio::test]
+    async fn reconnect_until_terminal_error() {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let stream = reconnecting(test_policy(), {
+            let attempts = attempts.clone();
+            move || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    match attempt {
+                        0 => Err(qs_error(Status::unavailable("connection refused"))),
+                        1 => Ok(tokio_stream::iter([1, 2])),
+                        _ => Err(qs_error(Status::failed_precondition("version unsupported"))),
+                    }
+                }
+            }
+        });
+        let items: Vec<_> = stream.collect().await;
+
+        assert_eq!(items.len(), 6);
+        assert!(matches!(
+            items[0],
+            Ok(ListenItem::Status(ListenStatus::Reconnecting {
+                attempt: 1,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            items[1],
+            Ok(ListenItem::Status(ListenStatus::Connected))
+        ));
+        assert!(matches!(items[2], Ok(ListenItem::Event(1))));
+        assert!(matches!(items[3], Ok(ListenItem::Event(2))));
+        // The stream ended, so the backoff starts over
+        assert!(matches!(
+            items[4],
+            Ok(ListenItem::Status(ListenStatus::Reconnecting {
+                attempt: 1,
+                ..
+            }))
+        ));
+        // A terminal error ends the stream without reconnecting
+        assert!(matches!(&items[5], Err(error) if error.is_terminal()));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+}
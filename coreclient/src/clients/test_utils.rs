@@ -6,15 +6,23 @@
 use aircommon::messages::client_ds_out::SendMessageCollisionTag;
 use openmls::group::Member;
 
-use aircommon::{codec::PersistenceCodec, identifiers::QualifiedGroupId};
-use openmls::prelude::GroupId;
+use aircommon::{
+    codec::PersistenceCodec,
+    identifiers::{QualifiedGroupId, RemoteAttachmentId},
+};
+use openmls::prelude::{GroupEpoch, GroupId, JoinProposal};
+use tls_codec::Serialize as _;
 use uuid::Uuid;
 
 use airprotos::client::{component::AirComponent, group::GroupData};
 
 use crate::{
+    AttachmentId,
     chats::GroupDataExt,
-    groups::{GroupDataBytes, self_group::SelfGroup},
+    groups::{
+        GroupDataBytes, openmls_provider::storage_provider::SqliteStorageProvider,
+        self_group::SelfGroup,
+    },
     job::pending_chat_operation::{PendingChatOperation, test_utils::PendingChatOperationInfo},
     outbound_service::resync::Resync,
 };
@@ -29,12 +37,33 @@ impl CoreUser {
         server_url: Url,
         push_token: Option<PushToken>,
         invitation_code: String,
+    ) -> Result<Self> {
+        Self::new_ephemeral_with_ciphersuite(
+            user_id,
+            server_url,
+            push_token,
+            invitation_code,
+            DEFAULT_CIPHERSUITE,
+        )
+        .await
+    }
+
+    /// The same as [`Self::new_with_ciphersuite()`], except that databases are ephemeral and are
+    /// dropped together with this instance of [`CoreUser`].
+    pub async fn new_ephemeral_with_ciphersuite(
+        user_id: UserId,
+        server_url: Url,
+        push_token: Option<PushToken>,
+        invitation_code: String,
+        ciphersuite: Ciphersuite,
     ) -> Result<Self> {
         use crate::{
             db::notification::DbNotificationsSender, utils::persistence::open_db_in_memory,
         };
 
-        info!(?user_id, "creating new ephemeral user");
+        check_ciphersuite(ciphersuite)?;
+
+        info!(?user_id, ?ciphersuite, "creating new ephemeral user");
 
         let notifier_tx = DbNotificationsSender::new();
 
@@ -55,10 +84,25 @@ impl CoreUser {
             client_db,
             global_lock,
             invitation_code,
+            ciphersuite,
         )
         .await
     }
 
+    /// Returns the ciphersuite of new groups and key packages of this user.
+    pub async fn ciphersuite(&self) -> Result<Ciphersuite> {
+        Ok(OwnClientInfo::load_ciphersuite(self.db().read().await?).await?)
+    }
+
+    /// Returns the ciphersuite of the group of the chat.
+    pub async fn group_ciphersuite(&self, chat_id: ChatId) -> Result<Option<Ciphersuite>> {
+        Ok(self
+            .db()
+            .with_read_transaction(async |txn| Group::load_with_chat_id(txn, chat_id).await)
+            .await?
+            .map(|group| group.mls_group().ciphersuite()))
+    }
+
     pub fn qs_user_id(&self) -> aircommon::identifiers::QsUserId {
         self.inner.qs_user_id
     }
@@ -123,6 +167,48 @@ impl CoreUser {
             .map(|group| group.members().collect())
     }
 
+    /// Returns the MLS group id and current epoch of the group of the chat.
+    pub async fn mls_group_id_and_epoch(
+        &self,
+        chat_id: ChatId,
+    ) -> Result<Option<(GroupId, GroupEpoch)>> {
+        Ok(self
+            .db()
+            .with_read_transaction(async |txn| Group::load_with_chat_id(txn, chat_id).await)
+            .await?
+            .map(|group| (group.group_id().clone(), group.mls_group().epoch())))
+    }
+
+    /// Creates a proposal of this user to join the group with the given id at the given epoch.
+    ///
+    /// Returns the serialized MLS message, which can be fed to
+    /// [`CoreUser::process_incoming_mls_message`] of a group member.
+    pub async fn external_join_proposal(
+        &self,
+        group_id: GroupId,
+        epoch: GroupEpoch,
+    ) -> Result<Vec<u8>> {
+        let key_package = self
+            .db()
+            .with_write_transaction(async |txn| {
+                let ciphersuite = OwnClientInfo::load_ciphersuite(&mut *txn).await?;
+                self.inner.key_store.generate_key_package(
+                    txn,
+                    &self.inner.qs_client_id,
+                    false,
+                    ciphersuite,
+                )
+            })
+            .await?;
+        let message = JoinProposal::new::<SqliteStorageProvider>(
+            &key_package,
+            group_id,
+            epoch,
+            self.signing_key(),
+        )?;
+        Ok(message.tls_serialize_detached()?)
+    }
+
     /// Enqueues a resync with a fabricated group_id that does not exist on the
     /// server. Uses the real group's keys so the request reaches the server and
     /// gets a "not found" response.
@@ -217,6 +303,7 @@ impl CoreUser {
             external_group_profile: None,
             legacy_title: Some(title),
             legacy_picture: None,
+            role_change: None,
         };
         let group_data_bytes = group_data.encode()?;
         let job = self
@@ -275,6 +362,33 @@ impl CoreUser {
         Ok(())
     }
 
+    /// Replaces the friendship token of the contact with a random one.
+    ///
+    /// Use this in tests to simulate a contact whose key packages can't be fetched.
+    pub async fn invalidate_friendship_token(&self, user_id: &UserId) -> anyhow::Result<()> {
+        self.db()
+            .with_write_transaction(async |txn| {
+                let mut contact = Contact::load(&mut *txn, user_id)
+                    .await?
+                    .with_context(|| format!("Can't find contact {user_id:?}"))?;
+                contact.friendship_token = FriendshipToken::random()?;
+                contact.upsert(txn).await?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Stores the username record locally without registering the username on the server.
+    ///
+    /// Use this in tests to simulate a username whose queue cannot be listened to.
+    pub async fn store_unregistered_username_record(
+        &self,
+        record: &UsernameRecord,
+    ) -> anyhow::Result<()> {
+        record.store(self.db().write().await?).await?;
+        Ok(())
+    }
+
     /// Send a message to the DS using the given collision tags instead of
     /// auto-derived ones. Used in tests to simulate a second emulator client
     /// sending with the same generation.
@@ -321,4 +435,39 @@ impl CoreUser {
 
         Ok(())
     }
+
+    /// Returns the id of the attachment at the DS.
+    pub async fn remote_attachment_id(
+        &self,
+        attachment_id: AttachmentId,
+    ) -> Result<Option<RemoteAttachmentId>> {
+        let record = AttachmentRecord::load(self.db().read().await?, attachment_id).await?;
+        Ok(record.and_then(|record| record.remote_attachment_id))
+    }
+
+    /// Requests the upload URL of the first part of the multipart upload of an attachment in the
+    /// chat as the sender of this user.
+    pub async fn request_attachment_part_upload_url(
+        &self,
+        chat_id: ChatId,
+        remote_attachment_id: RemoteAttachmentId,
+        content_length: u64,
+    ) -> Result<Result<(), airapiclient::ds_api::DsRequestError>> {
+        use airapiclient::ds_api::DsMultipartUpload;
+
+        let group = Group::load_with_chat_id_clean(self.db().read().await?, chat_id)
+            .await?
+            .context("group not found")?;
+        let upload = DsMultipartUpload {
+            group_state_ear_key: group.group_state_ear_key(),
+            group_id: group.group_id(),
+            sender_index: group.own_index(),
+            remote_attachment_id,
+        };
+        Ok(self
+            .api_client()?
+            .ds_get_attachment_part_upload_url(self.signing_key(), upload, 1, content_length)
+            .await
+            .map(|_| ()))
+    }
 }
@@ -2,11 +2,18 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use aircommon::{codec::PersistenceCodec, identifiers::UserId};
+use aircommon::{codec::PersistenceCodec, crypto::aead::AEAD_NONCE_SIZE, identifiers::UserId};
+use airprotos::{
+    common::v1::Ciphertext,
+    queue_service::v1::{QueueEmpty, QueueMessage},
+};
 use airserver_test_harness::utils::setup::TestBackend;
 
 use crate::{
-    clients::store::{ClientRecord, ClientRecordState, UserCreationState},
+    clients::{
+        FetchedQsMessages, InvalidQueueMessageReason, ListenResponse, listen_response,
+        store::{ClientRecord, ClientRecordState, UserCreationState},
+    },
     db::{access::DbAccess, notification::DbNotificationsSender},
     utils::persistence::open_db_in_memory,
 };
@@ -169,3 +176,89 @@ async fn user_stages() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn collect_invalid_queue_messages() {
+    let message = |sequence_number, ciphertext| ListenResponse {
+        event: Some(listen_response::Event::Message(QueueMessage {
+            sequence_number,
+            ciphertext,
+        })),
+    };
+    let ciphertext = |nonce_len| Ciphertext {
+        ciphertext: vec![1, 2, 3],
+        nonce: vec![0; nonce_len],
+    };
+    let stream = tokio_stream::iter([
+        message(0, Some(ciphertext(AEAD_NONCE_SIZE))),
+        message(1, None),
+        message(2, Some(ciphertext(AEAD_NONCE_SIZE - 1))),
+        ListenResponse {
+            event: Some(listen_response::Event::Empty(QueueEmpty {})),
+        },
+        // not collected after the queue is empty
+        message(3, Some(ciphertext(AEAD_NONCE_SIZE))),
+    ]);
+
+    let fetched = FetchedQsMessages::collect(stream).await;
+
+    let sequence_numbers: Vec<_> = fetched.messages.iter().map(|m| m.sequence_number).collect();
+    assert_eq!(sequence_numbers, [0]);
+    assert_eq!(fetched.invalid.len(), 2);
+    assert_eq!(fetched.invalid[0].sequence_number, 1);
+    assert!(matches!(
+        fetched.invalid[0].reason,
+        InvalidQueueMessageReason::MissingField(_)
+    ));
+    assert_eq!(fetched.invalid[1].sequence_number, 2);
+    assert!(matches!(
+        fetched.invalid[1].reason,
+        InvalidQueueMessageReason::InvalidNonceLen(_)
+    ));
+}
+
+#[test]
+fn scan_non_utf8_values() {
+    use super::scanned_value_matches;
+
+    // CBOR array of bytes with an invalid UTF-8 sequence
+    let value = [0x98, 0x18, 0xff, 0x18, 0xfe, b'a', b'b'];
+
+    assert!(scanned_value_matches(&value, &[0xff, 0x18, 0xfe], true));
+    assert!(!scanned_value_matches(&value, &[0xff, 0xfe], true));
+    assert!(scanned_value_matches(&value, &[0xff, 0xfe], false));
+    // The lossy conversion replaces invalid bytes, which then must not match
+    assert!(!scanned_value_matches(&value, "\u{fffd}".as_bytes(), false));
+    assert!(scanned_value_matches(&value, b"ab", true));
+}
+
+#[tokio::test]
+async fn new_user_with_unsupported_ciphersuite() -> anyhow::Result<()> {
+    use openmls::prelude::Ciphersuite;
+
+    use aircommon::mls_group_config::SUPPORTED_CIPHERSUITES;
+
+    use super::{CoreUser, UnsupportedCiphersuiteError, check_ciphersuite};
+
+    for &ciphersuite in SUPPORTED_CIPHERSUITES {
+        assert!(check_ciphersuite(ciphersuite).is_ok());
+    }
+
+    let unsupported = Ciphersuite::MLS_128_MLKEM768_AES256GCM_SHA384_Ed25519;
+    let db_dir = tempfile::tempdir()?;
+    let error = CoreUser::new_with_ciphersuite(
+        UserId::random("example.com".parse().unwrap()),
+        db_dir.path().to_str().unwrap(),
+        None,
+        String::new(),
+        unsupported,
+    )
+    .await
+    .unwrap_err();
+    let error = error.downcast_ref::<UnsupportedCiphersuiteError>();
+    assert!(matches!(error, Some(UnsupportedCiphersuiteError(suite)) if *suite == unsupported));
+    // Nothing was created
+    assert_eq!(std::fs::read_dir(db_dir.path())?.count(), 0);
+
+    Ok(())
+}
@@ -14,7 +14,10 @@ use crate::{
     clients::block_contact::BlockedContact,
     groups::Group,
     key_stores::indexed_keys::StorableIndexedKey,
-    user_profiles::{IndexedUserProfile, UserProfile, update::UserProfileUpdate},
+    user_profiles::{
+        IndexedUserProfile, UserProfile, status_text::normalize_status_text,
+        update::UserProfileUpdate,
+    },
 };
 
 use super::CoreUser;
@@ -22,9 +25,14 @@ use super::CoreUser;
 impl CoreUser {
     pub async fn update_user_profile(
         &self,
-        user_profile_content: UserProfile,
+        mut user_profile_content: UserProfile,
     ) -> anyhow::Result<()> {
+        user_profile_content.status_text =
+            normalize_status_text(user_profile_content.status_text.take())?;
         let user_profile_key = UserProfileKey::random(self.user_id())?;
+        // Clients which don't understand the status text can't read a profile containing it
+        let share_status_text = user_profile_content.status_text.is_some()
+            && self.all_members_support_status_text().await?;
 
         // Phase 1: Store the new user profile key in the database
         let encryptable_user_profile = self
@@ -38,6 +46,7 @@ impl CoreUser {
                     current_profile,
                     user_profile_content,
                     user_profile_key.index().clone(),
+                    share_status_text,
                     &self.inner.key_store.signing_key,
                 )?
                 .store(&mut *txn)
@@ -108,4 +117,21 @@ impl CoreUser {
 
         Ok(())
     }
+
+    /// Returns whether the members of all groups understand the status text in user profiles.
+    async fn all_members_support_status_text(&self) -> anyhow::Result<bool> {
+        let mut connection = self.db().read().await?;
+        for group_id in Group::load_all_group_ids(&mut connection).await? {
+            let group = Group::load(&mut connection, &group_id)
+                .await?
+                .context("Failed to load group")?;
+            let all_supported = group.members_air_component().all(|component| {
+                component.is_some_and(|component| component.features.user_profile_status_text)
+            });
+            if !all_supported {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
 }
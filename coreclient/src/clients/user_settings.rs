@@ -2,7 +2,12 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::time::Duration;
+
+use airprotos::client::component::AirFeatures;
 use anyhow::bail;
+use chrono::{DateTime, Utc};
+use enumset::{EnumSet, EnumSetType};
 use tracing::error;
 
 use crate::clients::CoreUser;
@@ -42,6 +47,119 @@ impl CoreUser {
         UserSettingRecord::store(self.db().write().await?, T::KEY, T::encode(value)?).await?;
         Ok(())
     }
+
+    /// Returns the features supported by this client and enabled by the user.
+    pub async fn feature_flags(&self) -> FeatureFlags {
+        let mut enabled = self.enabled_features().await;
+        let read_receipts = self
+            .user_setting::<ReadReceiptsSetting>()
+            .await
+            .map(|setting| setting.0)
+            .unwrap_or(true);
+        if read_receipts {
+            enabled.insert(Feature::ReadReceipts);
+        } else {
+            enabled.remove(Feature::ReadReceipts);
+        }
+        FeatureFlags {
+            supported: Feature::supported(),
+            enabled,
+        }
+    }
+
+    /// Enables or disables a feature locally.
+    pub async fn set_feature(&self, feature: Feature, enabled: bool) -> anyhow::Result<()> {
+        // Read receipts have a dedicated setting
+        if feature == Feature::ReadReceipts {
+            return self.set_user_setting(&ReadReceiptsSetting(enabled)).await;
+        }
+        let mut features = self.enabled_features().await;
+        if enabled {
+            features.insert(feature);
+        } else {
+            features.remove(feature);
+        }
+        self.set_user_setting(&EnabledFeaturesSetting(features))
+            .await
+    }
+
+    /// Measures the offset of the local clock from the server's clock and persists it.
+    ///
+    /// Returns the measured offset. See [`Self::server_clock_offset`].
+    pub async fn update_server_clock_offset(&self) -> anyhow::Result<chrono::Duration> {
+        let offset = self.api_client()?.server_clock_offset().await?;
+        self.set_user_setting(&ServerClockOffsetSetting(offset))
+            .await?;
+        Ok(offset)
+    }
+
+    /// Returns the last persisted offset of the local clock from the server's clock.
+    ///
+    /// Adding the offset to a local time yields the corresponding server time. If the offset was
+    /// never measured, it is zero.
+    pub async fn server_clock_offset(&self) -> chrono::Duration {
+        self.user_setting::<ServerClockOffsetSetting>()
+            .await
+            .map(|setting| setting.0)
+            .unwrap_or_else(chrono::Duration::zero)
+    }
+
+    async fn enabled_features(&self) -> EnumSet<Feature> {
+        self.user_setting::<EnabledFeaturesSetting>()
+            .await
+            .map(|setting| setting.0)
+            .unwrap_or_else(Feature::enabled_by_default)
+    }
+}
+
+/// An opt-in feature of the client
+///
+/// Note: The set of enabled features is persisted as a bit set indexed by variant. New variants
+/// must only be appended.
+#[derive(Debug, Hash, EnumSetType)]
+pub enum Feature {
+    ReadReceipts,
+    TypingIndicators,
+    Presence,
+}
+
+impl Feature {
+    /// Features implemented by this client
+    ///
+    /// Features which need support by the other chat members are only supported if they are
+    /// advertised in this client's capabilities.
+    fn supported() -> EnumSet<Self> {
+        let features = AirFeatures::default_leaf_or_key_package_features();
+        let mut supported = EnumSet::only(Feature::ReadReceipts);
+        if features.typing_indicators {
+            supported |= Feature::TypingIndicators;
+        }
+        if features.presence {
+            supported |= Feature::Presence;
+        }
+        supported
+    }
+
+    /// Features enabled unless the user disabled them
+    fn enabled_by_default() -> EnumSet<Self> {
+        EnumSet::only(Feature::ReadReceipts)
+    }
+}
+
+/// Supported and locally enabled features of a user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureFlags {
+    /// Features implemented by this client
+    pub supported: EnumSet<Feature>,
+    /// Features enabled by the user
+    pub enabled: EnumSet<Feature>,
+}
+
+impl FeatureFlags {
+    /// Returns whether the feature is supported and enabled.
+    pub fn is_active(&self, feature: Feature) -> bool {
+        self.supported.contains(feature) && self.enabled.contains(feature)
+    }
 }
 
 pub trait UserSetting: Send + Sync {
@@ -87,6 +205,131 @@ impl UserSetting for IsDeveloperSetting {
     }
 }
 
+/// Maximum size in bytes of an attachment that can be sent
+pub struct MaxAttachmentSizeSetting(pub u64);
+
+impl UserSetting for MaxAttachmentSizeSetting {
+    const KEY: &'static str = "max_attachment_bytes";
+
+    fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(self.0.to_le_bytes().to_vec())
+    }
+
+    fn decode(bytes: Vec<u8>) -> anyhow::Result<Self> {
+        match bytes.try_into() {
+            Ok(bytes) => Ok(Self(u64::from_le_bytes(bytes))),
+            Err(_) => bail!("invalid max_attachment_bytes bytes"),
+        }
+    }
+}
+
+/// Limits on outgoing connection requests which were not accepted yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionRequestLimitSetting {
+    /// Maximum number of pending connection requests
+    pub max_pending: u32,
+    /// Maximum number of pending connection requests sent within `window`
+    pub max_per_window: u32,
+    pub window: Duration,
+}
+
+impl Default for ConnectionRequestLimitSetting {
+    fn default() -> Self {
+        Self {
+            max_pending: 50,
+            max_per_window: 10,
+            window: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl ConnectionRequestLimitSetting {
+    /// Returns whether sending another connection request exceeds the limit, given the times the
+    /// pending connection requests were sent at.
+    pub(crate) fn is_exceeded_by_next(
+        &self,
+        sent_at: &[DateTime<Utc>],
+        now: DateTime<Utc>,
+    ) -> bool {
+        if sent_at.len() >= self.max_pending as usize {
+            return true;
+        }
+        let sent_within_window = sent_at
+            .iter()
+            .filter(|sent_at| {
+                // Requests sent in the future (clock skew) count as sent within the window
+                !now.signed_duration_since(**sent_at)
+                    .to_std()
+                    .is_ok_and(|elapsed| elapsed >= self.window)
+            })
+            .count();
+        sent_within_window >= self.max_per_window as usize
+    }
+}
+
+impl UserSetting for ConnectionRequestLimitSetting {
+    const KEY: &'static str = "connection_request_limit";
+
+    fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(16);
+        bytes.extend_from_slice(&self.max_pending.to_le_bytes());
+        bytes.extend_from_slice(&self.max_per_window.to_le_bytes());
+        bytes.extend_from_slice(&self.window.as_secs().to_le_bytes());
+        Ok(bytes)
+    }
+
+    fn decode(bytes: Vec<u8>) -> anyhow::Result<Self> {
+        let Ok::<[u8; 16], _>(bytes) = bytes.try_into() else {
+            bail!("invalid connection_request_limit bytes");
+        };
+        let (max_pending, rest) = bytes.split_at(4);
+        let (max_per_window, window) = rest.split_at(4);
+        Ok(Self {
+            max_pending: u32::from_le_bytes(max_pending.try_into()?),
+            max_per_window: u32::from_le_bytes(max_per_window.try_into()?),
+            window: Duration::from_secs(u64::from_le_bytes(window.try_into()?)),
+        })
+    }
+}
+
+/// Offset of the local clock from the server's clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerClockOffsetSetting(pub chrono::Duration);
+
+impl UserSetting for ServerClockOffsetSetting {
+    const KEY: &'static str = "server_clock_offset";
+
+    fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(self.0.num_milliseconds().to_le_bytes().to_vec())
+    }
+
+    fn decode(bytes: Vec<u8>) -> anyhow::Result<Self> {
+        match bytes.try_into() {
+            Ok(bytes) => Ok(Self(chrono::Duration::milliseconds(i64::from_le_bytes(
+                bytes,
+            )))),
+            Err(_) => bail!("invalid server_clock_offset bytes"),
+        }
+    }
+}
+
+struct EnabledFeaturesSetting(EnumSet<Feature>);
+
+impl UserSetting for EnabledFeaturesSetting {
+    const KEY: &'static str = "enabled_features";
+
+    fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(self.0.as_u32().to_le_bytes().to_vec())
+    }
+
+    fn decode(bytes: Vec<u8>) -> anyhow::Result<Self> {
+        match bytes.try_into() {
+            Ok(bytes) => Ok(Self(EnumSet::from_u32_truncated(u32::from_le_bytes(bytes)))),
+            Err(_) => bail!("invalid enabled_features bytes"),
+        }
+    }
+}
+
 pub(crate) struct UserSettingRecord {}
 
 mod persistence {
@@ -120,3 +363,44 @@ mod persistence {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connection_request_limit() {
+        let limit = ConnectionRequestLimitSetting {
+            max_pending: 3,
+            max_per_window: 2,
+            window: Duration::from_secs(60),
+        };
+        let now = Utc::now();
+        let minutes_ago = |minutes| now - chrono::Duration::minutes(minutes);
+
+        assert!(!limit.is_exceeded_by_next(&[], now));
+        assert!(!limit.is_exceeded_by_next(&[minutes_ago(5)], now));
+        // Two requests within the window
+        assert!(limit.is_exceeded_by_next(&[now, now], now));
+        // Requests sent before the window only count towards the pending limit
+        assert!(!limit.is_exceeded_by_next(&[minutes_ago(5), now], now));
+        assert!(limit.is_exceeded_by_next(&[minutes_ago(5), minutes_ago(3), now], now));
+
+        let decoded = ConnectionRequestLimitSetting::decode(limit.encode().unwrap()).unwrap();
+        assert_eq!(decoded, limit);
+    }
+
+    #[test]
+    fn server_clock_offset_roundtrip() {
+        for offset in [
+            chrono::Duration::zero(),
+            chrono::Duration::milliseconds(-1500),
+            chrono::Duration::hours(3),
+        ] {
+            let setting = ServerClockOffsetSetting(offset);
+            let decoded = ServerClockOffsetSetting::decode(setting.encode().unwrap()).unwrap();
+            assert_eq!(decoded, setting);
+        }
+        assert!(ServerClockOffsetSetting::decode(vec![0; 4]).is_err());
+    }
+}
@@ -0,0 +1,233 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! User-defined labels of contacts (e.g. "Family", "Work").
+
+use aircommon::identifiers::UserId;
+use anyhow::ensure;
+
+/// Maximum length of a contact label in characters
+pub(crate) const MAX_CONTACT_LABEL_LENGTH: usize = 64;
+
+/// Labels of a single contact
+pub(crate) struct ContactLabels;
+
+/// Trims the labels, and removes empty and duplicate labels.
+///
+/// Fails if a label is longer than [`MAX_CONTACT_LABEL_LENGTH`].
+pub(crate) fn normalize_labels(labels: Vec<String>) -> anyhow::Result<Vec<String>> {
+    let mut normalized: Vec<String> = Vec::with_capacity(labels.len());
+    for label in labels {
+        let label = label.trim();
+        if label.is_empty() || normalized.iter().any(|l| l == label) {
+            continue;
+        }
+        ensure!(
+            label.chars().count() <= MAX_CONTACT_LABEL_LENGTH,
+            "contact label is longer than {MAX_CONTACT_LABEL_LENGTH} characters"
+        );
+        normalized.push(label.to_owned());
+    }
+    Ok(normalized)
+}
+
+mod persistence {
+    use sqlx::{query, query_as, query_scalar};
+    use tokio_stream::StreamExt;
+
+    use crate::{
+        Contact,
+        contacts::persistence::SqlContact,
+        db::access::{ReadConnection, WriteConnection, WriteDbTransaction},
+    };
+
+    use super::*;
+
+    impl ContactLabels {
+        /// Replaces all labels of the contact with the given [`UserId`].
+        pub(crate) async fn replace(
+            txn: &mut WriteDbTransaction<'_>,
+            user_id: &UserId,
+            labels: &[String],
+        ) -> sqlx::Result<()> {
+            let uuid = user_id.uuid();
+            let domain = user_id.domain();
+            query!(
+                "DELETE FROM contact_label WHERE user_uuid = ? AND user_domain = ?",
+                uuid,
+                domain,
+            )
+            .execute(txn.as_mut())
+            .await?;
+            for label in labels {
+                query!(
+                    "INSERT INTO contact_label (user_uuid, user_domain, label) VALUES (?, ?, ?)",
+                    uuid,
+                    domain,
+                    label,
+                )
+                .execute(txn.as_mut())
+                .await?;
+            }
+            txn.notifier().update(user_id.clone());
+            Ok(())
+        }
+
+        pub(crate) async fn delete(
+            mut connection: impl WriteConnection,
+            user_id: &UserId,
+        ) -> sqlx::Result<()> {
+            let uuid = user_id.uuid();
+            let domain = user_id.domain();
+            let res = query!(
+                "DELETE FROM contact_label WHERE user_uuid = ? AND user_domain = ?",
+                uuid,
+                domain,
+            )
+            .execute(connection.as_mut())
+            .await?;
+            if res.rows_affected() > 0 {
+                connection.notifier().update(user_id.clone());
+            }
+            Ok(())
+        }
+
+        pub(crate) async fn load(
+            mut connection: impl ReadConnection,
+            user_id: &UserId,
+        ) -> sqlx::Result<Vec<String>> {
+            let uuid = user_id.uuid();
+            let domain = user_id.domain();
+            query_scalar!(
+                "SELECT label FROM contact_label
+                WHERE user_uuid = ? AND user_domain = ?
+                ORDER BY label",
+                uuid,
+                domain,
+            )
+            .fetch_all(connection.as_mut())
+            .await
+        }
+
+        /// Loads all distinct labels of existing contacts.
+        pub(crate) async fn load_all_distinct(
+            mut connection: impl ReadConnection,
+        ) -> sqlx::Result<Vec<String>> {
+            query_scalar!(
+                "SELECT DISTINCT l.label FROM contact_label l
+                INNER JOIN contact c
+                    ON c.user_uuid = l.user_uuid AND c.user_domain = l.user_domain
+                ORDER BY l.label"
+            )
+            .fetch_all(connection.as_mut())
+            .await
+        }
+
+        /// Loads all contacts with the given label.
+        pub(crate) async fn load_contacts(
+            mut connection: impl ReadConnection,
+            label: &str,
+        ) -> sqlx::Result<Vec<Contact>> {
+            query_as!(
+                SqlContact,
+                r#"SELECT
+                    c.user_uuid AS "user_uuid: _",
+                    c.user_domain AS "user_domain: _",
+                    c.chat_id AS "chat_id: _",
+                    c.wai_ear_key AS "wai_ear_key: _",
+                    c.friendship_token AS "friendship_token: _"
+                FROM contact c
+                INNER JOIN contact_label l
+                    ON l.user_uuid = c.user_uuid AND l.user_domain = c.user_domain
+                WHERE l.label = ?"#,
+                label,
+            )
+            .fetch(connection.as_mut())
+            .map(|res| res.map(From::from))
+            .collect()
+            .await
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use sqlx::SqlitePool;
+
+        use crate::{
+            ChatId, chats::Chat, chats::persistence::tests::test_chat,
+            contacts::persistence::tests::test_contact, db::access::DbAccess,
+        };
+
+        use super::*;
+
+        #[sqlx::test]
+        async fn store_load_labels(pool: SqlitePool) -> anyhow::Result<()> {
+            let pool = DbAccess::for_tests(pool);
+
+            let chat = test_chat();
+            chat.store(pool.write().await?).await?;
+            let contact = test_contact(chat.id());
+            contact.upsert(pool.write().await?).await?;
+            let other_chat = test_chat();
+            other_chat.store(pool.write().await?).await?;
+            let other_contact = test_contact(other_chat.id());
+            other_contact.upsert(pool.write().await?).await?;
+
+            let labels = vec!["Work".to_owned(), "Family".to_owned()];
+            let mut connection = pool.write().await?;
+            let mut txn = connection.begin().await?;
+            ContactLabels::replace(&mut txn, &contact.user_id, &labels).await?;
+            ContactLabels::replace(&mut txn, &other_contact.user_id, &["Work".to_owned()]).await?;
+            txn.commit().await?;
+            drop(connection);
+
+            let loaded = ContactLabels::load(pool.read().await?, &contact.user_id).await?;
+            assert_eq!(loaded, ["Family", "Work"]);
+            let all = ContactLabels::load_all_distinct(pool.read().await?).await?;
+            assert_eq!(all, ["Family", "Work"]);
+            let mut work: Vec<ChatId> = ContactLabels::load_contacts(pool.read().await?, "Work")
+                .await?
+                .into_iter()
+                .map(|contact| contact.chat_id)
+                .collect();
+            work.sort_by_key(|chat_id| chat_id.uuid());
+            let mut expected = vec![chat.id(), other_chat.id()];
+            expected.sort_by_key(|chat_id| chat_id.uuid());
+            assert_eq!(work, expected);
+
+            // Upserting the contact keeps the labels
+            contact.upsert(pool.write().await?).await?;
+            let loaded = ContactLabels::load(pool.read().await?, &contact.user_id).await?;
+            assert_eq!(loaded, ["Family", "Work"]);
+
+            // Deleting the contact deletes the labels
+            Chat::delete(pool.write().await?, chat.id()).await?;
+            let loaded = ContactLabels::load(pool.read().await?, &contact.user_id).await?;
+            assert!(loaded.is_empty());
+            let family = ContactLabels::load_contacts(pool.read().await?, "Family").await?;
+            assert!(family.is_empty());
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        let labels = normalize_labels(vec![
+            " Work ".to_owned(),
+            "".to_owned(),
+            "Work".to_owned(),
+            "Family".to_owned(),
+        ])
+        .unwrap();
+        assert_eq!(labels, ["Work", "Family"]);
+
+        assert!(normalize_labels(vec!["x".repeat(MAX_CONTACT_LABEL_LENGTH + 1)]).is_err());
+    }
+}
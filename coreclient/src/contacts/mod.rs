@@ -28,7 +28,9 @@ use crate::{
 };
 use anyhow::{Context, Result, bail, ensure};
 
+pub(crate) mod label;
 pub(crate) mod persistence;
+pub(crate) mod presence;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Contact {
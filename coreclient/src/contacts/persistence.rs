@@ -7,8 +7,8 @@ use aircommon::{
     identifiers::{Fqdn, UserId, Username},
     messages::FriendshipToken,
 };
-use chrono::Utc;
-use sqlx::{query, query_as};
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as, query_scalar};
 use tokio_stream::StreamExt;
 use uuid::Uuid;
 
@@ -21,12 +21,12 @@ use crate::{
 
 use super::UsernameContact;
 
-struct SqlContact {
-    user_uuid: Uuid,
-    user_domain: Fqdn,
-    chat_id: ChatId,
-    wai_ear_key: WelcomeAttributionInfoEarKey,
-    friendship_token: FriendshipToken,
+pub(super) struct SqlContact {
+    pub(super) user_uuid: Uuid,
+    pub(super) user_domain: Fqdn,
+    pub(super) chat_id: ChatId,
+    pub(super) wai_ear_key: WelcomeAttributionInfoEarKey,
+    pub(super) friendship_token: FriendshipToken,
 }
 
 impl From<SqlContact> for Contact {
@@ -379,6 +379,24 @@ impl PartialContact {
         }
     }
 
+    /// Loads the times the pending outgoing connection requests were sent at.
+    pub(crate) async fn load_outgoing_created_at(
+        mut connection: impl ReadConnection,
+    ) -> sqlx::Result<Vec<DateTime<Utc>>> {
+        query_scalar!(
+            r#"SELECT p.created_at AS "created_at!: DateTime<Utc>"
+            FROM (
+                SELECT chat_id, created_at FROM username_contact
+                UNION ALL
+                SELECT chat_id, created_at FROM targeted_message_contact
+            ) p
+            INNER JOIN chat c ON c.chat_id = p.chat_id
+            WHERE NOT c.is_incoming"#
+        )
+        .fetch_all(connection.as_mut())
+        .await
+    }
+
     pub(crate) async fn mark_as_complete(
         self,
         txn: &mut WriteDbTransaction<'_>,
@@ -401,7 +419,7 @@ impl PartialContact {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use std::vec;
 
     use aircommon::{
@@ -420,7 +438,7 @@ mod tests {
 
     use super::*;
 
-    fn test_contact(chat_id: ChatId) -> Contact {
+    pub(crate) fn test_contact(chat_id: ChatId) -> Contact {
         let user_id = UserId::random("localhost".parse().unwrap());
         Contact {
             user_id,
@@ -0,0 +1,224 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Presence of contacts.
+//!
+//! A presence update is a MIMI message with the [`PRESENCE_CONTENT_TYPE`] content type. Like a
+//! typing signal, it is sent by the outbound service without being stored as a message, and only
+//! to contacts advertising support for it. Recipients only keep the last known [`ContactPresence`]
+//! per user.
+
+use aircommon::{crypto::secrets::Secret, identifiers::UserId};
+use chrono::{DateTime, Utc};
+use mimi_content::{Disposition, MimiContent, NestedPart};
+
+/// Content type of a presence update.
+pub(crate) const PRESENCE_CONTENT_TYPE: &str = "application/vnd.air.presence";
+
+/// Presence state published by a user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PresenceState {
+    Online,
+    Away,
+    Offline,
+}
+
+impl PresenceState {
+    fn to_byte(self) -> u8 {
+        match self {
+            Self::Online => 0,
+            Self::Away => 1,
+            Self::Offline => 2,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Online),
+            1 => Some(Self::Away),
+            2 => Some(Self::Offline),
+            _ => None,
+        }
+    }
+
+    /// Content of an ephemeral message carrying this presence state.
+    pub(crate) fn to_content(self) -> anyhow::Result<MimiContent> {
+        Ok(MimiContent {
+            salt: Secret::<16>::random()?.secret().to_vec(),
+            nested_part: NestedPart::SinglePart {
+                disposition: Disposition::Unspecified,
+                language: String::new(),
+                content_type: PRESENCE_CONTENT_TYPE.to_owned(),
+                content: vec![self.to_byte()],
+            },
+            ..Default::default()
+        })
+    }
+
+    /// Parses the content of a presence message.
+    pub(crate) fn from_content(content: &[u8]) -> Option<Self> {
+        match content {
+            [byte] => Self::from_byte(*byte),
+            _ => None,
+        }
+    }
+}
+
+/// Last known presence of a user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContactPresence {
+    pub state: PresenceState,
+    /// Time of the last presence update of the user
+    pub last_seen: DateTime<Utc>,
+}
+
+mod persistence {
+    use sqlx::{query, query_as};
+
+    use crate::db::access::{ReadConnection, WriteConnection};
+
+    use super::*;
+
+    struct SqlContactPresence {
+        state: i64,
+        last_seen: DateTime<Utc>,
+    }
+
+    impl ContactPresence {
+        /// Stores the presence of the user, unless a newer presence is already stored.
+        ///
+        /// Returns whether the presence was stored.
+        pub(crate) async fn store_if_newer(
+            &self,
+            mut connection: impl WriteConnection,
+            user_id: &UserId,
+        ) -> sqlx::Result<bool> {
+            let uuid = user_id.uuid();
+            let domain = user_id.domain();
+            let state = i64::from(self.state.to_byte());
+            let res = query!(
+                "INSERT INTO contact_presence (user_uuid, user_domain, state, last_seen)
+                VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT (user_uuid, user_domain) DO UPDATE
+                SET state = excluded.state, last_seen = excluded.last_seen
+                WHERE excluded.last_seen > contact_presence.last_seen",
+                uuid,
+                domain,
+                state,
+                self.last_seen,
+            )
+            .execute(connection.as_mut())
+            .await?;
+            let stored = res.rows_affected() > 0;
+            if stored {
+                connection.notifier().update(user_id.clone());
+            }
+            Ok(stored)
+        }
+
+        pub(crate) async fn load(
+            mut connection: impl ReadConnection,
+            user_id: &UserId,
+        ) -> sqlx::Result<Option<Self>> {
+            let uuid = user_id.uuid();
+            let domain = user_id.domain();
+            let record = query_as!(
+                SqlContactPresence,
+                r#"SELECT
+                    state,
+                    last_seen AS "last_seen: _"
+                FROM contact_presence
+                WHERE user_uuid = ? AND user_domain = ?"#,
+                uuid,
+                domain,
+            )
+            .fetch_optional(connection.as_mut())
+            .await?;
+            Ok(record.and_then(|record| {
+                let state = u8::try_from(record.state)
+                    .ok()
+                    .and_then(PresenceState::from_byte)?;
+                Some(Self {
+                    state,
+                    last_seen: record.last_seen,
+                })
+            }))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use chrono::Duration;
+        use sqlx::SqlitePool;
+
+        use crate::db::access::DbAccess;
+
+        use super::*;
+
+        #[sqlx::test]
+        async fn store_only_newer_presence(pool: SqlitePool) -> anyhow::Result<()> {
+            let pool = DbAccess::for_tests(pool);
+            let user_id = UserId::random("localhost".parse()?);
+            let now = Utc::now();
+
+            let online = ContactPresence {
+                state: PresenceState::Online,
+                last_seen: now,
+            };
+            assert!(online.store_if_newer(pool.write().await?, &user_id).await?);
+
+            // An older update is ignored
+            let outdated = ContactPresence {
+                state: PresenceState::Offline,
+                last_seen: now - Duration::minutes(1),
+            };
+            assert!(
+                !outdated
+                    .store_if_newer(pool.write().await?, &user_id)
+                    .await?
+            );
+            let loaded = ContactPresence::load(pool.read().await?, &user_id).await?;
+            assert_eq!(loaded, Some(online));
+
+            let away = ContactPresence {
+                state: PresenceState::Away,
+                last_seen: now + Duration::minutes(1),
+            };
+            assert!(away.store_if_newer(pool.write().await?, &user_id).await?);
+            let loaded = ContactPresence::load(pool.read().await?, &user_id).await?;
+            assert_eq!(loaded, Some(away));
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presence_content_roundtrip() -> anyhow::Result<()> {
+        for state in [
+            PresenceState::Online,
+            PresenceState::Away,
+            PresenceState::Offline,
+        ] {
+            let content = state.to_content()?;
+            let NestedPart::SinglePart {
+                content_type,
+                content,
+                ..
+            } = content.nested_part
+            else {
+                panic!("unexpected nested part");
+            };
+            assert_eq!(content_type, PRESENCE_CONTENT_TYPE);
+            assert_eq!(PresenceState::from_content(&content), Some(state));
+        }
+        assert_eq!(PresenceState::from_content(&[42]), None);
+        assert_eq!(PresenceState::from_content(&[]), None);
+        Ok(())
+    }
+}
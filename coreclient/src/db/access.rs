@@ -63,9 +63,14 @@ pub(crate) struct WriteDbConnection {
 ///
 /// The transaction must be committed manually via [`WriteDbTransaction::commit`]. On drop, it is
 /// automatically rolled back.
+///
+/// Outside of this crate, the transaction is handed to the handler of
+/// [`CoreUser::dequeue_db_notification_with`].
+///
+/// [`CoreUser::dequeue_db_notification_with`]: crate::clients::CoreUser::dequeue_db_notification_with
 #[derive(Debug)]
 #[must_use = "transactions must be committed or rolled back"]
-pub(crate) struct WriteDbTransaction<'a> {
+pub struct WriteDbTransaction<'a> {
     txn: SqliteTransaction<'a>,
     notifier: &'a mut DbNotifier,
 }
@@ -2,7 +2,11 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::{collections::BTreeMap, mem, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    mem,
+    sync::Arc,
+};
 
 use aircommon::identifiers::UserId;
 use enumset::{EnumSet, EnumSetType};
@@ -70,9 +74,17 @@ impl DbNotifier {
         self
     }
 
+    /// Signal that a user is typing in a chat.
+    ///
+    /// Notification will be sent when the `notify` function is called.
+    pub(crate) fn typing(&mut self, chat_id: ChatId, user_id: UserId) -> &mut Self {
+        self.notification.typing.insert((chat_id, user_id));
+        self
+    }
+
     /// Send collected notifications to the subscribers, if there are any.
     pub(crate) fn notify(mut self) {
-        if !self.notification.ops.is_empty() {
+        if !self.notification.is_empty() {
             let notification = mem::take(&mut self.notification);
             self.tx.notify(Arc::new(notification));
         }
@@ -86,7 +98,7 @@ impl DbNotifier {
 
 impl Drop for DbNotifier {
     fn drop(&mut self) {
-        if !self.notification.ops.is_empty() {
+        if !self.notification.is_empty() {
             // Note: This might be ok. E.g. an error might happen after some notifications were
             // added to the notifier.
             warn!(
@@ -135,6 +147,18 @@ impl DbNotificationsSender {
         })
     }
 
+    /// Same as [`Self::subscribe`], but only yields notifications which contain an entity of any
+    /// of the given kinds.
+    ///
+    /// A yielded notification is not stripped and might also contain other entities.
+    pub(crate) fn subscribe_filtered(
+        &self,
+        kinds: DbEntityKinds,
+    ) -> impl Stream<Item = Arc<DbNotification>> + 'static {
+        self.subscribe()
+            .filter(move |notification| notification.contains_any(kinds))
+    }
+
     /// Returns all pending notifications.
     ///
     /// The pending notifications are the notifications captured starting at the call to this function.
@@ -177,6 +201,10 @@ impl Default for DbNotificationsSender {
 #[cfg_attr(test, derive(PartialEq, Eq))]
 pub struct DbNotification {
     pub ops: BTreeMap<DbEntityId, EnumSet<DbOperation>>,
+    /// Users that are typing in a chat
+    ///
+    /// Typing signals are ephemeral and are not persisted in the notification queue.
+    pub typing: BTreeSet<(ChatId, UserId)>,
 }
 
 impl DbNotification {
@@ -185,11 +213,19 @@ impl DbNotification {
     }
 
     pub fn is_empty(&self) -> bool {
-        self.ops.is_empty()
+        self.ops.is_empty() && self.typing.is_empty()
     }
 
     fn clear(&mut self) {
         self.ops.clear();
+        self.typing.clear();
+    }
+
+    /// Returns whether the notification contains an entity of any of the given kinds.
+    ///
+    /// Typing signals are not entities and are never matched.
+    pub fn contains_any(&self, kinds: DbEntityKinds) -> bool {
+        self.ops.keys().any(|id| kinds.contains(id.kind()))
     }
 }
 
@@ -215,7 +251,7 @@ pub enum DbEntityId {
 }
 
 impl DbEntityId {
-    pub(crate) fn kind(&self) -> DbEntityKind {
+    pub fn kind(&self) -> DbEntityKind {
         match self {
             DbEntityId::User(_) => DbEntityKind::User,
             DbEntityId::Chat(_) => DbEntityKind::Chat,
@@ -225,17 +261,21 @@ impl DbEntityId {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub(crate) enum DbEntityKind {
+/// Kind of an entity stored in the database.
+#[derive(Debug, PartialOrd, Ord, Hash, EnumSetType)]
+pub enum DbEntityKind {
     User = 0,
     Chat = 1,
     Message = 2,
     Attachment = 3,
 }
 
+/// Set of [`DbEntityKind`]s, e.g. to subscribe to
+pub type DbEntityKinds = EnumSet<DbEntityKind>;
+
 #[derive(Debug, thiserror::Error)]
 #[error("Invalid DB entity kind: {0}")]
-pub(crate) struct InvalidDbEntityKind(i64);
+pub struct InvalidDbEntityKind(i64);
 
 impl TryFrom<i64> for DbEntityKind {
     type Error = InvalidDbEntityKind;
@@ -289,20 +329,73 @@ mod tests {
 
         tx.notify(DbNotification {
             ops: ops_1.into_iter().collect(),
+            ..Default::default()
         });
 
         let mut iter = tx.subscribe_iter();
 
-        tx.notify(DbNotification { ops: ops_2.clone() });
+        tx.notify(DbNotification {
+            ops: ops_2.clone(),
+            ..Default::default()
+        });
 
         // first notification is not observed, because it was sent before the subscription
         assert_eq!(iter.next().unwrap().ops, ops_2);
         assert_eq!(iter.next(), None);
 
-        tx.notify(DbNotification { ops: ops_3.clone() });
+        tx.notify(DbNotification {
+            ops: ops_3.clone(),
+            ..Default::default()
+        });
         assert_eq!(iter.next().unwrap().ops, ops_3);
-        tx.notify(DbNotification { ops: ops_4.clone() });
+        tx.notify(DbNotification {
+            ops: ops_4.clone(),
+            ..Default::default()
+        });
         assert_eq!(iter.next().unwrap().ops, ops_4);
         assert_eq!(iter.next(), None);
     }
+
+    #[tokio::test]
+    async fn subscribe_filtered() {
+        let tx = DbNotificationsSender::new();
+        let mut stream =
+            std::pin::pin!(tx.subscribe_filtered(DbEntityKind::Message | DbEntityKind::User));
+
+        let chat_id = ChatId::random();
+        let mut notification = DbNotification::default();
+        notification
+            .ops
+            .insert(chat_id.into(), DbOperation::Update.into());
+        tx.notify(notification);
+
+        let message_id = MessageId::random();
+        let mut notification = DbNotification::default();
+        notification
+            .ops
+            .insert(chat_id.into(), DbOperation::Update.into());
+        notification
+            .ops
+            .insert(message_id.into(), DbOperation::Add.into());
+        tx.notify(notification);
+
+        let mut notification = DbNotification::default();
+        notification
+            .typing
+            .insert((chat_id, UserId::random("localhost".parse().unwrap())));
+        tx.notify(notification);
+
+        let user_id = UserId::random("localhost".parse().unwrap());
+        let mut notification = DbNotification::default();
+        notification
+            .ops
+            .insert(user_id.clone().into(), DbOperation::Update.into());
+        tx.notify(notification);
+
+        // The chat-only and typing-only notifications are dropped
+        let received = stream.next().await.unwrap();
+        assert!(received.ops.contains_key(&DbEntityId::Message(message_id)));
+        let received = stream.next().await.unwrap();
+        assert!(received.ops.contains_key(&DbEntityId::User(user_id)));
+    }
 }
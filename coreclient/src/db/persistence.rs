@@ -12,7 +12,10 @@ use tokio_stream::StreamExt;
 use tracing::error;
 use uuid::Uuid;
 
-use crate::{AttachmentId, ChatId, MessageId, db::access::WriteConnection};
+use crate::{
+    AttachmentId, ChatId, MessageId,
+    db::access::{WriteConnection, WriteDbTransaction},
+};
 
 use super::notification::{DbEntityId, DbEntityKind, DbNotification, DbOperation};
 
@@ -113,7 +116,14 @@ impl SqlDbNotification {
 }
 
 impl DbNotification {
+    /// Persists the notification in the notification queue.
+    ///
+    /// Notifications about an entity which is already queued are merged into the queued one, such
+    /// that the queue holds at most one entry per entity.
     pub(crate) async fn enqueue(&self, mut connection: impl WriteConnection) -> sqlx::Result<()> {
+        if self.ops.is_empty() {
+            return Ok(());
+        }
         let mut transaction = connection.begin().await?;
         for (entity_id, operation) in &self.ops {
             let kind = entity_id.kind();
@@ -168,16 +178,44 @@ impl DbNotification {
                 }
             }
         }
-        Ok(DbNotification { ops })
+        Ok(DbNotification {
+            ops,
+            ..Default::default()
+        })
+    }
+
+    /// Dequeues the persisted notifications and handles them in the same transaction.
+    ///
+    /// The notifications are only removed from the queue when `handle` succeeds and the
+    /// transaction commits. If `handle` fails or the process is killed before the commit, they are
+    /// dequeued again the next time.
+    pub(crate) async fn dequeue_with<T, E>(
+        mut connection: impl WriteConnection,
+        handle: impl AsyncFnOnce(&mut WriteDbTransaction<'_>, DbNotification) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        T: Send,
+        E: From<sqlx::Error>,
+    {
+        connection
+            .with_transaction(async |txn| {
+                let notification = Self::dequeue(&mut *txn).await?;
+                handle(txn, notification).await
+            })
+            .await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use sqlx::SqlitePool;
+    use sqlx::{SqlitePool, query_scalar};
     use uuid::Uuid;
 
-    use crate::{ChatId, MessageId, db::access::DbAccess};
+    use crate::{
+        ChatId, MessageId,
+        chats::{messages::persistence::tests::test_chat_message, persistence::tests::test_chat},
+        db::access::DbAccess,
+    };
 
     use super::*;
 
@@ -213,6 +251,45 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn dequeue_is_not_lost_without_commit(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut notification = DbNotification::default();
+        notification
+            .ops
+            .insert(DbEntityId::Chat(ChatId::random()), DbOperation::Add.into());
+        notification.enqueue(pool.write().await?).await?;
+
+        // Crash between dequeue and commit
+        {
+            let mut connection = pool.write().await?;
+            let mut txn = connection.begin().await?;
+            let dequeued = DbNotification::dequeue(&mut txn).await?;
+            assert_eq!(dequeued, notification);
+            drop(txn); // rolled back
+        }
+
+        // Failing handler
+        let result: anyhow::Result<()> =
+            DbNotification::dequeue_with(pool.write().await?, async |_, dequeued| {
+                assert_eq!(dequeued, notification);
+                anyhow::bail!("handler failed")
+            })
+            .await;
+        assert!(result.is_err());
+
+        let handled = DbNotification::dequeue_with(pool.write().await?, async |_, dequeued| {
+            Ok::<_, sqlx::Error>(dequeued)
+        })
+        .await?;
+        assert_eq!(handled, notification);
+
+        let dequeued = DbNotification::dequeue(pool.write().await?).await?;
+        assert!(dequeued.is_empty());
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn queue_notification_with_conflict(pool: SqlitePool) -> anyhow::Result<()> {
         let pool = DbAccess::for_tests(pool);
@@ -243,9 +320,56 @@ mod tests {
                 DbOperation::Add | DbOperation::Update | DbOperation::Remove,
             )]
             .into(),
+            ..Default::default()
         };
         assert_eq!(dequeued_notification, expected);
 
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn queue_notifications_coalesced_per_entity(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let chat = test_chat();
+        chat.store(pool.write().await?).await?;
+
+        let mut message_ids = Vec::new();
+        for _ in 0..20 {
+            let message = test_chat_message(chat.id());
+            message.store(pool.write().await?).await?;
+            message_ids.push(message.id());
+
+            let mut notification = DbNotification::default();
+            notification
+                .ops
+                .insert(DbEntityId::Message(message.id()), DbOperation::Add.into());
+            notification
+                .ops
+                .insert(DbEntityId::Chat(chat.id()), DbOperation::Update.into());
+            notification.enqueue(pool.write().await?).await?;
+        }
+
+        let num_queued: i64 = query_scalar!("SELECT COUNT(*) FROM store_notification")
+            .fetch_one(pool.read().await?.as_mut())
+            .await?;
+        assert_eq!(num_queued, 21);
+
+        // No notification is lost
+        let dequeued_notification = DbNotification::dequeue(pool.write().await?).await?;
+        assert_eq!(dequeued_notification.ops.len(), 21);
+        assert_eq!(
+            dequeued_notification.ops.get(&DbEntityId::Chat(chat.id())),
+            Some(&DbOperation::Update.into())
+        );
+        for message_id in message_ids {
+            assert_eq!(
+                dequeued_notification
+                    .ops
+                    .get(&DbEntityId::Message(message_id)),
+                Some(&DbOperation::Add.into())
+            );
+        }
+
+        Ok(())
+    }
 }
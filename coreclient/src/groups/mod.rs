@@ -47,8 +47,8 @@ use aircommon::{
     messages::{
         client_as::ConnectionOfferHash,
         client_ds::{
-            AadMessage, AadPayload, ApqWelcomeBundle, DsJoinerInformation, GroupOperationParamsAad,
-            WelcomeBundle,
+            AadMessage, AadPayload, ApqWelcomeBundle, ChangeRoleParamsAad, DsJoinerInformation,
+            GroupOperationParamsAad, WelcomeBundle,
         },
         client_ds_out::{
             AddUsersInfoOut, ApqGroupOperationParamsOut, CollisionTag, CreateGroupParamsOut,
@@ -70,8 +70,9 @@ use aircommon::{
     time::TimeStamp,
     utils::removed_client,
 };
-use airprotos::client::component::{
-    AIR_COMPONENT_ID, AirComponent, AirFeatures, SUPPORTED_COMPONENTS,
+use airprotos::client::{
+    component::{AIR_COMPONENT_ID, AirComponent, AirFeatures, SUPPORTED_COMPONENTS},
+    group::GroupData,
 };
 use anyhow::{Context, Result, anyhow, bail, ensure};
 use hkdf::Hkdf;
@@ -87,7 +88,7 @@ use tracing::{Level, debug, enabled, error, warn};
 
 use crate::{
     ChatId, SystemMessage,
-    chats::messages::TimestampedMessage,
+    chats::{GroupDataExt, messages::TimestampedMessage},
     clients::{
         api_clients::ApiClients,
         block_contact::{BlockedContact, BlockedContactError},
@@ -110,7 +111,7 @@ use openmls::{
     },
     key_packages::KeyPackageBundle,
     prelude::{
-        AppDataDictionaryExtension, BasicCredentialError, Credential, CredentialType,
+        AppDataDictionaryExtension, BasicCredentialError, Ciphersuite, Credential, CredentialType,
         CredentialWithKey, Extension, Extensions, GroupId, LeafNode, LeafNodeIndex,
         LeafNodeParameters, MlsGroup, MlsMessageBodyIn, MlsMessageIn, MlsMessageOut,
         OpenMlsProvider, PURE_PLAINTEXT_WIRE_FORMAT_POLICY, PreSharedKeyProposal, Proposal,
@@ -400,6 +401,16 @@ impl Group {
             .collect()
     }
 
+    /// Returns the users currently in the room together with their role according to
+    /// `room_state`.
+    pub(crate) fn participants_with_roles(&self) -> Result<Vec<(UserId, RoleIndex)>> {
+        self.room_state
+            .users()
+            .iter()
+            .map(|(bytes, role)| Ok((UserId::tls_deserialize_exact_bytes(bytes)?, *role)))
+            .collect()
+    }
+
     /// Errors if this group (or its PQ counterpart, for APQ groups) has a
     /// pending commit. Used by clean loaders to refuse to hand out a
     /// `Group` whose MLS state has an in-flight commit, since further
@@ -454,13 +465,14 @@ impl Group {
         })
     }
 
-    /// Create a group.
+    /// Create a group with the given ciphersuite.
     pub(super) fn create_group(
         mut connection: impl WriteConnection,
         signer: &ClientSigningKey,
         identity_link_wrapper_key: IdentityLinkWrapperKey,
         group_id: GroupId,
         group_data_bytes: GroupDataBytes,
+        ciphersuite: Ciphersuite,
     ) -> Result<(Self, PartialCreateGroupParams)> {
         let provider = AirOpenMlsProvider::new(connection.as_mut());
         let group_state_ear_key = GroupStateEarKey::random()?;
@@ -482,6 +494,7 @@ impl Group {
 
         let mls_group = MlsGroup::builder()
             .with_group_id(group_id.clone())
+            .ciphersuite(ciphersuite)
             .with_capabilities(default_leaf_node_capabilities())
             .with_group_context_extensions(gc_extensions)
             .with_leaf_node_extensions(default_leaf_node_extensions::<AirComponent>())?
@@ -1971,10 +1984,68 @@ impl Group {
         new_group_data: Option<GroupDataBytes>,
     ) -> Result<GroupOperationParamsOut> {
         // We don't expect there to be a welcome.
-        let aad = AadMessage::from(AadPayload::GroupOperation(GroupOperationParamsAad {
+        let aad_payload = AadPayload::GroupOperation(GroupOperationParamsAad {
             new_encrypted_user_profile_keys: Vec::new(),
-        }))
-        .tls_serialize_detached()?;
+        });
+        self.stage_self_update(txn, signer, aad_payload, new_group_data)
+    }
+
+    /// Stages a self-update commit changing the role of `target` to `role`.
+    ///
+    /// The role change is carried in the AAD of the commit for the DS and in the group data of the
+    /// group context, such that all members agree on it. It is applied to the room state when the
+    /// commit is merged.
+    pub(super) fn stage_change_role(
+        &mut self,
+        txn: &mut WriteDbTransaction<'_>,
+        signer: &ClientSigningKey,
+        role_change: ChangeRoleParamsAad,
+    ) -> Result<GroupOperationParamsOut> {
+        let mut group_data = match self.group_data() {
+            Some(bytes) => GroupData::decode(&bytes)?,
+            None => GroupData::empty(),
+        };
+        group_data.role_change = Some(role_change.clone());
+        self.stage_self_update(
+            txn,
+            signer,
+            AadPayload::ChangeRole(role_change),
+            Some(group_data.encode()?),
+        )
+    }
+
+    /// Returns the role change carried in the group context by the staged commit, if any.
+    ///
+    /// Fails if the commit changes the group data other than by the role change.
+    pub(crate) fn staged_role_change(
+        &self,
+        staged_commit: &StagedCommit,
+    ) -> Result<Option<ChangeRoleParamsAad>> {
+        let Some(bytes) = GroupDataBytes::from_staged_commit(staged_commit) else {
+            return Ok(None);
+        };
+        let mut group_data = GroupData::decode(&bytes)?;
+        let role_change = group_data.role_change.take();
+        let mut current_group_data = match self.group_data() {
+            Some(bytes) => GroupData::decode(&bytes)?,
+            None => GroupData::empty(),
+        };
+        current_group_data.role_change = None;
+        ensure!(
+            group_data == current_group_data,
+            "Role change must not change the group data"
+        );
+        Ok(role_change)
+    }
+
+    fn stage_self_update(
+        &mut self,
+        txn: &mut WriteDbTransaction<'_>,
+        signer: &ClientSigningKey,
+        aad_payload: AadPayload,
+        new_group_data: Option<GroupDataBytes>,
+    ) -> Result<GroupOperationParamsOut> {
+        let aad = AadMessage::from(aad_payload).tls_serialize_detached()?;
 
         let extensions = new_group_data
             .map(|gd| -> Result<_> {
@@ -2351,6 +2422,26 @@ impl Group {
         Ok(result?)
     }
 
+    /// Applies a role change committed by `sender` to the room state.
+    ///
+    /// Returns the system message describing the change.
+    pub(crate) fn apply_role_change(
+        &mut self,
+        sender: &UserId,
+        ChangeRoleParamsAad { target, role }: ChangeRoleParamsAad,
+        ds_timestamp: TimeStamp,
+    ) -> Result<TimestampedMessage> {
+        self.room_state_change_role(sender, &target, role)?;
+        Ok(TimestampedMessage::system_message(
+            SystemMessage::ChangeRole {
+                sender: sender.clone(),
+                target,
+                role,
+            },
+            ds_timestamp,
+        ))
+    }
+
     pub(crate) fn group_data(&self) -> Option<GroupDataBytes> {
         self.mls_group().extensions().iter().find_map(|e| match e {
             Extension::Unknown(GROUP_DATA_EXTENSION_TYPE, extension_bytes) => {
@@ -2671,6 +2762,7 @@ mod handle_group_not_found_tests {
             IdentityLinkWrapperKey::random()?,
             group_id.clone(),
             GroupDataBytes::from(b"test-group-data".to_vec()),
+            aircommon::mls_group_config::DEFAULT_CIPHERSUITE,
         )?;
         group.store(&mut connection).await?;
 
@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::ops::Deref;
+use std::{collections::HashSet, ops::Deref};
 
 use aircommon::{
     codec::{BlobDecoded, BlobEncoded, PersistenceCodec},
@@ -11,13 +11,13 @@ use aircommon::{
     identifiers::UserId,
     time::TimeStamp,
 };
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use mimi_room_policy::{RoomState, VerifiedRoomState};
 use openmls::group::{GroupId, MlsGroup, MlsGroupState};
 use openmls::prelude::{LeafNodeIndex, StagedCommit};
 use openmls_traits::{OpenMlsProvider, storage::StorageProvider};
 use sqlx::{SqliteConnection, query, query_as, query_scalar};
-use tls_codec::Serialize as _;
+use tls_codec::{DeserializeBytes as _, Serialize as _};
 use tracing::error;
 
 use crate::{
@@ -192,6 +192,19 @@ impl GroupStorageWitness for VerifiedGroup {
 }
 
 impl Group {
+    /// Returns the users in the room according to the persisted `room_state`, without loading
+    /// the MLS group.
+    pub(crate) fn participants_from_room_state(room_state: &[u8]) -> Result<HashSet<UserId>> {
+        let room_state = PersistenceCodec::from_slice::<RoomState>(room_state)?;
+        let room_state = VerifiedRoomState::verify(room_state)
+            .map_err(|_| anyhow!("Failed to verify room state"))?;
+        room_state
+            .users()
+            .keys()
+            .map(|bytes| Ok(UserId::tls_deserialize_exact_bytes(bytes)?))
+            .collect()
+    }
+
     pub(crate) async fn store(&self, mut connection: impl WriteConnection) -> sqlx::Result<()> {
         let group_id = GroupIdRefWrapper::from(self.group_id());
         let room_state = BlobEncoded(&self.room_state);
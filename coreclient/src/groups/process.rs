@@ -12,7 +12,8 @@ use aircommon::{
     crypto::{aead::keys::EncryptedUserProfileKey, hash::Hash, indexed_aead::keys::UserProfileKey},
     identifiers::UserId,
     messages::client_ds::{
-        AadMessage, AadPayload, GroupOperationParamsAad, JoinConnectionGroupParamsAad,
+        AadMessage, AadPayload, ChangeRoleParamsAad, GroupOperationParamsAad,
+        JoinConnectionGroupParamsAad,
     },
     utils::removed_client,
 };
@@ -126,9 +127,14 @@ impl Group {
         pq_processed_message: Option<&ProcessedMessage>,
     ) -> Result<ProcessMessageResult> {
         let post_process_state = match processed_message.content() {
-            // For now, we only care about commits.
             ProcessedMessageContent::ExternalJoinProposalMessage(_) => {
-                bail!("Unsupported message type")
+                // The joiner is not a member of the group, so there is no
+                // sender leaf to check. The proposal is inspected by the caller.
+                return Ok(ProcessMessageResult::Processed(ProcessMessageProcessed {
+                    processed_message,
+                    we_were_removed: false,
+                    profile_infos: Vec::new(),
+                }));
             }
             ProcessedMessageContent::ApplicationMessage(_) => {
                 debug!("process application message");
@@ -333,6 +339,48 @@ impl Group {
                 we_were_removed: true,
                 encrypted_profile_infos: Vec::new(),
             },
+            // The role change is applied to the room state when the commit is merged.
+            AadPayload::ChangeRole(role_change) => {
+                let staged_commit = expect_staged_commit(processed_message)?;
+                ensure!(
+                    staged_commit.add_proposals().next().is_none()
+                        && staged_commit.remove_proposals().next().is_none()
+                        && !matches!(role_change.role, RoleIndex::Outsider),
+                    "Role change must not add or remove members"
+                );
+                // The role change must be part of the group context, such that all members
+                // agree on it.
+                ensure!(
+                    self.staged_role_change(staged_commit)?.as_ref() == Some(&role_change),
+                    "Role change is not part of the group context"
+                );
+                let ChangeRoleParamsAad { target, role } = role_change;
+                self.verify_role_change(sender_credential.user_id(), &target, role)?;
+                PostProcessAadResult {
+                    we_were_removed: false,
+                    encrypted_profile_infos: Vec::new(),
+                }
+            }
+            // Extension payload of a newer client. An ignorable payload only carries optional
+            // information, which is safe to skip as long as no members are added or removed.
+            AadPayload::Unknown(payload) => {
+                ensure!(
+                    payload.is_ignorable(),
+                    "Unsupported AAD payload with tag {}",
+                    payload.tag()
+                );
+                let staged_commit = expect_staged_commit(processed_message)?;
+                ensure!(
+                    staged_commit.add_proposals().next().is_none()
+                        && staged_commit.remove_proposals().next().is_none(),
+                    "Commit with an unknown AAD payload must not add or remove members"
+                );
+                warn!(tag = payload.tag(), "Ignoring unknown AAD payload");
+                PostProcessAadResult {
+                    we_were_removed: false,
+                    encrypted_profile_infos: Vec::new(),
+                }
+            }
         };
 
         Ok(result)
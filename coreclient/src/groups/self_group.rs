@@ -105,6 +105,7 @@ impl CoreUser {
             legacy_picture: None,
             encrypted_title: Some(encrypted_title),
             external_group_profile: None,
+            role_change: None,
         }
         .encode()?;
 
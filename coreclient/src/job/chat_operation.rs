@@ -11,11 +11,13 @@ use airprotos::{
     delivery_service::v1::StorageObjectType,
 };
 use anyhow::{Context, anyhow, bail};
+use mimi_room_policy::RoleIndex;
 use openmls::treesync::errors::LeafNodeValidationError;
 use thiserror::Error;
 
 use crate::{
     Chat, ChatAttributes, ChatId, ChatMessage, ChatStatus,
+    clients::invite_users::InviteOutcome,
     db::access::WriteConnection,
     groups::Group,
     job::{Job, JobContext, JobContextDb, JobError, pending_chat_operation::PendingChatOperation},
@@ -29,6 +31,7 @@ enum ChatOperationType {
     Delete,
     Update(Option<ChatAttributes>),
     ApqUpdate,
+    ChangeRole { target: UserId, role: RoleIndex },
 }
 
 pub(crate) struct ChatOperation {
@@ -45,6 +48,59 @@ pub(crate) enum ChatOperationError {
     UserProfileKeyEncryptionError(EncryptionError),
 }
 
+/// Adds members to a chat, skipping the members which can't be added.
+pub(crate) struct PartialAddMembers {
+    chat_id: ChatId,
+    users: Vec<UserId>,
+}
+
+impl PartialAddMembers {
+    pub(crate) fn new(chat_id: ChatId, users: Vec<UserId>) -> Self {
+        Self { chat_id, users }
+    }
+}
+
+impl Job for PartialAddMembers {
+    type Output = InviteOutcome;
+
+    type DomainError = ChatOperationError;
+
+    async fn execute_logic(
+        self,
+        context: &mut JobContext<'_, '_>,
+    ) -> Result<InviteOutcome, JobError<Self::DomainError>> {
+        // Same validity checks as when adding all members
+        let mut operation = ChatOperation::add_members(self.chat_id, self.users);
+        operation.check_validity_and_refine(&mut context.db).await?;
+        let ChatOperationType::AddMembers(users) = operation.operation else {
+            unreachable!("validity check does not change the operation type");
+        };
+        if users.is_empty() {
+            return Ok(InviteOutcome::default());
+        }
+
+        let JobContext {
+            api_clients,
+            db,
+            key_store,
+            ..
+        } = context;
+        let (job, mut outcome) = Box::pin(PendingChatOperation::create_partial_add(
+            db.write().await?,
+            api_clients,
+            &key_store.signing_key,
+            self.chat_id,
+            users,
+        ))
+        .await?;
+
+        if let Some(job) = job {
+            outcome.messages = job.execute(context).await?;
+        }
+        Ok(outcome)
+    }
+}
+
 impl Job for ChatOperation {
     type Output = Vec<ChatMessage>;
 
@@ -115,6 +171,13 @@ impl ChatOperation {
         }
     }
 
+    pub(crate) fn change_role(chat_id: ChatId, target: UserId, role: RoleIndex) -> Self {
+        ChatOperation {
+            chat_id,
+            operation: ChatOperationType::ChangeRole { target, role },
+        }
+    }
+
     pub(crate) fn delete_chat(chat_id: ChatId) -> Self {
         ChatOperation {
             chat_id,
@@ -157,6 +220,11 @@ impl ChatOperation {
                 let members: HashSet<_> = group.members().collect();
                 user_ids.retain(|user_id| members.contains(user_id));
             }
+            ChatOperationType::ChangeRole { target, .. } => {
+                if !group.members().any(|member| member == *target) {
+                    bail!("Cannot change the role of a non-member");
+                }
+            }
             // The following operations are always valid as long as the
             // group is active.
             ChatOperationType::Leave
@@ -196,6 +264,9 @@ impl ChatOperation {
                 self.execute_update(context, chat_attributes).await
             }
             ChatOperationType::ApqUpdate => self.execute_apq_self_update(context).await,
+            ChatOperationType::ChangeRole { target, role } => {
+                self.execute_change_role(context, target, role).await
+            }
         }
     }
 
@@ -246,6 +317,32 @@ impl ChatOperation {
         job.execute(context).await
     }
 
+    /// Change the role of a member of the chat
+    async fn execute_change_role(
+        &mut self,
+        context: &mut JobContext<'_, '_>,
+        target: UserId,
+        role: RoleIndex,
+    ) -> Result<Vec<ChatMessage>, JobError<ChatOperationError>> {
+        let JobContext { db, key_store, .. } = context;
+        let job = db
+            .write()
+            .await?
+            .with_transaction(async |txn| {
+                PendingChatOperation::create_change_role(
+                    txn,
+                    &key_store.signing_key,
+                    self.chat_id,
+                    target,
+                    role,
+                )
+                .await
+            })
+            .await?;
+
+        job.execute(context).await
+    }
+
     /// Leave the chat
     async fn execute_leave_chat(
         &mut self,
@@ -308,6 +405,7 @@ impl ChatOperation {
                     },
                     content_length,
                     StorageObjectType::GroupProfile,
+                    false,
                 )
                 .await?;
             let object_id = provision_response.object_id.context("no object id")?;
@@ -342,6 +440,7 @@ impl ChatOperation {
                 external_group_profile: Some(external),
                 legacy_title: Some(group_profile.title),
                 legacy_picture: None,
+                role_change: None,
             };
             (Some(group_data), attributes.picture)
         } else {
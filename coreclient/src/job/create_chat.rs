@@ -6,7 +6,7 @@ use std::convert::Infallible;
 
 use aircommon::{
     crypto::{aead::keys::IdentityLinkWrapperKey, indexed_aead::keys::UserProfileKey},
-    identifiers::QsReference,
+    identifiers::{QsReference, QualifiedGroupId},
     mls_group_config::AppComponent,
     time::TimeStamp,
 };
@@ -18,6 +18,7 @@ use tracing::error;
 use crate::{
     Chat, ChatAttributes, ChatId, ChatMessage, SystemMessage,
     chats::GroupDataExt,
+    clients::own_client_info::OwnClientInfo,
     db::access::WriteConnection,
     groups::Group,
     job::{Job, JobContext, JobError},
@@ -28,6 +29,8 @@ pub(crate) struct CreateChat {
     pub chat_attributes: ChatAttributes,
     pub client_reference: QsReference,
     pub is_apq: bool,
+    /// Fixed group id to request from the DS instead of a random one
+    pub group_id: Option<QualifiedGroupId>,
 }
 
 type DomainError = Infallible;
@@ -55,9 +58,17 @@ impl CreateChat {
             chat_attributes,
             client_reference,
             is_apq,
+            group_id: None,
         }
     }
 
+    /// Requests the given group id from the DS instead of a random one.
+    #[cfg(feature = "test_utils")]
+    pub(crate) fn with_group_id(mut self, group_id: QualifiedGroupId) -> Self {
+        self.group_id = Some(group_id);
+        self
+    }
+
     async fn execute_internal(
         self,
         context: &mut JobContext<'_, '_>,
@@ -66,6 +77,7 @@ impl CreateChat {
             chat_attributes,
             client_reference,
             is_apq,
+            group_id: requested_group_id,
         } = self;
 
         let JobContext {
@@ -98,9 +110,19 @@ impl CreateChat {
         // If we can't get a new group ID, we can't create the chat. Getting a
         // new group ID is repeatable.
         let api_client = api_clients.default_client()?;
-        let (group_id, pq_group_id, group_profile_provisioning) = api_client
-            .ds_request_group_id(Some(group_profile_bytes.len()), is_apq)
-            .await?;
+        let group_profile_size = Some(group_profile_bytes.len());
+        let (group_id, pq_group_id, group_profile_provisioning) = match &requested_group_id {
+            Some(requested_group_id) => {
+                api_client
+                    .ds_request_specific_group_id(requested_group_id, group_profile_size, is_apq)
+                    .await?
+            }
+            None => {
+                api_client
+                    .ds_request_group_id(group_profile_size, is_apq)
+                    .await?
+            }
+        };
 
         let external_group_profile = if let Some(provisioning) = group_profile_provisioning
             && let Some(object_id) = provisioning.object_id
@@ -129,6 +151,7 @@ impl CreateChat {
             external_group_profile,
             legacy_title: Some(chat_attributes.title.clone()),
             legacy_picture: None,
+            role_change: None,
         }
         .encode()?;
 
@@ -153,12 +176,14 @@ impl CreateChat {
                         AirComponent::default_for_leaf_or_key_package(),
                     )?
                 } else {
+                    let ciphersuite = OwnClientInfo::load_ciphersuite(&mut *txn).await?;
                     Group::create_group(
                         &mut *txn,
                         &key_store.signing_key,
                         identity_link_wrapper_key,
                         group_id,
                         group_data_bytes,
+                        ciphersuite,
                     )?
                 };
 
@@ -7,17 +7,20 @@ use aircommon::{
     credentials::{ClientCredential, keys::ClientSigningKey},
     crypto::indexed_aead::keys::UserProfileKey,
     identifiers::{QualifiedGroupId, UserId},
-    messages::client_ds_out::{
-        ApqGroupOperationParamsOut, DeleteGroupParamsOut, GroupOperationParamsOut,
-        SelfRemoveParamsOut,
+    messages::{
+        client_ds::ChangeRoleParamsAad,
+        client_ds_out::{
+            ApqGroupOperationParamsOut, DeleteGroupParamsOut, GroupOperationParamsOut,
+            SelfRemoveParamsOut,
+        },
     },
     time::TimeStamp,
 };
 use airprotos::client::group::GroupData;
-use anyhow::{Context as _, anyhow, bail};
+use anyhow::{Context as _, anyhow, bail, ensure};
 use apqmls::commit_builder::ApqCommitMessageBundle;
 use chrono::{DateTime, Duration, Utc};
-use mimi_room_policy::RoleIndex;
+use mimi_room_policy::{RoleIndex, VerifiedRoomState};
 use openmls::group::GroupId;
 use serde::{Deserialize, Serialize};
 use sqlx::{query, query_as, query_scalar};
@@ -26,7 +29,13 @@ use tracing::{debug, error, info};
 use crate::{
     Chat, ChatAttributes, ChatId, ChatMessage, ChatStatus, Contact, SystemMessage,
     chats::{GroupDataExt, messages::TimestampedMessage},
-    clients::{CoreUser, api_clients::ApiClients, update_key::update_chat_attributes},
+    clients::{
+        CoreUser,
+        api_clients::ApiClients,
+        invite_users::{InviteFailure, InviteOutcome},
+        pending_operations::{PendingOperationKind, PendingOperationSummary},
+        update_key::update_chat_attributes,
+    },
     db::access::{WriteConnection, WriteDbTransaction},
     groups::{
         Group, GroupDataBytes, PreparedInvitee, VerifiedGroup,
@@ -46,6 +55,35 @@ const RETRY_INTERVAL: Duration = Duration::seconds(5);
 #[cfg(any(test, feature = "test_utils"))]
 const RETRY_INTERVAL: Duration = Duration::seconds(1);
 
+#[cfg(not(any(test, feature = "test_utils")))]
+const MAX_RETRY_INTERVAL: Duration = Duration::minutes(5);
+#[cfg(any(test, feature = "test_utils"))]
+const MAX_RETRY_INTERVAL: Duration = Duration::seconds(10);
+
+/// Factor by which the retry interval grows with each failed attempt
+const RETRY_BACKOFF_FACTOR: i32 = 3;
+
+/// Maximum jitter added to the retry interval, relative to the interval
+const RETRY_JITTER: f64 = 0.2;
+
+/// Maximum number of attempts of an operation failing with network errors
+const MAX_RETRIES: u32 = 5;
+
+/// Returns the retry interval after `number_of_attempts` failed attempts, without jitter.
+///
+/// The interval starts at [`RETRY_INTERVAL`] and grows by [`RETRY_BACKOFF_FACTOR`] with each
+/// further attempt, up to [`MAX_RETRY_INTERVAL`].
+fn retry_backoff(number_of_attempts: u32) -> Duration {
+    let mut interval = RETRY_INTERVAL;
+    for _ in 1..number_of_attempts {
+        if interval >= MAX_RETRY_INTERVAL {
+            break;
+        }
+        interval = (interval * RETRY_BACKOFF_FACTOR).min(MAX_RETRY_INTERVAL);
+    }
+    interval
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub(super) enum OperationType {
     Leave(Box<SelfRemoveParamsOut>),
@@ -61,6 +99,9 @@ pub(super) enum OperationType {
         /// chat picture.
         #[serde(with = "serde_bytes")]
         new_chat_picture: Option<Vec<u8>>,
+        /// Role change carried by the commit (if any)
+        #[serde(default)]
+        role_change: Option<ChangeRoleParamsAad>,
     },
     ApqOther {
         params: Box<ApqGroupOperationParamsOut>,
@@ -87,6 +128,18 @@ impl std::fmt::Display for OperationType {
 }
 
 impl OperationType {
+    fn kind(&self) -> PendingOperationKind {
+        match self {
+            OperationType::Leave(_) => PendingOperationKind::Leave,
+            OperationType::Delete(_) | OperationType::ApqDelete { .. } => {
+                PendingOperationKind::Delete
+            }
+            OperationType::Other { .. } | OperationType::ApqOther { .. } => {
+                PendingOperationKind::Other
+            }
+        }
+    }
+
     fn other(params: GroupOperationParamsOut) -> Self {
         Self::other_with_picture(params, None)
     }
@@ -98,6 +151,7 @@ impl OperationType {
         Self::Other {
             params: Box::new(params),
             new_chat_picture,
+            role_change: None,
         }
     }
 
@@ -115,6 +169,28 @@ impl OperationType {
         }
     }
 
+    /// Attaches the role change carried by the commit of this operation.
+    fn with_role_change(mut self, role_change: ChangeRoleParamsAad) -> Self {
+        if let OperationType::Other {
+            role_change: slot, ..
+        } = &mut self
+        {
+            *slot = Some(role_change);
+        }
+        self
+    }
+
+    /// Returns the role change carried by the commit of this operation, if any.
+    fn role_change(&self) -> Option<&ChangeRoleParamsAad> {
+        match self {
+            OperationType::Other { role_change, .. } => role_change.as_ref(),
+            OperationType::Leave(_)
+            | OperationType::Delete(_)
+            | OperationType::ApqDelete { .. }
+            | OperationType::ApqOther { .. } => None,
+        }
+    }
+
     fn is_commit(&self) -> bool {
         match self {
             OperationType::Leave(_) => false,
@@ -137,6 +213,8 @@ impl OperationType {
 enum PendingChatOperationStatus {
     ReadyToRetry,
     WaitingForQueueResponse,
+    /// The request was sent to the DS and the response is not processed yet
+    InFlight,
 }
 
 /// Represents a pending chat operation to be retried.
@@ -162,10 +240,7 @@ impl Job for PendingChatOperation {
         match self.execute_internal(context).await {
             // Update retry_due at on network errors
             Err(JobError::NetworkError) => {
-                #[cfg(not(any(test, feature = "test_utils")))]
-                let retry_due = context.now + RETRY_INTERVAL;
-                #[cfg(any(test, feature = "test_utils"))]
-                let retry_due = context.now + RETRY_INTERVAL;
+                let retry_due = self.next_retry_due_at(context.now);
                 self.update_retry_due_at(context.db.write().await?, retry_due)
                     .await?;
                 let group_id = self.group.group_id();
@@ -195,12 +270,7 @@ impl Job for PendingChatOperation {
                     .db
                     .write()
                     .await?
-                    .with_transaction(async |txn| -> anyhow::Result<()> {
-                        let group = self.group.group_mut();
-                        group.discard_pending_commit(&mut *txn).await?;
-                        Self::delete(txn, self.group.group_id()).await?;
-                        Ok(())
-                    })
+                    .with_transaction(async |txn| self.discard(txn).await)
                     .await
                     .inspect_err(|error| {
                         error!(%error, "Failed to delete pending chat operation");
@@ -228,10 +298,69 @@ impl PendingChatOperation {
         self.group.group_id()
     }
 
+    /// Discards the staged commit of the operation and deletes the job.
+    async fn discard(&mut self, txn: &mut WriteDbTransaction<'_>) -> anyhow::Result<()> {
+        self.group
+            .group_mut()
+            .discard_pending_commit(&mut *txn)
+            .await?;
+        Self::delete(txn, self.group.group_id()).await?;
+        Ok(())
+    }
+
+    /// Cancels the pending operation of the chat, see [`CoreUser::cancel_pending_operation`].
+    ///
+    /// Operations whose request is in flight or might have been accepted by the DS can't be
+    /// cancelled. If a leave was already applied locally, the chat is made active again and the
+    /// previous room state is restored.
+    pub(crate) async fn cancel(
+        txn: &mut WriteDbTransaction<'_>,
+        chat_id: ChatId,
+    ) -> anyhow::Result<bool> {
+        let Some(mut operation) = Self::load(&mut *txn, &chat_id).await? else {
+            return Ok(false);
+        };
+        match operation.status {
+            PendingChatOperationStatus::ReadyToRetry => {}
+            PendingChatOperationStatus::WaitingForQueueResponse => {
+                bail!(
+                    "Operation might have been accepted by the DS; waiting for the queue response"
+                )
+            }
+            PendingChatOperationStatus::InFlight => {
+                bail!("Operation is being sent to the DS")
+            }
+        }
+        info!(%chat_id, operation = %operation.operation, "Cancelling pending chat operation");
+
+        if operation.is_leave()
+            && let Some(room_state) = operation.load_previous_room_state(&mut *txn).await?
+        {
+            let mut chat = Chat::load(&mut *txn, &chat_id)
+                .await?
+                .with_context(|| format!("Can't find chat with id {chat_id}"))?;
+            chat.set_active(&mut *txn).await?;
+            operation.group.group_mut().room_state = room_state;
+            operation
+                .group
+                .group_mut()
+                .store_update(&mut *txn, None, None)
+                .await?;
+        }
+
+        operation.discard(txn).await?;
+        Ok(true)
+    }
+
     pub(crate) fn is_leave(&self) -> bool {
         matches!(self.operation, OperationType::Leave(_))
     }
 
+    /// Returns the role change carried by the pending commit, if any.
+    pub(crate) fn role_change(&self) -> Option<&ChangeRoleParamsAad> {
+        self.operation.role_change()
+    }
+
     pub async fn execute_internal(
         &mut self,
         context: &mut JobContext<'_, '_>,
@@ -302,6 +431,11 @@ impl PendingChatOperation {
                 Ok(own_encrypted_user_profile_key)
             };
 
+        // The operation can't be cancelled until the response of the DS is processed. If the
+        // response is never processed, the operation is retried after the maximum interval.
+        self.mark_as_in_flight(db.write().await?, *now + MAX_RETRY_INTERVAL)
+            .await?;
+
         let mut new_chat_picture = None;
         // TODO: Can we avoid cloning here?
         let res = match self.operation.clone() {
@@ -323,6 +457,7 @@ impl PendingChatOperation {
             OperationType::Other {
                 params,
                 new_chat_picture: chat_picture,
+                ..
             } => {
                 new_chat_picture = chat_picture;
                 let own_qs_client_reference = key_store.create_own_client_reference(qs_client_id);
@@ -366,6 +501,8 @@ impl PendingChatOperation {
             Ok(ds_timestamp) => ds_timestamp,
             Err(error) => {
                 self.number_of_attempts += 1;
+                self.mark_as_ready_to_retry(context.db.write().await?)
+                    .await?;
                 if !is_leave {
                     let job_error = self.handle_error(context.db.write().await?, error).await?;
                     return Err(job_error);
@@ -419,7 +556,15 @@ impl PendingChatOperation {
                         .merge_pending_commit(&mut *txn, None, ds_timestamp)
                         .await?;
 
-                    if let Some(bytes) = group_data_bytes {
+                    if let Some(role_change) = self.operation.role_change() {
+                        // The group data only carries the role change
+                        let message = self.group.group_mut().apply_role_change(
+                            &own_user_id,
+                            role_change.clone(),
+                            ds_timestamp,
+                        )?;
+                        group_messages.push(message);
+                    } else if let Some(bytes) = group_data_bytes {
                         let group_data = GroupData::decode(&bytes)?;
                         let (chat_title, _external_group_profile) =
                             group_data.into_parts(self.group.identity_link_wrapper_key());
@@ -445,6 +590,9 @@ impl PendingChatOperation {
                     // it has already happened once (indicated by chat being
                     // inactive).
 
+                    // Keep the room state, so that the leave can be reverted if it is cancelled
+                    self.store_previous_room_state(&mut *txn, &self.group.room_state)
+                        .await?;
                     self.group.group_mut().room_state_change_role(
                         &own_user_id,
                         &own_user_id,
@@ -486,7 +634,7 @@ impl PendingChatOperation {
                 } else {
                     // If it's a leave operation that hasn't been confirmed by
                     // the DS, we want to set a due date for retrying
-                    let retry_due = *now + RETRY_INTERVAL;
+                    let retry_due = self.next_retry_due_at(*now);
                     self.update_retry_due_at(txn, retry_due).await?;
                 }
 
@@ -503,7 +651,6 @@ impl PendingChatOperation {
         error: DsRequestError,
     ) -> Result<JobError<ChatOperationError>, JobError<ChatOperationError>> {
         debug!(?error, "DS request failed");
-        const MAX_RETRIES: u32 = 5;
         if error.is_not_found() {
             // The group no longer exists on the DS. There is no point
             // in retrying, the group needs to be torn down instead.
@@ -539,6 +686,17 @@ impl PendingChatOperation {
         }
     }
 
+    /// Returns the time at which the operation is retried next, based on the number of attempts.
+    ///
+    /// A random jitter of up to [`RETRY_JITTER`] of the interval is added, such that retries of
+    /// different operations are spread out.
+    fn next_retry_due_at(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let interval = retry_backoff(self.number_of_attempts);
+        let jitter = rand::random_range(0.0..=RETRY_JITTER);
+        let jitter = Duration::milliseconds((interval.num_milliseconds() as f64 * jitter) as i64);
+        now + interval + jitter
+    }
+
     /// Creates and stores a PendingChatOperation for removing users.
     pub(super) async fn create_remove(
         txn: &mut WriteDbTransaction<'_>,
@@ -578,6 +736,40 @@ impl PendingChatOperation {
         Ok(job)
     }
 
+    /// Creates and stores a PendingChatOperation for changing the role of a member.
+    pub(super) async fn create_change_role(
+        txn: &mut WriteDbTransaction<'_>,
+        signer: &ClientSigningKey,
+        chat_id: ChatId,
+        target: UserId,
+        role: RoleIndex,
+    ) -> anyhow::Result<Self> {
+        // Members are added and removed with the corresponding operations
+        ensure!(
+            !matches!(role, RoleIndex::Outsider),
+            "Can't change the role of a member to outsider"
+        );
+
+        let mut group = Group::load_with_chat_id_clean_verified(&mut *txn, chat_id)
+            .await?
+            .with_context(|| format!("Can't find group with chat id {chat_id}"))?;
+
+        // Room policy check (doesn't apply changes to room state yet)
+        let own_id = signer.credential().user_id();
+        group.verify_role_change(own_id, &target, role)?;
+
+        // Like title updates, role changes are regular commits in APQ groups too: the role change
+        // is carried in the group data of the group context.
+        let role_change = ChangeRoleParamsAad { target, role };
+        let params = group
+            .group_mut()
+            .stage_change_role(&mut *txn, signer, role_change.clone())?;
+        let operation_type = OperationType::other(params).with_role_change(role_change);
+        let job = Self::new(group, operation_type);
+        job.store(txn).await?;
+        Ok(job)
+    }
+
     pub(super) async fn create_leave(
         txn: &mut WriteDbTransaction<'_>,
         signer: &ClientSigningKey,
@@ -703,98 +895,195 @@ impl PendingChatOperation {
         new_members: Vec<UserId>,
     ) -> Result<Self, JobError<ChatOperationError>> {
         // Load local data to prepare add operation
-        let mut group = Group::load_verified_with_chat_id(&mut connection, chat_id)
+        let group = Group::load_verified_with_chat_id(&mut connection, chat_id)
+            .await?
+            .context("Can't find group for chat with id {chat_id:?}")?;
+        let own_id = signer.credential().user_id();
+        let mut outcome = InviteOutcome::default();
+
+        // Fail before fetching any key packages if a member can't be added anyway
+        let candidates =
+            Self::check_invitees(&mut connection, &group, own_id, new_members, &mut outcome)
+                .await?;
+        outcome.ensure_none_failed()?;
+        let invitees = Self::fetch_invitees(
+            &mut connection,
+            api_clients,
+            &group,
+            candidates,
+            &mut outcome,
+        )
+        .await?;
+        outcome.ensure_none_failed()?;
+
+        connection
+            .with_transaction(async |txn| Self::stage_add(txn, group, signer, invitees).await)
+            .await
+    }
+
+    /// Same as [`Self::create_add`], but members which can't be added are skipped instead of
+    /// failing the whole operation.
+    ///
+    /// Returns `None` if none of the members can be added. The returned outcome contains the
+    /// members to be added and the skipped members, but no messages.
+    pub(crate) async fn create_partial_add(
+        mut connection: impl WriteConnection,
+        api_clients: &ApiClients,
+        signer: &ClientSigningKey,
+        chat_id: ChatId,
+        new_members: Vec<UserId>,
+    ) -> Result<(Option<Self>, InviteOutcome), JobError<ChatOperationError>> {
+        let group = Group::load_verified_with_chat_id(&mut connection, chat_id)
             .await?
             .context("Can't find group for chat with id {chat_id:?}")?;
+        let own_id = signer.credential().user_id();
+        let mut outcome = InviteOutcome::default();
 
-        // Bundle the per-invitee data (contact, client credential) before we
-        // fetch the server-side add info, so that the parallel pieces stay
-        // associated with the same user end-to-end.
-        struct InviteeBuildup {
-            contact: Contact,
-            client_credential: ClientCredential,
+        let candidates =
+            Self::check_invitees(&mut connection, &group, own_id, new_members, &mut outcome)
+                .await?;
+        let invitees = Self::fetch_invitees(
+            &mut connection,
+            api_clients,
+            &group,
+            candidates,
+            &mut outcome,
+        )
+        .await?;
+
+        if invitees.is_empty() {
+            return Ok((None, outcome));
         }
-        let mut buildups = Vec::with_capacity(new_members.len());
-        for new_member in &new_members {
-            let contact = Contact::load(&mut connection, new_member)
-                .await?
-                .with_context(|| format!("Can't find contact {new_member:?}"))?;
-            let client_credential =
-                StorableClientCredential::load_by_user_id(&mut connection, new_member)
+
+        let pending_chat_operation = connection
+            .with_transaction(async |txn| Self::stage_add(txn, group, signer, invitees).await)
+            .await?;
+        Ok((Some(pending_chat_operation), outcome))
+    }
+
+    /// Checks locally whether the new members can be added to the group.
+    ///
+    /// Members which can't be added are recorded as skipped in `outcome`. No key packages are
+    /// fetched, so that none are used up for members which can't be added anyway.
+    async fn check_invitees(
+        mut connection: impl WriteConnection,
+        group: &VerifiedGroup,
+        own_id: &UserId,
+        new_members: Vec<UserId>,
+        outcome: &mut InviteOutcome,
+    ) -> anyhow::Result<Vec<InviteeCandidate>> {
+        let mut candidates = Vec::with_capacity(new_members.len());
+        for user_id in new_members {
+            let Some(contact) = Contact::load(&mut connection, &user_id).await? else {
+                outcome.skip(user_id, InviteFailure::NotAContact);
+                continue;
+            };
+            let Some(client_credential) =
+                StorableClientCredential::load_by_user_id(&mut connection, &user_id)
                     .await?
                     .map(ClientCredential::from)
-                    .with_context(|| {
-                        format!("Can't find client credential for contact {new_member:?}")
-                    })?;
-            buildups.push(InviteeBuildup {
+            else {
+                outcome.skip(user_id, InviteFailure::MissingClientCredential);
+                continue;
+            };
+            // Room policy check (doesn't apply changes to room state yet)
+            if let Err(error) = group.verify_role_change(own_id, &user_id, RoleIndex::Regular) {
+                let reason = error.to_string();
+                outcome.skip(user_id, InviteFailure::NotAllowed { reason });
+                continue;
+            }
+            candidates.push(InviteeCandidate {
+                user_id,
                 contact,
                 client_credential,
             });
         }
+        Ok(candidates)
+    }
 
-        // Fetch add infos from the server and produce one PreparedInvitee per
-        // entry so the staging API doesn't need parallel vectors.
-        let mut invitees = Vec::with_capacity(buildups.len());
-        for InviteeBuildup {
+    /// Fetches the add infos of the candidates from the server.
+    ///
+    /// Candidates whose key package can't be fetched are recorded as skipped in `outcome`, the
+    /// others as invited.
+    async fn fetch_invitees(
+        mut connection: impl WriteConnection,
+        api_clients: &ApiClients,
+        group: &VerifiedGroup,
+        candidates: Vec<InviteeCandidate>,
+        outcome: &mut InviteOutcome,
+    ) -> anyhow::Result<Vec<PreparedInvitee>> {
+        let mut invitees = Vec::with_capacity(candidates.len());
+        for InviteeCandidate {
+            user_id,
             contact,
             client_credential,
-        } in buildups
+        } in candidates
         {
-            let wai_key = contact.wai_ear_key().clone();
-            let add_info = contact
+            match contact
                 .fetch_add_infos(&mut connection, api_clients, group.is_apq())
-                .await?;
-            invitees.push(PreparedInvitee {
-                add_info,
-                wai_key,
-                client_credential,
-            });
-        }
-
-        connection
-            .with_transaction(async |txn| {
-                let own_id = signer.credential().user_id();
-
-                // Room policy check (doesn't apply changes to room state yet)
-                for target in &new_members {
-                    group.verify_role_change(own_id, target, RoleIndex::Regular)?;
+                .await
+            {
+                Ok(add_info) => {
+                    invitees.push(PreparedInvitee {
+                        add_info,
+                        wai_key: contact.wai_ear_key().clone(),
+                        client_credential,
+                    });
+                    outcome.invited.push(user_id);
+                }
+                Err(error) => {
+                    let reason = error.to_string();
+                    outcome.skip(user_id, InviteFailure::KeyPackageUnavailable { reason });
                 }
+            }
+        }
+        Ok(invitees)
+    }
 
-                // Adds new member and stages commit
-                let operation_type = if !group.is_apq() {
-                    let params = group
-                        .group_mut()
-                        .stage_invite(&mut *txn, signer, invitees)?
-                        // Check if we got a leaf node validation error which is domain specific and should
-                        // be propagated to the user.
-                        .map_err(|validation| {
-                            JobError::domain(ChatOperationError::from(validation))
-                        })?;
-                    OperationType::other(params)
-                } else {
-                    let params = group
-                        .group_mut()
-                        .stage_apq_invite(&mut *txn, signer, signer, invitees)?
-                        // Check if we got a leaf node validation error which is domain specific and should
-                        // be propagated to the user.
-                        .map_err(|validation| {
-                            JobError::domain(ChatOperationError::from(validation))
-                        })?;
-                    OperationType::apq_other(params)
-                };
+    /// Stages the commit adding the invitees and stores the resulting job.
+    async fn stage_add(
+        txn: &mut WriteDbTransaction<'_>,
+        mut group: VerifiedGroup,
+        signer: &ClientSigningKey,
+        invitees: Vec<PreparedInvitee>,
+    ) -> Result<Self, JobError<ChatOperationError>> {
+        // Adds new member and stages commit
+        let operation_type = if !group.is_apq() {
+            let params = group
+                .group_mut()
+                .stage_invite(&mut *txn, signer, invitees)?
+                // Check if we got a leaf node validation error which is domain specific and should
+                // be propagated to the user.
+                .map_err(|validation| JobError::domain(ChatOperationError::from(validation)))?;
+            OperationType::other(params)
+        } else {
+            let params = group
+                .group_mut()
+                .stage_apq_invite(&mut *txn, signer, signer, invitees)?
+                // Check if we got a leaf node validation error which is domain specific and should
+                // be propagated to the user.
+                .map_err(|validation| JobError::domain(ChatOperationError::from(validation)))?;
+            OperationType::apq_other(params)
+        };
 
-                // Create PendingChatOperation job
-                let pending_chat_operation = PendingChatOperation::new(group, operation_type);
-                pending_chat_operation.store(txn).await?;
+        // Create PendingChatOperation job
+        let pending_chat_operation = PendingChatOperation::new(group, operation_type);
+        pending_chat_operation.store(txn).await?;
 
-                Ok(pending_chat_operation)
-            })
-            .await
+        Ok(pending_chat_operation)
     }
 }
 
+/// New member which passed the local checks, see [`PendingChatOperation::check_invitees`]
+struct InviteeCandidate {
+    user_id: UserId,
+    contact: Contact,
+    client_credential: ClientCredential,
+}
+
 mod persistence {
     use aircommon::codec::{BlobDecoded, BlobEncoded};
+    use mimi_room_policy::RoomState;
     use thiserror::Error;
     use uuid::Uuid;
 
@@ -810,6 +1099,7 @@ mod persistence {
 
     const READY_TO_RETRY: &str = "ready_to_retry";
     const WAITING_FOR_QUEUE_RESPONSE: &str = "waiting_for_queue_response";
+    const IN_FLIGHT: &str = "in_flight";
 
     impl sqlx::Encode<'_, sqlx::Sqlite> for OperationType {
         fn encode_by_ref(
@@ -828,6 +1118,7 @@ mod persistence {
                 PendingChatOperationStatus::WaitingForQueueResponse => {
                     write!(f, "{}", WAITING_FOR_QUEUE_RESPONSE)
                 }
+                PendingChatOperationStatus::InFlight => write!(f, "{}", IN_FLIGHT),
             }
         }
     }
@@ -848,6 +1139,7 @@ mod persistence {
                 WAITING_FOR_QUEUE_RESPONSE => {
                     Ok(PendingChatOperationStatus::WaitingForQueueResponse)
                 }
+                IN_FLIGHT => Ok(PendingChatOperationStatus::InFlight),
                 s => {
                     let e = PendingChatOperationStatusError {
                         actual: s.to_string(),
@@ -959,6 +1251,91 @@ mod persistence {
             Ok(())
         }
 
+        /// Marks the request of the operation as in flight.
+        ///
+        /// The retry due time is moved to `retry_due`, so that the operation is retried if the
+        /// response is never processed, e.g. because the app was terminated during the request.
+        pub(super) async fn mark_as_in_flight(
+            &mut self,
+            mut connection: impl WriteConnection,
+            retry_due: DateTime<Utc>,
+        ) -> sqlx::Result<()> {
+            let group_id = self.group.group_id().as_slice();
+            query!(
+                "UPDATE pending_chat_operation
+                SET request_status = ?, retry_due_at = ?
+                WHERE group_id = ?",
+                PendingChatOperationStatus::InFlight as _,
+                retry_due,
+                group_id
+            )
+            .execute(connection.as_mut())
+            .await?;
+
+            self.status = PendingChatOperationStatus::InFlight;
+            self.retry_due_at = Some(retry_due);
+
+            Ok(())
+        }
+
+        pub(super) async fn mark_as_ready_to_retry(
+            &mut self,
+            mut connection: impl WriteConnection,
+        ) -> sqlx::Result<()> {
+            let group_id = self.group.group_id().as_slice();
+            query!(
+                "UPDATE pending_chat_operation SET request_status = ? WHERE group_id = ?",
+                PendingChatOperationStatus::ReadyToRetry as _,
+                group_id
+            )
+            .execute(connection.as_mut())
+            .await?;
+
+            self.status = PendingChatOperationStatus::ReadyToRetry;
+
+            Ok(())
+        }
+
+        /// Stores the room state from before the leave operation was applied locally.
+        pub(super) async fn store_previous_room_state(
+            &self,
+            mut connection: impl WriteConnection,
+            room_state: &VerifiedRoomState,
+        ) -> sqlx::Result<()> {
+            let group_id = self.group.group_id().as_slice();
+            let room_state = BlobEncoded(room_state);
+            query!(
+                "UPDATE pending_chat_operation SET previous_room_state = ? WHERE group_id = ?",
+                room_state,
+                group_id
+            )
+            .execute(connection.as_mut())
+            .await?;
+
+            Ok(())
+        }
+
+        /// Loads the room state stored with [`Self::store_previous_room_state`], if any.
+        pub(super) async fn load_previous_room_state(
+            &self,
+            mut connection: impl ReadConnection,
+        ) -> anyhow::Result<Option<VerifiedRoomState>> {
+            let group_id = self.group.group_id().as_slice();
+            let room_state = query_scalar!(
+                r#"SELECT previous_room_state AS "previous_room_state: BlobDecoded<RoomState>"
+                FROM pending_chat_operation
+                WHERE group_id = ?"#,
+                group_id
+            )
+            .fetch_optional(connection.as_mut())
+            .await?
+            .flatten();
+            room_state
+                .map(|BlobDecoded(room_state)| VerifiedRoomState::verify(room_state))
+                .transpose()
+                .map_err(|_| anyhow!("Failed to verify room state"))
+        }
+
         pub(crate) async fn load_by_group_id(
             mut connection: impl ReadConnection,
             group_id: &GroupId,
@@ -1020,6 +1397,41 @@ mod persistence {
                 .map(Some)
         }
 
+        pub(crate) async fn load_summaries(
+            mut connection: impl ReadConnection,
+        ) -> sqlx::Result<Vec<PendingOperationSummary>> {
+            let records = query!(
+                r#"SELECT
+                    c.chat_id AS "chat_id: ChatId",
+                    pco.operation_data AS "operation_data: BlobDecoded<OperationType>",
+                    pco.retry_due_at AS "retry_due_at: DateTime<Utc>",
+                    pco.request_status AS "request_status: PendingChatOperationStatus",
+                    pco.number_of_attempts
+                FROM pending_chat_operation pco
+                JOIN chat c ON pco.group_id = c.group_id
+                ORDER BY pco.retry_due_at"#
+            )
+            .fetch_all(connection.as_mut())
+            .await?;
+            Ok(records
+                .into_iter()
+                .map(|record| PendingOperationSummary {
+                    chat_id: record.chat_id,
+                    kind: record.operation_data.0.kind(),
+                    number_of_attempts: record.number_of_attempts as u32,
+                    retry_due_at: record.retry_due_at,
+                    waiting_for_queue_response: matches!(
+                        record.request_status,
+                        PendingChatOperationStatus::WaitingForQueueResponse
+                    ),
+                    in_flight: matches!(
+                        record.request_status,
+                        PendingChatOperationStatus::InFlight
+                    ),
+                })
+                .collect())
+        }
+
         pub(crate) async fn is_pending_for_chat(
             mut connection: impl ReadConnection,
             chat_id: ChatId,
@@ -1047,12 +1459,14 @@ mod persistence {
                 SELECT group_id
                 FROM pending_chat_operation
                 WHERE (locked_by IS NULL OR locked_by != ?1)
-                    AND request_status = ?2
-                    AND retry_due_at <= ?3
+                    AND request_status IN (?2, ?3)
+                    AND retry_due_at <= ?4
                 LIMIT 1
                 "#,
                 task_id,
                 PendingChatOperationStatus::ReadyToRetry as _,
+                // The response to an in-flight request was not processed in time
+                PendingChatOperationStatus::InFlight as _,
                 now
             )
             .fetch_optional(txn.as_mut())
@@ -1220,6 +1634,7 @@ mod tests {
             identity_link_wrapper_key,
             group_id.clone(),
             group_data_bytes,
+            aircommon::mls_group_config::DEFAULT_CIPHERSUITE,
         )?;
         group.store(&mut connection).await?;
         let group = VerifiedGroup::new_for_test(group);
@@ -1282,6 +1697,42 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn next_retry_due_at_backs_off_exponentially() -> anyhow::Result<()> {
+        let (pool, mut group, _chat_id, signing_key) = setup_group_and_chat().await?;
+
+        let leave_params = group
+            .group_mut()
+            .stage_leave_group(pool.write().await?, &signing_key)?;
+        let mut pending =
+            PendingChatOperation::new(group, OperationType::Leave(Box::new(leave_params)));
+
+        let now = Utc::now();
+        let mut previous_backoff = Duration::zero();
+        for number_of_attempts in 1..=MAX_RETRIES {
+            pending.number_of_attempts = number_of_attempts;
+            let backoff = retry_backoff(number_of_attempts);
+            let retry_due = pending.next_retry_due_at(now);
+
+            // The jitter is bounded relative to the interval
+            let max_jitter =
+                Duration::milliseconds((backoff.num_milliseconds() as f64 * RETRY_JITTER) as i64);
+            assert!(now + backoff <= retry_due);
+            assert!(retry_due <= now + backoff + max_jitter);
+
+            // The interval grows until it is capped
+            assert!(backoff > previous_backoff || backoff == MAX_RETRY_INTERVAL);
+            assert!(backoff <= MAX_RETRY_INTERVAL);
+            previous_backoff = backoff;
+        }
+        assert_eq!(retry_backoff(1), RETRY_INTERVAL);
+        assert_eq!(retry_backoff(2), RETRY_INTERVAL * RETRY_BACKOFF_FACTOR);
+        assert_eq!(retry_backoff(MAX_RETRIES), MAX_RETRY_INTERVAL);
+        assert_eq!(retry_backoff(u32::MAX), MAX_RETRY_INTERVAL);
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn mark_as_waiting_for_queue_response_updates_status() -> anyhow::Result<()> {
         let (pool, mut group, _chat_id, signing_key) = setup_group_and_chat().await?;
@@ -1365,4 +1816,96 @@ mod tests {
             })
             .await
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn list_and_cancel_pending_operation() -> anyhow::Result<()> {
+        let (pool, mut group, chat_id, signing_key) = setup_group_and_chat().await?;
+        let mut connection = pool.write().await?;
+
+        let leave_params = group
+            .group_mut()
+            .stage_leave_group(&mut connection, &signing_key)?;
+        let mut pending =
+            PendingChatOperation::new(group, OperationType::Leave(Box::new(leave_params)));
+        pending.store(&mut connection).await?;
+
+        let summaries = PendingChatOperation::load_summaries(&mut connection).await?;
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].chat_id, chat_id);
+        assert_eq!(summaries[0].kind, PendingOperationKind::Leave);
+        assert_eq!(summaries[0].number_of_attempts, 0);
+        assert!(!summaries[0].waiting_for_queue_response);
+        assert!(!summaries[0].in_flight);
+
+        // An operation whose request is in flight can't be cancelled
+        pending
+            .mark_as_in_flight(&mut connection, Utc::now() + Duration::minutes(1))
+            .await?;
+        connection
+            .with_transaction(async |txn| {
+                assert!(PendingChatOperation::cancel(txn, chat_id).await.is_err());
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+        let summaries = PendingChatOperation::load_summaries(&mut connection).await?;
+        assert!(summaries[0].in_flight);
+
+        // An operation waiting for the queue response can't be cancelled
+        pending
+            .mark_as_waiting_for_queue_response(&mut connection)
+            .await?;
+        connection
+            .with_transaction(async |txn| {
+                assert!(PendingChatOperation::cancel(txn, chat_id).await.is_err());
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+        let summaries = PendingChatOperation::load_summaries(&mut connection).await?;
+        assert!(summaries[0].waiting_for_queue_response);
+
+        pending.mark_as_ready_to_retry(&mut connection).await?;
+
+        // Apply the leave locally like the post-processing of the operation does
+        let own_user_id = signing_key.credential().user_id().clone();
+        let participants = pending.group.participants()?;
+        connection
+            .with_transaction(async |txn| {
+                pending
+                    .store_previous_room_state(&mut *txn, &pending.group.room_state)
+                    .await?;
+                let group = pending.group.group_mut();
+                group.room_state_change_role(&own_user_id, &own_user_id, RoleIndex::Outsider)?;
+                group.store_update(&mut *txn, None, None).await?;
+                let mut chat = Chat::load(&mut *txn, &chat_id).await?.unwrap();
+                chat.set_inactive(&mut *txn, Vec::new()).await?;
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        connection
+            .with_transaction(async |txn| {
+                assert!(PendingChatOperation::cancel(txn, chat_id).await?);
+                // Nothing left to cancel
+                assert!(!PendingChatOperation::cancel(txn, chat_id).await?);
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        assert!(
+            PendingChatOperation::load_summaries(&mut connection)
+                .await?
+                .is_empty()
+        );
+        assert!(!Group::pending_commit_failed(&mut connection, pending.group_id()).await?);
+
+        // The leave was reverted
+        let chat = Chat::load(&mut connection, &chat_id).await?.unwrap();
+        assert_eq!(chat.status(), &ChatStatus::Active);
+        let group = Group::load_verified(&mut connection, pending.group_id())
+            .await?
+            .unwrap();
+        assert_eq!(group.participants()?, participants);
+
+        Ok(())
+    }
 }
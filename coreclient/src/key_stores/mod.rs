@@ -16,15 +16,14 @@ use airprotos::client::component::AirComponent;
 use anyhow::Result;
 use apqmls::{authentication::ApqCredentialWithKey, messages::ApqKeyPackage};
 use openmls::prelude::{
-    Credential, CredentialType, CredentialWithKey, Extension, KeyPackage, KeyPackageRef,
-    LastResortExtension, OpenMlsProvider, SignaturePublicKey, UnknownExtension,
+    Ciphersuite, Credential, CredentialType, CredentialWithKey, Extension, KeyPackage,
+    KeyPackageRef, LastResortExtension, OpenMlsProvider, SignaturePublicKey, UnknownExtension,
 };
 use openmls_traits::storage::StorageProvider;
 use tls_codec::Serialize as TlsSerializeTrait;
 
 use crate::{
-    clients::{CIPHERSUITE, api_clients::ApiClients},
-    db::access::WriteConnection,
+    clients::api_clients::ApiClients, db::access::WriteConnection,
     groups::openmls_provider::AirOpenMlsProvider,
 };
 
@@ -85,6 +84,7 @@ impl MemoryUserKeyStore {
         mut connection: impl WriteConnection,
         qs_client_id: &QsClientId,
         last_resort: bool,
+        ciphersuite: Ciphersuite,
     ) -> Result<KeyPackage> {
         let credential_with_key = CredentialWithKey {
             credential: self.signing_key.credential().try_into()?,
@@ -118,7 +118,7 @@ impl MemoryUserKeyStore {
             .leaf_node_capabilities(default_leaf_node_capabilities())
             .leaf_node_extensions(leaf_node_extensions)
             .build(
-                CIPHERSUITE,
+                ciphersuite,
                 &provider,
                 &self.signing_key,
                 credential_with_key,
@@ -21,38 +21,57 @@ mod utils;
 
 pub use crate::{
     chats::{
-        Chat, ChatAttributes, ChatId, ChatMuted, ChatStatus, ChatType, InactiveChat, MessageDraft,
+        Chat, ChatAttributes, ChatId, ChatListFilter, ChatMuted, ChatPreview, ChatStatus, ChatType,
+        InactiveChat, MarkAllAsReadOptions, MessageDraft,
         messages::{
             ChatMessage, ContentMessage, ErrorMessage, EventMessage, InReplyToMessage, Message,
             MessageId, SystemMessage,
         },
-        pending::AcceptContactRequestError,
+        pending::{AcceptContactRequestError, ConnectionRequest},
     },
     clients::{
-        add_contact::AddUsernameContactError,
+        add_contact::{AddUsernameContactError, TooManyPendingRequestsError},
         attachment::{
-            AttachmentContent, AttachmentId, AttachmentStatus, AttachmentUrl,
-            AttachmentUrlParseError, MimiContentExt, ProvisionAttachmentError, UploadTaskError,
+            AttachmentContent, AttachmentHashMismatchError, AttachmentId, AttachmentStatus,
+            AttachmentTooLargeError, AttachmentUrl, AttachmentUrlParseError, MimiContentExt,
+            ProvisionAttachmentError, UploadTaskError,
             progress::{AttachmentProgress, AttachmentProgressEvent},
         },
         block_contact::BlockedContactError,
+        chat_export::ExportFormat,
         debug_info::{TimedTaskDebugInfo, UserDebugInfo},
+        event_loop::{EventLoopConfig, EventLoopOccupancy, EventLoopStoppedError},
+        integrity::IntegrityReport,
         invitation_code::{InvitationCode, RequestInvitationCodeError},
-        invite_users::InviteUsersError,
+        invite_users::{InviteFailure, InviteOutcome, InviteUsersError},
+        pending_operations::{PendingOperationKind, PendingOperationSummary},
         safety_code::SafetyCode,
-        user_settings::{IsDeveloperSetting, ReadReceiptsSetting, UserSetting},
+        user_settings::{
+            ConnectionRequestLimitSetting, Feature, FeatureFlags, IsDeveloperSetting,
+            MaxAttachmentSizeSetting, ReadReceiptsSetting, ServerClockOffsetSetting, UserSetting,
+        },
+    },
+    contacts::{
+        Contact, ContactType, PartialContact, TargetedMessageContact,
+        presence::{ContactPresence, PresenceState},
     },
-    contacts::{Contact, ContactType, PartialContact, TargetedMessageContact},
     groups::debug_info::{
         AppDataDebugInfo, DebugCapabilities, EncryptedGroupTitleDebugInfo,
         ExternalGroupProfileDebugInfo, GroupDataDebugInfo, GroupDebugInfo, PqGroupDebugInfo,
         RequiredDebugCapabilities,
     },
     privacy_pass::{RequestTokensError, TokenId},
-    user_profiles::{Asset, DisplayName, DisplayNameError, UserProfile},
+    user_profiles::{
+        Asset, DisplayName, DisplayNameError, UserProfile,
+        status_text::{MAX_STATUS_TEXT_CHARS, StatusTextError},
+    },
     usernames::UsernameRecord,
     utils::{
-        image::image_is_animated,
+        image::{ProfileImageError, ProfileImageFormat, ProfileImageOptions, image_is_animated},
         persistence::{delete_client_database, delete_databases, open_client_db},
     },
 };
+#[cfg(not(target_os = "android"))]
+pub use utils::db_dump::{
+    ClientDbDump, DumpedSchemaObject, DumpedTable, DumpedValue, dump_client_db, import_client_db,
+};
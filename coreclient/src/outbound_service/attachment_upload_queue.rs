@@ -0,0 +1,262 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::AttachmentId;
+
+/// An encrypted attachment which is uploaded in parts but not yet completely uploaded.
+///
+/// The entry is kept until the upload either succeeds or fails permanently, such that an
+/// interrupted upload can be resumed after the last uploaded part without encrypting and
+/// provisioning the attachment again.
+pub(crate) struct AttachmentUploadQueue {
+    pub(crate) attachment_id: AttachmentId,
+    pub(crate) ciphertext: Vec<u8>,
+    /// Size of all parts except the last one
+    pub(crate) part_size: u64,
+}
+
+/// A part of a queued attachment upload which is already uploaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UploadedPart {
+    /// Number of the part starting at 1
+    pub(crate) part_number: u32,
+    /// ETag returned by the storage provider for the part
+    pub(crate) etag: String,
+}
+
+mod persistence {
+    use aircommon::time::TimeStamp;
+    use anyhow::Context;
+    use sqlx::{query, query_as, query_scalar};
+    use tracing::debug;
+
+    use crate::db::access::{ReadConnection, WriteConnection};
+
+    use super::*;
+
+    struct SqlUploadedPart {
+        part_number: i64,
+        etag: String,
+    }
+
+    impl TryFrom<SqlUploadedPart> for UploadedPart {
+        type Error = anyhow::Error;
+
+        fn try_from(
+            SqlUploadedPart { part_number, etag }: SqlUploadedPart,
+        ) -> anyhow::Result<Self> {
+            Ok(Self {
+                part_number: part_number.try_into().context("invalid part number")?,
+                etag,
+            })
+        }
+    }
+
+    impl AttachmentUploadQueue {
+        pub(crate) async fn enqueue(
+            &self,
+            mut connection: impl WriteConnection,
+        ) -> anyhow::Result<()> {
+            debug!(attachment_id = ?self.attachment_id, "Enqueueing attachment upload");
+            let part_size: i64 = self.part_size.try_into().context("invalid part size")?;
+            let now = TimeStamp::now();
+            query!(
+                "INSERT INTO attachment_upload_queue (
+                    attachment_id,
+                    ciphertext,
+                    part_size,
+                    created_at
+                ) VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT (attachment_id) DO UPDATE SET
+                    ciphertext = excluded.ciphertext,
+                    part_size = excluded.part_size",
+                self.attachment_id,
+                self.ciphertext,
+                part_size,
+                now,
+            )
+            .execute(connection.as_mut())
+            .await?;
+            // parts of a previous upload of the same attachment are obsolete
+            query!(
+                "DELETE FROM attachment_upload_part WHERE attachment_id = ?",
+                self.attachment_id
+            )
+            .execute(connection.as_mut())
+            .await?;
+            Ok(())
+        }
+
+        /// Loads the ids of all queued attachments ordered by the time they were enqueued.
+        pub(crate) async fn load_ids(
+            mut connection: impl ReadConnection,
+        ) -> sqlx::Result<Vec<AttachmentId>> {
+            query_scalar!(
+                r#"SELECT attachment_id AS "attachment_id: _"
+                FROM attachment_upload_queue
+                ORDER BY created_at ASC"#
+            )
+            .fetch_all(connection.as_mut())
+            .await
+        }
+
+        pub(crate) async fn load(
+            mut connection: impl ReadConnection,
+            attachment_id: AttachmentId,
+        ) -> anyhow::Result<Option<Self>> {
+            let Some(record) = query!(
+                r#"SELECT
+                    ciphertext,
+                    part_size
+                FROM attachment_upload_queue
+                WHERE attachment_id = ?"#,
+                attachment_id
+            )
+            .fetch_optional(connection.as_mut())
+            .await?
+            else {
+                return Ok(None);
+            };
+            Ok(Some(Self {
+                attachment_id,
+                ciphertext: record.ciphertext,
+                part_size: record.part_size.try_into().context("invalid part size")?,
+            }))
+        }
+
+        pub(crate) async fn remove(
+            mut connection: impl WriteConnection,
+            attachment_id: AttachmentId,
+        ) -> sqlx::Result<()> {
+            query!(
+                "DELETE FROM attachment_upload_queue WHERE attachment_id = ?",
+                attachment_id
+            )
+            .execute(connection.as_mut())
+            .await?;
+            Ok(())
+        }
+
+        /// Records that a part of the upload is uploaded.
+        pub(crate) async fn add_part(
+            mut connection: impl WriteConnection,
+            attachment_id: AttachmentId,
+            part: &UploadedPart,
+        ) -> sqlx::Result<()> {
+            query!(
+                "INSERT INTO attachment_upload_part (attachment_id, part_number, etag)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT (attachment_id, part_number) DO UPDATE SET etag = excluded.etag",
+                attachment_id,
+                part.part_number,
+                part.etag,
+            )
+            .execute(connection.as_mut())
+            .await?;
+            Ok(())
+        }
+
+        /// Loads the uploaded parts of the upload ordered by their number.
+        pub(crate) async fn load_parts(
+            mut connection: impl ReadConnection,
+            attachment_id: AttachmentId,
+        ) -> anyhow::Result<Vec<UploadedPart>> {
+            query_as!(
+                SqlUploadedPart,
+                "SELECT part_number, etag
+                FROM attachment_upload_part
+                WHERE attachment_id = ?
+                ORDER BY part_number ASC",
+                attachment_id
+            )
+            .fetch_all(connection.as_mut())
+            .await?
+            .into_iter()
+            .map(TryFrom::try_from)
+            .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{Pool, Sqlite};
+
+    use crate::{
+        chats::{messages::persistence::tests::test_chat_message, persistence::tests::test_chat},
+        clients::attachment::persistence::test::test_attachment_record,
+        db::access::DbAccess,
+    };
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn attachment_upload_queue_cycle(pool: Pool<Sqlite>) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let chat = test_chat();
+        chat.store(pool.write().await?).await?;
+        let message = test_chat_message(chat.id());
+        message.store(pool.write().await?).await?;
+        let record = test_attachment_record(chat.id(), message.id());
+        record.store(pool.write().await?, None).await?;
+        let attachment_id = record.attachment_id;
+
+        let upload = AttachmentUploadQueue {
+            attachment_id,
+            ciphertext: vec![1, 2, 3],
+            part_size: 5 * 1024 * 1024,
+        };
+        upload.enqueue(pool.write().await?).await?;
+
+        let ids = AttachmentUploadQueue::load_ids(pool.read().await?).await?;
+        assert_eq!(ids, [attachment_id]);
+
+        let queued = AttachmentUploadQueue::load(pool.read().await?, attachment_id)
+            .await?
+            .expect("missing queued upload");
+        assert_eq!(queued.attachment_id, attachment_id);
+        assert_eq!(queued.ciphertext, upload.ciphertext);
+        assert_eq!(queued.part_size, upload.part_size);
+
+        let parts = [
+            UploadedPart {
+                part_number: 2,
+                etag: "\"etag-2\"".to_owned(),
+            },
+            UploadedPart {
+                part_number: 1,
+                etag: "\"etag-1\"".to_owned(),
+            },
+        ];
+        for part in &parts {
+            AttachmentUploadQueue::add_part(pool.write().await?, attachment_id, part).await?;
+        }
+        let loaded_parts =
+            AttachmentUploadQueue::load_parts(pool.read().await?, attachment_id).await?;
+        assert_eq!(loaded_parts, [parts[1].clone(), parts[0].clone()]);
+
+        // Enqueueing the attachment again restarts the upload
+        upload.enqueue(pool.write().await?).await?;
+        assert!(
+            AttachmentUploadQueue::load_parts(pool.read().await?, attachment_id)
+                .await?
+                .is_empty()
+        );
+
+        AttachmentUploadQueue::add_part(pool.write().await?, attachment_id, &parts[0]).await?;
+        AttachmentUploadQueue::remove(pool.write().await?, attachment_id).await?;
+        assert!(
+            AttachmentUploadQueue::load_ids(pool.read().await?)
+                .await?
+                .is_empty()
+        );
+        assert!(
+            AttachmentUploadQueue::load_parts(pool.read().await?, attachment_id)
+                .await?
+                .is_empty()
+        );
+
+        Ok(())
+    }
+}
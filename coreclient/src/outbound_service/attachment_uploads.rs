@@ -0,0 +1,219 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Resumption of interrupted attachment uploads.
+//!
+//! Chat attachments are uploaded in parts. An attachment stays in the [`AttachmentUploadQueue`]
+//! until its upload is complete. If the upload is interrupted, the outbound service resumes it
+//! after the last uploaded part. The uploads run in background tasks, such that they don't block
+//! the other work of the service.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info};
+
+use crate::{
+    AttachmentId, AttachmentProgress, AttachmentStatus, ChatId,
+    clients::attachment::upload::{
+        QueuedAttachmentUpload, all_attachments_uploaded, finish_queued_upload,
+        is_upload_interrupted,
+    },
+    outbound_service::{
+        attachment_upload_queue::AttachmentUploadQueue, chat_message_queue::ChatMessageQueue,
+    },
+};
+
+use super::{OutboundService, OutboundServiceContext};
+
+/// Attachments which are currently being uploaded by this client.
+#[derive(Debug, Clone)]
+pub(crate) struct UploadsInFlight {
+    attachment_ids: watch::Sender<HashSet<AttachmentId>>,
+    /// Cancels the uploads resumed by the service
+    cancel_token: Arc<Mutex<CancellationToken>>,
+}
+
+impl Default for UploadsInFlight {
+    fn default() -> Self {
+        Self {
+            attachment_ids: watch::Sender::new(HashSet::new()),
+            cancel_token: Default::default(),
+        }
+    }
+}
+
+impl UploadsInFlight {
+    /// Marks the upload of the attachment as in flight until the returned guard is dropped.
+    ///
+    /// Returns `None` if the upload is already in flight.
+    fn acquire(&self, attachment_id: AttachmentId) -> Option<UploadInFlightGuard> {
+        let inserted = self
+            .attachment_ids
+            .send_if_modified(|attachment_ids| attachment_ids.insert(attachment_id));
+        inserted.then(|| UploadInFlightGuard {
+            uploads: self.clone(),
+            attachment_id,
+        })
+    }
+
+    /// Returns the token cancelling the uploads resumed by the service.
+    fn cancel_token(&self) -> CancellationToken {
+        self.cancel_token.lock().unwrap().clone()
+    }
+
+    /// Cancels the uploads resumed by the service.
+    ///
+    /// Their attachments stay queued, such that they are resumed again by the next run.
+    pub(super) fn cancel_all(&self) {
+        let cancel_token = std::mem::take(&mut *self.cancel_token.lock().unwrap());
+        cancel_token.cancel();
+    }
+
+    /// Waits until no upload is in flight.
+    async fn wait_for_none(&self) {
+        let mut rx = self.attachment_ids.subscribe();
+        // The sender is owned by `self`, so the channel can't be closed.
+        let _ = rx
+            .wait_for(|attachment_ids| attachment_ids.is_empty())
+            .await;
+    }
+}
+
+/// Marks the upload of an attachment as in flight while it is alive.
+#[derive(Debug)]
+pub(crate) struct UploadInFlightGuard {
+    uploads: UploadsInFlight,
+    attachment_id: AttachmentId,
+}
+
+impl UploadInFlightGuard {
+    pub(crate) fn attachment_id(&self) -> AttachmentId {
+        self.attachment_id
+    }
+}
+
+impl Drop for UploadInFlightGuard {
+    fn drop(&mut self) {
+        self.uploads
+            .attachment_ids
+            .send_if_modified(|attachment_ids| attachment_ids.remove(&self.attachment_id));
+    }
+}
+
+impl OutboundService {
+    /// Marks the upload of the attachment as in flight, such that the service does not resume it
+    /// concurrently.
+    ///
+    /// Returns `None` if the upload is already in flight.
+    pub(crate) fn acquire_upload(
+        &self,
+        attachment_id: AttachmentId,
+    ) -> Option<UploadInFlightGuard> {
+        self.context.uploads_in_flight.acquire(attachment_id)
+    }
+
+    /// Waits until no attachment upload is in flight, including the uploads resumed by the
+    /// service.
+    pub async fn wait_for_uploads(&self) {
+        self.context.uploads_in_flight.wait_for_none().await;
+    }
+
+    /// Cancels the attachment uploads resumed by the service.
+    pub(crate) fn cancel_uploads(&self) {
+        self.context.uploads_in_flight.cancel_all();
+    }
+}
+
+impl OutboundServiceContext {
+    /// Spawns tasks resuming the uploads of all queued attachments which are not in flight.
+    ///
+    /// The tasks outlive the run, such that stopping the service does not interrupt the uploads;
+    /// they are cancelled when the service is paused. When all attachments of an unsent message
+    /// are uploaded, the message is enqueued and a flush of its chat is requested.
+    pub(super) async fn spawn_pending_uploads(&self) -> anyhow::Result<()> {
+        let attachment_ids = AttachmentUploadQueue::load_ids(self.db.read().await?).await?;
+        for attachment_id in attachment_ids {
+            let Some(guard) = self.uploads_in_flight.acquire(attachment_id) else {
+                debug!(?attachment_id, "Attachment upload already in flight");
+                continue;
+            };
+            let context = self.clone();
+            let cancel_token = self.uploads_in_flight.cancel_token();
+            let task = cancel_token.run_until_cancelled_owned(async move {
+                if let Err(error) = context.resume_upload(attachment_id).await {
+                    error!(%error, ?attachment_id, "Failed to resume attachment upload");
+                }
+                drop(guard);
+            });
+            tokio::spawn(task);
+        }
+        Ok(())
+    }
+
+    async fn resume_upload(&self, attachment_id: AttachmentId) -> anyhow::Result<()> {
+        let Some(upload) =
+            QueuedAttachmentUpload::load(self.db.read().await?, attachment_id).await?
+        else {
+            return Ok(()); // finished in the meantime
+        };
+
+        debug!(?attachment_id, "Resuming attachment upload");
+        let api_client = self.api_clients.default_client()?;
+        let (mut progress_tx, _progress) = AttachmentProgress::new();
+        let status = match upload
+            .upload(
+                &self.db,
+                &self.http_client,
+                &api_client,
+                self.signing_key(),
+                &mut progress_tx,
+            )
+            .await
+        {
+            Ok(()) => AttachmentStatus::Ready,
+            Err(error) if is_upload_interrupted(&error) => {
+                info!(?attachment_id, %error, "Attachment upload interrupted again");
+                return Ok(());
+            }
+            Err(error) => {
+                info!(?attachment_id, %error, "Failed to resume attachment upload");
+                AttachmentStatus::UploadFailed
+            }
+        };
+
+        let enqueued_chat_id = self
+            .db
+            .with_write_transaction(async |txn| -> anyhow::Result<Option<ChatId>> {
+                let Some(message) = finish_queued_upload(&mut *txn, attachment_id, status).await?
+                else {
+                    return Ok(None);
+                };
+                if message.is_sent() {
+                    return Ok(None);
+                }
+                let message_queue = ChatMessageQueue::new(message.chat_id(), message.id());
+                match status {
+                    AttachmentStatus::Ready => {
+                        if all_attachments_uploaded(&mut *txn, message.id()).await? {
+                            message_queue.enqueue(txn).await?;
+                            return Ok(Some(message.chat_id()));
+                        }
+                    }
+                    _ => message_queue.remove_and_mark_as_failed(txn).await?,
+                }
+                Ok(None)
+            })
+            .await?;
+        if let Some(chat_id) = enqueued_chat_id {
+            // send the message
+            self.chat_flushes.request(chat_id);
+        }
+        Ok(())
+    }
+}
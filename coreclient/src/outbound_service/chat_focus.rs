@@ -0,0 +1,246 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::HashSet;
+
+use aircommon::identifiers::MimiId;
+use mimi_content::MessageStatus;
+use tracing::debug;
+
+use crate::{ChatId, MessageId};
+
+use super::OutboundService;
+
+/// Tracks which chat is currently focused in the UI.
+///
+/// Read receipts scheduled via [`OutboundService::enqueue_read_receipts`] are only enqueued for
+/// the focused chat. For all other chats, they are persisted as [`DeferredReadReceipt`]s until
+/// the chat is focused.
+#[derive(Debug, Default)]
+pub(crate) struct ChatFocus {
+    focused: Option<ChatId>,
+}
+
+impl ChatFocus {
+    /// Sets or clears the focus of the given chat.
+    ///
+    /// Returns whether the chat is focused afterwards.
+    fn set_focused(&mut self, chat_id: ChatId, focused: bool) -> bool {
+        if focused {
+            self.focused = Some(chat_id);
+        } else if self.focused == Some(chat_id) {
+            self.focused = None;
+        }
+        focused
+    }
+
+    fn is_focused(&self, chat_id: ChatId) -> bool {
+        self.focused == Some(chat_id)
+    }
+}
+
+/// Read receipt which is held back until its chat is focused.
+pub(crate) struct DeferredReadReceipt;
+
+mod persistence {
+    use sqlx::{query, query_as};
+
+    use crate::db::access::WriteConnection;
+
+    use super::*;
+
+    impl DeferredReadReceipt {
+        pub(crate) async fn store(
+            mut connection: impl WriteConnection,
+            chat_id: ChatId,
+            receipts: &[(MessageId, MimiId)],
+        ) -> sqlx::Result<()> {
+            for (message_id, mimi_id) in receipts {
+                query!(
+                    "INSERT INTO deferred_read_receipt (message_id, chat_id, mimi_id)
+                    VALUES (?, ?, ?)
+                    ON CONFLICT (message_id) DO NOTHING",
+                    message_id,
+                    chat_id,
+                    mimi_id,
+                )
+                .execute(connection.as_mut())
+                .await?;
+            }
+            Ok(())
+        }
+
+        /// Removes and returns the deferred read receipts of the chat.
+        pub(crate) async fn take(
+            mut connection: impl WriteConnection,
+            chat_id: ChatId,
+        ) -> sqlx::Result<Vec<(MessageId, MimiId)>> {
+            struct Record {
+                message_id: MessageId,
+                mimi_id: MimiId,
+            }
+            let records = query_as!(
+                Record,
+                r#"DELETE FROM deferred_read_receipt WHERE chat_id = ?
+                RETURNING
+                    message_id AS "message_id: _",
+                    mimi_id AS "mimi_id: _""#,
+                chat_id,
+            )
+            .fetch_all(connection.as_mut())
+            .await?;
+            Ok(records
+                .into_iter()
+                .map(|record| (record.message_id, record.mimi_id))
+                .collect())
+        }
+
+        /// Drops the deferred read receipts of the given messages.
+        pub(crate) async fn remove(
+            mut connection: impl WriteConnection,
+            message_ids: &HashSet<MessageId>,
+        ) -> sqlx::Result<()> {
+            for message_id in message_ids {
+                query!(
+                    "DELETE FROM deferred_read_receipt WHERE message_id = ?",
+                    message_id
+                )
+                .execute(connection.as_mut())
+                .await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+impl OutboundService {
+    /// Sets whether the chat with the given id is focused in the UI.
+    ///
+    /// At most one chat is focused at a time. Focusing a chat enqueues the read receipts which
+    /// were deferred while the chat was not focused.
+    pub async fn set_chat_focused(&self, chat_id: ChatId, focused: bool) -> anyhow::Result<()> {
+        let focused = self
+            .chat_focus
+            .lock()
+            .unwrap()
+            .set_focused(chat_id, focused);
+        if !focused {
+            return Ok(());
+        }
+        self.context
+            .db
+            .with_write_transaction(async |txn| {
+                let deferred = DeferredReadReceipt::take(&mut *txn, chat_id).await?;
+                let statuses = deferred
+                    .iter()
+                    .map(|(message_id, mimi_id)| (*message_id, mimi_id, MessageStatus::Read));
+                self.schedule_receipts(txn, chat_id, statuses).await
+            })
+            .await
+    }
+
+    /// Returns whether the chat with the given id is focused in the UI.
+    pub fn is_chat_focused(&self, chat_id: ChatId) -> bool {
+        self.chat_focus.lock().unwrap().is_focused(chat_id)
+    }
+
+    /// Enqueues read receipts if the chat is focused, otherwise defers them until it is.
+    ///
+    /// Unlike [`OutboundService::enqueue_receipts`], which is used when messages are explicitly
+    /// marked as read, this is meant for read receipts scheduled automatically.
+    pub async fn enqueue_read_receipts(
+        &self,
+        chat_id: ChatId,
+        receipts: impl IntoIterator<Item = (MessageId, MimiId)>,
+    ) -> anyhow::Result<()> {
+        let receipts: Vec<_> = receipts.into_iter().collect();
+        if receipts.is_empty() {
+            return Ok(());
+        }
+        if self.is_chat_focused(chat_id) {
+            let statuses = receipts
+                .iter()
+                .map(|(message_id, mimi_id)| (*message_id, mimi_id, MessageStatus::Read));
+            self.enqueue_receipts(chat_id, statuses).await
+        } else {
+            debug!(?chat_id, "Deferring read receipts for unfocused chat");
+            DeferredReadReceipt::store(self.context.db.write().await?, chat_id, &receipts).await?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::SqlitePool;
+
+    use crate::{
+        chats::{
+            messages::persistence::tests::test_chat_message_with_salt,
+            persistence::tests::test_chat,
+        },
+        db::access::DbAccess,
+    };
+
+    use super::*;
+
+    #[test]
+    fn focus_single_chat() {
+        let mut focus = ChatFocus::default();
+        let chat_a = ChatId::new(uuid::Uuid::from_u128(1));
+        let chat_b = ChatId::new(uuid::Uuid::from_u128(2));
+
+        assert!(!focus.is_focused(chat_a));
+        assert!(focus.set_focused(chat_a, true));
+        assert!(focus.is_focused(chat_a));
+
+        // Focusing another chat moves the focus
+        assert!(focus.set_focused(chat_b, true));
+        assert!(!focus.is_focused(chat_a));
+
+        // Unfocusing a chat which is not focused keeps the focus
+        assert!(!focus.set_focused(chat_a, false));
+        assert!(focus.is_focused(chat_b));
+        assert!(!focus.set_focused(chat_b, false));
+        assert!(!focus.is_focused(chat_b));
+    }
+
+    #[sqlx::test]
+    async fn deferred_read_receipts_are_persisted(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+        let mut connection = pool.write().await?;
+
+        let chat = test_chat();
+        chat.store(&mut connection).await?;
+        let other_chat = test_chat();
+        other_chat.store(&mut connection).await?;
+
+        let mut receipts = Vec::new();
+        for (salt, chat_id) in [(1, chat.id()), (2, chat.id()), (3, other_chat.id())] {
+            let message = test_chat_message_with_salt(chat_id, [salt; 16]);
+            message.store(&mut connection).await?;
+            receipts.push((message.id(), *message.message().mimi_id().unwrap()));
+        }
+
+        DeferredReadReceipt::store(&mut connection, chat.id(), &receipts[..2]).await?;
+        DeferredReadReceipt::store(&mut connection, other_chat.id(), &receipts[2..]).await?;
+        // Deferring again is a no-op
+        DeferredReadReceipt::store(&mut connection, chat.id(), &receipts[..1]).await?;
+
+        // Explicitly read messages are no longer deferred
+        DeferredReadReceipt::remove(&mut connection, &[receipts[1].0].into()).await?;
+
+        let taken = DeferredReadReceipt::take(&mut connection, chat.id()).await?;
+        assert_eq!(taken, receipts[..1]);
+        assert!(
+            DeferredReadReceipt::take(&mut connection, chat.id())
+                .await?
+                .is_empty()
+        );
+        let taken = DeferredReadReceipt::take(&mut connection, other_chat.id()).await?;
+        assert_eq!(taken, receipts[2..]);
+
+        Ok(())
+    }
+}
@@ -25,7 +25,7 @@ mod persistence {
     use tracing::debug;
     use uuid::Uuid;
 
-    use crate::db::access::{WriteConnection, WriteDbTransaction};
+    use crate::db::access::{ReadConnection, WriteConnection, WriteDbTransaction};
 
     use super::*;
 
@@ -54,19 +54,27 @@ mod persistence {
             Ok(())
         }
 
+        /// Locks the oldest queued message which is not locked by this task.
+        ///
+        /// If `chat_id` is given, only messages of this chat are considered.
+        ///
+        /// Returns the chat and message id, and the time at which the message was enqueued.
         pub(crate) async fn dequeue(
             txn: &mut WriteDbTransaction<'_>,
             task_id: Uuid,
-        ) -> anyhow::Result<Option<(ChatId, MessageId)>> {
+            chat_id: Option<ChatId>,
+        ) -> anyhow::Result<Option<(ChatId, MessageId, TimeStamp)>> {
             let Some(message_id) = query_scalar!(
                 r#"
                 SELECT message_id
                 FROM chat_message_queue
-                WHERE locked_by IS NULL OR locked_by != ?1
+                WHERE (locked_by IS NULL OR locked_by != ?1)
+                    AND (?2 IS NULL OR chat_id = ?2)
                 ORDER BY created_at ASC
                 LIMIT 1
                 "#,
-                task_id
+                task_id,
+                chat_id,
             )
             .fetch_optional(txn.as_mut())
             .await?
@@ -77,6 +85,7 @@ mod persistence {
             struct DequeuedMessage {
                 message_id: Uuid,
                 chat_id: Uuid,
+                created_at: TimeStamp,
             }
             let res = query_as!(
                 DequeuedMessage,
@@ -84,7 +93,10 @@ mod persistence {
                 UPDATE chat_message_queue
                 SET locked_by = ?1
                 WHERE message_id = ?2
-                RETURNING message_id AS "message_id: _", chat_id AS "chat_id: _"
+                RETURNING
+                    message_id AS "message_id: _",
+                    chat_id AS "chat_id: _",
+                    created_at AS "created_at: _"
                 "#,
                 task_id,
                 message_id
@@ -95,14 +107,27 @@ mod persistence {
             if let Some(DequeuedMessage {
                 message_id,
                 chat_id,
+                created_at,
             }) = res
             {
-                Ok(Some((ChatId::new(chat_id), MessageId::new(message_id))))
+                Ok(Some((
+                    ChatId::new(chat_id),
+                    MessageId::new(message_id),
+                    created_at,
+                )))
             } else {
                 Ok(None)
             }
         }
 
+        /// Returns the number of queued messages.
+        pub(crate) async fn count(mut connection: impl ReadConnection) -> sqlx::Result<usize> {
+            query_scalar!(r#"SELECT COUNT(*) AS "count: _" FROM chat_message_queue"#)
+                .fetch_one(connection.as_mut())
+                .await
+                .map(|n: u32| n.try_into().expect("usize overflow"))
+        }
+
         pub(crate) async fn remove(
             txn: &mut WriteDbTransaction<'_>,
             message_id: MessageId,
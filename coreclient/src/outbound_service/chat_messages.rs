@@ -2,6 +2,8 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::collections::HashSet;
+
 use anyhow::anyhow;
 use anyhow::{Context, ensure};
 use mimi_content::MessageStatus;
@@ -15,8 +17,8 @@ use crate::groups::handle_group_not_found_on_ds;
 use crate::job::pending_chat_operation::PendingChatOperation;
 use crate::outbound_service::resync::Resync;
 use crate::{
-    Chat, ChatMessage, ChatStatus, Message, MessageId,
-    outbound_service::chat_message_queue::ChatMessageQueue,
+    Chat, ChatId, ChatMessage, ChatStatus, Message, MessageId,
+    outbound_service::{chat_message_queue::ChatMessageQueue, stats::OutboundKind},
 };
 
 use super::{OutboundService, OutboundServiceContext};
@@ -99,23 +101,57 @@ impl OutboundServiceContext {
     pub(super) async fn send_queued_messages(
         &self,
         run_token: &CancellationToken,
+    ) -> anyhow::Result<()> {
+        self.send_queued_messages_of(None, run_token).await
+    }
+
+    /// Sends the queued messages of a single chat.
+    pub(super) async fn send_queued_chat_messages(
+        &self,
+        chat_id: ChatId,
+        run_token: &CancellationToken,
+    ) -> anyhow::Result<()> {
+        self.send_queued_messages_of(Some(chat_id), run_token).await
+    }
+
+    /// Sends the queued messages of the given chat, or of all chats if `only_chat` is `None`.
+    ///
+    /// Messages of a chat are sent in the order they were enqueued. If a message of a chat can't
+    /// be sent in this run, the remaining messages of the chat are held back until a later run.
+    async fn send_queued_messages_of(
+        &self,
+        only_chat: Option<ChatId>,
+        run_token: &CancellationToken,
     ) -> anyhow::Result<()> {
         // Used to identify locked messages by this task
         let task_id = Uuid::new_v4();
+        // Chats whose remaining messages are not sent in this run to preserve their order
+        let mut held_back_chats = HashSet::new();
         loop {
             if run_token.is_cancelled() {
                 return Ok(()); // the task is being stopped
             }
 
-            let Some((chat_id, message_id)) = self
+            let Some((chat_id, message_id, enqueued_at)) = self
                 .db
-                .with_write_transaction(async |txn| ChatMessageQueue::dequeue(txn, task_id).await)
+                .with_write_transaction(async |txn| {
+                    ChatMessageQueue::dequeue(txn, task_id, only_chat).await
+                })
                 .await?
             else {
                 return Ok(());
             };
             debug!(?message_id, "dequeued messages");
 
+            if held_back_chats.contains(&chat_id) {
+                debug!(
+                    ?message_id,
+                    ?chat_id,
+                    "Holding back chat message until a previous message is sent"
+                );
+                continue;
+            }
+
             // If a chat operation is pending, we skip sending chat messages for
             // this chat
             if PendingChatOperation::is_pending_for_chat(self.db.read().await?, chat_id).await? {
@@ -123,6 +159,7 @@ impl OutboundServiceContext {
                     ?chat_id,
                     "Skipping sending chat message due to pending chat operation"
                 );
+                held_back_chats.insert(chat_id);
                 continue;
             }
 
@@ -136,6 +173,8 @@ impl OutboundServiceContext {
                             Ok(())
                         })
                         .await?;
+                    self.counters.sent(OutboundKind::Message);
+                    self.counters.time_in_queue(enqueued_at);
                 }
                 Ok(SendOutcome::Collided) => {
                     // Leave the message in the queue so a later run retries it
@@ -146,9 +185,12 @@ impl OutboundServiceContext {
                         ?chat_id,
                         "Message collided, re-enqueuing for a later run"
                     );
+                    self.counters.retried(OutboundKind::Message);
+                    held_back_chats.insert(chat_id);
                 }
                 Err(e) => {
                     warn!(error = ?e, ?message_id, "Failed to send chat message");
+                    self.counters.send_failed(OutboundKind::Message);
                     // If the message fails, we mark it and all other queued
                     // messages as "failed" and delete them from the queue.
                     self.db
@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::{
+    future,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Mutex, atomic::AtomicBool},
     task::{Context, Poll},
     time::Duration,
 };
@@ -13,26 +14,41 @@ use aircommon::{
     credentials::keys::ClientSigningKey,
     identifiers::{QsClientId, UserId},
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use pin_project::pin_project;
-use tokio::{sync::watch, time};
+use tokio::{
+    sync::{self, mpsc, oneshot, watch},
+    time,
+};
 use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
 use tracing::{debug, error, info};
 
 use crate::{
+    ChatId,
     clients::api_clients::ApiClients,
     db::access::DbAccess,
     job::{Job, JobContext, JobContextDb, JobError},
     key_stores::MemoryUserKeyStore,
-    outbound_service::error::OutboundServiceRunError,
+    outbound_service::{
+        attachment_uploads::UploadsInFlight,
+        chat_focus::ChatFocus,
+        error::OutboundServiceRunError,
+        presence::PresenceQueue,
+        typing::{TypingQueue, TypingThrottle},
+    },
     utils::global_lock::GlobalLock,
 };
 
+pub use stats::OutboundServiceStats;
 pub use timed_tasks::{APQ_KEY_PACKAGES, KEY_PACKAGES};
 
+pub(crate) mod attachment_upload_queue;
+pub(crate) mod attachment_uploads;
+mod chat_focus;
 mod chat_message_queue;
 mod chat_messages;
 mod error;
+mod presence;
 mod profile;
 mod push_tokens;
 mod reaction_queue;
@@ -41,22 +57,37 @@ mod receipt_queue;
 mod receipts;
 pub(crate) mod resync;
 mod retry_pending_chat_operations;
+pub(crate) mod scheduled_message_queue;
+mod scheduled_messages;
+mod stats;
 pub(crate) mod timed_tasks;
+pub(crate) mod typing;
 
 /// Cadence at which a started outbound service wakes itself to run scheduled work.
 const PERIODIC_WAKE_INTERVAL: Duration = Duration::from_secs(60);
+/// Minimal delay of a scheduled wake, so that work which stays due does not run in a busy loop.
+const MIN_SCHEDULED_WAKE_DELAY: Duration = Duration::from_secs(1);
 
 /// A service which is responsible for processing outbound messages.
 ///
 /// The service starts a background task which dequeues messages from the corresponding work queues.
 /// The initial state of the service is `Stopped`, that is, the background task is not running. The
 /// background task runs when the service is started, when there is a notification to run, and
-/// periodically every [`PERIODIC_WAKE_INTERVAL`] while started. After doing the work once, it waits
-/// for the next notification or wake, or stops if it is stopped.
+/// periodically every [`PERIODIC_WAKE_INTERVAL`] while started. Additionally, it wakes up when
+/// scheduled work is due, see [`OutboundServiceWork::next_due_at`]. After doing the work once, it
+/// waits for the next notification or wake, or stops if it is stopped.
+///
+/// The service can be paused, in which case it does not perform any work until it is resumed; see
+/// [`OutboundService::pause`].
 #[derive(Debug)]
 pub struct OutboundService<C: OutboundServiceWork = OutboundServiceContext> {
     context: Arc<C>,
     run_token_tx: watch::Sender<RunToken>,
+    flush_tx: mpsc::UnboundedSender<FlushRequest>,
+    exclusive_tx: mpsc::UnboundedSender<ExclusiveWork>,
+    pause_state: Arc<Mutex<PauseState>>,
+    chat_focus: Arc<Mutex<ChatFocus>>,
+    typing: Arc<Mutex<TypingThrottle>>,
 }
 
 impl<C: OutboundServiceWork> Clone for OutboundService<C> {
@@ -64,18 +95,78 @@ impl<C: OutboundServiceWork> Clone for OutboundService<C> {
         Self {
             context: self.context.clone(),
             run_token_tx: self.run_token_tx.clone(),
+            flush_tx: self.flush_tx.clone(),
+            exclusive_tx: self.exclusive_tx.clone(),
+            pause_state: self.pause_state.clone(),
+            chat_focus: self.chat_focus.clone(),
+            typing: self.typing.clone(),
         }
     }
 }
 
 pub trait OutboundServiceWork: Clone + Send + 'static {
     fn work(&self, run_token: CancellationToken) -> impl Future<Output = ()> + Send;
+
+    /// Processes the pending outbound messages of a single chat.
+    ///
+    /// By default, all work is done.
+    fn flush_chat(
+        &self,
+        _chat_id: ChatId,
+        run_token: CancellationToken,
+    ) -> impl Future<Output = ()> + Send {
+        self.work(run_token)
+    }
+
+    /// Returns when work is due next, e.g. a scheduled message.
+    ///
+    /// A started service wakes up at this time even if it is before the next periodic wake. By
+    /// default, no work is scheduled.
+    fn next_due_at(&self) -> impl Future<Output = Option<DateTime<Utc>>> + Send {
+        future::ready(None)
+    }
+
+    /// Returns the next chat which should be flushed on behalf of work spawned in the
+    /// background, e.g. when an attachment upload finished and its message can be sent.
+    ///
+    /// The flush is performed like [`OutboundService::flush_chat`]. By default, no flushes are
+    /// requested.
+    fn flush_requested(&self) -> impl Future<Output = ChatId> + Send {
+        future::pending()
+    }
+
+    /// Cancels the work spawned in the background, e.g. attachment uploads.
+    ///
+    /// Called when the service is paused. By default, nothing is spawned.
+    fn cancel_background_work(&self) {}
 }
 
 impl OutboundServiceWork for OutboundServiceContext {
     async fn work(&self, run_token: CancellationToken) {
         OutboundServiceContext::work(self, run_token).await;
     }
+
+    async fn flush_chat(&self, chat_id: ChatId, run_token: CancellationToken) {
+        if let Err(error) = self.send_queued_chat_messages(chat_id, &run_token).await {
+            error!(%error, ?chat_id, "Failed to flush queued chat messages");
+        }
+    }
+
+    async fn next_due_at(&self) -> Option<DateTime<Utc>> {
+        self.next_scheduled_message_at()
+            .await
+            .inspect_err(|error| error!(%error, "Failed to load next scheduled message"))
+            .ok()
+            .flatten()
+    }
+
+    async fn flush_requested(&self) -> ChatId {
+        self.chat_flushes.next().await
+    }
+
+    fn cancel_background_work(&self) {
+        self.uploads_in_flight.cancel_all();
+    }
 }
 
 impl OutboundService<OutboundServiceContext> {
@@ -93,7 +184,14 @@ impl OutboundService<OutboundServiceContext> {
             http_client,
             key_store,
             qs_client_id,
+            timed_tasks_enabled: Arc::new(AtomicBool::new(true)),
+            uploads_in_flight: Default::default(),
+            chat_flushes: Default::default(),
+            counters: Default::default(),
+            typing_queue: Default::default(),
+            presence_queue: Default::default(),
         };
+        OutboundServiceCounters::describe_metrics();
         Self::with_context(context, global_lock)
     }
 }
@@ -105,21 +203,44 @@ impl<C: OutboundServiceWork> OutboundService<C> {
 
     fn build(context: C, global_lock: GlobalLock, wake_interval: Duration) -> Self {
         let (run_token_tx, run_token_rx) = watch::channel(RunToken::new_cancelled());
+        let (flush_tx, flush_rx) = mpsc::unbounded_channel();
+        let (exclusive_tx, exclusive_rx) = mpsc::unbounded_channel();
+        let pause_state: Arc<Mutex<PauseState>> = Default::default();
         let task = OutboundServiceTask {
             context: context.clone(),
             wake_interval,
+            pause_state: pause_state.clone(),
         };
-        tokio::spawn(task.run(run_token_rx, global_lock));
+        tokio::spawn(task.run(run_token_rx, flush_rx, exclusive_rx, global_lock));
         Self {
             context: Arc::new(context),
             run_token_tx,
+            flush_tx,
+            exclusive_tx,
+            pause_state,
+            chat_focus: Default::default(),
+            typing: Default::default(),
         }
     }
 
     /// Starts the background task.
     ///
+    /// If the service is paused, the background task is started when the service is resumed.
+    ///
     /// Returns a future which finishes when the background task is done.
     pub(crate) fn start(&self) -> WaitForDoneFuture {
+        {
+            let mut pause_state = self.pause_state.lock().unwrap();
+            if pause_state.paused {
+                pause_state.start_on_resume = true;
+                debug!("service is paused; starting background task on resume");
+                return WaitForDoneFuture::new(None);
+            }
+        }
+        self.start_unpaused()
+    }
+
+    fn start_unpaused(&self) -> WaitForDoneFuture {
         let mut done_token = None;
         self.run_token_tx.send_if_modified(|run_token| {
             if !run_token.rotate() {
@@ -136,6 +257,7 @@ impl<C: OutboundServiceWork> OutboundService<C> {
     ///
     /// Returns a futures which resolves when the background task fully stops.
     pub(crate) fn stop(&self) -> WaitForDoneFuture {
+        self.pause_state.lock().unwrap().start_on_resume = false;
         let mut done_token = None;
         self.run_token_tx.send_if_modified(|run_token| {
             run_token.cancel();
@@ -166,6 +288,108 @@ impl<C: OutboundServiceWork> OutboundService<C> {
         self.notify_work()
     }
 
+    /// Notifies the background task about a newly scheduled message, so that it wakes up when the
+    /// message is due.
+    pub(crate) fn notify_scheduled_message(&self) -> WaitForDoneFuture {
+        self.notify_work()
+    }
+
+    /// Pauses the service.
+    ///
+    /// While paused, the service does not perform any outbound network activity: the running work
+    /// is cancelled, and starting the service, notifications about new work and flushes of chats
+    /// have no effect. Queued work stays persisted and is processed after the service is resumed.
+    ///
+    /// Returns a future which resolves when the running work (if any) is cancelled.
+    pub fn pause(&self) -> WaitForDoneFuture {
+        let mut done_token = None;
+        {
+            let mut pause_state = self.pause_state.lock().unwrap();
+            if pause_state.paused {
+                return WaitForDoneFuture::new(None);
+            }
+            pause_state.paused = true;
+            self.run_token_tx.send_if_modified(|run_token| {
+                pause_state.start_on_resume = !run_token.is_cancelled();
+                run_token.cancel();
+                done_token = Some(run_token.done.clone());
+                false // no more work => no need to wake up the background task
+            });
+        }
+        self.context.cancel_background_work();
+        info!("pausing outbound service");
+        WaitForDoneFuture::new(done_token)
+    }
+
+    /// Resumes the paused service.
+    ///
+    /// If the service was started before it was paused or while it was paused, the background
+    /// task is started again and processes the accumulated work.
+    ///
+    /// Returns a future which resolves when the background task is done.
+    pub fn resume(&self) -> WaitForDoneFuture {
+        let start = {
+            let mut pause_state = self.pause_state.lock().unwrap();
+            if !pause_state.paused {
+                return WaitForDoneFuture::new(None);
+            }
+            pause_state.paused = false;
+            std::mem::take(&mut pause_state.start_on_resume)
+        };
+        info!(?start, "resuming outbound service");
+        if start {
+            self.start_unpaused()
+        } else {
+            WaitForDoneFuture::new(None)
+        }
+    }
+
+    /// Returns whether the service is paused; see [`Self::pause`].
+    pub fn is_paused(&self) -> bool {
+        self.pause_state.lock().unwrap().paused
+    }
+
+    /// Sends the pending outbound messages of the chat with the given id.
+    ///
+    /// In contrast to [`Self::run_once`], only the message queue of this chat is processed. The
+    /// flush is performed by the background task, even if the service is stopped, but it does not
+    /// interrupt a run which is already in progress. If the service is paused, nothing is flushed.
+    ///
+    /// Returns a future which resolves when the chat has no more pending messages which can be
+    /// sent, or when the flush is cancelled by stopping the service.
+    pub fn flush_chat(&self, chat_id: ChatId) -> WaitForDoneFuture {
+        let done = CancellationToken::new();
+        let request = FlushRequest {
+            chat_id,
+            done: done.clone(),
+        };
+        if self.flush_tx.send(request).is_err() {
+            // The background task is gone; there is nothing to wait for.
+            return WaitForDoneFuture::new(None);
+        }
+        debug!(?chat_id, "requesting flush of chat");
+        WaitForDoneFuture::new(Some(done))
+    }
+
+    /// Runs the given work in the background task while holding the global lock.
+    ///
+    /// The work does not overlap with the work of the background task and, via the global lock,
+    /// with the work of other processes using the same database. It is run even if the service is
+    /// stopped or paused.
+    ///
+    /// Returns `None` if the background task is gone.
+    pub(crate) async fn run_exclusive<T: Send + 'static>(
+        &self,
+        work: impl Future<Output = T> + Send + 'static,
+    ) -> Option<T> {
+        let (tx, rx) = oneshot::channel();
+        let work: ExclusiveWork = Box::pin(async move {
+            let _ = tx.send(work.await);
+        });
+        self.exclusive_tx.send(work).ok()?;
+        rx.await.ok()
+    }
+
     /// Runs the background task and waits until it is done.
     ///
     /// If the background is already running, just waits until it is done.
@@ -177,18 +401,76 @@ impl<C: OutboundServiceWork> OutboundService<C> {
     }
 }
 
+/// A request to flush the pending messages of a chat; see [`OutboundService::flush_chat`].
+#[derive(Debug)]
+struct FlushRequest {
+    chat_id: ChatId,
+    done: CancellationToken,
+}
+
+/// Chats to flush on behalf of work spawned in the background; see
+/// [`OutboundServiceWork::flush_requested`].
+#[derive(Debug, Clone)]
+struct ChatFlushRequests {
+    tx: mpsc::UnboundedSender<ChatId>,
+    rx: Arc<sync::Mutex<mpsc::UnboundedReceiver<ChatId>>>,
+}
+
+impl Default for ChatFlushRequests {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            tx,
+            rx: Arc::new(sync::Mutex::new(rx)),
+        }
+    }
+}
+
+impl ChatFlushRequests {
+    fn request(&self, chat_id: ChatId) {
+        // The receiver is owned by `self`, so the channel can't be closed.
+        let _ = self.tx.send(chat_id);
+    }
+
+    async fn next(&self) -> ChatId {
+        match self.rx.lock().await.recv().await {
+            Some(chat_id) => chat_id,
+            None => future::pending().await,
+        }
+    }
+}
+
+/// Work which is run under the global lock; see [`OutboundService::run_exclusive`].
+type ExclusiveWork = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Whether the service is paused; see [`OutboundService::pause`].
+#[derive(Debug, Default)]
+struct PauseState {
+    paused: bool,
+    /// Whether the service is started when it is resumed.
+    start_on_resume: bool,
+}
+
 struct OutboundServiceTask<C> {
     context: C,
     wake_interval: Duration,
+    pause_state: Arc<Mutex<PauseState>>,
 }
 
 impl<C: OutboundServiceWork> OutboundServiceTask<C> {
-    async fn run(self, mut run_token_rx: watch::Receiver<RunToken>, mut global_lock: GlobalLock) {
+    async fn run(
+        self,
+        mut run_token_rx: watch::Receiver<RunToken>,
+        mut flush_rx: mpsc::UnboundedReceiver<FlushRequest>,
+        mut exclusive_rx: mpsc::UnboundedReceiver<ExclusiveWork>,
+        mut global_lock: GlobalLock,
+    ) {
         let mut ticker = time::interval_at(
             time::Instant::now() + self.wake_interval,
             self.wake_interval,
         );
         ticker.set_missed_tick_behavior(time::MissedTickBehavior::Skip);
+        let mut next_due: Option<time::Instant> = None;
 
         loop {
             let (run_token, from_notification) = tokio::select! {
@@ -212,6 +494,47 @@ impl<C: OutboundServiceWork> OutboundServiceTask<C> {
                     debug!("periodic wake");
                     (run_token, false)
                 }
+                _ = sleep_until(next_due) => {
+                    next_due = None;
+                    let run_token = run_token_rx.borrow().clone();
+                    if run_token.is_cancelled() {
+                        continue;
+                    }
+                    debug!("scheduled wake");
+                    (run_token, false)
+                }
+                Some(request) = self.next_flush_request(&mut flush_rx) => {
+                    if self.pause_state.lock().unwrap().paused {
+                        debug!(chat_id = ?request.chat_id, "service is paused; skipping flush");
+                        request.done.cancel();
+                        continue;
+                    }
+                    // A started service cancels the flush when it is stopped. A stopped service
+                    // flushes the chat anyway, since it was explicitly requested.
+                    let run_token = run_token_rx.borrow().clone();
+                    let cancel = if run_token.is_cancelled() {
+                        CancellationToken::new()
+                    } else {
+                        run_token.cancel
+                    };
+                    let _guard = global_lock
+                        .lock()
+                        .await
+                        .expect("fatal: failed to acquire global lock");
+                    debug!(chat_id = ?request.chat_id, "flushing chat in background task");
+                    self.context.flush_chat(request.chat_id, cancel).await;
+                    request.done.cancel();
+                    continue;
+                }
+                Some(work) = exclusive_rx.recv() => {
+                    let _guard = global_lock
+                        .lock()
+                        .await
+                        .expect("fatal: failed to acquire global lock");
+                    debug!("running exclusive work in background task");
+                    work.await;
+                    continue;
+                }
             };
 
             {
@@ -224,6 +547,14 @@ impl<C: OutboundServiceWork> OutboundServiceTask<C> {
                 debug!("finished work in background task");
             }
 
+            next_due = self.context.next_due_at().await.map(|due_at| {
+                let delay = (due_at - Utc::now())
+                    .to_std()
+                    .unwrap_or_default()
+                    .max(MIN_SCHEDULED_WAKE_DELAY);
+                time::Instant::now() + delay
+            });
+
             // We reset the ticker when the work was triggered by a notification.
             if from_notification {
                 ticker.reset();
@@ -234,6 +565,31 @@ impl<C: OutboundServiceWork> OutboundServiceTask<C> {
     }
 }
 
+impl<C: OutboundServiceWork> OutboundServiceTask<C> {
+    /// Returns the next flush requested via [`OutboundService::flush_chat`] or by work spawned in
+    /// the background.
+    async fn next_flush_request(
+        &self,
+        flush_rx: &mut mpsc::UnboundedReceiver<FlushRequest>,
+    ) -> Option<FlushRequest> {
+        tokio::select! {
+            request = flush_rx.recv() => request,
+            chat_id = self.context.flush_requested() => Some(FlushRequest {
+                chat_id,
+                done: CancellationToken::new(),
+            }),
+        }
+    }
+}
+
+/// Sleeps until `deadline`, or forever if there is none.
+async fn sleep_until(deadline: Option<time::Instant>) {
+    match deadline {
+        Some(deadline) => time::sleep_until(deadline).await,
+        None => future::pending().await,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OutboundServiceContext {
     db: DbAccess,
@@ -241,6 +597,19 @@ pub struct OutboundServiceContext {
     http_client: reqwest::Client,
     key_store: MemoryUserKeyStore,
     qs_client_id: QsClientId,
+    /// Whether timed tasks are executed; see [`OutboundService::set_timed_tasks_enabled`].
+    timed_tasks_enabled: Arc<AtomicBool>,
+    /// Attachments which are currently being uploaded; see [`OutboundService::acquire_upload`].
+    uploads_in_flight: UploadsInFlight,
+    /// Flushes requested by work spawned in the background; see
+    /// [`OutboundServiceWork::flush_requested`].
+    chat_flushes: ChatFlushRequests,
+    /// Counters exposed via [`OutboundService::stats`].
+    counters: OutboundServiceCounters,
+    /// Typing signals queued by [`OutboundService::send_typing`].
+    typing_queue: Arc<Mutex<TypingQueue>>,
+    /// Presence updates queued by [`OutboundService::publish_presence`].
+    presence_queue: Arc<Mutex<PresenceQueue>>,
 }
 
 impl OutboundServiceContext {
@@ -282,18 +651,33 @@ impl OutboundServiceContext {
         if let Err(error) = self.send_queued_receipts(&run_token).await {
             error!(%error, "Failed to send queued receipts");
         }
+        if let Err(error) = self.enqueue_due_scheduled_messages().await {
+            error!(%error, "Failed to enqueue due scheduled messages");
+        }
+        if let Err(error) = self.spawn_pending_uploads().await {
+            error!(%error, "Failed to resume pending attachment uploads");
+        }
         if let Err(error) = self.send_queued_messages(&run_token).await {
             error!(%error, "Failed to send queued messages");
         }
         if let Err(error) = self.send_queued_reactions(&run_token).await {
             error!(%error, "Failed to send queued reactions");
         }
+        if let Err(error) = self.send_queued_typing(&run_token).await {
+            error!(%error, "Failed to send queued typing signals");
+        }
+        if let Err(error) = self.send_queued_presence(&run_token).await {
+            error!(%error, "Failed to send queued presence updates");
+        }
         if let Err(error) = self.send_pending_push_token_updates(&run_token).await {
             error!(%error, "Failed to send push token update");
         }
         if let Err(error) = self.execute_timed_tasks(&run_token).await {
             error!(%error, "Failed to execute timed tasks");
         }
+        if let Err(error) = self.record_queue_sizes().await {
+            error!(%error, "Failed to record outbound queue sizes");
+        }
 
         fetch_profiles.await;
     }
@@ -471,6 +855,25 @@ mod test {
         assert_eq!(1, context.counter.load(Ordering::SeqCst));
     }
 
+    #[tokio::test]
+    async fn run_exclusive_waits_for_running_work() {
+        init_test_tracing();
+
+        let context = DelayedCounterContext::default();
+        let service = OutboundService::with_context(context.clone(), global_lock());
+
+        let done = service.start();
+        // Let the background task pick up the work
+        sleep(Duration::from_millis(10)).await;
+
+        let counter = context.counter.clone();
+        let value = service
+            .run_exclusive(async move { counter.load(Ordering::SeqCst) })
+            .await;
+        assert_eq!(value, Some(1), "exclusive work runs after the running work");
+        done.await;
+    }
+
     /// A short wake interval used in tests so the periodic ticker fires quickly.
     const TEST_WAKE_INTERVAL: Duration = Duration::from_millis(20);
 
@@ -498,6 +901,43 @@ mod test {
         .expect("periodic wake should run work again while started");
     }
 
+    /// Counts the runs like [`DelayedCounterContext`] and has work due shortly after its first run.
+    #[derive(Default, Clone)]
+    struct ScheduledCounterContext {
+        inner: DelayedCounterContext,
+    }
+
+    impl OutboundServiceWork for ScheduledCounterContext {
+        async fn work(&self, run_token: CancellationToken) {
+            self.inner.work(run_token).await
+        }
+
+        async fn next_due_at(&self) -> Option<DateTime<Utc>> {
+            (self.inner.counter.load(Ordering::SeqCst) == 1)
+                .then(|| Utc::now() + Duration::from_millis(50))
+        }
+    }
+
+    #[tokio::test]
+    async fn scheduled_wake_runs_work_when_due() {
+        init_test_tracing();
+
+        let context = ScheduledCounterContext::default();
+        // The default wake interval is too long to be reached by this test
+        let service = OutboundService::with_context(context.clone(), global_lock());
+
+        service.start().await; // +1 => counter = 1
+        assert_eq!(1, context.inner.counter.load(Ordering::SeqCst));
+
+        timeout(Duration::from_secs(5), async {
+            while context.inner.counter.load(Ordering::SeqCst) < 2 {
+                sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("scheduled wake should run work again when due");
+    }
+
     #[tokio::test]
     async fn periodic_wake_does_nothing_while_stopped() {
         init_test_tracing();
@@ -643,6 +1083,126 @@ mod test {
         assert!(service.run_token_tx.subscribe().borrow().is_cancelled());
     }
 
+    #[derive(Debug, Clone, Default)]
+    struct FlushRecorderContext {
+        work_counter: Arc<AtomicUsize>,
+        flushed_chats: Arc<Mutex<Vec<ChatId>>>,
+    }
+
+    impl OutboundServiceWork for FlushRecorderContext {
+        async fn work(&self, _run_token: CancellationToken) {
+            self.work_counter.fetch_add(1, Ordering::SeqCst);
+        }
+
+        async fn flush_chat(&self, chat_id: ChatId, _run_token: CancellationToken) {
+            sleep(Duration::from_millis(30)).await;
+            self.flushed_chats.lock().unwrap().push(chat_id);
+        }
+    }
+
+    #[tokio::test]
+    async fn flush_chat_only_flushes_the_chat() {
+        init_test_tracing();
+
+        let context = FlushRecorderContext::default();
+        let service = OutboundService::with_context(context.clone(), global_lock());
+
+        let chat_a = ChatId::new(uuid::Uuid::new_v4());
+        let chat_b = ChatId::new(uuid::Uuid::new_v4());
+
+        // The service is stopped, but the flush is performed anyway.
+        service.flush_chat(chat_a).await;
+        assert_eq!(*context.flushed_chats.lock().unwrap(), [chat_a]);
+        assert_eq!(0, context.work_counter.load(Ordering::SeqCst));
+
+        service.start().await;
+        assert_eq!(1, context.work_counter.load(Ordering::SeqCst));
+        service.flush_chat(chat_b).await;
+        assert_eq!(*context.flushed_chats.lock().unwrap(), [chat_a, chat_b]);
+        assert_eq!(1, context.work_counter.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn pause_prevents_work() {
+        init_test_tracing();
+
+        let context = DelayedCounterContext::default();
+        let service = OutboundService::with_context(context.clone(), global_lock());
+
+        service.start().await; // +1 => counter = 1
+        service.pause().await;
+        assert!(service.is_paused());
+
+        service.notify_work().await;
+        service.start().await;
+        service.run_once().await;
+        assert_eq!(1, context.counter.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn pause_cancels_running_work() {
+        init_test_tracing();
+
+        let context = DelayedCounterContext::default();
+        let service = OutboundService::with_context(context.clone(), global_lock());
+
+        service.start();
+        service.pause().await;
+        assert_eq!(0, context.counter.load(Ordering::SeqCst));
+
+        // The service was started before it was paused, so resuming processes the backlog.
+        service.resume().await;
+        assert!(!service.is_paused());
+        assert_eq!(1, context.counter.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn resume_starts_service_started_while_paused() {
+        init_test_tracing();
+
+        let context = DelayedCounterContext::default();
+        let service = OutboundService::with_context(context.clone(), global_lock());
+
+        service.pause().await;
+        service.start().await;
+        assert_eq!(0, context.counter.load(Ordering::SeqCst));
+
+        service.resume().await;
+        assert_eq!(1, context.counter.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn resume_does_not_start_stopped_service() {
+        init_test_tracing();
+
+        let context = DelayedCounterContext::default();
+        let service = OutboundService::with_context(context.clone(), global_lock());
+
+        service.start().await; // +1 => counter = 1
+        service.pause().await;
+        service.stop().await;
+        service.resume().await;
+        service.notify_work().await;
+        assert_eq!(1, context.counter.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn pause_prevents_flush() {
+        init_test_tracing();
+
+        let context = FlushRecorderContext::default();
+        let service = OutboundService::with_context(context.clone(), global_lock());
+
+        let chat_id = ChatId::new(uuid::Uuid::new_v4());
+        service.pause().await;
+        service.flush_chat(chat_id).await;
+        assert!(context.flushed_chats.lock().unwrap().is_empty());
+
+        service.resume().await;
+        service.flush_chat(chat_id).await;
+        assert_eq!(*context.flushed_chats.lock().unwrap(), [chat_id]);
+    }
+
     #[derive(Clone)]
     struct BlockingWork {
         gate: Arc<Notify>,
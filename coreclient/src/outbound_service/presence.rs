@@ -0,0 +1,132 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Ephemeral presence updates.
+//!
+//! Like typing signals, presence updates are queued in memory and sent by the background task of
+//! the [`OutboundService`], and only to connection chats in which all members support them.
+
+use std::collections::HashMap;
+
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use crate::{
+    ChatId, Contact, clients::block_contact::BlockedContact, contacts::presence::PresenceState,
+};
+
+use super::{OutboundService, OutboundServiceContext, typing::EphemeralOutcome};
+
+/// Presence updates waiting to be sent by the background task.
+#[derive(Debug, Default)]
+pub(crate) struct PresenceQueue {
+    /// State to be published to all contacts
+    state: Option<PresenceState>,
+    /// Chats to which a previously published state has to be sent again after a collision
+    retries: HashMap<ChatId, PresenceState>,
+}
+
+impl PresenceQueue {
+    /// Queues the state for all contacts. It supersedes all pending retries.
+    fn push(&mut self, state: PresenceState) {
+        self.state = Some(state);
+        self.retries.clear();
+    }
+
+    /// Queues the state to be sent again to the chat, unless a newer state is queued.
+    fn retry(&mut self, chat_id: ChatId, state: PresenceState) {
+        if self.state.is_none() {
+            self.retries.entry(chat_id).or_insert(state);
+        }
+    }
+
+    fn take(&mut self) -> (Option<PresenceState>, HashMap<ChatId, PresenceState>) {
+        (self.state.take(), std::mem::take(&mut self.retries))
+    }
+}
+
+impl OutboundService {
+    /// Queues the presence state to be published to the connection chats of all contacts.
+    ///
+    /// Only the latest queued state is sent. Presence updates are best-effort: they are not
+    /// retried if sending fails.
+    pub fn publish_presence(&self, state: PresenceState) {
+        self.context.presence_queue.lock().unwrap().push(state);
+        self.notify_work();
+    }
+}
+
+impl OutboundServiceContext {
+    /// Sends the queued presence updates.
+    pub(super) async fn send_queued_presence(
+        &self,
+        run_token: &CancellationToken,
+    ) -> anyhow::Result<()> {
+        let (state, retries) = self.presence_queue.lock().unwrap().take();
+        let mut pending: Vec<(ChatId, PresenceState)> = retries.into_iter().collect();
+        if let Some(state) = state {
+            let contacts = Contact::load_all(self.db.read().await?).await?;
+            for contact in contacts {
+                if BlockedContact::check_blocked(self.db.read().await?, &contact.user_id).await? {
+                    continue;
+                }
+                pending.push((contact.chat_id, state));
+            }
+        }
+
+        for (chat_id, state) in pending {
+            if run_token.is_cancelled() {
+                // Keep the update for the next run
+                self.presence_queue.lock().unwrap().retry(chat_id, state);
+                continue;
+            }
+            let outcome = self
+                .send_ephemeral(chat_id, state.to_content()?, |features| features.presence)
+                .await;
+            match outcome {
+                Ok(EphemeralOutcome::Sent | EphemeralOutcome::Skipped) => {}
+                Ok(EphemeralOutcome::Collided) => {
+                    debug!(
+                        %chat_id,
+                        "Presence update collided, re-queuing for a later run"
+                    );
+                    self.presence_queue.lock().unwrap().retry(chat_id, state);
+                }
+                Err(error) => warn!(%chat_id, %error, "Failed to publish presence"),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_presence_supersedes_retries() {
+        let mut queue = PresenceQueue::default();
+        let chat_a = ChatId::new(uuid::Uuid::new_v4());
+        let chat_b = ChatId::new(uuid::Uuid::new_v4());
+
+        queue.push(PresenceState::Online);
+        let (state, retries) = queue.take();
+        assert_eq!(state, Some(PresenceState::Online));
+        assert!(retries.is_empty());
+
+        queue.retry(chat_a, PresenceState::Online);
+        queue.push(PresenceState::Away);
+        // The newer state is sent to all contacts anyway
+        queue.retry(chat_b, PresenceState::Online);
+
+        let (state, retries) = queue.take();
+        assert_eq!(state, Some(PresenceState::Away));
+        assert!(retries.is_empty());
+
+        queue.retry(chat_a, PresenceState::Away);
+        let (state, retries) = queue.take();
+        assert_eq!(state, None);
+        assert_eq!(retries, HashMap::from([(chat_a, PresenceState::Away)]));
+    }
+}
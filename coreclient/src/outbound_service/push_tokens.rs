@@ -86,7 +86,7 @@ impl OutboundServiceContext {
             .qs_update_client(
                 self.qs_client_id,
                 queue_encryption_key.clone(),
-                encrypted_push_token,
+                encrypted_push_token.into_iter().collect(),
                 &signing_key,
             )
             .await
@@ -30,7 +30,10 @@ mod persistence {
     use tracing::debug;
     use uuid::Uuid;
 
-    use crate::{ChatId, db::access::WriteConnection};
+    use crate::{
+        ChatId,
+        db::access::{ReadConnection, WriteConnection},
+    };
 
     use super::*;
 
@@ -118,6 +121,14 @@ mod persistence {
             Ok(Some((chat_id, statuses)))
         }
 
+        /// Returns the number of queued receipts.
+        pub(crate) async fn count(mut connection: impl ReadConnection) -> sqlx::Result<usize> {
+            query_scalar!(r#"SELECT COUNT(*) AS "count: _" FROM receipt_queue"#)
+                .fetch_one(connection.as_mut())
+                .await
+                .map(|n: u32| n.try_into().expect("usize overflow"))
+        }
+
         pub(crate) async fn remove(
             mut connection: impl WriteConnection,
             task_id: Uuid,
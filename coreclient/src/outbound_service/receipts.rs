@@ -28,20 +28,38 @@ use crate::{
     outbound_service::{
         error::{OutboundServiceError, classify_ds_error},
         resync::Resync,
+        stats::OutboundKind,
     },
 };
 
-use super::{OutboundService, OutboundServiceContext, receipt_queue::ReceiptQueue};
+use super::{
+    OutboundService, OutboundServiceContext, chat_focus::DeferredReadReceipt,
+    receipt_queue::ReceiptQueue,
+};
 
 impl OutboundService {
+    /// Enqueues the given receipts for sending.
+    ///
+    /// Read receipts are enqueued regardless of whether the chat is focused, and replace any
+    /// deferred read receipts of the same messages (see [`OutboundService::set_chat_focused`]).
     pub async fn enqueue_receipts<'a>(
         &self,
         chat_id: ChatId,
         statuses: impl Iterator<Item = (MessageId, &'a MimiId, MessageStatus)> + Send,
     ) -> anyhow::Result<()> {
+        let statuses: Vec<_> = statuses.collect();
+        let read_message_ids: HashSet<MessageId> = statuses
+            .iter()
+            .filter(|(_, _, status)| *status == MessageStatus::Read)
+            .map(|(message_id, _, _)| *message_id)
+            .collect();
+        let statuses = statuses.into_iter();
         self.context
             .db
             .with_write_transaction(async |txn| {
+                if !read_message_ids.is_empty() {
+                    DeferredReadReceipt::remove(&mut *txn, &read_message_ids).await?;
+                }
                 self.schedule_receipts(txn, chat_id, statuses).await
             })
             .await?;
@@ -109,6 +127,7 @@ impl OutboundServiceContext {
                 Ok(Some(receipt)) => match self.send_chat_receipt(chat_id, receipt).await {
                     Ok(ReceiptSendOutcome::Sent) => {
                         ReceiptQueue::remove(self.db.write().await?, task_id).await?;
+                        self.counters.sent(OutboundKind::Receipt);
                     }
                     Ok(ReceiptSendOutcome::Collided { delivered }) => {
                         // A sibling already delivered `delivered`; drop just those
@@ -116,15 +135,18 @@ impl OutboundServiceContext {
                         // re-encrypted and resent at a later generation.
                         ReceiptQueue::remove_delivered(self.db.write().await?, task_id, &delivered)
                             .await?;
+                        self.counters.retried(OutboundKind::Receipt);
                         continue;
                     }
                     Err(OutboundServiceError::Fatal(error)) => {
                         error!(%error, ?chat_id, "Failed to send receipt; dropping");
+                        self.counters.send_failed(OutboundKind::Receipt);
                         ReceiptQueue::remove(self.db.write().await?, task_id).await?;
                         continue;
                     }
                     Err(OutboundServiceError::Recoverable(error)) => {
                         error!(%error, "Failed to send receipt; will retry later");
+                        self.counters.retried(OutboundKind::Receipt);
                         // Don't unlock the receipts now; they will be unlocked after a threshold.
                         continue;
                     }
@@ -135,6 +157,7 @@ impl OutboundServiceContext {
                 }
                 Err(error) => {
                     error!(%error, "Failed to create receipt; dropping");
+                    self.counters.send_failed(OutboundKind::Receipt);
                     // There is no chance we will be able to create a receipt next time
                     // => Remove from the queue
                     ReceiptQueue::remove(self.db.write().await?, task_id).await?;
@@ -0,0 +1,105 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use chrono::{DateTime, Utc};
+
+use crate::{ChatId, MessageId};
+
+/// A chat message which is sent once `send_at` is due.
+pub(crate) struct ScheduledMessageQueue {
+    chat_id: ChatId,
+    message_id: MessageId,
+    send_at: DateTime<Utc>,
+}
+
+impl ScheduledMessageQueue {
+    pub(crate) fn new(chat_id: ChatId, message_id: MessageId, send_at: DateTime<Utc>) -> Self {
+        Self {
+            chat_id,
+            message_id,
+            send_at,
+        }
+    }
+}
+
+mod persistence {
+    use sqlx::{query, query_scalar};
+    use tracing::debug;
+
+    use crate::db::access::{ReadConnection, WriteConnection};
+
+    use super::*;
+
+    impl ScheduledMessageQueue {
+        pub(crate) async fn enqueue(
+            &self,
+            mut connection: impl WriteConnection,
+        ) -> sqlx::Result<()> {
+            debug!(?self.message_id, %self.send_at, "Scheduling chat message");
+            query!(
+                "INSERT INTO scheduled_message (message_id, chat_id, send_at)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT (message_id) DO UPDATE SET send_at = excluded.send_at",
+                self.message_id,
+                self.chat_id,
+                self.send_at,
+            )
+            .execute(connection.as_mut())
+            .await?;
+            Ok(())
+        }
+
+        /// Removes all messages which are due at `now` from the queue.
+        ///
+        /// Returns the removed messages ordered by their due time.
+        pub(crate) async fn dequeue_due(
+            mut connection: impl WriteConnection,
+            now: DateTime<Utc>,
+        ) -> sqlx::Result<Vec<(ChatId, MessageId)>> {
+            let mut due = query!(
+                r#"DELETE FROM scheduled_message
+                WHERE send_at <= ?1
+                RETURNING
+                    message_id AS "message_id: MessageId",
+                    chat_id AS "chat_id: ChatId",
+                    send_at AS "send_at: DateTime<Utc>""#,
+                now,
+            )
+            .fetch_all(connection.as_mut())
+            .await?;
+            due.sort_by_key(|record| record.send_at);
+            Ok(due
+                .into_iter()
+                .map(|record| (record.chat_id, record.message_id))
+                .collect())
+        }
+
+        /// Returns the earliest due time of all scheduled messages, if any.
+        pub(crate) async fn next_send_at(
+            mut connection: impl ReadConnection,
+        ) -> sqlx::Result<Option<DateTime<Utc>>> {
+            query_scalar!(
+                r#"SELECT MIN(send_at) AS "send_at: DateTime<Utc>" FROM scheduled_message"#
+            )
+            .fetch_one(connection.as_mut())
+            .await
+        }
+
+        /// Removes the message from the queue.
+        ///
+        /// Returns `false` if the message was not scheduled.
+        pub(crate) async fn remove(
+            mut connection: impl WriteConnection,
+            message_id: MessageId,
+        ) -> sqlx::Result<bool> {
+            let res = query!(
+                "DELETE FROM scheduled_message WHERE message_id = ?",
+                message_id
+            )
+            .execute(connection.as_mut())
+            .await?;
+            Ok(res.rows_affected() > 0)
+        }
+    }
+}
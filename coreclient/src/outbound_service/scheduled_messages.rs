@@ -0,0 +1,39 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use chrono::{DateTime, Utc};
+use tracing::debug;
+
+use crate::outbound_service::{
+    chat_message_queue::ChatMessageQueue, scheduled_message_queue::ScheduledMessageQueue,
+};
+
+use super::OutboundServiceContext;
+
+impl OutboundServiceContext {
+    /// Moves the scheduled messages which are due to the chat message queue.
+    ///
+    /// The messages are sent by the same run of the service.
+    pub(super) async fn enqueue_due_scheduled_messages(&self) -> anyhow::Result<()> {
+        let now = Utc::now();
+        self.db
+            .with_write_transaction(async |txn| {
+                for (chat_id, message_id) in
+                    ScheduledMessageQueue::dequeue_due(&mut *txn, now).await?
+                {
+                    debug!(?message_id, "Scheduled message is due");
+                    ChatMessageQueue::new(chat_id, message_id)
+                        .enqueue(&mut *txn)
+                        .await?;
+                }
+                Ok(())
+            })
+            .await
+    }
+
+    /// Returns when the next scheduled message is due, if any.
+    pub(super) async fn next_scheduled_message_at(&self) -> anyhow::Result<Option<DateTime<Utc>>> {
+        Ok(ScheduledMessageQueue::next_send_at(self.db.read().await?).await?)
+    }
+}
@@ -0,0 +1,171 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Observability of the outbound service.
+//!
+//! The service records metrics via the [`metrics`] crate, which are exported if the application
+//! installs a recorder. Independently of that, the service keeps in-memory counters which are
+//! exposed as a snapshot via [`OutboundService::stats`].
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use aircommon::time::TimeStamp;
+use chrono::Utc;
+use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
+
+use crate::outbound_service::{chat_message_queue::ChatMessageQueue, receipt_queue::ReceiptQueue};
+
+use super::{OutboundService, OutboundServiceContext};
+
+const METRIC_AIR_CLIENT_QUEUED_MESSAGES: &str = "air_client_outbound_queued_messages";
+const METRIC_AIR_CLIENT_QUEUED_RECEIPTS: &str = "air_client_outbound_queued_receipts";
+const METRIC_AIR_CLIENT_SENT: &str = "air_client_outbound_sent_total";
+const METRIC_AIR_CLIENT_SEND_FAILURES: &str = "air_client_outbound_send_failures_total";
+const METRIC_AIR_CLIENT_SEND_RETRIES: &str = "air_client_outbound_send_retries_total";
+const METRIC_AIR_CLIENT_TIME_IN_QUEUE: &str = "air_client_outbound_message_time_in_queue_seconds";
+
+/// Kind of an item sent by the outbound service; used as metrics label.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum OutboundKind {
+    Message,
+    Receipt,
+}
+
+impl OutboundKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Message => "message",
+            Self::Receipt => "receipt",
+        }
+    }
+}
+
+/// Snapshot of the state of the outbound service.
+///
+/// Counters are accumulated since the service was created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutboundServiceStats {
+    /// Number of chat messages waiting to be sent
+    pub queued_messages: usize,
+    /// Number of receipts waiting to be sent
+    pub queued_receipts: usize,
+    /// Number of sent chat messages
+    pub sent_messages: u64,
+    /// Number of sent receipts
+    pub sent_receipts: u64,
+    /// Number of chat messages and receipts which failed to be sent
+    pub send_failures: u64,
+    /// Number of chat messages and receipts which are retried in a later run
+    pub retries: u64,
+    /// Time the most recently sent chat message spent in the queue
+    pub last_time_in_queue: Option<Duration>,
+}
+
+impl OutboundServiceStats {
+    /// Returns whether there are chat messages waiting to be sent.
+    pub fn is_sending(&self) -> bool {
+        self.queued_messages > 0
+    }
+}
+
+/// Counters of the outbound service, shared between the service and its background task.
+#[derive(Debug, Default, Clone)]
+pub(super) struct OutboundServiceCounters {
+    stats: Arc<Mutex<OutboundServiceStats>>,
+}
+
+impl OutboundServiceCounters {
+    pub(super) fn describe_metrics() {
+        describe_gauge!(
+            METRIC_AIR_CLIENT_QUEUED_MESSAGES,
+            "Number of chat messages waiting to be sent"
+        );
+        describe_gauge!(
+            METRIC_AIR_CLIENT_QUEUED_RECEIPTS,
+            "Number of receipts waiting to be sent"
+        );
+        describe_counter!(
+            METRIC_AIR_CLIENT_SENT,
+            "Number of sent messages and receipts"
+        );
+        describe_counter!(
+            METRIC_AIR_CLIENT_SEND_FAILURES,
+            "Number of messages and receipts which failed to be sent"
+        );
+        describe_counter!(
+            METRIC_AIR_CLIENT_SEND_RETRIES,
+            "Number of messages and receipts which are retried in a later run"
+        );
+        describe_histogram!(
+            METRIC_AIR_CLIENT_TIME_IN_QUEUE,
+            metrics::Unit::Seconds,
+            "Time a chat message spent in the queue before it was sent"
+        );
+    }
+
+    pub(super) fn sent(&self, kind: OutboundKind) {
+        counter!(METRIC_AIR_CLIENT_SENT, "kind" => kind.label()).increment(1);
+        let mut stats = self.stats.lock().unwrap();
+        match kind {
+            OutboundKind::Message => stats.sent_messages += 1,
+            OutboundKind::Receipt => stats.sent_receipts += 1,
+        }
+    }
+
+    pub(super) fn send_failed(&self, kind: OutboundKind) {
+        counter!(METRIC_AIR_CLIENT_SEND_FAILURES, "kind" => kind.label()).increment(1);
+        self.stats.lock().unwrap().send_failures += 1;
+    }
+
+    pub(super) fn retried(&self, kind: OutboundKind) {
+        counter!(METRIC_AIR_CLIENT_SEND_RETRIES, "kind" => kind.label()).increment(1);
+        self.stats.lock().unwrap().retries += 1;
+    }
+
+    /// Records the time a sent message spent in the queue since it was enqueued at `enqueued_at`.
+    pub(super) fn time_in_queue(&self, enqueued_at: TimeStamp) {
+        let time_in_queue = (Utc::now() - *enqueued_at).to_std().unwrap_or_default();
+        histogram!(METRIC_AIR_CLIENT_TIME_IN_QUEUE).record(time_in_queue.as_secs_f64());
+        self.stats.lock().unwrap().last_time_in_queue = Some(time_in_queue);
+    }
+
+    fn snapshot(&self) -> OutboundServiceStats {
+        *self.stats.lock().unwrap()
+    }
+}
+
+impl OutboundService {
+    /// Returns a snapshot of the state of the service.
+    pub async fn stats(&self) -> anyhow::Result<OutboundServiceStats> {
+        let (queued_messages, queued_receipts) = self.context.queue_sizes().await?;
+        Ok(OutboundServiceStats {
+            queued_messages,
+            queued_receipts,
+            ..self.context.counters.snapshot()
+        })
+    }
+}
+
+impl OutboundServiceContext {
+    async fn queue_sizes(&self) -> anyhow::Result<(usize, usize)> {
+        self.db
+            .with_read_transaction(async |txn| -> anyhow::Result<_> {
+                let messages = ChatMessageQueue::count(&mut *txn).await?;
+                let receipts = ReceiptQueue::count(txn).await?;
+                Ok((messages, receipts))
+            })
+            .await
+    }
+
+    /// Records the current sizes of the message and receipt queues as gauges.
+    pub(super) async fn record_queue_sizes(&self) -> anyhow::Result<()> {
+        let (queued_messages, queued_receipts) = self.queue_sizes().await?;
+        gauge!(METRIC_AIR_CLIENT_QUEUED_MESSAGES).set(queued_messages as f64);
+        gauge!(METRIC_AIR_CLIENT_QUEUED_RECEIPTS).set(queued_receipts as f64);
+        Ok(())
+    }
+}
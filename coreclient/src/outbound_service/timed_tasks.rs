@@ -2,6 +2,8 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::sync::atomic::Ordering;
+
 use aircommon::identifiers::USERNAME_REFRESH_THRESHOLD;
 use airprotos::{auth_service::v1::OperationType, client::group::GroupData};
 use chrono::{DateTime, Duration, Utc};
@@ -15,6 +17,7 @@ use uuid::Uuid;
 use crate::{
     Chat, ChatAttributes, ChatId,
     chats::{GroupDataExt, GroupDataProfilePart},
+    clients::own_client_info::OwnClientInfo,
     groups::Group,
     job::{
         JobError,
@@ -26,7 +29,7 @@ use crate::{
     usernames::UsernameRecord,
 };
 
-use super::OutboundServiceContext;
+use super::{OutboundService, OutboundServiceContext};
 
 /// Number of key packages to upload (excluding the last resort key package)
 #[cfg(not(feature = "test_utils"))]
@@ -167,11 +170,34 @@ struct TimedTaskContext {
     loaded_credentials: bool,
 }
 
+impl OutboundService {
+    /// Enables or disables the execution of timed tasks.
+    ///
+    /// Other work of the service, like sending messages, is not affected. Timed tasks which
+    /// became due while disabled are executed on the next run, which is triggered right away
+    /// when the tasks are enabled again and the service is started.
+    pub fn set_timed_tasks_enabled(&self, enabled: bool) {
+        let was_enabled = self
+            .context
+            .timed_tasks_enabled
+            .swap(enabled, Ordering::Relaxed);
+        debug!(enabled, "Setting timed tasks enabled");
+        if enabled && !was_enabled {
+            self.notify_work();
+        }
+    }
+}
+
 impl OutboundServiceContext {
     pub(super) async fn execute_timed_tasks(
         &self,
         run_token: &CancellationToken,
     ) -> anyhow::Result<()> {
+        if !self.timed_tasks_enabled.load(Ordering::Relaxed) {
+            debug!("Timed tasks are disabled; skipping");
+            return Ok(());
+        }
+
         self.ensure_timed_tasks_exist().await?;
 
         let mut timed_task_context = TimedTaskContext {
@@ -429,19 +455,24 @@ impl OutboundServiceContext {
         let key_packages = self
             .db
             .with_write_transaction(async |txn| {
+                let ciphersuite = OwnClientInfo::load_ciphersuite(&mut *txn).await?;
                 let mut key_packages = Vec::with_capacity(KEY_PACKAGES + 1);
                 for _ in 0..KEY_PACKAGES {
                     let kp = self.key_store.generate_key_package(
                         &mut *txn,
                         &self.qs_client_id,
                         false,
+                        ciphersuite,
                     )?;
                     key_packages.push(kp);
                 }
 
-                let last_resort_kp =
-                    self.key_store
-                        .generate_key_package(txn, &self.qs_client_id, true)?;
+                let last_resort_kp = self.key_store.generate_key_package(
+                    txn,
+                    &self.qs_client_id,
+                    true,
+                    ciphersuite,
+                )?;
                 key_packages.push(last_resort_kp);
 
                 Ok::<_, anyhow::Error>(key_packages)
@@ -817,17 +848,14 @@ mod persistence {
     mod test {
         use aircommon::{
             codec::PersistenceCodec, credentials::test_utils::create_test_credentials,
-            identifiers::UserId,
+            identifiers::UserId, mls_group_config::DEFAULT_CIPHERSUITE,
         };
         use openmls::prelude::{CredentialWithKey, KeyPackage, SignaturePublicKey};
         use openmls_traits::OpenMlsProvider;
         use sqlx::{Row, SqlitePool, query, query_scalar};
         use url::Host;
 
-        use crate::{
-            clients::CIPHERSUITE, db::access::DbAccess,
-            groups::openmls_provider::AirOpenMlsProvider,
-        };
+        use crate::{db::access::DbAccess, groups::openmls_provider::AirOpenMlsProvider};
 
         use super::*;
 
@@ -858,7 +886,7 @@ mod persistence {
                 .map(|_| {
                     let bundle = KeyPackage::builder()
                         .build(
-                            CIPHERSUITE,
+                            DEFAULT_CIPHERSUITE,
                             &provider,
                             &client_sk,
                             credential_with_key.clone(),
@@ -0,0 +1,272 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Ephemeral typing signals.
+//!
+//! A typing signal is a MIMI message with the [`TYPING_CONTENT_TYPE`] content type. It is neither
+//! stored nor retried, and recipients surface it as a
+//! [`DbNotification`](crate::db::notification::DbNotification) instead of a message. Signals are
+//! queued in memory and sent by the background task of the [`OutboundService`], so they don't race
+//! with other messages for the same MLS generation and respect [`OutboundService::pause`].
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use aircommon::crypto::secrets::Secret;
+use airprotos::client::component::AirFeatures;
+use anyhow::Context;
+use mimi_content::{Disposition, MimiContent, NestedPart};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use crate::{
+    Chat, ChatId, ChatStatus,
+    groups::{Group, handle_group_not_found_on_ds},
+    job::pending_chat_operation::PendingChatOperation,
+    outbound_service::resync::Resync,
+};
+
+use super::{OutboundService, OutboundServiceContext};
+
+/// Content type of a typing signal.
+pub(crate) const TYPING_CONTENT_TYPE: &str = "application/vnd.air.typing";
+
+/// Minimum interval between two typing signals sent to the same chat.
+///
+/// A queued signal which could not be sent within this interval is dropped.
+const TYPING_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Tracks when the last typing signal was sent to each chat.
+#[derive(Debug, Default)]
+pub(crate) struct TypingThrottle {
+    last_sent: HashMap<ChatId, Instant>,
+}
+
+impl TypingThrottle {
+    /// Returns whether a typing signal may be sent to the chat at `now`, and if so, records it as
+    /// sent.
+    fn try_acquire(&mut self, chat_id: ChatId, now: Instant) -> bool {
+        if let Some(last_sent) = self.last_sent.get(&chat_id)
+            && now.saturating_duration_since(*last_sent) < TYPING_INTERVAL
+        {
+            return false;
+        }
+        self.last_sent.insert(chat_id, now);
+        true
+    }
+}
+
+/// Typing signals waiting to be sent by the background task, with the time they were queued.
+#[derive(Debug, Default)]
+pub(crate) struct TypingQueue {
+    queued: HashMap<ChatId, Instant>,
+}
+
+impl TypingQueue {
+    fn push(&mut self, chat_id: ChatId, queued_at: Instant) {
+        self.queued.entry(chat_id).or_insert(queued_at);
+    }
+
+    /// Takes all queued signals which are not older than [`TYPING_INTERVAL`] at `now`.
+    ///
+    /// Older signals are dropped, since the user might have stopped typing in the meantime.
+    fn take_fresh(&mut self, now: Instant) -> Vec<(ChatId, Instant)> {
+        self.queued
+            .drain()
+            .filter(|(_, queued_at)| now.saturating_duration_since(*queued_at) < TYPING_INTERVAL)
+            .collect()
+    }
+}
+
+/// Outcome of sending an ephemeral message; see [`OutboundServiceContext::send_ephemeral`].
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum EphemeralOutcome {
+    Sent,
+    /// The message was not sent, because the chat is not active, has a pending group change, or
+    /// not all members support the message.
+    Skipped,
+    /// A sibling client took the MLS generation or the group moved to a new epoch. The message can
+    /// be sent again in a later run.
+    Collided,
+}
+
+impl OutboundService {
+    /// Queues a typing signal to the chat with the given id.
+    ///
+    /// At most one signal per [`TYPING_INTERVAL`] is queued for a chat; further calls are ignored.
+    /// The signal is sent by the background task, unless it is older than [`TYPING_INTERVAL`] by
+    /// then. Typing signals are best-effort: they are not retried if sending fails.
+    pub fn send_typing(&self, chat_id: ChatId) {
+        let now = Instant::now();
+        let acquired = self.typing.lock().unwrap().try_acquire(chat_id, now);
+        if !acquired {
+            debug!(?chat_id, "Skipping typing signal due to rate limit");
+            return;
+        }
+        self.context.typing_queue.lock().unwrap().push(chat_id, now);
+        self.notify_work();
+    }
+}
+
+impl OutboundServiceContext {
+    /// Sends the queued typing signals.
+    pub(super) async fn send_queued_typing(
+        &self,
+        run_token: &CancellationToken,
+    ) -> anyhow::Result<()> {
+        let queued = self.typing_queue.lock().unwrap().take_fresh(Instant::now());
+        for (chat_id, queued_at) in queued {
+            if run_token.is_cancelled() {
+                // Keep the signal for the next run
+                self.typing_queue.lock().unwrap().push(chat_id, queued_at);
+                continue;
+            }
+            let outcome = self
+                .send_ephemeral(chat_id, typing_content()?, |features| {
+                    features.typing_indicators
+                })
+                .await;
+            match outcome {
+                Ok(EphemeralOutcome::Sent | EphemeralOutcome::Skipped) => {}
+                Ok(EphemeralOutcome::Collided) => {
+                    debug!(
+                        ?chat_id,
+                        "Typing signal collided, re-queuing for a later run"
+                    );
+                    self.typing_queue.lock().unwrap().push(chat_id, queued_at);
+                }
+                Err(error) => warn!(%error, ?chat_id, "Failed to send typing signal"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends an ephemeral message with the given content directly to the DS.
+    ///
+    /// The message is skipped if the chat is not active, has a pending group change, or if not all
+    /// members of the group support it according to `supported`.
+    pub(super) async fn send_ephemeral(
+        &self,
+        chat_id: ChatId,
+        content: MimiContent,
+        supported: impl Fn(&AirFeatures) -> bool,
+    ) -> anyhow::Result<EphemeralOutcome> {
+        let chat = self
+            .db
+            .with_read_transaction(async |txn| Chat::load(txn, &chat_id).await)
+            .await?
+            .with_context(|| format!("Can't find chat with id {chat_id}"))?;
+        if !matches!(chat.status(), ChatStatus::Active) {
+            return Ok(EphemeralOutcome::Skipped);
+        }
+
+        // Don't interfere with a pending resync or chat operation
+        if Resync::is_pending_for_chat(self.db.read().await?, &chat_id).await?
+            || PendingChatOperation::is_pending_for_chat(self.db.read().await?, chat_id).await?
+        {
+            debug!(
+                ?chat_id,
+                "Skipping ephemeral message due to pending group change"
+            );
+            return Ok(EphemeralOutcome::Skipped);
+        }
+
+        let group_id = chat.group_id();
+        let group = Group::load(self.db.read().await?, group_id)
+            .await?
+            .with_context(|| format!("Can't find group with id {group_id:?}"))?;
+        let all_supported = group.members_air_component().all(|component| {
+            component
+                .map(|component| supported(&component.features))
+                .unwrap_or(false)
+        });
+        if !all_supported {
+            debug!(
+                ?chat_id,
+                "Skipping ephemeral message not supported by all members"
+            );
+            return Ok(EphemeralOutcome::Skipped);
+        }
+
+        let (group_state_ear_key, params) = self.new_mls_message(&chat, content, None).await?;
+        let epoch = params.epoch;
+        let sent_tags = params.collision_tags.clone();
+        let generation = params.generation;
+
+        if let Err(ds_error) = self
+            .api_clients
+            .get(&chat.owner_domain())?
+            .ds_send_message(params, self.signing_key(), &group_state_ear_key)
+            .await
+        {
+            if ds_error.is_not_found() {
+                self.db
+                    .with_write_transaction(async |txn| {
+                        handle_group_not_found_on_ds(txn, chat.group_id()).await
+                    })
+                    .await?;
+            }
+            // Like queued reactions, the message is re-encrypted in a later run: after a sibling
+            // took the generation, or after the queue messages of the new epoch are processed.
+            if ds_error.is_wrong_epoch() || !ds_error.process_tag_collisions(&sent_tags).is_empty()
+            {
+                return Ok(EphemeralOutcome::Collided);
+            }
+            return Err(ds_error.into());
+        }
+
+        self.confirm_mls_message(&chat, epoch, generation).await?;
+        Ok(EphemeralOutcome::Sent)
+    }
+}
+
+fn typing_content() -> anyhow::Result<MimiContent> {
+    Ok(MimiContent {
+        salt: Secret::<16>::random()?.secret().to_vec(),
+        nested_part: NestedPart::SinglePart {
+            disposition: Disposition::Unspecified,
+            language: String::new(),
+            content_type: TYPING_CONTENT_TYPE.to_owned(),
+            content: Vec::new(),
+        },
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttle_typing_per_chat() {
+        let mut throttle = TypingThrottle::default();
+        let chat_a = ChatId::new(uuid::Uuid::new_v4());
+        let chat_b = ChatId::new(uuid::Uuid::new_v4());
+        let now = Instant::now();
+
+        assert!(throttle.try_acquire(chat_a, now));
+        assert!(!throttle.try_acquire(chat_a, now + Duration::from_secs(1)));
+        assert!(throttle.try_acquire(chat_b, now + Duration::from_secs(1)));
+        assert!(throttle.try_acquire(chat_a, now + TYPING_INTERVAL));
+    }
+
+    #[test]
+    fn drop_stale_typing_signals() {
+        let mut queue = TypingQueue::default();
+        let chat_a = ChatId::new(uuid::Uuid::new_v4());
+        let chat_b = ChatId::new(uuid::Uuid::new_v4());
+        let now = Instant::now();
+
+        queue.push(chat_a, now);
+        queue.push(chat_b, now + Duration::from_secs(2));
+        // Re-queuing keeps the original time
+        queue.push(chat_a, now + Duration::from_secs(3));
+
+        let fresh = queue.take_fresh(now + TYPING_INTERVAL);
+        assert_eq!(fresh, [(chat_b, now + Duration::from_secs(2))]);
+        assert!(queue.take_fresh(now + TYPING_INTERVAL).is_empty());
+    }
+}
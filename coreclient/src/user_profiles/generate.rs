@@ -32,6 +32,7 @@ impl NewUserProfile {
             decryption_key_index,
             display_name,
             profile_picture,
+            status_text: None,
         };
         let signed_profile = profile.sign(signing_key)?;
         Ok(NewUserProfile(signed_profile))
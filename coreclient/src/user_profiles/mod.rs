@@ -21,7 +21,7 @@ use aircommon::{
             signable::{Signable, Signature, SignedStruct, Verifiable, VerifiedStruct},
         },
     },
-    identifiers::UserId,
+    identifiers::{TlsString, UserId},
     messages::client_as_out::EncryptedUserProfileCtype,
 };
 use display_name::BaseDisplayName;
@@ -29,14 +29,19 @@ pub use display_name::{DisplayName, DisplayNameError};
 use sealed::Seal;
 use serde::{Deserialize, Serialize};
 use sqlx::{Database, Decode, Encode, Sqlite, encode::IsNull, error::BoxDynError};
+use status_text::sanitize_status_text;
 use thiserror::Error;
-use tls_codec::{Serialize as _, TlsDeserializeBytes, TlsSerialize, TlsSize};
+use tls_codec::{
+    DeserializeBytes as _, Serialize as _, Size as _, TlsDeserializeBytes, TlsSerialize, TlsSize,
+    VLBytes,
+};
 use tracing::info;
 
 pub mod display_name;
 pub(crate) mod generate;
 pub(crate) mod persistence;
 pub(crate) mod process;
+pub mod status_text;
 #[cfg(test)]
 mod tests;
 pub(crate) mod update;
@@ -87,7 +92,9 @@ pub(crate) struct SignedUserProfile {
 
 impl Verifiable for VerifiableUserProfile {
     fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
-        self.tbs.tls_serialize_detached()
+        // The signature covers the profile as it was received, including extensions of an
+        // unknown type.
+        Ok(self.tbs_bytes.clone())
     }
 
     fn signature(&self) -> impl AsRef<[u8]> {
@@ -112,12 +119,66 @@ impl VerifiedStruct<VerifiableUserProfile> for UnvalidatedUserProfile {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, TlsSize, TlsSerialize, TlsDeserializeBytes)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct VerifiableUserProfile {
     tbs: UnvalidatedUserProfile,
+    tbs_bytes: Vec<u8>,
     signature: ClientSignature,
 }
 
+impl tls_codec::DeserializeBytes for VerifiableUserProfile {
+    fn tls_deserialize_bytes(input: &[u8]) -> Result<(Self, &[u8]), tls_codec::Error> {
+        let (user_id, bytes) = UserId::tls_deserialize_bytes(input)?;
+        let (epoch, bytes) = u64::tls_deserialize_bytes(bytes)?;
+        let (decryption_key_index, bytes) = UserProfileKeyIndex::tls_deserialize_bytes(bytes)?;
+        let (display_name, bytes) = BaseDisplayName::tls_deserialize_bytes(bytes)?;
+        let (profile_picture, bytes) = Option::<Asset>::tls_deserialize_bytes(bytes)?;
+        let (extensions, bytes) = match bytes.split_first() {
+            Some((&USER_PROFILE_EXTENSIONS_MARKER, bytes)) => {
+                Vec::<UserProfileExtension>::tls_deserialize_bytes(bytes)?
+            }
+            _ => (Vec::new(), bytes),
+        };
+        let tbs_bytes = input[..input.len() - bytes.len()].to_vec();
+        let (signature, bytes) = ClientSignature::tls_deserialize_bytes(bytes)?;
+
+        let mut status_text = None;
+        for extension in extensions {
+            match extension.extension_type {
+                STATUS_TEXT_EXTENSION_TYPE if status_text.is_none() => {
+                    status_text = Some(TlsString::tls_deserialize_exact_bytes(
+                        extension.extension_data.as_slice(),
+                    )?);
+                }
+                STATUS_TEXT_EXTENSION_TYPE => {
+                    return Err(tls_codec::Error::DecodingError(
+                        "duplicate user profile extension".to_owned(),
+                    ));
+                }
+                // Extensions of an unknown type are ignored
+                _ => {}
+            }
+        }
+
+        let tbs = UnvalidatedUserProfile {
+            user_id,
+            epoch,
+            decryption_key_index,
+            display_name,
+            profile_picture,
+            status_text,
+        };
+        Ok((
+            Self {
+                tbs,
+                tbs_bytes,
+                signature,
+            },
+            bytes,
+        ))
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum UserProfileValidationError {
     #[error("User profile is outdated")]
@@ -135,6 +196,8 @@ pub struct UserProfile {
     pub user_id: UserId,
     pub display_name: DisplayName,
     pub profile_picture: Option<Asset>,
+    /// Short bio or availability note
+    pub status_text: Option<String>,
 }
 
 impl UserProfile {
@@ -143,6 +206,7 @@ impl UserProfile {
             user_id: user_id.clone(),
             display_name: DisplayName::from_user_id(user_id),
             profile_picture: None,
+            status_text: None,
         }
     }
 }
@@ -153,21 +217,95 @@ impl From<IndexedUserProfile> for UserProfile {
             user_id: user_profile.user_id,
             display_name: user_profile.display_name,
             profile_picture: user_profile.profile_picture,
+            status_text: user_profile
+                .status_text
+                .map(|TlsString(status_text)| status_text),
         }
     }
 }
 
-/// A user profile contains information about a user, such as their display name
-/// and profile picture.
-#[derive(
-    Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserializeBytes, TlsSize, Serialize, Deserialize,
-)]
+/// A user profile contains information about a user, such as their display name,
+/// profile picture and status text.
+///
+/// Fields added after the first version of the profile are encoded as [`UserProfileExtension`]s.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct BaseIndexedUserProfile<const VALIDATED: bool> {
     user_id: UserId,
     epoch: u64,
     decryption_key_index: UserProfileKeyIndex,
     display_name: BaseDisplayName<VALIDATED>,
     profile_picture: Option<Asset>,
+    #[serde(default)]
+    status_text: Option<TlsString>,
+}
+
+/// Marks the start of the extensions of a user profile.
+///
+/// A profile without extensions is directly followed by the signature. The variable-length
+/// encoding of the signature never starts with this byte, since the length prefix `0b11` is
+/// invalid ([RFC 9420, Section 2.1.2]). Therefore, clients which don't know about extensions can
+/// still read profiles without extensions.
+///
+/// [RFC 9420, Section 2.1.2]: https://www.rfc-editor.org/rfc/rfc9420.html#section-2.1.2
+const USER_PROFILE_EXTENSIONS_MARKER: u8 = 0xff;
+
+/// Extension type of the status text of a user profile
+const STATUS_TEXT_EXTENSION_TYPE: u16 = 1;
+
+/// An additional field of a user profile.
+///
+/// Extensions of an unknown type are ignored when reading a profile.
+#[derive(Debug, Clone, PartialEq, Eq, TlsSize, TlsSerialize, TlsDeserializeBytes)]
+struct UserProfileExtension {
+    extension_type: u16,
+    extension_data: VLBytes,
+}
+
+impl<const VALIDATED: bool> BaseIndexedUserProfile<VALIDATED> {
+    fn extensions(&self) -> Result<Vec<UserProfileExtension>, tls_codec::Error> {
+        let mut extensions = Vec::new();
+        if let Some(status_text) = &self.status_text {
+            extensions.push(UserProfileExtension {
+                extension_type: STATUS_TEXT_EXTENSION_TYPE,
+                extension_data: status_text.tls_serialize_detached()?.into(),
+            });
+        }
+        Ok(extensions)
+    }
+}
+
+impl<const VALIDATED: bool> tls_codec::Size for BaseIndexedUserProfile<VALIDATED> {
+    fn tls_serialized_len(&self) -> usize {
+        let extensions_len = match self.extensions() {
+            Ok(extensions) if !extensions.is_empty() => {
+                USER_PROFILE_EXTENSIONS_MARKER.tls_serialized_len()
+                    + extensions.tls_serialized_len()
+            }
+            _ => 0,
+        };
+        self.user_id.tls_serialized_len()
+            + self.epoch.tls_serialized_len()
+            + self.decryption_key_index.tls_serialized_len()
+            + self.display_name.tls_serialized_len()
+            + self.profile_picture.tls_serialized_len()
+            + extensions_len
+    }
+}
+
+impl<const VALIDATED: bool> tls_codec::Serialize for BaseIndexedUserProfile<VALIDATED> {
+    fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<usize, tls_codec::Error> {
+        let mut written = self.user_id.tls_serialize(writer)?;
+        written += self.epoch.tls_serialize(writer)?;
+        written += self.decryption_key_index.tls_serialize(writer)?;
+        written += self.display_name.tls_serialize(writer)?;
+        written += self.profile_picture.tls_serialize(writer)?;
+        let extensions = self.extensions()?;
+        if !extensions.is_empty() {
+            written += USER_PROFILE_EXTENSIONS_MARKER.tls_serialize(writer)?;
+            written += extensions.tls_serialize(writer)?;
+        }
+        Ok(written)
+    }
 }
 
 pub(crate) type IndexedUserProfile = BaseIndexedUserProfile<true>;
@@ -177,7 +315,7 @@ pub(crate) type UnvalidatedUserProfile = BaseIndexedUserProfile<false>;
 impl UnvalidatedUserProfile {
     /// Validates the display name and returns an [`IndexedUserProfile`].
     /// If the display name is invalid, it is replaced with a default
-    /// based on the user id.
+    /// based on the user id. A too long status text is truncated.
     pub fn validate_display_name(self) -> IndexedUserProfile {
         let display_name = self.display_name.validate().unwrap_or_else(|e| {
             info!(error = %e, "Invalid display name, generating default");
@@ -189,6 +327,8 @@ impl UnvalidatedUserProfile {
             decryption_key_index: self.decryption_key_index,
             display_name,
             profile_picture: self.profile_picture,
+            status_text: sanitize_status_text(self.status_text.map(|TlsString(s)| s))
+                .map(TlsString),
         }
     }
 }
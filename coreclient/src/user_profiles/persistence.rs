@@ -2,13 +2,19 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use aircommon::{crypto::indexed_aead::keys::UserProfileKeyIndex, identifiers::UserId};
-use sqlx::{query, query_as};
+use std::collections::HashMap;
+
+use aircommon::{
+    crypto::indexed_aead::keys::UserProfileKeyIndex,
+    identifiers::{Fqdn, TlsString, UserId},
+};
+use sqlx::{QueryBuilder, query, query_as};
 use tracing::error;
+use uuid::Uuid;
 
 use crate::db::access::{DbAccess, ReadConnection, WriteConnection};
 
-use super::{Asset, IndexedUserProfile, UserProfile, display_name::BaseDisplayName};
+use super::{Asset, DisplayName, IndexedUserProfile, UserProfile, display_name::BaseDisplayName};
 
 impl IndexedUserProfile {
     /// Stores this [`BaseIndexedUserProfile`].
@@ -29,14 +35,16 @@ impl IndexedUserProfile {
                 epoch,
                 decryption_key_index,
                 display_name,
-                profile_picture
-            ) VALUES (?, ?, ?, ?, ?, ?)",
+                profile_picture,
+                status_text
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)",
             uuid,
             domain,
             epoch,
             self.decryption_key_index,
             self.display_name,
             self.profile_picture,
+            self.status_text,
         )
         .execute(connection.as_mut())
         .await?;
@@ -46,7 +54,7 @@ impl IndexedUserProfile {
         Ok(())
     }
 
-    /// Update the user's display name and profile picture in the database.
+    /// Update the user's display name, profile picture and status text in the database.
     pub(crate) async fn update(&self, mut connection: impl WriteConnection) -> sqlx::Result<()> {
         let uuid = self.user_id.uuid();
         let domain = self.user_id.domain();
@@ -56,14 +64,16 @@ impl IndexedUserProfile {
                 epoch = ?3,
                 decryption_key_index = ?4,
                 display_name = ?5,
-                profile_picture = ?6
+                profile_picture = ?6,
+                status_text = ?7
             WHERE user_uuid = ?1 AND user_domain = ?2",
             uuid,
             domain,
             epoch,
             self.decryption_key_index,
             self.display_name,
-            self.profile_picture
+            self.profile_picture,
+            self.status_text,
         )
         .execute(connection.as_mut())
         .await?;
@@ -77,6 +87,7 @@ struct SqlUser {
     decryption_key_index: UserProfileKeyIndex,
     display_name: BaseDisplayName<true>,
     profile_picture: Option<Asset>,
+    status_text: Option<TlsString>,
 }
 
 impl From<(UserId, SqlUser)> for IndexedUserProfile {
@@ -88,6 +99,7 @@ impl From<(UserId, SqlUser)> for IndexedUserProfile {
                 decryption_key_index,
                 display_name,
                 profile_picture,
+                status_text,
             },
         ): (UserId, SqlUser),
     ) -> Self {
@@ -97,6 +109,7 @@ impl From<(UserId, SqlUser)> for IndexedUserProfile {
             decryption_key_index,
             display_name,
             profile_picture,
+            status_text,
         }
     }
 }
@@ -114,7 +127,8 @@ impl IndexedUserProfile {
                 epoch AS "epoch: _",
                 decryption_key_index AS "decryption_key_index: _",
                 display_name AS "display_name: _",
-                profile_picture AS "profile_picture: _"
+                profile_picture AS "profile_picture: _",
+                status_text AS "status_text: _"
             FROM user
             WHERE user_uuid = ? AND user_domain = ?"#,
             uuid,
@@ -143,6 +157,100 @@ impl UserProfile {
             .unwrap_or_else(|| UserProfile::from_user_id(user_id))
     }
 
+    /// Loads the display names of the given users with a single query.
+    ///
+    /// The names are returned in the order of `user_ids`. Users without a stored profile get
+    /// the same fallback display name as in [`UserProfile::load`].
+    pub(crate) async fn load_display_names(
+        mut connection: impl ReadConnection,
+        user_ids: &[UserId],
+    ) -> sqlx::Result<Vec<DisplayName>> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut qb = QueryBuilder::new(
+            "SELECT user_uuid, user_domain, display_name FROM user
+            WHERE (user_uuid, user_domain) IN (VALUES ",
+        );
+        let mut values = qb.separated(", ");
+        for user_id in user_ids {
+            values
+                .push("(")
+                .push_bind_unseparated(user_id.uuid())
+                .push_unseparated(", ")
+                .push_bind_unseparated(user_id.domain().clone())
+                .push_unseparated(")");
+        }
+        qb.push(")");
+
+        let mut display_names: HashMap<UserId, DisplayName> = qb
+            .build_query_as::<(Uuid, Fqdn, DisplayName)>()
+            .fetch_all(connection.as_mut())
+            .await?
+            .into_iter()
+            .map(|(uuid, domain, display_name)| (UserId::new(uuid, domain), display_name))
+            .collect();
+
+        Ok(user_ids
+            .iter()
+            .map(|user_id| {
+                display_names
+                    .remove(user_id)
+                    .unwrap_or_else(|| DisplayName::from_user_id(user_id))
+            })
+            .collect())
+    }
+
+    /// Loads the display names of the given users as they are shown to the user, with a single
+    /// query.
+    ///
+    /// For blocked users, the display name they had when they were blocked is returned instead
+    /// of their current one. Users without a stored profile get the same fallback display name
+    /// as in [`UserProfile::load`].
+    pub(crate) async fn load_visible_display_names(
+        mut connection: impl ReadConnection,
+        user_ids: &[UserId],
+    ) -> sqlx::Result<HashMap<UserId, DisplayName>> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut qb = QueryBuilder::new("WITH ids(user_uuid, user_domain) AS (VALUES ");
+        let mut values = qb.separated(", ");
+        for user_id in user_ids {
+            values
+                .push("(")
+                .push_bind_unseparated(user_id.uuid())
+                .push_unseparated(", ")
+                .push_bind_unseparated(user_id.domain().clone())
+                .push_unseparated(")");
+        }
+        qb.push(
+            ") SELECT ids.user_uuid, ids.user_domain, b.last_display_name, u.display_name
+            FROM ids
+            LEFT JOIN blocked_contact b
+                ON b.user_uuid = ids.user_uuid AND b.user_domain = ids.user_domain
+            LEFT JOIN user u
+                ON u.user_uuid = ids.user_uuid AND u.user_domain = ids.user_domain",
+        );
+
+        let rows = qb
+            .build_query_as::<(Uuid, Fqdn, Option<DisplayName>, Option<DisplayName>)>()
+            .fetch_all(connection.as_mut())
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(uuid, domain, last_display_name, display_name)| {
+                let user_id = UserId::new(uuid, domain);
+                let display_name = last_display_name
+                    .or(display_name)
+                    .unwrap_or_else(|| DisplayName::from_user_id(&user_id));
+                (user_id, display_name)
+            })
+            .collect())
+    }
+
     /// Public API for loading a user profile from the database directly.
     pub async fn load_from_db(
         db_access: &DbAccess,
@@ -159,7 +267,10 @@ mod tests {
     use aircommon::crypto::indexed_aead::keys::UserProfileKey;
     use sqlx::SqlitePool;
 
-    use crate::{Asset, db::access::DbAccess, key_stores::indexed_keys::StorableIndexedKey};
+    use crate::{
+        Asset, clients::block_contact::BlockedContact, db::access::DbAccess,
+        key_stores::indexed_keys::StorableIndexedKey,
+    };
 
     use super::*;
 
@@ -172,6 +283,7 @@ mod tests {
             decryption_key_index: user_profile_key.index().clone(),
             display_name: "Alice".parse().unwrap(),
             profile_picture: Some(Asset::Value(vec![1, 2, 3])),
+            status_text: Some(TlsString("On vacation".to_owned())),
         };
         (user_profile, user_profile_key)
     }
@@ -203,6 +315,81 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn load_display_names(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+
+        let (alice, alice_key) = test_profile();
+        alice_key.store(pool.write().await?).await?;
+        alice.store(pool.write().await?, true).await?;
+
+        let (mut bob, bob_key) = test_profile();
+        bob.display_name = "Bob".parse()?;
+        bob_key.store(pool.write().await?).await?;
+        bob.store(pool.write().await?, true).await?;
+
+        let unknown = UserId::random("localhost".parse().unwrap());
+
+        let names = UserProfile::load_display_names(
+            pool.read().await?,
+            &[bob.user_id.clone(), unknown.clone(), alice.user_id.clone()],
+        )
+        .await?;
+        assert_eq!(
+            names,
+            [
+                bob.display_name,
+                DisplayName::from_user_id(&unknown),
+                alice.display_name,
+            ]
+        );
+
+        assert!(
+            UserProfile::load_display_names(pool.read().await?, &[])
+                .await?
+                .is_empty()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn load_visible_display_names(pool: SqlitePool) -> anyhow::Result<()> {
+        let pool = DbAccess::for_tests(pool);
+
+        let (alice, alice_key) = test_profile();
+        alice_key.store(pool.write().await?).await?;
+        alice.store(pool.write().await?, true).await?;
+
+        let (mut bob, bob_key) = test_profile();
+        bob.display_name = "Bob".parse()?;
+        bob_key.store(pool.write().await?).await?;
+        bob.store(pool.write().await?, true).await?;
+
+        // Bob is blocked with his display name at the time of blocking
+        BlockedContact::new(bob.user_id.clone())
+            .store(pool.write().await?)
+            .await?;
+
+        let unknown = UserId::random("localhost".parse().unwrap());
+
+        let names = UserProfile::load_visible_display_names(
+            pool.read().await?,
+            &[alice.user_id.clone(), bob.user_id.clone(), unknown.clone()],
+        )
+        .await?;
+        assert_eq!(
+            names,
+            HashMap::from([
+                (alice.user_id.clone(), alice.display_name),
+                (bob.user_id.clone(), DisplayName::from_user_id(&bob.user_id)),
+                (unknown.clone(), DisplayName::from_user_id(&unknown)),
+            ])
+        );
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn update_load(pool: SqlitePool) -> anyhow::Result<()> {
         let pool = DbAccess::for_tests(pool);
@@ -219,6 +406,7 @@ mod tests {
         let mut new_profile = profile.clone();
         new_profile.display_name = "Alice In Wonderland".parse()?;
         new_profile.profile_picture = None;
+        new_profile.status_text = None;
 
         new_profile.update(pool.write().await?).await?;
         let loaded = IndexedUserProfile::load(pool.read().await?, &profile.user_id)
@@ -0,0 +1,75 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Status text of a user profile, e.g. a short bio or an availability note.
+
+use thiserror::Error;
+
+/// Maximum length of a status text in chars
+pub const MAX_STATUS_TEXT_CHARS: usize = 140;
+
+#[derive(Debug, Error)]
+pub enum StatusTextError {
+    #[error("Status text is longer than {MAX_STATUS_TEXT_CHARS} characters")]
+    StatusTextTooLong,
+}
+
+/// Trims the status text. An empty status text is treated as no status text.
+///
+/// Fails if the trimmed status text is longer than [`MAX_STATUS_TEXT_CHARS`].
+pub(crate) fn normalize_status_text(
+    status_text: Option<String>,
+) -> Result<Option<String>, StatusTextError> {
+    let Some(status_text) = status_text else {
+        return Ok(None);
+    };
+    let status_text = status_text.trim();
+    if status_text.chars().count() > MAX_STATUS_TEXT_CHARS {
+        return Err(StatusTextError::StatusTextTooLong);
+    }
+    Ok((!status_text.is_empty()).then(|| status_text.to_owned()))
+}
+
+/// Same as [`normalize_status_text`], but truncates a too long status text.
+///
+/// Used for status texts received from other users.
+pub(crate) fn sanitize_status_text(status_text: Option<String>) -> Option<String> {
+    let status_text: String = status_text?
+        .trim()
+        .chars()
+        .take(MAX_STATUS_TEXT_CHARS)
+        .collect();
+    let status_text = status_text.trim_end();
+    (!status_text.is_empty()).then(|| status_text.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        assert_eq!(normalize_status_text(None).unwrap(), None);
+        assert_eq!(normalize_status_text(Some("  ".to_owned())).unwrap(), None);
+        assert_eq!(
+            normalize_status_text(Some(" On vacation ".to_owned())).unwrap(),
+            Some("On vacation".to_owned())
+        );
+        let too_long = "a".repeat(MAX_STATUS_TEXT_CHARS + 1);
+        assert!(matches!(
+            normalize_status_text(Some(too_long)),
+            Err(StatusTextError::StatusTextTooLong)
+        ));
+    }
+
+    #[test]
+    fn sanitize() {
+        assert_eq!(sanitize_status_text(Some(" \n ".to_owned())), None);
+        let too_long = format!(" {}", "a".repeat(MAX_STATUS_TEXT_CHARS + 10));
+        assert_eq!(
+            sanitize_status_text(Some(too_long)),
+            Some("a".repeat(MAX_STATUS_TEXT_CHARS))
+        );
+    }
+}
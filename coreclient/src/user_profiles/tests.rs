@@ -8,25 +8,33 @@ use airbackend::auth_service::user_record::UserRecord;
 use aircommon::{
     credentials::{
         AsCredential, AsIntermediateCredentialCsr, ClientCredentialCsr, ClientCredentialPayload,
-        keys::ClientSigningKey,
+        keys::{ClientSigningKey, PreliminaryClientKeyType},
     },
     crypto::{
-        indexed_aead::{ciphertexts::IndexEncryptable, keys::UserProfileKey},
-        signatures::signable::Signable,
+        indexed_aead::keys::UserProfileKeyIndex,
+        indexed_aead::{
+            ciphertexts::{IndexDecryptable, IndexEncryptable},
+            keys::UserProfileKey,
+        },
+        signatures::signable::{Signable, Signature, SignedStruct, Verifiable},
     },
-    identifiers::{Fqdn, UserId},
+    identifiers::{Fqdn, TlsString, UserId},
 };
 use openmls::prelude::SignatureScheme;
 use sqlx::SqlitePool;
+use tls_codec::{DeserializeBytes as _, Serialize as _, TlsSerialize, TlsSize};
 
 use crate::{
     DisplayName, UserProfile,
     db::access::{DbAccess, WriteConnection},
     key_stores::indexed_keys::StorableIndexedKey,
-    user_profiles::{IndexedUserProfile, update::UserProfileUpdate},
+    user_profiles::{
+        IndexedUserProfile, UnvalidatedUserProfile, VerifiableUserProfile,
+        update::UserProfileUpdate,
+    },
 };
 
-use super::{Asset, generate::NewUserProfile};
+use super::{Asset, USER_PROFILE_LABEL, UserProfileExtension, generate::NewUserProfile};
 
 #[test]
 fn backend_interaction() {
@@ -86,17 +94,20 @@ fn backend_interaction() {
         decryption_key_index: user_profile_key.index().clone(),
         display_name,
         profile_picture: profile_picture.clone(),
+        status_text: None,
     };
     let new_user_profile = UserProfile {
         user_id: user_id.clone(),
         display_name: "Alice Wonderland".parse().unwrap(),
         profile_picture: None,
+        status_text: Some("On vacation".to_owned()),
     };
     let new_user_profile_key = UserProfileKey::random(&user_id).unwrap();
     let new_encrypted_user_profile = UserProfileUpdate::update_own_profile(
         current_profile,
         new_user_profile,
         new_user_profile_key.index().clone(),
+        true,
         &client_sk,
     )
     .unwrap()
@@ -134,6 +145,183 @@ fn backend_interaction() {
     assert_eq!(returned_user_profile, new_encrypted_user_profile);
 }
 
+/// Encoding of a user profile before extensions were introduced
+#[derive(Debug, TlsSize, TlsSerialize)]
+struct UserProfileV1 {
+    user_id: UserId,
+    epoch: u64,
+    decryption_key_index: UserProfileKeyIndex,
+    display_name: DisplayName,
+    profile_picture: Option<Asset>,
+}
+
+/// Encoding of a user profile with extensions, which might be unknown to this client
+#[derive(Debug, TlsSize, TlsSerialize)]
+struct UserProfileWithExtensions {
+    profile: UserProfileV1,
+    extensions_marker: u8,
+    extensions: Vec<UserProfileExtension>,
+}
+
+#[derive(Debug, TlsSize, TlsSerialize)]
+struct SignedUserProfileWithExtensions {
+    tbs: UserProfileWithExtensions,
+    signature: Signature<PreliminaryClientKeyType>,
+}
+
+impl Signable for UserProfileWithExtensions {
+    type SignedOutput = SignedUserProfileWithExtensions;
+
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.tls_serialize_detached()
+    }
+
+    fn label(&self) -> &str {
+        USER_PROFILE_LABEL
+    }
+}
+
+impl SignedStruct<UserProfileWithExtensions, PreliminaryClientKeyType>
+    for SignedUserProfileWithExtensions
+{
+    fn from_payload(
+        payload: UserProfileWithExtensions,
+        signature: Signature<PreliminaryClientKeyType>,
+    ) -> Self {
+        Self {
+            tbs: payload,
+            signature,
+        }
+    }
+}
+
+#[test]
+fn extensions_encoding() {
+    let user_id = UserId::random("localhost".parse().unwrap());
+    let user_profile_key = UserProfileKey::random(&user_id).unwrap();
+    let (_credential_csr, signing_key) =
+        ClientCredentialCsr::new(user_id.clone(), SignatureScheme::ED25519).unwrap();
+    let profile = IndexedUserProfile {
+        user_id: user_id.clone(),
+        epoch: 3,
+        decryption_key_index: user_profile_key.index().clone(),
+        display_name: "Alice".parse().unwrap(),
+        profile_picture: Some(Asset::Value(vec![1, 2, 3])),
+        status_text: None,
+    };
+    let v1 = UserProfileV1 {
+        user_id: profile.user_id.clone(),
+        epoch: profile.epoch,
+        decryption_key_index: profile.decryption_key_index.clone(),
+        display_name: profile.display_name.clone(),
+        profile_picture: profile.profile_picture.clone(),
+    };
+
+    // A profile without status text is encoded without extensions, so clients which don't know
+    // about extensions can still read it
+    assert_eq!(
+        profile.tls_serialize_detached().unwrap(),
+        v1.tls_serialize_detached().unwrap()
+    );
+
+    let mut profile_with_status_text = profile.clone();
+    profile_with_status_text.status_text = Some(TlsString("On vacation".to_owned()));
+
+    for profile in [profile.clone(), profile_with_status_text] {
+        let signed = profile.clone().sign(&signing_key).unwrap();
+        let bytes = signed.tls_serialize_detached().unwrap();
+        let verifiable = VerifiableUserProfile::tls_deserialize_exact_bytes(&bytes).unwrap();
+        let verified: UnvalidatedUserProfile =
+            verifiable.verify(signing_key.verifying_key()).unwrap();
+        assert_eq!(verified.validate_display_name(), profile);
+    }
+
+    // Extensions of an unknown type are ignored, but still covered by the signature
+    let status_text = TlsString("On vacation".to_owned());
+    let signed = UserProfileWithExtensions {
+        profile: v1,
+        extensions_marker: 0xff,
+        extensions: vec![
+            UserProfileExtension {
+                extension_type: 0x1234,
+                extension_data: vec![4, 5, 6].into(),
+            },
+            UserProfileExtension {
+                extension_type: 1,
+                extension_data: status_text.tls_serialize_detached().unwrap().into(),
+            },
+        ],
+    }
+    .sign(&signing_key)
+    .unwrap();
+    let bytes = signed.tls_serialize_detached().unwrap();
+    let verifiable = VerifiableUserProfile::tls_deserialize_exact_bytes(&bytes).unwrap();
+    let verified: UnvalidatedUserProfile = verifiable.verify(signing_key.verifying_key()).unwrap();
+    let mut expected = profile;
+    expected.status_text = Some(status_text);
+    assert_eq!(verified.validate_display_name(), expected);
+}
+
+#[test]
+fn unshared_status_text_is_not_published() {
+    let user_id = UserId::random("localhost".parse().unwrap());
+    let user_profile_key = UserProfileKey::random(&user_id).unwrap();
+    let (credential_csr, signing_key) =
+        ClientCredentialCsr::new(user_id.clone(), SignatureScheme::ED25519).unwrap();
+
+    let domain = Fqdn::from_str("localhost").unwrap();
+    let (_as_credential, ac_sk) =
+        AsCredential::new(SignatureScheme::ED25519, domain.clone(), None).unwrap();
+    let (as_intermediate_credential_csr, aic_sk) =
+        AsIntermediateCredentialCsr::new(SignatureScheme::ED25519, domain).unwrap();
+    let as_intermediate_credential = as_intermediate_credential_csr.sign(&ac_sk, None).unwrap();
+    let aic_sk = aic_sk.convert();
+    let client_credential = ClientCredentialPayload::new(
+        credential_csr,
+        None,
+        *as_intermediate_credential.fingerprint(),
+    )
+    .sign(&aic_sk)
+    .unwrap();
+    let client_sk = ClientSigningKey::from_prelim_key(signing_key, client_credential).unwrap();
+
+    let current_profile = IndexedUserProfile {
+        user_id: user_id.clone(),
+        epoch: 0,
+        decryption_key_index: user_profile_key.index().clone(),
+        display_name: "Alice".parse().unwrap(),
+        profile_picture: None,
+        status_text: None,
+    };
+    let new_user_profile = UserProfile {
+        user_id: user_id.clone(),
+        display_name: "Alice".parse().unwrap(),
+        profile_picture: None,
+        status_text: Some("On vacation".to_owned()),
+    };
+    let new_user_profile_key = UserProfileKey::random(&user_id).unwrap();
+    let encrypted_user_profile = UserProfileUpdate::update_own_profile(
+        current_profile,
+        new_user_profile,
+        new_user_profile_key.index().clone(),
+        false,
+        &client_sk,
+    )
+    .unwrap()
+    .skip_storage()
+    .encrypt_with_index(&new_user_profile_key)
+    .unwrap();
+
+    let published_profile =
+        VerifiableUserProfile::decrypt_with_index(&new_user_profile_key, &encrypted_user_profile)
+            .unwrap()
+            .verify::<UnvalidatedUserProfile>(client_sk.verifying_key())
+            .unwrap()
+            .validate_display_name();
+    assert_eq!(published_profile.status_text, None);
+    assert_eq!(published_profile.epoch, 1);
+}
+
 #[sqlx::test]
 fn profile_deletion_trigger(pool: SqlitePool) {
     let pool = DbAccess::for_tests(pool);
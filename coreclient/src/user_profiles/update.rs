@@ -5,6 +5,7 @@
 use aircommon::{
     credentials::keys::ClientSigningKey,
     crypto::{indexed_aead::keys::UserProfileKeyIndex, signatures::signable::Signable},
+    identifiers::TlsString,
 };
 
 use crate::db::access::WriteConnection;
@@ -14,14 +15,22 @@ use super::{
     UserProfileValidationError,
 };
 
+/// An update of the own user profile.
+///
+/// The updated profile is stored locally as is, while the published profile only contains the
+/// status text if it is shared.
 #[derive(Debug)]
-pub(crate) struct UserProfileUpdate(SignedUserProfile);
+pub(crate) struct UserProfileUpdate {
+    profile: IndexedUserProfile,
+    published_profile: SignedUserProfile,
+}
 
 impl UserProfileUpdate {
     pub(crate) fn update_own_profile(
         mut current_profile: IndexedUserProfile,
         new_user_profile: UserProfile,
         key_index: UserProfileKeyIndex,
+        share_status_text: bool,
         signing_key: &ClientSigningKey,
     ) -> Result<UserProfileUpdate, UserProfileValidationError> {
         let expected_user_id = signing_key.credential().user_id();
@@ -34,22 +43,32 @@ impl UserProfileUpdate {
         }
         current_profile.display_name = new_user_profile.display_name;
         current_profile.profile_picture = new_user_profile.profile_picture;
+        current_profile.status_text = new_user_profile.status_text.map(TlsString);
         current_profile.decryption_key_index = key_index;
         current_profile.epoch += 1;
-        let verifiable_user_profile = current_profile.sign(signing_key)?;
-        Ok(UserProfileUpdate(verifiable_user_profile))
+
+        let mut published_profile = current_profile.clone();
+        if !share_status_text {
+            published_profile.status_text = None;
+        }
+        let published_profile = published_profile.sign(signing_key)?;
+
+        Ok(UserProfileUpdate {
+            profile: current_profile,
+            published_profile,
+        })
     }
 
     pub(crate) async fn store(
         self,
         connection: impl WriteConnection,
     ) -> sqlx::Result<EncryptableUserProfile> {
-        self.0.tbs.update(connection).await?;
-        Ok(EncryptableUserProfile(self.0))
+        self.profile.update(connection).await?;
+        Ok(EncryptableUserProfile(self.published_profile))
     }
 
     #[cfg(test)]
     pub(crate) fn skip_storage(self) -> EncryptableUserProfile {
-        EncryptableUserProfile(self.0)
+        EncryptableUserProfile(self.published_profile)
     }
 }
@@ -149,6 +149,7 @@ impl CoreUser {
             return Err(error.into());
         }
 
+        self.notify_username_changes();
         Ok(Some(record))
     }
 
@@ -190,9 +191,15 @@ impl CoreUser {
 
     pub(crate) async fn remove_username_locally(&self, username: &Username) -> anyhow::Result<()> {
         UsernameRecord::delete(self.db().write().await?, username).await?;
+        self.notify_username_changes();
         Ok(())
     }
 
+    /// Notifies the listeners of username queues that a username was added or removed.
+    fn notify_username_changes(&self) {
+        self.inner.username_changes.send_replace(());
+    }
+
     /// Consumes a token from the local cache.
     ///
     /// Returns an error if the cache is empty. Callers must NOT replenish
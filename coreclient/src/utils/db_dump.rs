@@ -0,0 +1,372 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Debug dump of a client database.
+//!
+//! A dump contains the schema and all rows of all tables of a client database. It can be imported
+//! into a fresh database file.
+//!
+//! WARNING: Nothing is redacted. A dump contains all data of the client, including private keys
+//! and message contents. Only use it for debugging, and never share it.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context, bail, ensure};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    AssertSqlSafe, Column, ConnectOptions, Connection, Row, SqliteConnection, TypeInfo, ValueRef,
+    query, query_as,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode},
+};
+use tracing::info;
+
+use super::file_lock::{FileLock, FileLockGuard};
+
+/// How long to wait for the lock of a database which is in use by a client
+const LOCK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Dump of a client database
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClientDbDump {
+    /// Tables, indexes, triggers and views in order of creation
+    pub schema: Vec<DumpedSchemaObject>,
+    pub tables: Vec<DumpedTable>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DumpedSchemaObject {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub name: String,
+    pub sql: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DumpedTable {
+    pub name: String,
+    /// Column names; empty if the table has no rows
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<DumpedValue>>,
+}
+
+/// Value of a single column in one of the SQLite storage classes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DumpedValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    /// Hex encoded bytes
+    Blob(String),
+}
+
+/// Dumps the schema and all rows of the client database at the given path.
+///
+/// Fails if the database is locked by a running client.
+///
+/// See the [module documentation](self) for a warning about the contained data.
+pub async fn dump_client_db(db_file: impl AsRef<Path>) -> anyhow::Result<ClientDbDump> {
+    let db_file = db_file.as_ref();
+    ensure!(db_file.exists(), "{} does not exist", db_file.display());
+
+    let mut lock = FileLock::new(lock_file_path(db_file))?;
+    let _guard = try_lock(&mut lock).await?;
+
+    let mut connection = SqliteConnectOptions::new()
+        .filename(db_file)
+        .read_only(true)
+        .connect()
+        .await?;
+    let mut txn = connection.begin().await?;
+
+    let schema: Vec<DumpedSchemaObject> = query_as::<_, (String, String, String)>(
+        "SELECT type, name, sql FROM sqlite_schema WHERE sql IS NOT NULL ORDER BY rowid",
+    )
+    .fetch_all(&mut *txn)
+    .await?
+    .into_iter()
+    .map(|(kind, name, sql)| DumpedSchemaObject { kind, name, sql })
+    .collect();
+
+    let mut tables = Vec::new();
+    for object in &schema {
+        if object.kind != "table" || !is_dumped_table(&object.name) {
+            continue;
+        }
+        let rows = query(AssertSqlSafe(format!(
+            "SELECT * FROM {}",
+            quote_identifier(&object.name)
+        )))
+        .fetch_all(&mut *txn)
+        .await?;
+
+        let columns = rows
+            .first()
+            .map(|row| {
+                row.columns()
+                    .iter()
+                    .map(|column| column.name().to_owned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let rows = rows
+            .iter()
+            .map(|row| (0..row.len()).map(|i| dump_value(row, i)).collect())
+            .collect::<anyhow::Result<_>>()
+            .with_context(|| format!("failed to dump table {}", object.name))?;
+
+        tables.push(DumpedTable {
+            name: object.name.clone(),
+            columns,
+            rows,
+        });
+    }
+
+    info!(path = %db_file.display(), tables = tables.len(), "Dumped client database");
+    Ok(ClientDbDump { schema, tables })
+}
+
+/// Imports the dump into a new client database at the given path.
+///
+/// Fails if the database file already exists.
+pub async fn import_client_db(
+    db_file: impl AsRef<Path>,
+    dump: &ClientDbDump,
+) -> anyhow::Result<()> {
+    let db_file = db_file.as_ref();
+    ensure!(!db_file.exists(), "{} already exists", db_file.display());
+
+    let mut lock = FileLock::new(lock_file_path(db_file))?;
+    let _guard = try_lock(&mut lock).await?;
+
+    let mut connection: SqliteConnection = SqliteConnectOptions::new()
+        .filename(db_file)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .foreign_keys(false)
+        .connect()
+        .await?;
+    let mut txn = connection.begin().await?;
+
+    // Create the tables before inserting the rows, and everything else (in particular triggers)
+    // after the rows are inserted.
+    let (schema_tables, schema_others): (Vec<_>, Vec<_>) = dump
+        .schema
+        .iter()
+        // Internal tables are created by SQLite itself
+        .filter(|object| !object.name.starts_with("sqlite_"))
+        .partition(|object| object.kind == "table");
+
+    for object in schema_tables {
+        query(AssertSqlSafe(object.sql.clone()))
+            .execute(&mut *txn)
+            .await
+            .with_context(|| format!("failed to create table {}", object.name))?;
+    }
+
+    // Inserting rows into tables with autoincrement columns updates the sequence, so it is
+    // restored last.
+    let (sequence, tables): (Vec<_>, Vec<_>) = dump
+        .tables
+        .iter()
+        .partition(|table| table.name == "sqlite_sequence");
+    for table in tables {
+        insert_rows(&mut txn, table).await?;
+    }
+    for table in sequence {
+        query("DELETE FROM sqlite_sequence")
+            .execute(&mut *txn)
+            .await?;
+        insert_rows(&mut txn, table).await?;
+    }
+
+    for object in schema_others {
+        query(AssertSqlSafe(object.sql.clone()))
+            .execute(&mut *txn)
+            .await
+            .with_context(|| format!("failed to create {} {}", object.kind, object.name))?;
+    }
+
+    txn.commit().await?;
+    info!(path = %db_file.display(), "Imported client database");
+    Ok(())
+}
+
+async fn insert_rows(connection: &mut SqliteConnection, table: &DumpedTable) -> anyhow::Result<()> {
+    if table.rows.is_empty() {
+        return Ok(());
+    }
+    let columns: Vec<String> = table.columns.iter().map(|c| quote_identifier(c)).collect();
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES ({placeholders})",
+        quote_identifier(&table.name),
+        columns.join(", ")
+    );
+    for row in &table.rows {
+        ensure!(
+            row.len() == columns.len(),
+            "invalid number of values in table {}",
+            table.name
+        );
+        let mut insert = query(AssertSqlSafe(sql.clone()));
+        for value in row {
+            insert = match value {
+                DumpedValue::Null => insert.bind(None::<i64>),
+                DumpedValue::Integer(value) => insert.bind(*value),
+                DumpedValue::Real(value) => insert.bind(*value),
+                DumpedValue::Text(value) => insert.bind(value.clone()),
+                DumpedValue::Blob(value) => insert.bind(hex::decode(value)?),
+            };
+        }
+        insert
+            .execute(&mut *connection)
+            .await
+            .with_context(|| format!("failed to insert into table {}", table.name))?;
+    }
+    Ok(())
+}
+
+/// The lock file shared by all databases in the same directory
+fn lock_file_path(db_file: &Path) -> PathBuf {
+    db_file
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("lockfile")
+}
+
+async fn try_lock(lock: &mut FileLock) -> anyhow::Result<FileLockGuard<'_>> {
+    match tokio::time::timeout(LOCK_TIMEOUT, lock.lock()).await {
+        Ok(guard) => Ok(guard?),
+        Err(_) => bail!("database is locked; is a client still running?"),
+    }
+}
+
+/// Internal tables of SQLite are not dumped, except for the sequence of autoincrement columns.
+fn is_dumped_table(name: &str) -> bool {
+    !name.starts_with("sqlite_") || name == "sqlite_sequence"
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn dump_value(row: &sqlx::sqlite::SqliteRow, i: usize) -> anyhow::Result<DumpedValue> {
+    let value = row.try_get_raw(i)?;
+    if value.is_null() {
+        return Ok(DumpedValue::Null);
+    }
+    let type_name = value.type_info().name().to_owned();
+    Ok(match type_name.as_str() {
+        "INTEGER" => DumpedValue::Integer(row.try_get(i)?),
+        "REAL" => DumpedValue::Real(row.try_get(i)?),
+        "TEXT" => DumpedValue::Text(row.try_get(i)?),
+        "BLOB" => DumpedValue::Blob(hex::encode(row.try_get::<Vec<u8>, _>(i)?)),
+        _ => bail!("unsupported column type {type_name}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_test_db(db_file: &Path) -> anyhow::Result<()> {
+        let mut connection = SqliteConnectOptions::new()
+            .filename(db_file)
+            .create_if_missing(true)
+            .connect()
+            .await?;
+        for sql in [
+            "CREATE TABLE message (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                text TEXT,
+                content BLOB,
+                score REAL
+            )",
+            "CREATE TABLE message_count (count INTEGER NOT NULL)",
+            "INSERT INTO message_count (count) VALUES (0)",
+            "CREATE INDEX message_text ON message (text)",
+            // Must not fire on import
+            "CREATE TRIGGER message_insert AFTER INSERT ON message
+            BEGIN
+                UPDATE message_count SET count = count + 1;
+            END",
+            "INSERT INTO message (text, content, score) VALUES ('Hello', X'00FF', 0.5)",
+            "INSERT INTO message (text, content, score) VALUES (NULL, NULL, NULL)",
+            "CREATE TABLE empty (id INTEGER)",
+        ] {
+            query(sql).execute(&mut connection).await?;
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn dump_and_import() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let db_file = dir.path().join("client.db");
+        create_test_db(&db_file).await?;
+
+        let dump = dump_client_db(&db_file).await?;
+        let message = dump
+            .tables
+            .iter()
+            .find(|table| table.name == "message")
+            .unwrap();
+        assert_eq!(message.columns, ["id", "text", "content", "score"]);
+        assert_eq!(
+            message.rows,
+            [
+                vec![
+                    DumpedValue::Integer(1),
+                    DumpedValue::Text("Hello".to_owned()),
+                    DumpedValue::Blob("00ff".to_owned()),
+                    DumpedValue::Real(0.5),
+                ],
+                vec![
+                    DumpedValue::Integer(2),
+                    DumpedValue::Null,
+                    DumpedValue::Null,
+                    DumpedValue::Null,
+                ],
+            ]
+        );
+
+        // Roundtrip through JSON and a fresh database
+        let dump: ClientDbDump = serde_json::from_slice(&serde_json::to_vec(&dump)?)?;
+        let imported_db_file = dir.path().join("imported.db");
+        import_client_db(&imported_db_file, &dump).await?;
+        let mut imported = dump_client_db(&imported_db_file).await?;
+        // The trigger did not fire on import, and the sequence is not duplicated
+        assert_eq!(imported.tables, dump.tables);
+        // Indexes and triggers are created after the tables
+        let mut schema = dump.schema.clone();
+        schema.sort_by(|a, b| a.name.cmp(&b.name));
+        imported.schema.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(imported.schema, schema);
+
+        // Importing into an existing database is refused
+        assert!(import_client_db(&imported_db_file, &dump).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn refuse_locked_db() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let db_file = dir.path().join("client.db");
+        create_test_db(&db_file).await?;
+
+        let mut lock = FileLock::new(lock_file_path(&db_file))?;
+        let guard = lock.lock().await?;
+        assert!(dump_client_db(&db_file).await.is_err());
+        drop(guard);
+
+        dump_client_db(&db_file).await?;
+        Ok(())
+    }
+}
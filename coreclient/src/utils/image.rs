@@ -2,9 +2,15 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+//! Re-encoding of profile pictures and image attachments.
+//!
+//! Images are always decoded to pixels and encoded again, and no metadata is written by the
+//! encoders. This way, metadata of the source image like EXIF (GPS location, device information,
+//! etc.) is never sent. The EXIF orientation is applied to the pixels before.
+
 use std::{
     fs::{self},
-    io::Cursor,
+    io::{BufRead, Cursor, Seek},
     path::Path,
 };
 
@@ -15,38 +21,214 @@ use image::{
     codecs::{gif::GifDecoder, png::PngDecoder, webp::WebPDecoder},
     guess_format,
 };
-use tracing::info;
+use tracing::{info, warn};
+
+/// Maximum dimensions of a stored profile image
+const MAX_PROFILE_IMAGE_WIDTH: u32 = 512;
+const MAX_PROFILE_IMAGE_HEIGHT: u32 = 512;
+const DEFAULT_STILL_PROFILE_IMAGE_QUALITY_PERCENT: u8 = 90;
+/// Maximum dimensions of an image accepted as profile image
+const MAX_PROFILE_IMAGE_SOURCE_DIMENSION: u32 = 8192;
+/// Maximum size of an image accepted as profile image
+const MAX_PROFILE_IMAGE_SOURCE_BYTES: usize = 20 * 1024 * 1024;
+/// Maximum size of an animated profile image after re-encoding
+///
+/// Larger animated images are stored as still images.
+const MAX_ANIMATED_PROFILE_IMAGE_BYTES: usize = 512 * 1024;
+const PROFILE_IMAGE_QUALITY_PERCENT: f32 = 80.0;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileImageError {
+    #[error("Unsupported profile image format")]
+    UnsupportedFormat,
+    #[error("Profile image is too large: {size} bytes")]
+    TooLarge { size: usize },
+    #[error("Profile image dimensions are too large: {width}x{height}")]
+    DimensionsTooLarge { width: u32, height: u32 },
+    #[error("Failed to encode profile image: {0}")]
+    Encoding(String),
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+}
+
+/// Output format of a re-encoded still profile image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProfileImageFormat {
+    #[default]
+    Jpeg,
+    Png,
+    WebP,
+}
+
+/// Options for re-encoding a profile image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProfileImageOptions {
+    /// Format of still images; animated images are always encoded as animated WebP
+    pub format: ProfileImageFormat,
+    /// Maximum width and height; capped at 512
+    pub max_dimension: u32,
+    /// Quality of still images between 1 and 100; ignored for PNG
+    pub quality_percent: u8,
+}
+
+impl Default for ProfileImageOptions {
+    fn default() -> Self {
+        Self {
+            format: ProfileImageFormat::default(),
+            max_dimension: MAX_PROFILE_IMAGE_WIDTH,
+            quality_percent: DEFAULT_STILL_PROFILE_IMAGE_QUALITY_PERCENT,
+        }
+    }
+}
+
+impl ProfileImageOptions {
+    fn max_dimensions(&self) -> (u32, u32) {
+        (
+            self.max_dimension.clamp(1, MAX_PROFILE_IMAGE_WIDTH),
+            self.max_dimension.clamp(1, MAX_PROFILE_IMAGE_HEIGHT),
+        )
+    }
+
+    fn quality_percent(&self) -> u8 {
+        self.quality_percent.clamp(1, 100)
+    }
+}
+
+/// Re-encodes an image to be used as profile or chat picture.
+///
+/// Only JPEG, PNG, GIF and WebP images are supported. Still images are resized to at most
+/// 512x512 and encoded as JPEG. Animated GIFs, animated WebPs, and APNGs are resized to the same
+/// dimensions and encoded as animated WebP, unless the result is larger than
+/// [`MAX_ANIMATED_PROFILE_IMAGE_BYTES`]; then the first frame is used as still image.
+pub(crate) fn resize_profile_image(image_bytes: &[u8]) -> Result<Vec<u8>, ProfileImageError> {
+    resize_profile_image_with_options(image_bytes, &ProfileImageOptions::default())
+}
 
-const MAX_PROFILE_IMAGE_WIDTH: u32 = 256;
-const MAX_PROFILE_IMAGE_HEIGHT: u32 = 256;
+/// Same as [`resize_profile_image`], but with the given output format, maximum dimensions and
+/// quality.
+pub(crate) fn resize_profile_image_with_options(
+    image_bytes: &[u8],
+    options: &ProfileImageOptions,
+) -> Result<Vec<u8>, ProfileImageError> {
+    let (max_width, max_height) = options.max_dimensions();
+    if image_bytes.len() > MAX_PROFILE_IMAGE_SOURCE_BYTES {
+        return Err(ProfileImageError::TooLarge {
+            size: image_bytes.len(),
+        });
+    }
+    let format = match guess_format(image_bytes) {
+        Ok(
+            format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::Gif | ImageFormat::WebP),
+        ) => format,
+        _ => return Err(ProfileImageError::UnsupportedFormat),
+    };
+    let (width, height) =
+        ImageReader::with_format(Cursor::new(image_bytes), format).into_dimensions()?;
+    if width > MAX_PROFILE_IMAGE_SOURCE_DIMENSION || height > MAX_PROFILE_IMAGE_SOURCE_DIMENSION {
+        return Err(ProfileImageError::DimensionsTooLarge { width, height });
+    }
 
-pub(crate) fn resize_profile_image(image_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
-    let mut decoder = ImageReader::new(Cursor::new(image_bytes))
-        .with_guessed_format()?
-        .into_decoder()?;
+    if image_is_animated(image_bytes) {
+        match resize_animated_profile_image(image_bytes, format, max_width, max_height) {
+            Ok(webp_data) if webp_data.len() <= MAX_ANIMATED_PROFILE_IMAGE_BYTES => {
+                info!(
+                    from_bytes = image_bytes.len(),
+                    to_bytes = webp_data.len(),
+                    "Resized animated profile image",
+                );
+                return Ok(webp_data);
+            }
+            Ok(webp_data) => {
+                info!(
+                    bytes = webp_data.len(),
+                    "Animated profile image is too large; using still image"
+                );
+            }
+            Err(error) => {
+                warn!(%error, "Failed to resize animated profile image; using still image");
+            }
+        }
+    }
+
+    let mut decoder = ImageReader::with_format(Cursor::new(image_bytes), format).into_decoder()?;
 
     let orientation = decoder.orientation().ok();
 
     // Decode, resize and rotate the image
     let image = DynamicImage::from_decoder(decoder)?;
-    let mut image = resize(image, MAX_PROFILE_IMAGE_WIDTH, MAX_PROFILE_IMAGE_HEIGHT);
+    let mut image = resize(image, max_width, max_height);
     if let Some(orientation) = orientation {
         image.apply_orientation(orientation);
     }
 
     // Save the resized image
-    let mut buf = Vec::new();
-    let mut cursor = Cursor::new(&mut buf);
-    let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut cursor, 90);
-    encoder.encode_image(&image)?;
+    let quality = options.quality_percent();
+    let buf = match options.format {
+        ProfileImageFormat::Jpeg => {
+            let mut buf = Vec::new();
+            let mut cursor = Cursor::new(&mut buf);
+            let mut encoder =
+                image::codecs::jpeg::JpegEncoder::new_with_quality(&mut cursor, quality);
+            encoder.encode_image(&image)?;
+            buf
+        }
+        ProfileImageFormat::Png => {
+            let mut buf = Vec::new();
+            image.write_to(&mut Cursor::new(&mut buf), ImageFormat::Png)?;
+            buf
+        }
+        ProfileImageFormat::WebP => {
+            let image_rgba = image.to_rgba8();
+            let (width, height) = image_rgba.dimensions();
+            webpx::Encoder::new_rgba(&image_rgba, width, height)
+                .quality(quality.into())
+                .encode(webpx::Unstoppable)
+                .map_err(|error| ProfileImageError::Encoding(error.to_string()))?
+        }
+    };
     info!(
         from_bytes = image_bytes.len(),
         to_bytes = buf.len(),
+        format = ?options.format,
         "Resized profile image",
     );
     Ok(buf)
 }
 
+fn resize_animated_profile_image(
+    image_bytes: &[u8],
+    format: ImageFormat,
+    max_width: u32,
+    max_height: u32,
+) -> anyhow::Result<Vec<u8>> {
+    let reader = Cursor::new(image_bytes);
+    let (webp_data, _first_frame) = match format {
+        ImageFormat::Gif => encode_animated_webp(
+            GifDecoder::new(reader)?,
+            max_width,
+            max_height,
+            PROFILE_IMAGE_QUALITY_PERCENT,
+            format,
+        )?,
+        ImageFormat::WebP => encode_animated_webp(
+            WebPDecoder::new(reader)?,
+            max_width,
+            max_height,
+            PROFILE_IMAGE_QUALITY_PERCENT,
+            format,
+        )?,
+        ImageFormat::Png => encode_animated_webp(
+            PngDecoder::new(reader)?.apng()?,
+            max_width,
+            max_height,
+            PROFILE_IMAGE_QUALITY_PERCENT,
+            format,
+        )?,
+        _ => anyhow::bail!("{format:?} is not an animated format"),
+    };
+    Ok(webp_data)
+}
+
 const ATTACHMENT_IMAGE_QUALITY_PERCENT: f32 = 90.0;
 const MAX_ATTACHMENT_IMAGE_WIDTH: u32 = 4096;
 const MAX_ATTACHMENT_IMAGE_HEIGHT: u32 = 4096;
@@ -54,61 +236,93 @@ const MAX_ATTACHMENT_IMAGE_HEIGHT: u32 = 4096;
 /// expecting the renderer to clamp it, so we ensure each frame contributes a
 /// non-zero duration to the resulting WebP timeline.
 const MIN_FRAME_DURATION_MS: i32 = 20;
+const ATTACHMENT_THUMBNAIL_QUALITY_PERCENT: f32 = 60.0;
+const MAX_ATTACHMENT_THUMBNAIL_WIDTH: u32 = 64;
+const MAX_ATTACHMENT_THUMBNAIL_HEIGHT: u32 = 64;
 
 pub(crate) struct ReencodedAttachmentImage {
     pub(crate) webp_image: Vec<u8>,
     pub(crate) image_dimensions: (u32, u32),
     pub(crate) blurhash: String,
+    /// Small still WebP preview of the image
+    pub(crate) thumbnail: Vec<u8>,
 }
 
 /// Loads an image and re-encodes it to WEBP format.
 ///
-/// If the path is not an image, returns `None`.
+/// If the file is not an image, returns `None`. Animated images are re-encoded as animated WebP;
+/// if that fails, the first frame is used as still image.
 ///
 /// This does several things:
 /// - Rotates and flips the image according to the EXIF orientation
 /// - Resizes the image to a maximum width and height of 4096x4096
 /// - Converts the image to WebP. Animated GIFs, animated WebPs, and APNGs are
 ///   re-encoded as animated WebP, preserving per-frame timing.
+/// - Generates a still WebP thumbnail of at most 64x64 pixels
 pub(crate) fn load_attachment_image(
     path: &Path,
 ) -> anyhow::Result<Option<ReencodedAttachmentImage>> {
     let file_size = fs::metadata(path)?.len();
-
     let reader = ImageReader::open(path)?.with_guessed_format()?;
+    reencode_attachment_image(reader, file_size)
+}
+
+/// Same as [`load_attachment_image`], but for an image which is already loaded into memory.
+pub(crate) fn load_attachment_image_from_bytes(
+    bytes: &[u8],
+) -> anyhow::Result<Option<ReencodedAttachmentImage>> {
+    let reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+    reencode_attachment_image(reader, bytes.len().try_into()?)
+}
+
+fn reencode_attachment_image<R: BufRead + Seek>(
+    reader: ImageReader<R>,
+    file_size: u64,
+) -> anyhow::Result<Option<ReencodedAttachmentImage>> {
     let Some(format) = reader.format() else {
         return Ok(None);
     };
-
-    let result = match format {
-        ImageFormat::Gif => {
-            let decoder = GifDecoder::new(reader.into_inner())?;
-            load_animated_frames(decoder, file_size, format)?
-        }
+    let mut inner = reader.into_inner();
+
+    let animated = match format {
+        ImageFormat::Gif => Some(load_animated_frames(
+            GifDecoder::new(&mut inner)?,
+            file_size,
+            format,
+        )),
         ImageFormat::WebP => {
-            let decoder = WebPDecoder::new(reader.into_inner())?;
-            if decoder.has_animation() {
-                load_animated_frames(decoder, file_size, format)?
-            } else {
-                load_still_image(decoder, file_size)?
-            }
+            let decoder = WebPDecoder::new(&mut inner)?;
+            decoder
+                .has_animation()
+                .then(|| load_animated_frames(decoder, file_size, format))
         }
         ImageFormat::Png => {
-            let decoder = PngDecoder::new(reader.into_inner())?;
+            let decoder = PngDecoder::new(&mut inner)?;
             if decoder.is_apng()? {
-                let apng = decoder.apng()?;
-                load_animated_frames(apng, file_size, format)?
+                Some(
+                    decoder
+                        .apng()
+                        .map_err(anyhow::Error::from)
+                        .and_then(|apng| load_animated_frames(apng, file_size, format)),
+                )
             } else {
-                load_still_image(decoder, file_size)?
+                None
             }
         }
-        _ => {
-            let decoder = reader.into_decoder()?;
-            load_still_image(decoder, file_size)?
-        }
+        _ => None,
     };
+    match animated {
+        Some(Ok(result)) => return Ok(Some(result)),
+        Some(Err(error)) => {
+            warn!(%error, ?format, "Failed to re-encode animated image; using first frame");
+        }
+        None => {}
+    }
 
-    Ok(Some(result))
+    // Still image or the first frame of an animation which could not be re-encoded
+    inner.rewind()?;
+    let decoder = ImageReader::with_format(inner, format).into_decoder()?;
+    Ok(Some(load_still_image(decoder, file_size)?))
 }
 
 /// Classifies an attachment's encoded bytes as animated by reading only the
@@ -158,6 +372,7 @@ fn load_still_image<D: ImageDecoder>(
     // `blurhash::encode` can only fail if the components dimension is out of range
     // => We should never get an error here.
     let blurhash = blurhash::encode(4, 3, width, height, &image_rgba)?;
+    let thumbnail = encode_thumbnail(&image_rgba)?;
 
     info!(
         from_bytes = file_size,
@@ -169,6 +384,7 @@ fn load_still_image<D: ImageDecoder>(
         webp_image: webp_data,
         image_dimensions: (width, height),
         blurhash,
+        thumbnail,
     })
 }
 
@@ -180,24 +396,54 @@ fn load_animated_frames<'a, D: AnimationDecoder<'a>>(
     file_size: u64,
     source: ImageFormat,
 ) -> anyhow::Result<ReencodedAttachmentImage> {
+    let (webp_data, first_buffer) = encode_animated_webp(
+        decoder,
+        MAX_ATTACHMENT_IMAGE_WIDTH,
+        MAX_ATTACHMENT_IMAGE_HEIGHT,
+        ATTACHMENT_IMAGE_QUALITY_PERCENT,
+        source,
+    )?;
+    let (width, height) = first_buffer.dimensions();
+
+    let blurhash = blurhash::encode(4, 3, width, height, first_buffer.as_raw())?;
+    let thumbnail = encode_thumbnail(&first_buffer)?;
+
+    info!(
+        from_bytes = file_size,
+        to_bytes = webp_data.len(),
+        ?source,
+        "Reencoded animated image as animated WebP",
+    );
+
+    Ok(ReencodedAttachmentImage {
+        webp_image: webp_data,
+        image_dimensions: (width, height),
+        blurhash,
+        thumbnail,
+    })
+}
+
+/// Re-encodes the frames of an animated image as animated WebP, resizing them to fit within the
+/// given dimensions. Returns the WebP data and the resized first frame.
+fn encode_animated_webp<'a, D: AnimationDecoder<'a>>(
+    decoder: D,
+    max_width: u32,
+    max_height: u32,
+    quality: f32,
+    source: ImageFormat,
+) -> anyhow::Result<(Vec<u8>, ImageBuffer<Rgba<u8>, Vec<u8>>)> {
     let mut frames = decoder.into_frames();
 
     let first = frames
         .next()
         .ok_or_else(|| anyhow::anyhow!("{source:?} has no frames"))??;
     let first_delay = first.delay();
-    let first_buffer = fit_to_max(
-        first.into_buffer(),
-        MAX_ATTACHMENT_IMAGE_WIDTH,
-        MAX_ATTACHMENT_IMAGE_HEIGHT,
-    );
+    let first_buffer = fit_to_max(first.into_buffer(), max_width, max_height);
     let (width, height) = first_buffer.dimensions();
 
-    let blurhash = blurhash::encode(4, 3, width, height, first_buffer.as_raw())?;
-
     let mut encoder = webpx::AnimationEncoder::with_options(width, height, true, 0)
         .context("WebP encoder init failed")?;
-    encoder.set_quality(ATTACHMENT_IMAGE_QUALITY_PERCENT);
+    encoder.set_quality(quality);
 
     let mut timestamp_ms: i32 = 0;
     encoder
@@ -208,11 +454,7 @@ fn load_animated_frames<'a, D: AnimationDecoder<'a>>(
     for frame_result in frames {
         let frame = frame_result?;
         let frame_delay = frame.delay();
-        let resized = fit_to_max(
-            frame.into_buffer(),
-            MAX_ATTACHMENT_IMAGE_WIDTH,
-            MAX_ATTACHMENT_IMAGE_HEIGHT,
-        );
+        let resized = fit_to_max(frame.into_buffer(), max_width, max_height);
         // The dimensions should never change mid-stream.
         if resized.dimensions() != (width, height) {
             anyhow::bail!("{source:?} frame dimensions changed mid-stream");
@@ -227,18 +469,45 @@ fn load_animated_frames<'a, D: AnimationDecoder<'a>>(
         .finish(timestamp_ms)
         .context("WebP finalize failed")?;
 
-    info!(
-        from_bytes = file_size,
-        to_bytes = webp_data.len(),
-        ?source,
-        "Reencoded animated image as animated WebP",
+    Ok((webp_data, first_buffer))
+}
+
+/// Encodes a downscaled still WebP thumbnail of the image.
+fn encode_thumbnail(image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> anyhow::Result<Vec<u8>> {
+    let (width, height) = thumbnail_dimensions(
+        image.dimensions(),
+        MAX_ATTACHMENT_THUMBNAIL_WIDTH,
+        MAX_ATTACHMENT_THUMBNAIL_HEIGHT,
     );
+    let thumbnail = image::imageops::thumbnail(image, width, height);
+    webpx::Encoder::new_rgba(&thumbnail, width, height)
+        .quality(ATTACHMENT_THUMBNAIL_QUALITY_PERCENT)
+        .encode(webpx::Unstoppable)
+        .context("WebP thumbnail encode failed")
+}
 
-    Ok(ReencodedAttachmentImage {
-        webp_image: webp_data,
-        image_dimensions: (width, height),
-        blurhash,
-    })
+/// Computes the dimensions of an image scaled down to fit within the given dimensions, preserving
+/// aspect ratio.
+///
+/// Images that already fit are not scaled up.
+fn thumbnail_dimensions(
+    (width, height): (u32, u32),
+    max_width: u32,
+    max_height: u32,
+) -> (u32, u32) {
+    if width <= max_width && height <= max_height {
+        return (width, height);
+    }
+    let (width, height) = (u64::from(width), u64::from(height));
+    let (max_width, max_height) = (u64::from(max_width), u64::from(max_height));
+    // Scale by the more constraining side
+    let (scaled_width, scaled_height) = if width * max_height >= height * max_width {
+        (max_width, (height * max_width / width).max(1))
+    } else {
+        ((width * max_height / height).max(1), max_height)
+    };
+    // The scaled dimensions are bounded by the max dimensions, so they fit into u32
+    (scaled_width as u32, scaled_height as u32)
 }
 
 /// Converts a frame delay to milliseconds, applying a floor to avoid
@@ -276,3 +545,198 @@ fn resize(image: DynamicImage, max_width: u32, max_height: u32) -> DynamicImage
     }
     image.resize(max_width, max_height, image::imageops::FilterType::Lanczos3)
 }
+
+#[cfg(test)]
+mod tests {
+    use image::{
+        Frame, Rgb,
+        codecs::gif::{GifEncoder, Repeat},
+    };
+
+    use super::*;
+
+    #[test]
+    fn thumbnail_dimensions_are_capped() {
+        assert_eq!(thumbnail_dimensions((32, 16), 64, 64), (32, 16));
+        assert_eq!(thumbnail_dimensions((4096, 4096), 64, 64), (64, 64));
+        assert_eq!(thumbnail_dimensions((4096, 1024), 64, 64), (64, 16));
+        assert_eq!(thumbnail_dimensions((1024, 4096), 64, 64), (16, 64));
+        assert_eq!(thumbnail_dimensions((4096, 1), 64, 64), (64, 1));
+    }
+
+    fn encode_gif(width: u32, height: u32, frames: u8) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut bytes);
+            encoder.set_repeat(Repeat::Infinite).unwrap();
+            for i in 0..frames {
+                let buffer = ImageBuffer::from_pixel(width, height, Rgba([i * 50, 0, 0, 255]));
+                let frame = Frame::from_parts(buffer, 0, 0, Delay::from_numer_denom_ms(100, 1));
+                encoder.encode_frame(frame).unwrap();
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn animated_attachment_image_stays_animated() {
+        let gif = encode_gif(100, 50, 3);
+        let image = load_attachment_image_from_bytes(&gif).unwrap().unwrap();
+        assert_eq!(guess_format(&image.webp_image).unwrap(), ImageFormat::WebP);
+        assert!(image_is_animated(&image.webp_image));
+        assert_eq!(image.image_dimensions, (100, 50));
+    }
+
+    #[test]
+    fn animated_profile_image_stays_animated() {
+        let gif = encode_gif(1024, 512, 3);
+        let resized = resize_profile_image(&gif).unwrap();
+        assert_eq!(guess_format(&resized).unwrap(), ImageFormat::WebP);
+        assert!(image_is_animated(&resized));
+        let (width, height) = ImageReader::with_format(Cursor::new(&resized), ImageFormat::WebP)
+            .into_dimensions()
+            .unwrap();
+        assert_eq!((width, height), (512, 256));
+    }
+
+    #[test]
+    fn still_profile_image_is_jpeg() {
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(ImageBuffer::from_pixel(32, 32, Rgba([0, 0, 255, 255])))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let resized = resize_profile_image(&png).unwrap();
+        assert_eq!(guess_format(&resized).unwrap(), ImageFormat::Jpeg);
+    }
+
+    #[test]
+    fn still_profile_image_with_options() {
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(ImageBuffer::from_pixel(128, 64, Rgba([0, 0, 255, 255])))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        for (format, image_format) in [
+            (ProfileImageFormat::Jpeg, ImageFormat::Jpeg),
+            (ProfileImageFormat::Png, ImageFormat::Png),
+            (ProfileImageFormat::WebP, ImageFormat::WebP),
+        ] {
+            let options = ProfileImageOptions {
+                format,
+                max_dimension: 32,
+                quality_percent: 50,
+            };
+            let resized = resize_profile_image_with_options(&png, &options).unwrap();
+            assert_eq!(guess_format(&resized).unwrap(), image_format);
+            let dimensions = ImageReader::with_format(Cursor::new(&resized), image_format)
+                .into_dimensions()
+                .unwrap();
+            assert_eq!(dimensions, (32, 16));
+        }
+
+        // The maximum dimension is capped
+        let options = ProfileImageOptions {
+            max_dimension: 4096,
+            ..Default::default()
+        };
+        let mut large_png = Vec::new();
+        DynamicImage::ImageRgba8(ImageBuffer::from_pixel(1024, 1024, Rgba([0, 0, 255, 255])))
+            .write_to(&mut Cursor::new(&mut large_png), ImageFormat::Png)
+            .unwrap();
+        let resized = resize_profile_image_with_options(&large_png, &options).unwrap();
+        let dimensions = ImageReader::with_format(Cursor::new(&resized), ImageFormat::Jpeg)
+            .into_dimensions()
+            .unwrap();
+        assert_eq!(dimensions, (512, 512));
+    }
+
+    /// Encodes a JPEG with an EXIF segment containing a GPS IFD.
+    fn jpeg_with_gps_exif() -> Vec<u8> {
+        let mut jpeg = Vec::new();
+        let image = ImageBuffer::from_fn(32, 32, |x, y| Rgb([x as u8 * 8, y as u8 * 8, 128]));
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+            .unwrap();
+
+        let mut exif = b"Exif\0\0II*\0".to_vec();
+        exif.extend_from_slice(&8u32.to_le_bytes());
+        // IFD0 with the offset of the GPS IFD
+        exif.extend_from_slice(&1u16.to_le_bytes());
+        exif.extend_from_slice(&0x8825u16.to_le_bytes());
+        exif.extend_from_slice(&4u16.to_le_bytes()); // LONG
+        exif.extend_from_slice(&1u32.to_le_bytes());
+        exif.extend_from_slice(&26u32.to_le_bytes());
+        exif.extend_from_slice(&0u32.to_le_bytes());
+        // GPS IFD with the latitude reference
+        exif.extend_from_slice(&1u16.to_le_bytes());
+        exif.extend_from_slice(&1u16.to_le_bytes());
+        exif.extend_from_slice(&2u16.to_le_bytes()); // ASCII
+        exif.extend_from_slice(&2u32.to_le_bytes());
+        exif.extend_from_slice(b"N\0\0\0");
+        exif.extend_from_slice(&0u32.to_le_bytes());
+
+        let mut segment = vec![0xff, 0xe1]; // APP1
+        segment.extend_from_slice(&u16::try_from(exif.len() + 2).unwrap().to_be_bytes());
+        segment.extend(exif);
+        // Insert after the SOI marker
+        jpeg.splice(2..2, segment);
+        jpeg
+    }
+
+    fn exif_metadata(bytes: &[u8], format: ImageFormat) -> Option<Vec<u8>> {
+        ImageReader::with_format(Cursor::new(bytes), format)
+            .into_decoder()
+            .unwrap()
+            .exif_metadata()
+            .unwrap()
+    }
+
+    #[test]
+    fn metadata_is_stripped() {
+        let jpeg = jpeg_with_gps_exif();
+        assert!(exif_metadata(&jpeg, ImageFormat::Jpeg).is_some());
+        let pixels = image::load_from_memory(&jpeg).unwrap().to_rgba8();
+
+        let options = ProfileImageOptions {
+            format: ProfileImageFormat::Png,
+            ..Default::default()
+        };
+        let profile_image = resize_profile_image_with_options(&jpeg, &options).unwrap();
+        assert!(exif_metadata(&profile_image, ImageFormat::Png).is_none());
+        assert_eq!(
+            image::load_from_memory(&profile_image).unwrap().to_rgba8(),
+            pixels
+        );
+
+        let profile_image = resize_profile_image(&jpeg).unwrap();
+        assert!(exif_metadata(&profile_image, ImageFormat::Jpeg).is_none());
+        assert!(!profile_image.windows(4).any(|window| window == b"Exif"));
+
+        let attachment = load_attachment_image_from_bytes(&jpeg).unwrap().unwrap();
+        assert!(exif_metadata(&attachment.webp_image, ImageFormat::WebP).is_none());
+        assert!(
+            !attachment
+                .webp_image
+                .windows(4)
+                .any(|window| window == b"Exif")
+        );
+        assert_eq!(attachment.image_dimensions, (32, 32));
+    }
+
+    #[test]
+    fn reject_invalid_profile_images() {
+        assert!(matches!(
+            resize_profile_image(b"not an image"),
+            Err(ProfileImageError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            resize_profile_image(&vec![0; MAX_PROFILE_IMAGE_SOURCE_BYTES + 1]),
+            Err(ProfileImageError::TooLarge { .. })
+        ));
+        let huge = encode_gif(MAX_PROFILE_IMAGE_SOURCE_DIMENSION + 1, 1, 1);
+        assert!(matches!(
+            resize_profile_image(&huge),
+            Err(ProfileImageError::DimensionsTooLarge { .. })
+        ));
+    }
+}
@@ -2,6 +2,8 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+#[cfg(not(target_os = "android"))]
+pub(crate) mod db_dump;
 mod file_lock;
 pub(crate) mod global_lock;
 pub(crate) mod image;
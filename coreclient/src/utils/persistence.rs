@@ -22,6 +22,7 @@ use sqlx::{
 use tracing::{error, info};
 
 use crate::{
+    ChatMessage,
     clients::store::ClientRecord,
     db::{access::DbAccess, notification::DbNotificationsSender},
     utils::global_lock::GlobalLock,
@@ -198,11 +199,23 @@ pub async fn open_client_db(user_id: &UserId, client_db_path: &str) -> sqlx::Res
     migrate!().run(&write_pool).await?;
     let read_pool = read_pool(opts).await?;
 
-    Ok(DbAccess::with_split_pools(
-        write_pool,
-        read_pool,
-        DbNotificationsSender::new(),
-    ))
+    let db = DbAccess::with_split_pools(write_pool, read_pool, DbNotificationsSender::new());
+
+    // Indexing old messages may take a while, so it must not delay opening the database.
+    tokio::spawn({
+        let db = db.clone();
+        async move {
+            let res = match db.write().await {
+                Ok(connection) => ChatMessage::backfill_search_index(connection).await,
+                Err(error) => Err(error),
+            };
+            if let Err(error) = res {
+                error!(%error, "Failed to backfill the message search index");
+            }
+        }
+    });
+
+    Ok(db)
 }
 
 pub(crate) fn open_lock_file(db_path: &str) -> std::io::Result<GlobalLock> {
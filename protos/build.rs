@@ -117,6 +117,8 @@ const SIGNED_REQUESTS: &[(Service, &str)] = &[
     (Service::Ds, "UpdateProfileKeyRequest"),
     (Service::Ds, "ProvisionAttachmentRequest"),
     (Service::Ds, "GetAttachmentUrlRequest"),
+    (Service::Ds, "GetAttachmentPartUploadUrlRequest"),
+    (Service::Ds, "CompleteAttachmentUploadRequest"),
     // Qs
     (Service::Qs, "UpdateUserRequest"),
     (Service::Qs, "DeleteUserRequest"),
@@ -50,6 +50,22 @@ pub struct AirFeatures {
     /// [APQMLS]: https://datatracker.ietf.org/doc/html/draft-ietf-mls-combiner
     #[tag(3)]
     pub pq_groups: bool,
+    /// Whether the client understands typing signals.
+    ///
+    /// Typing signals are only sent to chats where all members support them.
+    #[tag(4)]
+    pub typing_indicators: bool,
+    /// Whether the client understands presence updates.
+    ///
+    /// Presence updates are only sent to connection chats where the contact supports them.
+    #[tag(5)]
+    pub presence: bool,
+    /// Whether the client understands the status text in user profiles.
+    ///
+    /// The status text is only published in the user profile if all members of all groups
+    /// support it.
+    #[tag(6)]
+    pub user_profile_status_text: bool,
 }
 
 impl AirComponent {
@@ -72,6 +88,9 @@ impl AirFeatures {
             encrypted_group_profiles: true,
             empty_connection_group_attributes: true,
             pq_groups: true,
+            typing_indicators: true,
+            presence: true,
+            user_profile_status_text: true,
         }
     }
 }
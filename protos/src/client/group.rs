@@ -12,6 +12,7 @@ use aircommon::{
         aead::{AEAD_NONCE_SIZE, AeadCiphertext, AeadKey, Payload, keys::IdentityLinkWrapperKey},
         errors::{DecryptionError, EncryptionError},
     },
+    messages::client_ds::ChangeRoleParamsAad,
     padme::padme_padding_len,
 };
 use airmacros::{DeserializeTaggedMap, SerializeTaggedMap};
@@ -51,6 +52,12 @@ pub struct GroupData {
     ///
     /// Using this data, it is possible to retrieve the group profile from the object storage.
     pub external_group_profile: Option<ExternalGroupProfile>,
+    /// Role change committed together with this group data
+    ///
+    /// Role changes are carried in the group context, such that all members agree on them. Only
+    /// set by the commit changing the role; the remaining group data is unchanged by this commit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role_change: Option<ChangeRoleParamsAad>,
 }
 
 impl GroupData {
@@ -60,6 +67,7 @@ impl GroupData {
             external_group_profile: None,
             legacy_title: None,
             legacy_picture: None,
+            role_change: None,
         }
     }
 
@@ -388,6 +396,7 @@ mod test {
             }),
             legacy_title: None,
             legacy_picture: None,
+            role_change: None,
         }
     }
 
@@ -463,6 +472,7 @@ mod test {
                 external_group_profile: None,
                 legacy_title: Some("My Chat".to_string()),
                 legacy_picture: None,
+                role_change: None,
             }
         );
     }
@@ -126,6 +126,22 @@ impl_signed_payload!(
     seal = private_mod::Seal,
 );
 
+impl_signed_payload!(
+    request = super::v1::GetAttachmentPartUploadUrlRequest,
+    payload = super::v1::GetAttachmentPartUploadUrlPayload,
+    key_type = ClientKeyType,
+    label = "GetAttachmentPartUploadUrlPayload",
+    seal = private_mod::Seal,
+);
+
+impl_signed_payload!(
+    request = super::v1::CompleteAttachmentUploadRequest,
+    payload = super::v1::CompleteAttachmentUploadPayload,
+    key_type = ClientKeyType,
+    label = "CompleteAttachmentUploadPayload",
+    seal = private_mod::Seal,
+);
+
 impl_signed_payload!(
     request = super::v1::ApqSelfRemoveRequest,
     payload = super::v1::ApqSelfRemovePayload,
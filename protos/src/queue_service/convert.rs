@@ -185,16 +185,44 @@ impl From<crypto::hpke::ClientIdEncryptionKey> for ClientIdEncryptionKey {
 }
 
 impl TryFrom<QueueMessage> for messages::QueueMessage {
-    type Error = InvalidNonceLen;
+    type Error = InvalidQueueMessage;
 
     fn try_from(proto: QueueMessage) -> Result<Self, Self::Error> {
+        let sequence_number = proto.sequence_number;
+        let invalid = |reason| InvalidQueueMessage {
+            sequence_number,
+            reason,
+        };
+        let ciphertext = proto
+            .ciphertext
+            .ok_or_missing_field("ciphertext")
+            .map_err(|error| invalid(InvalidQueueMessageReason::MissingField(error)))?
+            .try_into()
+            .map_err(|error| invalid(InvalidQueueMessageReason::InvalidNonceLen(error)))?;
         Ok(Self {
-            sequence_number: proto.sequence_number,
-            ciphertext: proto.ciphertext.unwrap_or_default().try_into()?,
+            sequence_number,
+            ciphertext,
         })
     }
 }
 
+/// A queue message received from the QS which could not be converted
+#[derive(Debug, thiserror::Error)]
+#[error("invalid queue message with sequence number {sequence_number}")]
+pub struct InvalidQueueMessage {
+    pub sequence_number: u64,
+    #[source]
+    pub reason: InvalidQueueMessageReason,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidQueueMessageReason {
+    #[error(transparent)]
+    MissingField(#[from] MissingFieldError<&'static str>),
+    #[error(transparent)]
+    InvalidNonceLen(#[from] InvalidNonceLen),
+}
+
 impl From<messages::QueueMessage> for QueueMessage {
     fn from(value: messages::QueueMessage) -> Self {
         Self {
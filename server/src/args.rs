@@ -40,13 +40,21 @@ pub enum CodeCommand {
         /// Include redeemed codes
         #[arg(long, default_value_t = false)]
         include_redeemed: bool,
+        /// Only list expired codes which were never redeemed
+        #[arg(long, default_value_t = false, conflicts_with = "include_redeemed")]
+        expired: bool,
     },
     /// Generate invitation codes
     Generate {
         /// Number of codes to generate
         #[arg(default_value_t = 1)]
         n: usize,
+        /// Time after which the codes can no longer be redeemed
+        #[arg(long)]
+        expires_at: Option<DateTime<Utc>>,
     },
+    /// Delete expired codes which were never redeemed
+    Sweep,
 }
 
 #[derive(clap::Args)]
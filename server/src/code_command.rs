@@ -29,9 +29,20 @@ pub async fn run_code_command(
             println!("Total codes: {}", stats.count);
             println!("Redeemed codes: {}", stats.redeemed);
         }
+        CodeCommand::List {
+            n,
+            include_redeemed: _,
+            expired: true,
+        } => {
+            let codes = auth_service.invitation_codes_list_expired(n).await?;
+            for (code, expires_at) in codes {
+                println!("{} {}", code, expires_at.format("%Y-%m-%dT%H:%M:%SZ"));
+            }
+        }
         CodeCommand::List {
             n,
             include_redeemed,
+            expired: false,
         } => {
             let codes = auth_service.invitation_codes_list(n, false).await?;
             for (code, redeemed) in codes {
@@ -42,9 +53,21 @@ pub async fn run_code_command(
                 }
             }
         }
-        CodeCommand::Generate { n } => {
-            auth_service.invitation_codes_generate(n).await?;
-            println!("Generated {} codes", n);
+        CodeCommand::Generate { n, expires_at } => match expires_at {
+            Some(expires_at) => {
+                auth_service
+                    .invitation_codes_generate_with_expiry(n, expires_at)
+                    .await?;
+                println!("Generated {} codes expiring at {}", n, expires_at);
+            }
+            None => {
+                auth_service.invitation_codes_generate(n).await?;
+                println!("Generated {} codes", n);
+            }
+        },
+        CodeCommand::Sweep => {
+            let deleted = auth_service.invitation_codes_delete_expired().await?;
+            println!("Deleted {} expired codes", deleted);
         }
     }
 
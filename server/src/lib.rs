@@ -9,6 +9,7 @@
 use std::{future, time::Duration};
 
 use airbackend::{
+    air_service::ping_database,
     auth_service::{AsConnector, AuthService, grpc::GrpcAs},
     ds::{Ds, GrpcDs},
     qs::{
@@ -21,9 +22,9 @@ use airbackend::{
     settings::RateLimitsSettings,
 };
 use airprotos::{
-    auth_service::v1::auth_service_server::AuthServiceServer,
-    delivery_service::v1::delivery_service_server::DeliveryServiceServer,
-    queue_service::v1::queue_service_server::QueueServiceServer,
+    auth_service::v1::auth_service_server::{self, AuthServiceServer},
+    delivery_service::v1::delivery_service_server::{self, DeliveryServiceServer},
+    queue_service::v1::queue_service_server::{self, QueueServiceServer},
     relay_service::v1::relay_service_server::RelayServiceServer,
 };
 use axum::extract::State;
@@ -41,14 +42,17 @@ use tokio_util::sync::CancellationToken;
 #[cfg(any(feature = "test_utils", test))]
 use tonic::{Request, Status};
 use tonic::{service::InterceptorLayer, transport::server::Connected};
-use tonic_health::pb::health_server::{Health, HealthServer};
+use tonic_health::{
+    ServingStatus,
+    pb::health_server::{Health, HealthServer},
+};
 use tower_governor::{
     GovernorLayer, governor::GovernorConfigBuilder, key_extractor::SmartIpKeyExtractor,
 };
 use tower_http::trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer};
-use tracing::{Level, enabled, error, info};
+use tracing::{Level, enabled, error, info, warn};
 
-use crate::grpc_metrics::GrpcMetricsLayer;
+use crate::{grpc_metrics::GrpcMetricsLayer, rate_limit::MethodRateLimitLayer};
 
 pub mod args;
 pub mod as_connector;
@@ -61,6 +65,7 @@ pub mod logging;
 pub mod network_provider;
 pub mod push_notification_provider;
 pub mod qs_connector;
+mod rate_limit;
 pub mod username_command;
 
 pub struct ServerRunParams<Qc, Ac, Listener> {
@@ -73,6 +78,10 @@ pub struct ServerRunParams<Qc, Ac, Listener> {
     pub qs_connector: Qc,
     pub rs: Rs,
     pub rate_limits: RateLimitsSettings,
+    /// Interval at which the connectivity to the databases is checked by the health service
+    pub health_check_interval: Duration,
+    /// When cancelled, the server stops accepting connections, drains in-flight requests and
+    /// stops its background tasks and the metrics server.
     pub shutdown: CancellationToken,
 }
 
@@ -121,6 +130,7 @@ pub async fn run<
         rs,
         as_connector,
         rate_limits,
+        health_check_interval,
         shutdown,
     }: ServerRunParams<Qc, Ac, L>,
     #[cfg(any(feature = "test_utils", test))] interceptor: impl Fn(
@@ -135,7 +145,7 @@ pub async fn run<
 
     info!(%grpc_addr, "Starting server");
 
-    serve_metrics(metrics_listener);
+    serve_metrics(metrics_listener, &shutdown);
 
     // Background task: VOPRF key rotation check.
     // Waits a cooldown period after startup, then checks daily with random
@@ -158,32 +168,66 @@ pub async fn run<
         }
     }));
 
+    let health_service =
+        configure_health_service(&auth_service, &ds, &qs, health_check_interval, &shutdown).await;
+
     // GRPC server
     let grpc_as = GrpcAs::new(auth_service);
     let grpc_ds = GrpcDs::new(ds, qs_connector.clone(), as_connector);
     let grpc_qs = GrpcQs::new(qs);
     let grpc_rs = GrpcRs::new(rs, qs_connector);
 
-    info!(?rate_limits, "Applying rate limits");
-    let RateLimitsSettings { period, burst } = rate_limits;
-
-    let governor_config = GovernorConfigBuilder::default()
-        .period(period)
-        .burst_size(burst)
-        .key_extractor(SmartIpKeyExtractor)
-        .finish()
-        .expect("invalid governor config");
+    let RateLimitsSettings {
+        period,
+        burst,
+        methods,
+    } = rate_limits;
+    info!(?period, burst, "Applying default rate limit");
+
+    let governor_config = |period, burst| {
+        GovernorConfigBuilder::default()
+            .period(period)
+            .burst_size(burst)
+            .key_extractor(SmartIpKeyExtractor)
+            .finish()
+            .expect("invalid governor config")
+    };
+    let default_governor_config = governor_config(period, burst);
+    let method_governor_configs: Vec<_> = methods
+        .into_iter()
+        .map(|settings| {
+            info!(
+                method = %settings.method,
+                period = ?settings.period,
+                burst = settings.burst,
+                "Applying method rate limit"
+            );
+            let config = governor_config(settings.period, settings.burst);
+            (settings, config)
+        })
+        .collect();
 
     // task cleaning up limiter tokens
-    let governor_limiter = governor_config.limiter().clone();
+    let governor_limiters: Vec<_> = std::iter::once(&default_governor_config)
+        .chain(method_governor_configs.iter().map(|(_, config)| config))
+        .map(|config| config.limiter().clone())
+        .collect();
     tokio::spawn(shutdown.clone().run_until_cancelled_owned(async move {
         loop {
             tokio::time::sleep(Duration::from_secs(60)).await;
-            governor_limiter.retain_recent();
+            for limiter in &governor_limiters {
+                limiter.retain_recent();
+            }
         }
     }));
 
-    let health_service = configure_health_service::<Qc, Ac, Np>().await;
+    let rate_limit_layer = MethodRateLimitLayer::new(
+        GovernorLayer::new(default_governor_config),
+        method_governor_configs
+            .into_iter()
+            .map(|(settings, config)| (settings, GovernorLayer::new(config)))
+            .collect(),
+    );
 
     #[cfg(any(feature = "test_utils", test))]
     let dss = DeliveryServiceServer::with_interceptor(grpc_ds, interceptor);
@@ -209,7 +253,7 @@ pub async fn run<
                         .include_headers(enabled!(Level::DEBUG)),
                 ),
         )
-        .layer(GovernorLayer::new(governor_config))
+        .layer(rate_limit_layer)
         .add_service(health_service)
         .add_service(AuthServiceServer::new(grpc_as))
         .add_service(dss)
@@ -218,7 +262,8 @@ pub async fn run<
         .serve_with_incoming_shutdown(listener.into_stream(), shutdown.cancelled_owned())
 }
 
-fn serve_metrics(metrics_listener: Option<TcpListener>) {
+/// Serves the metrics until the shutdown token is cancelled.
+fn serve_metrics(metrics_listener: Option<TcpListener>, shutdown: &CancellationToken) {
     GrpcMetricsLayer::describe_metrics();
     if let Some(listener) = metrics_listener {
         let addr = listener.local_addr().expect("Could not get local address");
@@ -236,15 +281,15 @@ fn serve_metrics(metrics_listener: Option<TcpListener>) {
         );
 
         const UPKEEP_TIMEOUT: Duration = Duration::from_secs(5);
-        tokio::spawn(async move {
+        tokio::spawn(shutdown.clone().run_until_cancelled_owned(async move {
             loop {
                 tokio::time::sleep(UPKEEP_TIMEOUT).await;
                 handle.run_upkeep();
             }
-        });
+        }));
 
         #[cfg(target_os = "linux")]
-        tokio::spawn(async move {
+        tokio::spawn(shutdown.clone().run_until_cancelled_owned(async move {
             describe_gauge!(
                 "air_server_memory_used_bytes",
                 "Bytes actively allocated by the application"
@@ -263,27 +308,78 @@ fn serve_metrics(metrics_listener: Option<TcpListener>) {
                 gauge!("air_server_memory_free_bytes").set(info.fordblks as f64);
                 gauge!("air_server_memory_mmap_bytes").set(info.hblkhd as f64);
             }
-        });
+        }));
 
+        let shutdown = shutdown.clone();
         tokio::spawn(async move {
             info!(%addr, "Serving metrics");
-            if let Err(error) = axum::serve(listener, router.into_make_service()).await {
+            if let Err(error) = axum::serve(listener, router.into_make_service())
+                .with_graceful_shutdown(shutdown.cancelled_owned())
+                .await
+            {
                 error!(%error, "Metrics server stopped");
             }
         });
     }
 }
 
-async fn configure_health_service<
-    Qc: QsConnector<EnqueueError = QsEnqueueError<Np>> + Clone,
-    Ac: AsConnector<Error = AsConnectorError> + Clone,
-    Np: NetworkProvider,
->() -> HealthServer<impl Health> {
+/// Configures the health service.
+///
+/// The services are reported as serving while their database is reachable, which is checked every
+/// `check_interval` until shutdown.
+async fn configure_health_service(
+    auth_service: &AuthService,
+    ds: &Ds,
+    qs: &Qs,
+    check_interval: Duration,
+    shutdown: &CancellationToken,
+) -> HealthServer<impl Health> {
     let (reporter, service) = tonic_health::server::health_reporter();
-    reporter.set_serving::<AuthServiceServer<GrpcAs>>().await;
-    reporter
-        .set_serving::<DeliveryServiceServer<GrpcDs<Qc, Ac>>>()
-        .await;
-    reporter.set_serving::<QueueServiceServer<GrpcQs>>().await;
+    let databases = [
+        (
+            auth_service_server::SERVICE_NAME,
+            auth_service.db_pool().clone(),
+        ),
+        (delivery_service_server::SERVICE_NAME, ds.db_pool().clone()),
+        (queue_service_server::SERVICE_NAME, qs.db_pool().clone()),
+    ];
+    for (service_name, _) in &databases {
+        reporter
+            .set_service_status(service_name, ServingStatus::Serving)
+            .await;
+    }
+
+    tokio::spawn(shutdown.clone().run_until_cancelled_owned(async move {
+        let mut serving = [true; 3];
+        loop {
+            tokio::time::sleep(check_interval).await;
+            for ((service_name, db_pool), serving) in databases.iter().zip(&mut serving) {
+                let is_reachable =
+                    match tokio::time::timeout(check_interval, ping_database(db_pool)).await {
+                        Ok(Ok(())) => true,
+                        Ok(Err(error)) => {
+                            warn!(service_name, %error, "Database is unreachable");
+                            false
+                        }
+                        Err(_) => {
+                            warn!(service_name, "Database ping timed out");
+                            false
+                        }
+                    };
+                if is_reachable == *serving {
+                    continue;
+                }
+                *serving = is_reachable;
+                let status = if is_reachable {
+                    info!(service_name, "Database is reachable again");
+                    ServingStatus::Serving
+                } else {
+                    ServingStatus::NotServing
+                };
+                reporter.set_service_status(service_name, status).await;
+            }
+        }
+    }));
+
     service
 }
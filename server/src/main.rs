@@ -113,7 +113,7 @@ async fn main() -> anyhow::Result<()> {
     // New database name for the QS provider
     configuration.database.name = format!("{base_db_name}_qs");
     // QS storage provider
-    let qs = Qs::new(
+    let mut qs = Qs::new(
         &configuration.database,
         domain.clone(),
         version_req.cloned(),
@@ -121,6 +121,10 @@ async fn main() -> anyhow::Result<()> {
     )
     .await
     .expect("Failed to connect to database.");
+    if let Some(token) = configuration.application.admintoken.clone() {
+        qs.set_admin_token(token);
+    }
+    qs.expire_queue_messages(&configuration.queues);
 
     let rs = Rs::new(shutdown.clone());
 
@@ -134,6 +138,7 @@ async fn main() -> anyhow::Result<()> {
     )
     .await
     .expect("Failed to connect to database.");
+    auth_service.set_spam_settings(&configuration.spam);
     if let Some(code) = configuration.application.unredeemablecode {
         auth_service.set_unredeemable_code(code);
     }
@@ -166,6 +171,7 @@ async fn main() -> anyhow::Result<()> {
             qs_connector,
             rs,
             rate_limits: configuration.ratelimits,
+            health_check_interval: configuration.application.healthcheckinterval,
             shutdown,
         },
         #[cfg(any(feature = "test_utils", test))]
@@ -0,0 +1,177 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! A layer that applies rate limits per gRPC service or method.
+//!
+//! Each configured service or method is limited by its own layer. Requests which are not matched
+//! by any of them are limited by the default layer.
+
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use airbackend::settings::MethodRateLimitSettings;
+use tonic::codegen::http::Request;
+use tower::{Layer, Service};
+
+/// A layer that dispatches each request to the rate limiting layer of its gRPC method.
+#[derive(Clone)]
+pub(crate) struct MethodRateLimitLayer<L> {
+    default: L,
+    methods: Vec<(Arc<MethodRateLimitSettings>, L)>,
+}
+
+impl<L> MethodRateLimitLayer<L> {
+    pub(crate) fn new(default: L, methods: Vec<(MethodRateLimitSettings, L)>) -> Self {
+        Self {
+            default,
+            methods: methods
+                .into_iter()
+                .map(|(settings, layer)| (Arc::new(settings), layer))
+                .collect(),
+        }
+    }
+}
+
+impl<S: Clone, L: Layer<S>> Layer<S> for MethodRateLimitLayer<L> {
+    type Service = MethodRateLimitService<L::Service>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MethodRateLimitService {
+            default: self.default.layer(inner.clone()),
+            methods: self
+                .methods
+                .iter()
+                .map(|(settings, layer)| (settings.clone(), layer.layer(inner.clone())))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct MethodRateLimitService<S> {
+    default: S,
+    methods: Vec<(Arc<MethodRateLimitSettings>, S)>,
+}
+
+impl<S, B> Service<Request<B>> for MethodRateLimitService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+
+    type Error = S::Error;
+
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The request is only known in `call`, so all services must be ready.
+        for (_, service) in &mut self.methods {
+            if service.poll_ready(cx)?.is_pending() {
+                return Poll::Pending;
+            }
+        }
+        self.default.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let path = req.uri().path();
+        let service = self
+            .methods
+            .iter_mut()
+            .find(|(settings, _)| settings.matches(path))
+            .map(|(_, service)| service)
+            .unwrap_or(&mut self.default);
+        service.call(req)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        convert::Infallible,
+        future::{Ready, ready},
+        time::Duration,
+    };
+
+    use super::*;
+
+    fn method(method: &str) -> MethodRateLimitSettings {
+        MethodRateLimitSettings {
+            method: method.to_owned(),
+            period: Duration::from_secs(1),
+            burst: 1,
+        }
+    }
+
+    #[test]
+    fn method_matches() {
+        let service = method("queue_service.v1.QueueService");
+        assert!(service.matches("/queue_service.v1.QueueService/Listen"));
+        assert!(service.matches("/queue_service.v1.QueueService/CreateClient"));
+        assert!(!service.matches("/queue_service.v1.QueueServiceV2/Listen"));
+        assert!(!service.matches("/auth_service.v1.AuthService/Listen"));
+
+        let listen = method("/queue_service.v1.QueueService/Listen");
+        assert!(listen.matches("/queue_service.v1.QueueService/Listen"));
+        assert!(!listen.matches("/queue_service.v1.QueueService/ListenAll"));
+        assert!(!listen.matches("/queue_service.v1.QueueService/CreateClient"));
+    }
+
+    /// Layer which tags the responses of its service with a name.
+    #[derive(Clone)]
+    struct NameLayer(&'static str);
+
+    impl<S> Layer<S> for NameLayer {
+        type Service = NameService;
+
+        fn layer(&self, _inner: S) -> Self::Service {
+            NameService(self.0)
+        }
+    }
+
+    #[derive(Clone)]
+    struct NameService(&'static str);
+
+    impl<B> Service<Request<B>> for NameService {
+        type Response = &'static str;
+        type Error = Infallible;
+        type Future = Ready<Result<&'static str, Infallible>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _req: Request<B>) -> Self::Future {
+            ready(Ok(self.0))
+        }
+    }
+
+    #[test]
+    fn dispatches_to_first_matching_method() {
+        let layer = MethodRateLimitLayer::new(
+            NameLayer("default"),
+            vec![
+                (
+                    method("queue_service.v1.QueueService/Listen"),
+                    NameLayer("listen"),
+                ),
+                (method("queue_service.v1.QueueService"), NameLayer("qs")),
+            ],
+        );
+        let mut service = layer.layer(());
+
+        let mut call = |path: &str| {
+            let req = Request::builder().uri(path).body(()).unwrap();
+            service.call(req).into_inner().unwrap()
+        };
+        assert_eq!(call("/queue_service.v1.QueueService/Listen"), "listen");
+        assert_eq!(call("/queue_service.v1.QueueService/CreateClient"), "qs");
+        assert_eq!(
+            call("/auth_service.v1.AuthService/ListenUsername"),
+            "default"
+        );
+    }
+}
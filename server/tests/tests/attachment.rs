@@ -2,17 +2,27 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::io::Cursor;
+use std::{io::Cursor, pin::pin};
 
+use airapiclient::ds_api::DsRequestError;
 use aircommon::assert_matches;
-use aircoreclient::{AttachmentProgressEvent, ProvisionAttachmentError};
+use aircoreclient::{
+    AttachmentContent, AttachmentProgressEvent, AttachmentStatus, AttachmentTooLargeError,
+    ProvisionAttachmentError, image_is_animated,
+};
 use airserver_test_harness::utils::setup::{TestBackend, TestBackendParams};
 use base64::{Engine, prelude::BASE64_STANDARD};
-use image::{ImageBuffer, Rgba};
+use image::{
+    Delay, Frame, ImageBuffer, Rgba,
+    codecs::gif::{GifEncoder, Repeat},
+};
 use mimi_content::content_container::NestedPart;
 use png::Encoder;
 use sha2::{Digest, Sha256};
+use tempfile::TempDir;
 use tokio_stream::StreamExt;
+use tokio_util::sync::CancellationToken;
+use tonic::Code;
 
 pub(crate) fn test_picture_bytes() -> Vec<u8> {
     // Create a new ImgBuf with width: 1px and height: 1px
@@ -39,6 +49,21 @@ pub(crate) fn test_picture_bytes() -> Vec<u8> {
     buffer.into_inner()
 }
 
+/// An animated GIF with a few frames of different colors
+pub(crate) fn test_animated_picture_bytes() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = GifEncoder::new(&mut bytes);
+        encoder.set_repeat(Repeat::Infinite).unwrap();
+        for i in 0..3u8 {
+            let img = ImageBuffer::from_pixel(200, 200, Rgba([i * 100, 0u8, 255u8, 255u8]));
+            let frame = Frame::from_parts(img, 0, 0, Delay::from_numer_denom_ms(100, 1));
+            encoder.encode_frame(frame).unwrap();
+        }
+    }
+    bytes
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "Send attachment test", skip_all)]
 async fn send_attachment() {
@@ -78,7 +103,16 @@ async fn send_attachment() {
     assert_eq!(pending_attachments.len(), 1);
     let attachment_id = pending_attachments[0];
 
-    let (progress, download_task) = bob.download_attachment(attachment_id);
+    // Non-image attachments don't have a thumbnail
+    assert!(
+        bob.load_attachment_thumbnail(attachment_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let (progress, download_task) =
+        bob.download_attachment(attachment_id, CancellationToken::new());
 
     let progress_events = progress.stream().collect::<Vec<_>>();
 
@@ -112,6 +146,133 @@ async fn send_attachment() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Cancel attachment download test", skip_all)]
+async fn cancel_attachment_download() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let attachment = vec![0x2a; 1024 * 1024];
+    setup
+        .send_attachment(chat_id, &alice, vec![&bob], &attachment, "test.bin")
+        .await
+        .unwrap();
+
+    let bob_test_user = setup.get_user(&bob);
+    let bob = &bob_test_user.user;
+
+    let pending_attachments = bob.pending_attachments().await.unwrap();
+    assert_eq!(pending_attachments.len(), 1);
+    let attachment_id = pending_attachments[0];
+
+    // Cancel the download before it is started
+    let cancel = CancellationToken::new();
+    let (progress, download_task) = bob.download_attachment(attachment_id, cancel.clone());
+    cancel.cancel();
+
+    let progress_events = progress.stream().collect::<Vec<_>>();
+    let (progress_events, res) = tokio::join!(progress_events, download_task);
+    res.expect("Download task failed");
+
+    assert_matches!(
+        progress_events.last().unwrap(),
+        AttachmentProgressEvent::Cancelled
+    );
+
+    // No bytes are persisted and the attachment can be downloaded again
+    assert_matches!(
+        bob.load_attachment(attachment_id).await.unwrap(),
+        AttachmentContent::Pending
+    );
+    assert_matches!(
+        bob.attachment_status(attachment_id).await.unwrap(),
+        Some(AttachmentStatus::Pending)
+    );
+    assert_eq!(bob.pending_attachments().await.unwrap(), [attachment_id]);
+
+    let (progress, download_task) =
+        bob.download_attachment(attachment_id, CancellationToken::new());
+    let progress_events = progress.stream().collect::<Vec<_>>();
+    let (progress_events, res) = tokio::join!(progress_events, download_task);
+    res.expect("Download task failed");
+    assert_matches!(
+        progress_events.last().unwrap(),
+        AttachmentProgressEvent::Completed
+    );
+
+    let content = bob
+        .load_attachment(attachment_id)
+        .await
+        .unwrap()
+        .into_bytes()
+        .unwrap();
+    assert_eq!(content, attachment);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Cancel attachment download mid-stream test", skip_all)]
+async fn cancel_attachment_download_mid_stream() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    // Large enough to be streamed in many chunks
+    let attachment = vec![0x2a; 8 * 1024 * 1024];
+    setup
+        .send_attachment(chat_id, &alice, vec![&bob], &attachment, "test.bin")
+        .await
+        .unwrap();
+
+    let bob_test_user = setup.get_user(&bob);
+    let bob = &bob_test_user.user;
+
+    let pending_attachments = bob.pending_attachments().await.unwrap();
+    assert_eq!(pending_attachments.len(), 1);
+    let attachment_id = pending_attachments[0];
+
+    let cancel = CancellationToken::new();
+    let (progress, download_task) = bob.download_attachment(attachment_id, cancel.clone());
+    let mut download_task = pin!(download_task);
+    let mut progress_events = pin!(progress.stream());
+
+    // Drive the download until the first chunk is streamed, then cancel it
+    loop {
+        tokio::select! {
+            biased;
+            Some(event) = progress_events.next() => {
+                if let AttachmentProgressEvent::Progress { bytes_loaded } = event
+                    && bytes_loaded > 0
+                {
+                    assert!(bytes_loaded < attachment.len());
+                    cancel.cancel();
+                    break;
+                }
+            }
+            res = &mut download_task => panic!("Download finished before cancellation: {res:?}"),
+        }
+    }
+    download_task.await.expect("Download task failed");
+
+    assert_matches!(
+        pin!(progress.stream()).next().await.unwrap(),
+        AttachmentProgressEvent::Cancelled
+    );
+
+    // The streamed bytes are discarded
+    assert_matches!(
+        bob.load_attachment(attachment_id).await.unwrap(),
+        AttachmentContent::Pending
+    );
+    assert_matches!(
+        bob.attachment_status(attachment_id).await.unwrap(),
+        Some(AttachmentStatus::Pending)
+    );
+    assert_eq!(bob.pending_attachments().await.unwrap(), [attachment_id]);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "Send image attachment test", skip_all)]
 async fn send_image_attachment() {
@@ -171,7 +332,17 @@ async fn send_image_attachment() {
     assert_eq!(pending_attachments.len(), 1);
     let attachment_id = pending_attachments[0];
 
-    let (progress, download_task) = bob.download_attachment(attachment_id);
+    // The thumbnail is available before the attachment is downloaded
+    let thumbnail = bob
+        .load_attachment_thumbnail(attachment_id)
+        .await
+        .unwrap()
+        .expect("missing thumbnail");
+    let thumbnail = image::load_from_memory(&thumbnail).unwrap();
+    assert_eq!((thumbnail.width(), thumbnail.height()), (64, 48));
+
+    let (progress, download_task) =
+        bob.download_attachment(attachment_id, CancellationToken::new());
 
     let progress_events = progress.stream().collect::<Vec<_>>();
 
@@ -205,6 +376,73 @@ async fn send_image_attachment() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Send animated image attachment", skip_all)]
+async fn send_animated_image_attachment() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let attachment = test_animated_picture_bytes();
+    let (_message_id, external_part) = setup
+        .send_attachment(chat_id, &alice, vec![&bob], &attachment, "test.gif")
+        .await
+        .unwrap();
+
+    let alice_user = &setup.get_user(&alice).user;
+    alice_user.outbound_service().run_once().await;
+
+    let NestedPart::ExternalPart {
+        content_type,
+        size,
+        content_hash,
+        ..
+    } = external_part
+    else {
+        panic!("unexpected attachment type");
+    };
+    assert_eq!(content_type, "image/webp");
+
+    let bob_user = &setup.get_user(&bob).user;
+    let pending_attachments = bob_user.pending_attachments().await.unwrap();
+    assert_eq!(pending_attachments.len(), 1);
+    let attachment_id = pending_attachments[0];
+    let (_progress, download_task) =
+        bob_user.download_attachment(attachment_id, CancellationToken::new());
+    download_task.await.expect("Download task failed");
+
+    let content = bob_user
+        .load_attachment(attachment_id)
+        .await
+        .unwrap()
+        .into_bytes()
+        .unwrap();
+    assert_eq!(content.len() as u64, size);
+    let sha256sum = Sha256::digest(&content);
+    assert_eq!(sha256sum.as_slice(), content_hash.as_slice());
+    // The animation is preserved
+    assert!(image_is_animated(&content));
+
+    // An animation above the size limit is rejected instead of sent as still image
+    let max_attachment_bytes = size - 1;
+    let alice_user = &setup.get_user(&alice).user;
+    alice_user
+        .set_max_attachment_bytes(max_attachment_bytes)
+        .await
+        .unwrap();
+    let result = setup
+        .send_attachment(chat_id, &alice, vec![&bob], &attachment, "test.gif")
+        .await;
+    assert_matches!(
+        result.unwrap_err(),
+        ProvisionAttachmentError::ExceedsMaxSize(AttachmentTooLargeError {
+            max_size_bytes,
+            actual_size_bytes,
+        }) if max_size_bytes == max_attachment_bytes && actual_size_bytes == size
+    );
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "Attachment too large", skip_all)]
 async fn attachment_too_large() {
@@ -234,5 +472,312 @@ async fn attachment_too_large() {
             assert_eq!(detail.max_size_bytes, MAX_ATTACHMENT_SIZE);
             assert_eq!(detail.actual_size_bytes, encrypted_size);
         }
+        ProvisionAttachmentError::ExceedsMaxSize(error) => {
+            panic!("unexpected local size limit error: {error}")
+        }
+        ProvisionAttachmentError::UnsupportedImageFormat { content_type } => {
+            panic!("unexpected unsupported image format: {content_type}")
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Attachment exceeds local max size", skip_all)]
+async fn attachment_exceeds_max_size() {
+    const MAX_ATTACHMENT_BYTES: u64 = 1023;
+
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let alice_user = &setup.get_user(&alice).user;
+    assert!(alice_user.max_attachment_bytes().await > MAX_ATTACHMENT_BYTES);
+    alice_user
+        .set_max_attachment_bytes(MAX_ATTACHMENT_BYTES)
+        .await
+        .unwrap();
+    assert_eq!(
+        alice_user.max_attachment_bytes().await,
+        MAX_ATTACHMENT_BYTES
+    );
+    let messages_count = alice_user.messages_count(chat_id).await.unwrap();
+
+    let attachment = vec![0; MAX_ATTACHMENT_BYTES as usize + 1];
+    let result = setup
+        .send_attachment(chat_id, &alice, vec![&bob], &attachment, "test.bin")
+        .await;
+    assert_matches!(
+        result.unwrap_err(),
+        ProvisionAttachmentError::ExceedsMaxSize(AttachmentTooLargeError {
+            max_size_bytes: MAX_ATTACHMENT_BYTES,
+            actual_size_bytes,
+        }) if actual_size_bytes == attachment.len() as u64
+    );
+
+    // Nothing was stored locally
+    let alice_user = &setup.get_user(&alice).user;
+    assert_eq!(
+        alice_user.messages_count(chat_id).await.unwrap(),
+        messages_count
+    );
+
+    // Attachments within the limit are sent
+    let attachment = vec![0; MAX_ATTACHMENT_BYTES as usize];
+    setup
+        .send_attachment(chat_id, &alice, vec![&bob], &attachment, "test.bin")
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Send multiple attachments", skip_all)]
+async fn send_multiple_attachments() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let file = vec![0x00, 0x01, 0x02, 0x03];
+    let picture = test_picture_bytes();
+
+    let alice_user = setup.get_user(&alice).user();
+    let message = alice_user
+        .send_attachments(
+            chat_id,
+            vec![
+                (file.clone(), "test.bin".to_owned()),
+                (picture, "picture.png".to_owned()),
+            ],
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        alice_user
+            .attachment_ids_for_message(message.id())
+            .await
+            .len(),
+        2
+    );
+    alice_user.outbound_service().run_once().await;
+
+    let bob_test_user = setup.get_user(&bob);
+    bob_test_user.fetch_and_process_qs_messages().await;
+    let bob = &bob_test_user.user;
+
+    let message = bob.last_message(chat_id).await.unwrap().unwrap();
+    let attachment_ids = bob.attachment_ids_for_message(message.id()).await;
+    assert_eq!(attachment_ids.len(), 2);
+    assert_eq!(bob.pending_attachments().await.unwrap().len(), 2);
+
+    for attachment_id in &attachment_ids {
+        let (_progress, download_task) =
+            bob.download_attachment(*attachment_id, CancellationToken::new());
+        download_task.await.expect("Download task failed");
+    }
+
+    // The file is sent as is
+    let content = bob
+        .load_attachment(attachment_ids[0])
+        .await
+        .unwrap()
+        .into_bytes()
+        .unwrap();
+    assert_eq!(content, file);
+    assert!(
+        bob.load_attachment_thumbnail(attachment_ids[0])
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // The picture is re-encoded and has a thumbnail
+    let content = bob
+        .load_attachment(attachment_ids[1])
+        .await
+        .unwrap()
+        .into_bytes()
+        .unwrap();
+    assert_eq!(
+        image::guess_format(&content).unwrap(),
+        image::ImageFormat::WebP
+    );
+    assert!(
+        bob.load_attachment_thumbnail(attachment_ids[1])
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Resume interrupted attachment upload", skip_all)]
+async fn resume_interrupted_attachment_upload() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    // Large enough to be uploaded in two parts
+    let attachment: Vec<u8> = (0..8 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+    let tmp_dir = TempDir::new().unwrap();
+    let path = tmp_dir.path().join("test.bin");
+    std::fs::write(&path, &attachment).unwrap();
+
+    let alice_user = setup.get_user(&alice).user();
+    let (attachment_id, progress, upload_task) = alice_user
+        .upload_chat_attachment(chat_id, &path)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // The upload is interrupted after the first part is uploaded
+    {
+        let mut upload_task = pin!(upload_task);
+        let mut progress_events = pin!(progress.stream());
+        loop {
+            tokio::select! {
+                biased;
+                Some(event) = progress_events.next() => {
+                    if let AttachmentProgressEvent::Uploading { sent, total } = event
+                        && sent > 0
+                    {
+                        assert!(sent < total);
+                        break;
+                    }
+                }
+                res = &mut upload_task => panic!("Upload finished before interruption: {res:?}"),
+            }
+        }
+    }
+    assert_matches!(
+        alice_user.attachment_status(attachment_id).await.unwrap(),
+        Some(AttachmentStatus::Uploading)
+    );
+
+    // The outbound service resumes the upload after the first part and sends the message
+    let outbound_service = alice_user.outbound_service();
+    outbound_service.run_once().await;
+    outbound_service.wait_for_uploads().await;
+    outbound_service.run_once().await;
+    assert_matches!(
+        alice_user.attachment_status(attachment_id).await.unwrap(),
+        Some(AttachmentStatus::Ready)
+    );
+
+    let bob_test_user = setup.get_user(&bob);
+    bob_test_user.fetch_and_process_qs_messages().await;
+    let bob = &bob_test_user.user;
+
+    let pending_attachments = bob.pending_attachments().await.unwrap();
+    assert_eq!(pending_attachments.len(), 1);
+    let (_progress, download_task) =
+        bob.download_attachment(pending_attachments[0], CancellationToken::new());
+    download_task.await.expect("Download task failed");
+
+    let content = bob
+        .load_attachment(pending_attachments[0])
+        .await
+        .unwrap()
+        .into_bytes()
+        .unwrap();
+    assert_eq!(content, attachment);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Multipart upload of another sender", skip_all)]
+async fn multipart_upload_of_another_sender() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    // Large enough to be uploaded in two parts
+    let attachment = vec![0x2a; 8 * 1024 * 1024];
+    let tmp_dir = TempDir::new().unwrap();
+    let path = tmp_dir.path().join("test.bin");
+    std::fs::write(&path, &attachment).unwrap();
+
+    // Alice provisions the upload, but doesn't upload any part
+    let alice_user = setup.get_user(&alice).user();
+    let (attachment_id, _progress, _upload_task) = alice_user
+        .upload_chat_attachment(chat_id, &path)
+        .await
+        .unwrap()
+        .unwrap();
+    let remote_attachment_id = alice_user
+        .remote_attachment_id(attachment_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let part_size = 5 * 1024 * 1024;
+    alice_user
+        .request_attachment_part_upload_url(chat_id, remote_attachment_id, part_size)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Bob is a member of the same group, but did not provision the upload
+    let bob_user = setup.get_user(&bob).user();
+    let error = bob_user
+        .request_attachment_part_upload_url(chat_id, remote_attachment_id, part_size)
+        .await
+        .unwrap()
+        .unwrap_err();
+    assert_matches!(error, DsRequestError::Tonic(status) if status.code() == Code::NotFound);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Forward attachment", skip_all)]
+async fn forward_attachment() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let charlie = setup.add_user().await;
+    let chat_alice_bob = setup.connect_users(&alice, &bob).await;
+    let chat_alice_charlie = setup.connect_users(&alice, &charlie).await;
+
+    let attachment = vec![0x00, 0x01, 0x02, 0x03];
+    let (message_id, external_part) = setup
+        .send_attachment(chat_alice_bob, &alice, vec![&bob], &attachment, "test.bin")
+        .await
+        .unwrap();
+
+    let alice_user = setup.get_user(&alice).user();
+    let original = alice_user.message(message_id).await.unwrap().unwrap();
+    let forwarded = alice_user
+        .forward_message(message_id, chat_alice_charlie)
+        .await
+        .unwrap();
+    assert_eq!(forwarded.chat_id(), chat_alice_charlie);
+    assert_ne!(forwarded.message().mimi_id(), original.message().mimi_id());
+    alice_user.outbound_service().run_once().await;
+
+    let charlie_test_user = setup.get_user(&charlie);
+    charlie_test_user.fetch_and_process_qs_messages().await;
+    let charlie = &charlie_test_user.user;
+
+    let pending_attachments = charlie.pending_attachments().await.unwrap();
+    assert_eq!(pending_attachments.len(), 1);
+    let attachment_id = pending_attachments[0];
+
+    let (_progress, download_task) =
+        charlie.download_attachment(attachment_id, CancellationToken::new());
+    download_task.await.expect("Download task failed");
+
+    let content = charlie
+        .load_attachment(attachment_id)
+        .await
+        .unwrap()
+        .into_bytes()
+        .unwrap();
+    match external_part {
+        NestedPart::ExternalPart { content_hash, .. } => {
+            assert_eq!(content, attachment);
+            let sha256sum = Sha256::digest(&content);
+            assert_eq!(sha256sum.as_slice(), content_hash.as_slice());
+        }
+        _ => panic!("unexpected attachment type"),
     }
 }
@@ -5,7 +5,10 @@
 use std::time::Duration;
 
 use aircommon::time::TimeStamp;
-use aircoreclient::{ChatId, EventMessage, Message, SystemMessage, clients::CoreUser};
+use aircoreclient::{
+    ChatId, EventMessage, Message, SystemMessage,
+    clients::{CoreUser, listen_all::InboundEvent, reconnect::ReconnectPolicy},
+};
 use airprotos::client::component::{AirComponent, AirFeatures};
 use airserver_test_harness::utils::setup::TestBackend;
 use chrono::{DateTime, TimeZone};
@@ -336,6 +339,132 @@ async fn connection_request_has_server_timestamp() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Decline connection request", skip_all)]
+async fn decline_connection_request() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+
+    // Bob adds a username
+    let test_bob = setup.get_user_mut(&bob);
+    let bob_username_record = test_bob.add_username().await.unwrap();
+    let bob_username = bob_username_record.username.clone();
+
+    // Alice sends a connection request to Bob
+    let alice_user = &setup.get_user(&alice).user;
+    let username_hash = spawn_blocking({
+        let username = bob_username.clone();
+        move || username.calculate_hash().unwrap()
+    })
+    .await
+    .unwrap();
+    let alice_chat_id = alice_user
+        .add_contact(bob_username.clone(), username_hash)
+        .await
+        .expect("fatal error")
+        .expect("non-fatal error");
+
+    // Outgoing requests can't be declined
+    assert!(
+        alice_user
+            .decline_connection_request(alice_chat_id)
+            .await
+            .is_err()
+    );
+    assert!(alice_user.chat(&alice_chat_id).await.is_some());
+
+    // Bob processes the connection request
+    let bob_user = &setup.get_user(&bob).user;
+    let (mut stream, responder) = bob_user
+        .listen_username(&bob_username_record)
+        .await
+        .unwrap();
+    let mut bob_chat_id = None;
+    while let Some(Some(message)) = tokio::time::timeout(Duration::from_millis(500), stream.next())
+        .await
+        .unwrap()
+    {
+        let message_id = message.message_id.unwrap();
+        let chat_id = bob_user
+            .process_username_queue_message(bob_username.clone(), message)
+            .await
+            .unwrap();
+        bob_chat_id = Some(chat_id);
+        responder.ack(message_id.into()).await;
+    }
+    let bob_chat_id = bob_chat_id.expect("Bob should have processed the connection request");
+
+    let requests = bob_user.pending_connection_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].chat_id, bob_chat_id);
+    assert_eq!(requests[0].sender, alice);
+    assert_eq!(requests[0].username.as_ref(), Some(&bob_username));
+
+    bob_user
+        .decline_connection_request(bob_chat_id)
+        .await
+        .unwrap();
+
+    assert!(
+        bob_user
+            .pending_connection_requests()
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(bob_user.chat(&bob_chat_id).await.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Listen to all queues", skip_all)]
+async fn listen_all_subscribes_added_username() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+
+    let bob_user = setup.get_user(&bob).user.clone();
+    let mut events = bob_user.listen_all(ReconnectPolicy::default());
+
+    // Bob adds a username after listening; its queue is subscribed automatically
+    let bob_username_record = setup.get_user_mut(&bob).add_username().await.unwrap();
+
+    let alice_user = &setup.get_user(&alice).user;
+    alice_user
+        .add_contact(
+            bob_username_record.username.clone(),
+            bob_username_record.hash,
+        )
+        .await
+        .expect("fatal error")
+        .expect("non-fatal error");
+
+    let (username, message, responder) = loop {
+        let event = tokio::time::timeout(Duration::from_secs(5), events.next())
+            .await
+            .expect("timeout")
+            .expect("stream ended");
+        match event {
+            InboundEvent::HandleMessage {
+                username,
+                message,
+                responder,
+            } => break (username, message, responder),
+            InboundEvent::Status(_) | InboundEvent::QsEvent(_) => continue,
+            InboundEvent::UnsupportedVersion => panic!("unsupported version"),
+        }
+    };
+    assert_eq!(username, bob_username_record.username);
+
+    let message_id = message.message_id.unwrap();
+    let chat_id = bob_user
+        .process_username_queue_message(username, message)
+        .await
+        .unwrap();
+    responder.ack(message_id.into()).await;
+    assert!(bob_user.chat(&chat_id).await.is_some());
+}
+
 /// Helper: trigger the self-update timed task for the given chat.
 async fn run_self_update(user: &CoreUser, chat_id: ChatId) {
     user.set_self_updated_at(chat_id, DateTime::UNIX_EPOCH)
@@ -546,6 +675,9 @@ async fn erase_connection_group_data_mixed_feature_support() {
             encrypted_group_profiles: true,
             empty_connection_group_attributes: false,
             pq_groups: setup.apq_groups,
+            typing_indicators: true,
+            presence: true,
+            user_profile_status_text: true,
         },
         is_self_group: false,
     };
@@ -620,6 +752,9 @@ async fn erase_connection_group_data_mixed_feature_support() {
             encrypted_group_profiles: true,
             empty_connection_group_attributes: true,
             pq_groups: setup.apq_groups,
+            typing_indicators: true,
+            presence: true,
+            user_profile_status_text: true,
         },
         is_self_group: false,
     };
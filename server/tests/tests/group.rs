@@ -2,10 +2,15 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::slice;
+use std::{collections::HashSet, slice};
 
+use aircommon::{
+    identifiers::{QualifiedGroupId, UserId},
+    mls_group_config::{DEFAULT_CIPHERSUITE, SUPPORTED_CIPHERSUITES},
+};
 use aircoreclient::{
-    ChatStatus, DisplayName, EventMessage, Message, MessageDraft, SystemMessage, UserProfile,
+    ChatAttributes, ChatId, ChatStatus, DisplayName, EventMessage, InviteFailure, Message,
+    MessageDraft, SystemMessage, UserProfile,
     clients::{
         listen_response,
         process::process_qs::{QsProcessEventResult, QsStreamProcessor},
@@ -14,8 +19,10 @@ use aircoreclient::{
 use airserver_test_harness::utils::setup::TestBackend;
 use chrono::{DateTime, Duration, Utc};
 use mimi_content::MimiContent;
+use mimi_room_policy::RoleIndex;
 use tokio_stream::StreamExt;
 use tracing::info;
+use uuid::Uuid;
 
 use super::attachment::test_picture_bytes;
 
@@ -27,6 +34,28 @@ async fn create_group() {
     setup.create_group(&alice).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Create group with fixed id", skip_all)]
+async fn create_group_with_fixed_id() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let alice_user = &setup.get_user(&alice).user;
+
+    let group_uuid = Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+    let qgid = QualifiedGroupId::with_fixed_uuid(group_uuid, alice.domain().clone());
+    let chat_id = alice_user
+        .create_group_with_id(
+            qgid.clone(),
+            ChatAttributes::new("Fixed group".to_owned(), None),
+        )
+        .await
+        .unwrap();
+
+    let chat = alice_user.chat(&chat_id).await.unwrap();
+    assert_eq!(QualifiedGroupId::try_from(chat.group_id()).unwrap(), qgid);
+    assert_eq!(chat.attributes().unwrap().title(), "Fixed group");
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "Create empty group", skip_all)]
 async fn create_empty_group() {
@@ -49,6 +78,210 @@ async fn create_empty_group() {
     assert!(participants.contains(&alice));
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Partially invite to group", skip_all)]
+async fn invite_users_partial() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    // Charlie is not a contact of Alice
+    let charlie = setup.add_user().await;
+    setup.connect_users(&alice, &bob).await;
+    let chat_id = setup.create_group(&alice).await;
+
+    let alice_user = &setup.get_user(&alice).user;
+    let outcome = alice_user
+        .invite_users_partial(chat_id, &[bob.clone(), charlie.clone()])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.invited, [bob.clone()]);
+    assert_eq!(
+        outcome.failed,
+        [(charlie.clone(), InviteFailure::NotAContact)]
+    );
+    assert!(!outcome.messages.is_empty());
+
+    let participants = alice_user.chat_participants(chat_id).await.unwrap();
+    assert_eq!(participants, HashSet::from([alice.clone(), bob.clone()]));
+
+    // Already a member: nothing to do
+    let outcome = alice_user
+        .invite_users_partial(chat_id, slice::from_ref(&bob))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(outcome.invited.is_empty());
+    assert!(outcome.failed.is_empty());
+    assert!(outcome.messages.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Partially invite without key package", skip_all)]
+async fn invite_users_partial_key_package_unavailable() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let charlie = setup.add_user().await;
+    setup.connect_users(&alice, &bob).await;
+    setup.connect_users(&alice, &charlie).await;
+    let chat_id = setup.create_group(&alice).await;
+
+    // Charlie's key packages can't be fetched anymore
+    let alice_user = &setup.get_user(&alice).user;
+    alice_user
+        .invalidate_friendship_token(&charlie)
+        .await
+        .unwrap();
+
+    // Adding all members fails as a whole
+    assert!(
+        alice_user
+            .invite_users(chat_id, &[bob.clone(), charlie.clone()])
+            .await
+            .is_err()
+    );
+    let participants = alice_user.chat_participants(chat_id).await.unwrap();
+    assert_eq!(participants, HashSet::from([alice.clone()]));
+
+    // Adding members partially skips Charlie
+    let outcome = alice_user
+        .invite_users_partial(chat_id, &[bob.clone(), charlie.clone()])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.invited, [bob.clone()]);
+    assert_eq!(outcome.failed.len(), 1);
+    assert_eq!(outcome.failed[0].0, charlie);
+    assert!(matches!(
+        outcome.failed[0].1,
+        InviteFailure::KeyPackageUnavailable { .. }
+    ));
+
+    let participants = alice_user.chat_participants(chat_id).await.unwrap();
+    assert_eq!(participants, HashSet::from([alice.clone(), bob.clone()]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Chat display title test", skip_all)]
+async fn chat_display_title() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let charlie = setup.add_user().await;
+    setup.connect_users(&alice, &bob).await;
+    setup.connect_users(&alice, &charlie).await;
+
+    // A titled chat returns its title
+    let titled_chat_id = setup.create_group(&alice).await;
+    let alice_user = &setup.get_user(&alice).user;
+    let title = alice_user
+        .chat(&titled_chat_id)
+        .await
+        .unwrap()
+        .attributes()
+        .unwrap()
+        .title()
+        .to_owned();
+    assert_eq!(alice_user.chat_display_title(titled_chat_id).await, title);
+
+    // An untitled chat derives its title from the other members
+    let untitled_chat_id = alice_user
+        .create_chat(String::new(), None, false)
+        .await
+        .unwrap();
+    assert_eq!(alice_user.chat_display_title(untitled_chat_id).await, "");
+
+    alice_user
+        .invite_users(untitled_chat_id, &[bob.clone(), charlie.clone()])
+        .await
+        .unwrap()
+        .unwrap();
+    let mut names = vec![
+        alice_user
+            .user_profile(&bob)
+            .await
+            .display_name
+            .into_string(),
+        alice_user
+            .user_profile(&charlie)
+            .await
+            .display_name
+            .into_string(),
+    ];
+    names.sort();
+    assert_eq!(
+        alice_user.chat_display_title(untitled_chat_id).await,
+        names.join(", ")
+    );
+
+    // Setting a title takes precedence
+    alice_user
+        .set_chat_title(untitled_chat_id, "New title".to_owned())
+        .await
+        .unwrap();
+    assert_eq!(
+        alice_user.chat_display_title(untitled_chat_id).await,
+        "New title"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Find chats test", skip_all)]
+async fn find_chats() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let bob_chat_id = setup.connect_users(&alice, &bob).await;
+
+    let group_chat_id = setup.create_group(&alice).await;
+    let alice_user = &setup.get_user(&alice).user;
+    alice_user
+        .set_chat_title(group_chat_id, "Book Club".to_owned())
+        .await
+        .unwrap();
+
+    // Partial words of the title, ignoring case
+    assert_eq!(
+        alice_user.find_chats("club bO").await.unwrap(),
+        [group_chat_id]
+    );
+
+    // Display name of the contact
+    let bob_name = alice_user
+        .user_profile(&bob)
+        .await
+        .display_name
+        .into_string();
+    let found = alice_user
+        .find_chats(&bob_name.to_uppercase())
+        .await
+        .unwrap();
+    assert_eq!(found, [bob_chat_id]);
+
+    // The own display name is not matched
+    let alice_name = alice_user
+        .user_profile(&alice)
+        .await
+        .display_name
+        .into_string();
+    assert!(
+        !alice_user
+            .find_chats(&alice_name)
+            .await
+            .unwrap()
+            .contains(&bob_chat_id)
+    );
+
+    assert!(
+        alice_user
+            .find_chats("no such chat")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "Invite to group test", skip_all)]
 async fn invite_to_group() {
@@ -64,6 +297,115 @@ async fn invite_to_group() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Chat member roles test", skip_all)]
+async fn chat_member_roles() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let charlie = setup.add_user().await;
+    setup.connect_users(&alice, &bob).await;
+    setup.connect_users(&alice, &charlie).await;
+    let chat_id = setup.create_group(&alice).await;
+    setup
+        .invite_to_group(chat_id, &alice, vec![&bob, &charlie])
+        .await;
+
+    let members = setup
+        .get_user(&alice)
+        .user
+        .chat_members_with_roles(chat_id)
+        .await
+        .unwrap();
+    assert_eq!(members.len(), 3);
+
+    // The group creator owns the group, invited members are regular members
+    for user in [&alice, &bob] {
+        assert_eq!(
+            member_role(&setup, user, chat_id, &alice).await,
+            Some(RoleIndex::Owner)
+        );
+        assert_eq!(
+            member_role(&setup, user, chat_id, &bob).await,
+            Some(RoleIndex::Regular)
+        );
+        assert_eq!(
+            member_role(&setup, user, chat_id, &charlie).await,
+            Some(RoleIndex::Regular)
+        );
+    }
+}
+
+async fn member_role(
+    setup: &TestBackend,
+    user: &UserId,
+    chat_id: ChatId,
+    member: &UserId,
+) -> Option<RoleIndex> {
+    let members = setup
+        .get_user(user)
+        .user
+        .chat_members_with_roles(chat_id)
+        .await
+        .unwrap();
+    members
+        .into_iter()
+        .find_map(|(user_id, role)| (&user_id == member).then_some(role))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Change member role test", skip_all)]
+async fn change_member_role() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let charlie = setup.add_user().await;
+    setup.connect_users(&alice, &bob).await;
+    setup.connect_users(&alice, &charlie).await;
+    let chat_id = setup.create_group(&alice).await;
+    setup
+        .invite_to_group(chat_id, &alice, vec![&bob, &charlie])
+        .await;
+
+    // A regular member is not allowed to change the role of the group creator
+    let charlie_user = &setup.get_user(&charlie).user;
+    charlie_user
+        .set_member_role(chat_id, alice.clone(), RoleIndex::Regular)
+        .await
+        .unwrap_err();
+
+    // The group creator grants Bob their own role
+    let alice_role = member_role(&setup, &alice, chat_id, &alice).await.unwrap();
+    let alice_user = &setup.get_user(&alice).user;
+    let messages = alice_user
+        .set_member_role(chat_id, bob.clone(), alice_role)
+        .await
+        .unwrap();
+    assert!(messages.iter().any(|message| matches!(
+        message.message(),
+        Message::Event(EventMessage::System(SystemMessage::ChangeRole { target, .. }))
+            if target == &bob
+    )));
+    assert_eq!(
+        member_role(&setup, &alice, chat_id, &bob).await,
+        Some(alice_role)
+    );
+
+    setup.get_user(&bob).fetch_and_process_qs_messages().await;
+    setup
+        .get_user(&charlie)
+        .fetch_and_process_qs_messages()
+        .await;
+    assert_eq!(
+        member_role(&setup, &bob, chat_id, &bob).await,
+        Some(alice_role)
+    );
+    assert_eq!(
+        member_role(&setup, &charlie, chat_id, &bob).await,
+        Some(alice_role)
+    );
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "Invite to group test", skip_all)]
 async fn update_group() {
@@ -247,6 +589,7 @@ async fn update_user_profile_on_group_join() {
         user_id: alice.clone(),
         display_name: alice_display_name.clone(),
         profile_picture: None,
+        status_text: Some("  Out of office  ".to_owned()),
     };
     setup
         .get_user(&alice)
@@ -314,6 +657,10 @@ async fn update_user_profile_on_group_join() {
     // Charlie should now have Alice's new profile.
     let charlie_user_profile = charlie_user.user_profile(&alice).await;
     assert_eq!(charlie_user_profile.display_name, alice_display_name);
+    assert_eq!(
+        charlie_user_profile.status_text.as_deref(),
+        Some("Out of office")
+    );
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -543,6 +890,52 @@ async fn own_pending_commit_merges_group_data_and_clears_pending_op() {
     );
 }
 
+/// A join proposal of a non-member is dropped: it neither changes the group nor ends up in the next
+/// commit.
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "External join proposal test", skip_all)]
+async fn external_join_proposal() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let charlie = setup.add_user().await;
+    setup.connect_users(&alice, &bob).await;
+    let chat_id = setup.create_group(&alice).await;
+    setup.invite_to_group(chat_id, &alice, vec![&bob]).await;
+
+    let (group_id, epoch) = setup
+        .get_user(&alice)
+        .user
+        .mls_group_id_and_epoch(chat_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let proposal = setup
+        .get_user(&charlie)
+        .user
+        .external_join_proposal(group_id, epoch)
+        .await
+        .unwrap();
+
+    let alice_user = &setup.get_user(&alice).user;
+    alice_user
+        .process_incoming_mls_message(&proposal)
+        .await
+        .unwrap();
+    let members = alice_user.group_members(chat_id).await.unwrap();
+    assert_eq!(members, HashSet::from([alice.clone(), bob.clone()]));
+
+    // The next commit succeeds and does not add Charlie
+    setup.update_group(chat_id, &alice).await;
+    let members = setup
+        .get_user(&alice)
+        .user
+        .group_members(chat_id)
+        .await
+        .unwrap();
+    assert_eq!(members, HashSet::from([alice, bob]));
+}
+
 /// Tests that after being invited to a group, the invitee fetches the encrypted group profile from
 /// object storage via the outbound service and sees the correct group attributes (title and
 /// picture).
@@ -991,6 +1384,38 @@ async fn self_update_skips_inactive_chats() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Invite user with another ciphersuite", skip_all)]
+async fn invite_user_with_other_ciphersuite() {
+    let ciphersuite = SUPPORTED_CIPHERSUITES
+        .iter()
+        .copied()
+        .find(|&ciphersuite| ciphersuite != DEFAULT_CIPHERSUITE)
+        .expect("no non-default ciphersuite");
+
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user_with_ciphersuite(ciphersuite).await;
+
+    // Connection groups are joined externally, so they work across ciphersuites
+    setup.connect_users(&alice, &bob).await;
+
+    // Bob only publishes key packages for his ciphersuite, so he can't be added to a group with
+    // the default ciphersuite
+    let chat_id = setup.create_non_apq_group(&alice).await;
+    let alice_user = &setup.get_user(&alice).user;
+    assert_eq!(
+        alice_user.group_ciphersuite(chat_id).await.unwrap(),
+        Some(DEFAULT_CIPHERSUITE)
+    );
+    let result = alice_user
+        .invite_users(chat_id, slice::from_ref(&bob))
+        .await;
+    assert!(!matches!(result, Ok(Ok(_))), "invite succeeded");
+    let participants = alice_user.chat_participants(chat_id).await.unwrap();
+    assert_eq!(participants, HashSet::from([alice.clone()]));
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "QS stream processor partially processes messages", skip_all)]
 async fn qs_stream_processor_partially_processes_messages() {
@@ -1049,6 +1474,187 @@ async fn qs_stream_processor_partially_processes_messages() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(
+    name = "QS stream processor does not process messages on flush",
+    skip_all
+)]
+async fn qs_stream_processor_flush_chat_with_failing_message() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+
+    let connection_chat_id = setup.connect_users(&alice, &bob).await;
+    let group_chat_id = setup.create_group(&alice).await;
+    setup
+        .invite_to_group(group_chat_id, &alice, vec![&bob])
+        .await;
+
+    // Processing the message in the group fails on bob's client
+    setup
+        .get_user(&bob)
+        .user
+        .erase_chat(group_chat_id)
+        .await
+        .unwrap();
+
+    let alice_user = &setup.get_user(&alice).user;
+    let content = MimiContent::simple_markdown_message("Hello from Alice!".to_owned(), [0; 16]);
+    alice_user
+        .send_message(group_chat_id, content.clone(), None)
+        .await
+        .unwrap();
+    alice_user
+        .send_message(connection_chat_id, content, None)
+        .await
+        .unwrap();
+    alice_user.outbound_service().run_once().await;
+
+    let bob_user = &setup.get_user(&bob).user;
+
+    let (mut stream, responder) = bob_user.listen_queue().await.unwrap();
+    let mut processor = QsStreamProcessor::new(Some(responder));
+
+    // Flushing while messages are accumulated does not process them, so the result of the failing
+    // message reaches the consumer of the stream
+    let mut flushed = false;
+    while let Some(message) = stream.next().await {
+        match processor.process_event(bob_user, message).await {
+            QsProcessEventResult::Accumulated => {
+                if !flushed {
+                    processor.flush_chat(bob_user, connection_chat_id).await;
+                    flushed = true;
+                }
+            }
+            QsProcessEventResult::Ignored => (),
+            QsProcessEventResult::FullyProcessed { processed } => {
+                assert!(flushed);
+                assert_eq!(processed.processed, 2);
+                assert_eq!(processed.errors.len(), 1);
+                return;
+            }
+            QsProcessEventResult::PartiallyProcessed { .. } => unreachable!(),
+        }
+    }
+    panic!("QS stream ended before the messages were processed");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "QS stream processor processes messages in batches", skip_all)]
+async fn qs_stream_processor_processes_batches() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let alice_user = &setup.get_user(&alice).user;
+    for i in 0..3 {
+        let content = MimiContent::simple_markdown_message(format!("Message {i}"), [0; 16]);
+        alice_user
+            .send_message(chat_id, content, None)
+            .await
+            .unwrap();
+    }
+    alice_user.outbound_service().run_once().await;
+
+    let bob_user = &setup.get_user(&bob).user;
+
+    let (mut stream, responder) = bob_user.listen_queue().await.unwrap();
+    let mut processor = QsStreamProcessor::new(Some(responder)).with_max_batch_size(2);
+
+    // The first two messages are processed before the queue is empty
+    let mut batches = Vec::new();
+    while let Some(message) = stream.next().await {
+        match processor.process_event(bob_user, message).await {
+            QsProcessEventResult::Accumulated => (),
+            QsProcessEventResult::Ignored => (),
+            QsProcessEventResult::FullyProcessed { processed } => {
+                batches.push(processed.processed);
+                if batches.iter().sum::<usize>() == 3 {
+                    break;
+                }
+            }
+            QsProcessEventResult::PartiallyProcessed { .. } => unreachable!(),
+        }
+    }
+    assert_eq!(batches, [2, 1]);
+
+    let messages = bob_user.messages(chat_id, 10).await.unwrap();
+    let texts: Vec<_> = messages
+        .iter()
+        .filter_map(|message| message.message().mimi_content())
+        .filter_map(|content| content.string_rendering().ok())
+        .collect();
+    assert_eq!(texts, ["Message 0", "Message 1", "Message 2"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Read-only QS stream processor does not ack", skip_all)]
+async fn qs_stream_processor_read_only() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let alice_user = &setup.get_user(&alice).user;
+    for i in 0..3 {
+        let content = MimiContent::simple_markdown_message(format!("Message {i}"), [0; 16]);
+        alice_user
+            .send_message(chat_id, content, None)
+            .await
+            .unwrap();
+    }
+    alice_user.outbound_service().run_once().await;
+
+    let bob_user = &setup.get_user(&bob).user;
+
+    let queued: Vec<u64> = bob_user
+        .qs_fetch_messages()
+        .await
+        .unwrap()
+        .iter()
+        .map(|message| message.sequence_number)
+        .collect();
+    assert_eq!(queued.len(), 3);
+
+    let (mut stream, _responder) = bob_user.listen_queue().await.unwrap();
+    let mut processor = QsStreamProcessor::new_read_only();
+    while let Some(message) = stream.next().await {
+        match processor.process_event(bob_user, message).await {
+            QsProcessEventResult::Accumulated => (),
+            QsProcessEventResult::Ignored => (),
+            QsProcessEventResult::FullyProcessed { processed } => {
+                assert_eq!(processed.processed, 3);
+                break;
+            }
+            QsProcessEventResult::PartiallyProcessed { .. } => unreachable!(),
+        }
+    }
+
+    // The local ratchet advanced past the processed messages
+    assert!(bob_user.qs_fetch_messages().await.unwrap().is_empty());
+    let messages = bob_user.messages(chat_id, 10).await.unwrap();
+    let texts: Vec<_> = messages
+        .iter()
+        .filter_map(|message| message.message().mimi_content())
+        .filter_map(|content| content.string_rendering().ok())
+        .collect();
+    assert_eq!(texts, ["Message 0", "Message 1", "Message 2"]);
+
+    // The server queue is unchanged
+    let remaining: Vec<u64> = bob_user
+        .qs_fetch_messages_from(queued[0])
+        .await
+        .unwrap()
+        .messages
+        .iter()
+        .map(|message| message.sequence_number)
+        .collect();
+    assert_eq!(remaining, queued);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "Legacy group data migration", skip_all)]
 async fn legacy_group_data_migration() {
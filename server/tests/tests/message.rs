@@ -3,11 +3,16 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use aircommon::messages::client_ds_out::SendMessageCollisionTag;
-use aircoreclient::{ChatId, ChatMessage, MimiContentExt, ReadReceiptsSetting, clients::CoreUser};
+use aircoreclient::{
+    Chat, ChatId, ChatListFilter, ChatMessage, Feature, MimiContentExt, PresenceState,
+    ReadReceiptsSetting, clients::CoreUser, db::notification::DbEntityId,
+};
 use airserver_test_harness::utils::setup::{TestBackend, TestUser};
+use chrono::{Duration, Utc};
 use indexmap::indexmap;
 use mimi_content::{MessageStatus, MimiContent};
 use rand::{RngExt, distr::Alphanumeric};
+use tokio_util::sync::CancellationToken;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "Edit message", skip_all)]
@@ -60,12 +65,17 @@ async fn delete_message() {
             .unwrap()
     };
 
-    assert!(
-        !setup
-            .scan_database(&string, false, vec![&alice, &bob])
-            .await
-            .is_empty(),
-    );
+    let matches = setup
+        .scan_database_located(&string, false, vec![&alice, &bob])
+        .await;
+    for user_id in [&alice, &bob] {
+        assert!(
+            matches.iter().any(|(owner, m)| owner == user_id
+                && m.table == "message"
+                && m.column == "content"),
+            "message content not found in the message table: {matches:?}"
+        );
+    }
 
     // Alice (sender) should have 0 unread messages
     let alice_user = &setup.get_user(&alice).user;
@@ -374,6 +384,162 @@ async fn mark_as_read() {
     assert_eq!(global_unread_messages_count, num_messages + 2);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Read receipts of unfocused chat test", skip_all)]
+async fn read_receipts_deferred_while_chat_unfocused() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let alice_bob_chat = setup.connect_users(&alice, &bob).await;
+    let alice_test_user = setup.get_user(&alice);
+    let alice_user = &alice_test_user.user;
+    let bob_test_user = setup.get_user(&bob);
+    let bob_user = &bob_test_user.user;
+
+    alice_user
+        .send_message(
+            alice_bob_chat,
+            MimiContent::simple_markdown_message("Hello".into(), [0; 16]),
+            None,
+        )
+        .await
+        .unwrap();
+    alice_user.outbound_service().run_once().await;
+
+    // Delivery receipts are sent on processing
+    bob_test_user.fetch_and_process_qs_messages().await;
+    bob_user.outbound_service().run_once().await;
+    alice_test_user.fetch_and_process_qs_messages().await;
+    let last_message = alice_user
+        .last_message(alice_bob_chat)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(last_message.status(), MessageStatus::Delivered);
+
+    // Read receipts are deferred while the chat is not focused
+    let last_message = bob_user
+        .last_message(alice_bob_chat)
+        .await
+        .unwrap()
+        .unwrap();
+    let receipt = (
+        last_message.id(),
+        *last_message.message().mimi_id().unwrap(),
+    );
+    bob_user
+        .outbound_service()
+        .enqueue_read_receipts(alice_bob_chat, [receipt])
+        .await
+        .unwrap();
+    bob_user.outbound_service().run_once().await;
+    alice_test_user.fetch_and_process_qs_messages().await;
+    let last_message = alice_user
+        .last_message(alice_bob_chat)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(last_message.status(), MessageStatus::Delivered);
+
+    // Focusing the chat sends the deferred read receipts
+    bob_user
+        .set_chat_focused(alice_bob_chat, true)
+        .await
+        .unwrap();
+    bob_user.outbound_service().run_once().await;
+    alice_test_user.fetch_and_process_qs_messages().await;
+    let last_message = alice_user
+        .last_message(alice_bob_chat)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(last_message.status(), MessageStatus::Read);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Outbound service stats test", skip_all)]
+async fn outbound_service_stats() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let alice_bob_chat = setup.connect_users(&alice, &bob).await;
+    let alice_user = &setup.get_user(&alice).user;
+
+    let stats_before = alice_user.outbound_service().stats().await.unwrap();
+    assert!(!stats_before.is_sending());
+
+    alice_user
+        .send_message(
+            alice_bob_chat,
+            MimiContent::simple_markdown_message("Hello".into(), [0; 16]),
+            None,
+        )
+        .await
+        .unwrap();
+    let stats = alice_user.outbound_service().stats().await.unwrap();
+    assert_eq!(stats.queued_messages, 1);
+    assert!(stats.is_sending());
+
+    alice_user.outbound_service().run_once().await;
+    let stats = alice_user.outbound_service().stats().await.unwrap();
+    assert_eq!(stats.queued_messages, 0);
+    assert!(!stats.is_sending());
+    assert_eq!(stats.sent_messages, stats_before.sent_messages + 1);
+    assert_eq!(stats.send_failures, stats_before.send_failures);
+    assert!(stats.last_time_in_queue.is_some());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Flush chat test", skip_all)]
+async fn flush_chat() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let charlie = setup.add_user().await;
+    let alice_bob_chat = setup.connect_users(&alice, &bob).await;
+    let alice_charlie_chat = setup.connect_users(&alice, &charlie).await;
+    let alice_user = &setup.get_user(&alice).user;
+
+    let mut sent_to_bob = Vec::new();
+    for text in ["First", "Second"] {
+        let message = alice_user
+            .send_message(
+                alice_bob_chat,
+                MimiContent::simple_markdown_message(text.into(), [0; 16]),
+                None,
+            )
+            .await
+            .unwrap();
+        sent_to_bob.push(message.id());
+    }
+    let to_charlie = alice_user
+        .send_message(
+            alice_charlie_chat,
+            MimiContent::simple_markdown_message("Hello".into(), [0; 16]),
+            None,
+        )
+        .await
+        .unwrap();
+
+    alice_user
+        .outbound_service()
+        .flush_chat(alice_bob_chat)
+        .await;
+
+    for message_id in sent_to_bob {
+        let message = alice_user.message(message_id).await.unwrap().unwrap();
+        assert!(message.is_sent());
+    }
+    let message = alice_user.message(to_charlie.id()).await.unwrap().unwrap();
+    assert!(!message.is_sent());
+    let stats = alice_user.outbound_service().stats().await.unwrap();
+    assert_eq!(stats.queued_messages, 1);
+
+    alice_user.outbound_service().run_once().await;
+    let message = alice_user.message(to_charlie.id()).await.unwrap().unwrap();
+    assert!(message.is_sent());
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "Read receipts setting test", skip_all)]
 async fn read_receipts_setting() {
@@ -630,7 +796,7 @@ async fn delete_message_preserves_other_messages() {
     let alice_test_user = setup.get_user(&alice);
     alice_test_user.fetch_and_process_qs_messages().await;
     alice_user
-        .delete_message(chat_id, message_to_delete_id)
+        .delete_message_for_everyone(chat_id, message_to_delete_id)
         .await
         .unwrap();
     alice_user.outbound_service().run_once().await;
@@ -785,6 +951,55 @@ async fn message_sending_failures() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Retry failed message", skip_all)]
+async fn retry_failed_message() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let alice_user = &setup.get_user(&alice).user;
+
+    // Make server drop messages
+    setup.listener_control_handle().set_drop_all();
+
+    let content = MimiContent::simple_markdown_message("Hello".to_string(), [0; 16]);
+    let message = alice_user
+        .send_message(chat_id, content, None)
+        .await
+        .unwrap();
+    alice_user.outbound_service().run_once().await;
+
+    let failed = alice_user.failed_messages(Some(chat_id)).await.unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].id(), message.id());
+    assert_eq!(failed[0].status(), MessageStatus::Error);
+    assert_eq!(alice_user.failed_messages(None).await.unwrap().len(), 1);
+
+    setup.listener_control_handle().set_normal();
+
+    let mut notifications = alice_user.pending_db_notifications();
+    alice_user.retry_message(message.id()).await.unwrap();
+    assert!(notifications.by_ref().any(|notification| {
+        notification
+            .ops
+            .contains_key(&DbEntityId::from(message.id()))
+    }));
+
+    let retried = alice_user.message(message.id()).await.unwrap().unwrap();
+    assert_ne!(retried.status(), MessageStatus::Error);
+    assert!(alice_user.failed_messages(None).await.unwrap().is_empty());
+
+    alice_user.outbound_service().run_once().await;
+    let sent = alice_user.message(message.id()).await.unwrap().unwrap();
+    assert!(sent.is_sent());
+
+    // Sent messages can't be retried
+    assert!(alice_user.retry_message(message.id()).await.is_err());
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "Delete message with attachment", skip_all)]
 async fn delete_message_with_attachment() {
@@ -871,7 +1086,8 @@ async fn delete_message_with_attachment() {
 
         // Download each attachment
         for remote_attachment_id in &bob_attachment_ids {
-            let (_, download_future) = bob_user.download_attachment(*remote_attachment_id);
+            let (_, download_future) =
+                bob_user.download_attachment(*remote_attachment_id, CancellationToken::new());
             download_future.await.unwrap();
         }
 
@@ -894,7 +1110,7 @@ async fn delete_message_with_attachment() {
 
         // Delete the specific message by ID
         alice_user
-            .delete_message(chat_id, message_id)
+            .delete_message_for_everyone(chat_id, message_id)
             .await
             .unwrap();
         alice_user.outbound_service().run_once().await;
@@ -1392,3 +1608,468 @@ async fn no_notification_for_reaction_on_others_message() {
     assert_eq!(processed.reaction_notifications.len(), 1);
     assert_eq!(processed.reaction_notifications[0].reactor, charlie);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Export account archive", skip_all)]
+async fn export_account_archive() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+
+    let chat_alice_bob = setup.connect_users(&alice, &bob).await;
+    setup
+        .send_message(chat_alice_bob, &alice, vec![&bob], None)
+        .await;
+    setup
+        .send_message(chat_alice_bob, &bob, vec![&alice], None)
+        .await;
+    let group_chat_id = setup.create_group(&alice).await;
+
+    let alice_user = &setup.get_user(&alice).user;
+    let archive = alice_user.export_account_archive().await.unwrap();
+    let records: Vec<serde_json::Value> = archive
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).unwrap())
+        .collect();
+    let records_of_type =
+        |ty: &'static str| records.iter().filter(move |record| record["type"] == ty);
+
+    assert_eq!(records[0]["type"], "header");
+    assert_eq!(records_of_type("profile").count(), 1);
+    assert_eq!(records_of_type("contact").count(), 1);
+
+    let chat_ids: Vec<_> = records_of_type("chat")
+        .map(|record| record["chat_id"].as_str().unwrap().to_owned())
+        .collect();
+    assert!(chat_ids.contains(&chat_alice_bob.uuid().to_string()));
+    assert!(chat_ids.contains(&group_chat_id.uuid().to_string()));
+
+    // Both content messages of the connection chat are in the transcript
+    let num_content_messages = records_of_type("message")
+        .filter(|record| record["chat_id"] == chat_alice_bob.uuid().to_string().as_str())
+        .filter(|record| record["sender"].is_string())
+        .count();
+    assert_eq!(num_content_messages, 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Messages before message", skip_all)]
+async fn messages_before_message() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+
+    let chat_id = setup.connect_users(&alice, &bob).await;
+    for _ in 0..3 {
+        setup.send_message(chat_id, &alice, vec![&bob], None).await;
+    }
+
+    let alice_user = &setup.get_user(&alice).user;
+    let messages = alice_user.messages(chat_id, 100).await.unwrap();
+    let ids: Vec<_> = messages.iter().map(|message| message.id()).collect();
+
+    let last = *ids.last().unwrap();
+    let page = alice_user
+        .messages_before_message(chat_id, last, 2)
+        .await
+        .unwrap();
+    let page_ids: Vec<_> = page.iter().map(|message| message.id()).collect();
+    assert_eq!(page_ids, ids[ids.len() - 3..ids.len() - 1]);
+
+    // At the beginning of the history
+    let page = alice_user
+        .messages_before_message(chat_id, ids[0], 2)
+        .await
+        .unwrap();
+    assert!(page.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Toggle reaction", skip_all)]
+async fn toggle_reaction() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let sent = setup.send_message(chat_id, &alice, vec![&bob], None).await;
+    let bob_target = sent.recipient_message_id(&bob);
+
+    let bob_user = setup.get_user(&bob).user();
+    assert!(
+        bob_user
+            .toggle_reaction(chat_id, bob_target, "👍".to_owned())
+            .await
+            .unwrap()
+    );
+    bob_user.outbound_service().run_once().await;
+    setup.get_user(&alice).fetch_and_process_qs_messages().await;
+    assert_eq!(
+        setup
+            .get_user(&alice)
+            .user()
+            .message_reactions(sent.own_message_id)
+            .await
+            .unwrap(),
+        indexmap! { "👍".to_owned() => vec![bob.clone()] },
+    );
+
+    // Toggling the same emoji again retracts the reaction
+    let bob_user = setup.get_user(&bob).user();
+    assert!(
+        !bob_user
+            .toggle_reaction(chat_id, bob_target, "👍".to_owned())
+            .await
+            .unwrap()
+    );
+    bob_user.outbound_service().run_once().await;
+    setup.get_user(&alice).fetch_and_process_qs_messages().await;
+    assert!(
+        setup
+            .get_user(&alice)
+            .user()
+            .message_reactions(sent.own_message_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Set and clear draft", skip_all)]
+async fn set_and_clear_draft() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let alice_user = setup.get_user(&alice).user();
+    assert_eq!(alice_user.draft(chat_id).await.unwrap(), None);
+
+    alice_user
+        .set_draft(chat_id, Some("Hello".to_owned()))
+        .await
+        .unwrap();
+    assert_eq!(
+        alice_user.draft(chat_id).await.unwrap().as_deref(),
+        Some("Hello")
+    );
+    let message_draft = alice_user.message_draft(chat_id).await.unwrap().unwrap();
+    assert!(message_draft.is_committed);
+
+    alice_user.set_draft(chat_id, None).await.unwrap();
+    assert_eq!(alice_user.draft(chat_id).await.unwrap(), None);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Mute chat", skip_all)]
+async fn mute_chat() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let alice_user = setup.get_user(&alice).user();
+    alice_user
+        .set_chat_muted(chat_id, Some(Utc::now() + Duration::hours(1)))
+        .await
+        .unwrap();
+    assert!(alice_user.chat(&chat_id).await.unwrap().is_muted());
+
+    // Muted chats still receive messages and count them as unread
+    setup.send_message(chat_id, &bob, vec![&alice], None).await;
+    let alice_user = setup.get_user(&alice).user();
+    assert_eq!(alice_user.unread_messages_count(chat_id).await, 1);
+
+    // Expired mutes behave as unmuted
+    alice_user
+        .set_chat_muted(chat_id, Some(Utc::now() - Duration::hours(1)))
+        .await
+        .unwrap();
+    assert!(!alice_user.chat(&chat_id).await.unwrap().is_muted());
+
+    alice_user
+        .set_chat_muted(chat_id, Some(Utc::now() + Duration::hours(1)))
+        .await
+        .unwrap();
+    alice_user.set_chat_muted(chat_id, None).await.unwrap();
+    assert!(!alice_user.chat(&chat_id).await.unwrap().is_muted());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Archive chat", skip_all)]
+async fn archive_chat() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let alice_user = setup.get_user(&alice).user();
+    alice_user.set_chat_archived(chat_id, true).await.unwrap();
+    assert!(alice_user.chat(&chat_id).await.unwrap().is_archived());
+
+    let chat_ids = |chats: Vec<Chat>| chats.iter().map(Chat::id).collect::<Vec<_>>();
+    let archived = alice_user.chats(ChatListFilter::Archived).await.unwrap();
+    assert_eq!(chat_ids(archived), [chat_id]);
+    let active = alice_user.chats(ChatListFilter::Active).await.unwrap();
+    assert!(!chat_ids(active).contains(&chat_id));
+
+    // A new message unarchives the chat
+    setup.send_message(chat_id, &bob, vec![&alice], None).await;
+    let alice_user = setup.get_user(&alice).user();
+    assert!(!alice_user.chat(&chat_id).await.unwrap().is_archived());
+    let active = alice_user.chats(ChatListFilter::Active).await.unwrap();
+    assert!(chat_ids(active).contains(&chat_id));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Typing signal", skip_all)]
+async fn typing_signal() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let alice_test_user = setup.get_user(&alice);
+    let alice_user = alice_test_user.user();
+    let messages_count = alice_user.messages_count(chat_id).await.unwrap();
+    let unread_count = alice_user.unread_messages_count(chat_id).await;
+    let mut notifications = alice_user.pending_db_notifications();
+
+    let bob_user = setup.get_user(&bob).user();
+    // Typing indicators are opt-in
+    bob_user.send_typing(chat_id).await.unwrap();
+    bob_user.outbound_service().run_once().await;
+    alice_test_user.fetch_and_process_qs_messages().await;
+    assert!(
+        notifications
+            .by_ref()
+            .all(|notification| notification.typing.is_empty())
+    );
+
+    bob_user
+        .set_feature(Feature::TypingIndicators, true)
+        .await
+        .unwrap();
+
+    // Nothing is sent while the outbound service is paused
+    bob_user.outbound_service().pause().await;
+    bob_user.send_typing(chat_id).await.unwrap();
+    // Rate limited: no second signal is sent
+    bob_user.send_typing(chat_id).await.unwrap();
+    bob_user.outbound_service().run_once().await;
+    alice_test_user.fetch_and_process_qs_messages().await;
+    assert!(
+        notifications
+            .by_ref()
+            .all(|notification| notification.typing.is_empty())
+    );
+
+    // The queued signal is sent after resuming
+    bob_user.outbound_service().resume().await;
+    bob_user.outbound_service().run_once().await;
+    alice_test_user.fetch_and_process_qs_messages().await;
+    let typing: Vec<_> = notifications
+        .by_ref()
+        .flat_map(|notification| notification.typing.clone())
+        .collect();
+    assert_eq!(typing, [(chat_id, bob.clone())]);
+
+    // Typing signals are neither stored nor counted as unread
+    assert_eq!(
+        alice_user.messages_count(chat_id).await.unwrap(),
+        messages_count
+    );
+    assert_eq!(
+        alice_user.unread_messages_count(chat_id).await,
+        unread_count
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Typing signal from blocked contact", skip_all)]
+async fn typing_signal_from_blocked_contact() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let charlie = setup.add_user().await;
+    setup.connect_users(&alice, &bob).await;
+    setup.connect_users(&alice, &charlie).await;
+
+    let chat_id = setup.create_group(&alice).await;
+    setup
+        .invite_to_group(chat_id, &alice, vec![&bob, &charlie])
+        .await;
+
+    let alice_test_user = setup.get_user(&alice);
+    alice_test_user
+        .user
+        .block_contact(bob.clone())
+        .await
+        .unwrap();
+    let mut alice_notifications = alice_test_user.user.pending_db_notifications();
+    let charlie_test_user = setup.get_user(&charlie);
+    let mut charlie_notifications = charlie_test_user.user.pending_db_notifications();
+
+    let bob_user = setup.get_user(&bob).user();
+    bob_user
+        .set_feature(Feature::TypingIndicators, true)
+        .await
+        .unwrap();
+    bob_user.send_typing(chat_id).await.unwrap();
+    bob_user.outbound_service().run_once().await;
+
+    // Typing signals of a blocked contact are dropped
+    alice_test_user.fetch_and_process_qs_messages().await;
+    assert!(
+        alice_notifications
+            .by_ref()
+            .all(|notification| notification.typing.is_empty())
+    );
+
+    charlie_test_user.fetch_and_process_qs_messages().await;
+    let typing: Vec<_> = charlie_notifications
+        .by_ref()
+        .flat_map(|notification| notification.typing.clone())
+        .collect();
+    assert_eq!(typing, [(chat_id, bob.clone())]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Presence update", skip_all)]
+async fn presence_update() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let alice_test_user = setup.get_user(&alice);
+    let alice_user = alice_test_user.user();
+    let messages_count = alice_user.messages_count(chat_id).await.unwrap();
+
+    let bob_user = setup.get_user(&bob).user();
+    // Presence is opt-in
+    bob_user
+        .publish_presence(PresenceState::Online)
+        .await
+        .unwrap();
+    bob_user.outbound_service().run_once().await;
+    alice_test_user.fetch_and_process_qs_messages().await;
+    assert_eq!(alice_user.contact_presence(&bob).await.unwrap(), None);
+
+    bob_user.set_feature(Feature::Presence, true).await.unwrap();
+
+    // Nothing is sent while the outbound service is paused
+    bob_user.outbound_service().pause().await;
+    bob_user
+        .publish_presence(PresenceState::Online)
+        .await
+        .unwrap();
+    // Only the latest state is sent
+    bob_user
+        .publish_presence(PresenceState::Away)
+        .await
+        .unwrap();
+    bob_user.outbound_service().run_once().await;
+    alice_test_user.fetch_and_process_qs_messages().await;
+    assert_eq!(alice_user.contact_presence(&bob).await.unwrap(), None);
+
+    // The queued update is sent after resuming
+    bob_user.outbound_service().resume().await;
+    bob_user.outbound_service().run_once().await;
+    alice_test_user.fetch_and_process_qs_messages().await;
+    let presence = alice_user.contact_presence(&bob).await.unwrap().unwrap();
+    assert_eq!(presence.state, PresenceState::Away);
+
+    // Presence updates are not stored as messages
+    assert_eq!(
+        alice_user.messages_count(chat_id).await.unwrap(),
+        messages_count
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Schedule message", skip_all)]
+async fn schedule_message() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let alice_user = setup.get_user(&alice).user();
+
+    // A message scheduled in the future is pending until canceled
+    let content = MimiContent::simple_markdown_message("Later".into(), [1; 16]);
+    let later_id = alice_user
+        .schedule_message(chat_id, content, Utc::now() + Duration::hours(1))
+        .await
+        .unwrap();
+    alice_user.outbound_service().run_once().await;
+    let later = alice_user.message(later_id).await.unwrap().unwrap();
+    assert!(!later.is_sent());
+    assert!(alice_user.cancel_scheduled_message(later_id).await.unwrap());
+    assert!(alice_user.message(later_id).await.unwrap().is_none());
+    assert!(!alice_user.cancel_scheduled_message(later_id).await.unwrap());
+
+    // A due message is sent on the next run
+    let content = MimiContent::simple_markdown_message("Now".into(), [2; 16]);
+    let due_id = alice_user
+        .schedule_message(chat_id, content.clone(), Utc::now())
+        .await
+        .unwrap();
+    alice_user.outbound_service().run_once().await;
+    assert!(alice_user.message(due_id).await.unwrap().unwrap().is_sent());
+    assert!(!alice_user.cancel_scheduled_message(due_id).await.unwrap());
+
+    let bob_test_user = setup.get_user(&bob);
+    bob_test_user.fetch_and_process_qs_messages().await;
+    let received = bob_test_user
+        .user()
+        .last_message(chat_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(received.message().mimi_content(), Some(&content));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Send reply", skip_all)]
+async fn send_reply() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+    let chat_id = setup.connect_users(&alice, &bob).await;
+
+    let sent = setup.send_message(chat_id, &alice, vec![&bob], None).await;
+    let reply_to = sent.mimi_id;
+
+    let bob_test_user = setup.get_user(&bob);
+    let content = MimiContent::simple_markdown_message("Reply".into(), [3; 16]);
+    let reply = bob_test_user
+        .user()
+        .send_reply(chat_id, content, Some(reply_to))
+        .await
+        .unwrap();
+    let reply = bob_test_user
+        .user()
+        .message(reply.id())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        reply.in_reply_to_message_id(),
+        Some(sent.recipient_message_id(&bob))
+    );
+    bob_test_user.user().outbound_service().run_once().await;
+
+    let alice_test_user = setup.get_user(&alice);
+    alice_test_user.fetch_and_process_qs_messages().await;
+    let received = alice_test_user
+        .user()
+        .last_message(chat_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(received.in_reply_to_message_id(), Some(sent.own_message_id));
+}
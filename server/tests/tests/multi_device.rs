@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use aircommon::mls_group_config::{DEFAULT_CIPHERSUITE, SUPPORTED_CIPHERSUITES};
 use aircoreclient::{
     ChatId, Message,
     clients::{
@@ -335,3 +336,67 @@ async fn multi_device_concurrent_linking_sessions_dont_interfere() {
     );
     assert_ne!(alice_device.qs_user_id(), bob_device.qs_user_id());
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Test non-default ciphersuite", skip_all)]
+async fn multi_device_non_default_ciphersuite() {
+    let ciphersuite = SUPPORTED_CIPHERSUITES
+        .iter()
+        .copied()
+        .find(|&ciphersuite| ciphersuite != DEFAULT_CIPHERSUITE)
+        .expect("no non-default ciphersuite");
+
+    let mut setup = TestBackend::single().await;
+    let domain = setup.domain().clone();
+    let server_url = setup.server_url();
+    let alice = setup.add_user_with_ciphersuite(ciphersuite).await;
+    let bob = setup.add_user_with_ciphersuite(ciphersuite).await;
+    let charlie = setup.add_user_with_ciphersuite(ciphersuite).await;
+    setup.connect_users(&alice, &bob).await;
+    setup.connect_users(&alice, &charlie).await;
+
+    // Bob and Charlie are added to the group with their key packages
+    let chat_id = setup.create_non_apq_group(&alice).await;
+    setup
+        .invite_to_group(chat_id, &alice, vec![&bob, &charlie])
+        .await;
+    for user_id in [&alice, &bob, &charlie] {
+        let user = setup.get_user(user_id).user();
+        assert_eq!(user.ciphersuite().await.unwrap(), ciphersuite);
+        assert_eq!(
+            user.group_ciphersuite(chat_id).await.unwrap(),
+            Some(ciphersuite)
+        );
+    }
+
+    // A linked device uses the ciphersuite of the user
+    let (session_tx, mut session_rx) = tokio::sync::mpsc::channel(1);
+    let new_device_task = tokio::spawn(async move {
+        let tmp = TempDir::new().unwrap();
+        let db_path = tmp.path().to_str().unwrap();
+        let new_device =
+            CoreUser::multi_device_provision_client(db_path, domain, Some(server_url), session_tx)
+                .await
+                .unwrap();
+        (new_device, tmp)
+    });
+    let session_id = recv_session_id(&mut session_rx).await;
+    setup
+        .get_user(&alice)
+        .user()
+        .multi_device_link_client(session_id, ignore_connected(), auto_confirm())
+        .await
+        .unwrap()
+        .unwrap();
+    let (new_device, _tmp) = new_device_task.await.unwrap();
+
+    assert_eq!(new_device.ciphersuite().await.unwrap(), ciphersuite);
+    let chat_id = new_device
+        .create_chat("Group of the new device".to_owned(), None, false)
+        .await
+        .unwrap();
+    assert_eq!(
+        new_device.group_ciphersuite(chat_id).await.unwrap(),
+        Some(ciphersuite)
+    );
+}
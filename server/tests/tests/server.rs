@@ -4,8 +4,13 @@
 
 use std::{collections::HashSet, slice, time::Duration};
 
-use airapiclient::{ApiClient, as_api::AsRequestError, qs_api::QsRequestError};
-use airbackend::settings::RateLimitsSettings;
+use airapiclient::{
+    ApiClient,
+    as_api::{AsRequestError, clock_offset},
+    health::{HealthCheckError, ServingStatus as HealthServingStatus},
+    qs_api::QsRequestError,
+};
+use airbackend::settings::{MethodRateLimitSettings, RateLimitsSettings};
 use aircommon::{
     assert_matches,
     credentials::keys::UsernameSigningKey,
@@ -44,6 +49,7 @@ async fn rate_limit() {
         rate_limits: Some(RateLimitsSettings {
             period: Duration::from_secs(1),
             burst: 100, // must be large enough to survive setup calls
+            methods: Vec::new(),
         }),
         ..Default::default()
     })
@@ -111,6 +117,48 @@ async fn rate_limit() {
     assert!(message.is_sent());
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Method rate limit test", skip_all)]
+async fn method_rate_limit() {
+    let setup = TestBackend::single_with_params(TestBackendParams {
+        rate_limits: Some(RateLimitsSettings {
+            period: Duration::from_millis(100),
+            burst: 10_000,
+            methods: vec![MethodRateLimitSettings {
+                method: "grpc.health.v1.Health/Check".to_owned(),
+                period: Duration::from_secs(60),
+                burst: 2,
+            }],
+        }),
+        ..Default::default()
+    })
+    .await;
+
+    if setup.is_external() {
+        warn!("Skipping test, because it is not possible to run it in an external environment.");
+        return;
+    }
+
+    let channel = Channel::from_shared(setup.server_url().to_string())
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let mut client = HealthClient::new(channel);
+    let request = || HealthCheckRequest {
+        service: auth_service_server::SERVICE_NAME.to_owned(),
+    };
+
+    // The health check is limited by its own limit
+    client.check(request()).await.unwrap();
+    client.check(request()).await.unwrap();
+    assert!(client.check(request()).await.is_err());
+
+    // Other methods are still limited by the default limit
+    let api_client = ApiClient::with_endpoint(&setup.server_url()).unwrap();
+    api_client.server_time().await.unwrap();
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "User deletion triggers", skip_all)]
 async fn user_deletion_triggers() {
@@ -244,6 +292,27 @@ async fn health_check() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "API client health check test", skip_all)]
+async fn api_client_health_check() {
+    let setup = TestBackend::single().await;
+    let client = ApiClient::with_endpoint(&setup.server_url()).unwrap();
+
+    let report = client.health_check().await.unwrap();
+    assert_eq!(report.auth_service, HealthServingStatus::Serving);
+    assert_eq!(report.delivery_service, HealthServingStatus::Serving);
+    assert_eq!(report.queue_service, HealthServingStatus::Serving);
+    assert!(report.is_serving());
+
+    // Nothing is listening on this port
+    let unreachable = ApiClient::with_endpoint(&"http://localhost:1".parse().unwrap()).unwrap();
+    let error = unreachable.health_check().await.unwrap_err();
+    assert_matches!(
+        error,
+        HealthCheckError::Unreachable(_) | HealthCheckError::Timeout
+    );
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "Max past epochs", skip_all)]
 async fn max_past_epochs() {
@@ -748,6 +817,7 @@ async fn resync_with_blank_leaf_succeeds() {
             user_id: dave.clone(),
             display_name: dave_display_name.clone(),
             profile_picture: None,
+            status_text: None,
         })
         .await
         .unwrap();
@@ -1122,3 +1192,84 @@ async fn listen_stream_eviction() {
         "second stream is closed"
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Server time", skip_all)]
+async fn server_time() {
+    let setup = TestBackend::single().await;
+    let client = ApiClient::with_endpoint(&setup.server_url()).unwrap();
+
+    let sent_at = Utc::now();
+    let server_time = client.server_time().await.unwrap();
+    let received_at = Utc::now();
+    let tolerance = chrono::Duration::seconds(5);
+    assert!(server_time >= sent_at - tolerance);
+    assert!(server_time <= received_at + tolerance);
+
+    let offset = client.server_clock_offset().await.unwrap();
+    assert!(offset.abs() < tolerance, "unexpected offset: {offset}");
+
+    // A client with a clock running one hour behind computes an offset of about one hour
+    let skew = chrono::Duration::hours(1);
+    let offset = clock_offset(sent_at - skew, server_time, received_at - skew);
+    assert!(
+        (offset - skew).abs() < tolerance,
+        "unexpected offset: {offset}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Persist server clock offset", skip_all)]
+async fn persist_server_clock_offset() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let alice = &setup.get_user(&alice).user;
+
+    assert_eq!(alice.server_clock_offset().await, chrono::Duration::zero());
+
+    let offset = alice.update_server_clock_offset().await.unwrap();
+    assert!(offset.abs() < chrono::Duration::seconds(5));
+    assert_eq!(
+        alice.server_clock_offset().await,
+        chrono::Duration::milliseconds(offset.num_milliseconds())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Pause and resume timed tasks", skip_all)]
+async fn pause_and_resume_timed_tasks() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let alice_user = &setup.get_user(&alice).user;
+    let outbound_service = alice_user.outbound_service();
+
+    let key_package_upload_due_at = async || {
+        alice_user
+            .user_debug_info()
+            .await
+            .unwrap()
+            .timed_tasks
+            .into_iter()
+            .find(|task| task.name == "Key Package Upload")
+            .unwrap()
+            .scheduled_at
+    };
+
+    outbound_service.set_timed_tasks_enabled(false);
+    outbound_service
+        .schedule_key_package_upload(Utc::now() - chrono::Duration::minutes(5))
+        .await
+        .unwrap();
+    outbound_service.run_once().await;
+    assert!(
+        key_package_upload_due_at().await <= Utc::now(),
+        "key package upload should be deferred while timed tasks are disabled"
+    );
+
+    outbound_service.set_timed_tasks_enabled(true);
+    outbound_service.run_once().await;
+    assert!(
+        key_package_upload_due_at().await > Utc::now(),
+        "key package upload should run once timed tasks are enabled again"
+    );
+}
@@ -5,10 +5,11 @@
 use std::{collections::HashMap, fs};
 
 use airapiclient::as_api::AsRequestError;
-use aircommon::{assert_matches, identifiers::Username};
+use aircommon::{assert_matches, credentials::keys::UsernameSigningKey, identifiers::Username};
 use aircoreclient::{
-    AddUsernameContactError, Asset, BlockedContactError, DisplayName, EventMessage, Message,
-    SystemMessage, UserProfile, clients::CoreUser,
+    AddUsernameContactError, Asset, BlockedContactError, DisplayName, EventMessage, Feature,
+    Message, ReadReceiptsSetting, SystemMessage, UserProfile, UsernameRecord, clients::CoreUser,
+    image_is_animated,
 };
 use airserver_test_harness::utils::setup::{TestBackend, TestUser};
 use mimi_content::MimiContent;
@@ -39,6 +40,7 @@ async fn exchange_user_profiles() {
         user_id: alice.clone(),
         display_name: alice_display_name.clone(),
         profile_picture: Some(alice_profile_picture.clone()),
+        status_text: None,
     };
     let alice_user = &setup.get_user(&alice).user;
     alice_user
@@ -55,6 +57,7 @@ async fn exchange_user_profiles() {
         user_id: bob.clone(),
         display_name: bob_display_name.clone(),
         profile_picture: Some(bob_profile_picture.clone()),
+        status_text: None,
     };
 
     let bob_user = &setup.get_user(&bob).user;
@@ -91,6 +94,7 @@ async fn exchange_user_profiles() {
         user_id: alice.clone(),
         display_name: "New Alice".parse().unwrap(),
         profile_picture: None,
+        status_text: None,
     };
 
     alice_user
@@ -108,6 +112,42 @@ async fn exchange_user_profiles() {
     assert_eq!(alice_user_profile, new_user_profile);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Animated profile picture exchange test", skip_all)]
+async fn exchange_animated_profile_picture() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+
+    let alice_profile = UserProfile {
+        user_id: alice.clone(),
+        display_name: "4l1c3".parse().unwrap(),
+        profile_picture: Some(Asset::Value(
+            super::attachment::test_animated_picture_bytes(),
+        )),
+        status_text: None,
+    };
+    let alice_user = &setup.get_user(&alice).user;
+    alice_user
+        .set_own_user_profile(alice_profile)
+        .await
+        .unwrap();
+
+    // The animation is preserved
+    let alice_own_profile = alice_user.own_user_profile().await.unwrap();
+    let alice_profile_picture = alice_own_profile.profile_picture.unwrap();
+    assert!(image_is_animated(alice_profile_picture.value().unwrap()));
+
+    setup.connect_users(&alice, &bob).await;
+
+    // Alice's animated profile picture is exchanged unchanged
+    let alice_user_profile = setup.get_user(&bob).user.user_profile(&alice).await;
+    assert_eq!(
+        alice_user_profile.profile_picture,
+        Some(alice_profile_picture)
+    );
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "User persistence test", skip_all)]
 async fn client_persistence() {
@@ -218,6 +258,7 @@ async fn blocked_contact() {
             user_id: alice.clone(),
             display_name: "Alice in Wonderland".parse().unwrap(),
             profile_picture: None,
+            status_text: None,
         })
         .await
         .unwrap();
@@ -229,6 +270,7 @@ async fn blocked_contact() {
             user_id: bob.clone(),
             display_name: "Annoying Bob".parse().unwrap(),
             profile_picture: None,
+            status_text: None,
         })
         .await
         .unwrap();
@@ -512,6 +554,7 @@ async fn add_contact_and_change_profile() {
         user_id: bob.clone(),
         display_name: "B0b".parse().unwrap(),
         profile_picture: None,
+        status_text: None,
     };
 
     bob_user
@@ -575,6 +618,50 @@ async fn add_contact_and_change_profile() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Process username queues with a failing queue", skip_all)]
+async fn process_username_queues_with_failing_queue() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let bob = setup.add_user().await;
+
+    let alice_username_record = setup.get_user_mut(&alice).add_username().await.unwrap();
+
+    // A username which is not registered on the server, so listening to its queue fails
+    let unregistered_username = Username::new("unregistered-username".to_owned()).unwrap();
+    let unregistered_record = UsernameRecord::new(
+        unregistered_username.clone(),
+        unregistered_username.calculate_hash().unwrap(),
+        UsernameSigningKey::generate().unwrap(),
+    );
+    let alice_user = &setup.get_user(&alice).user;
+    alice_user
+        .store_unregistered_username_record(&unregistered_record)
+        .await
+        .unwrap();
+
+    let bob_user = &setup.get_user(&bob).user;
+    bob_user
+        .add_contact(
+            alice_username_record.username.clone(),
+            alice_username_record.hash,
+        )
+        .await
+        .expect("fatal error")
+        .expect("non-fatal error");
+
+    // The connection request is processed despite the failing queue
+    let processed = alice_user
+        .fetch_and_process_username_messages()
+        .await
+        .unwrap();
+    assert_eq!(processed.chat_ids.len(), 1);
+    assert_eq!(processed.errors.len(), 1);
+    assert_eq!(processed.errors[0].0, unregistered_username);
+
+    assert!(alice_user.chat(&processed.chat_ids[0]).await.is_some());
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[tracing::instrument(name = "Request invitation code", skip_all)]
 async fn request_invitation_code() {
@@ -652,3 +739,34 @@ async fn mark_invitation_code_as_copied() {
     assert_eq!(stored.len(), 1);
     assert!(stored[0].copied, "code should be marked as copied");
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tracing::instrument(name = "Feature flags", skip_all)]
+async fn feature_flags() {
+    let mut setup = TestBackend::single().await;
+    let alice = setup.add_user().await;
+    let alice_user = &setup.get_user(&alice).user;
+
+    let flags = alice_user.feature_flags().await;
+    assert!(flags.is_active(Feature::ReadReceipts));
+    assert!(!flags.enabled.contains(Feature::TypingIndicators));
+    assert!(!flags.enabled.contains(Feature::Presence));
+
+    alice_user
+        .set_feature(Feature::ReadReceipts, false)
+        .await
+        .unwrap();
+    alice_user
+        .set_feature(Feature::TypingIndicators, true)
+        .await
+        .unwrap();
+
+    let flags = alice_user.feature_flags().await;
+    assert!(!flags.is_active(Feature::ReadReceipts));
+    // Advertised in the client's capabilities
+    assert!(flags.is_active(Feature::TypingIndicators));
+
+    // Read receipts are backed by the dedicated setting
+    let read_receipts = alice_user.user_setting::<ReadReceiptsSetting>().await;
+    assert!(matches!(read_receipts, Some(ReadReceiptsSetting(false))));
+}
@@ -48,6 +48,7 @@ const LOCAL_CONFIG: &str = include_str!("../../../server/configuration/local.yam
 const TEST_RATE_LIMITS: RateLimitsSettings = RateLimitsSettings {
     period: Duration::from_millis(1),
     burst: 1000,
+    methods: Vec::new(),
 };
 
 pub struct SpawnedApp {
@@ -252,6 +253,7 @@ pub(crate) async fn spawn_app(
             qs_connector,
             rs,
             rate_limits: rate_limits.unwrap_or(TEST_RATE_LIMITS),
+            health_check_interval: configuration.application.healthcheckinterval,
             shutdown: stop.clone(),
         },
         interceptor,
@@ -12,7 +12,7 @@ use std::{
 
 use airbackend::settings::RateLimitsSettings;
 use aircommon::{
-    OpenMlsRand, RustCrypto,
+    Ciphersuite, OpenMlsRand, RustCrypto,
     identifiers::{Fqdn, MimiId, UserId, Username},
 };
 use aircoreclient::{ChatId, ChatStatus, ChatType, clients::CoreUser, *};
@@ -67,6 +67,31 @@ impl TestUser {
         user
     }
 
+    /// Same as [`Self::new`], but new groups and key packages of the user use the given
+    /// ciphersuite.
+    pub async fn new_with_ciphersuite(
+        user_id: &UserId,
+        server_url: Url,
+        ciphersuite: Ciphersuite,
+    ) -> Self {
+        let user = CoreUser::new_ephemeral_with_ciphersuite(
+            user_id.clone(),
+            server_url,
+            None,
+            "DUMMY007".to_owned(),
+            ciphersuite,
+        )
+        .await
+        .unwrap();
+        // Run outbound service to upload KeyPackages
+        user.outbound_service().run_once().await;
+        Self {
+            user,
+            db_dir: None,
+            username_record: None,
+        }
+    }
+
     pub async fn try_new(
         user_id: &UserId,
         server_url: Url,
@@ -194,6 +219,7 @@ impl TestBackendParams {
             rate_limits: Some(RateLimitsSettings {
                 period: Duration::from_millis(100),
                 burst: 10_000,
+                methods: Vec::new(),
             }),
             ..Default::default()
         }
@@ -328,6 +354,14 @@ impl TestBackend {
         user_id
     }
 
+    pub async fn add_user_with_ciphersuite(&mut self, ciphersuite: Ciphersuite) -> UserId {
+        let user_id = self.random_user_id();
+        info!(?user_id, ?ciphersuite, "Creating user");
+        let user = TestUser::new_with_ciphersuite(&user_id, self.server_url(), ciphersuite).await;
+        self.users.insert(user_id.clone(), user);
+        user_id
+    }
+
     pub fn get_user(&self, user_id: &UserId) -> &TestUser {
         self.users.get(user_id).unwrap()
     }
@@ -917,7 +951,7 @@ impl TestBackend {
 
         test_sender
             .user
-            .delete_message(chat_id, message_id)
+            .delete_message_for_everyone(chat_id, message_id)
             .await
             .unwrap();
         test_sender.user.outbound_service().run_once().await;
@@ -1080,6 +1114,28 @@ impl TestBackend {
         result
     }
 
+    /// Same as [`Self::scan_database`], but also returns where each match was found.
+    pub async fn scan_database_located(
+        &mut self,
+        query: &str,
+        strict: bool,
+        users: Vec<&UserId>,
+    ) -> Vec<(UserId, clients::ScanMatch)> {
+        let mut result = Vec::new();
+        for user_id in users {
+            let user = self.users.get_mut(user_id).unwrap();
+            let user = &mut user.user;
+
+            let matches = user
+                .scan_database_located(query.as_bytes(), strict)
+                .await
+                .unwrap();
+            result.extend(matches.into_iter().map(|m| (user_id.clone(), m)));
+        }
+
+        result
+    }
+
     pub async fn create_apq_group(&mut self, user_id: &UserId) -> ChatId {
         self.create_group_inner(user_id, true).await
     }
@@ -1088,6 +1144,11 @@ impl TestBackend {
         self.create_group_inner(user_id, self.apq_groups).await
     }
 
+    /// Same as [`Self::create_group`], but never creates an APQ group.
+    pub async fn create_non_apq_group(&mut self, user_id: &UserId) -> ChatId {
+        self.create_group_inner(user_id, false).await
+    }
+
     async fn create_group_inner(&mut self, user_id: &UserId, is_apq: bool) -> ChatId {
         let test_user = self.users.get_mut(user_id).unwrap();
         let user = &mut test_user.user;
@@ -1796,6 +1857,9 @@ fn display_messages_to_string_map(display_messages: Vec<ChatMessage>) -> HashSet
                     },
                     SystemMessage::NewDirectConnectionChat(user_id) => {
                         format!("You requested a connection with {user_id:?}").into()
+                    },
+                    SystemMessage::ChangeRole { sender, target, role } => {
+                        format!("{sender:?} changed the role of {target:?} to {role:?}").into()
                     },
                                     }
             } else {
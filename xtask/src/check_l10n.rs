@@ -0,0 +1,150 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs,
+    sync::LazyLock,
+};
+
+use anyhow::{Result, bail, ensure};
+use camino::Utf8PathBuf;
+use clap::Args;
+use regex::Regex;
+
+use crate::{
+    prune_unused_l10n::{
+        DEFAULT_ARB, DEFAULT_EXCLUDE_DIRS, DEFAULT_EXTENSIONS, DEFAULT_INCLUDE_FILES,
+        DEFAULT_PROJECT_ROOT, DEFAULT_SEARCH_ROOTS, collect_candidate_files, load_keys,
+        relative_path, resolve_relative,
+    },
+    util::workspace_root,
+};
+
+/// Members of `AppLocalizations` which are not localization keys.
+const DEFAULT_IGNORED_MEMBERS: &[&str] = &["localeName", "bytesToHumanReadable"];
+
+/// Matches accesses of localization keys, e.g. `loc.chatList_you` or
+/// `AppLocalizations.of(context).chatList_you`.
+static KEY_REFERENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:loc|AppLocalizations\.of\([^)]*\))\s*\.\s*([A-Za-z_][A-Za-z0-9_]*)").unwrap()
+});
+
+#[derive(Args, Debug)]
+pub(crate) struct CheckArgs {
+    /// Print every file in which a missing key is referenced.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    verbose: bool,
+    /// Resolve relative paths against this directory.
+    #[arg(long, default_value = DEFAULT_PROJECT_ROOT)]
+    project_root: String,
+    /// Canonical ARB file defining the keys.
+    #[arg(long, default_value = DEFAULT_ARB)]
+    arb: String,
+    /// Directories to scan for localization usages.
+    #[arg(
+        long,
+        value_name = "path",
+        default_values = DEFAULT_SEARCH_ROOTS
+    )]
+    search_root: Vec<String>,
+    /// File extensions to include while scanning.
+    #[arg(long, value_name = ".dart", default_values = DEFAULT_EXTENSIONS)]
+    ext: Vec<String>,
+    /// Directories to skip when searching for usages.
+    #[arg(
+        long,
+        value_name = "path",
+        default_values = DEFAULT_EXCLUDE_DIRS
+    )]
+    exclude_dir: Vec<String>,
+    /// Files that are always scanned even if they live in excluded directories.
+    #[arg(
+        long,
+        value_name = "path",
+        default_values = DEFAULT_INCLUDE_FILES
+    )]
+    include_file: Vec<String>,
+    /// Referenced names which are not localization keys.
+    #[arg(long, value_name = "name", default_values = DEFAULT_IGNORED_MEMBERS)]
+    ignore: Vec<String>,
+}
+
+pub(crate) fn run(args: CheckArgs) -> Result<()> {
+    let workspace_root_path = workspace_root();
+    let project_root = resolve_relative(workspace_root_path.as_ref(), &args.project_root);
+    let resolve = |input: &str| resolve_relative(project_root.as_ref(), input);
+
+    let arb_path = resolve(&args.arb);
+    ensure!(arb_path.exists(), "ARB file not found: {}", arb_path);
+
+    let search_roots: Vec<Utf8PathBuf> =
+        args.search_root.iter().map(|root| resolve(root)).collect();
+    let include_exts: HashSet<String> = args
+        .ext
+        .iter()
+        .map(|ext| {
+            if ext.starts_with('.') {
+                ext.clone()
+            } else {
+                format!(".{ext}")
+            }
+        })
+        .collect();
+    let exclude_dirs: Vec<Utf8PathBuf> = args.exclude_dir.iter().map(|dir| resolve(dir)).collect();
+    let include_files: HashSet<Utf8PathBuf> =
+        args.include_file.iter().map(|file| resolve(file)).collect();
+
+    let keys: HashSet<String> = load_keys(&arb_path)?.into_iter().collect();
+    ensure!(!keys.is_empty(), "No keys found in {}.", arb_path);
+
+    let candidate_files =
+        collect_candidate_files(&search_roots, include_exts, &exclude_dirs, include_files);
+    if candidate_files.is_empty() {
+        bail!("No files matched the provided search criteria.");
+    }
+
+    let ignored: HashSet<&str> = args.ignore.iter().map(String::as_str).collect();
+
+    // Missing key => files referencing it
+    let mut missing: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for file in &candidate_files {
+        let text = match fs::read_to_string(file) {
+            Ok(text) => text,
+            Err(error) => {
+                eprintln!("⚠️  Skipping {file}: {error}");
+                continue;
+            }
+        };
+        for captures in KEY_REFERENCE.captures_iter(&text) {
+            let key = &captures[1];
+            if keys.contains(key) || ignored.contains(key) {
+                continue;
+            }
+            missing
+                .entry(key.to_owned())
+                .or_default()
+                .insert(relative_path(file, project_root.as_ref()));
+        }
+    }
+
+    if missing.is_empty() {
+        println!("✅ All referenced localization keys are defined in {arb_path}.");
+        return Ok(());
+    }
+
+    println!(
+        "Found {} referenced key(s) missing from {arb_path}:",
+        missing.len()
+    );
+    for (key, files) in &missing {
+        println!(" • {key}");
+        if args.verbose {
+            for file in files {
+                println!("     {file}");
+            }
+        }
+    }
+    bail!("{} localization key(s) are not defined", missing.len())
+}
@@ -0,0 +1,64 @@
+// SPDX-FileCopyrightText: 2026 Phoenix R&D GmbH <hello@phnx.im>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{fs, io::Write};
+
+use aircoreclient::{ClientDbDump, dump_client_db, import_client_db};
+use anyhow::{Context, Result};
+use camino::Utf8PathBuf;
+use clap::{Args, Subcommand};
+
+const SENSITIVE_DATA_WARNING: &str = "WARNING: The dump contains ALL data of the client in plain \
+    text, including private keys and messages. Only use it for debugging and never share it.";
+
+#[derive(Args, Debug)]
+pub(crate) struct ClientDbArgs {
+    #[command(subcommand)]
+    command: ClientDbCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum ClientDbCommand {
+    /// Dump the schema and all rows of a client database as JSON.
+    ///
+    /// Refuses to run while the database is locked by a running client.
+    Dump {
+        /// Path of the client database file
+        db: Utf8PathBuf,
+        /// Write the dump to this file instead of standard output.
+        #[arg(long, short)]
+        output: Option<Utf8PathBuf>,
+    },
+    /// Import a JSON dump into a new client database.
+    Import {
+        /// Path of the JSON dump
+        dump: Utf8PathBuf,
+        /// Path of the client database file to create
+        db: Utf8PathBuf,
+    },
+}
+
+pub(crate) fn run(args: ClientDbArgs) -> Result<()> {
+    eprintln!("{SENSITIVE_DATA_WARNING}");
+    let runtime = tokio::runtime::Runtime::new()?;
+    match args.command {
+        ClientDbCommand::Dump { db, output } => {
+            let dump = runtime.block_on(dump_client_db(&db))?;
+            let json = serde_json::to_vec_pretty(&dump)?;
+            match output {
+                Some(output) => {
+                    fs::write(&output, json).with_context(|| format!("failed to write {output}"))?
+                }
+                None => std::io::stdout().write_all(&json)?,
+            }
+        }
+        ClientDbCommand::Import { dump, db } => {
+            let json = fs::read(&dump).with_context(|| format!("failed to read {dump}"))?;
+            let dump: ClientDbDump = serde_json::from_slice(&json)?;
+            runtime.block_on(import_client_db(&db, &dump))?;
+            eprintln!("Imported into {db}");
+        }
+    }
+    Ok(())
+}
@@ -3,6 +3,9 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 mod bump_version;
+mod check_l10n;
+#[cfg(feature = "client_db")]
+mod client_db;
 mod generate_emoji;
 mod prune_unused_l10n;
 mod publish_linux_packages;
@@ -29,6 +32,9 @@ enum Commands {
     /// Scan Flutter / mobile sources for unused localization keys and prune them from ARB files.
     #[command(name = "prune-unused-l10n")]
     PruneUnusedL10n(prune_unused_l10n::PruneArgs),
+    /// Scan Flutter sources for localization keys that are missing from the ARB file; fails if there are any.
+    #[command(name = "check-l10n")]
+    CheckL10n(check_l10n::CheckArgs),
     /// Sign and publish a .deb or .rpm to an S3-hosted package repository.
     #[command(name = "publish-packages")]
     PublishLinuxPackages(publish_linux_packages::PublishArgs),
@@ -36,6 +42,11 @@ enum Commands {
     /// emojis grouped by category.
     #[command(name = "generate-emoji")]
     GenerateEmoji(generate_emoji::GenerateEmojiArgs),
+    // Only available with the `client_db` feature, e.g. via `cargo xtask-client-db`
+    /// Dump a client database to JSON for debugging, or import such a dump into a fresh database.
+    #[cfg(feature = "client_db")]
+    #[command(name = "client-db")]
+    ClientDb(client_db::ClientDbArgs),
 }
 
 fn main() -> anyhow::Result<()> {
@@ -43,7 +54,10 @@ fn main() -> anyhow::Result<()> {
     match cli.command {
         Commands::BumpVersion => bump_version::run(),
         Commands::PruneUnusedL10n(args) => prune_unused_l10n::run(args),
+        Commands::CheckL10n(args) => check_l10n::run(args),
         Commands::PublishLinuxPackages(args) => publish_linux_packages::run(args),
         Commands::GenerateEmoji(args) => generate_emoji::run(args),
+        #[cfg(feature = "client_db")]
+        Commands::ClientDb(args) => client_db::run(args),
     }
 }
@@ -14,12 +14,12 @@ use xshell::{Shell, cmd};
 
 use crate::util::workspace_root;
 
-const DEFAULT_PROJECT_ROOT: &str = "app";
-const DEFAULT_ARB: &str = "lib/l10n/app_en.arb";
-const DEFAULT_SEARCH_ROOTS: &[&str] = &["lib", "test"];
-const DEFAULT_EXTENSIONS: &[&str] = &[".dart", ".kt", ".swift", ".java", ".m", ".mm"];
-const DEFAULT_EXCLUDE_DIRS: &[&str] = &["lib/l10n"];
-const DEFAULT_INCLUDE_FILES: &[&str] = &[
+pub(crate) const DEFAULT_PROJECT_ROOT: &str = "app";
+pub(crate) const DEFAULT_ARB: &str = "lib/l10n/app_en.arb";
+pub(crate) const DEFAULT_SEARCH_ROOTS: &[&str] = &["lib", "test"];
+pub(crate) const DEFAULT_EXTENSIONS: &[&str] = &[".dart", ".kt", ".swift", ".java", ".m", ".mm"];
+pub(crate) const DEFAULT_EXCLUDE_DIRS: &[&str] = &["lib/l10n"];
+pub(crate) const DEFAULT_INCLUDE_FILES: &[&str] = &[
     "lib/l10n/app_localizations_extension.dart",
     "lib/l10n/language_options.dart",
 ];
@@ -180,7 +180,7 @@ pub(crate) fn run(args: PruneArgs) -> Result<()> {
     Ok(())
 }
 
-fn resolve_relative(base: &Utf8Path, raw: &str) -> Utf8PathBuf {
+pub(crate) fn resolve_relative(base: &Utf8Path, raw: &str) -> Utf8PathBuf {
     let path = Utf8PathBuf::from(raw);
     if path.is_absolute() {
         path
@@ -189,7 +189,7 @@ fn resolve_relative(base: &Utf8Path, raw: &str) -> Utf8PathBuf {
     }
 }
 
-fn load_keys(path: &Utf8Path) -> Result<Vec<String>> {
+pub(crate) fn load_keys(path: &Utf8Path) -> Result<Vec<String>> {
     let raw = fs::read_to_string(path).with_context(|| format!("Failed to read {path}"))?;
     let data: serde_json::Map<String, Value> =
         serde_json::from_str(&raw).with_context(|| format!("Failed to parse {path}"))?;
@@ -205,7 +205,7 @@ fn load_keys(path: &Utf8Path) -> Result<Vec<String>> {
         .collect())
 }
 
-fn collect_candidate_files(
+pub(crate) fn collect_candidate_files(
     search_roots: &[Utf8PathBuf],
     include_extensions: HashSet<String>,
     exclude_dirs: &[Utf8PathBuf],
@@ -357,7 +357,7 @@ fn prune_arb_file(
     Ok(removed)
 }
 
-fn relative_path(target: &Utf8Path, root: &Utf8Path) -> String {
+pub(crate) fn relative_path(target: &Utf8Path, root: &Utf8Path) -> String {
     target
         .strip_prefix(root)
         .map(|path| path.to_string())